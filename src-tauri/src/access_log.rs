@@ -0,0 +1,348 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::DesktopError;
+use crate::{config, logs, openclaw_home_dir};
+
+const DEFAULT_WINDOW_SECS: u64 = 60 * 60;
+/// Only the end of the log is scanned; older lines fall outside any useful window anyway
+const SCAN_BYTES: u64 = 8 * 1024 * 1024;
+const TOP_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+struct AccessRecord {
+    timestamp: i64,
+    client: Option<String>,
+    method: String,
+    path: String,
+    status: u16,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointCount {
+    pub method: String,
+    pub path: String,
+    pub count: u64,
+    pub errors: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientCount {
+    pub client: String,
+    pub count: u64,
+    pub errors: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AccessSummary {
+    /// Whether an access log was found at all
+    pub available: bool,
+    pub log_path: Option<String>,
+    pub window_secs: u64,
+    pub total_requests: u64,
+    pub requests_per_minute: f64,
+    /// Responses by class: `2xx`, `3xx`, `4xx`, `5xx`
+    pub status_classes: HashMap<String, u64>,
+    /// Share of 4xx and 5xx responses, 0.0 to 1.0
+    pub error_rate: f64,
+    pub top_endpoints: Vec<EndpointCount>,
+    pub unique_clients: usize,
+    pub top_clients: Vec<ClientCount>,
+    /// Lines in the window that matched no known format
+    pub unparsed_lines: u64,
+}
+
+/// Access log location: `gateway.accessLog` (a path or `{ "path": ... }`) in the
+/// config, else `~/.openclaw/access.log`
+fn access_log_path() -> Result<PathBuf, String> {
+    let configured = config::load_resolved_config().ok().and_then(|config| {
+        let value = config.pointer("/gateway/accessLog")?;
+        value
+            .as_str()
+            .or_else(|| value.get("path").and_then(Value::as_str))
+            .map(str::to_string)
+    });
+    match configured {
+        Some(path) => Ok(match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+            None => PathBuf::from(path),
+        }),
+        None => Ok(openclaw_home_dir()?.join("access.log")),
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// `+0200` / `-07:00` / `Z` as seconds east of UTC
+fn parse_offset(offset: &str) -> Option<i64> {
+    if offset.is_empty() || offset == "Z" {
+        return Some(0);
+    }
+    let sign = match offset.as_bytes()[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits: String = offset[1..].chars().filter(|c| *c != ':').collect();
+    if digits.len() != 4 {
+        return None;
+    }
+    let hours: i64 = digits[..2].parse().ok()?;
+    let minutes: i64 = digits[2..].parse().ok()?;
+    Some(sign * (hours * 3600 + minutes * 60))
+}
+
+fn epoch(year: i64, month: i64, day: i64, h: i64, m: i64, s: i64, offset: i64) -> i64 {
+    days_from_civil(year, month, day) * 86_400 + h * 3600 + m * 60 + s - offset
+}
+
+/// `10/Oct/2024:13:55:36 -0700`
+fn parse_clf_time(text: &str) -> Option<i64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let (datetime, offset) = text.split_once(' ').unwrap_or((text, ""));
+    let mut parts = datetime.splitn(4, ':');
+    let date = parts.next()?;
+    let (h, m, s) = (parts.next()?, parts.next()?, parts.next()?);
+    let mut date = date.split('/');
+    let day = date.next()?.parse().ok()?;
+    let month = MONTHS.iter().position(|name| Some(*name) == date.next())? as i64 + 1;
+    let year = date.next()?.parse().ok()?;
+    Some(epoch(
+        year,
+        month,
+        day,
+        h.parse().ok()?,
+        m.parse().ok()?,
+        s.parse().ok()?,
+        parse_offset(offset)?,
+    ))
+}
+
+/// `2024-10-10T13:55:36.123Z` and similar RFC 3339 timestamps
+fn parse_rfc3339(text: &str) -> Option<i64> {
+    if text.len() < 19 {
+        return None;
+    }
+    let year = text.get(0..4)?.parse().ok()?;
+    let month = text.get(5..7)?.parse().ok()?;
+    let day = text.get(8..10)?.parse().ok()?;
+    let h = text.get(11..13)?.parse().ok()?;
+    let m = text.get(14..16)?.parse().ok()?;
+    let s = text.get(17..19)?.parse().ok()?;
+    let rest = text[19..].trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    Some(epoch(year, month, day, h, m, s, parse_offset(rest)?))
+}
+
+/// Common / combined log format:
+/// `127.0.0.1 - - [10/Oct/2024:13:55:36 -0700] "GET /api/health HTTP/1.1" 200 17 ...`
+fn parse_clf(line: &str) -> Option<AccessRecord> {
+    let client = line.split_whitespace().next()?.to_string();
+    let time_start = line.find('[')?;
+    let time_end = time_start + line[time_start..].find(']')?;
+    let timestamp = parse_clf_time(&line[time_start + 1..time_end])?;
+
+    let rest = &line[time_end + 1..];
+    let request_start = rest.find('"')?;
+    let request_len = rest[request_start + 1..].find('"')?;
+    let request = &rest[request_start + 1..request_start + 1 + request_len];
+    let mut request = request.split_whitespace();
+    let method = request.next()?.to_string();
+    let path = request.next()?.to_string();
+
+    let status = rest[request_start + request_len + 2..]
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()?;
+    Some(AccessRecord {
+        timestamp,
+        client: Some(client).filter(|c| c != "-"),
+        method,
+        path,
+        status,
+    })
+}
+
+/// JSON lines with common field names (`time`/`ts`, `method`, `path`/`url`, `status`, `ip`)
+fn parse_json(line: &str) -> Option<AccessRecord> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    let field = |names: &[&str]| names.iter().find_map(|name| value.get(*name));
+
+    let timestamp = match field(&["time", "ts", "timestamp"])? {
+        Value::String(text) => parse_rfc3339(text)?,
+        // Epoch milliseconds or seconds
+        Value::Number(n) => {
+            let n = n.as_i64()?;
+            if n > 10_000_000_000 {
+                n / 1000
+            } else {
+                n
+            }
+        }
+        _ => return None,
+    };
+    let status = field(&["status", "statusCode"])?.as_u64()? as u16;
+    Some(AccessRecord {
+        timestamp,
+        client: field(&["ip", "remoteAddr", "client", "remote_addr"])
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        method: field(&["method"])
+            .and_then(Value::as_str)
+            .unwrap_or("GET")
+            .to_string(),
+        path: field(&["path", "url", "uri"])?.as_str()?.to_string(),
+        status,
+    })
+}
+
+fn parse_access_line(line: &str) -> Option<AccessRecord> {
+    if line.starts_with('{') {
+        parse_json(line)
+    } else {
+        parse_clf(line)
+    }
+}
+
+fn is_identifier(segment: &str) -> bool {
+    let numeric = !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit());
+    let opaque = segment.len() >= 16
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() || c == '-' || c == '_');
+    numeric || opaque
+}
+
+/// Group requests per route: drop the query and replace id-like segments with `:id`
+fn normalize_path(path: &str) -> String {
+    let path = path.split(['?', '#']).next().unwrap_or(path);
+    path.split('/')
+        .map(|segment| {
+            if is_identifier(segment) {
+                ":id"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn summarize(lines: &[String], window_secs: u64, now: i64) -> AccessSummary {
+    let since = now - window_secs as i64;
+    let mut summary = AccessSummary {
+        available: true,
+        window_secs,
+        ..AccessSummary::default()
+    };
+    let mut endpoints: HashMap<(String, String), (u64, u64)> = HashMap::new();
+    let mut clients: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut errors = 0u64;
+
+    for line in lines.iter().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+        let Some(record) = parse_access_line(line) else {
+            summary.unparsed_lines += 1;
+            continue;
+        };
+        if record.timestamp < since {
+            continue;
+        }
+        let is_error = record.status >= 400;
+        summary.total_requests += 1;
+        errors += is_error as u64;
+        *summary
+            .status_classes
+            .entry(format!("{}xx", record.status / 100))
+            .or_default() += 1;
+
+        let endpoint = endpoints
+            .entry((record.method, normalize_path(&record.path)))
+            .or_default();
+        endpoint.0 += 1;
+        endpoint.1 += is_error as u64;
+        if let Some(client) = record.client {
+            let client = clients.entry(client).or_default();
+            client.0 += 1;
+            client.1 += is_error as u64;
+        }
+    }
+
+    if summary.total_requests > 0 {
+        summary.error_rate = errors as f64 / summary.total_requests as f64;
+        summary.requests_per_minute =
+            summary.total_requests as f64 / (window_secs.max(60) as f64 / 60.0);
+    }
+
+    let mut top_endpoints: Vec<EndpointCount> = endpoints
+        .into_iter()
+        .map(|((method, path), (count, errors))| EndpointCount {
+            method,
+            path,
+            count,
+            errors,
+        })
+        .collect();
+    top_endpoints.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.path.cmp(&b.path)));
+    top_endpoints.truncate(TOP_LIMIT);
+    summary.top_endpoints = top_endpoints;
+
+    summary.unique_clients = clients.len();
+    let mut top_clients: Vec<ClientCount> = clients
+        .into_iter()
+        .map(|(client, (count, errors))| ClientCount {
+            client,
+            count,
+            errors,
+        })
+        .collect();
+    top_clients.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.client.cmp(&b.client)));
+    top_clients.truncate(TOP_LIMIT);
+    summary.top_clients = top_clients;
+    summary
+}
+
+/// Aggregate the gateway's HTTP access log over the last `window` seconds (default
+/// one hour): request rate, status mix, busiest endpoints and clients
+#[tauri::command]
+pub async fn get_access_summary(window: Option<u64>) -> Result<AccessSummary, DesktopError> {
+    let window_secs = window.unwrap_or(DEFAULT_WINDOW_SECS);
+    tauri::async_runtime::spawn_blocking(move || {
+        let path = access_log_path()?;
+        if !path.exists() {
+            return Ok(AccessSummary {
+                window_secs,
+                ..AccessSummary::default()
+            });
+        }
+        let size = fs::metadata(&path)
+            .map_err(|e| format!("Failed to read access log: {}", e))?
+            .len();
+        let (page, _, _) = logs::read_page(&path, size.saturating_sub(SCAN_BYTES), SCAN_BYTES)?;
+        let lines: Vec<String> = page.into_iter().map(|line| line.text).collect();
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let mut summary = summarize(&lines, window_secs, now);
+        summary.log_path = Some(path.display().to_string());
+        Ok(summary)
+    })
+    .await
+    .map_err(|e| format!("Failed to summarize access log: {}", e))?
+}
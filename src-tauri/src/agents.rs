@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::error::DesktopError;
+use crate::events::{AgentChange, AgentConfigChanged, AGENT_CONFIG_CHANGED_EVENT};
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+use crate::kiosk;
+use crate::{openclaw_home_dir, paths};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentSource {
+    Gateway,
+    Disk,
+}
+
+/// An agent definition as stored in `~/.openclaw/agents/<id>.json` or served by the gateway
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentDefinition {
+    pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    /// Settings this app doesn't know about, kept as they are
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentEntry {
+    #[serde(flatten)]
+    pub agent: AgentDefinition,
+    pub source: AgentSource,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AgentsResponse {
+    List(Vec<AgentDefinition>),
+    Wrapped { agents: Vec<AgentDefinition> },
+}
+
+fn agents_dir() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join("agents"))
+}
+
+/// Agent ids become file names and URL segments, so keep them plain
+fn check_agent_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        && id.len() <= 64;
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid agent id '{}': use up to 64 letters, numbers, '-' or '_'",
+            id
+        ))
+    }
+}
+
+pub(crate) fn validate(agent: &AgentDefinition) -> Result<(), String> {
+    check_agent_id(&agent.id)?;
+    if agent.name.as_deref().is_some_and(|n| n.trim().is_empty()) {
+        return Err("Agent name cannot be empty".to_string());
+    }
+    if agent.model.as_deref().is_some_and(|m| m.trim().is_empty()) {
+        return Err("Agent model cannot be empty".to_string());
+    }
+    Ok(())
+}
+
+fn agent_file(id: &str) -> Result<PathBuf, String> {
+    paths::join_within(&agents_dir()?, format!("{}.json", id))
+}
+
+fn read_agent_file(path: &PathBuf) -> Option<AgentDefinition> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub(crate) fn list_agent_files() -> Result<Vec<AgentEntry>, String> {
+    let dir = agents_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(&dir).map_err(|e| format!("Failed to read agents directory: {}", e))?;
+    let mut agents: Vec<AgentEntry> = entries
+        .filter_map(|e| e.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| read_agent_file(&path))
+        .map(|agent| AgentEntry {
+            agent,
+            source: AgentSource::Disk,
+        })
+        .collect();
+    agents.sort_by(|a, b| a.agent.id.cmp(&b.agent.id));
+    Ok(agents)
+}
+
+pub(crate) fn write_agent_file(agent: &AgentDefinition) -> Result<(), String> {
+    let dir = agents_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create agents directory: {}", e))?;
+    let content = serde_json::to_string_pretty(agent)
+        .map_err(|e| format!("Failed to serialize agent: {}", e))?;
+    let path = agent_file(&agent.id)?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, content).map_err(|e| format!("Failed to write agent: {}", e))?;
+    fs::rename(&tmp_path, &path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("Failed to save agent: {}", e)
+    })
+}
+
+pub(crate) fn emit_change(app: &AppHandle, id: &str, change: AgentChange) {
+    let payload = AgentConfigChanged {
+        id: id.to_string(),
+        change,
+    };
+    if let Err(e) = app.emit(AGENT_CONFIG_CHANGED_EVENT, payload) {
+        log_error!("Failed to emit agent change: {}", e);
+    }
+}
+
+/// List agents from the gateway, or from `~/.openclaw/agents/` while it is down
+#[tauri::command]
+pub async fn list_agents(api: State<'_, GatewayApi>) -> Result<Vec<AgentEntry>, DesktopError> {
+    match api.get::<AgentsResponse>("/api/agents").await {
+        Ok(AgentsResponse::List(agents) | AgentsResponse::Wrapped { agents }) => Ok(agents
+            .into_iter()
+            .map(|agent| AgentEntry {
+                agent,
+                source: AgentSource::Gateway,
+            })
+            .collect()),
+        Err(e) if e.kind == GatewayApiErrorKind::NotRunning => Ok(list_agent_files()?),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[tauri::command]
+pub async fn create_agent(
+    app: AppHandle,
+    api: State<'_, GatewayApi>,
+    agent: AgentDefinition,
+) -> Result<AgentEntry, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    validate(&agent)?;
+    let entry = match api.post::<AgentDefinition>("/api/agents", &agent).await {
+        Ok(created) => AgentEntry {
+            agent: created,
+            source: AgentSource::Gateway,
+        },
+        Err(e) if e.kind == GatewayApiErrorKind::NotRunning => {
+            if agent_file(&agent.id)?.exists() {
+                return Err(DesktopError::InvalidInput(format!(
+                    "Agent '{}' already exists",
+                    agent.id
+                )));
+            }
+            write_agent_file(&agent)?;
+            AgentEntry {
+                agent,
+                source: AgentSource::Disk,
+            }
+        }
+        Err(e) => return Err(e.into()),
+    };
+    emit_change(&app, &entry.agent.id, AgentChange::Created);
+    Ok(entry)
+}
+
+/// Replace an agent's definition; the id in the path wins over the one in `agent`
+#[tauri::command]
+pub async fn update_agent_config(
+    app: AppHandle,
+    api: State<'_, GatewayApi>,
+    id: String,
+    mut agent: AgentDefinition,
+) -> Result<AgentEntry, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    check_agent_id(&id)?;
+    agent.id = id.clone();
+    validate(&agent)?;
+    let path = format!("/api/agents/{}", id);
+    let entry = match api.put::<AgentDefinition>(&path, &agent).await {
+        Ok(updated) => AgentEntry {
+            agent: updated,
+            source: AgentSource::Gateway,
+        },
+        Err(e) if e.kind == GatewayApiErrorKind::NotRunning => {
+            if !agent_file(&id)?.exists() {
+                return Err(DesktopError::NotFound(format!("Agent '{}' not found", id)));
+            }
+            write_agent_file(&agent)?;
+            AgentEntry {
+                agent,
+                source: AgentSource::Disk,
+            }
+        }
+        Err(e) => return Err(e.into()),
+    };
+    emit_change(&app, &id, AgentChange::Updated);
+    Ok(entry)
+}
+
+#[tauri::command]
+pub async fn delete_agent(
+    app: AppHandle,
+    api: State<'_, GatewayApi>,
+    id: String,
+) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    check_agent_id(&id)?;
+    match api.delete(&format!("/api/agents/{}", id)).await {
+        Ok(()) => {}
+        Err(e) if e.kind == GatewayApiErrorKind::NotRunning => {
+            let path = agent_file(&id)?;
+            if !path.exists() {
+                return Err(DesktopError::NotFound(format!("Agent '{}' not found", id)));
+            }
+            fs::remove_file(path).map_err(|e| format!("Failed to delete agent: {}", e))?;
+        }
+        Err(e) => return Err(e.into()),
+    }
+    emit_change(&app, &id, AgentChange::Deleted);
+    Ok(())
+}
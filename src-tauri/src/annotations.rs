@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::DesktopError;
+use crate::kiosk;
+use crate::logs::{read_page, resolve_log_file};
+
+const ANNOTATIONS_FILE: &str = "log-annotations.json";
+
+/// Longest line snippet kept with an annotation
+const SNIPPET_MAX_CHARS: usize = 500;
+
+static ID_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogAnnotation {
+    pub id: String,
+    /// Log file id relative to the OpenClaw home, e.g. `gateway.log`
+    pub file: String,
+    /// Byte offset of the annotated line's first byte
+    pub offset: u64,
+    /// Text of the line when it was annotated, to re-anchor after the log is rewritten
+    pub line_text: String,
+    /// `None` for a plain bookmark
+    pub note: Option<String>,
+    pub created_at: u64,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn next_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!(
+        "{:x}-{:x}",
+        nanos,
+        ID_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn annotations_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(ANNOTATIONS_FILE))
+}
+
+pub(crate) fn load<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<LogAnnotation>, String> {
+    let path = annotations_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read annotations: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse annotations: {}", e))
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, annotations: &[LogAnnotation]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(annotations)
+        .map_err(|e| format!("Failed to serialize annotations: {}", e))?;
+    fs::write(annotations_path(app)?, content)
+        .map_err(|e| format!("Failed to write annotations: {}", e))
+}
+
+fn snippet(text: &str) -> String {
+    text.chars().take(SNIPPET_MAX_CHARS).collect()
+}
+
+fn line_at(path: &Path, offset: u64) -> Result<String, String> {
+    let (lines, _, _) = read_page(path, offset, SNIPPET_MAX_CHARS as u64 * 4)?;
+    let line = lines
+        .into_iter()
+        .next()
+        .filter(|line| line.offset == offset)
+        .ok_or_else(|| format!("No log line at offset {}", offset))?;
+    Ok(snippet(&line.text))
+}
+
+/// Offset of the line reading `line_text` closest to `near`
+fn find_line(path: &Path, line_text: &str, near: u64) -> Option<u64> {
+    let (lines, _, _) = read_page(path, 0, u64::MAX).ok()?;
+    lines
+        .into_iter()
+        .filter(|line| snippet(&line.text) == line_text)
+        .map(|line| line.offset)
+        .min_by_key(|offset| offset.abs_diff(near))
+}
+
+/// Load annotations, moving any whose line shifted (e.g. the log was rewritten
+/// or trimmed) to where that line is now. `file` limits the check to one log.
+pub(crate) fn load_anchored<R: Runtime>(
+    app: &AppHandle<R>,
+    file: Option<&str>,
+) -> Result<Vec<LogAnnotation>, String> {
+    let mut annotations = load(app)?;
+    let mut moved = false;
+    for annotation in annotations
+        .iter_mut()
+        .filter(|a| file.is_none_or(|f| a.file == f))
+    {
+        let Ok(path) = resolve_log_file(&annotation.file) else {
+            continue;
+        };
+        if line_at(&path, annotation.offset).is_ok_and(|text| text == annotation.line_text) {
+            continue;
+        }
+        if let Some(offset) = find_line(&path, &annotation.line_text, annotation.offset) {
+            annotation.offset = offset;
+            moved = true;
+        }
+    }
+    if moved {
+        save(app, &annotations)?;
+    }
+    Ok(annotations)
+}
+
+/// Bookmark a log line, optionally attaching a note
+#[tauri::command]
+pub fn add_log_annotation(
+    app: AppHandle,
+    file: String,
+    offset: u64,
+    note: Option<String>,
+) -> Result<LogAnnotation, DesktopError> {
+    let path = resolve_log_file(&file)?;
+    let annotation = LogAnnotation {
+        id: next_id(),
+        line_text: line_at(&path, offset)?,
+        file,
+        offset,
+        note: note.filter(|n| !n.trim().is_empty()),
+        created_at: now_secs(),
+    };
+
+    let mut annotations = load(&app)?;
+    annotations.push(annotation.clone());
+    save(&app, &annotations)?;
+    Ok(annotation)
+}
+
+/// Update the note on an existing annotation
+#[tauri::command]
+pub fn update_log_annotation(
+    app: AppHandle,
+    id: String,
+    note: Option<String>,
+) -> Result<LogAnnotation, DesktopError> {
+    let mut annotations = load(&app)?;
+    let annotation = annotations
+        .iter_mut()
+        .find(|a| a.id == id)
+        .ok_or_else(|| DesktopError::NotFound(format!("Annotation {} not found", id)))?;
+    annotation.note = note.filter(|n| !n.trim().is_empty());
+    let updated = annotation.clone();
+    save(&app, &annotations)?;
+    Ok(updated)
+}
+
+/// List annotations, optionally limited to one log file
+#[tauri::command]
+pub fn list_log_annotations(
+    app: AppHandle,
+    file: Option<String>,
+) -> Result<Vec<LogAnnotation>, DesktopError> {
+    let mut annotations: Vec<LogAnnotation> = load_anchored(&app, file.as_deref())?
+        .into_iter()
+        .filter(|a| file.as_ref().is_none_or(|f| &a.file == f))
+        .collect();
+    annotations.sort_by(|a, b| a.file.cmp(&b.file).then(a.offset.cmp(&b.offset)));
+    Ok(annotations)
+}
+
+#[tauri::command]
+pub fn delete_log_annotation(app: AppHandle, id: String) -> Result<(), DesktopError> {
+    let mut annotations = load(&app)?;
+    let before = annotations.len();
+    annotations.retain(|a| a.id != id);
+    if annotations.len() == before {
+        return Err(DesktopError::NotFound(format!(
+            "Annotation {} not found",
+            id
+        )));
+    }
+    Ok(save(&app, &annotations)?)
+}
+
+/// Render annotations as a Markdown section for exported reports
+pub(crate) fn render_report<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    let mut annotations = load_anchored(app, None)?;
+    annotations.sort_by(|a, b| a.file.cmp(&b.file).then(a.offset.cmp(&b.offset)));
+
+    let mut report = String::from("## Log annotations\n\n");
+    if annotations.is_empty() {
+        report.push_str("_No annotations._\n");
+        return Ok(report);
+    }
+
+    for annotation in &annotations {
+        // Flag notes whose line has since been rotated away
+        let current = resolve_log_file(&annotation.file)
+            .and_then(|path| line_at(&path, annotation.offset))
+            .ok();
+        let stale = current.as_deref() != Some(annotation.line_text.as_str());
+
+        let _ = writeln!(
+            report,
+            "- **{}** @ byte {}{}",
+            annotation.file,
+            annotation.offset,
+            if stale {
+                " (line no longer in log)"
+            } else {
+                ""
+            }
+        );
+        let _ = writeln!(report, "  ```\n  {}\n  ```", annotation.line_text);
+        if let Some(note) = &annotation.note {
+            let _ = writeln!(report, "  {}", note);
+        }
+    }
+    Ok(report)
+}
+
+/// Write a Markdown report of all log annotations to `path`
+#[tauri::command]
+pub fn export_log_report(app: AppHandle, path: String) -> Result<String, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let report = format!("# OpenClaw log report\n\n{}", render_report(&app)?);
+    fs::write(&path, report).map_err(|e| format!("Failed to write report: {}", e))?;
+    Ok(path)
+}
@@ -0,0 +1,54 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::events::{Announcement, Politeness, ANNOUNCE_EVENT};
+
+/// The same message again within this window is dropped
+const DEDUPE_WINDOW: Duration = Duration::from_secs(30);
+/// At most `RATE_LIMIT` announcements per `RATE_WINDOW`; assertive ones always go out
+const RATE_LIMIT: usize = 3;
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+static RECENT: Mutex<VecDeque<(Instant, String)>> = Mutex::new(VecDeque::new());
+
+/// Whether a message may go out now, recording it if so
+fn admit(message: &str, politeness: Politeness) -> bool {
+    let Ok(mut recent) = RECENT.lock() else {
+        return true;
+    };
+    let now = Instant::now();
+    while recent
+        .front()
+        .is_some_and(|(at, _)| now.duration_since(*at) > DEDUPE_WINDOW)
+    {
+        recent.pop_front();
+    }
+    if recent.iter().any(|(_, text)| text == message) {
+        return false;
+    }
+    let in_window = recent
+        .iter()
+        .filter(|(at, _)| now.duration_since(*at) <= RATE_WINDOW)
+        .count();
+    if politeness == Politeness::Polite && in_window >= RATE_LIMIT {
+        return false;
+    }
+    recent.push_back((now, message.to_string()));
+    true
+}
+
+/// Send a short screen-reader message for the frontend's ARIA live region
+pub fn announce<R: Runtime>(app: &AppHandle<R>, politeness: Politeness, message: &str) {
+    if !admit(message, politeness) {
+        return;
+    }
+    let payload = Announcement {
+        message: message.to_string(),
+        politeness,
+    };
+    if let Err(e) = app.emit(ANNOUNCE_EVENT, payload) {
+        log_error!("Failed to emit announcement: {}", e);
+    }
+}
@@ -0,0 +1,46 @@
+//! The app's own log, `desktop.log` next to the gateway logs. Release builds
+//! have no console on Windows and macOS, so anything only printed to stderr
+//! would be lost; this file is rotated with the gateway logs and included in
+//! diagnostics bundles.
+
+use chrono::{Local, SecondsFormat};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::paths;
+
+const APP_LOG_FILE: &str = "desktop.log";
+
+pub(crate) fn log_path() -> Result<PathBuf, String> {
+    Ok(paths::log_dir()?.join(APP_LOG_FILE))
+}
+
+/// Append a timestamped line to the app log, echoed to stderr in debug builds.
+/// Use through `log_error!`, which formats like `eprintln!`.
+pub(crate) fn write(message: &str) {
+    let line = format!(
+        "{} {}\n",
+        Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+        message
+    );
+    if cfg!(debug_assertions) {
+        eprint!("{}", line);
+    }
+    // The path is resolved first and nothing is locked, so a failure logged
+    // while resolving the data root can't deadlock or recurse
+    let written = log_path().and_then(|path| {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| e.to_string())
+    });
+    if let Err(e) = written {
+        if !cfg!(debug_assertions) {
+            eprint!("{}", line);
+        }
+        eprintln!("Failed to write the app log: {}", e);
+    }
+}
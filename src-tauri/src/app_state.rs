@@ -0,0 +1,147 @@
+//! The state the main window renders, kept in one place on the Rust side.
+//!
+//! Background monitors and commands change it through [`update`], which bumps
+//! the revision and emits `state-changed` with only the top-level fields that
+//! changed. The frontend loads the whole state once with `get_app_state` and
+//! applies each diff on top, reloading when it sees a revision gap.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Runtime};
+use ts_rs::TS;
+
+use crate::connectivity::{self, ConnectivityState};
+use crate::events::{TaskProgress, STATE_CHANGED_EVENT};
+use crate::{
+    detect_openclaw_version, gateway_status, openclaw_installed, profiles, tasks, GatewayStatus,
+};
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct InstallState {
+    pub installed: bool,
+    pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct AppState {
+    /// Bumped on every change; a diff applies on top of the revision before it
+    #[ts(type = "number")]
+    pub revision: u64,
+    /// Unset until the first check after launch, as is `install`
+    pub gateway: Option<GatewayStatus>,
+    pub install: Option<InstallState>,
+    pub active_profile: Option<String>,
+    pub connectivity: Option<ConnectivityState>,
+    /// Running tasks; finished ones drop out
+    pub tasks: Vec<TaskProgress>,
+}
+
+/// Payload of `state-changed`
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct StateChanged {
+    #[ts(type = "number")]
+    pub revision: u64,
+    /// The fields that changed, with their new values
+    #[ts(type = "Partial<Omit<AppState, \"revision\">>")]
+    pub changes: Map<String, Value>,
+}
+
+fn store() -> &'static Mutex<AppState> {
+    static STATE: OnceLock<Mutex<AppState>> = OnceLock::new();
+    STATE.get_or_init(|| Mutex::new(AppState::default()))
+}
+
+fn fields(state: &AppState) -> Map<String, Value> {
+    match serde_json::to_value(state) {
+        Ok(Value::Object(mut fields)) => {
+            fields.remove("revision");
+            fields
+        }
+        _ => Map::new(),
+    }
+}
+
+/// Change the state and broadcast what changed; nothing is sent for a no-op
+pub(crate) fn update<R: Runtime>(app: &AppHandle<R>, change: impl FnOnce(&mut AppState)) {
+    let event = {
+        let Ok(mut state) = store().lock() else {
+            return;
+        };
+        let before = fields(&state);
+        change(&mut state);
+        let changes: Map<String, Value> = fields(&state)
+            .into_iter()
+            .filter(|(key, value)| before.get(key) != Some(value))
+            .collect();
+        if changes.is_empty() {
+            return;
+        }
+        state.revision += 1;
+        StateChanged {
+            revision: state.revision,
+            changes,
+        }
+    };
+    if let Err(e) = app.emit(STATE_CHANGED_EVENT, event) {
+        log_error!("Failed to emit state change: {}", e);
+    }
+}
+
+pub(crate) fn set_gateway<R: Runtime>(app: &AppHandle<R>, status: GatewayStatus) {
+    update(app, |state| state.gateway = Some(status));
+}
+
+pub(crate) fn set_connectivity<R: Runtime>(app: &AppHandle<R>, connectivity: ConnectivityState) {
+    update(app, |state| state.connectivity = Some(connectivity));
+}
+
+pub(crate) fn refresh_tasks<R: Runtime>(app: &AppHandle<R>) {
+    update(app, |state| state.tasks = tasks::list_tasks());
+}
+
+pub(crate) fn refresh_profile<R: Runtime>(app: &AppHandle<R>) {
+    update(app, |state| {
+        state.active_profile = profiles::active_profile()
+    });
+}
+
+/// Re-detect the CLI after it was installed, updated or removed
+pub(crate) async fn refresh_install<R: Runtime>(app: &AppHandle<R>) {
+    let install = tauri::async_runtime::spawn_blocking(|| InstallState {
+        installed: openclaw_installed(),
+        version: detect_openclaw_version(),
+    })
+    .await;
+    match install {
+        Ok(install) => update(app, |state| state.install = Some(install)),
+        Err(e) => log_error!("Failed to detect OpenClaw install: {}", e),
+    }
+}
+
+/// Fill in everything once at launch; the monitors keep it current from there
+pub fn spawn_initial_load<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        refresh_profile(&app);
+        refresh_tasks(&app);
+        if let Some(connectivity) = connectivity::cached() {
+            set_connectivity(&app, connectivity);
+        }
+        refresh_install(&app).await;
+        if let Ok(status) = tauri::async_runtime::spawn_blocking(gateway_status).await {
+            set_gateway(&app, status);
+        }
+    });
+}
+
+/// The whole state, to apply `state-changed` diffs on top of
+#[tauri::command]
+pub fn get_app_state() -> AppState {
+    store()
+        .lock()
+        .map(|state| state.clone())
+        .unwrap_or_default()
+}
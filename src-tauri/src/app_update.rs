@@ -0,0 +1,259 @@
+//! Updates for the desktop app itself, through the Tauri updater and the
+//! `latest.json` manifest attached to each GitHub release (the endpoint is in
+//! `tauri.conf.json`).
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::error::DesktopError;
+use crate::events::{AppUpdateProgress, APP_UPDATE_PROGRESS_EVENT};
+use crate::i18n;
+use crate::notifications::{self, NotificationCategory};
+use crate::{kiosk, settings, tray};
+
+/// Minisign public key of the release signing key, provided by the release build
+const UPDATER_PUBKEY: Option<&str> = option_env!("OPENCLAW_UPDATER_PUBKEY");
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+/// Progress is reported in steps of this many bytes when the size is unknown
+const PROGRESS_STEP: u64 = 256 * 1024;
+
+static PENDING: Mutex<Option<PendingUpdate>> = Mutex::new(None);
+
+struct PendingUpdate {
+    update: Update,
+    /// Signed package, once downloaded
+    bytes: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppUpdateSettings {
+    /// Look for a new release in the background and flag it in the tray
+    pub auto_check: bool,
+}
+
+impl Default for AppUpdateSettings {
+    fn default() -> Self {
+        Self { auto_check: true }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppUpdateCheck {
+    pub current: String,
+    /// Newer version on offer, if any
+    pub available: Option<String>,
+    pub notes: Option<String>,
+    /// The package is downloaded and ready to install
+    pub downloaded: bool,
+}
+
+/// Version of the update waiting to be installed, and whether it is downloaded
+pub(crate) fn pending() -> Option<(String, bool)> {
+    let pending = PENDING.lock().ok()?;
+    pending
+        .as_ref()
+        .map(|p| (p.update.version.clone(), p.bytes.is_some()))
+}
+
+fn status<R: Runtime>(app: &AppHandle<R>) -> AppUpdateCheck {
+    let pending = PENDING.lock().ok();
+    let pending = pending.as_ref().and_then(|p| p.as_ref());
+    AppUpdateCheck {
+        current: app.package_info().version.to_string(),
+        available: pending.map(|p| p.update.version.clone()),
+        notes: pending.and_then(|p| p.update.body.clone()),
+        downloaded: pending.is_some_and(|p| p.bytes.is_some()),
+    }
+}
+
+fn refresh_tray<R: Runtime>(app: &AppHandle<R>) {
+    if let Err(e) = tray::rebuild_tray(app) {
+        log_error!("Failed to refresh tray menu: {}", e);
+    }
+}
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, payload: AppUpdateProgress) {
+    if let Err(e) = app.emit(APP_UPDATE_PROGRESS_EVENT, payload) {
+        log_error!("Failed to emit app update progress: {}", e);
+    }
+}
+
+/// Ask the release feed for a newer version; returns whether one is pending
+async fn check<R: Runtime>(app: &AppHandle<R>) -> Result<bool, String> {
+    let pubkey = UPDATER_PUBKEY.ok_or("Self-update is not available in this build")?;
+    let update = app
+        .updater_builder()
+        .pubkey(pubkey)
+        .build()
+        .map_err(|e| format!("Failed to set up updater: {}", e))?
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {}", e))?;
+
+    let mut pending = PENDING
+        .lock()
+        .map_err(|_| "Update lock poisoned".to_string())?;
+    match update {
+        // Keep an already downloaded package for the same version
+        Some(update)
+            if pending
+                .as_ref()
+                .is_some_and(|p| p.update.version == update.version) => {}
+        Some(update) => {
+            *pending = Some(PendingUpdate {
+                update,
+                bytes: None,
+            })
+        }
+        None => *pending = None,
+    }
+    Ok(pending.is_some())
+}
+
+/// Check GitHub Releases for a newer version of the app
+#[tauri::command]
+pub async fn check_app_update(app: AppHandle) -> Result<AppUpdateCheck, DesktopError> {
+    check(&app).await?;
+    refresh_tray(&app);
+    Ok(status(&app))
+}
+
+async fn download<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let update = {
+        let pending = PENDING
+            .lock()
+            .map_err(|_| "Update lock poisoned".to_string())?;
+        match pending.as_ref() {
+            Some(p) if p.bytes.is_some() => return Ok(()),
+            Some(p) => p.update.clone(),
+            None => return Err("No update available; check for updates first".to_string()),
+        }
+    };
+    let version = update.version.clone();
+    let progress = |stage: &'static str, downloaded: u64, total: Option<u64>| AppUpdateProgress {
+        version: version.clone(),
+        stage,
+        downloaded,
+        total,
+    };
+
+    emit_progress(app, progress("started", 0, None));
+    let mut downloaded = 0u64;
+    let mut reported = 0u64;
+    let result = update
+        .download(
+            |chunk, total| {
+                downloaded += chunk as u64;
+                let step = total.map(|t| (t / 100).max(1)).unwrap_or(PROGRESS_STEP);
+                if downloaded - reported >= step || total == Some(downloaded) {
+                    reported = downloaded;
+                    emit_progress(app, progress("downloading", downloaded, total));
+                }
+            },
+            || {},
+        )
+        .await;
+    let bytes = match result {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            emit_progress(app, progress("failed", downloaded, None));
+            return Err(format!("Failed to download update: {}", e));
+        }
+    };
+
+    let size = bytes.len() as u64;
+    if let Ok(mut pending) = PENDING.lock() {
+        if let Some(p) = pending.as_mut().filter(|p| p.update.version == version) {
+            p.bytes = Some(bytes);
+        }
+    }
+    emit_progress(app, progress("finished", size, Some(size)));
+    refresh_tray(app);
+    Ok(())
+}
+
+/// Download the pending update, reporting `app-update-progress` events
+#[tauri::command]
+pub async fn download_app_update(app: AppHandle) -> Result<AppUpdateCheck, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    download(&app).await?;
+    Ok(status(&app))
+}
+
+fn install<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let mut pending = PENDING
+        .lock()
+        .map_err(|_| "Update lock poisoned".to_string())?;
+    let Some(PendingUpdate {
+        update,
+        bytes: Some(bytes),
+    }) = pending.as_ref()
+    else {
+        return Err("Download the update before installing it".to_string());
+    };
+    update
+        .install(bytes)
+        .map_err(|e| format!("Failed to install update: {}", e))?;
+    *pending = None;
+    drop(pending);
+    app.restart()
+}
+
+/// Install the downloaded update and relaunch into the new version
+#[tauri::command]
+pub fn install_app_update(app: AppHandle) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    Ok(install(&app)?)
+}
+
+/// Tray entry: download if needed, then install and relaunch
+pub(crate) fn update_from_tray<R: Runtime>(app: &AppHandle<R>) {
+    if kiosk::is_locked() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = download(&app).await.and_then(|_| install(&app)) {
+            log_error!("{}", e);
+        }
+    });
+}
+
+/// Check for app updates every few hours while `app_update.auto_check` is on,
+/// notifying once per new version
+pub fn spawn_app_update_checker<R: Runtime>(app: AppHandle<R>) {
+    if UPDATER_PUBKEY.is_none() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        let mut announced: Option<String> = None;
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if !settings::current().app_update.auto_check {
+                continue;
+            }
+            if let Err(e) = check(&app).await {
+                log_error!("{}", e);
+                continue;
+            }
+            refresh_tray(&app);
+            let Some((version, _)) = pending() else {
+                continue;
+            };
+            if announced.as_deref() != Some(version.as_str()) {
+                notifications::notify(
+                    &app,
+                    NotificationCategory::AppUpdate,
+                    &i18n::t("app_update.available"),
+                    &i18n::t_with("app_update.available_body", &[("version", &version)]),
+                );
+                announced = Some(version);
+            }
+        }
+    });
+}
@@ -0,0 +1,246 @@
+//! Tool calls the gateway holds until someone allows or denies them.
+//!
+//! The event bridge feeds requests and resolutions into a queue kept here. Each
+//! new request raises a notification, with Allow and Deny buttons where the
+//! platform supports them, and the tray shows how many are waiting.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use ts_rs::TS;
+
+use crate::error::DesktopError;
+use crate::events::APPROVALS_CHANGED_EVENT;
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+use crate::i18n::{t, t_with};
+use crate::notifications::{self, NotificationCategory};
+use crate::window::show_main_window;
+use crate::{audit, kiosk, tray};
+
+const APPROVALS_PATH: &str = "/api/approvals";
+pub(crate) const REQUESTED_EVENT: &str = "tool.approval_requested";
+pub(crate) const RESOLVED_EVENT: &str = "tool.approval_resolved";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct PendingApproval {
+    pub id: String,
+    /// Tool the agent wants to run, e.g. `shell` or `write_file`
+    pub tool: String,
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// What the call would do, as the gateway describes it
+    #[serde(default)]
+    pub summary: Option<String>,
+    /// Unix seconds; filled in on arrival when the gateway leaves it out
+    #[serde(default)]
+    #[ts(type = "number")]
+    pub requested_at: u64,
+}
+
+static QUEUE: Mutex<Vec<PendingApproval>> = Mutex::new(Vec::new());
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+pub(crate) fn pending_count() -> usize {
+    QUEUE.lock().map(|queue| queue.len()).unwrap_or_default()
+}
+
+fn pending() -> Vec<PendingApproval> {
+    QUEUE.lock().map(|queue| queue.clone()).unwrap_or_default()
+}
+
+/// Tell the UI and redraw the tray after the queue changed
+fn publish<R: Runtime>(app: &AppHandle<R>) {
+    if let Err(e) = app.emit(APPROVALS_CHANGED_EVENT, pending()) {
+        log_error!("Failed to emit approvals change: {}", e);
+    }
+    if let Err(e) = tray::rebuild_tray(app) {
+        log_error!("Failed to refresh tray: {}", e);
+    }
+}
+
+fn remove(id: &str) -> bool {
+    QUEUE.lock().is_ok_and(|mut queue| {
+        let before = queue.len();
+        queue.retain(|approval| approval.id != id);
+        queue.len() != before
+    })
+}
+
+fn notify_request<R: Runtime>(app: &AppHandle<R>, approval: &PendingApproval) {
+    let agent = approval
+        .agent
+        .clone()
+        .unwrap_or_else(|| t("approvals.unknown_agent"));
+    let title = t("approvals.requested");
+    let body = match &approval.summary {
+        Some(summary) => t_with(
+            "approvals.requested_summary",
+            &[
+                ("agent", &agent),
+                ("tool", &approval.tool),
+                ("summary", summary),
+            ],
+        ),
+        None => t_with(
+            "approvals.requested_body",
+            &[("agent", &agent), ("tool", &approval.tool)],
+        ),
+    };
+    let actions = [
+        ("allow", t("approvals.allow")),
+        ("deny", t("approvals.deny")),
+    ];
+    let id = approval.id.clone();
+    let app_handle = app.clone();
+    notifications::notify_actionable(
+        app,
+        NotificationCategory::ToolApproval,
+        &title,
+        &body,
+        &actions,
+        move |action| match action.as_str() {
+            "allow" | "deny" => {
+                let allow = action == "allow";
+                tauri::async_runtime::spawn(async move {
+                    let api = app_handle.state::<GatewayApi>();
+                    if let Err(e) = respond(&app_handle, &api, &id, allow).await {
+                        log_error!("Failed to answer approval from notification: {}", e);
+                    }
+                });
+            }
+            _ => show_main_window(&app_handle),
+        },
+    );
+}
+
+fn add<R: Runtime>(app: &AppHandle<R>, mut approval: PendingApproval) {
+    if approval.requested_at == 0 {
+        approval.requested_at = now_secs();
+    }
+    let added = QUEUE.lock().is_ok_and(|mut queue| {
+        if queue.iter().any(|a| a.id == approval.id) {
+            return false;
+        }
+        queue.push(approval.clone());
+        true
+    });
+    if added {
+        notify_request(app, &approval);
+        publish(app);
+    }
+}
+
+/// Apply a gateway event from the bridge; other event types are ignored
+pub(crate) fn handle_event<R: Runtime>(app: &AppHandle<R>, kind: &str, event: &Value) {
+    // Newer gateways nest the request, older ones put it at the top level
+    let body = event.get("approval").unwrap_or(event);
+    match kind {
+        REQUESTED_EVENT => match serde_json::from_value::<PendingApproval>(body.clone()) {
+            Ok(approval) => add(app, approval),
+            Err(e) => log_error!("Ignoring malformed approval request: {}", e),
+        },
+        RESOLVED_EVENT => {
+            if let Some(id) = body.get("id").and_then(Value::as_str) {
+                if remove(id) {
+                    publish(app);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replace the queue with what the gateway still holds, after (re)connecting
+pub(crate) async fn sync<R: Runtime>(app: &AppHandle<R>) {
+    let api = app.state::<GatewayApi>();
+    let approvals = match api.get::<Vec<PendingApproval>>(APPROVALS_PATH).await {
+        Ok(approvals) => approvals,
+        // Gateways without the listing only announce new requests
+        Err(e) if e.kind == GatewayApiErrorKind::NotFound => return,
+        Err(e) => {
+            log_error!("Failed to list pending approvals: {}", e);
+            return;
+        }
+    };
+    if let Ok(mut queue) = QUEUE.lock() {
+        *queue = approvals;
+    }
+    publish(app);
+}
+
+/// Forget the queue once the bridge loses the gateway
+pub(crate) fn clear<R: Runtime>(app: &AppHandle<R>) {
+    let had_pending = QUEUE.lock().is_ok_and(|mut queue| {
+        let had_pending = !queue.is_empty();
+        queue.clear();
+        had_pending
+    });
+    if had_pending {
+        publish(app);
+    }
+}
+
+async fn respond<R: Runtime>(
+    app: &AppHandle<R>,
+    api: &GatewayApi,
+    id: &str,
+    allow: bool,
+) -> Result<(), DesktopError> {
+    let path = format!("{}/{}", APPROVALS_PATH, urlencoding::encode(id));
+    let result = api
+        .post::<Value>(&path, &serde_json::json!({ "allow": allow }))
+        .await;
+    let action = if allow {
+        "approval_allow"
+    } else {
+        "approval_deny"
+    };
+    audit::record_result(action, id, &result);
+    match result {
+        Ok(_) => {}
+        // Answered elsewhere or timed out on the gateway
+        Err(e) if e.kind == GatewayApiErrorKind::NotFound => {
+            if remove(id) {
+                publish(app);
+            }
+            return Err(DesktopError::NotFound(format!(
+                "Approval {} is no longer pending",
+                id
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    }
+    if remove(id) {
+        publish(app);
+    }
+    Ok(())
+}
+
+/// Tool calls waiting for a decision, oldest first
+#[tauri::command]
+pub fn list_pending_approvals() -> Vec<PendingApproval> {
+    pending()
+}
+
+/// Allow or deny a held tool call
+#[tauri::command]
+pub async fn respond_to_approval(
+    app: AppHandle,
+    api: State<'_, GatewayApi>,
+    id: String,
+    allow: bool,
+) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    respond(&app, &api, &id, allow).await
+}
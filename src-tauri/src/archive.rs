@@ -0,0 +1,185 @@
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+use crate::error::DesktopError;
+use crate::gateway_api::{GatewayApi, GatewaySession};
+use crate::kiosk;
+use crate::sessions::{self, TranscriptMessage};
+
+const ARCHIVE_EXTENSION: &str = "session.json.gz";
+
+/// One archived session: the gateway's metadata plus its full transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionArchive {
+    pub session: GatewaySession,
+    pub messages: Vec<TranscriptMessage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedSession {
+    pub id: String,
+    pub title: Option<String>,
+    pub updated_at: Option<String>,
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveResult {
+    pub archived: Vec<ArchivedSession>,
+    /// Sessions that could not be archived, with the reason
+    pub failed: Vec<(String, String)>,
+}
+
+fn archive_dir(destination: &str) -> Result<PathBuf, String> {
+    let dir = PathBuf::from(destination);
+    if !dir.is_absolute() {
+        return Err("Archive folder must be an absolute path".to_string());
+    }
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create archive folder: {}", e))?;
+    Ok(dir)
+}
+
+fn archive_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.{}", id, ARCHIVE_EXTENSION))
+}
+
+fn write_archive(path: &Path, archive: &SessionArchive) -> Result<u64, String> {
+    let tmp = path.with_extension("tmp");
+    let file = File::create(&tmp).map_err(|e| format!("Failed to create archive: {}", e))?;
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    serde_json::to_writer(&mut encoder, archive)
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    let mut file = encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress archive: {}", e))?;
+    file.flush()
+        .map_err(|e| format!("Failed to write archive: {}", e))?;
+    drop(file);
+    fs::rename(&tmp, path).map_err(|e| format!("Failed to save archive: {}", e))?;
+    Ok(fs::metadata(path).map(|m| m.len()).unwrap_or(0))
+}
+
+fn read_archive(path: &Path) -> Result<SessionArchive, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open archive: {}", e))?;
+    serde_json::from_reader(GzDecoder::new(BufReader::new(file)))
+        .map_err(|e| format!("Failed to read archive {}: {}", path.display(), e))
+}
+
+fn summary(archive: &SessionArchive, path: &Path, size: u64) -> ArchivedSession {
+    ArchivedSession {
+        id: archive.session.id.clone(),
+        title: archive.session.title.clone(),
+        updated_at: archive.session.updated_at.clone(),
+        path: path.display().to_string(),
+        size,
+    }
+}
+
+/// Export sessions last updated before `older_than` (RFC 3339) to compressed JSON in
+/// `destination`, optionally deleting each from the gateway once its archive is written
+#[tauri::command]
+pub async fn archive_sessions(
+    api: State<'_, GatewayApi>,
+    older_than: String,
+    destination: String,
+    delete_from_gateway: bool,
+) -> Result<ArchiveResult, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let dir = archive_dir(&destination)?;
+    let sessions = api.list_sessions().await?;
+
+    let mut result = ArchiveResult::default();
+    // RFC 3339 timestamps in the same zone order lexicographically
+    for session in sessions.into_iter().filter(|s| {
+        s.updated_at
+            .as_deref()
+            .or(s.created_at.as_deref())
+            .is_some_and(|t| t < older_than.as_str())
+    }) {
+        let id = session.id.clone();
+        if let Err(e) = sessions::check_session_id(&id) {
+            result.failed.push((id, e));
+            continue;
+        }
+        let messages = match sessions::fetch_transcript(&api, &id).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                result.failed.push((id, e.to_string()));
+                continue;
+            }
+        };
+
+        let archive = SessionArchive { session, messages };
+        let path = archive_path(&dir, &id);
+        let size = match write_archive(&path, &archive) {
+            Ok(size) => size,
+            Err(e) => {
+                result.failed.push((id, e));
+                continue;
+            }
+        };
+        if delete_from_gateway {
+            if let Err(e) = api.delete(&format!("/api/sessions/{}", id)).await {
+                result
+                    .failed
+                    .push((id.clone(), format!("Archived but not deleted: {}", e)));
+            }
+        }
+        result.archived.push(summary(&archive, &path, size));
+    }
+    Ok(result)
+}
+
+/// Archived sessions found in a folder, newest first
+#[tauri::command]
+pub fn list_archived_sessions(destination: String) -> Result<Vec<ArchivedSession>, DesktopError> {
+    let dir = PathBuf::from(&destination);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(&dir).map_err(|e| format!("Failed to read archive folder: {}", e))?;
+    let mut archived: Vec<ArchivedSession> = entries
+        .filter_map(|e| e.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.ends_with(ARCHIVE_EXTENSION))
+        })
+        .filter_map(|path| {
+            let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            match read_archive(&path) {
+                Ok(archive) => Some(summary(&archive, &path, size)),
+                Err(e) => {
+                    log_error!("{}", e);
+                    None
+                }
+            }
+        })
+        .collect();
+    archived.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    Ok(archived)
+}
+
+/// Import an archived session back into the gateway
+#[tauri::command]
+pub async fn restore_archived_session(
+    api: State<'_, GatewayApi>,
+    path: String,
+) -> Result<String, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let archive = read_archive(Path::new(&path))?;
+    let id = archive.session.id.clone();
+    api.post::<serde_json::Value>("/api/sessions/import", &archive)
+        .await
+        .map_err(|e| format!("Failed to restore session {}: {}", id, e))?;
+    Ok(id)
+}
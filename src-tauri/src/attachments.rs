@@ -0,0 +1,225 @@
+//! Files and clipboard contents handed to agent sessions as gateway attachments.
+//!
+//! Files dropped on the main window are uploaded as they arrive and reported
+//! with `attachments-dropped`; the chat UI references the returned ids when it
+//! sends the next message.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use ts_rs::TS;
+
+use crate::error::DesktopError;
+use crate::events::ATTACHMENTS_DROPPED_EVENT;
+use crate::gateway_api::GatewayApi;
+use crate::kiosk;
+
+const ATTACHMENTS_PATH: &str = "/api/attachments";
+/// Larger files are refused before reading them into memory
+const MAX_ATTACHMENT_BYTES: u64 = 25 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct Attachment {
+    pub id: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub size: u64,
+    pub mime_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct AttachmentFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct AttachmentUploads {
+    pub attachments: Vec<Attachment>,
+    pub failed: Vec<AttachmentFailure>,
+}
+
+fn mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        Some("pdf") => "application/pdf",
+        Some("json") => "application/json",
+        Some("zip") => "application/zip",
+        Some("csv") => "text/csv",
+        Some("md") => "text/markdown",
+        Some("html" | "htm") => "text/html",
+        Some("txt" | "log" | "rs" | "ts" | "tsx" | "js" | "py" | "toml" | "yaml" | "yml") => {
+            "text/plain"
+        }
+        _ => "application/octet-stream",
+    }
+}
+
+/// The gateway answers with the attachment or wraps it in `{ "attachment": ... }`
+fn attachment_id(response: &Value) -> Option<String> {
+    response
+        .get("id")
+        .or_else(|| response.pointer("/attachment/id"))
+        .and_then(|id| match id {
+            Value::String(id) => Some(id.clone()),
+            Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        })
+}
+
+pub(crate) async fn upload(
+    api: &GatewayApi,
+    name: String,
+    mime_type: &str,
+    bytes: Vec<u8>,
+    session_id: Option<&str>,
+) -> Result<Attachment, DesktopError> {
+    let size = bytes.len() as u64;
+    let part = reqwest::multipart::Part::bytes(bytes)
+        .file_name(name.clone())
+        .mime_str(mime_type)
+        .map_err(|e| format!("Failed to prepare upload: {}", e))?;
+    let mut form = reqwest::multipart::Form::new().part("file", part);
+    if let Some(session_id) = session_id {
+        form = form.text("session_id", session_id.to_string());
+    }
+    let response: Value = api.post_multipart(ATTACHMENTS_PATH, form).await?;
+    let id = attachment_id(&response)
+        .ok_or_else(|| "The gateway did not return an attachment id".to_string())?;
+    Ok(Attachment {
+        id,
+        name,
+        size,
+        mime_type: mime_type.to_string(),
+    })
+}
+
+async fn upload_file(
+    api: &GatewayApi,
+    path: &Path,
+    session_id: Option<&str>,
+) -> Result<Attachment, DesktopError> {
+    let metadata = tokio::fs::metadata(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    if !metadata.is_file() {
+        return Err(DesktopError::InvalidInput(format!(
+            "{} is not a file",
+            path.display()
+        )));
+    }
+    if metadata.len() > MAX_ATTACHMENT_BYTES {
+        return Err(DesktopError::InvalidInput(format!(
+            "{} is larger than {} MB",
+            path.display(),
+            MAX_ATTACHMENT_BYTES / 1024 / 1024
+        )));
+    }
+    let bytes = tokio::fs::read(path)
+        .await
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "file".to_string());
+    upload(api, name, mime_type(path), bytes, session_id).await
+}
+
+async fn upload_files(
+    api: &GatewayApi,
+    paths: Vec<PathBuf>,
+    session_id: Option<&str>,
+) -> AttachmentUploads {
+    let mut uploads = AttachmentUploads::default();
+    for path in paths {
+        match upload_file(api, &path, session_id).await {
+            Ok(attachment) => uploads.attachments.push(attachment),
+            Err(e) => uploads.failed.push(AttachmentFailure {
+                path: path.display().to_string(),
+                error: e.to_string(),
+            }),
+        }
+    }
+    uploads
+}
+
+pub(crate) fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(rgba))
+        .map_err(|e| format!("Failed to encode clipboard image: {}", e))?;
+    Ok(bytes)
+}
+
+/// Upload files dropped on the main window and tell the webview their ids
+pub(crate) fn on_drop<R: Runtime>(app: &AppHandle<R>, paths: Vec<PathBuf>) {
+    if paths.is_empty() || kiosk::is_locked() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let uploads = upload_files(&app.state::<GatewayApi>(), paths, None).await;
+        for failure in &uploads.failed {
+            log_error!("Failed to attach {}: {}", failure.path, failure.error);
+        }
+        if let Err(e) = app.emit(ATTACHMENTS_DROPPED_EVENT, &uploads) {
+            log_error!("Failed to emit dropped attachments: {}", e);
+        }
+    });
+}
+
+/// Upload files as attachments, optionally to one session
+#[tauri::command]
+pub async fn upload_attachments(
+    api: State<'_, GatewayApi>,
+    paths: Vec<String>,
+    session_id: Option<String>,
+) -> Result<AttachmentUploads, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let paths = paths.into_iter().map(PathBuf::from).collect();
+    Ok(upload_files(&api, paths, session_id.as_deref()).await)
+}
+
+/// Attach the clipboard to a session: an image as PNG, otherwise the text
+#[tauri::command]
+pub async fn send_clipboard_to_agent(
+    app: AppHandle,
+    api: State<'_, GatewayApi>,
+    session_id: String,
+) -> Result<Attachment, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let clipboard = app.clipboard();
+    let (name, mime_type, bytes) = match clipboard.read_image() {
+        Ok(image) => (
+            "clipboard.png",
+            "image/png",
+            encode_png(image.width(), image.height(), image.rgba())?,
+        ),
+        Err(_) => {
+            let text = clipboard
+                .read_text()
+                .ok()
+                .filter(|text| !text.is_empty())
+                .ok_or_else(|| DesktopError::InvalidInput("The clipboard is empty".to_string()))?;
+            ("clipboard.txt", "text/plain", text.into_bytes())
+        }
+    };
+    upload(&api, name.to_string(), mime_type, bytes, Some(&session_id)).await
+}
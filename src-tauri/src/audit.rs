@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::DesktopError;
+use crate::openclaw_home_dir;
+
+const AUDIT_FILE: &str = "desktop-audit.log";
+const DEFAULT_LIMIT: usize = 200;
+
+/// One security-relevant or state-changing action taken through the app,
+/// stored as a JSON line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub at: u64,
+    pub action: String,
+    pub target: String,
+    /// Whether the action was permitted and, once carried out, succeeded
+    pub allowed: bool,
+    pub detail: Option<String>,
+}
+
+/// Narrows `get_audit_log`; every field that is set must match
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AuditFilter {
+    /// Exact action, or a prefix ending in `_` such as `gateway_`
+    pub action: Option<String>,
+    /// Case-insensitive substring of the target
+    pub target: Option<String>,
+    /// Unix seconds, inclusive
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+    pub allowed: Option<bool>,
+}
+
+impl AuditFilter {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        let action = self.action.as_deref().is_none_or(|action| {
+            entry.action == action || (action.ends_with('_') && entry.action.starts_with(action))
+        });
+        let target = self
+            .target
+            .as_deref()
+            .is_none_or(|target| entry.target.to_lowercase().contains(&target.to_lowercase()));
+        action
+            && target
+            && self.since.is_none_or(|since| entry.at >= since)
+            && self.until.is_none_or(|until| entry.at <= until)
+            && self.allowed.is_none_or(|allowed| entry.allowed == allowed)
+    }
+}
+
+/// Append an entry to `~/.openclaw/desktop-audit.log`; failures are logged, never fatal
+pub fn record(action: &str, target: &str, allowed: bool, detail: Option<String>) {
+    let entry = AuditEntry {
+        at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        action: action.to_string(),
+        target: target.to_string(),
+        allowed,
+        detail,
+    };
+
+    let result = openclaw_home_dir().and_then(|home| {
+        let line = serde_json::to_string(&entry)
+            .map_err(|e| format!("Failed to serialize audit entry: {}", e))?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(home.join(AUDIT_FILE))
+            .and_then(|mut file| writeln!(file, "{}", line))
+            .map_err(|e| format!("Failed to write audit log: {}", e))
+    });
+    if let Err(e) = result {
+        log_error!("{}", e);
+    }
+}
+
+/// Record the outcome of an action that changed something on this machine
+pub fn record_result<T, E: Display>(action: &str, target: &str, result: &Result<T, E>) {
+    record(
+        action,
+        target,
+        result.is_ok(),
+        result.as_ref().err().map(|e| e.to_string()),
+    );
+}
+
+/// Recorded actions, newest first: starts and stops, installs, config writes,
+/// token rotations and permission decisions
+#[tauri::command]
+pub fn get_audit_log(
+    limit: Option<usize>,
+    filter: Option<AuditFilter>,
+) -> Result<Vec<AuditEntry>, DesktopError> {
+    let path = openclaw_home_dir()?.join(AUDIT_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read audit log: {}", e))?;
+    let filter = filter.unwrap_or_default();
+    Ok(content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .filter(|entry| filter.matches(entry))
+        .take(limit.unwrap_or(DEFAULT_LIMIT))
+        .collect())
+}
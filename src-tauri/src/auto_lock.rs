@@ -0,0 +1,340 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::DesktopError;
+use crate::kiosk;
+use crate::settings;
+use crate::window;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Locks the dashboard after a stretch without input, for shared machines
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoLockSettings {
+    pub enabled: bool,
+    pub idle_minutes: u64,
+    /// Same format as the kiosk passcode; unlocking by OS verification needs none
+    pub passcode_hash: Option<String>,
+    /// Offer the OS's own user verification (Touch ID, Windows Hello, polkit)
+    pub allow_os_verification: bool,
+}
+
+impl Default for AutoLockSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: 15,
+            passcode_hash: None,
+            allow_os_verification: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoLockStatus {
+    pub enabled: bool,
+    pub locked: bool,
+    pub idle_minutes: u64,
+    pub has_passcode: bool,
+    pub allow_os_verification: bool,
+}
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Last moment the user was known to be at the app: a focus change, or input
+/// anywhere on the machine while a window had focus
+static LAST_ACTIVITY: Mutex<Option<Instant>> = Mutex::new(None);
+static FOCUSED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_locked() -> bool {
+    LOCKED.load(Ordering::SeqCst)
+}
+
+/// Guard for anything that hands out the tokenized dashboard URL
+pub fn ensure_unlocked() -> Result<(), DesktopError> {
+    if is_locked() {
+        Err(DesktopError::DashboardLocked)
+    } else {
+        Ok(())
+    }
+}
+
+fn touch() {
+    if let Ok(mut last) = LAST_ACTIVITY.lock() {
+        *last = Some(Instant::now());
+    }
+}
+
+/// Called from the window event handler
+pub fn on_focus_changed(focused: bool) {
+    FOCUSED.store(focused, Ordering::SeqCst);
+    touch();
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) fn system_idle() -> Option<Duration> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find(|line| line.contains("\"HIDIdleTime\""))
+        .and_then(|line| line.rsplit('=').next())
+        .and_then(|ns| ns.trim().parse::<u64>().ok())
+        .map(Duration::from_nanos)
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) fn system_idle() -> Option<Duration> {
+    use windows::Win32::System::SystemInformation::GetTickCount;
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+        return None;
+    }
+    let now = unsafe { GetTickCount() };
+    Some(Duration::from_millis(now.wrapping_sub(info.dwTime) as u64))
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn system_idle() -> Option<Duration> {
+    use std::process::Command;
+
+    if let Ok(output) = Command::new("xprintidle").output() {
+        if let Ok(ms) = String::from_utf8_lossy(&output.stdout)
+            .trim()
+            .parse::<u64>()
+        {
+            return Some(Duration::from_millis(ms));
+        }
+    }
+    // GNOME on Wayland, replies `(uint64 1234,)`
+    let output = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.gnome.Mutter.IdleMonitor",
+            "--object-path",
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            "--method",
+            "org.gnome.Mutter.IdleMonitor.GetIdletime",
+        ])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .nth(1)
+        .and_then(|ms| ms.trim_end_matches([',', ')']).parse::<u64>().ok())
+        .map(Duration::from_millis)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub(crate) fn system_idle() -> Option<Duration> {
+    None
+}
+
+/// How long the dashboard has gone unused
+fn idle_for() -> Duration {
+    let last = LAST_ACTIVITY
+        .lock()
+        .ok()
+        .and_then(|last| *last)
+        .map(|at| at.elapsed())
+        .unwrap_or_default();
+    if !FOCUSED.load(Ordering::SeqCst) {
+        return last;
+    }
+    // While focused, input anywhere counts; without an idle source, stay unlocked
+    system_idle().map_or(Duration::ZERO, |idle| idle.min(last))
+}
+
+/// Lock, and send every window showing the dashboard back to the app's lock screen
+fn lock<R: Runtime>(app: &AppHandle<R>) {
+    LOCKED.store(true, Ordering::SeqCst);
+    let Some(app_url) = window::app_url() else {
+        return;
+    };
+    for (label, webview) in app.webview_windows() {
+        // Custom schemes have opaque origins, so compare the parts instead
+        let on_app = webview.url().is_ok_and(|url| {
+            url.scheme() == app_url.scheme()
+                && url.host_str() == app_url.host_str()
+                && url.port() == app_url.port()
+        });
+        if on_app {
+            continue;
+        }
+        if let Err(e) = webview.navigate(app_url.clone()) {
+            log_error!("Failed to lock window {}: {}", label, e);
+        }
+    }
+}
+
+/// Check for inactivity and lock the dashboard once the configured time has passed
+pub fn spawn_auto_lock<R: Runtime>(app: AppHandle<R>) {
+    touch();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let config = settings::current().auto_lock;
+            if !config.enabled || is_locked() {
+                continue;
+            }
+            let idle = tauri::async_runtime::spawn_blocking(idle_for)
+                .await
+                .unwrap_or_default();
+            if idle >= Duration::from_secs(config.idle_minutes.max(1) * 60) {
+                lock(&app);
+            }
+        }
+    });
+}
+
+#[cfg(target_os = "macos")]
+fn verify_os_user() -> Result<(), String> {
+    // Prompts for the account password, or Touch ID where the system allows it for admin prompts
+    let status = std::process::Command::new("osascript")
+        .args([
+            "-e",
+            "do shell script \"true\" with prompt \"OpenClaw Desktop wants to unlock the dashboard.\" with administrator privileges",
+        ])
+        .status()
+        .map_err(|e| format!("Failed to request verification: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("Verification was cancelled or failed".to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn verify_os_user() -> Result<(), String> {
+    use windows::core::HSTRING;
+    use windows::Security::Credentials::UI::{UserConsentVerificationResult, UserConsentVerifier};
+
+    let result = UserConsentVerifier::RequestVerificationAsync(&HSTRING::from(
+        "Unlock the OpenClaw dashboard",
+    ))
+    .and_then(|operation| operation.get())
+    .map_err(|e| format!("Failed to request verification: {}", e))?;
+    if result == UserConsentVerificationResult::Verified {
+        Ok(())
+    } else {
+        Err("Verification was cancelled or failed".to_string())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn verify_os_user() -> Result<(), String> {
+    let status = std::process::Command::new("pkexec")
+        .arg("true")
+        .status()
+        .map_err(|e| format!("Failed to request verification: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err("Verification was cancelled or failed".to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn verify_os_user() -> Result<(), String> {
+    Err("OS verification is not supported on this platform".to_string())
+}
+
+fn status() -> AutoLockStatus {
+    let config = settings::current().auto_lock;
+    AutoLockStatus {
+        enabled: config.enabled,
+        locked: is_locked(),
+        idle_minutes: config.idle_minutes,
+        has_passcode: config.passcode_hash.is_some(),
+        allow_os_verification: config.allow_os_verification,
+    }
+}
+
+#[tauri::command]
+pub fn get_auto_lock_status() -> AutoLockStatus {
+    status()
+}
+
+/// Configure the inactivity lock. A new passcode replaces the stored one;
+/// enabling needs a passcode or OS verification to unlock with.
+#[tauri::command]
+pub fn set_auto_lock(
+    enabled: bool,
+    idle_minutes: u64,
+    passcode: Option<String>,
+    allow_os_verification: bool,
+) -> Result<AutoLockStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    ensure_unlocked()?;
+    if idle_minutes == 0 {
+        return Err(DesktopError::InvalidInput(
+            "The inactivity timeout must be at least one minute".to_string(),
+        ));
+    }
+    let passcode = passcode.filter(|p| !p.is_empty());
+    let has_passcode = passcode.is_some() || settings::current().auto_lock.passcode_hash.is_some();
+    if enabled && !has_passcode && !allow_os_verification {
+        return Err(DesktopError::InvalidInput(
+            "Set a passcode or allow OS verification to unlock with".to_string(),
+        ));
+    }
+
+    settings::update(|s| {
+        s.auto_lock.enabled = enabled;
+        s.auto_lock.idle_minutes = idle_minutes;
+        s.auto_lock.allow_os_verification = allow_os_verification;
+        if let Some(passcode) = &passcode {
+            s.auto_lock.passcode_hash = Some(kiosk::new_passcode_hash(passcode));
+        }
+    })?;
+    touch();
+    Ok(status())
+}
+
+/// Unlock with the passcode, or with OS user verification when `passcode` is `None`
+#[tauri::command]
+pub async fn unlock_dashboard(passcode: Option<String>) -> Result<AutoLockStatus, DesktopError> {
+    let config = settings::current().auto_lock;
+    match passcode {
+        Some(passcode) => {
+            let stored = config.passcode_hash.ok_or("No dashboard passcode is set")?;
+            if !kiosk::verify_passcode(&stored, &passcode) {
+                return Err(DesktopError::InvalidInput("Incorrect passcode".to_string()));
+            }
+        }
+        None => {
+            if !config.allow_os_verification {
+                return Err(DesktopError::Unavailable(
+                    "OS verification is turned off for the dashboard lock".to_string(),
+                ));
+            }
+            tauri::async_runtime::spawn_blocking(verify_os_user)
+                .await
+                .map_err(|e| format!("Failed to request verification: {}", e))??;
+        }
+    }
+    LOCKED.store(false, Ordering::SeqCst);
+    touch();
+    Ok(status())
+}
+
+/// Lock the dashboard right away
+#[tauri::command]
+pub fn lock_dashboard(app: AppHandle) -> AutoLockStatus {
+    lock(&app);
+    status()
+}
@@ -0,0 +1,412 @@
+//! Opt-in loopback HTTP API so scripts can drive the app without the GUI.
+//!
+//! Each start picks a fresh token and writes it with the port to
+//! `~/.openclaw/desktop-api.json`, readable only by the user. Requests need
+//! `Authorization: Bearer <token>`; browser requests (any `Origin`) and other
+//! hosts are refused so web pages can't reach it.
+
+use axum::extract::{Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::availability::{self, ActionId};
+use crate::error::{DesktopError, ErrorCode};
+use crate::permissions::{self, Permission};
+use crate::{
+    audit, gateway_log_paths, gateway_status, kiosk, logs, openclaw_home_dir,
+    restart_default_gateway, settings, start_and_wait, stop_default_gateway, tokens,
+};
+
+const DISCOVERY_FILE: &str = "desktop-api.json";
+const DEFAULT_LOG_LINES: usize = 100;
+
+static API: Mutex<Option<RunningApi>> = Mutex::new(None);
+
+struct RunningApi {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutomationApiSettings {
+    pub enabled: bool,
+    /// Fixed port; `None` picks a free one each launch
+    pub port: Option<u16>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AutomationApiStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: Option<u16>,
+    /// Where scripts find the port and token
+    pub discovery_path: Option<String>,
+}
+
+/// Contents of the discovery file
+#[derive(Serialize)]
+struct Discovery<'a> {
+    port: u16,
+    token: &'a str,
+}
+
+#[derive(Clone)]
+struct ApiState {
+    app: AppHandle,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct LogsQuery {
+    lines: Option<usize>,
+    /// `stdout` (default) or `stderr`
+    stream: Option<String>,
+}
+
+fn discovery_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(DISCOVERY_FILE))
+}
+
+fn write_discovery(port: u16, token: &str) -> Result<(), String> {
+    let path = discovery_path()?;
+    let content = serde_json::to_string_pretty(&Discovery { port, token })
+        .map_err(|e| format!("Failed to serialize API discovery file: {}", e))?;
+    // A leftover file keeps its mode when reopened, so start from a fresh one
+    let _ = fs::remove_file(&path);
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // Private from the moment it exists, since it holds the API token
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(&path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| format!("Failed to write API discovery file: {}", e))
+}
+
+fn remove_discovery() {
+    if let Ok(path) = discovery_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+fn running_port() -> Option<u16> {
+    API.lock().ok().and_then(|api| api.as_ref().map(|a| a.port))
+}
+
+fn status() -> AutomationApiStatus {
+    let port = running_port();
+    AutomationApiStatus {
+        enabled: settings::current().automation_api.enabled,
+        running: port.is_some(),
+        port,
+        discovery_path: port
+            .and_then(|_| discovery_path().ok())
+            .map(|p| p.display().to_string()),
+    }
+}
+
+/// Same `{ code, message, hint }` body the webview gets, with a matching status
+struct ApiError(DesktopError);
+
+impl<E: Into<DesktopError>> From<E> for ApiError {
+    fn from(error: E) -> Self {
+        Self(error.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = match self.0.code() {
+            ErrorCode::PermissionDenied
+            | ErrorCode::KioskLocked
+            | ErrorCode::DashboardLocked
+            | ErrorCode::PolicyLocked => StatusCode::FORBIDDEN,
+            ErrorCode::RemoteConnection | ErrorCode::Unavailable => StatusCode::CONFLICT,
+            ErrorCode::NotFound | ErrorCode::NotInstalled => StatusCode::NOT_FOUND,
+            ErrorCode::InvalidInput => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(self.0)).into_response()
+    }
+}
+
+/// Refuse browsers and DNS rebinding, then check the token
+fn check_request(headers: &HeaderMap, token: &str) -> Result<(), StatusCode> {
+    if headers.contains_key(header::ORIGIN) {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let local_host = headers
+        .get(header::HOST)
+        .and_then(|h| h.to_str().ok())
+        .map(|h| h.rsplit_once(':').map_or(h, |(host, _)| host))
+        .is_some_and(|host| host == "127.0.0.1" || host == "localhost");
+    if !local_host {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let authorized = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .is_some_and(|presented| tokens::constant_time_eq(presented.trim(), token));
+    if !authorized {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+    Ok(())
+}
+
+async fn authorize(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    match check_request(request.headers(), &state.token) {
+        Ok(()) => next.run(request).await,
+        Err(status) => status.into_response(),
+    }
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, ApiError> {
+    Ok(tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Automation API task failed: {}", e))??)
+}
+
+async fn status_route() -> Result<impl IntoResponse, ApiError> {
+    Ok(Json(blocking(|| Ok(gateway_status())).await?))
+}
+
+async fn start_route(State(state): State<ApiState>) -> Result<impl IntoResponse, ApiError> {
+    availability::ensure(ActionId::StartGateway)?;
+    let result = start_and_wait(&state.app, false).await?;
+    Ok(Json(result))
+}
+
+async fn stop_route(State(state): State<ApiState>) -> Result<impl IntoResponse, ApiError> {
+    availability::ensure(ActionId::StopGateway)?;
+    let message = blocking(move || stop_default_gateway(&state.app)).await?;
+    Ok(Json(serde_json::json!({ "message": message })))
+}
+
+async fn restart_route(State(state): State<ApiState>) -> Result<impl IntoResponse, ApiError> {
+    availability::ensure(ActionId::RestartGateway)?;
+    let message = blocking(move || restart_default_gateway(&state.app)).await?;
+    Ok(Json(serde_json::json!({ "message": message })))
+}
+
+async fn logs_route(Query(query): Query<LogsQuery>) -> Result<impl IntoResponse, ApiError> {
+    let stderr = match query.stream.as_deref() {
+        None | Some("stdout") => false,
+        Some("stderr") => true,
+        Some(other) => {
+            return Err(DesktopError::InvalidInput(format!(
+                "Unknown log stream '{}'; use stdout or stderr",
+                other
+            ))
+            .into())
+        }
+    };
+    let lines = blocking(move || {
+        let (log_path, error_log_path) = gateway_log_paths()?;
+        let path = if stderr { error_log_path } else { log_path };
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        logs::tail_lines(&path, query.lines.unwrap_or(DEFAULT_LOG_LINES))
+    })
+    .await?;
+    Ok(Json(serde_json::json!({ "lines": lines })))
+}
+
+async fn start(app: &AppHandle) -> Result<(), String> {
+    if running_port().is_some() {
+        return Ok(());
+    }
+    let port = settings::current().automation_api.port.unwrap_or(0);
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .await
+        .map_err(|e| format!("Failed to start automation API: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to start automation API: {}", e))?
+        .port();
+    let token = tokens::generate_token();
+    write_discovery(port, &token)?;
+
+    let state = ApiState {
+        app: app.clone(),
+        token,
+    };
+    let router = Router::new()
+        .route("/v1/status", get(status_route))
+        .route("/v1/start", post(start_route))
+        .route("/v1/stop", post(stop_route))
+        .route("/v1/restart", post(restart_route))
+        .route("/v1/logs", get(logs_route))
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state);
+
+    let (shutdown, stopped) = oneshot::channel::<()>();
+    tauri::async_runtime::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = stopped.await;
+        });
+        if let Err(e) = server.await {
+            log_error!("Automation API stopped: {}", e);
+        }
+    });
+
+    if let Ok(mut api) = API.lock() {
+        *api = Some(RunningApi { port, shutdown });
+    }
+    Ok(())
+}
+
+/// Close the listener and remove the discovery file, so no stale token is left on disk
+pub(crate) fn stop() {
+    if let Some(api) = API.lock().ok().and_then(|mut api| api.take()) {
+        let _ = api.shutdown.send(());
+    }
+    remove_discovery();
+}
+
+/// Bring the API up at launch when it was left enabled
+pub fn spawn_automation_api(app: AppHandle) {
+    if !settings::current().automation_api.enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = start(&app).await {
+            log_error!("{}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_automation_api_status() -> AutomationApiStatus {
+    status()
+}
+
+/// Turn the API on or off. Turning it on asks for consent, since it lets local
+/// scripts control the gateway without further prompts.
+#[tauri::command]
+pub async fn set_automation_api_enabled(
+    app: AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<AutomationApiStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if enabled {
+        permissions::ensure_granted(&app, Permission::ControlGateway).await?;
+    }
+    settings::update(|s| {
+        s.automation_api.enabled = enabled;
+        s.automation_api.port = port;
+    })?;
+    // A new port only applies once the listener is recreated
+    stop();
+    let result = if enabled { start(&app).await } else { Ok(()) };
+    audit::record_result(
+        if enabled {
+            "automation_api_enable"
+        } else {
+            "automation_api_disable"
+        },
+        "localhost",
+        &result,
+    );
+    result?;
+    Ok(status())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderName, HeaderValue};
+
+    fn headers(pairs: &[(HeaderName, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(name.clone(), HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn accepts_the_token_on_loopback() {
+        for host in ["127.0.0.1:7000", "localhost:7000", "localhost"] {
+            let request = headers(&[
+                (header::HOST, host),
+                (header::AUTHORIZATION, "Bearer tok3n"),
+            ]);
+            assert_eq!(check_request(&request, "tok3n"), Ok(()), "{}", host);
+        }
+    }
+
+    #[test]
+    fn refuses_browsers_and_rebound_hosts() {
+        let from_page = headers(&[
+            (header::HOST, "127.0.0.1:7000"),
+            (header::ORIGIN, "http://127.0.0.1:7000"),
+            (header::AUTHORIZATION, "Bearer tok3n"),
+        ]);
+        assert_eq!(
+            check_request(&from_page, "tok3n"),
+            Err(StatusCode::FORBIDDEN)
+        );
+        for host in ["evil.example:7000", "127.0.0.1.evil.example:7000", ""] {
+            let rebound = headers(&[
+                (header::HOST, host),
+                (header::AUTHORIZATION, "Bearer tok3n"),
+            ]);
+            assert_eq!(
+                check_request(&rebound, "tok3n"),
+                Err(StatusCode::FORBIDDEN),
+                "{}",
+                host
+            );
+        }
+        let no_host = headers(&[(header::AUTHORIZATION, "Bearer tok3n")]);
+        assert_eq!(check_request(&no_host, "tok3n"), Err(StatusCode::FORBIDDEN));
+    }
+
+    #[test]
+    fn refuses_missing_or_wrong_tokens() {
+        for authorization in [
+            "Bearer wrong",
+            "Bearer tok3n2",
+            "Basic tok3n",
+            "tok3n",
+            "Bearer ",
+        ] {
+            let request = headers(&[
+                (header::HOST, "127.0.0.1:7000"),
+                (header::AUTHORIZATION, authorization),
+            ]);
+            assert_eq!(
+                check_request(&request, "tok3n"),
+                Err(StatusCode::UNAUTHORIZED),
+                "{}",
+                authorization
+            );
+        }
+        let no_token = headers(&[(header::HOST, "127.0.0.1:7000")]);
+        assert_eq!(
+            check_request(&no_token, "tok3n"),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+    }
+}
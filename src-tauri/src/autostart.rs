@@ -0,0 +1,172 @@
+#[cfg(not(target_os = "windows"))]
+use std::fs;
+use std::path::PathBuf;
+#[cfg(target_os = "windows")]
+use std::process::Command;
+
+use crate::error::DesktopError;
+use crate::kiosk;
+
+/// CLI flag that starts the app hidden in the tray
+pub const MINIMIZED_FLAG: &str = "--minimized";
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+const AUTOSTART_ID: &str = "com.ai-dev-2024.openclaw-desktop";
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+/// Whether this launch asked to start hidden in the tray
+pub fn launched_minimized() -> bool {
+    std::env::args().any(|arg| arg == MINIMIZED_FLAG)
+}
+
+fn current_exe() -> Result<PathBuf, String> {
+    std::env::current_exe().map_err(|e| format!("Failed to locate app executable: {}", e))
+}
+
+#[cfg(target_os = "macos")]
+fn entry_path() -> Result<PathBuf, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    Ok(home
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", AUTOSTART_ID)))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn entry_path() -> Result<PathBuf, String> {
+    let config = dirs::config_dir().ok_or("Could not find config directory")?;
+    Ok(config.join("autostart").join("openclaw-desktop.desktop"))
+}
+
+#[cfg(target_os = "macos")]
+fn entry_contents(exe: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        AUTOSTART_ID, exe, MINIMIZED_FLAG
+    )
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn entry_contents(exe: &str) -> String {
+    format!(
+        "[Desktop Entry]\nType=Application\nName=OpenClaw Desktop\nExec=\"{}\" {}\nX-GNOME-Autostart-enabled=true\nTerminal=false\n",
+        exe, MINIMIZED_FLAG
+    )
+}
+
+#[cfg(not(target_os = "windows"))]
+fn is_enabled() -> Result<bool, String> {
+    Ok(entry_path()?.exists())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn enable() -> Result<(), String> {
+    let path = entry_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create autostart directory: {}", e))?;
+    }
+    let exe = current_exe()?;
+    fs::write(&path, entry_contents(&exe.to_string_lossy()))
+        .map_err(|e| format!("Failed to write autostart entry: {}", e))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn disable() -> Result<(), String> {
+    let path = entry_path()?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove autostart entry: {}", e))?;
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn reg(args: &[&str]) -> Result<std::process::Output, String> {
+    use std::os::windows::process::CommandExt;
+
+    Command::new("reg")
+        .args(args)
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Failed to run reg: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn is_enabled() -> Result<bool, String> {
+    Ok(reg(&["query", RUN_KEY, "/v", AUTOSTART_ID])?
+        .status
+        .success())
+}
+
+#[cfg(target_os = "windows")]
+fn enable() -> Result<(), String> {
+    let exe = current_exe()?;
+    let value = format!("\"{}\" {}", exe.display(), MINIMIZED_FLAG);
+    let output = reg(&[
+        "add",
+        RUN_KEY,
+        "/v",
+        AUTOSTART_ID,
+        "/t",
+        "REG_SZ",
+        "/d",
+        &value,
+        "/f",
+    ])?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Failed to add startup entry: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn disable() -> Result<(), String> {
+    if is_enabled()? {
+        let output = reg(&["delete", RUN_KEY, "/v", AUTOSTART_ID, "/f"])?;
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to remove startup entry: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_launch_at_login() -> Result<bool, DesktopError> {
+    let enabled = tauri::async_runtime::spawn_blocking(is_enabled)
+        .await
+        .map_err(|e| format!("Failed to check login item: {}", e))??;
+    Ok(enabled)
+}
+
+/// Register or remove the per-user login item that starts the app with `--minimized`
+#[tauri::command]
+pub async fn set_launch_at_login(enabled: bool) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    tauri::async_runtime::spawn_blocking(move || if enabled { enable() } else { disable() })
+        .await
+        .map_err(|e| format!("Failed to update login item: {}", e))??;
+    Ok(())
+}
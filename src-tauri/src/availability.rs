@@ -0,0 +1,309 @@
+//! Whether user-facing actions can run right now, and why not.
+//!
+//! The commands behind these actions call [`ensure`] with the same checks the
+//! UI reads through [`get_action_availability`], so a button is grayed out
+//! exactly when the backend would refuse it.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::error::DesktopError;
+use crate::updater::{self, InstallSource};
+use crate::{
+    active_gateway_port, auto_lock, binary, connection, connectivity, is_gateway_running, kiosk,
+    policy, port_owner, prerequisites,
+};
+
+static IN_PROGRESS: Mutex<Vec<Operation>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionId {
+    StartGateway,
+    StopGateway,
+    RestartGateway,
+    /// Pausing or resuming agents on a running gateway
+    PauseGateway,
+    UpdateOpenclaw,
+    UpgradeWithFailover,
+    OpenDashboard,
+    /// Binding the local gateway beyond loopback
+    LanMode,
+}
+
+/// Long-running work that other actions have to wait for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Install,
+    Upgrade,
+    Restore,
+}
+
+impl Operation {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Install => "An install",
+            Self::Upgrade => "An upgrade",
+            Self::Restore => "A backup restore",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DisabledReason {
+    KioskLocked,
+    DashboardLocked,
+    /// The app is connected to a remote gateway it can't manage
+    RemoteConnection,
+    NotInstalled,
+    IncompatibleVersion {
+        tool: String,
+        installed: Option<String>,
+        required: String,
+    },
+    /// `setting` names the policy entry that forbids it
+    PolicyLocked {
+        setting: String,
+    },
+    OperationInProgress {
+        operation: Operation,
+    },
+    GatewayNotRunning,
+    PortInUse {
+        port: u16,
+        owner: Option<String>,
+    },
+    Offline,
+    RegistryUnreachable,
+}
+
+impl DisabledReason {
+    fn error(&self) -> DesktopError {
+        match self {
+            Self::KioskLocked => DesktopError::KioskLocked,
+            Self::DashboardLocked => DesktopError::DashboardLocked,
+            Self::RemoteConnection => DesktopError::RemoteConnection,
+            Self::NotInstalled => DesktopError::NotInstalled("OpenClaw".to_string()),
+            Self::PolicyLocked { .. } => DesktopError::PolicyLocked(self.message()),
+            Self::GatewayNotRunning => DesktopError::GatewayNotRunning(self.message()),
+            Self::Offline => DesktopError::Offline(self.message()),
+            Self::RegistryUnreachable => DesktopError::RegistryUnreachable(self.message()),
+            _ => DesktopError::Unavailable(self.message()),
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::KioskLocked => "Locked: this action is unavailable in kiosk mode".to_string(),
+            Self::DashboardLocked => {
+                "The dashboard is locked after inactivity; unlock it first".to_string()
+            }
+            Self::RemoteConnection => {
+                "Not available while connected to a remote gateway".to_string()
+            }
+            Self::NotInstalled => "OpenClaw is not installed".to_string(),
+            Self::IncompatibleVersion {
+                tool,
+                installed,
+                required,
+            } => match installed {
+                Some(installed) => format!(
+                    "{} {} is too old; {} or later is required",
+                    tool, installed, required
+                ),
+                None => format!("{} {} or later is required", tool, required),
+            },
+            Self::PolicyLocked { setting } => format!("Disabled by policy ({})", setting),
+            Self::OperationInProgress { operation } => {
+                format!("{} is in progress", operation.label())
+            }
+            Self::GatewayNotRunning => "The gateway is not running".to_string(),
+            Self::PortInUse { port, owner } => format!(
+                "Port {} is in use by {}",
+                port,
+                owner.as_deref().unwrap_or("another program")
+            ),
+            Self::Offline => "No internet connection".to_string(),
+            Self::RegistryUnreachable => "The npm registry can't be reached".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionAvailability {
+    pub action: ActionId,
+    pub enabled: bool,
+    pub reasons: Vec<DisabledReason>,
+    /// Human-readable form of the first reason, for a tooltip
+    pub message: Option<String>,
+}
+
+/// Marks an operation as running until dropped
+pub(crate) struct OperationGuard(Operation);
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        if let Ok(mut running) = IN_PROGRESS.lock() {
+            running.retain(|op| *op != self.0);
+        }
+    }
+}
+
+/// Claim `operation`, failing when one of the same kind is already running
+pub(crate) fn begin(operation: Operation) -> Result<OperationGuard, String> {
+    let mut running = IN_PROGRESS
+        .lock()
+        .map_err(|_| "Operation lock poisoned".to_string())?;
+    if running.contains(&operation) {
+        return Err(DisabledReason::OperationInProgress { operation }.message());
+    }
+    running.push(operation);
+    Ok(OperationGuard(operation))
+}
+
+fn in_progress(blocking: &[Operation], reasons: &mut Vec<DisabledReason>) {
+    let running = IN_PROGRESS.lock().map(|r| r.clone()).unwrap_or_default();
+    reasons.extend(
+        running
+            .into_iter()
+            .filter(|op| blocking.contains(op))
+            .map(|operation| DisabledReason::OperationInProgress { operation }),
+    );
+}
+
+fn local_gateway(reasons: &mut Vec<DisabledReason>) {
+    if connection::is_remote() {
+        reasons.push(DisabledReason::RemoteConnection);
+    }
+}
+
+fn installed(reasons: &mut Vec<DisabledReason>) {
+    if binary::resolve().is_none() {
+        reasons.push(DisabledReason::NotInstalled);
+    }
+}
+
+/// Judged from the last background check, so this never waits on the network
+fn network(needs_registry: bool, reasons: &mut Vec<DisabledReason>) {
+    let Some(state) = connectivity::cached() else {
+        return;
+    };
+    if !state.internet {
+        reasons.push(DisabledReason::Offline);
+    } else if needs_registry && !state.npm_registry {
+        reasons.push(DisabledReason::RegistryUnreachable);
+    }
+}
+
+fn node(reasons: &mut Vec<DisabledReason>) {
+    let node = prerequisites::node();
+    if !node.ok {
+        reasons.push(DisabledReason::IncompatibleVersion {
+            tool: "Node.js".to_string(),
+            installed: node.version,
+            required: node.minimum,
+        });
+    }
+}
+
+/// Everything currently standing in the way of `action`
+pub(crate) fn reasons(action: ActionId) -> Vec<DisabledReason> {
+    use Operation::*;
+
+    let mut reasons = Vec::new();
+    if action == ActionId::OpenDashboard {
+        if auto_lock::is_locked() {
+            reasons.push(DisabledReason::DashboardLocked);
+        }
+    } else if kiosk::is_locked() {
+        reasons.push(DisabledReason::KioskLocked);
+    }
+
+    match action {
+        ActionId::StartGateway => {
+            local_gateway(&mut reasons);
+            installed(&mut reasons);
+            in_progress(&[Install, Upgrade, Restore], &mut reasons);
+            let port = active_gateway_port();
+            if policy::check_port(port).is_err() {
+                reasons.push(DisabledReason::PolicyLocked {
+                    setting: "allowed_port_range".to_string(),
+                });
+            }
+            if let Some(owner) = port_owner::foreign_owner(port) {
+                reasons.push(DisabledReason::PortInUse {
+                    port,
+                    owner: owner.name,
+                });
+            }
+        }
+        ActionId::StopGateway | ActionId::RestartGateway => {
+            local_gateway(&mut reasons);
+            in_progress(&[Upgrade, Restore], &mut reasons);
+        }
+        ActionId::PauseGateway => {
+            in_progress(&[Upgrade, Restore], &mut reasons);
+            if !is_gateway_running() {
+                reasons.push(DisabledReason::GatewayNotRunning);
+            }
+        }
+        ActionId::UpdateOpenclaw => {
+            in_progress(&[Install, Upgrade, Restore], &mut reasons);
+            match updater::detect_install_source() {
+                InstallSource::Unknown => reasons.push(DisabledReason::NotInstalled),
+                InstallSource::Npm | InstallSource::Pnpm => {
+                    node(&mut reasons);
+                    network(true, &mut reasons);
+                }
+                InstallSource::Brew | InstallSource::Standalone => network(false, &mut reasons),
+            }
+        }
+        ActionId::UpgradeWithFailover => {
+            local_gateway(&mut reasons);
+            in_progress(&[Install, Upgrade, Restore], &mut reasons);
+            node(&mut reasons);
+            if !is_gateway_running() {
+                reasons.push(DisabledReason::GatewayNotRunning);
+            }
+        }
+        ActionId::OpenDashboard => {
+            if !is_gateway_running() {
+                reasons.push(DisabledReason::GatewayNotRunning);
+            }
+        }
+        ActionId::LanMode => {
+            local_gateway(&mut reasons);
+            in_progress(&[Restore], &mut reasons);
+            if policy::current().disable_lan_access {
+                reasons.push(DisabledReason::PolicyLocked {
+                    setting: "disable_lan_access".to_string(),
+                });
+            }
+        }
+    }
+    reasons
+}
+
+/// Fail with the first reason `action` is unavailable
+pub(crate) fn ensure(action: ActionId) -> Result<(), DesktopError> {
+    match reasons(action).first() {
+        Some(reason) => Err(reason.error()),
+        None => Ok(()),
+    }
+}
+
+/// Report whether `action` is available, with every reason it isn't
+#[tauri::command]
+pub async fn get_action_availability(action: ActionId) -> Result<ActionAvailability, DesktopError> {
+    let reasons = tauri::async_runtime::spawn_blocking(move || reasons(action))
+        .await
+        .map_err(|e| format!("Failed to check availability: {}", e))?;
+    Ok(ActionAvailability {
+        action,
+        enabled: reasons.is_empty(),
+        message: reasons.first().map(DisabledReason::message),
+        reasons,
+    })
+}
@@ -0,0 +1,347 @@
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::disk_space::{self, SpaceCheck};
+use crate::error::DesktopError;
+use crate::focus;
+use crate::i18n;
+use crate::kiosk;
+use crate::notifications::{self, NotificationCategory};
+use crate::{config, openclaw_home_dir, read_gateway_token, secrets, settings};
+
+const HISTORY_FILE: &str = "backup-history.json";
+const HISTORY_LIMIT: usize = 100;
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Header of encrypted backups, followed by the salt, nonce, and AES-256-GCM ciphertext
+const ENCRYPTED_MAGIC: &[u8] = b"OCBK1";
+const PBKDF2_ROUNDS: u32 = 200_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupSchedule {
+    Daily,
+    Weekly,
+}
+
+impl BackupSchedule {
+    fn interval_secs(self) -> u64 {
+        match self {
+            BackupSchedule::Daily => 24 * 60 * 60,
+            BackupSchedule::Weekly => 7 * 24 * 60 * 60,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub schedule: BackupSchedule,
+    /// Folder backups are written to, e.g. an external drive or NAS mount
+    pub destination: Option<String>,
+    /// Number of backups kept in the destination
+    pub retention: u32,
+    /// Encrypt with the passphrase stored in the OS keychain. Only encrypted backups
+    /// include the gateway token.
+    pub encrypt: bool,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedule: BackupSchedule::Daily,
+            destination: None,
+            retention: 10,
+            encrypt: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRecord {
+    pub at: u64,
+    pub scheduled: bool,
+    pub success: bool,
+    pub path: Option<String>,
+    pub encrypted: bool,
+    pub error: Option<String>,
+    /// Free space at the destination before writing
+    #[serde(default)]
+    pub space: Option<SpaceCheck>,
+}
+
+struct WrittenBackup {
+    path: PathBuf,
+    encrypted: bool,
+    space: SpaceCheck,
+}
+
+/// Everything needed to restore the gateway's configuration
+#[derive(Serialize)]
+struct BackupBundle {
+    created_at: u64,
+    config: serde_json::Value,
+    desktop_settings: settings::Settings,
+    gateway_token: Option<String>,
+    /// The token is only kept in encrypted backups
+    secrets_included: bool,
+}
+
+impl BackupBundle {
+    fn collect(created_at: u64, include_secrets: bool) -> Result<Self, String> {
+        let mut config = config::load_config()?;
+        // Config variable values live in the keychain; settings only hold their names
+        let desktop_settings = settings::current();
+        if !include_secrets {
+            if let Some(auth) = config
+                .pointer_mut("/gateway/auth")
+                .and_then(|auth| auth.as_object_mut())
+            {
+                auth.remove("token");
+            }
+        }
+        Ok(Self {
+            created_at,
+            config,
+            desktop_settings,
+            gateway_token: include_secrets.then(read_gateway_token).flatten(),
+            secrets_included: include_secrets,
+        })
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn history_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(HISTORY_FILE))
+}
+
+fn load_history<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<BackupRecord>, String> {
+    let path = history_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read backup history: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse backup history: {}", e))
+}
+
+fn record<R: Runtime>(app: &AppHandle<R>, entry: BackupRecord) {
+    let result = load_history(app).and_then(|mut history| {
+        history.push(entry);
+        if history.len() > HISTORY_LIMIT {
+            history.drain(..history.len() - HISTORY_LIMIT);
+        }
+        let content = serde_json::to_string_pretty(&history)
+            .map_err(|e| format!("Failed to serialize backup history: {}", e))?;
+        fs::write(history_path(app)?, content)
+            .map_err(|e| format!("Failed to write backup history: {}", e))
+    });
+    if let Err(e) = result {
+        log_error!("{}", e);
+    }
+}
+
+pub(crate) fn encrypt(plaintext: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), &salt, PBKDF2_ROUNDS, &mut key);
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| format!("Failed to initialize encryption: {}", e))?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), plaintext)
+        .map_err(|e| format!("Failed to encrypt backup: {}", e))?;
+
+    Ok([ENCRYPTED_MAGIC, &salt, &nonce, &ciphertext].concat())
+}
+
+pub(crate) fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(ENCRYPTED_MAGIC)
+}
+
+pub(crate) fn decrypt(data: &[u8], passphrase: &str) -> Result<Vec<u8>, String> {
+    let rest = data
+        .strip_prefix(ENCRYPTED_MAGIC)
+        .ok_or("Not an encrypted backup")?;
+    if rest.len() < 16 + 12 {
+        return Err("Encrypted backup is truncated".to_string());
+    }
+    let (salt, rest) = rest.split_at(16);
+    let (nonce, ciphertext) = rest.split_at(12);
+
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, PBKDF2_ROUNDS, &mut key);
+    let cipher = Aes256Gcm::new_from_slice(&key)
+        .map_err(|e| format!("Failed to initialize decryption: {}", e))?;
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| "Wrong passphrase or corrupted backup".to_string())
+}
+
+/// Delete the oldest backups beyond the retention count
+fn prune(destination: &Path, retention: u32) {
+    let Ok(entries) = fs::read_dir(destination) else {
+        return;
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("openclaw-backup-"))
+        })
+        .collect();
+    // Timestamped names sort chronologically
+    backups.sort();
+    let excess = backups.len().saturating_sub(retention.max(1) as usize);
+    for path in backups.into_iter().take(excess) {
+        if let Err(e) = fs::remove_file(&path) {
+            log_error!("Failed to prune backup {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn write_backup(config: &BackupSettings) -> Result<WrittenBackup, String> {
+    let destination = match &config.destination {
+        Some(dir) => PathBuf::from(dir),
+        None => openclaw_home_dir()?.join("backups"),
+    };
+    fs::create_dir_all(&destination)
+        .map_err(|e| format!("Backup destination is unavailable: {}", e))?;
+
+    let created_at = now_secs();
+    let bundle = BackupBundle::collect(created_at, config.encrypt)?;
+    let json = serde_json::to_vec_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize backup: {}", e))?;
+
+    let (content, path) = if config.encrypt {
+        let passphrase = secrets::backup_passphrase()
+            .ok_or("Backup encryption is on but no passphrase is set")?;
+        (
+            encrypt(&json, &passphrase)?,
+            destination.join(format!("openclaw-backup-{}.json.enc", created_at)),
+        )
+    } else {
+        (
+            json,
+            destination.join(format!("openclaw-backup-{}.json", created_at)),
+        )
+    };
+
+    let space = disk_space::preflight(&destination, content.len() as u64, 1)?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write backup: {}", e))?;
+    prune(&destination, config.retention);
+    Ok(WrittenBackup {
+        path,
+        encrypted: config.encrypt,
+        space,
+    })
+}
+
+fn run_backup<R: Runtime>(app: &AppHandle<R>, scheduled: bool) -> Result<BackupRecord, String> {
+    let config = settings::current().backup;
+    let result = write_backup(&config);
+    let entry = BackupRecord {
+        at: now_secs(),
+        scheduled,
+        success: result.is_ok(),
+        path: result
+            .as_ref()
+            .ok()
+            .map(|written| written.path.display().to_string()),
+        encrypted: result.as_ref().is_ok_and(|written| written.encrypted),
+        error: result.as_ref().err().cloned(),
+        space: result.as_ref().ok().map(|written| written.space.clone()),
+    };
+    record(app, entry.clone());
+    result.map(|_| entry)
+}
+
+fn last_success<R: Runtime>(app: &AppHandle<R>) -> Option<u64> {
+    load_history(app)
+        .ok()?
+        .iter()
+        .rev()
+        .find(|r| r.success)
+        .map(|r| r.at)
+}
+
+/// Run scheduled backups when the configured interval has elapsed since the last success
+pub fn spawn_backup_scheduler<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SCHEDULE_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let config = settings::current().backup;
+            if !config.enabled {
+                continue;
+            }
+            let due = last_success(&app)
+                .is_none_or(|at| now_secs() >= at + config.schedule.interval_secs());
+            if !due {
+                continue;
+            }
+            focus::wait_until_available().await;
+
+            let task_app = app.clone();
+            let result =
+                tauri::async_runtime::spawn_blocking(move || run_backup(&task_app, true)).await;
+            if let Ok(Err(e)) = result {
+                notifications::notify(
+                    &app,
+                    NotificationCategory::BackupFailed,
+                    &i18n::t("backup.failed"),
+                    &e,
+                );
+            }
+        }
+    });
+}
+
+/// Back up the config, token, and desktop settings right away
+#[tauri::command]
+pub async fn run_backup_now(app: AppHandle) -> Result<BackupRecord, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let record = tauri::async_runtime::spawn_blocking(move || run_backup(&app, false))
+        .await
+        .map_err(|e| format!("Failed to run backup: {}", e))??;
+    Ok(record)
+}
+
+/// Past backup attempts, oldest first
+#[tauri::command]
+pub fn get_backup_history(app: AppHandle) -> Result<Vec<BackupRecord>, DesktopError> {
+    Ok(load_history(&app)?)
+}
+
+/// Set or clear the passphrase used to encrypt backups
+#[tauri::command]
+pub fn set_backup_passphrase(passphrase: Option<String>) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    secrets::set_backup_passphrase(passphrase.as_deref().filter(|p| !p.is_empty()))?;
+    Ok(())
+}
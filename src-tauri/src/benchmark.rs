@@ -0,0 +1,353 @@
+//! Timing the path from the app to a model reply, piece by piece, to tell
+//! whether slowness comes from the gateway, a provider, or the network.
+//!
+//! A run samples the gateway's health endpoint, times a request to a neutral
+//! site as the network baseline, times each configured provider's API, and
+//! optionally sends one small completion through the gateway. Reports are kept
+//! in `benchmark-history.json` in the app data directory.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::error::DesktopError;
+use crate::gateway_api::GatewayApi;
+use crate::{connection, connectivity, providers, proxy};
+
+const HISTORY_FILE: &str = "benchmark-history.json";
+const HISTORY_LIMIT: usize = 50;
+const DEFAULT_SAMPLES: u32 = 5;
+const MAX_SAMPLES: u32 = 50;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const COMPLETION_TIMEOUT: Duration = Duration::from_secs(60);
+/// Median health round trip above which the gateway counts as slow
+const GATEWAY_SLOW_MS: u64 = 250;
+/// Same for a remote gateway, whose round trip includes the network
+const REMOTE_GATEWAY_SLOW_MS: u64 = 750;
+const NETWORK_SLOW_MS: u64 = 1000;
+/// Provider round trip beyond the network baseline above which it counts as slow
+const PROVIDER_SLOW_MS: u64 = 1500;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyStats {
+    pub samples: u32,
+    pub failures: u32,
+    pub min_ms: Option<u64>,
+    pub median_ms: Option<u64>,
+    pub max_ms: Option<u64>,
+    /// Last failure, when any sample failed
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderTiming {
+    pub id: String,
+    pub name: String,
+    pub reachable: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletionTiming {
+    pub ok: bool,
+    pub total_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Where the time goes, judged from the measurements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Bottleneck {
+    None,
+    GatewayDown,
+    Gateway,
+    Network,
+    Provider,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    /// Unix seconds
+    pub started_at: u64,
+    pub remote: bool,
+    pub gateway: LatencyStats,
+    /// Round trip to a neutral site, for comparing provider timings against
+    pub network: LatencyStats,
+    pub providers: Vec<ProviderTiming>,
+    /// Only when asked for, as it costs tokens
+    pub completion: Option<CompletionTiming>,
+    pub bottleneck: Bottleneck,
+    pub summary: String,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+fn stats(timings: &[Result<u64, String>]) -> LatencyStats {
+    let mut ok: Vec<u64> = timings
+        .iter()
+        .filter_map(|t| t.as_ref().ok())
+        .copied()
+        .collect();
+    ok.sort_unstable();
+    LatencyStats {
+        samples: timings.len() as u32,
+        failures: (timings.len() - ok.len()) as u32,
+        min_ms: ok.first().copied(),
+        median_ms: ok.get(ok.len() / 2).copied(),
+        max_ms: ok.last().copied(),
+        error: timings.iter().rev().find_map(|t| t.clone().err()),
+    }
+}
+
+async fn time_gateway(api: &GatewayApi, samples: u32) -> LatencyStats {
+    let mut timings = Vec::with_capacity(samples as usize);
+    for _ in 0..samples {
+        let started = Instant::now();
+        let result = api.get::<Value>("/api/health").await;
+        timings.push(
+            result
+                .map(|_| millis(started.elapsed()))
+                .map_err(|e| e.to_string()),
+        );
+    }
+    stats(&timings)
+}
+
+fn external_client() -> Result<reqwest::Client, String> {
+    proxy::apply(reqwest::Client::builder().timeout(REQUEST_TIMEOUT))?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+/// Round trip of a HEAD request; any HTTP status counts as an answer
+async fn time_request(client: &reqwest::Client, url: &str) -> Result<(u64, u16), String> {
+    let started = Instant::now();
+    let response = client
+        .head(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+    Ok((millis(started.elapsed()), response.status().as_u16()))
+}
+
+async fn time_network(client: &reqwest::Client, samples: u32) -> LatencyStats {
+    let mut timings = Vec::with_capacity(samples as usize);
+    for _ in 0..samples {
+        let mut result = Err("No network check answered".to_string());
+        for url in connectivity::INTERNET_URLS {
+            result = time_request(client, url).await.map(|(ms, _)| ms);
+            if result.is_ok() {
+                break;
+            }
+        }
+        timings.push(result);
+    }
+    stats(&timings)
+}
+
+async fn time_providers(client: &reqwest::Client) -> Vec<ProviderTiming> {
+    let targets = match tauri::async_runtime::spawn_blocking(providers::reachability_targets)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|targets| targets)
+    {
+        Ok(targets) => targets,
+        Err(e) => {
+            log_error!("Failed to list providers to benchmark: {}", e);
+            Vec::new()
+        }
+    };
+    let mut timings = Vec::with_capacity(targets.len());
+    for (id, name, url) in targets {
+        let started = Instant::now();
+        let timing = match time_request(client, &url).await {
+            Ok((latency_ms, status)) => ProviderTiming {
+                id,
+                name,
+                reachable: true,
+                status: Some(status),
+                latency_ms,
+                error: None,
+            },
+            Err(e) => ProviderTiming {
+                id,
+                name,
+                reachable: false,
+                status: None,
+                latency_ms: millis(started.elapsed()),
+                error: Some(e),
+            },
+        };
+        timings.push(timing);
+    }
+    timings
+}
+
+async fn time_completion(api: &GatewayApi, agent: Option<&str>) -> CompletionTiming {
+    let body =
+        json!({ "message": "Reply with the single word: ok", "agent": agent, "stream": false });
+    let started = Instant::now();
+    let request = api.post::<Value>("/api/chat", &body);
+    let result = match tokio::time::timeout(COMPLETION_TIMEOUT, request).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err("Timed out".to_string()),
+    };
+    CompletionTiming {
+        ok: result.is_ok(),
+        total_ms: millis(started.elapsed()),
+        error: result.err(),
+    }
+}
+
+fn judge(report: &BenchmarkReport) -> (Bottleneck, String) {
+    let Some(gateway_ms) = report.gateway.median_ms else {
+        return (
+            Bottleneck::GatewayDown,
+            "The gateway did not answer its health check".to_string(),
+        );
+    };
+    let gateway_limit = if report.remote {
+        REMOTE_GATEWAY_SLOW_MS
+    } else {
+        GATEWAY_SLOW_MS
+    };
+    if gateway_ms > gateway_limit {
+        return (
+            Bottleneck::Gateway,
+            format!(
+                "The gateway takes {} ms to answer its health check",
+                gateway_ms
+            ),
+        );
+    }
+    let Some(network_ms) = report.network.median_ms else {
+        return (
+            Bottleneck::Network,
+            "The internet could not be reached".to_string(),
+        );
+    };
+    if network_ms > NETWORK_SLOW_MS {
+        return (
+            Bottleneck::Network,
+            format!("The network is slow: {} ms to a neutral site", network_ms),
+        );
+    }
+    let slow_provider = report
+        .providers
+        .iter()
+        .find(|p| !p.reachable || p.latency_ms.saturating_sub(network_ms) > PROVIDER_SLOW_MS);
+    if let Some(provider) = slow_provider {
+        let message = if provider.reachable {
+            format!("{} is slow: {} ms", provider.name, provider.latency_ms)
+        } else {
+            format!("{} could not be reached", provider.name)
+        };
+        return (Bottleneck::Provider, message);
+    }
+    (
+        Bottleneck::None,
+        format!(
+            "Gateway {} ms, network {} ms; nothing stands out",
+            gateway_ms, network_ms
+        ),
+    )
+}
+
+fn history_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(HISTORY_FILE))
+}
+
+fn load_history<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<BenchmarkReport>, String> {
+    let path = history_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read benchmark history: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse benchmark history: {}", e))
+}
+
+fn record<R: Runtime>(app: &AppHandle<R>, report: BenchmarkReport) {
+    let result = load_history(app).and_then(|mut history| {
+        history.push(report);
+        if history.len() > HISTORY_LIMIT {
+            history.drain(..history.len() - HISTORY_LIMIT);
+        }
+        let content = serde_json::to_string_pretty(&history)
+            .map_err(|e| format!("Failed to serialize benchmark history: {}", e))?;
+        fs::write(history_path(app)?, content)
+            .map_err(|e| format!("Failed to write benchmark history: {}", e))
+    });
+    if let Err(e) = result {
+        log_error!("{}", e);
+    }
+}
+
+/// Time the gateway, the network and the configured providers, and with
+/// `completion` one small reply through the gateway, which costs tokens
+#[tauri::command]
+pub async fn run_gateway_benchmark(
+    app: AppHandle,
+    api: State<'_, GatewayApi>,
+    samples: Option<u32>,
+    completion: bool,
+    agent: Option<String>,
+) -> Result<BenchmarkReport, DesktopError> {
+    let samples = samples.unwrap_or(DEFAULT_SAMPLES);
+    if !(1..=MAX_SAMPLES).contains(&samples) {
+        return Err(DesktopError::InvalidInput(format!(
+            "Samples must be between 1 and {}",
+            MAX_SAMPLES
+        )));
+    }
+    let started_at = now_secs();
+    let client = external_client()?;
+
+    let gateway = time_gateway(&api, samples).await;
+    let (network, providers) =
+        tokio::join!(time_network(&client, samples), time_providers(&client));
+    let completion = if completion && gateway.median_ms.is_some() {
+        Some(time_completion(&api, agent.as_deref()).await)
+    } else {
+        None
+    };
+
+    let mut report = BenchmarkReport {
+        started_at,
+        remote: connection::is_remote(),
+        gateway,
+        network,
+        providers,
+        completion,
+        bottleneck: Bottleneck::None,
+        summary: String::new(),
+    };
+    (report.bottleneck, report.summary) = judge(&report);
+    record(&app, report.clone());
+    Ok(report)
+}
+
+/// Past benchmark reports, oldest first
+#[tauri::command]
+pub fn get_benchmark_history(app: AppHandle) -> Result<Vec<BenchmarkReport>, DesktopError> {
+    Ok(load_history(&app)?)
+}
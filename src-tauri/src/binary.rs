@@ -0,0 +1,158 @@
+use serde::Serialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::capabilities::parse_version;
+use crate::error::DesktopError;
+use crate::{installer, kiosk, settings};
+
+/// Last binary found by searching, so commands don't rescan the disk every time
+static DISCOVERED: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[cfg(target_os = "windows")]
+const BINARY_NAMES: &[&str] = &["openclaw.exe", "openclaw.cmd"];
+#[cfg(not(target_os = "windows"))]
+const BINARY_NAMES: &[&str] = &["openclaw"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BinarySource {
+    /// Set with `set_openclaw_binary_path`
+    Override,
+    /// Prebuilt binary downloaded by the installer
+    Installed,
+    /// Found on PATH or in a well-known install location
+    Discovered,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenclawBinary {
+    pub path: Option<String>,
+    pub source: Option<BinarySource>,
+}
+
+fn find_in(dir: &Path) -> Option<PathBuf> {
+    BINARY_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// `bin` directories of every nvm-managed Node, newest version first
+fn nvm_bin_dirs(home: &Path) -> Vec<PathBuf> {
+    let versions = home.join(".nvm").join("versions");
+    // Both `versions/node/<v>` (current nvm) and `versions/<v>` (older layouts)
+    let mut dirs: Vec<PathBuf> = [versions.join("node"), versions]
+        .iter()
+        .filter_map(|root| fs::read_dir(root).ok())
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path().join("bin"))
+        .filter(|bin| bin.is_dir())
+        .collect();
+    dirs.sort_by_key(|bin| {
+        let name = bin.parent().and_then(Path::file_name);
+        std::cmp::Reverse(name.and_then(|n| parse_version(&n.to_string_lossy())))
+    });
+    dirs
+}
+
+/// Install locations GUI launches often miss because they only land on the shell's PATH
+fn candidate_dirs() -> Vec<PathBuf> {
+    let mut candidates: Vec<PathBuf> = env::var_os("PATH")
+        .map(|path| env::split_paths(&path).collect())
+        .unwrap_or_default();
+
+    if let Some(home) = dirs::home_dir() {
+        candidates.extend(nvm_bin_dirs(&home));
+        #[cfg(not(target_os = "windows"))]
+        candidates.extend([
+            home.join(".volta").join("bin"),
+            home.join(".npm-global").join("bin"),
+            home.join(".local").join("share").join("pnpm"),
+            home.join(".local").join("bin"),
+            home.join(".bun").join("bin"),
+            PathBuf::from("/opt/homebrew/bin"),
+            PathBuf::from("/usr/local/bin"),
+        ]);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(appdata) = env::var_os("APPDATA") {
+            candidates.push(PathBuf::from(appdata).join("npm"));
+        }
+        if let Some(local) = env::var_os("LOCALAPPDATA") {
+            let local = PathBuf::from(local);
+            candidates.push(local.join("pnpm"));
+            candidates.push(local.join("Volta").join("bin"));
+        }
+        if let Some(home) = dirs::home_dir() {
+            candidates.push(home.join(".bun").join("bin"));
+        }
+    }
+
+    candidates
+}
+
+fn discover() -> Option<PathBuf> {
+    let mut cached = DISCOVERED.lock().ok()?;
+    if let Some(path) = cached.as_ref().filter(|p| p.is_file()) {
+        return Some(path.clone());
+    }
+    // Misses aren't cached so a later install is picked up right away
+    *cached = candidate_dirs().iter().find_map(|dir| find_in(dir));
+    cached.clone()
+}
+
+/// Locate the `openclaw` binary: explicit override, then the installer's copy,
+/// then PATH and common install locations
+pub fn resolve() -> Option<(PathBuf, BinarySource)> {
+    if let Some(path) = settings::current().openclaw_binary_path {
+        return Some((path, BinarySource::Override));
+    }
+    if let Some(path) = installer::installed_binary_path()
+        .ok()
+        .filter(|p| p.exists())
+    {
+        return Some((path, BinarySource::Installed));
+    }
+    discover().map(|path| (path, BinarySource::Discovered))
+}
+
+/// Get the `openclaw` binary commands run, and how it was found
+#[tauri::command]
+pub fn get_openclaw_binary_path() -> OpenclawBinary {
+    let resolved = resolve();
+    OpenclawBinary {
+        path: resolved.as_ref().map(|(p, _)| p.display().to_string()),
+        source: resolved.map(|(_, source)| source),
+    }
+}
+
+/// Use a specific `openclaw` binary, or go back to automatic discovery with `None`
+#[tauri::command]
+pub fn set_openclaw_binary_path(path: Option<String>) -> Result<OpenclawBinary, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let path = match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            if !path.is_file() {
+                return Err(DesktopError::InvalidInput(format!(
+                    "'{}' is not a file",
+                    path.display()
+                )));
+            }
+            Some(path)
+        }
+        None => None,
+    };
+
+    if let Ok(mut cached) = DISCOVERED.lock() {
+        *cached = None;
+    }
+    settings::update(|s| s.openclaw_binary_path = path)?;
+    Ok(get_openclaw_binary_path())
+}
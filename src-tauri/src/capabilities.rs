@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::sync::RwLock;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::error::DesktopError;
+use crate::{connection, read_gateway_token};
+
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayFeature {
+    HotReload,
+    WebsocketEvents,
+    MetricsApi,
+    Pause,
+}
+
+impl GatewayFeature {
+    const ALL: [GatewayFeature; 4] = [
+        GatewayFeature::HotReload,
+        GatewayFeature::WebsocketEvents,
+        GatewayFeature::MetricsApi,
+        GatewayFeature::Pause,
+    ];
+
+    /// First gateway release that ships the feature, used when the gateway
+    /// predates the capabilities endpoint and only reports a version
+    fn min_version(self) -> &'static str {
+        match self {
+            GatewayFeature::WebsocketEvents => "1.0.0",
+            GatewayFeature::HotReload => "1.2.0",
+            GatewayFeature::MetricsApi => "1.3.0",
+            GatewayFeature::Pause => "1.4.0",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            GatewayFeature::HotReload => "Hot reload",
+            GatewayFeature::WebsocketEvents => "WebSocket events",
+            GatewayFeature::MetricsApi => "Metrics API",
+            GatewayFeature::Pause => "Pause/resume",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayCapabilities {
+    pub version: Option<String>,
+    /// Version of the HTTP API, reported separately by newer gateways
+    #[serde(default)]
+    pub api_version: Option<String>,
+    pub features: BTreeSet<GatewayFeature>,
+    /// Whether the set came from the gateway itself or was inferred from its version
+    pub reported: bool,
+}
+
+/// Capability set of the connected gateway, discovered when it comes up
+#[derive(Default)]
+pub struct CapabilityCache(RwLock<Option<GatewayCapabilities>>);
+
+#[derive(Deserialize)]
+struct CapabilitiesResponse {
+    version: Option<String>,
+    #[serde(default, alias = "apiVersion")]
+    api_version: Option<String>,
+    #[serde(default)]
+    features: Vec<String>,
+}
+
+/// Parse the first `major.minor.patch` triple found in a version string
+pub fn parse_version(text: &str) -> Option<(u64, u64, u64)> {
+    text.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .filter(|part| part.contains('.'))
+        .find_map(|part| {
+            let mut nums = part.split('.').map(|n| n.parse::<u64>().ok());
+            let major = nums.next()??;
+            let minor = nums.next()??;
+            let patch = nums.next().flatten().unwrap_or(0);
+            Some((major, minor, patch))
+        })
+}
+
+fn infer_features(version: &str) -> BTreeSet<GatewayFeature> {
+    let Some(current) = parse_version(version) else {
+        return BTreeSet::new();
+    };
+    GatewayFeature::ALL
+        .into_iter()
+        .filter(|f| parse_version(f.min_version()).is_some_and(|min| current >= min))
+        .collect()
+}
+
+fn parse_feature(name: &str) -> Option<GatewayFeature> {
+    serde_json::from_value(serde_json::Value::String(name.replace('-', "_"))).ok()
+}
+
+/// Ask the running gateway what it supports
+async fn discover() -> Result<GatewayCapabilities, String> {
+    let client = connection::apply_tls(reqwest::Client::builder().timeout(DISCOVERY_TIMEOUT))?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let base = connection::base_url();
+    let token = read_gateway_token();
+    let get = |path: &str| {
+        let request = client.get(format!("{}{}", base, path));
+        match &token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    };
+
+    if let Ok(response) = get("/api/capabilities").send().await {
+        if response.status().is_success() {
+            if let Ok(body) = response.json::<CapabilitiesResponse>().await {
+                return Ok(GatewayCapabilities {
+                    version: body.version,
+                    api_version: body.api_version,
+                    features: body
+                        .features
+                        .iter()
+                        .filter_map(|f| parse_feature(f))
+                        .collect(),
+                    reported: true,
+                });
+            }
+        }
+    }
+
+    // Older gateways only expose their version; fall back to the release table
+    let (version, api_version) = match get("/api/version").send().await {
+        Ok(response) if response.status().is_success() => response
+            .json::<CapabilitiesResponse>()
+            .await
+            .map(|b| (b.version, b.api_version))
+            .unwrap_or_default(),
+        _ => (None, None),
+    };
+    let version = version.or_else(crate::detect_openclaw_version);
+
+    Ok(GatewayCapabilities {
+        features: version.as_deref().map(infer_features).unwrap_or_default(),
+        version,
+        api_version,
+        reported: false,
+    })
+}
+
+/// Re-discover capabilities and cache them in managed state
+pub async fn refresh<R: Runtime>(app: &AppHandle<R>) -> Result<GatewayCapabilities, String> {
+    let capabilities = discover().await?;
+    let cache = app.state::<CapabilityCache>();
+    if let Ok(mut guard) = cache.0.write() {
+        *guard = Some(capabilities.clone());
+    }
+    Ok(capabilities)
+}
+
+/// Cached capabilities, without asking the gateway
+pub fn cached<R: Runtime>(app: &AppHandle<R>) -> Option<GatewayCapabilities> {
+    app.state::<CapabilityCache>()
+        .0
+        .read()
+        .ok()
+        .and_then(|guard| guard.clone())
+}
+
+/// Forget cached capabilities, e.g. after the gateway stops
+pub fn clear<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(mut guard) = app.state::<CapabilityCache>().0.write() {
+        *guard = None;
+    }
+}
+
+/// Fail with a "requires gateway ≥ X" message unless the connected gateway supports `feature`
+pub fn require<R: Runtime>(app: &AppHandle<R>, feature: GatewayFeature) -> Result<(), String> {
+    let cache = app.state::<CapabilityCache>();
+    let guard = cache
+        .0
+        .read()
+        .map_err(|_| "Capability cache lock poisoned".to_string())?;
+
+    match guard.as_ref() {
+        Some(caps) if caps.features.contains(&feature) => Ok(()),
+        Some(caps) => Err(format!(
+            "{} requires gateway ≥ {} (connected gateway is {})",
+            feature.label(),
+            feature.min_version(),
+            caps.version.as_deref().unwrap_or("an unknown version")
+        )),
+        None => Err(format!(
+            "{} requires a running gateway ≥ {}",
+            feature.label(),
+            feature.min_version()
+        )),
+    }
+}
+
+/// Get the capabilities of the connected gateway, discovering them if not cached yet
+#[tauri::command]
+pub async fn get_gateway_capabilities(
+    app: AppHandle,
+    cache: State<'_, CapabilityCache>,
+) -> Result<Option<GatewayCapabilities>, DesktopError> {
+    if let Some(caps) = cache.0.read().ok().and_then(|guard| guard.clone()) {
+        return Ok(Some(caps));
+    }
+    if !crate::is_gateway_running() {
+        return Ok(None);
+    }
+    Ok(Some(refresh(&app).await?))
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+use crate::error::DesktopError;
+use crate::events::{
+    AgentReplyChunk, AgentReplyDone, AGENT_REPLY_CHUNK_EVENT, AGENT_REPLY_DONE_EVENT,
+};
+use crate::gateway_api::GatewayApi;
+use crate::integrations::{self, WebhookEvent};
+use crate::session_search;
+
+const CHAT_PATH: &str = "/api/chat";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatRequest {
+    pub message: String,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub agent: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ChatBody<'a> {
+    #[serde(flatten)]
+    request: &'a ChatRequest,
+    stream: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatReply {
+    pub session_id: Option<String>,
+    pub message_id: Option<String>,
+    pub reply: Option<String>,
+}
+
+/// Text carried by one server-sent event, plus the session id if the gateway reports it.
+/// Events are JSON (`{"delta": ...}`, `{"content": ...}` or `{"text": ...}`) or plain text.
+fn parse_event(data: &str) -> (Option<String>, Option<String>) {
+    let Ok(json) = serde_json::from_str::<Value>(data) else {
+        return (Some(data.to_string()), None);
+    };
+    let delta = ["delta", "content", "text"]
+        .iter()
+        .find_map(|key| json.get(key).and_then(Value::as_str))
+        .map(str::to_string);
+    let session_id = json
+        .get("session_id")
+        .or_else(|| json.get("sessionId"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    (delta, session_id)
+}
+
+async fn forward_stream<R: Runtime>(
+    app: &AppHandle<R>,
+    stream_id: u64,
+    mut response: reqwest::Response,
+) -> Result<Option<String>, String> {
+    // Bytes, not text: a character can be split across network chunks
+    let mut buffer = Vec::new();
+    let mut session_id = None;
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Reply stream interrupted: {}", e))?
+    {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            let bytes: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&bytes);
+            let Some(data) = line.trim_end().strip_prefix("data:") else {
+                continue;
+            };
+            let data = data.trim_start();
+            if data == "[DONE]" {
+                return Ok(session_id);
+            }
+            let (delta, id) = parse_event(data);
+            if id.is_some() {
+                session_id = id;
+            }
+            if let Some(delta) = delta.filter(|d| !d.is_empty()) {
+                if let Err(e) = app.emit(
+                    AGENT_REPLY_CHUNK_EVENT,
+                    AgentReplyChunk { stream_id, delta },
+                ) {
+                    log_error!("Failed to emit reply chunk: {}", e);
+                }
+            }
+        }
+    }
+    Ok(session_id)
+}
+
+/// Send a message and wait for the agent's complete reply
+#[tauri::command]
+pub async fn send_agent_message(
+    api: State<'_, GatewayApi>,
+    request: ChatRequest,
+) -> Result<ChatReply, DesktopError> {
+    let body = ChatBody {
+        request: &request,
+        stream: false,
+    };
+    Ok(api.post(CHAT_PATH, &body).await?)
+}
+
+/// Send a message and stream the reply as `agent-reply-chunk` events, ending with
+/// `agent-reply-done`. The events carry the caller's `stream_id`, so the caller
+/// knows it before the first chunk can arrive.
+#[tauri::command]
+pub async fn stream_agent_reply(
+    app: AppHandle,
+    api: State<'_, GatewayApi>,
+    stream_id: u64,
+    request: ChatRequest,
+) -> Result<(), DesktopError> {
+    let body = ChatBody {
+        request: &request,
+        stream: true,
+    };
+    let response = api.post_stream(CHAT_PATH, &body).await?;
+
+    let fallback_session = request.session_id.clone();
+    tauri::async_runtime::spawn(async move {
+        let (session_id, error) = match forward_stream(&app, stream_id, response).await {
+            Ok(id) => (id.or(fallback_session), None),
+            Err(e) => (fallback_session, Some(e)),
+        };
+        if let Some(id) = &session_id {
+            session_search::mark_dirty(id);
+        }
+        match &error {
+            Some(e) => integrations::forward(
+                WebhookEvent::Error,
+                format!("Agent reply failed: {}", e),
+                serde_json::json!({ "session_id": session_id, "error": e }),
+            ),
+            None => integrations::forward(
+                WebhookEvent::SessionCompleted,
+                "Agent reply completed".to_string(),
+                serde_json::json!({ "session_id": session_id }),
+            ),
+        }
+        let done = AgentReplyDone {
+            stream_id,
+            session_id,
+            error,
+        };
+        if let Err(e) = app.emit(AGENT_REPLY_DONE_EVENT, done) {
+            log_error!("Failed to emit reply completion: {}", e);
+        }
+    });
+    Ok(())
+}
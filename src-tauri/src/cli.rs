@@ -0,0 +1,277 @@
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Runtime};
+
+use crate::{
+    kiosk, openclaw_home_dir, profiles, settings, start_default_gateway, stop_default_gateway,
+    window,
+};
+
+/// Pid of the running desktop app, so `config set` knows whether to forward itself
+const INSTANCE_FILE: &str = "run/desktop.pid";
+
+/// Settings that only change through their own flow
+const PROTECTED_KEYS: &[&str] = &["permission_grants"];
+
+/// `openclaw-desktop config get <key>` / `config set <key> <value>`, for scripts.
+///
+/// Keys are dotted paths into the settings, e.g. `auto_restart.enabled`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigCommand {
+    Get { key: String },
+    Set { key: String, value: String },
+}
+
+/// Actions requested on the command line, e.g. `openclaw-desktop --profile work --start-gateway`.
+///
+/// Flags meant for other parts of the app (`--minimized`, `--safe-mode`, deep
+/// link URLs) are ignored here.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CliArgs {
+    pub start_gateway: bool,
+    pub stop_gateway: bool,
+    pub show_logs: bool,
+    /// Read-only kiosk mode for this run, whatever the settings say
+    pub kiosk: bool,
+    pub profile: Option<String>,
+}
+
+impl CliArgs {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Parse arguments after the program name
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<CliArgs, String> {
+    let mut parsed = CliArgs::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--start-gateway" => parsed.start_gateway = true,
+            "--stop-gateway" => parsed.stop_gateway = true,
+            "--show-logs" => parsed.show_logs = true,
+            kiosk::KIOSK_FLAG => parsed.kiosk = true,
+            "--profile" => {
+                parsed.profile = Some(args.next().ok_or("--profile needs a profile name")?);
+            }
+            _ => {
+                if let Some(name) = arg.strip_prefix("--profile=") {
+                    parsed.profile = Some(name.to_string());
+                }
+            }
+        }
+    }
+    if parsed.start_gateway && parsed.stop_gateway {
+        return Err("--start-gateway and --stop-gateway can't be combined".to_string());
+    }
+    Ok(parsed)
+}
+
+/// Parse a `config` subcommand from the arguments after the program name
+pub fn parse_config_command(args: &[String]) -> Option<Result<ConfigCommand, String>> {
+    if args.first().map(String::as_str) != Some("config") {
+        return None;
+    }
+    let usage = "Usage: config get <key> | config set <key> <value>".to_string();
+    Some(match &args[1..] {
+        [action, key] if action == "get" => Ok(ConfigCommand::Get { key: key.clone() }),
+        [action, key, value] if action == "set" => Ok(ConfigCommand::Set {
+            key: key.clone(),
+            value: value.clone(),
+        }),
+        _ => Err(usage),
+    })
+}
+
+fn lookup<'a>(settings: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.')
+        .try_fold(settings, |value, part| value.as_object()?.get(part))
+}
+
+/// Read a setting; strings print bare, everything else as JSON
+pub fn config_get(key: &str) -> Result<String, String> {
+    let settings = serde_json::to_value(settings::current())
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    match lookup(&settings, key) {
+        Some(Value::String(text)) => Ok(text.clone()),
+        Some(value) => serde_json::to_string_pretty(value)
+            .map_err(|e| format!("Failed to serialize {}: {}", key, e)),
+        None => Err(format!("Unknown setting: {}", key)),
+    }
+}
+
+/// Change one setting. The value is read as JSON when it parses, so `true`,
+/// `8080` and `null` keep their types; anything else is taken as a string.
+pub fn config_set(key: &str, value: &str) -> Result<(), String> {
+    kiosk::ensure_unlocked()?;
+    let parts: Vec<&str> = key.split('.').collect();
+    if parts.iter().any(|part| part.is_empty()) {
+        return Err(format!("Invalid setting key: {}", key));
+    }
+    if PROTECTED_KEYS.contains(&parts[0]) {
+        return Err(format!("{} can't be changed from the command line", key));
+    }
+    let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+
+    let mut next = serde_json::to_value(settings::current())
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    if lookup(&next, parts[0]).is_none() {
+        return Err(format!("Unknown setting: {}", parts[0]));
+    }
+    let (last, parents) = parts.split_last().ok_or("Empty setting key")?;
+    let mut slot = &mut next;
+    for part in parents {
+        let map = slot
+            .as_object_mut()
+            .ok_or_else(|| format!("{} is not a group of settings", part))?;
+        slot = map
+            .entry(part.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if slot.is_null() {
+            *slot = Value::Object(Map::new());
+        }
+    }
+    slot.as_object_mut()
+        .ok_or_else(|| format!("Can't set {}", key))?
+        .insert(last.to_string(), value);
+
+    let next: settings::Settings =
+        serde_json::from_value(next).map_err(|e| format!("Invalid value for {}: {}", key, e))?;
+    settings::update(|current| *current = next).map(|_| ())
+}
+
+fn instance_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(INSTANCE_FILE))
+}
+
+/// Record this process as the running desktop app
+pub fn mark_running() {
+    let result = instance_path().and_then(|path| {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create runtime directory: {}", e))?;
+        }
+        fs::write(&path, std::process::id().to_string())
+            .map_err(|e| format!("Failed to write instance file: {}", e))
+    });
+    if let Err(e) = result {
+        log_error!("{}", e);
+    }
+}
+
+/// Whether another copy of this app is running, checked by pid and executable
+/// so a recycled pid isn't mistaken for it
+pub fn is_app_running() -> bool {
+    let Some(pid) = instance_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| text.trim().parse::<u32>().ok())
+    else {
+        return false;
+    };
+    if pid == std::process::id() {
+        return false;
+    }
+    let pid = Pid::from_u32(pid);
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        true,
+        ProcessRefreshKind::new().with_exe(sysinfo::UpdateKind::OnlyIfNotSet),
+    );
+    let Some(process) = system.process(pid) else {
+        return false;
+    };
+    match (process.exe(), std::env::current_exe().ok()) {
+        (Some(exe), Some(current)) => exe == current,
+        _ => true,
+    }
+}
+
+/// Handle `config get|set` before the app starts.
+///
+/// Returns the exit code when the command was completed here. A `set` while the
+/// app is running returns `None` so the launch is forwarded to that instance,
+/// which holds the live settings and would otherwise overwrite the change.
+pub fn run_config_command(args: &[String]) -> Option<i32> {
+    let command = match parse_config_command(args)? {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}", e);
+            return Some(2);
+        }
+    };
+    let result = match &command {
+        ConfigCommand::Get { key } => config_get(key).map(|value| println!("{}", value)),
+        ConfigCommand::Set { .. } if is_app_running() => return None,
+        ConfigCommand::Set { key, value } => config_set(key, value),
+    };
+    Some(match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    })
+}
+
+/// Carry out the requested actions: profile first, so a start uses it
+pub fn apply<R: Runtime>(app: &AppHandle<R>, args: CliArgs) {
+    if args.is_empty() {
+        return;
+    }
+    // Before anything else, so `--kiosk --start-gateway` is refused
+    if args.kiosk {
+        kiosk::force(app);
+    }
+    if args.show_logs {
+        window::show_logs(app);
+    }
+    if args.profile.is_none() && !args.start_gateway && !args.stop_gateway {
+        return;
+    }
+    if let Err(e) = kiosk::ensure_unlocked() {
+        log_error!("Ignoring command line actions: {}", e);
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        if let Some(profile) = args.profile {
+            if let Err(e) = profiles::set_active_profile(Some(profile)) {
+                log_error!("Failed to switch profile: {}", e);
+                return;
+            }
+        }
+        let result = if args.start_gateway {
+            start_default_gateway(&app)
+        } else if args.stop_gateway {
+            stop_default_gateway(&app)
+        } else {
+            return;
+        };
+        if let Err(e) = result {
+            log_error!("Command line action failed: {}", e);
+        }
+    });
+}
+
+/// Parse and apply the arguments of this launch or of a forwarded second launch
+pub fn handle<R: Runtime>(app: &AppHandle<R>, args: Vec<String>) {
+    let args: Vec<String> = args.into_iter().skip(1).collect();
+    if let Some(command) = parse_config_command(&args) {
+        if let Ok(ConfigCommand::Set { key, value }) = command {
+            if let Err(e) = config_set(&key, &value) {
+                log_error!("Failed to set {}: {}", key, e);
+            }
+        }
+        return;
+    }
+    match parse(args) {
+        Ok(args) => apply(app, args),
+        Err(e) => log_error!("Invalid arguments: {}", e),
+    }
+}
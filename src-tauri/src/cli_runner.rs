@@ -0,0 +1,99 @@
+//! Read-only `openclaw` subcommands the frontend may run directly, with output
+//! streamed as `cli-output` events and a closing `cli-done`.
+
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::DesktopError;
+use crate::events::{CliDone, CliOutput, CLI_DONE_EVENT, CLI_OUTPUT_EVENT};
+use crate::installer::pipe_output;
+use crate::openclaw_command;
+use crate::policy;
+
+static NEXT_RUN_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Flags any allowed subcommand may take
+const ALLOWED_FLAGS: &[&str] = &["--json"];
+
+/// Accept `status`, `sessions list`, `skills list` and `config get <key>`,
+/// optionally followed by [`ALLOWED_FLAGS`]
+fn check_allowed(args: &[String]) -> Result<(), DesktopError> {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let flags = match args.as_slice() {
+        ["status", flags @ ..]
+        | ["sessions", "list", flags @ ..]
+        | ["skills", "list", flags @ ..] => flags,
+        ["config", "get", key, flags @ ..] if !key.starts_with('-') => flags,
+        _ => {
+            return Err(DesktopError::PermissionDenied(format!(
+                "`openclaw {}` is not an allowed subcommand",
+                args.join(" ")
+            )))
+        }
+    };
+    match flags.iter().find(|flag| !ALLOWED_FLAGS.contains(*flag)) {
+        Some(flag) => Err(DesktopError::InvalidInput(format!(
+            "Unsupported argument: {}",
+            flag
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn run(app: &AppHandle, run_id: u64, args: &[String]) -> Result<Option<i32>, String> {
+    let mut command = openclaw_command();
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run openclaw: {}", e))?;
+
+    let emitter = app.clone();
+    pipe_output(
+        child.stdout.take(),
+        child.stderr.take(),
+        move |stream, line| {
+            let payload = CliOutput {
+                run_id,
+                stream,
+                line,
+            };
+            if let Err(e) = emitter.emit(CLI_OUTPUT_EVENT, payload) {
+                log_error!("Failed to emit CLI output: {}", e);
+            }
+        },
+    );
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for openclaw: {}", e))?;
+    Ok(status.code())
+}
+
+/// Run an allow-listed `openclaw` subcommand in the background and return the
+/// run id its `cli-output` and `cli-done` events are tagged with
+#[tauri::command]
+pub fn run_openclaw_subcommand(app: AppHandle, args: Vec<String>) -> Result<u64, DesktopError> {
+    policy::ensure_passthrough_allowed()?;
+    check_allowed(&args)?;
+    let run_id = NEXT_RUN_ID.fetch_add(1, Ordering::Relaxed);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let (code, error) = match run(&app, run_id, &args) {
+            Ok(code) => (code, None),
+            Err(e) => (None, Some(e)),
+        };
+        let done = CliDone {
+            run_id,
+            code,
+            error,
+        };
+        if let Err(e) = app.emit(CLI_DONE_EVENT, done) {
+            log_error!("Failed to emit CLI completion: {}", e);
+        }
+    });
+    Ok(run_id)
+}
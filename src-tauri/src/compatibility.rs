@@ -0,0 +1,255 @@
+//! Which CLI, gateway and gateway API versions this desktop release works with.
+//!
+//! The table is embedded in the app, so each release knows its own limits
+//! without a network lookup. A version that can't be detected is not judged.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Runtime};
+
+use crate::capabilities::{self, parse_version};
+use crate::error::DesktopError;
+use crate::i18n::{t, t_with};
+use crate::notifications::{self, NotificationCategory};
+
+const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Supported versions for desktop releases from `app_since` on. Ranges include
+/// the minimum and exclude the upper bound.
+struct Compat {
+    app_since: &'static str,
+    cli_min: &'static str,
+    cli_below: &'static str,
+    /// Major versions of the gateway's HTTP API
+    api_min: u64,
+    api_below: u64,
+}
+
+/// Oldest first; the last row at or below the app's version applies
+const TABLE: &[Compat] = &[
+    Compat {
+        app_since: "0.1.0",
+        cli_min: "0.9.0",
+        cli_below: "1.2.0",
+        api_min: 0,
+        api_below: 1,
+    },
+    Compat {
+        app_since: "0.2.0",
+        cli_min: "1.0.0",
+        cli_below: "2.0.0",
+        api_min: 1,
+        api_below: 2,
+    },
+];
+
+/// Last report, for diagnostics
+static LAST: Mutex<Option<CompatibilityReport>> = Mutex::new(None);
+/// Issues already notified about, so a restart doesn't repeat the warning
+static WARNED: Mutex<Vec<CompatibilityIssue>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Component {
+    Cli,
+    Gateway,
+    GatewayApi,
+}
+
+impl Component {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Cli => "OpenClaw CLI",
+            Self::Gateway => "Gateway",
+            Self::GatewayApi => "Gateway API",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompatibilityIssue {
+    pub component: Component,
+    pub found: String,
+    /// e.g. `>= 1.0.0, < 2.0.0`
+    pub supported: String,
+    /// Whether `found` is above the range, so the desktop app is the one to update
+    pub too_new: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompatibilityReport {
+    pub app_version: String,
+    pub cli_version: Option<String>,
+    pub gateway_version: Option<String>,
+    pub api_version: Option<String>,
+    pub compatible: bool,
+    pub issues: Vec<CompatibilityIssue>,
+}
+
+fn row() -> Option<&'static Compat> {
+    let app = parse_version(APP_VERSION)?;
+    TABLE
+        .iter()
+        .rev()
+        .find(|row| parse_version(row.app_since).is_some_and(|since| since <= app))
+}
+
+/// Issue for `found` outside `[min, below)`, if any
+fn judge<T: PartialOrd>(
+    component: Component,
+    found: &str,
+    value: T,
+    (min, below): (T, T),
+    supported: String,
+) -> Option<CompatibilityIssue> {
+    let too_new = value >= below;
+    if value >= min && !too_new {
+        return None;
+    }
+    let advice = if too_new {
+        "update OpenClaw Desktop"
+    } else {
+        "update OpenClaw"
+    };
+    Some(CompatibilityIssue {
+        message: format!(
+            "{} {} is not supported by OpenClaw Desktop {} ({} required); {}",
+            component.label(),
+            found,
+            APP_VERSION,
+            supported,
+            advice
+        ),
+        component,
+        found: found.to_string(),
+        supported,
+        too_new,
+    })
+}
+
+/// Major version of an API version such as `1`, `v1` or `1.3`
+fn api_major(text: &str) -> Option<u64> {
+    text.trim()
+        .trim_start_matches('v')
+        .split('.')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn evaluate(
+    cli_version: Option<String>,
+    gateway_version: Option<String>,
+    api_version: Option<String>,
+) -> CompatibilityReport {
+    let mut issues = Vec::new();
+    if let Some(row) = row() {
+        let cli_range = (parse_version(row.cli_min), parse_version(row.cli_below));
+        let cli_supported = format!(">= {}, < {}", row.cli_min, row.cli_below);
+        for (component, version) in [
+            (Component::Cli, &cli_version),
+            (Component::Gateway, &gateway_version),
+        ] {
+            let Some(found) = version.as_deref() else {
+                continue;
+            };
+            if let (Some(value), (Some(min), Some(below))) = (parse_version(found), cli_range) {
+                issues.extend(judge(
+                    component,
+                    found,
+                    value,
+                    (min, below),
+                    cli_supported.clone(),
+                ));
+            }
+        }
+        if let Some(found) = api_version.as_deref() {
+            if let Some(major) = api_major(found) {
+                issues.extend(judge(
+                    Component::GatewayApi,
+                    found,
+                    major,
+                    (row.api_min, row.api_below),
+                    format!(">= {}, < {}", row.api_min, row.api_below),
+                ));
+            }
+        }
+    }
+    CompatibilityReport {
+        app_version: APP_VERSION.to_string(),
+        cli_version,
+        gateway_version,
+        api_version,
+        compatible: issues.is_empty(),
+        issues,
+    }
+}
+
+async fn check(cached: Option<capabilities::GatewayCapabilities>) -> CompatibilityReport {
+    let cli_version = tauri::async_runtime::spawn_blocking(crate::detect_openclaw_version)
+        .await
+        .ok()
+        .flatten();
+    let (gateway_version, api_version) = cached
+        .map(|caps| (caps.version, caps.api_version))
+        .unwrap_or_default();
+    let report = evaluate(cli_version, gateway_version, api_version);
+    if let Ok(mut last) = LAST.lock() {
+        *last = Some(report.clone());
+    }
+    report
+}
+
+/// Most recent report, if a check has run
+pub fn last_report() -> Option<CompatibilityReport> {
+    LAST.lock().ok().and_then(|last| last.clone())
+}
+
+/// Check after the gateway came up and notify about mismatches not reported yet
+pub(crate) async fn check_and_warn<R: Runtime>(app: &AppHandle<R>) {
+    let report = check(capabilities::cached(app)).await;
+    let new: Vec<CompatibilityIssue> = match WARNED.lock() {
+        Ok(mut warned) => {
+            let new = report
+                .issues
+                .iter()
+                .filter(|issue| !warned.contains(issue))
+                .cloned()
+                .collect();
+            *warned = report.issues.clone();
+            new
+        }
+        Err(_) => return,
+    };
+    let Some(first) = new.first() else {
+        return;
+    };
+    notifications::notify(
+        app,
+        NotificationCategory::Compatibility,
+        &t("compatibility.mismatch"),
+        &t_with(
+            "compatibility.mismatch_body",
+            &[
+                ("component", &first.component.label()),
+                ("version", &first.found),
+                ("supported", &first.supported),
+            ],
+        ),
+    );
+}
+
+/// Compare the desktop app, CLI and gateway versions with the supported ranges
+#[tauri::command]
+pub async fn check_compatibility(app: AppHandle) -> Result<CompatibilityReport, DesktopError> {
+    let mut cached = capabilities::cached(&app);
+    if cached.is_none()
+        && tauri::async_runtime::spawn_blocking(crate::is_gateway_running)
+            .await
+            .unwrap_or(false)
+    {
+        cached = capabilities::refresh(&app).await.ok();
+    }
+    Ok(check(cached).await)
+}
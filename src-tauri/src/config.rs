@@ -0,0 +1,600 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+use crate::audit;
+use crate::error::DesktopError;
+use crate::events::CONFIG_CHANGED_EVENT;
+use crate::kiosk;
+use crate::{gateway_env, openclaw_home_dir, policy, secrets, settings};
+
+/// Config file names in the order they are looked for; the first one present wins
+const CONFIG_FILES: [(&str, ConfigFormat); 5] = [
+    ("openclaw.json", ConfigFormat::Json),
+    ("openclaw.json5", ConfigFormat::Json5),
+    ("openclaw.toml", ConfigFormat::Toml),
+    ("openclaw.yaml", ConfigFormat::Yaml),
+    ("openclaw.yml", ConfigFormat::Yaml),
+];
+/// Config with `${VAR}` placeholders, edited in place of `openclaw.json` once it exists
+const TEMPLATE_FILE: &str = "openclaw.template.json";
+/// Where the resolved template is written for the gateway to read
+const RESOLVED_FILE: &str = "run/openclaw.resolved.json";
+
+/// Top-level sections the CLI understands; anything else is likely a typo
+const KNOWN_SECTIONS: [&str; 16] = [
+    "agents", "auth", "channels", "env", "gateway", "hooks", "logging", "messages", "meta",
+    "models", "plugins", "session", "skills", "tools", "ui", "wizard",
+];
+
+const MIN_TOKEN_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigIssue {
+    /// Dotted path of the offending key, e.g. `gateway.port`
+    pub path: String,
+    pub message: String,
+    pub severity: IssueSeverity,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigValidation {
+    pub valid: bool,
+    pub issues: Vec<ConfigIssue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    Json,
+    Json5,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    fn of(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json5") => Self::Json5,
+            Some("toml") => Self::Toml,
+            Some("yaml" | "yml") => Self::Yaml,
+            _ => Self::Json,
+        }
+    }
+
+    fn parse(self, content: &str) -> Result<Value, String> {
+        match self {
+            Self::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            Self::Json5 => json5::from_str(content).map_err(|e| e.to_string()),
+            Self::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+
+    /// JSON5 files are written back as plain JSON, which JSON5 accepts; comments are lost
+    fn serialize(self, config: &Value) -> Result<String, String> {
+        match self {
+            Self::Json | Self::Json5 => {
+                serde_json::to_string_pretty(config).map_err(|e| e.to_string())
+            }
+            // TOML has no null, so such keys can't be saved there
+            Self::Toml => toml::to_string_pretty(config).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::to_string(config).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigFileInfo {
+    pub path: String,
+    pub format: ConfigFormat,
+    pub exists: bool,
+    /// The `${VAR}` template is being edited instead of the config itself
+    pub template: bool,
+}
+
+/// The config file in `dir`, trying each supported format in priority order
+pub(crate) fn find_config(dir: &Path) -> Option<PathBuf> {
+    CONFIG_FILES
+        .iter()
+        .map(|(name, _)| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// The config file in use, or where a new `openclaw.json` would go
+pub(crate) fn config_path() -> Result<PathBuf, String> {
+    let home = openclaw_home_dir()?;
+    Ok(find_config(&home).unwrap_or_else(|| home.join(CONFIG_FILES[0].0)))
+}
+
+/// Parse a config file according to its extension
+pub(crate) fn read_config_file(path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path).map_err(|e| format!("Failed to read config: {}", e))?;
+    ConfigFormat::of(path)
+        .parse(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))
+}
+
+fn template_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(TEMPLATE_FILE))
+}
+
+/// The file the editor works on: the template when there is one, else `openclaw.json`
+pub(crate) fn editable_path() -> Result<PathBuf, String> {
+    let template = template_path()?;
+    if template.exists() {
+        Ok(template)
+    } else {
+        config_path()
+    }
+}
+
+fn contains_placeholder(value: &Value) -> bool {
+    match value {
+        Value::String(s) => s.contains("${"),
+        Value::Array(items) => items.iter().any(contains_placeholder),
+        Value::Object(map) => map.values().any(contains_placeholder),
+        _ => false,
+    }
+}
+
+/// Replace `${NAME}` in a string; `$${` escapes a literal `${`
+fn interpolate_str(
+    text: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    missing: &mut Vec<String>,
+) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        out.push_str(&rest[..start]);
+        let Some(len) = rest[start + 2..].find('}') else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let name = &rest[start + 2..start + 2 + len];
+        match lookup(name) {
+            Some(value) => out.push_str(&value),
+            None => missing.push(name.to_string()),
+        }
+        rest = &rest[start + 3 + len..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn interpolate(
+    value: &Value,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    missing: &mut Vec<String>,
+) -> Value {
+    match value {
+        Value::String(s) if s.contains("${") => {
+            let resolved = interpolate_str(s, lookup, missing);
+            // A string that was only a placeholder may hold a number or bool, e.g. a port
+            if s.starts_with("${") && s.ends_with('}') {
+                if let Ok(parsed @ (Value::Number(_) | Value::Bool(_))) =
+                    serde_json::from_str::<Value>(&resolved)
+                {
+                    return parsed;
+                }
+            }
+            Value::String(resolved)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|v| interpolate(v, lookup, missing))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), interpolate(v, lookup, missing)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Resolve placeholders from the active profile's variables, then the environment
+pub(crate) fn resolve(template: &Value) -> Result<Value, String> {
+    let profile = gateway_env::profile_key(None)?;
+    let names = settings::current()
+        .config_variables
+        .remove(&profile)
+        .unwrap_or_default();
+    let lookup = |name: &str| {
+        names
+            .iter()
+            .any(|n| n == name)
+            .then(|| secrets::config_variable(&profile, name))
+            .flatten()
+            .or_else(|| std::env::var(name).ok())
+    };
+
+    let mut missing = Vec::new();
+    let resolved = interpolate(template, &lookup, &mut missing);
+    if missing.is_empty() {
+        Ok(resolved)
+    } else {
+        missing.sort();
+        missing.dedup();
+        Err(format!(
+            "Undefined config variables: {}",
+            missing.join(", ")
+        ))
+    }
+}
+
+/// The config as the gateway will see it, with any template resolved
+pub(crate) fn load_resolved_config() -> Result<Value, String> {
+    let config = load_config()?;
+    if template_path()?.exists() {
+        resolve(&config)
+    } else {
+        Ok(config)
+    }
+}
+
+/// Resolve the template into a private runtime file for a gateway about to start.
+///
+/// Returns `None` when no template is in use and the gateway reads `openclaw.json`
+/// directly. The template on disk keeps its placeholders, so secrets supplied via
+/// variables never land in the user-edited config.
+pub(crate) fn materialize() -> Result<Option<PathBuf>, String> {
+    let template = template_path()?;
+    if !template.exists() {
+        return Ok(None);
+    }
+    let resolved = resolve(&load_config()?)?;
+    let path = openclaw_home_dir()?.join(RESOLVED_FILE);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create runtime directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&resolved)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    // Private from the moment it exists, since it holds the resolved secrets
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(&path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .map_err(|e| format!("Failed to write resolved config: {}", e))?;
+    Ok(Some(path))
+}
+
+/// Re-resolve the template before `openclaw daemon start` or `restart`, so the
+/// service reads current variables rather than those of its last launch
+pub(crate) fn refresh_for_daemon(args: &[&str]) -> Result<(), String> {
+    if matches!(args, ["daemon", "start" | "restart", ..]) {
+        materialize()?;
+    }
+    Ok(())
+}
+
+/// Move config variable values that older versions kept in the settings file into
+/// the keychain, leaving only their names. Returns whether anything changed.
+pub(crate) fn migrate_plaintext_variables(raw: &mut Value) -> bool {
+    let Some(profiles) = raw
+        .get_mut("config_variables")
+        .and_then(Value::as_object_mut)
+    else {
+        return false;
+    };
+    let mut migrated = false;
+    for (profile, vars) in profiles.iter_mut() {
+        let Some(values) = vars.as_object() else {
+            continue;
+        };
+        let mut names = Vec::with_capacity(values.len());
+        for (name, value) in values {
+            let stored = value
+                .as_str()
+                .ok_or_else(|| "not a string".to_string())
+                .and_then(|value| secrets::set_config_variable(profile, name, Some(value)));
+            match stored {
+                Ok(()) => names.push(Value::String(name.clone())),
+                Err(e) => log_error!("Dropped config variable {} while migrating: {}", name, e),
+            }
+        }
+        *vars = Value::Array(names);
+        migrated = true;
+    }
+    migrated
+}
+
+pub(crate) fn load_config() -> Result<Value, String> {
+    let path = editable_path()?;
+    if !path.exists() {
+        return Ok(Value::Object(Map::new()));
+    }
+    read_config_file(&path)
+}
+
+fn check_port(issues: &mut Vec<ConfigIssue>, path: &str, value: &Value) {
+    let valid =
+        contains_placeholder(value) || value.as_u64().is_some_and(|p| (1..=65535).contains(&p));
+    if !valid {
+        issues.push(ConfigIssue {
+            path: path.to_string(),
+            message: "Port must be an integer between 1 and 65535".to_string(),
+            severity: IssueSeverity::Error,
+        });
+    }
+}
+
+fn check_token(issues: &mut Vec<ConfigIssue>, path: &str, value: &Value) {
+    let Some(token) = value.as_str() else {
+        issues.push(ConfigIssue {
+            path: path.to_string(),
+            message: "Token must be a string".to_string(),
+            severity: IssueSeverity::Error,
+        });
+        return;
+    };
+    if token.contains("${") {
+        return;
+    }
+    if token.is_empty() || token.chars().any(char::is_whitespace) {
+        issues.push(ConfigIssue {
+            path: path.to_string(),
+            message: "Token must be non-empty and contain no whitespace".to_string(),
+            severity: IssueSeverity::Error,
+        });
+    } else if token.len() < MIN_TOKEN_LEN {
+        issues.push(ConfigIssue {
+            path: path.to_string(),
+            message: format!("Token is shorter than {} characters", MIN_TOKEN_LEN),
+            severity: IssueSeverity::Warning,
+        });
+    }
+}
+
+fn is_loopback(bind: &str) -> bool {
+    matches!(bind, "loopback" | "localhost" | "127.0.0.1" | "::1")
+}
+
+/// Structural checks for `openclaw.json`: object shape, ports, token format, known sections
+pub fn validate(config: &Value) -> ConfigValidation {
+    let mut issues = Vec::new();
+
+    let Some(root) = config.as_object() else {
+        return ConfigValidation {
+            valid: false,
+            issues: vec![ConfigIssue {
+                path: String::new(),
+                message: "Config must be a JSON object".to_string(),
+                severity: IssueSeverity::Error,
+            }],
+        };
+    };
+
+    for (key, value) in root {
+        if !KNOWN_SECTIONS.contains(&key.as_str()) {
+            issues.push(ConfigIssue {
+                path: key.clone(),
+                message: format!("Unknown section \"{}\"", key),
+                severity: IssueSeverity::Warning,
+            });
+        } else if !value.is_object() && key != "env" {
+            issues.push(ConfigIssue {
+                path: key.clone(),
+                message: "Section must be an object".to_string(),
+                severity: IssueSeverity::Error,
+            });
+        }
+    }
+
+    if let Some(gateway) = root.get("gateway").and_then(Value::as_object) {
+        if let Some(port) = gateway.get("port") {
+            check_port(&mut issues, "gateway.port", port);
+        }
+        if let Some(bind) = gateway.get("bind") {
+            match bind.as_str() {
+                None => issues.push(ConfigIssue {
+                    path: "gateway.bind".to_string(),
+                    message: "Bind address must be a string".to_string(),
+                    severity: IssueSeverity::Error,
+                }),
+                Some(bind) if policy::current().disable_lan_access && !is_loopback(bind) => issues
+                    .push(ConfigIssue {
+                        path: "gateway.bind".to_string(),
+                        message: "LAN access is disabled by policy; bind to loopback".to_string(),
+                        severity: IssueSeverity::Error,
+                    }),
+                Some(_) => {}
+            }
+        }
+        match gateway.get("auth") {
+            Some(Value::Object(auth)) => {
+                if let Some(token) = auth.get("token") {
+                    check_token(&mut issues, "gateway.auth.token", token);
+                }
+            }
+            Some(_) => issues.push(ConfigIssue {
+                path: "gateway.auth".to_string(),
+                message: "Auth settings must be an object".to_string(),
+                severity: IssueSeverity::Error,
+            }),
+            None => {}
+        }
+    }
+
+    ConfigValidation {
+        valid: !issues.iter().any(|i| i.severity == IssueSeverity::Error),
+        issues,
+    }
+}
+
+/// Replace the editable config atomically, keeping the previous version as a `.bak`.
+///
+/// A config with placeholders is saved as the template, which from then on is
+/// what the editor reads and writes.
+pub(crate) fn write_atomic(config: &Value) -> Result<(), String> {
+    let path = if contains_placeholder(config) {
+        template_path()?
+    } else {
+        editable_path()?
+    };
+    let format = ConfigFormat::of(&path);
+    let content = format
+        .serialize(config)
+        .map_err(|e| format!("Failed to serialize config: {}", e))?;
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("json")
+        .to_string();
+
+    if path.exists() {
+        fs::copy(&path, path.with_extension(format!("{}.bak", extension)))
+            .map_err(|e| format!("Failed to back up config: {}", e))?;
+    }
+
+    let tmp_path = path.with_extension(format!("{}.tmp", extension));
+    let result = fs::write(&tmp_path, content)
+        .map_err(|e| format!("Failed to write config: {}", e))
+        .and_then(|_| {
+            fs::rename(&tmp_path, &path).map_err(|e| {
+                let _ = fs::remove_file(&tmp_path);
+                format!("Failed to replace config: {}", e)
+            })
+        });
+    audit::record_result("config_write", &path.display().to_string(), &result);
+    result
+}
+
+/// Read the editable config (`openclaw.template.json` if present, else
+/// `openclaw.{json,json5,toml,yaml}`), or an empty object when it does not exist yet
+#[tauri::command]
+pub fn read_openclaw_config() -> Result<Value, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    Ok(load_config()?)
+}
+
+/// Which file the config editor reads and writes, and in what format
+#[tauri::command]
+pub fn get_openclaw_config_file() -> Result<ConfigFileInfo, DesktopError> {
+    let path = editable_path()?;
+    Ok(ConfigFileInfo {
+        format: ConfigFormat::of(&path),
+        exists: path.exists(),
+        template: path == template_path()?,
+        path: path.display().to_string(),
+    })
+}
+
+/// Validate the given config, or the file on disk when none is passed
+#[tauri::command]
+pub fn validate_openclaw_config(config: Option<Value>) -> Result<ConfigValidation, DesktopError> {
+    let config = match config {
+        Some(config) => config,
+        None => load_config()?,
+    };
+    Ok(validate(&config))
+}
+
+/// Validate and save the config, then emit `config-changed`
+#[tauri::command]
+pub fn write_openclaw_config(
+    app: AppHandle,
+    config: Value,
+) -> Result<ConfigValidation, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let validation = validate(&config);
+    if !validation.valid {
+        let errors: Vec<String> = validation
+            .issues
+            .iter()
+            .filter(|i| i.severity == IssueSeverity::Error)
+            .map(|i| format!("{}: {}", i.path, i.message))
+            .collect();
+        return Err(DesktopError::InvalidInput(format!(
+            "Invalid config:\n{}",
+            errors.join("\n")
+        )));
+    }
+
+    write_atomic(&config)?;
+    if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, &config) {
+        log_error!("Failed to emit config change: {}", e);
+    }
+    Ok(validation)
+}
+
+fn validate_variable_name(name: &str) -> Result<(), String> {
+    if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid variable name '{}': use letters, numbers and '_'",
+            name
+        ))
+    }
+}
+
+/// Names of the config template variables set for a profile, which defaults to
+/// the active one. Values stay in the keychain.
+#[tauri::command]
+pub fn get_config_variables(profile: Option<String>) -> Result<Vec<String>, DesktopError> {
+    let profile = gateway_env::profile_key(profile)?;
+    Ok(settings::current()
+        .config_variables
+        .remove(&profile)
+        .unwrap_or_default())
+}
+
+/// Set a config template variable, or remove it with `None`. It applies the
+/// next time the gateway starts.
+#[tauri::command]
+pub fn set_config_variable(
+    profile: Option<String>,
+    name: String,
+    value: Option<String>,
+) -> Result<Vec<String>, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let profile = gateway_env::profile_key(profile)?;
+    let name = name.trim().to_string();
+    validate_variable_name(&name)?;
+    secrets::set_config_variable(&profile, &name, value.as_deref())?;
+
+    let saved = settings::update(|s| {
+        let names = s.config_variables.entry(profile.clone()).or_default();
+        names.retain(|n| n != &name);
+        if value.is_some() {
+            names.push(name.clone());
+            names.sort();
+        }
+        if names.is_empty() {
+            s.config_variables.remove(&profile);
+        }
+    })?;
+    Ok(saved
+        .config_variables
+        .get(&profile)
+        .cloned()
+        .unwrap_or_default())
+}
@@ -0,0 +1,330 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+use crate::agents::{self, AgentDefinition};
+use crate::config::{self, IssueSeverity};
+use crate::diagnostics::{redact, REDACTED};
+use crate::error::DesktopError;
+use crate::events::{AgentChange, CONFIG_CHANGED_EVENT};
+use crate::{kiosk, settings};
+
+const BUNDLE_FORMAT: u32 = 1;
+
+/// Settings that describe this machine rather than the user's setup
+const LOCAL_SETTINGS: &[&str] = &[
+    "openclaw_binary_path",
+    "workspaces",
+    "permission_grants",
+    "kiosk",
+    "backup",
+    "window_geometry",
+    "main_window_state",
+];
+
+/// A setup that can be carried to another machine, with secrets redacted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ConfigBundle {
+    format: u32,
+    created_at: u64,
+    app_version: String,
+    /// `openclaw.json`, or the template when one is in use; includes skill settings
+    config: Value,
+    settings: Value,
+    agents: Vec<AgentDefinition>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Add what's missing and keep local values where both sides set one
+    Merge,
+    /// Take the bundle's values wherever they differ
+    Overwrite,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigBundleExport {
+    pub path: String,
+    pub agents: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigBundleImport {
+    /// Dotted paths whose value changed
+    pub changed: Vec<String>,
+    pub agents_added: Vec<String>,
+    pub agents_updated: Vec<String>,
+    /// Dotted paths where both sides differ and the local value was kept
+    pub conflicts: Vec<String>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn is_redacted(value: &Value) -> bool {
+    value.as_str() == Some(REDACTED)
+}
+
+fn contains_redacted(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => map.values().any(contains_redacted),
+        Value::Array(items) => items.iter().any(contains_redacted),
+        other => is_redacted(other),
+    }
+}
+
+/// Drop redacted entries so placeholders never become real values
+fn without_redacted(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .filter(|(_, v)| !is_redacted(v))
+                .map(|(k, v)| (k.clone(), without_redacted(v)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Fold `incoming` into `local`. A local value equal to its default counts as
+/// unset, so merging settings still picks up what the other machine customized.
+fn merge(
+    local: &mut Value,
+    incoming: &Value,
+    default: Option<&Value>,
+    mode: ImportMode,
+    path: &str,
+    report: &mut ConfigBundleImport,
+) {
+    if is_redacted(incoming) || local == incoming {
+        return;
+    }
+    if let (Value::Object(local_map), Value::Object(incoming_map)) = (&mut *local, incoming) {
+        for (key, incoming_value) in incoming_map {
+            let key_path = join(path, key);
+            match local_map.get_mut(key) {
+                Some(local_value) => merge(
+                    local_value,
+                    incoming_value,
+                    default.and_then(|d| d.get(key)),
+                    mode,
+                    &key_path,
+                    report,
+                ),
+                None if !is_redacted(incoming_value) => {
+                    local_map.insert(key.clone(), without_redacted(incoming_value));
+                    report.changed.push(key_path);
+                }
+                None => {}
+            }
+        }
+        return;
+    }
+    let unset = local.is_null() || default.is_some_and(|d| d == local);
+    if (mode == ImportMode::Overwrite || unset) && !contains_redacted(incoming) {
+        *local = incoming.clone();
+        report.changed.push(path.to_string());
+    } else {
+        report.conflicts.push(path.to_string());
+    }
+}
+
+fn portable_settings() -> Result<Value, String> {
+    let mut value = serde_json::to_value(settings::current())
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    if let Some(map) = value.as_object_mut() {
+        for key in LOCAL_SETTINGS {
+            map.remove(*key);
+        }
+    }
+    redact(&mut value);
+    Ok(value)
+}
+
+/// Write agents, config (including skill settings), and desktop settings to a
+/// portable JSON bundle. Tokens, keys, and other secrets are redacted, and
+/// machine-specific settings such as paths are left out.
+#[tauri::command]
+pub fn export_config_bundle(path: String) -> Result<ConfigBundleExport, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(DesktopError::InvalidInput(
+            "Bundle path must be absolute".to_string(),
+        ));
+    }
+
+    let mut config = config::load_config()?;
+    redact(&mut config);
+    let agents: Vec<AgentDefinition> = agents::list_agent_files()?
+        .into_iter()
+        .map(|entry| {
+            let mut agent = serde_json::to_value(entry.agent).unwrap_or(Value::Null);
+            redact(&mut agent);
+            serde_json::from_value(agent)
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|e| format!("Failed to serialize agents: {}", e))?;
+    let bundle = ConfigBundle {
+        format: BUNDLE_FORMAT,
+        created_at: now_secs(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        config,
+        settings: portable_settings()?,
+        agents,
+    };
+
+    let content = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| format!("Failed to serialize bundle: {}", e))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create folder: {}", e))?;
+    }
+    fs::write(&path, content).map_err(|e| format!("Failed to write bundle: {}", e))?;
+    Ok(ConfigBundleExport {
+        path: path.display().to_string(),
+        agents: bundle.agents.len(),
+    })
+}
+
+/// Apply a bundle made by `export_config_bundle`.
+///
+/// Nothing is written unless the merged config validates. Redacted values in the
+/// bundle never replace local secrets. Agents are written to `~/.openclaw/agents/`.
+#[tauri::command]
+pub fn import_config_bundle(
+    app: AppHandle,
+    path: String,
+    mode: ImportMode,
+) -> Result<ConfigBundleImport, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read bundle: {}", e))?;
+    let bundle: ConfigBundle =
+        serde_json::from_str(&content).map_err(|e| format!("Not a config bundle: {}", e))?;
+    if bundle.format > BUNDLE_FORMAT {
+        return Err(DesktopError::InvalidInput(
+            "This bundle was made by a newer version of the app".to_string(),
+        ));
+    }
+    for agent in &bundle.agents {
+        agents::validate(agent)?;
+    }
+
+    let mut report = ConfigBundleImport::default();
+
+    let mut config = config::load_config()?;
+    if !config.is_object() {
+        config = Value::Object(Map::new());
+    }
+    let before = report.changed.len();
+    merge(
+        &mut config,
+        &bundle.config,
+        None,
+        mode,
+        "config",
+        &mut report,
+    );
+    let config_changed = report.changed.len() > before;
+    let validation = config::validate(&config);
+    if !validation.valid {
+        let errors: Vec<String> = validation
+            .issues
+            .iter()
+            .filter(|i| i.severity == IssueSeverity::Error)
+            .map(|i| format!("{}: {}", i.path, i.message))
+            .collect();
+        return Err(DesktopError::InvalidInput(format!(
+            "Imported config is invalid:\n{}",
+            errors.join("\n")
+        )));
+    }
+
+    let mut incoming_settings = bundle.settings.clone();
+    if let Some(map) = incoming_settings.as_object_mut() {
+        for key in LOCAL_SETTINGS {
+            map.remove(*key);
+        }
+    }
+    let mut settings_value = serde_json::to_value(settings::current())
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let defaults = serde_json::to_value(settings::Settings::default())
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let before = report.changed.len();
+    merge(
+        &mut settings_value,
+        &incoming_settings,
+        Some(&defaults),
+        mode,
+        "settings",
+        &mut report,
+    );
+    let settings_changed = report.changed.len() > before;
+    let next_settings: settings::Settings = serde_json::from_value(settings_value)
+        .map_err(|e| format!("Imported settings are invalid: {}", e))?;
+
+    let existing: Vec<AgentDefinition> = agents::list_agent_files()?
+        .into_iter()
+        .map(|entry| entry.agent)
+        .collect();
+    let mut agent_writes = Vec::new();
+    for agent in bundle.agents {
+        let incoming = serde_json::to_value(&agent)
+            .map_err(|e| format!("Failed to serialize agent: {}", e))?;
+        match existing.iter().find(|a| a.id == agent.id) {
+            None => {
+                let agent: AgentDefinition = serde_json::from_value(without_redacted(&incoming))
+                    .map_err(|e| format!("Failed to import agent {}: {}", agent.id, e))?;
+                report.agents_added.push(agent.id.clone());
+                agent_writes.push((agent, AgentChange::Created));
+            }
+            Some(local) => {
+                let mut merged = serde_json::to_value(local)
+                    .map_err(|e| format!("Failed to serialize agent: {}", e))?;
+                let before = report.changed.len();
+                let path = format!("agents.{}", agent.id);
+                merge(&mut merged, &incoming, None, mode, &path, &mut report);
+                if report.changed.len() > before {
+                    let agent: AgentDefinition = serde_json::from_value(merged)
+                        .map_err(|e| format!("Failed to import agent {}: {}", agent.id, e))?;
+                    report.agents_updated.push(agent.id.clone());
+                    agent_writes.push((agent, AgentChange::Updated));
+                }
+            }
+        }
+    }
+
+    if config_changed {
+        config::write_atomic(&config)?;
+        if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, &config) {
+            log_error!("Failed to emit config change: {}", e);
+        }
+    }
+    for (agent, change) in agent_writes {
+        agents::write_agent_file(&agent)?;
+        agents::emit_change(&app, &agent.id, change);
+    }
+    if settings_changed {
+        settings::update(|current| {
+            let grants = std::mem::take(&mut current.permission_grants);
+            *current = next_settings;
+            current.permission_grants = grants;
+        })?;
+    }
+    Ok(report)
+}
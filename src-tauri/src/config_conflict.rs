@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::error::DesktopError;
+use crate::events::{ConfigConflict, ConflictField, CONFIG_CHANGED_EVENT, CONFIG_CONFLICT_EVENT};
+use crate::{
+    active_gateway_port, config, connection, is_gateway_running, kiosk, profiles, settings,
+    start_default_gateway, stop_default_gateway,
+};
+
+/// Which side wins when settings and `openclaw.json` disagree
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictChoice {
+    /// Keep the app's values and write them into `openclaw.json`
+    App,
+    /// Adopt the values from `openclaw.json` into the app's settings
+    Config,
+}
+
+fn config_port(config: &Value) -> Option<u16> {
+    config
+        .pointer("/gateway/port")
+        .and_then(Value::as_u64)
+        .and_then(|port| u16::try_from(port).ok())
+}
+
+fn config_profile(config: &Value) -> Option<String> {
+    config
+        .pointer("/meta/profile")
+        .and_then(Value::as_str)
+        .filter(|p| !p.trim().is_empty())
+        .map(str::to_string)
+}
+
+/// Values the app's settings and `openclaw.json` disagree on.
+///
+/// Keys the config leaves out are not conflicts; the app's values apply.
+pub fn detect() -> Vec<ConfigConflict> {
+    let Ok(config) = config::load_resolved_config() else {
+        return Vec::new();
+    };
+    let mut conflicts = Vec::new();
+
+    let port = active_gateway_port();
+    if let Some(config_port) = config_port(&config).filter(|p| *p != port) {
+        conflicts.push(ConfigConflict {
+            field: ConflictField::Port,
+            app_value: Some(port.to_string()),
+            config_value: Some(config_port.to_string()),
+        });
+    }
+    let profile = profiles::active_profile();
+    if let Some(config_profile) = config_profile(&config).filter(|p| Some(p) != profile.as_ref()) {
+        conflicts.push(ConfigConflict {
+            field: ConflictField::Profile,
+            app_value: profile,
+            config_value: Some(config_profile),
+        });
+    }
+    conflicts
+}
+
+/// Tell the frontend about conflicts found at launch
+pub fn check_at_startup<R: Runtime>(app: &AppHandle<R>) {
+    let conflicts = detect();
+    if conflicts.is_empty() {
+        return;
+    }
+    if let Err(e) = app.emit(CONFIG_CONFLICT_EVENT, &conflicts) {
+        log_error!("Failed to emit config conflict: {}", e);
+    }
+}
+
+/// Write the app's values into the editable config
+fn apply_to_config<R: Runtime>(
+    app: &AppHandle<R>,
+    conflicts: &[ConfigConflict],
+) -> Result<(), String> {
+    let mut config = config::load_config()?;
+    let root = config
+        .as_object_mut()
+        .ok_or("openclaw.json is not a JSON object")?;
+    for conflict in conflicts {
+        let (section, key, value) = match conflict.field {
+            ConflictField::Port => ("gateway", "port", Some(Value::from(active_gateway_port()))),
+            ConflictField::Profile => (
+                "meta",
+                "profile",
+                profiles::active_profile().map(Value::from),
+            ),
+        };
+        let section = root
+            .entry(section)
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or_else(|| format!("`{}` in openclaw.json is not an object", section))?;
+        match value {
+            Some(value) => {
+                section.insert(key.to_string(), value);
+            }
+            None => {
+                section.remove(key);
+            }
+        }
+    }
+    config::write_atomic(&config)?;
+    if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, &config) {
+        log_error!("Failed to emit config change: {}", e);
+    }
+    Ok(())
+}
+
+/// Adopt the config's values into settings
+fn apply_to_settings(conflicts: &[ConfigConflict]) -> Result<(), String> {
+    for conflict in conflicts {
+        match conflict.field {
+            ConflictField::Port => {
+                let port = conflict
+                    .config_value
+                    .as_deref()
+                    .and_then(|p| p.parse::<u16>().ok())
+                    .ok_or("Config port is not a valid port number")?;
+                settings::update(|s| s.gateway_port = Some(port))?;
+            }
+            ConflictField::Profile => {
+                profiles::set_active_profile(conflict.config_value.clone())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Conflicts between the app's settings and `openclaw.json` right now
+#[tauri::command]
+pub fn get_config_conflicts() -> Vec<ConfigConflict> {
+    detect()
+}
+
+/// Make settings and `openclaw.json` agree on the chosen side's values.
+///
+/// Adopting the config restarts a running local gateway so it picks up the new
+/// port or profile. Returns whatever conflicts remain.
+#[tauri::command]
+pub async fn resolve_config_conflict(
+    app: AppHandle,
+    choice: ConflictChoice,
+) -> Result<Vec<ConfigConflict>, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let conflicts = detect();
+        if conflicts.is_empty() {
+            return Ok(conflicts);
+        }
+        match choice {
+            ConflictChoice::App => apply_to_config(&app, &conflicts)?,
+            ConflictChoice::Config => {
+                let restart = !connection::is_remote() && is_gateway_running();
+                if restart {
+                    stop_default_gateway(&app)?;
+                }
+                apply_to_settings(&conflicts)?;
+                if restart {
+                    start_default_gateway(&app)?;
+                }
+            }
+        }
+        Ok(detect())
+    })
+    .await
+    .map_err(|e| format!("Failed to resolve config conflict: {}", e))?
+}
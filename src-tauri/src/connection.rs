@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::error::DesktopError;
+use crate::{active_gateway_port, kiosk, secrets, settings, socket_transport, supervisor};
+
+/// Where the app finds the gateway it controls
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum GatewayConnection {
+    /// A gateway on this machine that the app can start and stop
+    #[default]
+    Local,
+    /// A gateway on another host; the app only observes and talks to its API.
+    /// The token lives in the keychain, not in the settings file.
+    Remote { host: String, port: u16, tls: bool },
+    /// A gateway on this machine listening on a unix domain socket or Windows
+    /// named pipe instead of a TCP port; the default location when `path` is unset
+    Socket { path: Option<PathBuf> },
+}
+
+/// How HTTPS connections to the gateway are verified
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GatewayTls {
+    /// The local gateway is served over HTTPS, built in or behind a local reverse proxy
+    pub local_https: bool,
+    /// PEM file with a CA certificate to trust besides the system roots.
+    /// Only the app's own requests use it; the dashboard webview trusts the OS store.
+    pub ca_cert_path: Option<PathBuf>,
+    /// Skip certificate validation entirely, for throwaway self-signed setups
+    pub accept_invalid_certs: bool,
+}
+
+fn load_ca_cert(path: &PathBuf) -> Result<reqwest::Certificate, String> {
+    let pem = fs::read(path)
+        .map_err(|e| format!("Failed to read CA certificate {}: {}", path.display(), e))?;
+    reqwest::Certificate::from_pem(&pem)
+        .map_err(|e| format!("Invalid CA certificate {}: {}", path.display(), e))
+}
+
+/// Apply the configured certificate trust to a client talking to the gateway
+pub fn apply_tls(builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder, String> {
+    let tls = settings::current().gateway_tls;
+    let mut builder = builder.danger_accept_invalid_certs(tls.accept_invalid_certs);
+    if let Some(path) = &tls.ca_cert_path {
+        builder = builder.add_root_certificate(load_ca_cert(path)?);
+    }
+    Ok(builder)
+}
+
+pub fn current() -> GatewayConnection {
+    settings::current().connection
+}
+
+pub fn is_remote() -> bool {
+    matches!(current(), GatewayConnection::Remote { .. })
+}
+
+/// Socket or pipe of the local gateway, when connected through one
+pub fn socket_path() -> Option<PathBuf> {
+    match current() {
+        GatewayConnection::Socket { path: Some(path) } => Some(path),
+        GatewayConnection::Socket { path: None } => socket_transport::default_path()
+            .map_err(|e| log_error!("Failed to resolve gateway socket: {}", e))
+            .ok(),
+        _ => None,
+    }
+}
+
+/// Base URL of the gateway without a trailing slash
+pub fn base_url() -> String {
+    match current() {
+        // Certificates are rarely issued for IP addresses, so HTTPS goes through `localhost`
+        GatewayConnection::Local if settings::current().gateway_tls.local_https => {
+            format!("https://localhost:{}", active_gateway_port())
+        }
+        GatewayConnection::Local => format!("http://127.0.0.1:{}", active_gateway_port()),
+        GatewayConnection::Remote { host, port, tls } => {
+            let scheme = if tls { "https" } else { "http" };
+            // Bare IPv6 addresses need brackets in a URL
+            if host.contains(':') && !host.starts_with('[') {
+                format!("{}://[{}]:{}", scheme, host, port)
+            } else {
+                format!("{}://{}:{}", scheme, host, port)
+            }
+        }
+        // reqwest and the webview reach the socket through the relay
+        GatewayConnection::Socket { .. } => {
+            let port = socket_path()
+                .ok_or_else(|| "No gateway socket path".to_string())
+                .and_then(|path| socket_transport::relay_port(&path))
+                .unwrap_or_else(|e| {
+                    log_error!("{}", e);
+                    0
+                });
+            format!("http://127.0.0.1:{}", port)
+        }
+    }
+}
+
+/// Fail commands that spawn or stop a local gateway while connected to a remote one
+pub fn ensure_local() -> Result<(), DesktopError> {
+    if is_remote() {
+        return Err(DesktopError::RemoteConnection);
+    }
+    Ok(())
+}
+
+/// Switch between the local gateway and a remote one.
+///
+/// `token` replaces the stored remote token; `None` keeps the current one.
+#[tauri::command]
+pub fn set_gateway_connection(
+    connection: GatewayConnection,
+    token: Option<String>,
+) -> Result<GatewayConnection, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if let GatewayConnection::Remote { host, port, .. } = &connection {
+        if host.trim().is_empty() {
+            return Err(DesktopError::InvalidInput(
+                "Remote host must not be empty".to_string(),
+            ));
+        }
+        if *port == 0 {
+            return Err(DesktopError::InvalidInput(
+                "Remote port must not be 0".to_string(),
+            ));
+        }
+        if let Some(token) = token {
+            secrets::set_remote_connection_token(Some(&token))?;
+        }
+    }
+    if let GatewayConnection::Socket { path: Some(path) } = &connection {
+        socket_transport::check_path(path).map_err(DesktopError::InvalidInput)?;
+    }
+    let connection = settings::update(|s| s.connection = connection)?.connection;
+    if !matches!(connection, GatewayConnection::Socket { .. }) {
+        socket_transport::stop_relay();
+    }
+    // A local gateway left behind is no longer ours to restart
+    if matches!(connection, GatewayConnection::Remote { .. }) {
+        supervisor::set_desired_running(false);
+    }
+    Ok(connection)
+}
+
+#[tauri::command]
+pub fn get_gateway_connection() -> GatewayConnection {
+    current()
+}
+
+/// Configure HTTPS for the gateway; a CA certificate must parse before it is saved
+#[tauri::command]
+pub fn set_gateway_tls(tls: GatewayTls) -> Result<GatewayTls, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if let Some(path) = &tls.ca_cert_path {
+        load_ca_cert(path)?;
+    }
+    Ok(settings::update(|s| s.gateway_tls = tls)?.gateway_tls)
+}
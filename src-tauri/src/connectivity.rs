@@ -0,0 +1,172 @@
+//! Whether the gateway, the internet and the npm registry can be reached, so
+//! network-bound commands fail with the actual cause.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::time::MissedTickBehavior;
+use ts_rs::TS;
+
+use crate::app_state;
+use crate::error::DesktopError;
+use crate::events::CONNECTIVITY_CHANGED_EVENT;
+use crate::{is_gateway_running, proxy};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+const REGISTRY_URL: &str = "https://registry.npmjs.org/";
+/// Any answer from one of these means the machine is online
+pub(crate) const INTERNET_URLS: &[&str] = &[
+    "https://connectivitycheck.gstatic.com/generate_204",
+    "https://www.cloudflare.com/cdn-cgi/trace",
+];
+
+static LAST_STATE: Mutex<Option<ConnectivityState>> = Mutex::new(None);
+
+/// The most pressing connectivity problem, for a banner
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum ConnectivityStatus {
+    Online,
+    NoInternet,
+    GatewayDown,
+    RegistryUnreachable,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct ConnectivityState {
+    pub status: ConnectivityStatus,
+    pub gateway: bool,
+    pub internet: bool,
+    pub npm_registry: bool,
+    /// Unix milliseconds
+    #[ts(type = "number")]
+    pub checked_at: u64,
+}
+
+impl ConnectivityState {
+    fn same_as(&self, other: &Self) -> bool {
+        (self.gateway, self.internet, self.npm_registry)
+            == (other.gateway, other.internet, other.npm_registry)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Whether `url` answers at all; an HTTP error status still proves the route works
+async fn reachable(client: &reqwest::Client, url: &str) -> bool {
+    client.head(url).send().await.is_ok()
+}
+
+/// `(internet, npm registry)`, through the policy proxy when one is set
+async fn check_network() -> Result<(bool, bool), String> {
+    let client = proxy::apply(reqwest::Client::builder().timeout(CHECK_TIMEOUT))?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let internet = async {
+        for url in INTERNET_URLS {
+            if reachable(&client, url).await {
+                return true;
+            }
+        }
+        false
+    };
+    let (registry, internet) = tokio::join!(reachable(&client, REGISTRY_URL), internet);
+    Ok((internet || registry, registry))
+}
+
+async fn check() -> Result<ConnectivityState, String> {
+    let gateway = tauri::async_runtime::spawn_blocking(is_gateway_running);
+    let (internet, npm_registry) = check_network().await?;
+    let gateway = gateway.await.unwrap_or(false);
+    let status = if !internet {
+        ConnectivityStatus::NoInternet
+    } else if !gateway {
+        ConnectivityStatus::GatewayDown
+    } else if !npm_registry {
+        ConnectivityStatus::RegistryUnreachable
+    } else {
+        ConnectivityStatus::Online
+    };
+    Ok(ConnectivityState {
+        status,
+        gateway,
+        internet,
+        npm_registry,
+        checked_at: now_ms(),
+    })
+}
+
+/// Store `state`, returning whether anything other than the timestamp changed
+fn record(state: &ConnectivityState) -> bool {
+    let Ok(mut last) = LAST_STATE.lock() else {
+        return false;
+    };
+    let changed = !last.as_ref().is_some_and(|last| last.same_as(state));
+    *last = Some(state.clone());
+    changed
+}
+
+/// Result of the last check, if one has finished
+pub(crate) fn cached() -> Option<ConnectivityState> {
+    LAST_STATE.lock().ok().and_then(|state| state.clone())
+}
+
+/// Fail with `Offline` or `RegistryUnreachable` unless the npm registry answers now
+pub(crate) async fn ensure_registry() -> Result<(), DesktopError> {
+    match check_network().await? {
+        (_, true) => Ok(()),
+        (false, false) => Err(DesktopError::Offline("No internet connection".to_string())),
+        (true, false) => Err(DesktopError::RegistryUnreachable(
+            "The npm registry (registry.npmjs.org) can't be reached".to_string(),
+        )),
+    }
+}
+
+/// Re-check on an interval, emitting `connectivity-changed` when anything flips
+pub fn spawn_connectivity_monitor<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let state = match check().await {
+                Ok(state) => state,
+                Err(e) => {
+                    log_error!("Connectivity check failed: {}", e);
+                    continue;
+                }
+            };
+            if record(&state) {
+                app_state::set_connectivity(&app, state.clone());
+                if let Err(e) = app.emit(CONNECTIVITY_CHANGED_EVENT, state) {
+                    log_error!("Failed to emit connectivity change: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Gateway, internet and npm registry reachability. Returns the last background
+/// check unless `refresh` is set or none has finished yet.
+#[tauri::command]
+pub async fn get_connectivity_state(
+    refresh: Option<bool>,
+) -> Result<ConnectivityState, DesktopError> {
+    if !refresh.unwrap_or(false) {
+        if let Some(state) = cached() {
+            return Ok(state);
+        }
+    }
+    let state = check().await?;
+    record(&state);
+    Ok(state)
+}
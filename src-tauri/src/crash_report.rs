@@ -0,0 +1,195 @@
+//! Backend panics and command failures, appended as JSON lines to
+//! `~/.openclaw/desktop-crash.log` so they survive the process that hit them.
+
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::error::DesktopError;
+use crate::{logs, openclaw_home_dir};
+
+const CRASH_LOG: &str = "desktop-crash.log";
+/// Left in `run/` by a panic and cleared once the next launch has reported it
+const CRASH_MARKER: &str = "crash-pending";
+/// The log is rotated to `.1` past this size
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+const DEFAULT_LIMIT: usize = 50;
+
+static APP_VERSION: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackendErrorKind {
+    Panic,
+    Command,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackendError {
+    /// Unix seconds
+    pub at: u64,
+    pub kind: BackendErrorKind,
+    /// Thread name for panics, command name for command errors
+    pub source: String,
+    pub message: String,
+    /// `file:line:column` of the panic
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub backtrace: Option<String>,
+    pub app_version: String,
+}
+
+fn crash_log_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(CRASH_LOG))
+}
+
+fn marker_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join("run").join(CRASH_MARKER))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn append(entry: &BackendError) -> Result<(), String> {
+    let path = crash_log_path()?;
+    if fs::metadata(&path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        let _ = fs::rename(&path, path.with_extension("log.1"));
+    }
+    let line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open crash log: {}", e))?;
+    writeln!(file, "{}", line).map_err(|e| format!("Failed to write crash log: {}", e))
+}
+
+fn entry(kind: BackendErrorKind, source: String, message: String) -> BackendError {
+    BackendError {
+        at: now_secs(),
+        kind,
+        source,
+        message,
+        location: None,
+        backtrace: None,
+        app_version: APP_VERSION.get().cloned().unwrap_or_default(),
+    }
+}
+
+/// Log every panic, on any thread, before the default hook prints it
+pub fn install_panic_hook(app_version: &str) {
+    let _ = APP_VERSION.set(app_version.to_string());
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let thread = std::thread::current();
+        let mut report = entry(
+            BackendErrorKind::Panic,
+            thread.name().unwrap_or("unnamed").to_string(),
+            message,
+        );
+        report.location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        report.backtrace = Some(Backtrace::force_capture().to_string());
+
+        if let Err(e) = append(&report) {
+            log_error!("{}", e);
+        }
+        if let Ok(marker) = marker_path() {
+            let _ = marker.parent().map(fs::create_dir_all);
+            let _ = fs::write(marker, report.at.to_string());
+        }
+        previous(info);
+    }));
+}
+
+/// Log a command that returned an error to the frontend
+pub(crate) fn record_command_error(command: &str, message: &str) {
+    let report = entry(
+        BackendErrorKind::Command,
+        command.to_string(),
+        message.to_string(),
+    );
+    if let Err(e) = append(&report) {
+        log_error!("{}", e);
+    }
+}
+
+fn recent(limit: usize) -> Result<Vec<BackendError>, String> {
+    let path = crash_log_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut errors: Vec<BackendError> = logs::tail_lines(&path, limit)?
+        .iter()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    errors.reverse();
+    Ok(errors)
+}
+
+/// After a launch that panicked, say so once and offer the crash log
+pub fn check_previous_crash<R: Runtime>(app: &AppHandle<R>) {
+    let Ok(marker) = marker_path() else {
+        return;
+    };
+    if !marker.exists() {
+        return;
+    }
+    if let Err(e) = fs::remove_file(&marker) {
+        log_error!("Failed to clear crash marker: {}", e);
+    }
+
+    let last_panic = recent(DEFAULT_LIMIT)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|e| e.kind == BackendErrorKind::Panic);
+    let detail = match &last_panic {
+        Some(panic) => format!("\n\n{}", panic.message),
+        None => String::new(),
+    };
+    let Ok(log_path) = crash_log_path() else {
+        return;
+    };
+    app.dialog()
+        .message(format!(
+            "OpenClaw Desktop ran into an internal error last time and some features may have stopped working.{}",
+            detail
+        ))
+        .title("OpenClaw Desktop recovered from an error")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Show Crash Log".to_string(),
+            "Dismiss".to_string(),
+        ))
+        .show(move |show| {
+            if show {
+                if let Err(e) = opener::reveal(&log_path) {
+                    log_error!("Failed to show crash log: {}", e);
+                }
+            }
+        });
+}
+
+/// Latest panics and command errors, newest first
+#[tauri::command]
+pub fn get_recent_backend_errors(limit: Option<usize>) -> Result<Vec<BackendError>, DesktopError> {
+    Ok(recent(limit.unwrap_or(DEFAULT_LIMIT))?)
+}
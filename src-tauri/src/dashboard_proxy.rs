@@ -0,0 +1,559 @@
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use crate::error::DesktopError;
+use crate::tokens::{constant_time_eq, generate_token};
+use crate::{connection, kiosk, read_gateway_token};
+
+/// Request bodies are buffered before forwarding; uploads beyond this are refused
+const BODY_LIMIT: usize = 64 * 1024 * 1024;
+const MAX_UPGRADE_HEAD: usize = 16 * 1024;
+
+/// Path the webview opens first; it trades the launch secret for a session cookie
+const LOGIN_PATH: &str = "/__openclaw-proxy/login/";
+const SESSION_COOKIE: &str = "openclaw_proxy_session";
+
+/// Origins of the app's own webview, besides the proxy itself
+const WEBVIEW_ORIGINS: &[&str] = &[
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+];
+
+/// Connection-scoped headers that must not be forwarded as-is
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+static PROXY: Mutex<Option<RunningProxy>> = Mutex::new(None);
+
+struct RunningProxy {
+    port: u16,
+    secret: String,
+    shutdown: oneshot::Sender<()>,
+}
+
+#[derive(Clone)]
+struct ProxyState {
+    client: reqwest::Client,
+    port: u16,
+    /// Generated on every start; only a client that presents it gets the token added
+    secret: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DashboardProxyStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// Gateway the proxy forwards to: its URL, or its socket or pipe
+    pub target: String,
+}
+
+fn status() -> DashboardProxyStatus {
+    let port = running_port();
+    DashboardProxyStatus {
+        running: port.is_some(),
+        port,
+        // Socket traffic passes through the relay, whose port means nothing to the user
+        target: connection::socket_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(connection::base_url),
+    }
+}
+
+pub fn running_port() -> Option<u16> {
+    PROXY
+        .lock()
+        .ok()
+        .and_then(|proxy| proxy.as_ref().map(|p| p.port))
+}
+
+/// URL the webview should load instead of the tokenized dashboard URL.
+///
+/// A locked kiosk never goes through the proxy, since the proxy would hand it
+/// full access instead of the viewer mode.
+pub fn dashboard_url() -> Option<String> {
+    if kiosk::is_locked() {
+        return None;
+    }
+    let proxy = PROXY.lock().ok()?;
+    let proxy = proxy.as_ref()?;
+    Some(format!(
+        "http://127.0.0.1:{}{}{}",
+        proxy.port, LOGIN_PATH, proxy.secret
+    ))
+}
+
+fn forwardable(name: &HeaderName) -> bool {
+    !HOP_BY_HOP.contains(&name.as_str())
+        && name != header::HOST
+        && name != header::AUTHORIZATION
+        && name != header::COOKIE
+}
+
+/// The request was addressed to the proxy by its loopback name, not a rebound domain
+fn host_allowed(headers: &HeaderMap, port: u16) -> bool {
+    let Some(host) = headers.get(header::HOST).and_then(|h| h.to_str().ok()) else {
+        return false;
+    };
+    host == format!("127.0.0.1:{}", port) || host == format!("localhost:{}", port)
+}
+
+/// No `Origin`, the proxy's own, or the app's webview; web pages elsewhere are refused
+fn origin_allowed(headers: &HeaderMap, port: u16) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN) else {
+        return true;
+    };
+    let Ok(origin) = origin.to_str() else {
+        return false;
+    };
+    origin == format!("http://127.0.0.1:{}", port)
+        || origin == format!("http://localhost:{}", port)
+        || WEBVIEW_ORIGINS.contains(&origin)
+}
+
+/// Cookies sent by the client, minus the proxy's own session cookie
+fn upstream_cookies(incoming: &HeaderMap) -> Option<String> {
+    let cookies: Vec<&str> = incoming
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .map(str::trim)
+        .filter(|cookie| !cookie.is_empty() && cookie.split('=').next() != Some(SESSION_COOKIE))
+        .collect();
+    (!cookies.is_empty()).then(|| cookies.join("; "))
+}
+
+fn has_session(incoming: &HeaderMap, secret: &str) -> bool {
+    incoming
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .any(|(name, value)| name == SESSION_COOKIE && constant_time_eq(value, secret))
+}
+
+/// Set the session cookie and send the webview on to the dashboard, keeping the query
+fn login(request: &Request, secret: &str) -> Response {
+    let presented = request
+        .uri()
+        .path()
+        .strip_prefix(LOGIN_PATH)
+        .unwrap_or_default();
+    if !constant_time_eq(presented, secret) {
+        return (StatusCode::FORBIDDEN, "Invalid dashboard proxy login").into_response();
+    }
+    let location = match request.uri().query() {
+        Some(query) => format!("/?{}", query),
+        None => "/".to_string(),
+    };
+    let cookie = format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Lax",
+        SESSION_COOKIE, secret
+    );
+    Response::builder()
+        .status(StatusCode::SEE_OTHER)
+        .header(header::LOCATION, location)
+        .header(header::SET_COOKIE, cookie)
+        .body(Body::empty())
+        .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+}
+
+/// Copy client headers, dropping the proxy's session cookie and adding the gateway token
+fn upstream_headers(incoming: &HeaderMap) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    for (name, value) in incoming {
+        if forwardable(name) {
+            headers.append(name.clone(), value.clone());
+        }
+    }
+    if let Some(cookies) = upstream_cookies(incoming) {
+        if let Ok(value) = HeaderValue::from_str(&cookies) {
+            headers.insert(header::COOKIE, value);
+        }
+    }
+    if let Some(token) = read_gateway_token() {
+        if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", token)) {
+            headers.insert(header::AUTHORIZATION, value);
+        }
+    }
+    headers
+}
+
+fn is_upgrade(request: &Request) -> bool {
+    request.headers().contains_key(header::UPGRADE)
+}
+
+async fn forward(State(state): State<ProxyState>, request: Request) -> Response {
+    if !host_allowed(request.headers(), state.port) {
+        return (StatusCode::MISDIRECTED_REQUEST, "Unexpected Host").into_response();
+    }
+    if !origin_allowed(request.headers(), state.port) {
+        return (StatusCode::FORBIDDEN, "Origin not allowed").into_response();
+    }
+    if request.uri().path().starts_with(LOGIN_PATH) {
+        return login(&request, &state.secret);
+    }
+    if !has_session(request.headers(), &state.secret) {
+        return (StatusCode::FORBIDDEN, "Dashboard proxy session required").into_response();
+    }
+    let result = if is_upgrade(&request) {
+        tunnel(request).await
+    } else {
+        proxy(state.client, request).await
+    };
+    result.unwrap_or_else(|e| (StatusCode::BAD_GATEWAY, e).into_response())
+}
+
+async fn proxy(client: reqwest::Client, request: Request) -> Result<Response, String> {
+    let base = connection::base_url();
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "/".to_string());
+    let method = request.method().clone();
+    let headers = upstream_headers(request.headers());
+    let body = to_bytes(request.into_body(), BODY_LIMIT)
+        .await
+        .map_err(|e| format!("Failed to read request body: {}", e))?;
+
+    let upstream = client
+        .request(method, format!("{}{}", base, path))
+        .headers(headers)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach gateway: {}", e))?;
+
+    let mut response = Response::builder().status(upstream.status());
+    for (name, value) in upstream.headers() {
+        if HOP_BY_HOP.contains(&name.as_str()) {
+            continue;
+        }
+        // Keep redirects on the proxy instead of sending the webview to the gateway
+        if name == header::LOCATION {
+            if let Some(relative) = value.to_str().ok().and_then(|v| v.strip_prefix(&base)) {
+                if let Ok(value) = HeaderValue::from_str(relative) {
+                    response = response.header(name, value);
+                    continue;
+                }
+            }
+        }
+        response = response.header(name, value);
+    }
+    response
+        .body(Body::from_stream(upstream.bytes_stream()))
+        .map_err(|e| format!("Failed to build response: {}", e))
+}
+
+/// Relay a WebSocket (or other upgrade) by replaying the handshake with the token
+/// added, then splicing the two connections together
+async fn tunnel(mut request: Request) -> Result<Response, String> {
+    let base = connection::base_url();
+    let url: reqwest::Url = base
+        .parse()
+        .map_err(|e| format!("Invalid gateway URL {}: {}", base, e))?;
+    if url.scheme() != "http" {
+        return Err("Upgrades can only be proxied to a plain HTTP gateway".to_string());
+    }
+    let host = url.host_str().unwrap_or("127.0.0.1").to_string();
+    let port = url.port_or_known_default().unwrap_or(80);
+
+    let mut upstream = TcpStream::connect((host.as_str(), port))
+        .await
+        .map_err(|e| format!("Failed to reach gateway: {}", e))?;
+
+    let path = request
+        .uri()
+        .path_and_query()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "/".to_string());
+    let mut head = format!(
+        "{} {} HTTP/1.1\r\nHost: {}:{}\r\n",
+        request.method(),
+        path,
+        host,
+        port
+    );
+    for (name, value) in upstream_headers(request.headers()).iter() {
+        head.push_str(&format!(
+            "{}: {}\r\n",
+            name,
+            value.to_str().unwrap_or_default()
+        ));
+    }
+    for name in [header::CONNECTION, header::UPGRADE] {
+        if let Some(value) = request.headers().get(&name) {
+            head.push_str(&format!(
+                "{}: {}\r\n",
+                name,
+                value.to_str().unwrap_or_default()
+            ));
+        }
+    }
+    head.push_str("\r\n");
+    upstream
+        .write_all(head.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to forward upgrade: {}", e))?;
+
+    // Read the gateway's response head; anything after it belongs to the upgraded stream
+    let mut buffer = Vec::new();
+    let head_end = loop {
+        let mut chunk = [0u8; 1024];
+        let read = upstream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("Failed to read upgrade response: {}", e))?;
+        if read == 0 {
+            return Err("Gateway closed the connection during the upgrade".to_string());
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if buffer.len() > MAX_UPGRADE_HEAD {
+            return Err("Upgrade response head is too large".to_string());
+        }
+    };
+    let leftover = buffer.split_off(head_end);
+    let text = String::from_utf8_lossy(&buffer).to_string();
+    let mut lines = text.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .ok_or("Gateway sent an invalid upgrade response")?;
+
+    let mut response = Response::builder().status(status);
+    for line in lines.filter(|line| !line.is_empty()) {
+        if let Some((name, value)) = line.split_once(':') {
+            response = response.header(name.trim(), value.trim());
+        }
+    }
+    if status != StatusCode::SWITCHING_PROTOCOLS {
+        return response
+            .body(Body::from(leftover))
+            .map_err(|e| format!("Failed to build response: {}", e));
+    }
+
+    let on_upgrade = hyper::upgrade::on(&mut request);
+    tauri::async_runtime::spawn(async move {
+        let upgraded = match on_upgrade.await {
+            Ok(upgraded) => upgraded,
+            Err(e) => {
+                log_error!("Dashboard proxy upgrade failed: {}", e);
+                return;
+            }
+        };
+        let mut client = TokioIo::new(upgraded);
+        if !leftover.is_empty() && client.write_all(&leftover).await.is_err() {
+            return;
+        }
+        let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+    });
+    response
+        .body(Body::empty())
+        .map_err(|e| format!("Failed to build response: {}", e))
+}
+
+/// Start the proxy on a free loopback port, or report the one already running
+#[tauri::command]
+pub async fn start_dashboard_proxy() -> Result<DashboardProxyStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if running_port().is_some() {
+        return Ok(status());
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .await
+        .map_err(|e| format!("Failed to start dashboard proxy: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to start dashboard proxy: {}", e))?
+        .port();
+    let client = connection::apply_tls(
+        reqwest::Client::builder().redirect(reqwest::redirect::Policy::none()),
+    )?
+    .build()
+    .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let secret = generate_token();
+    let router = Router::new().fallback(forward).with_state(ProxyState {
+        client,
+        port,
+        secret: secret.clone(),
+    });
+
+    let (shutdown, stopped) = oneshot::channel::<()>();
+    tauri::async_runtime::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = stopped.await;
+        });
+        if let Err(e) = server.await {
+            log_error!("Dashboard proxy stopped: {}", e);
+        }
+    });
+
+    if let Ok(mut proxy) = PROXY.lock() {
+        *proxy = Some(RunningProxy {
+            port,
+            secret,
+            shutdown,
+        });
+    }
+    Ok(status())
+}
+
+/// Login URL of the proxy, starting it first if needed
+pub(crate) async fn login_url() -> Result<String, DesktopError> {
+    start_dashboard_proxy().await?;
+    dashboard_url().ok_or_else(|| "Dashboard proxy is not running".into())
+}
+
+#[tauri::command]
+pub fn stop_dashboard_proxy() -> Result<DashboardProxyStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if let Some(proxy) = PROXY.lock().ok().and_then(|mut proxy| proxy.take()) {
+        let _ = proxy.shutdown.send(());
+    }
+    Ok(status())
+}
+
+#[tauri::command]
+pub fn get_dashboard_proxy_status() -> DashboardProxyStatus {
+    status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(HeaderName, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.append(name.clone(), HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn host_must_name_the_proxy_on_loopback() {
+        assert!(host_allowed(
+            &headers(&[(header::HOST, "127.0.0.1:4100")]),
+            4100
+        ));
+        assert!(host_allowed(
+            &headers(&[(header::HOST, "localhost:4100")]),
+            4100
+        ));
+        assert!(!host_allowed(
+            &headers(&[(header::HOST, "127.0.0.1:4101")]),
+            4100
+        ));
+        assert!(!host_allowed(
+            &headers(&[(header::HOST, "127.0.0.1")]),
+            4100
+        ));
+        assert!(!host_allowed(
+            &headers(&[(header::HOST, "evil.example:4100")]),
+            4100
+        ));
+        assert!(!host_allowed(
+            &headers(&[(header::HOST, "localhost.evil.example:4100")]),
+            4100
+        ));
+        assert!(!host_allowed(&headers(&[]), 4100));
+    }
+
+    #[test]
+    fn origin_must_be_the_proxy_or_the_webview() {
+        assert!(origin_allowed(&headers(&[]), 4100));
+        assert!(origin_allowed(
+            &headers(&[(header::ORIGIN, "http://127.0.0.1:4100")]),
+            4100
+        ));
+        assert!(origin_allowed(
+            &headers(&[(header::ORIGIN, "http://localhost:4100")]),
+            4100
+        ));
+        for origin in WEBVIEW_ORIGINS {
+            assert!(origin_allowed(&headers(&[(header::ORIGIN, origin)]), 4100));
+        }
+        assert!(!origin_allowed(
+            &headers(&[(header::ORIGIN, "http://127.0.0.1:4101")]),
+            4100
+        ));
+        assert!(!origin_allowed(
+            &headers(&[(header::ORIGIN, "https://127.0.0.1:4100")]),
+            4100
+        ));
+        assert!(!origin_allowed(
+            &headers(&[(header::ORIGIN, "https://evil.example")]),
+            4100
+        ));
+        assert!(!origin_allowed(&headers(&[(header::ORIGIN, "null")]), 4100));
+    }
+
+    #[test]
+    fn session_needs_the_exact_secret() {
+        let cookie = |value: &str| headers(&[(header::COOKIE, value)]);
+        assert!(has_session(
+            &cookie("openclaw_proxy_session=s3cret"),
+            "s3cret"
+        ));
+        assert!(has_session(
+            &cookie("theme=dark; openclaw_proxy_session=s3cret"),
+            "s3cret"
+        ));
+        assert!(!has_session(
+            &cookie("openclaw_proxy_session=s3cre"),
+            "s3cret"
+        ));
+        assert!(!has_session(
+            &cookie("openclaw_proxy_session=s3cretx"),
+            "s3cret"
+        ));
+        assert!(!has_session(&cookie("other_session=s3cret"), "s3cret"));
+        assert!(!has_session(&headers(&[]), "s3cret"));
+    }
+
+    #[test]
+    fn session_cookie_and_credentials_stay_with_the_proxy() {
+        let incoming = headers(&[
+            (header::COOKIE, "theme=dark; openclaw_proxy_session=s3cret"),
+            (header::COOKIE, "lang=en"),
+        ]);
+        assert_eq!(
+            upstream_cookies(&incoming).as_deref(),
+            Some("theme=dark; lang=en")
+        );
+        let only_session = headers(&[(header::COOKIE, "openclaw_proxy_session=s3cret")]);
+        assert_eq!(upstream_cookies(&only_session), None);
+
+        assert!(forwardable(&header::ACCEPT));
+        assert!(!forwardable(&header::HOST));
+        assert!(!forwardable(&header::AUTHORIZATION));
+        assert!(!forwardable(&header::COOKIE));
+        assert!(!forwardable(&header::CONNECTION));
+    }
+}
@@ -0,0 +1,133 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Runtime, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::auto_lock;
+use crate::events::{DeepLinkTarget, DEEP_LINK_EVENT};
+use crate::window::{self, show_main_window};
+
+pub const SCHEME: &str = "openclaw";
+
+/// Link that arrived before the dashboard was showing, opened by the next navigation
+static PENDING: Mutex<Option<DeepLinkTarget>> = Mutex::new(None);
+
+fn decode(segment: &str) -> Option<String> {
+    urlencoding::decode(segment)
+        .ok()
+        .map(|s| s.into_owned())
+        .filter(|s| !s.is_empty())
+}
+
+/// `openclaw://session/<id>`, `openclaw://agent/<name>` or `openclaw://agent/<name>/chat`
+pub fn parse(url: &Url) -> Option<DeepLinkTarget> {
+    if url.scheme() != SCHEME {
+        return None;
+    }
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|s| s.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+    match (url.host_str()?, segments.as_slice()) {
+        ("session", [id]) => decode(id).map(DeepLinkTarget::Session),
+        ("agent", [name]) => decode(name).map(DeepLinkTarget::Agent),
+        ("agent", [name, "chat"]) => decode(name).map(DeepLinkTarget::AgentChat),
+        _ => None,
+    }
+}
+
+/// Dashboard route for a target, relative to the dashboard root
+fn dashboard_path(target: &DeepLinkTarget) -> String {
+    match target {
+        DeepLinkTarget::Session(id) => format!("sessions/{}", urlencoding::encode(id)),
+        DeepLinkTarget::Agent(name) => format!("agents/{}", urlencoding::encode(name)),
+        DeepLinkTarget::AgentChat(name) => format!("agents/{}/chat", urlencoding::encode(name)),
+    }
+}
+
+/// The target's route under a dashboard URL, keeping its login code or token query
+fn target_url(base: &str, target: &DeepLinkTarget) -> Result<Url, String> {
+    let base = Url::parse(base).map_err(|e| format!("Invalid dashboard URL: {}", e))?;
+    let mut url = base
+        .join(&dashboard_path(target))
+        .map_err(|e| format!("Invalid deep link target: {}", e))?;
+    url.set_query(base.query());
+    Ok(url)
+}
+
+/// Dashboard URL for the pending link, if one is waiting
+pub fn take_pending_url(base: &str) -> Option<Url> {
+    let target = PENDING.lock().ok()?.take()?;
+    target_url(base, &target).ok()
+}
+
+/// Focus the app and take the dashboard to the linked view.
+///
+/// A window already showing the dashboard is navigated directly; otherwise the
+/// link is kept for the next dashboard navigation and the app UI gets a
+/// `deep-link` event.
+pub async fn handle_deep_link<R: Runtime>(app: &AppHandle<R>, url: &Url) {
+    let Some(target) = parse(url) else {
+        log_error!("Ignoring unsupported deep link: {}", url);
+        return;
+    };
+    show_main_window(app);
+
+    let on_dashboard = window::main_window(app).is_ok_and(|w| {
+        let app_url = window::app_url();
+        w.url().is_ok_and(|current| {
+            app_url.is_some_and(|app_url| {
+                current.scheme() != app_url.scheme() || current.host_str() != app_url.host_str()
+            })
+        })
+    });
+    if on_dashboard && !auto_lock::is_locked() {
+        let result = crate::dashboard_webview_url()
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|base| target_url(&base, &target))
+            .and_then(|url| {
+                window::main_window(app)
+                    .and_then(|main| main.navigate(url))
+                    .map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            log_error!("Failed to open deep link: {}", e);
+        }
+        return;
+    }
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = Some(target.clone());
+    }
+    if let Err(e) = app.emit(DEEP_LINK_EVENT, target) {
+        log_error!("Failed to emit deep link event: {}", e);
+    }
+}
+
+fn dispatch<R: Runtime>(app: &AppHandle<R>, urls: Vec<Url>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        for url in urls {
+            handle_deep_link(&app, &url).await;
+        }
+    });
+}
+
+/// Route links that open the app, including the one it was launched with.
+///
+/// Later links from a second launch arrive through the single-instance plugin.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    // Linux (and Windows dev builds) register the scheme at runtime
+    #[cfg(any(target_os = "linux", all(debug_assertions, target_os = "windows")))]
+    app.deep_link()
+        .register_all()
+        .map_err(|e| format!("Failed to register {}:// links: {}", SCHEME, e))?;
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        dispatch(&handle, event.urls());
+    });
+    if let Ok(Some(urls)) = app.deep_link().get_current() {
+        dispatch(app, urls);
+    }
+    Ok(())
+}
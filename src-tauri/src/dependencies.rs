@@ -0,0 +1,286 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tauri::State;
+
+use crate::error::DesktopError;
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+use crate::{config, connection, kiosk, process};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(800);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyKind {
+    Database,
+    VectorStore,
+    BrowserDriver,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DependencyHealth {
+    Healthy,
+    Unhealthy,
+    Unreachable,
+    #[serde(other)]
+    Unknown,
+}
+
+/// A sidecar service the gateway relies on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayDependency {
+    pub name: String,
+    pub kind: DependencyKind,
+    /// Where the sidecar listens, as configured
+    pub endpoint: Option<String>,
+    pub health: DependencyHealth,
+    pub detail: Option<String>,
+    pub latency_ms: Option<u64>,
+    /// The CLI can start and stop this sidecar
+    pub controllable: bool,
+}
+
+/// A dependency as the gateway reports it on `/api/dependencies`
+#[derive(Deserialize)]
+struct ReportedDependency {
+    name: String,
+    #[serde(default = "other_kind")]
+    kind: DependencyKind,
+    #[serde(default, alias = "url")]
+    endpoint: Option<String>,
+    #[serde(default = "unknown_health", alias = "status")]
+    health: DependencyHealth,
+    #[serde(default, alias = "message")]
+    detail: Option<String>,
+    #[serde(default, alias = "latencyMs")]
+    latency_ms: Option<u64>,
+    #[serde(default)]
+    managed: bool,
+}
+
+fn other_kind() -> DependencyKind {
+    DependencyKind::Other
+}
+
+fn unknown_health() -> DependencyHealth {
+    DependencyHealth::Unknown
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DependenciesResponse {
+    List(Vec<ReportedDependency>),
+    Wrapped {
+        dependencies: Vec<ReportedDependency>,
+    },
+}
+
+/// Whether the installed CLI has `openclaw sidecar start|stop`
+fn cli_supports_control() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        crate::run_openclaw_output_blocking(&["sidecar", "--help"], process::VERSION_TIMEOUT)
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+    })
+}
+
+/// Sidecars declared in `openclaw.json`: `gateway.sidecars.<name>` entries plus
+/// the browser driver configured under `tools.browser`
+fn configured(config: &Value) -> Vec<(String, DependencyKind, Option<String>, bool)> {
+    let mut found = Vec::new();
+    if let Some(sidecars) = config
+        .pointer("/gateway/sidecars")
+        .and_then(Value::as_object)
+    {
+        for (name, entry) in sidecars {
+            let kind = entry
+                .get("kind")
+                .and_then(|k| serde_json::from_value(k.clone()).ok())
+                .unwrap_or(DependencyKind::Other);
+            let endpoint = entry.get("url").and_then(Value::as_str).map(str::to_string);
+            let managed = entry
+                .get("managed")
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            found.push((name.clone(), kind, endpoint, managed));
+        }
+    }
+    let browser = config
+        .pointer("/tools/browser/cdpUrl")
+        .and_then(Value::as_str);
+    if let Some(url) = browser {
+        if !found
+            .iter()
+            .any(|(_, kind, ..)| *kind == DependencyKind::BrowserDriver)
+        {
+            found.push((
+                "browser".to_string(),
+                DependencyKind::BrowserDriver,
+                Some(url.to_string()),
+                false,
+            ));
+        }
+    }
+    found
+}
+
+/// Check that something accepts connections at the endpoint
+fn check_endpoint(endpoint: &str) -> (DependencyHealth, Option<String>, Option<u64>) {
+    let Ok(url) = reqwest::Url::parse(endpoint) else {
+        return (
+            DependencyHealth::Unknown,
+            Some(format!("Can't parse endpoint '{}'", endpoint)),
+            None,
+        );
+    };
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return (
+            DependencyHealth::Unknown,
+            Some("Endpoint has no host and port to check".to_string()),
+            None,
+        );
+    };
+    let addrs = match (host, port).to_socket_addrs() {
+        Ok(addrs) => addrs.collect::<Vec<_>>(),
+        Err(e) => {
+            return (
+                DependencyHealth::Unreachable,
+                Some(format!("Failed to resolve {}: {}", host, e)),
+                None,
+            )
+        }
+    };
+    let started = Instant::now();
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(_) => {
+                return (
+                    DependencyHealth::Healthy,
+                    None,
+                    Some(started.elapsed().as_millis() as u64),
+                )
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    let detail = last_error
+        .map(|e| format!("Failed to connect to {}:{}: {}", host, port, e))
+        .unwrap_or_else(|| format!("{} did not resolve to any address", host));
+    (DependencyHealth::Unreachable, Some(detail), None)
+}
+
+/// Sidecars from the local config, each health-checked with a TCP connect.
+///
+/// A remote gateway's sidecars live on its host, so only the gateway can report them.
+pub fn check_configured() -> Vec<GatewayDependency> {
+    if connection::is_remote() {
+        return Vec::new();
+    }
+    let Ok(config) = config::load_resolved_config() else {
+        return Vec::new();
+    };
+    let entries = configured(&config);
+    let control = !entries.is_empty() && cli_supports_control();
+    entries
+        .into_iter()
+        .map(|(name, kind, endpoint, managed)| {
+            let (health, detail, latency_ms) = match &endpoint {
+                Some(endpoint) => check_endpoint(endpoint),
+                None => (
+                    DependencyHealth::Unknown,
+                    Some("No endpoint configured".to_string()),
+                    None,
+                ),
+            };
+            GatewayDependency {
+                name,
+                kind,
+                endpoint,
+                health,
+                detail,
+                latency_ms,
+                controllable: control && managed,
+            }
+        })
+        .collect()
+}
+
+/// Dependencies as the gateway sees them, falling back to the config when it
+/// predates the endpoint or isn't running
+#[tauri::command]
+pub async fn get_gateway_dependencies(
+    api: State<'_, GatewayApi>,
+) -> Result<Vec<GatewayDependency>, DesktopError> {
+    match api.get::<DependenciesResponse>("/api/dependencies").await {
+        Ok(DependenciesResponse::List(reported))
+        | Ok(DependenciesResponse::Wrapped {
+            dependencies: reported,
+        }) => {
+            let control = !connection::is_remote()
+                && reported.iter().any(|d| d.managed)
+                && tauri::async_runtime::spawn_blocking(cli_supports_control)
+                    .await
+                    .unwrap_or(false);
+            Ok(reported
+                .into_iter()
+                .map(|d| GatewayDependency {
+                    controllable: control && d.managed,
+                    name: d.name,
+                    kind: d.kind,
+                    endpoint: d.endpoint,
+                    health: d.health,
+                    detail: d.detail,
+                    latency_ms: d.latency_ms,
+                })
+                .collect())
+        }
+        Err(e) if e.kind == GatewayApiErrorKind::Unauthorized => Err(e.into()),
+        Err(_) => Ok(tauri::async_runtime::spawn_blocking(check_configured)
+            .await
+            .map_err(|e| format!("Failed to check dependencies: {}", e))?),
+    }
+}
+
+async fn control_dependency(action: &str, name: &str) -> Result<String, String> {
+    kiosk::ensure_unlocked()?;
+    connection::ensure_local()?;
+    let supported = tauri::async_runtime::spawn_blocking(cli_supports_control)
+        .await
+        .unwrap_or(false);
+    if !supported {
+        return Err("The installed OpenClaw CLI can't manage sidecars".to_string());
+    }
+    let output = crate::run_openclaw_output(&["sidecar", action, name]).await?;
+    if output.status.success() {
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if stdout.is_empty() {
+            Ok(format!("Sidecar {} {} command sent", name, action))
+        } else {
+            Ok(stdout)
+        }
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        if stderr.is_empty() {
+            Err(format!("openclaw sidecar {} {} failed", action, name))
+        } else {
+            Err(stderr)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn start_gateway_dependency(name: String) -> Result<String, DesktopError> {
+    Ok(control_dependency("start", &name).await?)
+}
+
+#[tauri::command]
+pub async fn stop_gateway_dependency(name: String) -> Result<String, DesktopError> {
+    Ok(control_dependency("stop", &name).await?)
+}
@@ -0,0 +1,166 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager};
+use zip::write::SimpleFileOptions;
+use zip::ZipWriter;
+
+use crate::error::DesktopError;
+use crate::gateway_core::Core;
+use crate::{app_log, config, disk_space, doctor, gateway_log_paths, kiosk, logs, storage};
+
+/// Lines taken from the end of each gateway log
+const BUNDLE_LOG_LINES: usize = 1000;
+
+/// Config keys whose values never leave the machine, matched case-insensitively
+const SECRET_KEY_PARTS: [&str; 6] = [
+    "token",
+    "secret",
+    "password",
+    "apikey",
+    "api_key",
+    "credential",
+];
+
+#[derive(Serialize)]
+struct SystemInfo {
+    app_version: String,
+    openclaw_version: Option<String>,
+    os: &'static str,
+    os_family: &'static str,
+    arch: &'static str,
+    os_release: Option<String>,
+}
+
+/// Stands in for secret values in exported files
+pub(crate) const REDACTED: &str = "[redacted]";
+
+/// Replace secret-looking values anywhere in the config with `"[redacted]"`
+pub(crate) fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                let key = key.to_ascii_lowercase();
+                if SECRET_KEY_PARTS.iter().any(|part| key.contains(part)) && !value.is_object() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+fn os_release() -> Option<String> {
+    #[cfg(target_os = "windows")]
+    let output = {
+        use std::os::windows::process::CommandExt;
+        Command::new("cmd")
+            .args(["/c", "ver"])
+            .creation_flags(crate::CREATE_NO_WINDOW)
+            .output()
+    };
+    #[cfg(target_os = "macos")]
+    let output = Command::new("sw_vers").output();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let output = Command::new("uname").args(["-srv"]).output();
+
+    let output = output.ok()?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn log_tail(path: &Path) -> String {
+    if !path.exists() {
+        return String::new();
+    }
+    logs::tail_lines(path, BUNDLE_LOG_LINES)
+        .map(|lines| lines.join("\n"))
+        .unwrap_or_else(|e| e)
+}
+
+fn to_json(value: &impl Serialize) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| format!("Failed to serialize: {}", e))
+}
+
+/// Collect doctor output, versions, redacted config, log tails and OS info into a zip
+/// at `path` for attaching to bug reports. Returns the path written.
+#[tauri::command]
+pub async fn export_diagnostics_bundle(
+    app: AppHandle,
+    path: String,
+) -> Result<String, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let mut path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(DesktopError::InvalidInput(
+            "Bundle path must be absolute".to_string(),
+        ));
+    }
+    if path.extension().is_none() {
+        path.set_extension("zip");
+    }
+    let app_version = app.package_info().version.to_string();
+    let doctor_output = crate::run_openclaw_doctor(app.clone())
+        .await
+        .unwrap_or_else(|e| e.to_string());
+
+    let core_app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let (log_path, error_log_path) = gateway_log_paths()?;
+        let diagnostics = core_app.state::<Core>().diagnostics();
+        let checks = doctor::local_checks();
+        let storage = match storage::report() {
+            Ok(report) => to_json(&report)?,
+            Err(e) => to_json(&serde_json::json!({ "error": e }))?,
+        };
+        let mut config = config::load_config().unwrap_or(Value::Null);
+        redact(&mut config);
+        let system = SystemInfo {
+            app_version,
+            openclaw_version: diagnostics.openclaw_version.clone(),
+            os: std::env::consts::OS,
+            os_family: std::env::consts::FAMILY,
+            arch: std::env::consts::ARCH,
+            os_release: os_release(),
+        };
+
+        let files: Vec<(&str, Vec<u8>)> = vec![
+            ("system.json", to_json(&system)?),
+            ("diagnostics.json", to_json(&diagnostics)?),
+            ("doctor.txt", doctor_output.into_bytes()),
+            ("doctor-checks.json", to_json(&checks)?),
+            ("storage.json", storage),
+            ("openclaw.json", to_json(&config)?),
+            ("gateway.log", log_tail(&log_path).into_bytes()),
+            ("gateway_error.log", log_tail(&error_log_path).into_bytes()),
+            ("desktop.log", log_tail(&app_log::log_path()?).into_bytes()),
+        ];
+
+        // Compression only shrinks the files, so their raw size is a safe upper bound
+        let size = files.iter().map(|(_, content)| content.len() as u64).sum();
+        if let Some(dir) = path.parent() {
+            disk_space::preflight(dir, size, 1)?;
+        }
+
+        let file = File::create(&path).map_err(|e| format!("Failed to create bundle: {}", e))?;
+        let mut zip = ZipWriter::new(file);
+        let options = SimpleFileOptions::default();
+        for (name, content) in files {
+            zip.start_file(name, options)
+                .map_err(|e| format!("Failed to add {}: {}", name, e))?;
+            zip.write_all(&content)
+                .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+        }
+        zip.finish()
+            .map_err(|e| format!("Failed to finish bundle: {}", e))?;
+        Ok(path.display().to_string())
+    })
+    .await
+    .map_err(|e| format!("Failed to export diagnostics: {}", e))?
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use ts_rs::TS;
+
+/// Rough footprint of a global package install of openclaw and its dependencies
+pub(crate) const PACKAGE_INSTALL_BYTES: u64 = 300 * 1024 * 1024;
+pub(crate) const PACKAGE_INSTALL_INODES: u64 = 20_000;
+/// The prebuilt binary plus its partial download
+pub(crate) const BINARY_INSTALL_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Free space measured at a target location before writing to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct SpaceCheck {
+    pub path: String,
+    #[ts(type = "number")]
+    pub required_bytes: u64,
+    #[ts(type = "number")]
+    pub available_bytes: u64,
+    /// Only measured on Linux
+    #[ts(type = "number | null")]
+    pub required_inodes: Option<u64>,
+    #[ts(type = "number | null")]
+    pub available_inodes: Option<u64>,
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= GB {
+        format!("{:.1} GB", bytes as f64 / GB)
+    } else {
+        format!("{:.0} MB", bytes as f64 / MB)
+    }
+}
+
+/// The path itself or its closest parent that exists, e.g. before a first install
+pub(crate) fn nearest_existing(path: &Path) -> PathBuf {
+    path.ancestors()
+        .find(|p| p.exists())
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
+#[cfg(target_os = "linux")]
+fn available_inodes(path: &Path) -> Option<u64> {
+    let stats = rustix::fs::statvfs(path).ok()?;
+    // Filesystems without fixed inode tables (btrfs, some FUSE mounts) report 0
+    (stats.f_files > 0).then_some(stats.f_favail)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_inodes(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Fail early with the figures when `path` lacks room for an operation
+/// expected to write `bytes` across `inodes` files
+pub(crate) fn preflight(path: &Path, bytes: u64, inodes: u64) -> Result<SpaceCheck, String> {
+    let target = nearest_existing(path);
+    let available_bytes = fs2::available_space(&target)
+        .map_err(|e| format!("Failed to read free space at {}: {}", target.display(), e))?;
+    let available_inodes = available_inodes(&target);
+
+    let check = SpaceCheck {
+        path: target.display().to_string(),
+        required_bytes: bytes,
+        available_bytes,
+        required_inodes: available_inodes.map(|_| inodes),
+        available_inodes,
+    };
+    if available_bytes < bytes {
+        return Err(format!(
+            "Insufficient space at {}: need {}, have {}",
+            check.path,
+            format_bytes(bytes),
+            format_bytes(available_bytes)
+        ));
+    }
+    if let Some(free) = available_inodes.filter(|free| *free < inodes) {
+        return Err(format!(
+            "Insufficient inodes at {}: need {}, have {}",
+            check.path, inodes, free
+        ));
+    }
+    Ok(check)
+}
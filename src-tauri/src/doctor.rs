@@ -0,0 +1,465 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::net::UdpSocket;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::disk_space::{format_bytes, nearest_existing};
+use crate::error::DesktopError;
+use crate::{config, kiosk, openclaw_home_dir, process};
+
+const NTP_SERVER: &str = "pool.ntp.org:123";
+const NTP_TIMEOUT: Duration = Duration::from_secs(3);
+/// Seconds between the NTP epoch (1900) and the Unix epoch
+const NTP_UNIX_OFFSET: u64 = 2_208_988_800;
+
+const SKEW_WARN_SECS: f64 = 30.0;
+/// Past this, signed tokens and TLS handshakes start failing
+const SKEW_FAIL_SECS: f64 = 300.0;
+
+const CERT_WARN_DAYS: i64 = 14;
+
+const DISK_WARN_BYTES: u64 = 1024 * 1024 * 1024;
+const DISK_FAIL_BYTES: u64 = 200 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skipped,
+}
+
+/// One row of the doctor checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DoctorCheck {
+    pub id: String,
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    pub fix_hint: Option<String>,
+    /// `openclaw` arguments that fix the problem, when the CLI suggests one
+    pub fix_command: Option<Vec<String>>,
+}
+
+impl DoctorCheck {
+    fn new(id: &str, name: &str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            status,
+            detail: detail.into(),
+            fix_hint: None,
+            fix_command: None,
+        }
+    }
+}
+
+/// Clock offset from an SNTP server in seconds; positive means the local clock is behind
+fn ntp_offset() -> Result<f64, String> {
+    let socket =
+        UdpSocket::bind("0.0.0.0:0").map_err(|e| format!("Failed to open socket: {}", e))?;
+    socket
+        .set_read_timeout(Some(NTP_TIMEOUT))
+        .map_err(|e| format!("Failed to set timeout: {}", e))?;
+
+    // LI = 0, version 4, mode 3 (client)
+    let mut packet = [0u8; 48];
+    packet[0] = 0x23;
+    let sent = SystemTime::now();
+    socket
+        .send_to(&packet, NTP_SERVER)
+        .map_err(|e| format!("Failed to query {}: {}", NTP_SERVER, e))?;
+    let (len, _) = socket
+        .recv_from(&mut packet)
+        .map_err(|e| format!("No reply from {}: {}", NTP_SERVER, e))?;
+    let received = SystemTime::now();
+    if len < 48 {
+        return Err("Short reply from NTP server".to_string());
+    }
+
+    // Transmit timestamp: seconds and fraction since 1900
+    let seconds = u32::from_be_bytes([packet[40], packet[41], packet[42], packet[43]]) as u64;
+    let fraction = u32::from_be_bytes([packet[44], packet[45], packet[46], packet[47]]) as f64;
+    let server = seconds.saturating_sub(NTP_UNIX_OFFSET) as f64 + fraction / 4_294_967_296.0;
+
+    let round_trip = received
+        .duration_since(sent)
+        .unwrap_or_default()
+        .as_secs_f64();
+    let local = received
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("System clock is before 1970: {}", e))?
+        .as_secs_f64();
+    Ok(server + round_trip / 2.0 - local)
+}
+
+fn check_clock_skew() -> DoctorCheck {
+    const ID: &str = "clock_skew";
+    const NAME: &str = "System clock";
+    match ntp_offset() {
+        Ok(offset) => {
+            let skew = offset.abs();
+            let detail = format!(
+                "Local clock is {:.1}s {} {}",
+                skew,
+                if offset > 0.0 { "behind" } else { "ahead of" },
+                NTP_SERVER.trim_end_matches(":123")
+            );
+            let status = if skew >= SKEW_FAIL_SECS {
+                CheckStatus::Fail
+            } else if skew >= SKEW_WARN_SECS {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Pass
+            };
+            DoctorCheck::new(ID, NAME, status, detail)
+        }
+        Err(e) => DoctorCheck::new(ID, NAME, CheckStatus::Skipped, e),
+    }
+}
+
+/// Certificate files referenced by the gateway's TLS config
+fn configured_certs(config: &Value) -> Vec<PathBuf> {
+    let Some(tls) = config.pointer("/gateway/tls") else {
+        return Vec::new();
+    };
+    ["cert", "certPath", "certFile", "ca", "caPath"]
+        .iter()
+        .filter_map(|key| tls.get(key).and_then(Value::as_str))
+        .map(|path| match path.strip_prefix("~/") {
+            Some(rest) => dirs::home_dir().unwrap_or_default().join(rest),
+            None => PathBuf::from(path),
+        })
+        .collect()
+}
+
+/// Unix timestamp at which the first certificate in a PEM file expires
+fn cert_not_after(path: &Path) -> Result<i64, String> {
+    let content = std::fs::read(path).map_err(|e| format!("Failed to read: {}", e))?;
+    let (_, pem) = x509_parser::pem::parse_x509_pem(&content)
+        .map_err(|e| format!("Not a PEM certificate: {}", e))?;
+    let cert = pem
+        .parse_x509()
+        .map_err(|e| format!("Invalid certificate: {}", e))?;
+    Ok(cert.validity().not_after.timestamp())
+}
+
+fn check_certificates() -> Vec<DoctorCheck> {
+    const NAME: &str = "Gateway TLS certificate";
+    let certs = config::load_resolved_config()
+        .map(|config| configured_certs(&config))
+        .unwrap_or_default();
+    if certs.is_empty() {
+        return vec![DoctorCheck::new(
+            "tls_cert",
+            NAME,
+            CheckStatus::Skipped,
+            "No TLS certificate configured",
+        )];
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    certs
+        .iter()
+        .map(|path| {
+            let id = format!("tls_cert:{}", path.display());
+            let (status, detail) = match cert_not_after(path) {
+                Ok(expires) => {
+                    let days = (expires - now) / 86_400;
+                    if expires <= now {
+                        (CheckStatus::Fail, format!("{} has expired", path.display()))
+                    } else if days < CERT_WARN_DAYS {
+                        (
+                            CheckStatus::Warn,
+                            format!("{} expires in {} days", path.display(), days),
+                        )
+                    } else {
+                        (
+                            CheckStatus::Pass,
+                            format!("{} valid for {} more days", path.display(), days),
+                        )
+                    }
+                }
+                Err(e) => (CheckStatus::Fail, format!("{}: {}", path.display(), e)),
+            };
+            DoctorCheck::new(&id, NAME, status, detail)
+        })
+        .collect()
+}
+
+fn check_disk_space() -> DoctorCheck {
+    const ID: &str = "disk_space";
+    const NAME: &str = "Disk space";
+    let home = match openclaw_home_dir() {
+        Ok(home) => home,
+        Err(e) => return DoctorCheck::new(ID, NAME, CheckStatus::Skipped, e),
+    };
+    // The home may not exist yet on a fresh install; measure the nearest existing parent
+    let target = nearest_existing(&home);
+    match fs2::available_space(&target) {
+        Ok(available) => {
+            let status = if available < DISK_FAIL_BYTES {
+                CheckStatus::Fail
+            } else if available < DISK_WARN_BYTES {
+                CheckStatus::Warn
+            } else {
+                CheckStatus::Pass
+            };
+            DoctorCheck::new(
+                ID,
+                NAME,
+                status,
+                format!("{} free under {}", format_bytes(available), home.display()),
+            )
+        }
+        Err(e) => DoctorCheck::new(
+            ID,
+            NAME,
+            CheckStatus::Skipped,
+            format!("Failed to read free space: {}", e),
+        ),
+    }
+}
+
+/// Checks the desktop app runs itself, beyond what `openclaw doctor` covers
+pub(crate) fn local_checks() -> Vec<DoctorCheck> {
+    let mut checks = vec![check_clock_skew()];
+    checks.extend(check_certificates());
+    checks.push(check_disk_space());
+    checks
+}
+
+/// Results of the last structured doctor run, consulted by `run_doctor_fix`
+static LAST_CHECKS: Mutex<Vec<DoctorCheck>> = Mutex::new(Vec::new());
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CliDoctorOutput {
+    List(Vec<CliCheck>),
+    Wrapped { checks: Vec<CliCheck> },
+}
+
+/// A check as reported by `openclaw doctor --json`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CliCheck {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(alias = "title")]
+    name: String,
+    status: String,
+    #[serde(default, alias = "message")]
+    detail: Option<String>,
+    #[serde(default, alias = "hint", alias = "fix")]
+    fix_hint: Option<String>,
+    #[serde(default)]
+    fix_command: Option<Vec<String>>,
+}
+
+fn parse_status(status: &str) -> CheckStatus {
+    match status.to_ascii_lowercase().as_str() {
+        "ok" | "pass" | "passed" | "success" => CheckStatus::Pass,
+        "warn" | "warning" => CheckStatus::Warn,
+        "fail" | "failed" | "error" => CheckStatus::Fail,
+        _ => CheckStatus::Skipped,
+    }
+}
+
+/// Stable id from a check name, e.g. "Gateway token" -> `gateway-token`
+fn slug(name: &str) -> String {
+    name.to_ascii_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// `openclaw ...` in a hint becomes the arguments to run as its fix
+fn fix_command_from_hint(hint: &str) -> Option<Vec<String>> {
+    let start = hint.find("openclaw ")?;
+    let command = hint[start + "openclaw ".len()..].trim_end_matches(['`', '.', ' ']);
+    let args: Vec<String> = command
+        .split_whitespace()
+        .map(|arg| arg.trim_matches('`').to_string())
+        .collect();
+    (!args.is_empty()).then_some(args)
+}
+
+fn from_cli(check: CliCheck) -> DoctorCheck {
+    let fix_command = check
+        .fix_command
+        .or_else(|| check.fix_hint.as_deref().and_then(fix_command_from_hint));
+    DoctorCheck {
+        id: check.id.unwrap_or_else(|| slug(&check.name)),
+        status: parse_status(&check.status),
+        detail: check.detail.unwrap_or_default(),
+        name: check.name,
+        fix_hint: check.fix_hint,
+        fix_command,
+    }
+}
+
+const PASS_MARKERS: [&str; 4] = ["✓", "✔", "[ok]", "[pass]"];
+const WARN_MARKERS: [&str; 3] = ["⚠", "[warn]", "!"];
+const FAIL_MARKERS: [&str; 5] = ["✗", "✖", "×", "[fail]", "[error]"];
+const HINT_PREFIXES: [&str; 5] = ["→", "->", "Fix:", "Hint:", "Run:"];
+
+fn strip_marker(line: &str) -> Option<(CheckStatus, &str)> {
+    [
+        (CheckStatus::Pass, &PASS_MARKERS[..]),
+        (CheckStatus::Warn, &WARN_MARKERS[..]),
+        (CheckStatus::Fail, &FAIL_MARKERS[..]),
+    ]
+    .iter()
+    .find_map(|(status, markers)| {
+        markers
+            .iter()
+            .find_map(|marker| line.strip_prefix(marker))
+            .map(|rest| (*status, rest.trim()))
+    })
+}
+
+/// Best-effort parse of the human-readable doctor output: one check per line
+/// starting with a status marker, with hint lines attached to the check above
+fn parse_text(output: &str) -> Vec<DoctorCheck> {
+    let mut checks: Vec<DoctorCheck> = Vec::new();
+    for line in output.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some((status, rest)) = strip_marker(line) {
+            let (name, detail) = rest
+                .split_once(" - ")
+                .or_else(|| rest.split_once(": "))
+                .unwrap_or((rest, ""));
+            checks.push(DoctorCheck::new(
+                &slug(name),
+                name.trim(),
+                status,
+                detail.trim(),
+            ));
+        } else if let Some(hint) = HINT_PREFIXES
+            .iter()
+            .find_map(|prefix| line.strip_prefix(prefix))
+        {
+            if let Some(check) = checks.last_mut() {
+                let hint = hint.trim().to_string();
+                check.fix_command = fix_command_from_hint(&hint);
+                check.fix_hint = Some(hint);
+            }
+        } else if let Some(check) = checks.last_mut() {
+            if !check.detail.is_empty() {
+                check.detail.push('\n');
+            }
+            check.detail.push_str(line);
+        }
+    }
+    checks
+}
+
+/// Per-check results from `openclaw doctor`, preferring `--json` and falling back
+/// to parsing the text output on CLIs that do not support it
+async fn cli_checks() -> Result<Vec<DoctorCheck>, String> {
+    if let Ok(output) =
+        crate::run_openclaw_output_timeout(&["doctor", "--json"], process::DOCTOR_TIMEOUT).await
+    {
+        if let Ok(parsed) = serde_json::from_slice::<CliDoctorOutput>(&output.stdout) {
+            let (CliDoctorOutput::List(checks) | CliDoctorOutput::Wrapped { checks }) = parsed;
+            return Ok(checks.into_iter().map(from_cli).collect());
+        }
+    }
+
+    let output = crate::run_openclaw_output_timeout(&["doctor"], process::DOCTOR_TIMEOUT).await?;
+    let text = format!(
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let checks = parse_text(&text);
+    if !checks.is_empty() {
+        return Ok(checks);
+    }
+    // Nothing recognizable: report the whole run as one row
+    let status = if output.status.success() {
+        CheckStatus::Pass
+    } else {
+        CheckStatus::Fail
+    };
+    Ok(vec![DoctorCheck::new(
+        "openclaw-doctor",
+        "openclaw doctor",
+        status,
+        text.trim(),
+    )])
+}
+
+fn remember(checks: &[DoctorCheck]) {
+    if let Ok(mut last) = LAST_CHECKS.lock() {
+        *last = checks.to_vec();
+    }
+}
+
+/// Run `openclaw doctor` and return its checks as structured results
+#[tauri::command]
+pub async fn run_openclaw_doctor_structured() -> Result<Vec<DoctorCheck>, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let checks = cli_checks().await?;
+    remember(&checks);
+    Ok(checks)
+}
+
+/// Run `openclaw doctor` and the local checks, returning one combined checklist
+#[tauri::command]
+pub async fn run_doctor_checklist() -> Result<Vec<DoctorCheck>, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let mut checks = cli_checks().await.unwrap_or_else(|e| {
+        vec![DoctorCheck::new(
+            "openclaw-doctor",
+            "openclaw doctor",
+            CheckStatus::Fail,
+            e,
+        )]
+    });
+    checks.extend(
+        tauri::async_runtime::spawn_blocking(local_checks)
+            .await
+            .map_err(|e| format!("Failed to run doctor checks: {}", e))?,
+    );
+    remember(&checks);
+    Ok(checks)
+}
+
+/// Run the fix the CLI suggested for a check from the last doctor run
+#[tauri::command]
+pub async fn run_doctor_fix(check_id: String) -> Result<String, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let check = LAST_CHECKS
+        .lock()
+        .map_err(|_| "Doctor results lock poisoned".to_string())?
+        .iter()
+        .find(|c| c.id == check_id)
+        .cloned()
+        .ok_or_else(|| format!("Unknown doctor check: {}", check_id))?;
+    let args = check.fix_command.ok_or_else(|| match &check.fix_hint {
+        Some(hint) => format!("No automatic fix for {}: {}", check.name, hint),
+        None => format!("No automatic fix for {}", check.name),
+    })?;
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = crate::run_openclaw_output(&args).await?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        Err(format!(
+            "Fix for {} failed: {}",
+            check.name,
+            if stderr.is_empty() { stdout } else { stderr }
+        )
+        .into())
+    }
+}
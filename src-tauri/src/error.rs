@@ -0,0 +1,195 @@
+//! The error every command returns.
+//!
+//! It reaches the webview as `{ code, message, hint }`, so the frontend can
+//! branch on `code` instead of matching message text. Helpers keep returning
+//! `String`; `?` turns those into [`DesktopError::Other`].
+
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+use ts_rs::TS;
+
+use crate::gateway_api::{GatewayApiError, GatewayApiErrorKind};
+use crate::i18n;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/errors.ts")]
+pub enum ErrorCode {
+    NotInstalled,
+    PermissionDenied,
+    KioskLocked,
+    DashboardLocked,
+    RemoteConnection,
+    PolicyLocked,
+    Unavailable,
+    GatewayNotRunning,
+    GatewayUnauthorized,
+    NotFound,
+    InvalidInput,
+    Cancelled,
+    Offline,
+    RegistryUnreachable,
+    Internal,
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum DesktopError {
+    /// The named tool is missing
+    #[error("{0} is not installed")]
+    NotInstalled(String),
+    #[error("{0}")]
+    PermissionDenied(String),
+    #[error("Locked: this action is unavailable in kiosk mode")]
+    KioskLocked,
+    #[error("The dashboard is locked after inactivity; unlock it first")]
+    DashboardLocked,
+    #[error("The app is connected to a remote gateway; start and stop it on its host")]
+    RemoteConnection,
+    #[error("{0}")]
+    PolicyLocked(String),
+    /// The action can't run right now, see `get_action_availability`
+    #[error("{0}")]
+    Unavailable(String),
+    #[error("{0}")]
+    GatewayNotRunning(String),
+    #[error("{0}")]
+    GatewayUnauthorized(String),
+    #[error("{0}")]
+    NotFound(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    /// Stopped by `cancel_task`
+    #[error("Cancelled")]
+    Cancelled,
+    /// No route to the internet at all
+    #[error("{0}")]
+    Offline(String),
+    /// Online, but the npm registry doesn't answer
+    #[error("{0}")]
+    RegistryUnreachable(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl DesktopError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::NotInstalled(_) => ErrorCode::NotInstalled,
+            Self::PermissionDenied(_) => ErrorCode::PermissionDenied,
+            Self::KioskLocked => ErrorCode::KioskLocked,
+            Self::DashboardLocked => ErrorCode::DashboardLocked,
+            Self::RemoteConnection => ErrorCode::RemoteConnection,
+            Self::PolicyLocked(_) => ErrorCode::PolicyLocked,
+            Self::Unavailable(_) => ErrorCode::Unavailable,
+            Self::GatewayNotRunning(_) => ErrorCode::GatewayNotRunning,
+            Self::GatewayUnauthorized(_) => ErrorCode::GatewayUnauthorized,
+            Self::NotFound(_) => ErrorCode::NotFound,
+            Self::InvalidInput(_) => ErrorCode::InvalidInput,
+            Self::Cancelled => ErrorCode::Cancelled,
+            Self::Offline(_) => ErrorCode::Offline,
+            Self::RegistryUnreachable(_) => ErrorCode::RegistryUnreachable,
+            Self::Other(_) => ErrorCode::Internal,
+        }
+    }
+
+    /// Translation key of what the user can do about it, when there is something
+    fn hint_key(&self) -> Option<&'static str> {
+        match self {
+            Self::NotInstalled(_) => Some("hint.not_installed"),
+            Self::PermissionDenied(_) => Some("hint.permission_denied"),
+            Self::KioskLocked => Some("hint.kiosk_locked"),
+            Self::DashboardLocked => Some("hint.dashboard_locked"),
+            Self::RemoteConnection => Some("hint.remote_connection"),
+            Self::PolicyLocked(_) => Some("hint.policy_locked"),
+            Self::GatewayNotRunning(_) => Some("hint.gateway_not_running"),
+            Self::GatewayUnauthorized(_) => Some("hint.gateway_unauthorized"),
+            Self::Offline(_) => Some("hint.offline"),
+            Self::RegistryUnreachable(_) => Some("hint.registry_unreachable"),
+            Self::Unavailable(_)
+            | Self::NotFound(_)
+            | Self::InvalidInput(_)
+            | Self::Cancelled
+            | Self::Other(_) => None,
+        }
+    }
+
+    /// What the user can do about it, in the active locale
+    pub fn hint(&self) -> Option<String> {
+        self.hint_key().map(i18n::t)
+    }
+
+    /// The message in the active locale. Only the fixed messages are translated;
+    /// the rest carry details such as paths and CLI output and stay as written.
+    pub fn localized_message(&self) -> String {
+        match self {
+            Self::KioskLocked => i18n::t("error.kiosk_locked"),
+            Self::DashboardLocked => i18n::t("error.dashboard_locked"),
+            Self::RemoteConnection => i18n::t("error.remote_connection"),
+            Self::Cancelled => i18n::t("error.cancelled"),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// Wire format of [`DesktopError`]
+#[derive(Serialize, TS)]
+#[ts(
+    export,
+    export_to = "../../src/bindings/errors.ts",
+    rename = "DesktopError"
+)]
+struct ErrorBody {
+    code: ErrorCode,
+    message: String,
+    hint: Option<String>,
+}
+
+impl Serialize for DesktopError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        ErrorBody {
+            code: self.code(),
+            message: self.localized_message(),
+            hint: self.hint(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// `io::Error`'s wording for a denied permission, as it ends up inside wrapped messages
+fn is_permission_denied(message: &str) -> bool {
+    message.contains("Permission denied (os error") || message.contains("Access is denied.")
+}
+
+impl From<String> for DesktopError {
+    fn from(message: String) -> Self {
+        if is_permission_denied(&message) {
+            Self::PermissionDenied(message)
+        } else {
+            Self::Other(message)
+        }
+    }
+}
+
+impl From<&str> for DesktopError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<GatewayApiError> for DesktopError {
+    fn from(error: GatewayApiError) -> Self {
+        match error.kind {
+            GatewayApiErrorKind::NotRunning => Self::GatewayNotRunning(error.message),
+            GatewayApiErrorKind::Unauthorized => Self::GatewayUnauthorized(error.message),
+            GatewayApiErrorKind::NotFound => Self::NotFound(error.message),
+            _ => Self::Other(error.message),
+        }
+    }
+}
+
+/// For helpers that still return `String` and call a guard
+impl From<DesktopError> for String {
+    fn from(error: DesktopError) -> Self {
+        error.to_string()
+    }
+}
@@ -0,0 +1,99 @@
+//! Long-lived WebSocket to the gateway's `/ws` event stream, for events the app
+//! handles natively rather than leaving to the dashboard.
+//!
+//! The bridge connects whenever the gateway is verified running, reconnects
+//! after drops, and hands each JSON event to the module that owns its type.
+//! `wss://` relies on the system trust store, not the custom CA in the TLS
+//! settings.
+
+use futures_util::StreamExt;
+use serde_json::Value;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::capabilities::{self, GatewayFeature};
+use crate::{approvals, connection, probe, read_gateway_token};
+
+const WEBSOCKET_PATH: &str = "/ws";
+/// Wait between attempts while the gateway is down or refuses the connection
+const RETRY_INTERVAL: Duration = Duration::from_secs(5);
+
+fn websocket_url() -> String {
+    let base = connection::base_url();
+    let base = match base.strip_prefix("https://") {
+        Some(rest) => format!("wss://{}", rest),
+        None => base.replacen("http://", "ws://", 1),
+    };
+    format!("{}{}", base, WEBSOCKET_PATH)
+}
+
+fn dispatch<R: Runtime>(app: &AppHandle<R>, text: &str) {
+    let Ok(event) = serde_json::from_str::<Value>(text) else {
+        return;
+    };
+    let Some(kind) = event.get("type").and_then(Value::as_str) else {
+        return;
+    };
+    match kind {
+        approvals::REQUESTED_EVENT | approvals::RESOLVED_EVENT => {
+            approvals::handle_event(app, kind, &event)
+        }
+        _ => {}
+    }
+}
+
+/// Read events until the connection closes
+async fn run<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    capabilities::require(app, GatewayFeature::WebsocketEvents)?;
+    let mut request = websocket_url()
+        .into_client_request()
+        .map_err(|e| format!("Invalid gateway WebSocket URL: {}", e))?;
+    if let Some(token) = read_gateway_token() {
+        if let Ok(value) = HeaderValue::from_str(&format!("Bearer {}", token)) {
+            request.headers_mut().insert("Authorization", value);
+        }
+    }
+    let (mut stream, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(|e| format!("Failed to open gateway event stream: {}", e))?;
+
+    // Requests raised while the bridge was down are only in the listing
+    approvals::sync(app).await;
+    while let Some(message) = stream.next().await {
+        match message {
+            Ok(Message::Text(text)) => dispatch(app, &text),
+            Ok(Message::Close(_)) => break,
+            Ok(_) => {}
+            Err(e) => return Err(format!("Gateway event stream failed: {}", e)),
+        }
+    }
+    Ok(())
+}
+
+/// Keep the event stream open while the gateway runs
+pub fn spawn_event_bridge<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut reported = false;
+        loop {
+            let running = tauri::async_runtime::spawn_blocking(probe::probe_gateway)
+                .await
+                .is_ok_and(|probe| probe.verified);
+            if running {
+                match run(&app).await {
+                    Ok(()) => reported = false,
+                    // Logged once per outage rather than on every retry
+                    Err(e) if !reported => {
+                        log_error!("{}", e);
+                        reported = true;
+                    }
+                    Err(_) => {}
+                }
+            }
+            approvals::clear(&app);
+            tokio::time::sleep(RETRY_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,326 @@
+//! Events emitted to the webview and their payloads.
+//!
+//! Payload types derive `TS`; `cargo test` regenerates `src/bindings/events.ts`
+//! so the frontend's `listen` wrapper stays in sync with what is emitted here.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::disk_space::SpaceCheck;
+use crate::gateway_exit::ExitInfo;
+use crate::logs::LogEntry;
+use crate::probe::GatewayProbeResult;
+use crate::readiness::GatewayStartResult;
+use crate::tasks::{TaskKind, TaskState};
+
+pub const GATEWAY_STATUS_CHANGED_EVENT: &str = "gateway-status-changed";
+pub const GATEWAY_CRASHED_EVENT: &str = "gateway-crashed";
+pub const GATEWAY_RECOVERED_EVENT: &str = "gateway-recovered";
+pub const GATEWAY_LOG_LINE_EVENT: &str = "gateway-log-line";
+pub const REMOTE_LOG_STATUS_EVENT: &str = "remote-log-status";
+pub const INSTALL_PROGRESS_EVENT: &str = "install-progress";
+pub const OPENCLAW_UPDATE_PROGRESS_EVENT: &str = "openclaw-update-progress";
+pub const APP_UPDATE_PROGRESS_EVENT: &str = "app-update-progress";
+pub const SKILL_INSTALL_PROGRESS_EVENT: &str = "skill-install-progress";
+pub const AGENT_REPLY_CHUNK_EVENT: &str = "agent-reply-chunk";
+pub const AGENT_REPLY_DONE_EVENT: &str = "agent-reply-done";
+pub const WORKSPACE_GATEWAY_EVENT: &str = "workspace-gateway";
+pub const LOGIN_START_EVENT: &str = "login-start-progress";
+/// Emitted when a tray quick action asks the main window to open a session or agent
+pub const TRAY_OPEN_EVENT: &str = "tray-open";
+pub const QUIT_REQUESTED_EVENT: &str = "quit-requested";
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+pub const CONFIG_CHANGED_EVENT: &str = "config-changed";
+pub const CONFIG_CONFLICT_EVENT: &str = "config-conflict";
+pub const ANNOUNCE_EVENT: &str = "announce";
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+pub const AGENT_CONFIG_CHANGED_EVENT: &str = "agent-config-changed";
+pub const TOKEN_INVALID_EVENT: &str = "token-invalid";
+pub const TASK_PROGRESS_EVENT: &str = "task-progress";
+pub const GATEWAY_START_PROGRESS_EVENT: &str = "gateway-start-progress";
+pub const TERMINAL_OUTPUT_EVENT: &str = "terminal-output";
+pub const TERMINAL_EXIT_EVENT: &str = "terminal-exit";
+pub const CLI_OUTPUT_EVENT: &str = "cli-output";
+pub const CLI_DONE_EVENT: &str = "cli-done";
+pub const CONNECTIVITY_CHANGED_EVENT: &str = "connectivity-changed";
+pub const NOTIFICATION_RECORDED_EVENT: &str = "notification-recorded";
+pub const SELFTEST_COMPLETED_EVENT: &str = "selftest-completed";
+pub const ATTACHMENTS_DROPPED_EVENT: &str = "attachments-dropped";
+pub const DASHBOARD_UNREACHABLE_EVENT: &str = "dashboard-unreachable";
+pub const THEME_CHANGED_EVENT: &str = "theme-changed";
+pub const APPROVALS_CHANGED_EVENT: &str = "approvals-changed";
+pub const STATE_CHANGED_EVENT: &str = "state-changed";
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct GatewayCrashedPayload {
+    pub exit: Option<ExitInfo>,
+    pub restarting: bool,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct GatewayRecoveredPayload {
+    pub attempts: u32,
+    #[ts(type = "number")]
+    pub downtime_secs: u64,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct GatewayLogLine {
+    pub stream: &'static str,
+    pub entry: LogEntry,
+    /// Remote gateway the line came from; unset for the local gateway
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub gateway: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct RemoteLogStatus {
+    pub gateway: String,
+    pub connected: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct InstallProgress {
+    /// `stdout` or `stderr`
+    pub stream: &'static str,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct UpdateProgress {
+    /// `started`, `output`, `finished`, or `failed`
+    pub stage: &'static str,
+    pub message: String,
+    /// Free space measured before starting, on the `started` event
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub space: Option<SpaceCheck>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct AppUpdateProgress {
+    pub version: String,
+    /// `started`, `downloading`, `finished`, or `failed`
+    pub stage: &'static str,
+    #[ts(type = "number")]
+    pub downloaded: u64,
+    /// Package size, when the server reports it
+    #[ts(type = "number | null")]
+    pub total: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct SkillInstallProgress {
+    pub name: String,
+    /// `started`, `output`, `finished`, or `failed`
+    pub stage: &'static str,
+    pub line: String,
+}
+
+/// One health poll while waiting for a started gateway
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct GatewayStartProgress {
+    #[ts(type = "number")]
+    pub elapsed_ms: u64,
+    #[ts(type = "number")]
+    pub timeout_ms: u64,
+    pub probe: GatewayProbeResult,
+    /// Set on the last event, once the gateway is ready or given up on
+    pub result: Option<GatewayStartResult>,
+}
+
+/// A registered task started, reported progress, or ended
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct TaskProgress {
+    /// Pass to `cancel_task`
+    pub id: String,
+    pub kind: TaskKind,
+    pub state: TaskState,
+    /// Latest progress line while running, the error once failed
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct TerminalOutput {
+    #[ts(type = "number")]
+    pub session_id: u64,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct TerminalExit {
+    #[ts(type = "number")]
+    pub session_id: u64,
+    /// Unknown when the process could not be waited on
+    pub code: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct CliOutput {
+    #[ts(type = "number")]
+    pub run_id: u64,
+    /// `stdout` or `stderr`
+    pub stream: &'static str,
+    pub line: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct CliDone {
+    #[ts(type = "number")]
+    pub run_id: u64,
+    /// Exit code, absent when the process was killed by a signal or never ran
+    pub code: Option<i32>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct AgentReplyChunk {
+    #[ts(type = "number")]
+    pub stream_id: u64,
+    pub delta: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct AgentReplyDone {
+    #[ts(type = "number")]
+    pub stream_id: u64,
+    pub session_id: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum WorkspaceAction {
+    Started,
+    Stopped,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct WorkspaceGatewayEvent {
+    pub gateway: String,
+    pub directory: String,
+    pub action: WorkspaceAction,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[serde(tag = "kind", content = "id", rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum TrayTarget {
+    Session(String),
+    Agent(String),
+}
+
+/// View an `openclaw://` link points at
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum DeepLinkTarget {
+    Session(String),
+    Agent(String),
+    AgentChat(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum AgentChange {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct AgentConfigChanged {
+    pub id: String,
+    pub change: AgentChange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum ConflictField {
+    Port,
+    Profile,
+}
+
+/// A value the app's settings and `openclaw.json` disagree on
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct ConfigConflict {
+    pub field: ConflictField,
+    pub app_value: Option<String>,
+    pub config_value: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct TokenInvalid {
+    /// Unix seconds of the first rejected check
+    pub since: u64,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum DashboardUnreachableReason {
+    GatewayDown,
+    /// The gateway rejected the token
+    Unauthorized,
+    /// Something answered, but not a healthy gateway
+    Unhealthy,
+}
+
+/// Why the dashboard was not opened; the window stays on the app's own UI
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct DashboardUnreachable {
+    pub reason: DashboardUnreachableReason,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct QuitRequest {
+    /// Sessions the gateway reports as still running
+    pub active_sessions: Vec<String>,
+}
+
+/// Maps to `aria-live`: polite waits for the reader to go idle, assertive interrupts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum Politeness {
+    Polite,
+    Assertive,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct Announcement {
+    pub message: String,
+    pub politeness: Politeness,
+}
@@ -0,0 +1,247 @@
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime, Url};
+
+use crate::error::DesktopError;
+use crate::{audit, config, gateway_log_paths, openclaw_home_dir};
+
+/// The OpenClaw paths the UI may open; nothing else is accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathKind {
+    GatewayLog,
+    ErrorLog,
+    Config,
+    OpenclawDir,
+}
+
+impl PathKind {
+    fn label(self) -> &'static str {
+        match self {
+            Self::GatewayLog => "gateway_log",
+            Self::ErrorLog => "error_log",
+            Self::Config => "config",
+            Self::OpenclawDir => "openclaw_dir",
+        }
+    }
+
+    fn path(self) -> Result<PathBuf, DesktopError> {
+        let path = match self {
+            Self::GatewayLog => gateway_log_paths()?.0,
+            Self::ErrorLog => gateway_log_paths()?.1,
+            Self::Config => config::editable_path()?,
+            Self::OpenclawDir => openclaw_home_dir()?,
+        };
+        if path.exists() {
+            Ok(path)
+        } else {
+            Err(DesktopError::NotFound(format!(
+                "{} does not exist yet",
+                path.display()
+            )))
+        }
+    }
+}
+
+/// Directories whose files may be opened with the system handler
+fn allowed_roots<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    [openclaw_home_dir().ok(), app.path().app_data_dir().ok()]
+        .into_iter()
+        .flatten()
+        .filter_map(|root| root.canonicalize().ok())
+        .collect()
+}
+
+pub(crate) fn check_file<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<PathBuf, String> {
+    check_within(path, &allowed_roots(app))
+}
+
+/// `path` with links and `..` resolved, if that lands under one of `roots`
+fn check_within(path: &Path, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let resolved = path
+        .canonicalize()
+        .map_err(|e| format!("Cannot open {}: {}", path.display(), e))?;
+    if roots.iter().any(|root| resolved.starts_with(root)) {
+        Ok(resolved)
+    } else {
+        Err(format!(
+            "Opening files outside the OpenClaw folders is not allowed: {}",
+            resolved.display()
+        ))
+    }
+}
+
+/// Show a file in the file manager, or open a folder there. Files are never run
+/// with their default handler, since ~/.openclaw holds executables.
+fn reveal(path: &Path) -> Result<(), String> {
+    let revealed = if path.is_dir() {
+        opener::open(path)
+    } else {
+        opener::reveal(path)
+    };
+    revealed.map_err(|e| format!("Failed to reveal {}: {}", path.display(), e))
+}
+
+/// The target as recorded in the audit log: URLs lose their query and fragment,
+/// which can carry the gateway token
+fn audit_target(target: &str) -> String {
+    match Url::parse(target) {
+        Ok(mut url) if url.scheme().len() > 1 => {
+            url.set_query(None);
+            url.set_fragment(None);
+            url.to_string()
+        }
+        _ => target.to_string(),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ExternalTarget {
+    Web(Url),
+    File(PathBuf),
+}
+
+/// An http(s) URL, or a file by `file://` URL or absolute path; any other
+/// scheme is refused
+fn classify(target: &str) -> Result<ExternalTarget, String> {
+    match Url::parse(target) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(ExternalTarget::Web(url)),
+        Ok(url) if url.scheme() == "file" => url
+            .to_file_path()
+            .map(ExternalTarget::File)
+            .map_err(|_| format!("Invalid file URL: {}", target)),
+        // Windows drive letters parse as a one-letter scheme
+        Ok(url) if url.scheme().len() > 1 => {
+            Err(format!("Scheme \"{}\" is not allowed", url.scheme()))
+        }
+        _ if Path::new(target).is_absolute() => Ok(ExternalTarget::File(PathBuf::from(target))),
+        _ => Err(format!("Not a URL or absolute path: {}", target)),
+    }
+}
+
+/// Open an http(s) URL in the browser, or reveal a file under the OpenClaw or app
+/// data folders in the file manager. Every attempt is written to the audit log.
+#[tauri::command]
+pub fn open_external(app: AppHandle, target: String) -> Result<(), DesktopError> {
+    let result = match classify(&target) {
+        Ok(ExternalTarget::Web(url)) => {
+            opener::open_browser(url.as_str()).map_err(|e| format!("Failed to open URL: {}", e))
+        }
+        Ok(ExternalTarget::File(path)) => check_file(&app, &path).and_then(|path| reveal(&path)),
+        Err(e) => Err(e),
+    };
+
+    audit::record(
+        "open_external",
+        &audit_target(&target),
+        result.is_ok(),
+        result.as_ref().err().cloned(),
+    );
+    Ok(result?)
+}
+
+/// Show a log, the config file, or the OpenClaw folder in the system file manager
+#[tauri::command]
+pub fn reveal_in_file_manager(path_kind: PathKind) -> Result<(), DesktopError> {
+    let result = path_kind
+        .path()
+        .and_then(|path| reveal(&path).map_err(Into::into));
+    audit::record(
+        "reveal_in_file_manager",
+        path_kind.label(),
+        result.is_ok(),
+        result.as_ref().err().map(ToString::to_string),
+    );
+    result
+}
+
+/// Open a log or the config file with its default application
+#[tauri::command]
+pub fn open_log_in_default_editor(path_kind: PathKind) -> Result<(), DesktopError> {
+    let result = match path_kind {
+        PathKind::OpenclawDir => Err(DesktopError::InvalidInput(
+            "The OpenClaw folder can only be revealed".to_string(),
+        )),
+        _ => path_kind.path().and_then(|path| {
+            opener::open(&path)
+                .map_err(|e| format!("Failed to open {}: {}", path.display(), e).into())
+        }),
+    };
+    audit::record(
+        "open_log_in_default_editor",
+        path_kind.label(),
+        result.is_ok(),
+        result.as_ref().err().map(ToString::to_string),
+    );
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn only_web_urls_and_files_are_opened() {
+        assert!(matches!(
+            classify("https://docs.openclaw.ai/setup?x=1"),
+            Ok(ExternalTarget::Web(url)) if url.host_str() == Some("docs.openclaw.ai")
+        ));
+        assert!(matches!(
+            classify("http://127.0.0.1:18789/"),
+            Ok(ExternalTarget::Web(_))
+        ));
+        for target in [
+            "javascript:alert(1)",
+            "ssh://host",
+            "smb://server/share",
+            "vscode://file/etc/passwd",
+            "openclaw://settings",
+            "relative/path.txt",
+            "",
+        ] {
+            assert!(classify(target).is_err(), "{}", target);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn file_targets_keep_their_path() {
+        assert_eq!(
+            classify("file:///home/u/.openclaw/gateway.log"),
+            Ok(ExternalTarget::File(PathBuf::from(
+                "/home/u/.openclaw/gateway.log"
+            )))
+        );
+        assert_eq!(
+            classify("/home/u/.openclaw/openclaw.json"),
+            Ok(ExternalTarget::File(PathBuf::from(
+                "/home/u/.openclaw/openclaw.json"
+            )))
+        );
+    }
+
+    #[test]
+    fn files_must_resolve_inside_a_root() {
+        let dir = std::env::temp_dir().join(format!("openclaw-external-{}", std::process::id()));
+        let root = dir.join("root");
+        let outside = dir.join("outside");
+        fs::create_dir_all(root.join("logs")).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(root.join("logs").join("gateway.log"), "").unwrap();
+        fs::write(outside.join("secret.txt"), "").unwrap();
+        let roots = vec![root.canonicalize().unwrap()];
+
+        assert!(check_within(&root.join("logs").join("gateway.log"), &roots).is_ok());
+        assert!(check_within(&outside.join("secret.txt"), &roots).is_err());
+        assert!(check_within(&root.join("..").join("outside").join("secret.txt"), &roots).is_err());
+        assert!(check_within(&root.join("missing.txt"), &roots).is_err());
+        #[cfg(unix)]
+        {
+            let link = root.join("escape");
+            std::os::unix::fs::symlink(&outside, &link).unwrap();
+            assert!(check_within(&link.join("secret.txt"), &roots).is_err());
+        }
+        let _ = fs::remove_dir_all(dir);
+    }
+}
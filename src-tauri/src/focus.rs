@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// OS focus queries spawn tools on some platforms; callers poll far more often than it changes
+const CACHE_TTL: Duration = Duration::from_secs(15);
+const DEFER_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+static CACHE: Mutex<Option<(Instant, FocusState)>> = Mutex::new(None);
+
+/// Whether the user has asked not to be interrupted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusState {
+    Available,
+    /// Do Not Disturb, a Focus mode, or Windows quiet hours
+    DoNotDisturb,
+    /// Presentation mode or a full-screen exclusive app
+    Presenting,
+}
+
+impl FocusState {
+    pub fn is_busy(self) -> bool {
+        self != Self::Available
+    }
+}
+
+/// Only the macOS and Linux backends shell out
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn output_text(command: &mut std::process::Command) -> Option<String> {
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(target_os = "macos")]
+mod backend {
+    use super::{output_text, FocusState};
+    use std::process::Command;
+
+    /// Focus modes (macOS 12+) keep their active assertions here
+    fn focus_active() -> bool {
+        let Some(path) =
+            dirs::home_dir().map(|home| home.join("Library/DoNotDisturb/DB/Assertions.json"))
+        else {
+            return false;
+        };
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+            .and_then(|json| {
+                json.get("data")?.as_array().map(|data| {
+                    data.iter().any(|entry| {
+                        entry
+                            .get("storeAssertionRecords")
+                            .and_then(|records| records.as_array())
+                            .is_some_and(|records| !records.is_empty())
+                    })
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    /// Pre-Monterey Do Not Disturb
+    fn legacy_dnd() -> bool {
+        output_text(Command::new("defaults").args([
+            "-currentHost",
+            "read",
+            "com.apple.notificationcenterui",
+            "doNotDisturb",
+        ]))
+        .is_some_and(|value| value == "1")
+    }
+
+    /// Presentation apps hold a display-sleep assertion while a slideshow runs
+    fn presenting() -> bool {
+        output_text(Command::new("pmset").args(["-g", "assertions"])).is_some_and(|text| {
+            text.lines().any(|line| {
+                line.contains("PreventUserIdleDisplaySleep")
+                    && (line.contains("Keynote") || line.contains("Microsoft PowerPoint"))
+            })
+        })
+    }
+
+    pub fn query() -> FocusState {
+        if presenting() {
+            FocusState::Presenting
+        } else if focus_active() || legacy_dnd() {
+            FocusState::DoNotDisturb
+        } else {
+            FocusState::Available
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod backend {
+    use super::FocusState;
+    use windows::Win32::UI::Shell::{
+        SHQueryUserNotificationState, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME,
+        QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    pub fn query() -> FocusState {
+        match unsafe { SHQueryUserNotificationState() } {
+            Ok(state)
+                if state == QUNS_PRESENTATION_MODE || state == QUNS_RUNNING_D3D_FULL_SCREEN =>
+            {
+                FocusState::Presenting
+            }
+            Ok(state) if state == QUNS_BUSY || state == QUNS_QUIET_TIME => FocusState::DoNotDisturb,
+            _ => FocusState::Available,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod backend {
+    use super::{output_text, FocusState};
+    use std::process::Command;
+
+    /// GNOME turns banners off for Do Not Disturb
+    fn gnome_dnd() -> bool {
+        output_text(Command::new("gsettings").args([
+            "get",
+            "org.gnome.desktop.notifications",
+            "show-banners",
+        ]))
+        .is_some_and(|value| value == "false")
+    }
+
+    /// KDE and other servers implementing the `Inhibited` property of the notification spec
+    fn notifications_inhibited() -> bool {
+        output_text(Command::new("gdbus").args([
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.Notifications",
+            "--object-path",
+            "/org/freedesktop/Notifications",
+            "--method",
+            "org.freedesktop.DBus.Properties.Get",
+            "org.freedesktop.Notifications",
+            "Inhibited",
+        ]))
+        .is_some_and(|value| value.contains("true"))
+    }
+
+    pub fn query() -> FocusState {
+        if gnome_dnd() || notifications_inhibited() {
+            FocusState::DoNotDisturb
+        } else {
+            FocusState::Available
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod backend {
+    use super::FocusState;
+
+    pub fn query() -> FocusState {
+        FocusState::Available
+    }
+}
+
+/// The OS focus state, cached briefly
+pub fn current() -> FocusState {
+    if let Ok(cache) = CACHE.lock() {
+        if let Some((at, state)) = *cache {
+            if at.elapsed() < CACHE_TTL {
+                return state;
+            }
+        }
+    }
+    let state = backend::query();
+    if let Ok(mut cache) = CACHE.lock() {
+        *cache = Some((Instant::now(), state));
+    }
+    state
+}
+
+/// Whether background work should hold off, honoring the user's preference
+pub fn should_defer() -> bool {
+    crate::settings::current().notifications.respect_focus && current().is_busy()
+}
+
+/// Wait until the user is no longer presenting or in Do Not Disturb
+pub async fn wait_until_available() {
+    while tauri::async_runtime::spawn_blocking(should_defer)
+        .await
+        .unwrap_or(false)
+    {
+        tokio::time::sleep(DEFER_POLL_INTERVAL).await;
+    }
+}
+
+/// Current OS focus state as seen by the app
+#[tauri::command]
+pub fn get_focus_state() -> FocusState {
+    current()
+}
@@ -0,0 +1,280 @@
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::State;
+
+use crate::connection::{self, GatewayTls};
+use crate::error::DesktopError;
+use crate::{read_gateway_token, settings};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Streamed responses (agent replies) can run far longer than a normal request
+const STREAM_TIMEOUT: Duration = Duration::from_secs(600);
+const UPLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GatewayApiErrorKind {
+    NotRunning,
+    Unauthorized,
+    NotFound,
+    Http,
+    Network,
+    Decode,
+}
+
+/// Error from a gateway API call; commands pass it on as a [`DesktopError`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayApiError {
+    pub kind: GatewayApiErrorKind,
+    pub message: String,
+    pub status: Option<u16>,
+}
+
+impl GatewayApiError {
+    fn new(kind: GatewayApiErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            status: None,
+        }
+    }
+
+    fn from_status(status: StatusCode, path: &str) -> Self {
+        let kind = match status {
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => GatewayApiErrorKind::Unauthorized,
+            StatusCode::NOT_FOUND => GatewayApiErrorKind::NotFound,
+            _ => GatewayApiErrorKind::Http,
+        };
+        Self {
+            kind,
+            message: format!("Gateway returned {} for {}", status, path),
+            status: Some(status.as_u16()),
+        }
+    }
+}
+
+impl std::fmt::Display for GatewayApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayHealth {
+    pub status: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub uptime_secs: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewaySession {
+    pub id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub message_count: Option<u64>,
+    /// e.g. `running` while an agent is working on the session
+    #[serde(default)]
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayAgent {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Authenticated client for the gateway's REST API
+#[derive(Default)]
+pub struct GatewayApi {
+    /// Rebuilt when the TLS settings it was made with change
+    client: Mutex<Option<(GatewayTls, reqwest::Client)>>,
+}
+
+impl GatewayApi {
+    fn client(&self) -> Result<reqwest::Client, GatewayApiError> {
+        let tls = settings::current().gateway_tls;
+        let mut cached = self.client.lock().map_err(|_| {
+            GatewayApiError::new(GatewayApiErrorKind::Network, "Client lock poisoned")
+        })?;
+        if let Some((built_for, client)) = cached.as_ref() {
+            if *built_for == tls {
+                return Ok(client.clone());
+            }
+        }
+        let client = connection::apply_tls(reqwest::Client::builder().timeout(REQUEST_TIMEOUT))
+            .and_then(|builder| {
+                builder
+                    .build()
+                    .map_err(|e| format!("Failed to create HTTP client: {}", e))
+            })
+            .map_err(|e| GatewayApiError::new(GatewayApiErrorKind::Network, e))?;
+        *cached = Some((tls, client.clone()));
+        Ok(client)
+    }
+
+    fn url(path: &str) -> String {
+        format!("{}{}", connection::base_url(), path)
+    }
+
+    fn authorize(request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match read_gateway_token() {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    async fn execute(
+        &self,
+        request: reqwest::RequestBuilder,
+        path: &str,
+    ) -> Result<reqwest::Response, GatewayApiError> {
+        let response = Self::authorize(request).send().await.map_err(|e| {
+            if e.is_connect() {
+                GatewayApiError::new(GatewayApiErrorKind::NotRunning, "Gateway is not running")
+            } else {
+                GatewayApiError::new(
+                    GatewayApiErrorKind::Network,
+                    format!("Failed to reach gateway: {}", e),
+                )
+            }
+        })?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(GatewayApiError::from_status(status, path));
+        }
+        Ok(response)
+    }
+
+    async fn send<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        path: &str,
+    ) -> Result<T, GatewayApiError> {
+        let response = self.execute(request, path).await?;
+        response.json::<T>().await.map_err(|e| {
+            GatewayApiError::new(
+                GatewayApiErrorKind::Decode,
+                format!("Unexpected response from {}: {}", path, e),
+            )
+        })
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T, GatewayApiError> {
+        self.send(self.client()?.get(Self::url(path)), path).await
+    }
+
+    pub async fn post<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<T, GatewayApiError> {
+        self.send(self.client()?.post(Self::url(path)).json(body), path)
+            .await
+    }
+
+    pub async fn put<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<T, GatewayApiError> {
+        self.send(self.client()?.put(Self::url(path)).json(body), path)
+            .await
+    }
+
+    /// POST a multipart form, e.g. a file upload
+    pub async fn post_multipart<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        form: reqwest::multipart::Form,
+    ) -> Result<T, GatewayApiError> {
+        let request = self
+            .client()?
+            .post(Self::url(path))
+            .timeout(UPLOAD_TIMEOUT)
+            .multipart(form);
+        self.send(request, path).await
+    }
+
+    /// POST and hand back the response once headers arrive, for reading the body as a stream
+    pub async fn post_stream(
+        &self,
+        path: &str,
+        body: &impl Serialize,
+    ) -> Result<reqwest::Response, GatewayApiError> {
+        let request = self
+            .client()?
+            .post(Self::url(path))
+            .timeout(STREAM_TIMEOUT)
+            .header(reqwest::header::ACCEPT, "text/event-stream")
+            .json(body);
+        self.execute(request, path).await
+    }
+
+    pub async fn list_sessions(&self) -> Result<Vec<GatewaySession>, GatewayApiError> {
+        match self.get::<SessionsResponse>("/api/sessions").await? {
+            SessionsResponse::List(sessions) | SessionsResponse::Wrapped { sessions } => {
+                Ok(sessions)
+            }
+        }
+    }
+
+    pub async fn list_agents(&self) -> Result<Vec<GatewayAgent>, GatewayApiError> {
+        match self.get::<AgentsResponse>("/api/agents").await? {
+            AgentsResponse::List(agents) | AgentsResponse::Wrapped { agents } => Ok(agents),
+        }
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), GatewayApiError> {
+        self.execute(self.client()?.delete(Self::url(path)), path)
+            .await
+            .map(|_| ())
+    }
+}
+
+/// Sessions come back either as a bare array or wrapped in `{ "sessions": [...] }`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SessionsResponse {
+    List(Vec<GatewaySession>),
+    Wrapped { sessions: Vec<GatewaySession> },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AgentsResponse {
+    List(Vec<GatewayAgent>),
+    Wrapped { agents: Vec<GatewayAgent> },
+}
+
+#[tauri::command]
+pub async fn gateway_get_health(api: State<'_, GatewayApi>) -> Result<GatewayHealth, DesktopError> {
+    Ok(api.get("/api/health").await?)
+}
+
+#[tauri::command]
+pub async fn gateway_list_sessions(
+    api: State<'_, GatewayApi>,
+) -> Result<Vec<GatewaySession>, DesktopError> {
+    Ok(api.list_sessions().await?)
+}
+
+/// The configuration as the running gateway sees it (secrets redacted by the gateway)
+#[tauri::command]
+pub async fn gateway_get_config(api: State<'_, GatewayApi>) -> Result<Value, DesktopError> {
+    Ok(api.get("/api/config").await?)
+}
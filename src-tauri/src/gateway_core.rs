@@ -0,0 +1,1206 @@
+//! Starting, stopping and inspecting the local gateway, written against traits
+//! for the processes, files, time and app state it depends on so it runs under
+//! test against fakes.
+//!
+//! `Core` is managed state built from the system implementations at launch.
+//! Every start, stop and restart goes through it, checks and bookkeeping
+//! included; commands, the tray and background tasks only add their own
+//! permission checks and report the outcome.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::compatibility::CompatibilityReport;
+use crate::dependencies::GatewayDependency;
+use crate::gateway_exit::ExitInfo;
+use crate::gateway_log_level::GatewayLogLevelStatus;
+use crate::gateway_process::{self, GatewayProcess};
+use crate::install_repair::InstallIssue;
+use crate::notices::NoticeView;
+use crate::platform::GatewayServiceStatus;
+use crate::port_owner::PortOwner;
+use crate::power::PowerState;
+use crate::preflight::EnvironmentValidation;
+use crate::safety_mode::SafetyModeStatus;
+use crate::start_lock::{StartGuard, StartLock};
+use crate::{
+    active_gateway_port, audit, compatibility, connection, dashboard_base_url, dependencies,
+    gateway_exit, gateway_log_level, gateway_log_paths, install_repair, integrations, log_rotation,
+    logs, notices, path_env, platform, port_owner, power, preflight, probe, process, profiles,
+    safety_mode, snapshots, spawn_gateway_process, start_lock, supervisor, token_health,
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GatewayDiagnostics {
+    pub openclaw_installed: bool,
+    pub gateway_running: bool,
+    pub gateway_port: u16,
+    pub dashboard_url: String,
+    pub openclaw_version: Option<String>,
+    pub profile_name: Option<String>,
+    pub log_path: String,
+    pub error_log_path: String,
+    pub last_exit: Option<ExitInfo>,
+    /// The gateway wrote to its error log within the last few minutes
+    pub has_recent_errors: bool,
+    /// PATH adopted from the login shell; unset when it couldn't be resolved
+    pub login_shell_path: Option<String>,
+    pub gateway_service: GatewayServiceStatus,
+    /// Leftovers of failed installs found at launch or by the last repair
+    pub install_issues: Vec<InstallIssue>,
+    /// Process listening on the gateway port, when one is
+    pub port_owner: Option<PortOwner>,
+    /// Deprecation and end-of-life notices that apply to this machine
+    pub notices: Vec<NoticeView>,
+    /// Sidecar services from the gateway config and whether they answer
+    pub dependencies: Vec<GatewayDependency>,
+    /// When the gateway started rejecting the stored token, while it still does
+    pub token_invalid_since: Option<u64>,
+    /// Version mismatches found by the last compatibility check
+    pub compatibility: Option<CompatibilityReport>,
+    pub power: PowerState,
+    pub gateway_log_level: GatewayLogLevelStatus,
+    pub safety_mode: SafetyModeStatus,
+    /// When this app last spawned the gateway, in unix seconds
+    pub last_started_at: Option<u64>,
+}
+
+/// What a finished `openclaw` invocation printed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct CommandOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl From<Output> for CommandOutput {
+    fn from(output: Output) -> Self {
+        Self {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).trim().to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }
+    }
+}
+
+/// The `openclaw` CLI, the gateway process the app owns, and the other
+/// processes and services it looks at
+pub(crate) trait ProcessRunner: Send + Sync {
+    fn run_openclaw(&self, args: &[&str], timeout: Duration) -> Result<CommandOutput, String>;
+    /// Spawn `openclaw gateway` in the foreground with output going to fresh logs
+    fn spawn_gateway(
+        &self,
+        port: u16,
+        log_path: &Path,
+        error_log_path: &Path,
+    ) -> Result<(), String>;
+    /// Kill the gateway this app spawned; false when there is none
+    fn kill_gateway(&self) -> Result<bool, String>;
+    /// The gateway answers its health check
+    fn gateway_running(&self) -> bool;
+    fn openclaw_installed(&self) -> bool;
+    /// Whoever listens on `port`
+    fn port_owner(&self, port: u16) -> Option<PortOwner>;
+    /// A program other than an OpenClaw gateway listening on `port`
+    fn foreign_owner(&self, port: u16) -> Option<PortOwner>;
+    fn service_status(&self) -> GatewayServiceStatus;
+}
+
+/// Size and modification time of a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct FileStat {
+    pub len: u64,
+    pub modified: SystemTime,
+}
+
+pub(crate) trait FileSystem: Send + Sync {
+    fn exists(&self, path: &Path) -> bool;
+    fn stat(&self, path: &Path) -> Option<FileStat>;
+    /// Empty a log in place, keeping it open for a writer
+    fn truncate(&self, path: &Path) -> Result<(), String>;
+}
+
+pub(crate) trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+/// The start lock, held from before the spawn until the gateway is up.
+/// Dropping it releases the lock right away.
+pub(crate) trait StartLease: Send {
+    fn release_when_ready(self: Box<Self>);
+}
+
+/// App state the diagnostics pass on as they are
+#[derive(Debug, Clone)]
+pub(crate) struct HostStatus {
+    pub profile_name: Option<String>,
+    pub last_exit: Option<ExitInfo>,
+    pub login_shell_path: Option<String>,
+    pub install_issues: Vec<InstallIssue>,
+    pub notices: Vec<NoticeView>,
+    pub dependencies: Vec<GatewayDependency>,
+    pub token_invalid_since: Option<u64>,
+    pub compatibility: Option<CompatibilityReport>,
+    pub power: PowerState,
+    pub gateway_log_level: GatewayLogLevelStatus,
+    pub safety_mode: SafetyModeStatus,
+}
+
+/// The app around the gateway: settings, the start lock, and the bookkeeping
+/// each action leaves behind
+pub(crate) trait Host: Send + Sync {
+    /// Port the default gateway is configured for
+    fn port(&self) -> u16;
+    fn dashboard_url(&self) -> String;
+    /// The gateway listens on a local socket rather than a port
+    fn uses_socket(&self) -> bool;
+    /// Fails while the app is pointed at a remote gateway
+    fn ensure_local(&self) -> Result<(), String>;
+    /// Claim the right to start the gateway, or learn who already has it
+    fn acquire_start_lock(&self) -> Result<Box<dyn StartLease>, StartLock>;
+    /// Checks that tell a start that can't succeed before anything is spawned
+    fn preflight(&self) -> EnvironmentValidation;
+    fn snapshot(&self, action: &str);
+    fn audit(&self, action: &str, target: &str, error: Option<&str>);
+    /// A start asked for by anyone other than the supervisor
+    fn start_requested(&self);
+    fn set_desired_running(&self, running: bool);
+    /// The coming exit is intended, not a crash
+    fn stop_requested(&self);
+    fn restarted(&self, port: u16);
+    fn status(&self) -> HostStatus;
+}
+
+/// How a start request was handled
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum StartAction {
+    AlreadyRunning,
+    Spawned,
+    /// Another start holds the lock, so nothing was spawned
+    AlreadyStarting(StartLock),
+    /// Pre-start checks failed, so nothing was spawned
+    Blocked(EnvironmentValidation),
+}
+
+impl StartAction {
+    /// A message for callers that only report the outcome; a start that didn't
+    /// happen is an error
+    pub(crate) fn into_message(self) -> Result<String, String> {
+        match self {
+            StartAction::AlreadyRunning => Ok("Gateway is already running".to_string()),
+            StartAction::Spawned => Ok("Gateway starting...".to_string()),
+            StartAction::AlreadyStarting(lock) => Err(lock.message()),
+            StartAction::Blocked(validation) => {
+                Err(format!("Gateway not started: {}", validation.summary()))
+            }
+        }
+    }
+}
+
+pub struct Core {
+    runner: Arc<dyn ProcessRunner>,
+    fs: Arc<dyn FileSystem>,
+    clock: Arc<dyn Clock>,
+    host: Arc<dyn Host>,
+    log_path: PathBuf,
+    error_log_path: PathBuf,
+    /// Unix seconds of the last spawn by this app
+    started_at: Mutex<Option<u64>>,
+}
+
+/// Message for a `openclaw daemon <action>` run, or its error output
+pub(crate) fn control_result(action: &str, output: CommandOutput) -> Result<String, String> {
+    match output {
+        CommandOutput {
+            success: true,
+            stdout,
+            ..
+        } if stdout.is_empty() => Ok(format!("Gateway {} command sent", action)),
+        CommandOutput {
+            success: true,
+            stdout,
+            ..
+        } => Ok(stdout),
+        CommandOutput { stderr, .. } if stderr.is_empty() => {
+            Err(format!("openclaw gateway {} failed", action))
+        }
+        CommandOutput { stderr, .. } => Err(stderr),
+    }
+}
+
+/// The version `openclaw --version` printed, if it ran and printed one
+pub(crate) fn version_from(output: CommandOutput) -> Option<String> {
+    (output.success && !output.stdout.is_empty()).then_some(output.stdout)
+}
+
+impl Core {
+    pub(crate) fn new(
+        runner: Arc<dyn ProcessRunner>,
+        fs: Arc<dyn FileSystem>,
+        clock: Arc<dyn Clock>,
+        host: Arc<dyn Host>,
+        (log_path, error_log_path): (PathBuf, PathBuf),
+    ) -> Self {
+        Self {
+            runner,
+            fs,
+            clock,
+            host,
+            log_path,
+            error_log_path,
+            started_at: Mutex::new(None),
+        }
+    }
+
+    /// Backed by the real CLI, file system, clock and app
+    pub(crate) fn system<R: Runtime>(app: &AppHandle<R>) -> Result<Self, String> {
+        Ok(Self::new(
+            Arc::new(SystemRunner { app: app.clone() }),
+            Arc::new(SystemFileSystem),
+            Arc::new(SystemClock),
+            Arc::new(SystemHost { app: app.clone() }),
+            gateway_log_paths()?,
+        ))
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        self.runner.gateway_running()
+    }
+
+    pub(crate) fn last_started_at(&self) -> Option<u64> {
+        self.started_at.lock().ok().and_then(|s| *s)
+    }
+
+    fn spawn(&self, port: u16) -> Result<(), String> {
+        self.runner
+            .spawn_gateway(port, &self.log_path, &self.error_log_path)?;
+        let now = self
+            .clock
+            .now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        if let Ok(mut started_at) = self.started_at.lock() {
+            *started_at = Some(now);
+        }
+        Ok(())
+    }
+
+    /// Refuse a port held by another program, then snapshot and spawn
+    fn checked_spawn(&self, port: u16) -> Result<(), String> {
+        let owner = match self.host.uses_socket() {
+            true => None,
+            false => self.runner.foreign_owner(port),
+        };
+        if let Some(owner) = owner {
+            return Err(format!(
+                "Port {} is in use by {} (pid {})",
+                owner.port,
+                owner.name.as_deref().unwrap_or("another program"),
+                owner.pid
+            ));
+        }
+        self.host.snapshot("start");
+        self.spawn(port)
+    }
+
+    /// Start the gateway unless it already runs. The start lock is held until
+    /// it is up, and the pre-start checks run first unless `force` is set.
+    pub(crate) fn start(&self, force: bool) -> Result<StartAction, String> {
+        self.host.ensure_local()?;
+        self.host.start_requested();
+        let lease = match self.host.acquire_start_lock() {
+            Ok(lease) => lease,
+            Err(lock) => return Ok(StartAction::AlreadyStarting(lock)),
+        };
+        if self.is_running() {
+            self.host.set_desired_running(true);
+            return Ok(StartAction::AlreadyRunning);
+        }
+        if !force {
+            let validation = self.host.preflight();
+            if !validation.ok {
+                return Ok(StartAction::Blocked(validation));
+            }
+        }
+
+        let port = self.host.port();
+        let spawned = self.checked_spawn(port);
+        self.host.audit(
+            "gateway_start",
+            &port.to_string(),
+            spawned.as_ref().err().map(String::as_str),
+        );
+        spawned?;
+        lease.release_when_ready();
+        self.host.set_desired_running(true);
+        Ok(StartAction::Spawned)
+    }
+
+    /// Stop via `openclaw daemon stop`, falling back to killing the child we spawned
+    pub(crate) fn stop(&self) -> Result<String, String> {
+        self.host.ensure_local()?;
+        self.host.snapshot("stop");
+        self.host.set_desired_running(false);
+        self.host.stop_requested();
+        let control = self
+            .runner
+            .run_openclaw(&["daemon", "stop"], process::DEFAULT_TIMEOUT)
+            .and_then(|output| control_result("stop", output));
+        let result = match control {
+            Ok(message) => Ok(message),
+            Err(e) => match self.runner.kill_gateway() {
+                Ok(true) => Ok("Gateway process stopped".to_string()),
+                Ok(false) => Err(e),
+                Err(kill_error) => Err(kill_error),
+            },
+        };
+        self.host.audit(
+            "gateway_stop",
+            &self.host.port().to_string(),
+            result.as_ref().err().map(String::as_str),
+        );
+        result
+    }
+
+    /// Restart via `openclaw daemon restart`, falling back to respawning the child we own
+    pub(crate) fn restart(&self) -> Result<String, String> {
+        self.host.ensure_local()?;
+        self.host.snapshot("restart");
+        self.host.stop_requested();
+        let port = self.host.port();
+        let control = self
+            .runner
+            .run_openclaw(&["daemon", "restart"], process::DEFAULT_TIMEOUT)
+            .and_then(|output| control_result("restart", output));
+        let result = match control {
+            Ok(message) => Ok(message),
+            Err(e) => match self.runner.kill_gateway() {
+                Ok(true) => self
+                    .spawn(port)
+                    .map(|_| "Gateway restarting...".to_string()),
+                Ok(false) => Err(e),
+                Err(kill_error) => Err(kill_error),
+            },
+        };
+        self.host.audit(
+            "gateway_restart",
+            &port.to_string(),
+            result.as_ref().err().map(String::as_str),
+        );
+        if result.is_ok() {
+            self.host.restarted(port);
+        }
+        result
+    }
+
+    pub(crate) fn version(&self) -> Option<String> {
+        self.runner
+            .run_openclaw(&["--version"], process::VERSION_TIMEOUT)
+            .ok()
+            .and_then(version_from)
+    }
+
+    /// The error log has something in it, written within the last few minutes
+    fn has_recent_errors(&self) -> bool {
+        self.fs.stat(&self.error_log_path).is_some_and(|stat| {
+            logs::is_recent_error_log(stat.len, stat.modified, self.clock.now())
+        })
+    }
+
+    /// Everything known about the gateway and its surroundings, for the
+    /// diagnostics view and bundles. Blocking: it runs the CLI and probes.
+    pub(crate) fn diagnostics(&self) -> GatewayDiagnostics {
+        let port = self.host.port();
+        let status = self.host.status();
+        GatewayDiagnostics {
+            openclaw_installed: self.runner.openclaw_installed(),
+            gateway_running: self.is_running(),
+            gateway_port: port,
+            dashboard_url: self.host.dashboard_url(),
+            openclaw_version: self.version(),
+            profile_name: status.profile_name,
+            log_path: self.log_path.display().to_string(),
+            error_log_path: self.error_log_path.display().to_string(),
+            last_exit: status.last_exit,
+            has_recent_errors: self.has_recent_errors(),
+            login_shell_path: status.login_shell_path,
+            gateway_service: self.runner.service_status(),
+            install_issues: status.install_issues,
+            port_owner: self.runner.port_owner(port),
+            notices: status.notices,
+            dependencies: status.dependencies,
+            token_invalid_since: status.token_invalid_since,
+            compatibility: status.compatibility,
+            power: status.power,
+            gateway_log_level: status.gateway_log_level,
+            safety_mode: status.safety_mode,
+            last_started_at: self.last_started_at(),
+        }
+    }
+
+    pub(crate) fn clear_logs(&self) -> Result<(), String> {
+        if self.fs.exists(&self.log_path) {
+            self.fs.truncate(&self.log_path)?;
+        }
+        Ok(())
+    }
+}
+
+struct SystemRunner<R: Runtime> {
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> ProcessRunner for SystemRunner<R> {
+    fn run_openclaw(&self, args: &[&str], timeout: Duration) -> Result<CommandOutput, String> {
+        crate::run_openclaw_output_blocking(args, timeout).map(CommandOutput::from)
+    }
+
+    fn spawn_gateway(
+        &self,
+        port: u16,
+        log_path: &Path,
+        error_log_path: &Path,
+    ) -> Result<(), String> {
+        let child = spawn_gateway_process(port, log_path, error_log_path, true)?;
+        gateway_process::track(&self.app, child);
+        Ok(())
+    }
+
+    fn kill_gateway(&self) -> Result<bool, String> {
+        self.app.state::<GatewayProcess>().kill()
+    }
+
+    fn gateway_running(&self) -> bool {
+        probe::probe_gateway().verified
+    }
+
+    fn openclaw_installed(&self) -> bool {
+        crate::openclaw_installed()
+    }
+
+    fn port_owner(&self, port: u16) -> Option<PortOwner> {
+        port_owner::lookup(port)
+    }
+
+    fn foreign_owner(&self, port: u16) -> Option<PortOwner> {
+        port_owner::foreign_owner(port)
+    }
+
+    fn service_status(&self) -> GatewayServiceStatus {
+        platform::service_status()
+    }
+}
+
+struct SystemFileSystem;
+
+impl FileSystem for SystemFileSystem {
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+
+    fn stat(&self, path: &Path) -> Option<FileStat> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(FileStat {
+            len: metadata.len(),
+            modified: metadata.modified().ok()?,
+        })
+    }
+
+    fn truncate(&self, path: &Path) -> Result<(), String> {
+        log_rotation::truncate_log(path)
+    }
+}
+
+struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+struct SystemLease<R: Runtime> {
+    guard: StartGuard,
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> StartLease for SystemLease<R> {
+    fn release_when_ready(self: Box<Self>) {
+        self.guard.release_when_ready(&self.app);
+    }
+}
+
+struct SystemHost<R: Runtime> {
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> Host for SystemHost<R> {
+    fn port(&self) -> u16 {
+        active_gateway_port()
+    }
+
+    fn dashboard_url(&self) -> String {
+        dashboard_base_url()
+    }
+
+    fn uses_socket(&self) -> bool {
+        connection::socket_path().is_some()
+    }
+
+    fn ensure_local(&self) -> Result<(), String> {
+        Ok(connection::ensure_local()?)
+    }
+
+    fn acquire_start_lock(&self) -> Result<Box<dyn StartLease>, StartLock> {
+        let guard = start_lock::acquire()?;
+        Ok(Box::new(SystemLease {
+            guard,
+            app: self.app.clone(),
+        }))
+    }
+
+    fn preflight(&self) -> EnvironmentValidation {
+        preflight::validate()
+    }
+
+    fn snapshot(&self, action: &str) {
+        snapshots::capture(&self.app, action);
+    }
+
+    fn audit(&self, action: &str, target: &str, error: Option<&str>) {
+        audit::record(action, target, error.is_none(), error.map(str::to_string));
+    }
+
+    fn start_requested(&self) {
+        supervisor::on_start_requested(&self.app);
+    }
+
+    fn set_desired_running(&self, running: bool) {
+        supervisor::set_desired_running(running);
+    }
+
+    fn stop_requested(&self) {
+        gateway_exit::mark_stop_requested();
+    }
+
+    fn restarted(&self, port: u16) {
+        integrations::forward(
+            integrations::WebhookEvent::Restart,
+            "Gateway restarted".to_string(),
+            serde_json::json!({ "port": port }),
+        );
+    }
+
+    fn status(&self) -> HostStatus {
+        HostStatus {
+            profile_name: profiles::active_profile(),
+            last_exit: gateway_exit::last_exit(),
+            login_shell_path: path_env::login_path().map(str::to_string),
+            install_issues: install_repair::last_issues(),
+            notices: notices::relevant(),
+            dependencies: dependencies::check_configured(),
+            token_invalid_since: token_health::invalid_since(),
+            compatibility: compatibility::last_report(),
+            power: power::state(),
+            gateway_log_level: gateway_log_level::status(),
+            safety_mode: safety_mode::status(),
+        }
+    }
+}
+
+/// Scripted stand-ins for the traits, recording what `Core` asked of them
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::*;
+    use crate::gateway_log_level::GatewayLogLevelStatus;
+    use crate::logs::LogLevel;
+    use crate::power::PowerSource;
+    use crate::safety_mode::SafetyMode;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    pub(crate) struct MockRunner {
+        pub running: Mutex<bool>,
+        /// Returned for each `openclaw` invocation, by joined args
+        pub outputs: Mutex<Vec<(String, Result<CommandOutput, String>)>>,
+        /// Whether there is an owned child for `kill_gateway` to kill
+        pub owns_child: Mutex<bool>,
+        pub spawn_error: Mutex<Option<String>>,
+        pub installed: Mutex<bool>,
+        pub owner: Mutex<Option<PortOwner>>,
+        pub calls: Mutex<Vec<String>>,
+    }
+
+    impl MockRunner {
+        pub(crate) fn respond(&self, args: &str, output: Result<CommandOutput, String>) {
+            self.outputs
+                .lock()
+                .unwrap()
+                .push((args.to_string(), output));
+        }
+
+        pub(crate) fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+
+        fn call(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    impl ProcessRunner for MockRunner {
+        fn run_openclaw(&self, args: &[&str], _timeout: Duration) -> Result<CommandOutput, String> {
+            let joined = args.join(" ");
+            self.call(format!("openclaw {}", joined));
+            self.outputs
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(a, _)| *a == joined)
+                .map(|(_, output)| output.clone())
+                .unwrap_or_else(|| Err("openclaw: not found".to_string()))
+        }
+
+        fn spawn_gateway(
+            &self,
+            port: u16,
+            _log_path: &Path,
+            _error_log_path: &Path,
+        ) -> Result<(), String> {
+            self.call(format!("spawn {}", port));
+            if let Some(e) = self.spawn_error.lock().unwrap().clone() {
+                return Err(e);
+            }
+            *self.running.lock().unwrap() = true;
+            *self.owns_child.lock().unwrap() = true;
+            Ok(())
+        }
+
+        fn kill_gateway(&self) -> Result<bool, String> {
+            self.call("kill".to_string());
+            let owned = std::mem::take(&mut *self.owns_child.lock().unwrap());
+            if owned {
+                *self.running.lock().unwrap() = false;
+            }
+            Ok(owned)
+        }
+
+        fn gateway_running(&self) -> bool {
+            *self.running.lock().unwrap()
+        }
+
+        fn openclaw_installed(&self) -> bool {
+            *self.installed.lock().unwrap()
+        }
+
+        fn port_owner(&self, port: u16) -> Option<PortOwner> {
+            self.owner
+                .lock()
+                .unwrap()
+                .clone()
+                .filter(|o| o.port == port)
+        }
+
+        fn foreign_owner(&self, port: u16) -> Option<PortOwner> {
+            self.port_owner(port).filter(|o| !o.is_gateway)
+        }
+
+        fn service_status(&self) -> GatewayServiceStatus {
+            GatewayServiceStatus {
+                kind: "systemd".to_string(),
+                installed: false,
+                running: false,
+                definition_path: None,
+            }
+        }
+    }
+
+    #[derive(Default)]
+    pub(crate) struct MockFileSystem {
+        pub files: Mutex<HashMap<PathBuf, FileStat>>,
+        pub truncated: Mutex<Vec<PathBuf>>,
+    }
+
+    impl MockFileSystem {
+        pub(crate) fn write(&self, path: &str, len: u64, modified: SystemTime) {
+            self.files
+                .lock()
+                .unwrap()
+                .insert(PathBuf::from(path), FileStat { len, modified });
+        }
+    }
+
+    impl FileSystem for MockFileSystem {
+        fn exists(&self, path: &Path) -> bool {
+            self.files.lock().unwrap().contains_key(path)
+        }
+
+        fn stat(&self, path: &Path) -> Option<FileStat> {
+            self.files.lock().unwrap().get(path).copied()
+        }
+
+        fn truncate(&self, path: &Path) -> Result<(), String> {
+            self.truncated.lock().unwrap().push(path.to_path_buf());
+            Ok(())
+        }
+    }
+
+    pub(crate) struct FixedClock(pub SystemTime);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> SystemTime {
+            self.0
+        }
+    }
+
+    pub(crate) const NOW: u64 = 1_700_000_000;
+
+    pub(crate) struct MockLease(Arc<Mutex<Vec<String>>>);
+
+    impl StartLease for MockLease {
+        fn release_when_ready(self: Box<Self>) {
+            self.0
+                .lock()
+                .unwrap()
+                .push("release when ready".to_string());
+        }
+    }
+
+    #[derive(Default)]
+    pub(crate) struct MockHost {
+        pub remote: Mutex<bool>,
+        pub socket: Mutex<bool>,
+        /// Someone else holds the start lock
+        pub locked_by: Mutex<Option<StartLock>>,
+        /// Failed pre-start checks, by message
+        pub failing_checks: Mutex<Vec<String>>,
+        /// Snapshots, audits and supervisor updates, in order
+        pub calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MockHost {
+        pub(crate) fn calls(&self) -> Vec<String> {
+            self.calls.lock().unwrap().clone()
+        }
+
+        fn call(&self, call: String) {
+            self.calls.lock().unwrap().push(call);
+        }
+    }
+
+    impl Host for MockHost {
+        fn port(&self) -> u16 {
+            18789
+        }
+
+        fn dashboard_url(&self) -> String {
+            "http://127.0.0.1:18789/".to_string()
+        }
+
+        fn uses_socket(&self) -> bool {
+            *self.socket.lock().unwrap()
+        }
+
+        fn ensure_local(&self) -> Result<(), String> {
+            match *self.remote.lock().unwrap() {
+                true => Err("Not available for a remote gateway".to_string()),
+                false => Ok(()),
+            }
+        }
+
+        fn acquire_start_lock(&self) -> Result<Box<dyn StartLease>, StartLock> {
+            match self.locked_by.lock().unwrap().clone() {
+                Some(lock) => Err(lock),
+                None => Ok(Box::new(MockLease(self.calls.clone()))),
+            }
+        }
+
+        fn preflight(&self) -> EnvironmentValidation {
+            let checks: Vec<_> = self
+                .failing_checks
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|message| crate::preflight::EnvironmentCheck {
+                    id: crate::preflight::EnvironmentCheckId::Port,
+                    passed: false,
+                    message: message.clone(),
+                })
+                .collect();
+            EnvironmentValidation {
+                ok: checks.is_empty(),
+                checks,
+            }
+        }
+
+        fn snapshot(&self, action: &str) {
+            self.call(format!("snapshot {}", action));
+        }
+
+        fn audit(&self, action: &str, target: &str, error: Option<&str>) {
+            match error {
+                Some(error) => self.call(format!("audit {} {}: {}", action, target, error)),
+                None => self.call(format!("audit {} {}", action, target)),
+            }
+        }
+
+        fn start_requested(&self) {}
+
+        fn set_desired_running(&self, running: bool) {
+            self.call(format!("desired running {}", running));
+        }
+
+        fn stop_requested(&self) {
+            self.call("stop requested".to_string());
+        }
+
+        fn restarted(&self, port: u16) {
+            self.call(format!("restarted {}", port));
+        }
+
+        fn status(&self) -> HostStatus {
+            HostStatus {
+                profile_name: Some("work".to_string()),
+                last_exit: None,
+                login_shell_path: None,
+                install_issues: Vec::new(),
+                notices: Vec::new(),
+                dependencies: Vec::new(),
+                token_invalid_since: None,
+                compatibility: None,
+                power: PowerState {
+                    source: PowerSource::Ac,
+                    battery_percent: None,
+                    low_battery: false,
+                    throttled: None,
+                },
+                gateway_log_level: GatewayLogLevelStatus {
+                    configured: LogLevel::Info,
+                    running: None,
+                    applied_live: false,
+                },
+                safety_mode: SafetyModeStatus {
+                    configured: SafetyMode::default(),
+                    running: None,
+                },
+            }
+        }
+    }
+
+    pub(crate) fn core(
+        runner: &Arc<MockRunner>,
+        fs: &Arc<MockFileSystem>,
+        host: &Arc<MockHost>,
+    ) -> Core {
+        Core::new(
+            runner.clone(),
+            fs.clone(),
+            Arc::new(FixedClock(UNIX_EPOCH + Duration::from_secs(NOW))),
+            host.clone(),
+            (
+                PathBuf::from("/logs/gateway.log"),
+                PathBuf::from("/logs/gateway_error.log"),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::{core, MockFileSystem, MockHost, MockRunner, NOW};
+    use super::*;
+
+    fn ok(stdout: &str) -> Result<CommandOutput, String> {
+        Ok(CommandOutput {
+            success: true,
+            stdout: stdout.to_string(),
+            stderr: String::new(),
+        })
+    }
+
+    fn failed(stderr: &str) -> Result<CommandOutput, String> {
+        Ok(CommandOutput {
+            success: false,
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+        })
+    }
+
+    fn at(secs_ago: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(NOW - secs_ago)
+    }
+
+    fn foreign(port: u16) -> PortOwner {
+        PortOwner {
+            port,
+            pid: 4242,
+            name: Some("nginx".to_string()),
+            command_line: None,
+            is_gateway: false,
+        }
+    }
+
+    fn setup() -> (Arc<MockRunner>, Arc<MockFileSystem>, Arc<MockHost>, Core) {
+        let runner = Arc::new(MockRunner::default());
+        let fs = Arc::new(MockFileSystem::default());
+        let host = Arc::new(MockHost::default());
+        let core = core(&runner, &fs, &host);
+        (runner, fs, host, core)
+    }
+
+    #[test]
+    fn start_spawns_when_stopped() {
+        let (runner, _, host, core) = setup();
+        assert_eq!(core.start(false), Ok(StartAction::Spawned));
+        assert_eq!(runner.calls(), vec!["spawn 18789"]);
+        assert_eq!(
+            host.calls(),
+            vec![
+                "snapshot start",
+                "audit gateway_start 18789",
+                "release when ready",
+                "desired running true"
+            ]
+        );
+        assert!(core.is_running());
+        assert_eq!(core.last_started_at(), Some(NOW));
+    }
+
+    #[test]
+    fn start_leaves_a_running_gateway_alone() {
+        let (runner, _, host, core) = setup();
+        *runner.running.lock().unwrap() = true;
+        host.failing_checks
+            .lock()
+            .unwrap()
+            .push("Port 18789 is in use".to_string());
+        assert_eq!(core.start(false), Ok(StartAction::AlreadyRunning));
+        assert!(runner.calls().is_empty());
+        assert_eq!(host.calls(), vec!["desired running true"]);
+        assert_eq!(core.last_started_at(), None);
+    }
+
+    #[test]
+    fn start_waits_for_a_start_in_progress() {
+        let (runner, _, host, core) = setup();
+        let lock = StartLock {
+            pid: 1234,
+            since: NOW,
+        };
+        *host.locked_by.lock().unwrap() = Some(lock.clone());
+        assert_eq!(core.start(true), Ok(StartAction::AlreadyStarting(lock)));
+        assert!(runner.calls().is_empty());
+        assert!(host.calls().is_empty());
+    }
+
+    #[test]
+    fn preflight_blocks_unless_forced() {
+        let (runner, _, host, core) = setup();
+        host.failing_checks
+            .lock()
+            .unwrap()
+            .push("Config does not parse".to_string());
+        let blocked = core.start(false).unwrap();
+        assert!(matches!(&blocked, StartAction::Blocked(v) if !v.ok));
+        assert_eq!(
+            blocked.into_message(),
+            Err("Gateway not started: Config does not parse".to_string())
+        );
+        assert!(runner.calls().is_empty());
+
+        assert_eq!(core.start(true), Ok(StartAction::Spawned));
+        assert_eq!(runner.calls(), vec!["spawn 18789"]);
+    }
+
+    #[test]
+    fn start_refuses_a_port_held_by_another_program() {
+        let (runner, _, host, core) = setup();
+        *runner.owner.lock().unwrap() = Some(foreign(18789));
+        let message = "Port 18789 is in use by nginx (pid 4242)".to_string();
+        assert_eq!(core.start(true), Err(message.clone()));
+        assert!(runner.calls().is_empty());
+        assert_eq!(
+            host.calls(),
+            vec![format!("audit gateway_start 18789: {}", message)]
+        );
+
+        // A socket transport doesn't need the port
+        *host.socket.lock().unwrap() = true;
+        assert_eq!(core.start(true), Ok(StartAction::Spawned));
+    }
+
+    #[test]
+    fn start_reports_spawn_failures() {
+        let (runner, _, host, core) = setup();
+        *runner.spawn_error.lock().unwrap() = Some("Failed to start gateway".to_string());
+        assert_eq!(core.start(true), Err("Failed to start gateway".to_string()));
+        assert!(!core.is_running());
+        assert_eq!(core.last_started_at(), None);
+        assert!(!host
+            .calls()
+            .iter()
+            .any(|c| c.starts_with("desired running")));
+    }
+
+    #[test]
+    fn remote_connections_are_refused() {
+        let (runner, _, host, core) = setup();
+        *host.remote.lock().unwrap() = true;
+        assert!(core.start(true).is_err());
+        assert!(core.stop().is_err());
+        assert!(core.restart().is_err());
+        assert!(runner.calls().is_empty());
+        assert!(host.calls().is_empty());
+    }
+
+    #[test]
+    fn stop_prefers_the_daemon() {
+        let (runner, _, host, core) = setup();
+        runner.respond("daemon stop", ok(""));
+        assert_eq!(core.stop(), Ok("Gateway stop command sent".to_string()));
+        assert_eq!(runner.calls(), vec!["openclaw daemon stop"]);
+        assert_eq!(
+            host.calls(),
+            vec![
+                "snapshot stop",
+                "desired running false",
+                "stop requested",
+                "audit gateway_stop 18789"
+            ]
+        );
+    }
+
+    #[test]
+    fn stop_falls_back_to_killing_our_child() {
+        let (runner, _, _, core) = setup();
+        core.start(true).unwrap();
+        runner.respond("daemon stop", failed("no daemon installed"));
+        assert_eq!(core.stop(), Ok("Gateway process stopped".to_string()));
+        assert!(!core.is_running());
+        assert_eq!(
+            runner.calls(),
+            vec!["spawn 18789", "openclaw daemon stop", "kill"]
+        );
+    }
+
+    #[test]
+    fn stop_without_daemon_or_child_reports_the_daemon_error() {
+        let (runner, _, host, core) = setup();
+        runner.respond("daemon stop", failed("no daemon installed"));
+        assert_eq!(core.stop(), Err("no daemon installed".to_string()));
+        assert_eq!(
+            host.calls().last().map(String::as_str),
+            Some("audit gateway_stop 18789: no daemon installed")
+        );
+    }
+
+    #[test]
+    fn restart_respawns_our_child_without_a_daemon() {
+        let (runner, _, host, core) = setup();
+        core.start(true).unwrap();
+        assert_eq!(core.restart(), Ok("Gateway restarting...".to_string()));
+        assert!(core.is_running());
+        assert_eq!(
+            runner.calls(),
+            vec![
+                "spawn 18789",
+                "openclaw daemon restart",
+                "kill",
+                "spawn 18789"
+            ]
+        );
+        assert_eq!(
+            host.calls().last().map(String::as_str),
+            Some("restarted 18789")
+        );
+    }
+
+    #[test]
+    fn version_needs_a_successful_run() {
+        let (runner, _, _, core) = setup();
+        assert_eq!(core.version(), None);
+        runner.respond("--version", ok("2026.3.1"));
+        assert_eq!(core.version(), Some("2026.3.1".to_string()));
+    }
+
+    #[test]
+    fn diagnostics_report_the_gateway_and_its_surroundings() {
+        let (runner, _, _, core) = setup();
+        *runner.installed.lock().unwrap() = true;
+        *runner.owner.lock().unwrap() = Some(foreign(18789));
+        runner.respond("--version", ok("2026.3.1"));
+        core.start(true).unwrap_err();
+        *runner.owner.lock().unwrap() = None;
+        core.start(true).unwrap();
+
+        let diagnostics = core.diagnostics();
+        assert!(diagnostics.openclaw_installed);
+        assert!(diagnostics.gateway_running);
+        assert_eq!(diagnostics.gateway_port, 18789);
+        assert_eq!(diagnostics.openclaw_version.as_deref(), Some("2026.3.1"));
+        assert_eq!(diagnostics.profile_name.as_deref(), Some("work"));
+        assert_eq!(diagnostics.error_log_path, "/logs/gateway_error.log");
+        assert_eq!(diagnostics.gateway_service.kind, "systemd");
+        assert!(diagnostics.port_owner.is_none());
+        assert_eq!(diagnostics.last_started_at, Some(NOW));
+    }
+
+    #[test]
+    fn diagnostics_without_the_cli() {
+        let (runner, _, _, core) = setup();
+        *runner.owner.lock().unwrap() = Some(foreign(18789));
+        let diagnostics = core.diagnostics();
+        assert!(!diagnostics.openclaw_installed);
+        assert!(!diagnostics.gateway_running);
+        assert_eq!(diagnostics.openclaw_version, None);
+        assert_eq!(diagnostics.port_owner.map(|o| o.pid), Some(4242));
+        assert_eq!(diagnostics.last_started_at, None);
+        assert_eq!(runner.calls(), vec!["openclaw --version"]);
+    }
+
+    #[test]
+    fn recent_errors_need_a_fresh_non_empty_error_log() {
+        let (_, fs, _, core) = setup();
+        assert!(!core.diagnostics().has_recent_errors);
+
+        fs.write("/logs/gateway_error.log", 0, at(5));
+        assert!(!core.diagnostics().has_recent_errors);
+
+        fs.write("/logs/gateway_error.log", 120, at(5));
+        assert!(core.diagnostics().has_recent_errors);
+
+        fs.write("/logs/gateway_error.log", 120, at(60 * 60));
+        assert!(!core.diagnostics().has_recent_errors);
+    }
+
+    #[test]
+    fn clear_logs_only_touches_existing_logs() {
+        let (_, fs, _, core) = setup();
+        core.clear_logs().unwrap();
+        assert!(fs.truncated.lock().unwrap().is_empty());
+
+        fs.write("/logs/gateway.log", 10, at(0));
+        core.clear_logs().unwrap();
+        assert_eq!(
+            *fs.truncated.lock().unwrap(),
+            vec![PathBuf::from("/logs/gateway.log")]
+        );
+    }
+
+    #[test]
+    fn control_result_messages() {
+        assert_eq!(
+            control_result("stop", ok("stopped").unwrap()),
+            Ok("stopped".to_string())
+        );
+        assert_eq!(
+            control_result("stop", failed("").unwrap()),
+            Err("openclaw gateway stop failed".to_string())
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Extra environment for the gateway process, per profile.
+//!
+//! The list of variables lives in settings. Values marked secret (and any whose
+//! name looks like a key or token) are kept in the keychain instead and only
+//! ever shown masked.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::process::Command;
+
+use crate::error::DesktopError;
+use crate::profiles::{self, active_profile};
+use crate::{kiosk, secrets, settings};
+
+const DEFAULT_PROFILE: &str = "default";
+/// Set by the app itself when it spawns the gateway
+const RESERVED: &[&str] = &[
+    "OPENCLAW_CONFIG_PATH",
+    "OPENCLAW_GATEWAY_TOKEN",
+    "OPENCLAW_TRACE_FILE",
+];
+const SECRET_MARKERS: &[&str] = &["KEY", "TOKEN", "SECRET", "PASSWORD", "CREDENTIAL"];
+
+/// One variable as stored in settings; a secret's value is in the keychain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewayEnvVar {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default)]
+    pub secret: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GatewayEnvInfo {
+    pub name: String,
+    pub secret: bool,
+    /// Plain value; `None` for secrets
+    pub value: Option<String>,
+    /// Last characters of a secret, e.g. `…f3a9`
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GatewayEnvInput {
+    pub name: String,
+    /// `None` keeps the stored secret
+    pub value: Option<String>,
+    #[serde(default)]
+    pub secret: bool,
+}
+
+/// Settings key for a profile, `default` when none is active
+pub(crate) fn profile_key(profile: Option<String>) -> Result<String, String> {
+    match profile.or_else(active_profile) {
+        Some(name) if name != DEFAULT_PROFILE => {
+            profiles::validate_profile_name(&name)?;
+            Ok(name)
+        }
+        _ => Ok(DEFAULT_PROFILE.to_string()),
+    }
+}
+
+fn looks_secret(name: &str) -> bool {
+    let upper = name.to_ascii_uppercase();
+    SECRET_MARKERS.iter().any(|marker| upper.contains(marker))
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!(
+            "Invalid variable name '{}': use letters, numbers and '_'",
+            name
+        ));
+    }
+    if RESERVED.iter().any(|r| r.eq_ignore_ascii_case(name)) {
+        return Err(format!(
+            "{} is set by the app and can't be overridden",
+            name
+        ));
+    }
+    Ok(())
+}
+
+fn hint(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 8 {
+        return "…".to_string();
+    }
+    format!("…{}", chars[chars.len() - 4..].iter().collect::<String>())
+}
+
+fn info(profile: &str, var: &GatewayEnvVar) -> GatewayEnvInfo {
+    GatewayEnvInfo {
+        name: var.name.clone(),
+        secret: var.secret,
+        value: (!var.secret).then(|| var.value.clone().unwrap_or_default()),
+        hint: var
+            .secret
+            .then(|| secrets::gateway_env_secret(profile, &var.name))
+            .flatten()
+            .map(|value| hint(&value)),
+    }
+}
+
+/// Add the active profile's variables to a gateway about to be spawned; they
+/// win over the app's own environment
+pub(crate) fn apply(command: &mut Command) {
+    let profile = active_profile().unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+    let Some(vars) = settings::current().gateway_env.remove(&profile) else {
+        return;
+    };
+    for var in vars {
+        let value = if var.secret {
+            secrets::gateway_env_secret(&profile, &var.name)
+        } else {
+            var.value
+        };
+        match value {
+            Some(value) => {
+                command.env(&var.name, value);
+            }
+            None => log_error!("No value stored for gateway variable {}", var.name),
+        }
+    }
+}
+
+/// Environment overrides for a profile's gateway, secrets masked. `profile`
+/// defaults to the active one.
+#[tauri::command]
+pub fn get_gateway_env(profile: Option<String>) -> Result<Vec<GatewayEnvInfo>, DesktopError> {
+    let profile = profile_key(profile)?;
+    let vars = settings::current()
+        .gateway_env
+        .remove(&profile)
+        .unwrap_or_default();
+    Ok(vars.iter().map(|var| info(&profile, var)).collect())
+}
+
+/// Replace a profile's environment overrides. They apply the next time the
+/// gateway starts.
+#[tauri::command]
+pub fn set_gateway_env(
+    profile: Option<String>,
+    vars: Vec<GatewayEnvInput>,
+) -> Result<Vec<GatewayEnvInfo>, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let profile = profile_key(profile)?;
+
+    let mut seen = HashSet::new();
+    let mut stored = Vec::with_capacity(vars.len());
+    for var in vars {
+        let name = var.name.trim().to_string();
+        validate_name(&name)?;
+        if !seen.insert(name.clone()) {
+            return Err(DesktopError::InvalidInput(format!(
+                "{} is listed more than once",
+                name
+            )));
+        }
+        let secret = var.secret || looks_secret(&name);
+        if secret {
+            match &var.value {
+                Some(value) => secrets::set_gateway_env_secret(&profile, &name, Some(value))?,
+                None if secrets::gateway_env_secret(&profile, &name).is_none() => {
+                    return Err(DesktopError::InvalidInput(format!(
+                        "{} needs a value",
+                        name
+                    )));
+                }
+                None => {}
+            }
+        }
+        stored.push(GatewayEnvVar {
+            name,
+            value: if secret { None } else { var.value },
+            secret,
+        });
+    }
+
+    // Secrets that were removed or are no longer secret leave the keychain
+    let previous = settings::current()
+        .gateway_env
+        .remove(&profile)
+        .unwrap_or_default();
+    for old in previous.iter().filter(|old| old.secret) {
+        if !stored.iter().any(|v| v.secret && v.name == old.name) {
+            secrets::set_gateway_env_secret(&profile, &old.name, None)?;
+        }
+    }
+
+    let saved = settings::update(|s| {
+        if stored.is_empty() {
+            s.gateway_env.remove(&profile);
+        } else {
+            s.gateway_env.insert(profile.clone(), stored);
+        }
+    })?;
+    Ok(saved
+        .gateway_env
+        .get(&profile)
+        .map(|vars| vars.iter().map(|var| info(&profile, var)).collect())
+        .unwrap_or_default())
+}
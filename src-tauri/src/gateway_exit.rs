@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::process::ExitStatus;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use ts_rs::TS;
+
+use crate::gateway_log_paths;
+
+/// How much of each log file is inspected when classifying an exit
+const LOG_TAIL_BYTES: u64 = 64 * 1024;
+
+const PORT_CONFLICT_PATTERNS: [&str; 3] = [
+    "eaddrinuse",
+    "address already in use",
+    "only one usage of each socket address",
+];
+
+const OOM_PATTERNS: [&str; 4] = [
+    "heap out of memory",
+    "out of memory",
+    "allocation failed",
+    "oom-kill",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum ExitReason {
+    CleanStop,
+    Crashed,
+    Signaled,
+    OomKilled,
+    PortConflict,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct ExitInfo {
+    pub reason: ExitReason,
+    pub code: Option<i32>,
+    pub signal: Option<i32>,
+    pub detail: Option<String>,
+    #[ts(type = "number")]
+    pub exited_at: u64,
+}
+
+static LAST_EXIT: Mutex<Option<ExitInfo>> = Mutex::new(None);
+static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// The most recent gateway exit observed by this app instance
+pub fn last_exit() -> Option<ExitInfo> {
+    LAST_EXIT.lock().ok().and_then(|guard| guard.clone())
+}
+
+/// Mark the next exit as user-initiated so it is not reported as a crash
+pub fn mark_stop_requested() {
+    STOP_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+fn record_exit(info: ExitInfo) {
+    if let Ok(mut guard) = LAST_EXIT.lock() {
+        *guard = Some(info);
+    }
+}
+
+fn read_log_tail(path: &Path) -> String {
+    let Ok(mut file) = File::open(path) else {
+        return String::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let start = len.saturating_sub(LOG_TAIL_BYTES);
+    if file.seek(SeekFrom::Start(start)).is_err() {
+        return String::new();
+    }
+    let mut buf = Vec::new();
+    if file.read_to_end(&mut buf).is_err() {
+        return String::new();
+    }
+    String::from_utf8_lossy(&buf).to_lowercase()
+}
+
+/// Find the last log line containing any of the given patterns
+fn find_matching_line(logs: &str, patterns: &[&str]) -> Option<String> {
+    logs.lines()
+        .rev()
+        .find(|line| patterns.iter().any(|p| line.contains(p)))
+        .map(|line| line.trim().to_string())
+}
+
+#[cfg(unix)]
+fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+/// Combine the exit status, stop intent, and log contents into a termination reason
+fn classify_exit(status: &ExitStatus, stop_requested: bool) -> ExitInfo {
+    let code = status.code();
+    let signal = exit_signal(status);
+
+    let logs = match gateway_log_paths() {
+        Ok((log_path, error_log_path)) => {
+            format!(
+                "{}\n{}",
+                read_log_tail(&log_path),
+                read_log_tail(&error_log_path)
+            )
+        }
+        Err(_) => String::new(),
+    };
+
+    let (reason, detail) = if stop_requested || status.success() {
+        (ExitReason::CleanStop, None)
+    } else if let Some(line) = find_matching_line(&logs, &PORT_CONFLICT_PATTERNS) {
+        (ExitReason::PortConflict, Some(line))
+    } else if let Some(line) = find_matching_line(&logs, &OOM_PATTERNS) {
+        (ExitReason::OomKilled, Some(line))
+    } else if signal == Some(9) {
+        // SIGKILL with no other explanation is almost always the kernel OOM killer
+        (
+            ExitReason::OomKilled,
+            Some("Killed by SIGKILL (likely out of memory)".to_string()),
+        )
+    } else if let Some(sig) = signal {
+        (
+            ExitReason::Signaled,
+            Some(format!("Terminated by signal {}", sig)),
+        )
+    } else {
+        (
+            ExitReason::Crashed,
+            code.map(|c| format!("Exited with code {}", c)),
+        )
+    };
+
+    ExitInfo {
+        reason,
+        code,
+        signal,
+        detail,
+        exited_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    }
+}
+
+/// Clear a stale stop request before tracking a newly spawned gateway
+pub fn reset_stop_requested() {
+    STOP_REQUESTED.store(false, Ordering::SeqCst);
+}
+
+/// Classify and record the exit of the tracked gateway child
+pub fn record_child_exit(status: &ExitStatus) -> ExitInfo {
+    let stop_requested = STOP_REQUESTED.swap(false, Ordering::SeqCst);
+    let info = classify_exit(status, stop_requested);
+    record_exit(info.clone());
+    info
+}
@@ -0,0 +1,124 @@
+//! How verbosely the gateway logs. The level is passed as a flag when the
+//! gateway starts, and gateways with the log-level endpoint switch it live.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::State;
+
+use crate::error::DesktopError;
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+use crate::logs::LogLevel;
+use crate::{audit, kiosk, settings};
+
+const LOG_LEVEL_PATH: &str = "/api/log-level";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GatewayLogSettings {
+    pub level: LogLevel,
+}
+
+impl Default for GatewayLogSettings {
+    fn default() -> Self {
+        // What `--verbose` gave before the level was configurable
+        Self {
+            level: LogLevel::Debug,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayLogLevelStatus {
+    /// Used the next time the gateway starts
+    pub configured: LogLevel,
+    /// Level the running gateway was started with or last switched to
+    pub running: Option<LogLevel>,
+    /// The running gateway picked up the change without a restart
+    pub applied_live: bool,
+}
+
+static RUNNING: Mutex<Option<LogLevel>> = Mutex::new(None);
+
+fn set_running(level: Option<LogLevel>) {
+    if let Ok(mut running) = RUNNING.lock() {
+        *running = level;
+    }
+}
+
+fn flag_value(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Trace => "trace",
+        LogLevel::Debug => "debug",
+        LogLevel::Info => "info",
+        LogLevel::Warn => "warn",
+        LogLevel::Error => "error",
+        LogLevel::Fatal => "fatal",
+    }
+}
+
+/// Add the configured level to an `openclaw gateway` command about to be spawned
+pub(crate) fn apply(command: &mut Command) {
+    let level = settings::current().gateway_log.level;
+    match level {
+        // Older gateways only know `--verbose`, which is debug
+        LogLevel::Debug => command.arg("--verbose"),
+        level => command.args(["--log-level", flag_value(level)]),
+    };
+    set_running(Some(level));
+}
+
+/// Forget the running level once the gateway process is gone
+pub(crate) fn clear() {
+    set_running(None);
+}
+
+pub(crate) fn status() -> GatewayLogLevelStatus {
+    GatewayLogLevelStatus {
+        configured: settings::current().gateway_log.level,
+        running: RUNNING.lock().ok().and_then(|r| *r),
+        applied_live: false,
+    }
+}
+
+/// Save the gateway log level and switch the running gateway to it when it
+/// supports that; otherwise it applies on the next start
+#[tauri::command]
+pub async fn set_gateway_log_level(
+    api: State<'_, GatewayApi>,
+    level: LogLevel,
+) -> Result<GatewayLogLevelStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let saved = settings::update(|s| s.gateway_log.level = level);
+    audit::record_result("gateway_log_level", flag_value(level), &saved);
+    saved?;
+
+    let applied_live = match api
+        .put::<serde_json::Value>(
+            LOG_LEVEL_PATH,
+            &serde_json::json!({ "level": flag_value(level) }),
+        )
+        .await
+    {
+        Ok(_) => true,
+        Err(e)
+            if matches!(
+                e.kind,
+                GatewayApiErrorKind::NotFound | GatewayApiErrorKind::NotRunning
+            ) =>
+        {
+            false
+        }
+        Err(e) => {
+            log_error!("Failed to change gateway log level live: {}", e);
+            false
+        }
+    };
+    if applied_live {
+        set_running(Some(level));
+    }
+    Ok(GatewayLogLevelStatus {
+        applied_live,
+        ..status()
+    })
+}
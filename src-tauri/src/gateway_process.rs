@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::process::Child;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime, State};
+
+use crate::gateway_exit::{self, ExitInfo};
+use crate::gateways::kill_process_tree;
+use crate::supervisor;
+
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+struct TrackedChild {
+    child: Child,
+    started_at: Instant,
+    started_at_unix: u64,
+}
+
+/// The default gateway process spawned by this app, if any
+#[derive(Default)]
+pub struct GatewayProcess {
+    current: Mutex<Option<TrackedChild>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayProcessInfo {
+    /// Whether the app is holding a handle to a live gateway child
+    pub tracked: bool,
+    pub pid: Option<u32>,
+    pub started_at: Option<u64>,
+    pub uptime_secs: Option<u64>,
+    pub last_exit: Option<ExitInfo>,
+}
+
+impl GatewayProcess {
+    pub fn info(&self) -> GatewayProcessInfo {
+        let guard = self.current.lock().ok();
+        let tracked = guard.as_ref().and_then(|g| g.as_ref());
+        GatewayProcessInfo {
+            tracked: tracked.is_some(),
+            pid: tracked.map(|t| t.child.id()),
+            started_at: tracked.map(|t| t.started_at_unix),
+            uptime_secs: tracked.map(|t| t.started_at.elapsed().as_secs()),
+            last_exit: gateway_exit::last_exit(),
+        }
+    }
+
+    pub fn pid(&self) -> Option<u32> {
+        self.current
+            .lock()
+            .ok()
+            .and_then(|g| g.as_ref().map(|t| t.child.id()))
+    }
+
+    /// Kill the tracked child directly; returns `false` when nothing is tracked
+    pub fn kill(&self) -> Result<bool, String> {
+        let mut guard = self
+            .current
+            .lock()
+            .map_err(|_| "Gateway process lock poisoned".to_string())?;
+        let Some(mut tracked) = guard.take() else {
+            return Ok(false);
+        };
+        drop(guard);
+
+        gateway_exit::mark_stop_requested();
+        kill_process_tree(&mut tracked.child)?;
+        if let Ok(Some(status)) = tracked.child.try_wait() {
+            gateway_exit::record_child_exit(&status);
+        }
+        Ok(true)
+    }
+}
+
+/// Stop tracking the current child without killing it, handing it back to the caller
+pub(crate) fn detach<R: Runtime>(app: &AppHandle<R>) -> Option<Child> {
+    let state = app.state::<GatewayProcess>();
+    let mut guard = state.current.lock().ok()?;
+    guard.take().map(|tracked| tracked.child)
+}
+
+/// Take ownership of a freshly spawned gateway and watch it until it exits
+pub fn track<R: Runtime>(app: &AppHandle<R>, child: Child) {
+    let pid = child.id();
+    gateway_exit::reset_stop_requested();
+
+    let state = app.state::<GatewayProcess>();
+    if let Ok(mut guard) = state.current.lock() {
+        *guard = Some(TrackedChild {
+            child,
+            started_at: Instant::now(),
+            started_at_unix: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+        });
+    }
+
+    let app = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(EXIT_POLL_INTERVAL);
+
+        let state = app.state::<GatewayProcess>();
+        let Ok(mut guard) = state.current.lock() else {
+            return;
+        };
+        let Some(tracked) = guard.as_mut() else {
+            // Killed through `GatewayProcess::kill`, which records the exit itself
+            return;
+        };
+        if tracked.child.id() != pid {
+            return;
+        }
+
+        match tracked.child.try_wait() {
+            Ok(Some(status)) => {
+                guard.take();
+                drop(guard);
+                let exit = gateway_exit::record_child_exit(&status);
+                supervisor::on_gateway_exit(&app, &exit);
+                return;
+            }
+            Ok(None) => {}
+            Err(e) => {
+                log_error!("Failed to poll gateway process: {}", e);
+                return;
+            }
+        }
+    });
+}
+
+/// Get pid, uptime, and last exit status of the gateway process spawned by the app
+#[tauri::command]
+pub fn get_gateway_process_info(process: State<'_, GatewayProcess>) -> GatewayProcessInfo {
+    process.info()
+}
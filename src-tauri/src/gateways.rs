@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Child;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Manager, State};
+
+use crate::error::DesktopError;
+use crate::settings;
+use crate::{
+    active_gateway_port, gateway_log_paths, is_gateway_running, is_port_open,
+    spawn_gateway_process, start_default_gateway, stop_default_gateway,
+};
+use crate::{kiosk, paths};
+
+/// Name of the primary gateway driven by `start_gateway` / `stop_gateway`
+pub const DEFAULT_GATEWAY: &str = "default";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GatewayDefinition {
+    pub name: String,
+    pub port: u16,
+    /// Base URL of a gateway running on another machine; local instances leave it unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayInstanceInfo {
+    pub name: String,
+    pub port: u16,
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub uptime_secs: Option<u64>,
+    pub dashboard_url: String,
+    pub log_path: String,
+    pub error_log_path: String,
+}
+
+struct RunningInstance {
+    port: u16,
+    child: Child,
+    started_at: Instant,
+}
+
+/// Tracks additional gateway instances spawned by the app, keyed by name
+#[derive(Default)]
+pub struct GatewayManager {
+    instances: Mutex<HashMap<String, RunningInstance>>,
+}
+
+fn validate_instance_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Gateway name cannot be empty".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid gateway name '{}': use letters, numbers, '-' or '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+fn instance_log_paths(name: &str) -> Result<(PathBuf, PathBuf), String> {
+    if name == DEFAULT_GATEWAY {
+        return gateway_log_paths();
+    }
+    let dir = paths::log_dir()?.join("logs").join(name);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create log directory: {}", e))?;
+    Ok((dir.join("gateway.log"), dir.join("gateway_error.log")))
+}
+
+/// Terminate a spawned gateway including any wrapper shell children
+pub(crate) fn kill_process_tree(child: &mut Child) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // `cmd /c openclaw` leaves node as a grandchild, so kill the whole tree
+        let status = std::process::Command::new("taskkill")
+            .args(["/PID", &child.id().to_string(), "/T", "/F"])
+            .creation_flags(crate::CREATE_NO_WINDOW)
+            .status()
+            .map_err(|e| format!("Failed to run taskkill: {}", e))?;
+        if status.success() {
+            let _ = child.wait();
+            return Ok(());
+        }
+    }
+
+    child
+        .kill()
+        .map_err(|e| format!("Failed to kill gateway process: {}", e))?;
+    let _ = child.wait();
+    Ok(())
+}
+
+impl GatewayManager {
+    /// Drop instances whose process has already exited
+    fn prune_exited(instances: &mut HashMap<String, RunningInstance>) {
+        instances.retain(|_, instance| matches!(instance.child.try_wait(), Ok(None)));
+    }
+
+    fn info(&self, definition: &GatewayDefinition) -> Result<GatewayInstanceInfo, String> {
+        let (log_path, error_log_path) = instance_log_paths(&definition.name)?;
+        let instances = self
+            .instances
+            .lock()
+            .map_err(|_| "Gateway manager lock poisoned".to_string())?;
+        let tracked = instances.get(&definition.name);
+
+        // Remote gateways are not probed; their state comes from the API
+        let running = if definition.url.is_some() {
+            false
+        } else if definition.name == DEFAULT_GATEWAY {
+            is_gateway_running()
+        } else {
+            is_port_open(definition.port)
+        };
+
+        Ok(GatewayInstanceInfo {
+            name: definition.name.clone(),
+            port: definition.port,
+            running,
+            pid: tracked.map(|t| t.child.id()),
+            uptime_secs: tracked.map(|t| t.started_at.elapsed().as_secs()),
+            dashboard_url: definition
+                .url
+                .clone()
+                .unwrap_or_else(|| format!("http://127.0.0.1:{}/", definition.port)),
+            log_path: log_path.display().to_string(),
+            error_log_path: error_log_path.display().to_string(),
+        })
+    }
+
+    /// Spawn a named instance unless it is already tracked or its port is taken
+    pub(crate) fn spawn_instance(&self, definition: &GatewayDefinition) -> Result<(), String> {
+        let mut instances = self
+            .instances
+            .lock()
+            .map_err(|_| "Gateway manager lock poisoned".to_string())?;
+        Self::prune_exited(&mut instances);
+
+        if definition.url.is_some() {
+            return Err(format!(
+                "Gateway '{}' is remote and cannot be started here",
+                definition.name
+            ));
+        }
+        if instances.contains_key(&definition.name) || is_port_open(definition.port) {
+            return Ok(());
+        }
+
+        let (log_path, error_log_path) = instance_log_paths(&definition.name)?;
+        let child = spawn_gateway_process(definition.port, &log_path, &error_log_path, true)?;
+        instances.insert(
+            definition.name.clone(),
+            RunningInstance {
+                port: definition.port,
+                child,
+                started_at: Instant::now(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Kill every named instance started by this app
+    pub(crate) fn stop_all(&self) -> Result<(), String> {
+        let mut instances = self
+            .instances
+            .lock()
+            .map_err(|_| "Gateway manager lock poisoned".to_string())?;
+        for (_, mut instance) in instances.drain() {
+            kill_process_tree(&mut instance.child)?;
+        }
+        Ok(())
+    }
+
+    /// Kill a named instance started by this app, returning its port
+    pub(crate) fn stop_instance(&self, name: &str) -> Result<Option<u16>, String> {
+        let mut instances = self
+            .instances
+            .lock()
+            .map_err(|_| "Gateway manager lock poisoned".to_string())?;
+        match instances.remove(name) {
+            Some(mut instance) => {
+                kill_process_tree(&mut instance.child)?;
+                Ok(Some(instance.port))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+pub(crate) fn known_definitions() -> Vec<GatewayDefinition> {
+    let mut definitions = vec![GatewayDefinition {
+        name: DEFAULT_GATEWAY.to_string(),
+        port: active_gateway_port(),
+        url: None,
+    }];
+    definitions.extend(
+        settings::current()
+            .gateways
+            .into_iter()
+            .filter(|g| g.name != DEFAULT_GATEWAY),
+    );
+    definitions
+}
+
+/// List the default gateway plus every named instance from settings
+#[tauri::command]
+pub fn list_gateways(
+    manager: State<'_, GatewayManager>,
+) -> Result<Vec<GatewayInstanceInfo>, DesktopError> {
+    if let Ok(mut instances) = manager.instances.lock() {
+        GatewayManager::prune_exited(&mut instances);
+    }
+
+    let infos = known_definitions()
+        .iter()
+        .map(|definition| manager.info(definition))
+        .collect::<Result<_, String>>()?;
+    Ok(infos)
+}
+
+/// Start a named gateway, registering it in settings when a port is provided
+#[tauri::command]
+pub async fn start_gateway_named(
+    app: AppHandle,
+    name: String,
+    port: Option<u16>,
+) -> Result<GatewayInstanceInfo, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    validate_instance_name(&name)?;
+    tauri::async_runtime::spawn_blocking(move || start_named(&app, name, port))
+        .await
+        .map_err(|e| format!("Failed to start gateway: {}", e))?
+}
+
+fn start_named(
+    app: &AppHandle,
+    name: String,
+    port: Option<u16>,
+) -> Result<GatewayInstanceInfo, DesktopError> {
+    let manager = app.state::<GatewayManager>();
+    if name == DEFAULT_GATEWAY {
+        start_default_gateway(app)?;
+        return Ok(manager.info(&known_definitions()[0])?);
+    }
+
+    let existing = known_definitions().into_iter().find(|g| g.name == name);
+    let port = match (port, &existing) {
+        (Some(port), _) => port,
+        (None, Some(definition)) => definition.port,
+        (None, None) => {
+            return Err(DesktopError::InvalidInput(format!(
+                "Gateway '{}' is not configured; provide a port to create it",
+                name
+            )))
+        }
+    };
+
+    if let Some(conflict) = known_definitions()
+        .iter()
+        .find(|g| g.port == port && g.name != name)
+    {
+        return Err(DesktopError::InvalidInput(format!(
+            "Port {} is already assigned to gateway '{}'",
+            port, conflict.name
+        )));
+    }
+
+    if existing.as_ref().is_some_and(|g| g.url.is_some()) {
+        return Err(DesktopError::InvalidInput(format!(
+            "Gateway '{}' is remote and cannot be started here",
+            name
+        )));
+    }
+
+    let definition = GatewayDefinition {
+        name: name.clone(),
+        port,
+        url: None,
+    };
+    if existing.as_ref() != Some(&definition) {
+        settings::update(|s| {
+            s.gateways.retain(|g| g.name != name);
+            s.gateways.push(definition.clone());
+        })?;
+    }
+
+    manager.spawn_instance(&definition)?;
+    Ok(manager.info(&definition)?)
+}
+
+/// Stop a named gateway that was started by this app
+#[tauri::command]
+pub async fn stop_gateway_named(app: AppHandle, name: String) -> Result<String, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    tauri::async_runtime::spawn_blocking(move || stop_named(&app, &name))
+        .await
+        .map_err(|e| format!("Failed to stop gateway: {}", e))?
+}
+
+fn stop_named(app: &AppHandle, name: &str) -> Result<String, DesktopError> {
+    if name == DEFAULT_GATEWAY {
+        return Ok(stop_default_gateway(app)?);
+    }
+
+    match app.state::<GatewayManager>().stop_instance(name)? {
+        Some(port) => Ok(format!("Gateway '{}' on port {} stopped", name, port)),
+        None => Err(DesktopError::GatewayNotRunning(format!(
+            "Gateway '{}' is not running under this app",
+            name
+        ))),
+    }
+}
@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindowBuilder};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::error::DesktopError;
+use crate::{auto_lock, kiosk, settings, window};
+
+pub const QUICK_CHAT_WINDOW: &str = "quick-chat";
+
+/// The shortcut currently registered with the OS, as configured
+static REGISTERED: Mutex<Option<String>> = Mutex::new(None);
+
+/// What the global shortcut does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    /// Show and focus the main window
+    #[default]
+    ShowWindow,
+    /// Toggle a small always-on-top window that messages the default agent
+    QuickChat,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GlobalShortcutSettings {
+    /// Accelerator such as `CmdOrCtrl+Shift+Space`; no shortcut when unset
+    pub shortcut: Option<String>,
+    pub action: ShortcutAction,
+}
+
+fn parse(shortcut: &str) -> Result<Shortcut, String> {
+    shortcut
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", shortcut, e))
+}
+
+fn toggle_quick_chat<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(QUICK_CHAT_WINDOW) {
+        if window.is_visible().unwrap_or(false) {
+            let _ = window.hide();
+        } else {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        return Ok(());
+    }
+
+    let window = WebviewWindowBuilder::new(
+        app,
+        QUICK_CHAT_WINDOW,
+        WebviewUrl::App("index.html#quick-chat".into()),
+    )
+    .title("OpenClaw Quick Chat")
+    .inner_size(560.0, 140.0)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .center()
+    .build()
+    .map_err(|e| format!("Failed to open quick chat: {}", e))?;
+    let _ = window.set_focus();
+
+    // Behaves like a popover: clicking elsewhere dismisses it
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Focused(focused) = event {
+            auto_lock::on_focus_changed(*focused);
+            if !focused {
+                let _ = handle.hide();
+            }
+        }
+    });
+    Ok(())
+}
+
+fn on_pressed<R: Runtime>(app: &AppHandle<R>) {
+    match settings::current().global_shortcut.action {
+        ShortcutAction::ShowWindow => window::show_main_window(app),
+        ShortcutAction::QuickChat => {
+            if let Err(e) = toggle_quick_chat(app) {
+                log_error!("{}", e);
+            }
+        }
+    }
+}
+
+/// Swap the OS registration over to `shortcut`; unchanged shortcuts are left alone
+fn apply<R: Runtime>(app: &AppHandle<R>, shortcut: Option<&str>) -> Result<(), String> {
+    let mut registered = REGISTERED
+        .lock()
+        .map_err(|_| "Shortcut lock poisoned".to_string())?;
+    if registered.as_deref() == shortcut {
+        return Ok(());
+    }
+    let next = shortcut.map(parse).transpose()?;
+
+    let manager = app.global_shortcut();
+    if let Some(previous) = registered.take() {
+        if let Err(e) =
+            parse(&previous).and_then(|s| manager.unregister(s).map_err(|e| e.to_string()))
+        {
+            log_error!("Failed to unregister shortcut {}: {}", previous, e);
+        }
+    }
+    if let Some(next) = next {
+        manager
+            .on_shortcut(next, |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    on_pressed(app);
+                }
+            })
+            .map_err(|e| format!("Failed to register shortcut: {}", e))?;
+        *registered = shortcut.map(str::to_string);
+    }
+    Ok(())
+}
+
+/// Register the saved shortcut and follow later settings changes
+pub fn init<R: Runtime>(app: AppHandle<R>) {
+    let saved = settings::current().global_shortcut.shortcut;
+    if let Err(e) = apply(&app, saved.as_deref()) {
+        log_error!("{}", e);
+    }
+    let mut rx = settings::subscribe();
+    tauri::async_runtime::spawn(async move {
+        while rx.changed().await.is_ok() {
+            let shortcut = rx.borrow_and_update().global_shortcut.shortcut.clone();
+            if let Err(e) = apply(&app, shortcut.as_deref()) {
+                log_error!("{}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_global_shortcut() -> GlobalShortcutSettings {
+    settings::current().global_shortcut
+}
+
+/// Register a new shortcut (or remove it with `None`) and save it. The OS
+/// refuses shortcuts already taken by another app, and nothing is saved then.
+#[tauri::command]
+pub fn set_global_shortcut(
+    app: AppHandle,
+    shortcut: Option<String>,
+    action: ShortcutAction,
+) -> Result<GlobalShortcutSettings, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let shortcut = shortcut
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let previous = settings::current().global_shortcut.shortcut;
+    apply(&app, shortcut.as_deref())?;
+    let next = GlobalShortcutSettings { shortcut, action };
+    if let Err(e) = settings::update(|s| s.global_shortcut = next.clone()) {
+        let _ = apply(&app, previous.as_deref());
+        return Err(e.into());
+    }
+    Ok(next)
+}
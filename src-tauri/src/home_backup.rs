@@ -0,0 +1,315 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::availability::{self, Operation};
+use crate::error::DesktopError;
+use crate::{audit, backup, connection, disk_space, kiosk, openclaw_home_dir, paths, secrets};
+use crate::{is_gateway_running, start_default_gateway, stop_default_gateway};
+
+const MANIFEST_NAME: &str = "backup-manifest.json";
+
+/// Top-level entries of `~/.openclaw` left out: runtime state, bulky captures,
+/// and earlier backups
+const EXCLUDED_DIRS: &[&str] = &["run", "captures", "backups", "logs"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ManifestEntry {
+    path: String,
+    size: u64,
+    sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Manifest {
+    created_at: u64,
+    app_version: String,
+    files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HomeBackup {
+    pub path: String,
+    pub files: usize,
+    /// Size of the files before compression
+    pub bytes: u64,
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HomeRestore {
+    pub files: usize,
+    pub created_at: u64,
+    /// The gateway was stopped for the restore and started again
+    pub restarted_gateway: bool,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Logs, plus SQLite journals that are meaningless without the live connection
+fn is_skipped(name: &str) -> bool {
+    name.ends_with(".log")
+        || name.contains(".log.")
+        || [".db-wal", ".db-shm", ".db-journal"]
+            .iter()
+            .any(|suffix| name.ends_with(suffix))
+}
+
+/// Files to back up, relative to the home directory
+fn collect(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if dir == root && EXCLUDED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            collect(root, &path, files)?;
+        } else if file_type.is_file() && !is_skipped(&name) {
+            if let Ok(relative) = path.strip_prefix(root) {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Zip path with forward slashes so archives move between platforms
+fn archive_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn write_zip(root: &Path, files: &[PathBuf]) -> Result<(Vec<u8>, Manifest), String> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut manifest = Manifest {
+        created_at: now_secs(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        files: Vec::new(),
+    };
+    for relative in files {
+        // Files can disappear while the gateway runs; skip them rather than fail
+        let Ok(content) = fs::read(root.join(relative)) else {
+            continue;
+        };
+        let name = archive_name(relative);
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to add {}: {}", name, e))?;
+        zip.write_all(&content)
+            .map_err(|e| format!("Failed to write {}: {}", name, e))?;
+        manifest.files.push(ManifestEntry {
+            path: name,
+            size: content.len() as u64,
+            sha256: format!("{:x}", Sha256::digest(&content)),
+        });
+    }
+    let manifest_json = serde_json::to_vec_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    zip.start_file(MANIFEST_NAME, options)
+        .map_err(|e| format!("Failed to add manifest: {}", e))?;
+    zip.write_all(&manifest_json)
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+    let cursor = zip
+        .finish()
+        .map_err(|e| format!("Failed to finish backup: {}", e))?;
+    Ok((cursor.into_inner(), manifest))
+}
+
+fn passphrase_or_saved(passphrase: Option<String>) -> Option<String> {
+    passphrase
+        .filter(|p| !p.is_empty())
+        .or_else(secrets::backup_passphrase)
+}
+
+/// Zip `~/.openclaw` into `dest_path`, leaving out logs and runtime files.
+///
+/// With `encrypt`, the archive is sealed with `passphrase`, or the backup
+/// passphrase from the keychain when none is given.
+#[tauri::command]
+pub async fn create_backup(
+    dest_path: String,
+    encrypt: bool,
+    passphrase: Option<String>,
+) -> Result<HomeBackup, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = openclaw_home_dir()?;
+        let dest = PathBuf::from(&dest_path);
+        if !dest.is_absolute() {
+            return Err(DesktopError::InvalidInput(
+                "Backup path must be absolute".to_string(),
+            ));
+        }
+        if dest.starts_with(&root) {
+            return Err(DesktopError::InvalidInput(
+                "Backups can't be written inside the OpenClaw directory".to_string(),
+            ));
+        }
+
+        let mut files = Vec::new();
+        collect(&root, &root, &mut files)?;
+        let (mut content, manifest) = write_zip(&root, &files)?;
+        if encrypt {
+            let passphrase = passphrase_or_saved(passphrase)
+                .ok_or("Encryption needs a passphrase, and none is saved")?;
+            content = backup::encrypt(&content, &passphrase)?;
+        }
+
+        if let Some(dir) = dest.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Backup destination is unavailable: {}", e))?;
+            disk_space::preflight(dir, content.len() as u64, 1)?;
+        }
+        let tmp = dest.with_extension("tmp");
+        fs::write(&tmp, &content).map_err(|e| format!("Failed to write backup: {}", e))?;
+        fs::rename(&tmp, &dest).map_err(|e| format!("Failed to save backup: {}", e))?;
+
+        Ok(HomeBackup {
+            path: dest.display().to_string(),
+            files: manifest.files.len(),
+            bytes: manifest.files.iter().map(|f| f.size).sum(),
+            encrypted: encrypt,
+        })
+    })
+    .await
+    .map_err(|e| format!("Failed to create backup: {}", e))?
+}
+
+/// Relative path of a zip entry, refusing anything that would land outside the home directory
+fn safe_relative(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    paths::check_relative(path).ok().map(|_| path.to_path_buf())
+}
+
+/// Archive paths with their contents
+type BackupFiles = Vec<(PathBuf, Vec<u8>)>;
+
+/// Open the archive and check every file against the manifest before anything is written
+fn verify(data: Vec<u8>) -> Result<(Manifest, BackupFiles), String> {
+    let mut zip =
+        ZipArchive::new(Cursor::new(data)).map_err(|e| format!("Not a valid backup: {}", e))?;
+    let manifest: Manifest = {
+        let mut entry = zip
+            .by_name(MANIFEST_NAME)
+            .map_err(|_| "Backup has no manifest".to_string())?;
+        let mut text = String::new();
+        entry
+            .read_to_string(&mut text)
+            .map_err(|e| format!("Failed to read manifest: {}", e))?;
+        serde_json::from_str(&text).map_err(|e| format!("Failed to parse manifest: {}", e))?
+    };
+
+    let mut files = Vec::with_capacity(manifest.files.len());
+    for expected in &manifest.files {
+        let relative = safe_relative(&expected.path)
+            .ok_or_else(|| format!("Backup contains an unsafe path: {}", expected.path))?;
+        let mut entry = zip
+            .by_name(&expected.path)
+            .map_err(|_| format!("Backup is missing {}", expected.path))?;
+        let mut content = Vec::with_capacity(expected.size as usize);
+        entry
+            .read_to_end(&mut content)
+            .map_err(|e| format!("Failed to read {}: {}", expected.path, e))?;
+        if format!("{:x}", Sha256::digest(&content)) != expected.sha256 {
+            return Err(format!("{} is corrupted in the backup", expected.path));
+        }
+        files.push((relative, content));
+    }
+    Ok((manifest, files))
+}
+
+fn write_files(root: &Path, files: &[(PathBuf, Vec<u8>)]) -> Result<(), String> {
+    for (relative, content) in files {
+        let path = paths::join_within(root, relative)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let tmp = path.with_extension("restore-tmp");
+        let mut file = File::create(&tmp)
+            .map_err(|e| format!("Failed to restore {}: {}", relative.display(), e))?;
+        file.write_all(content)
+            .map_err(|e| format!("Failed to restore {}: {}", relative.display(), e))?;
+        drop(file);
+        fs::rename(&tmp, &path)
+            .map_err(|e| format!("Failed to restore {}: {}", relative.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Restore `~/.openclaw` from a backup made by `create_backup`.
+///
+/// The whole archive is verified first; the local gateway is then stopped while
+/// files are replaced and started again afterwards. Files not in the backup are left alone.
+#[tauri::command]
+pub async fn restore_backup(
+    app: AppHandle,
+    src_path: String,
+    passphrase: Option<String>,
+) -> Result<HomeRestore, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let _operation = availability::begin(Operation::Restore)?;
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let data = fs::read(&src_path).map_err(|e| format!("Failed to read backup: {}", e))?;
+        let data = if backup::is_encrypted(&data) {
+            let passphrase = passphrase_or_saved(passphrase)
+                .ok_or("This backup is encrypted; enter its passphrase")?;
+            backup::decrypt(&data, &passphrase)?
+        } else {
+            data
+        };
+        let (manifest, files) = verify(data)?;
+
+        let root = openclaw_home_dir()?;
+        let size = files.iter().map(|(_, content)| content.len() as u64).sum();
+        disk_space::preflight(&root, size, files.len() as u64)?;
+
+        let restart = !connection::is_remote() && is_gateway_running();
+        if restart {
+            stop_default_gateway(&app)?;
+        }
+        let written = write_files(&root, &files);
+        if restart {
+            if let Err(e) = start_default_gateway(&app) {
+                log_error!("Failed to restart gateway after restore: {}", e);
+            }
+        }
+        written?;
+
+        Ok(HomeRestore {
+            files: files.len(),
+            created_at: manifest.created_at,
+            restarted_gateway: restart,
+        })
+    })
+    .await
+    .map_err(|e| format!("Failed to restore backup: {}", e))?;
+
+    audit::record(
+        "restore_backup",
+        "openclaw_home",
+        result.is_ok(),
+        result.as_ref().err().map(ToString::to_string),
+    );
+    result
+}
@@ -0,0 +1,123 @@
+//! Translations for text the backend shows itself: tray labels, notifications,
+//! screen-reader announcements, and error hints.
+//!
+//! Bundles are flat JSON maps compiled into the binary. Values may contain
+//! `{name}` placeholders filled by [`t_with`]. A key missing from the active
+//! bundle falls back to English, then to the key itself.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+use crate::error::DesktopError;
+use crate::{settings, tray};
+
+const BUNDLES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.json")),
+    ("de", include_str!("../locales/de.json")),
+];
+const FALLBACK: &str = "en";
+
+type Bundle = HashMap<String, String>;
+
+fn bundles() -> &'static HashMap<&'static str, Bundle> {
+    static PARSED: OnceLock<HashMap<&'static str, Bundle>> = OnceLock::new();
+    PARSED.get_or_init(|| {
+        BUNDLES
+            .iter()
+            .filter_map(|(locale, json)| match serde_json::from_str(json) {
+                Ok(bundle) => Some((*locale, bundle)),
+                Err(e) => {
+                    log_error!("Failed to parse {} translations: {}", locale, e);
+                    None
+                }
+            })
+            .collect()
+    })
+}
+
+/// Bundle for a locale tag, exactly or by language (`de_AT.UTF-8` finds `de`)
+fn resolve(tag: &str) -> Option<&'static str> {
+    let tag = tag.trim().replace('_', "-").to_lowercase();
+    let language = tag.split(['-', '.']).next().unwrap_or_default();
+    BUNDLES
+        .iter()
+        .map(|(locale, _)| *locale)
+        .find(|locale| *locale == tag || *locale == language)
+}
+
+fn system_locale() -> Option<&'static str> {
+    static SYSTEM: OnceLock<Option<&'static str>> = OnceLock::new();
+    *SYSTEM.get_or_init(|| sys_locale::get_locale().as_deref().and_then(resolve))
+}
+
+/// Locale in effect: the user's choice, else the system's, else English
+pub fn locale() -> &'static str {
+    settings::current()
+        .locale
+        .as_deref()
+        .and_then(resolve)
+        .or_else(system_locale)
+        .unwrap_or(FALLBACK)
+}
+
+fn lookup(locale: &str, key: &str) -> Option<&'static String> {
+    bundles().get(locale)?.get(key)
+}
+
+/// Translate `key` into the active locale
+pub fn t(key: &str) -> String {
+    lookup(locale(), key)
+        .or_else(|| lookup(FALLBACK, key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Translate `key` and fill its `{name}` placeholders
+pub fn t_with(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(t(key), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocaleInfo {
+    /// Locale in effect
+    pub locale: String,
+    /// What the user picked; `None` follows the system
+    pub preference: Option<String>,
+    pub available: Vec<String>,
+}
+
+fn info() -> LocaleInfo {
+    LocaleInfo {
+        locale: locale().to_string(),
+        preference: settings::current().locale,
+        available: BUNDLES.iter().map(|(l, _)| l.to_string()).collect(),
+    }
+}
+
+#[tauri::command]
+pub fn get_locale() -> LocaleInfo {
+    info()
+}
+
+/// Choose the language for tray, notification and error text; `None` follows the system
+#[tauri::command]
+pub fn set_locale(app: AppHandle, locale: Option<String>) -> Result<LocaleInfo, DesktopError> {
+    let locale = match locale.as_deref().filter(|l| !l.trim().is_empty()) {
+        Some(tag) => Some(
+            resolve(tag)
+                .ok_or_else(|| DesktopError::InvalidInput(format!("No translations for {}", tag)))?
+                .to_string(),
+        ),
+        None => None,
+    };
+    settings::update(|s| s.locale = locale)?;
+    if let Err(e) = tray::rebuild_tray(&app) {
+        log_error!("Failed to rebuild tray after locale change: {}", e);
+    }
+    Ok(info())
+}
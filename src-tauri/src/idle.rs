@@ -0,0 +1,197 @@
+//! Reacting to the user being away: the OS session is locked, or there has
+//! been no input for a while. Depending on the policy the gateway is paused
+//! until they return, or agents are told through the gateway's presence API.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::auto_lock::system_idle;
+use crate::gateway_api::GatewayApi;
+use crate::{is_gateway_running, pause, settings};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const PRESENCE_PATH: &str = "/api/presence";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IdlePolicy {
+    #[default]
+    Ignore,
+    /// Pause while away, resume on return
+    PauseGateway,
+    /// Only tell the gateway, so agents can hold off on interactive work
+    NotifyOnly,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdleSettings {
+    pub policy: IdlePolicy,
+    /// Minutes without input before counting as away; a locked session counts right away
+    pub idle_minutes: u64,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        Self {
+            policy: IdlePolicy::Ignore,
+            idle_minutes: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IdleState {
+    pub policy: IdlePolicy,
+    /// Unknown when the platform offers no way to tell
+    pub locked: Option<bool>,
+    pub idle_secs: Option<u64>,
+    pub away: bool,
+    /// The gateway is paused because of the policy and resumes on return
+    pub paused_by_idle: bool,
+}
+
+static AWAY: AtomicBool = AtomicBool::new(false);
+static PAUSED_BY_IDLE: AtomicBool = AtomicBool::new(false);
+
+#[cfg(target_os = "macos")]
+fn session_locked() -> Option<bool> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-n", "Root", "-d1"])
+        .output()
+        .ok()?;
+    Some(String::from_utf8_lossy(&output.stdout).contains("\"CGSSessionScreenIsLocked\"=Yes"))
+}
+
+#[cfg(target_os = "windows")]
+fn session_locked() -> Option<bool> {
+    use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+    // The lock screen is drawn by LogonUI, which only runs while locked
+    let mut system = System::new();
+    system.refresh_processes_specifics(ProcessesToUpdate::All, true, ProcessRefreshKind::new());
+    let locked = system
+        .processes_by_exact_name("LogonUI.exe".as_ref())
+        .next()
+        .is_some();
+    Some(locked)
+}
+
+#[cfg(target_os = "linux")]
+fn session_locked() -> Option<bool> {
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string());
+    let output = std::process::Command::new("loginctl")
+        .args(["show-session", &session, "-p", "LockedHint", "--value"])
+        .output()
+        .ok()?;
+    match String::from_utf8_lossy(&output.stdout).trim() {
+        "yes" => Some(true),
+        "no" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn session_locked() -> Option<bool> {
+    None
+}
+
+fn state() -> IdleState {
+    let config = settings::current().idle;
+    let locked = session_locked();
+    let idle = system_idle();
+    let threshold = Duration::from_secs(config.idle_minutes.max(1) * 60);
+    IdleState {
+        policy: config.policy,
+        locked,
+        idle_secs: idle.map(|d| d.as_secs()),
+        away: locked == Some(true) || idle.is_some_and(|idle| idle >= threshold),
+        paused_by_idle: PAUSED_BY_IDLE.load(Ordering::SeqCst),
+    }
+}
+
+async fn send_presence<R: Runtime>(app: &AppHandle<R>, away: bool) {
+    let body = serde_json::json!({ "state": if away { "away" } else { "present" } });
+    if let Err(e) = app
+        .state::<GatewayApi>()
+        .post::<serde_json::Value>(PRESENCE_PATH, &body)
+        .await
+    {
+        log_error!("Failed to send presence to gateway: {}", e);
+    }
+}
+
+async fn on_away<R: Runtime>(app: &AppHandle<R>, policy: IdlePolicy) {
+    match policy {
+        IdlePolicy::Ignore => {}
+        IdlePolicy::NotifyOnly => send_presence(app, true).await,
+        IdlePolicy::PauseGateway => {
+            let running = tauri::async_runtime::spawn_blocking(is_gateway_running)
+                .await
+                .unwrap_or(false);
+            // A pause the user made themselves is theirs to undo
+            if !running || pause::is_paused() {
+                return;
+            }
+            match pause::set_paused(app, true).await {
+                Ok(_) => PAUSED_BY_IDLE.store(true, Ordering::SeqCst),
+                Err(e) => log_error!("Failed to pause gateway while away: {}", e),
+            }
+        }
+    }
+}
+
+async fn on_return<R: Runtime>(app: &AppHandle<R>, policy: IdlePolicy) {
+    if policy == IdlePolicy::NotifyOnly {
+        send_presence(app, false).await;
+    }
+    // Resume even if the policy changed while away
+    if PAUSED_BY_IDLE.swap(false, Ordering::SeqCst) && pause::is_paused() {
+        if let Err(e) = pause::set_paused(app, false).await {
+            log_error!("Failed to resume gateway after returning: {}", e);
+        }
+    }
+}
+
+/// Watch for lock and idle, applying the policy on each change
+pub fn spawn_idle_watcher<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let policy = settings::current().idle.policy;
+            if policy == IdlePolicy::Ignore && !PAUSED_BY_IDLE.load(Ordering::SeqCst) {
+                AWAY.store(false, Ordering::SeqCst);
+                continue;
+            }
+            let away = tauri::async_runtime::spawn_blocking(state)
+                .await
+                .map(|s| s.away)
+                .unwrap_or(false);
+            if AWAY.swap(away, Ordering::SeqCst) == away {
+                continue;
+            }
+            if away {
+                on_away(&app, policy).await;
+            } else {
+                on_return(&app, policy).await;
+            }
+        }
+    });
+}
+
+/// Whether the session is locked or idle, and what the policy did about it
+#[tauri::command]
+pub async fn get_idle_state() -> IdleState {
+    tauri::async_runtime::spawn_blocking(state)
+        .await
+        .unwrap_or_else(|_| IdleState {
+            policy: settings::current().idle.policy,
+            locked: None,
+            idle_secs: None,
+            away: false,
+            paused_by_idle: PAUSED_BY_IDLE.load(Ordering::SeqCst),
+        })
+}
@@ -0,0 +1,322 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::AppHandle;
+
+use crate::error::DesktopError;
+use crate::installer::{self, BinaryInstallResult, NpmInstallResult};
+use crate::prerequisites::{self, PackageManager};
+use crate::{kiosk, openclaw_home_dir};
+
+/// Present while an install runs; left behind when it was interrupted
+const TRANSACTION_FILE: &str = "install-transaction.json";
+
+/// Issues found at launch or after the last repair
+static LAST_ISSUES: Mutex<Vec<InstallIssue>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallKind {
+    /// Global install through npm, pnpm, or bun
+    Package,
+    /// Prebuilt binary download
+    Binary,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TransactionRecord {
+    kind: InstallKind,
+    package_manager: Option<PackageManager>,
+    started_at: u64,
+}
+
+/// Marks an install as in progress until `commit` is called
+pub(crate) struct InstallTransaction {
+    path: PathBuf,
+}
+
+impl InstallTransaction {
+    /// The install finished; anything it left is intended
+    pub(crate) fn commit(self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            log_error!("Failed to clear install transaction: {}", e);
+        }
+    }
+}
+
+fn transaction_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join("run").join(TRANSACTION_FILE))
+}
+
+pub(crate) fn begin(
+    kind: InstallKind,
+    package_manager: Option<PackageManager>,
+) -> Result<InstallTransaction, String> {
+    let path = transaction_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create run directory: {}", e))?;
+    }
+    let record = TransactionRecord {
+        kind,
+        package_manager,
+        started_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
+    let content = serde_json::to_string(&record)
+        .map_err(|e| format!("Failed to serialize install transaction: {}", e))?;
+    fs::write(&path, content)
+        .map_err(|e| format!("Failed to record install transaction: {}", e))?;
+    Ok(InstallTransaction { path })
+}
+
+fn interrupted_transaction() -> Option<TransactionRecord> {
+    let content = fs::read_to_string(transaction_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallIssueKind {
+    /// An install started but never finished
+    InterruptedInstall,
+    /// Resumable download of the prebuilt binary
+    PartialDownload,
+    /// Staging directory npm leaves behind when a rename fails (`ENOTEMPTY`)
+    StagingLeftover,
+    /// `node_modules/openclaw` without a `package.json`
+    IncompletePackage,
+    /// `openclaw` shim or symlink pointing at a missing package
+    BrokenBin,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstallIssue {
+    pub kind: InstallIssueKind,
+    pub path: Option<String>,
+}
+
+impl InstallIssue {
+    fn at(kind: InstallIssueKind, path: &Path) -> Self {
+        Self {
+            kind,
+            path: Some(path.display().to_string()),
+        }
+    }
+}
+
+/// Global `node_modules` and the directory npm links bins into
+fn npm_global_dirs() -> Option<(PathBuf, PathBuf)> {
+    let output = prerequisites::tool_command("npm")
+        .args(["prefix", "-g"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let prefix = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    if cfg!(target_os = "windows") {
+        Some((prefix.join("node_modules"), prefix))
+    } else {
+        Some((prefix.join("lib").join("node_modules"), prefix.join("bin")))
+    }
+}
+
+fn package_issues(issues: &mut Vec<InstallIssue>) {
+    let Some((modules, bin)) = npm_global_dirs() else {
+        return;
+    };
+
+    if let Ok(entries) = fs::read_dir(&modules) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(".openclaw-") {
+                issues.push(InstallIssue::at(
+                    InstallIssueKind::StagingLeftover,
+                    &entry.path(),
+                ));
+            }
+        }
+    }
+
+    let package = modules.join("openclaw");
+    let package_complete = package.join("package.json").is_file();
+    if package.exists() && !package_complete {
+        issues.push(InstallIssue::at(
+            InstallIssueKind::IncompletePackage,
+            &package,
+        ));
+    }
+
+    let shims: &[&str] = if cfg!(target_os = "windows") {
+        &["openclaw.cmd", "openclaw.ps1", "openclaw"]
+    } else {
+        &["openclaw"]
+    };
+    for shim in shims.iter().map(|name| bin.join(name)) {
+        // `exists` follows symlinks, so a dangling link reports false
+        let present = shim.symlink_metadata().is_ok();
+        let broken = if cfg!(target_os = "windows") {
+            !package_complete
+        } else {
+            !shim.exists()
+        };
+        if present && broken {
+            issues.push(InstallIssue::at(InstallIssueKind::BrokenBin, &shim));
+        }
+    }
+}
+
+fn binary_issues(issues: &mut Vec<InstallIssue>) {
+    let Some(dir) = installer::installed_binary_path()
+        .ok()
+        .and_then(|p| p.parent().map(Path::to_path_buf))
+    else {
+        return;
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.ends_with(".part") || name.ends_with(".part.json") {
+            issues.push(InstallIssue::at(
+                InstallIssueKind::PartialDownload,
+                &entry.path(),
+            ));
+        }
+    }
+}
+
+/// Look for leftovers of failed or interrupted installs
+pub fn detect() -> Vec<InstallIssue> {
+    let mut issues = Vec::new();
+    if interrupted_transaction().is_some() {
+        issues.push(InstallIssue {
+            kind: InstallIssueKind::InterruptedInstall,
+            path: transaction_path().ok().map(|p| p.display().to_string()),
+        });
+    }
+    package_issues(&mut issues);
+    binary_issues(&mut issues);
+    issues
+}
+
+fn remember(issues: &[InstallIssue]) {
+    if let Ok(mut last) = LAST_ISSUES.lock() {
+        *last = issues.to_vec();
+    }
+}
+
+/// Issues found by the last check
+pub fn last_issues() -> Vec<InstallIssue> {
+    LAST_ISSUES
+        .lock()
+        .map(|issues| issues.clone())
+        .unwrap_or_default()
+}
+
+/// Check for install leftovers once at launch so they show up in diagnostics
+pub fn spawn_launch_check() {
+    tauri::async_runtime::spawn_blocking(|| {
+        let issues = detect();
+        if !issues.is_empty() {
+            log_error!(
+                "Found {} leftover(s) from a failed OpenClaw install; run repair_installation to clean up",
+                issues.len()
+            );
+        }
+        remember(&issues);
+    });
+}
+
+fn remove(path: &Path) -> std::io::Result<()> {
+    let metadata = path.symlink_metadata()?;
+    if metadata.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepairReport {
+    /// Paths deleted during cleanup
+    pub removed: Vec<String>,
+    /// Paths that could not be deleted, with the reason
+    pub failed: Vec<String>,
+    /// Install retried after cleanup, if any
+    pub retried: Option<InstallKind>,
+    pub package_install: Option<NpmInstallResult>,
+    pub binary_install: Option<BinaryInstallResult>,
+    pub error: Option<String>,
+}
+
+/// Remove leftovers of failed installs, then run the interrupted install again
+/// (or a fresh one when OpenClaw is missing)
+#[tauri::command]
+pub async fn repair_installation(app: AppHandle) -> Result<RepairReport, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let (issues, interrupted) =
+        tauri::async_runtime::spawn_blocking(|| (detect(), interrupted_transaction()))
+            .await
+            .map_err(|e| format!("Failed to inspect installation: {}", e))?;
+
+    let mut report = RepairReport {
+        removed: Vec::new(),
+        failed: Vec::new(),
+        retried: None,
+        package_install: None,
+        binary_install: None,
+        error: None,
+    };
+    for path in issues.iter().filter_map(|issue| issue.path.as_deref()) {
+        match remove(Path::new(path)) {
+            Ok(()) => report.removed.push(path.to_string()),
+            Err(e) => report.failed.push(format!("{}: {}", path, e)),
+        }
+    }
+
+    let retry = match interrupted {
+        Some(record) => Some(record.kind),
+        None if crate::openclaw_installed() => None,
+        None if issues
+            .iter()
+            .any(|i| i.kind == InstallIssueKind::PartialDownload) =>
+        {
+            Some(InstallKind::Binary)
+        }
+        None => Some(InstallKind::Package),
+    };
+    report.retried = retry;
+    match retry {
+        Some(InstallKind::Package) => match installer::install_openclaw(app).await {
+            Ok(result) => report.package_install = Some(result),
+            Err(e) => report.error = Some(e.to_string()),
+        },
+        Some(InstallKind::Binary) => match installer::install_openclaw_binary(app).await {
+            Ok(result) => report.binary_install = Some(result),
+            Err(e) => report.error = Some(e.to_string()),
+        },
+        None => {}
+    }
+
+    let remaining = tauri::async_runtime::spawn_blocking(detect)
+        .await
+        .map_err(|e| format!("Failed to inspect installation: {}", e))?;
+    remember(&remaining);
+    Ok(report)
+}
+
+/// Leftovers of failed installs found right now
+#[tauri::command]
+pub async fn get_installation_issues() -> Result<Vec<InstallIssue>, DesktopError> {
+    let issues = tauri::async_runtime::spawn_blocking(detect)
+        .await
+        .map_err(|e| format!("Failed to inspect installation: {}", e))?;
+    remember(&issues);
+    Ok(issues)
+}
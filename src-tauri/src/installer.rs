@@ -0,0 +1,534 @@
+use reqwest::header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStderr, ChildStdout, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::app_state;
+use crate::audit;
+use crate::availability::{self, Operation};
+use crate::connectivity;
+use crate::disk_space::{self, SpaceCheck};
+use crate::error::DesktopError;
+use crate::events::{InstallProgress, INSTALL_PROGRESS_EVENT};
+use crate::gateways::kill_process_tree;
+use crate::install_repair::{self, InstallKind};
+use crate::kiosk;
+use crate::openclaw_home_dir;
+use crate::permissions::{self, Permission};
+use crate::prerequisites::{self, PackageManager};
+use crate::proxy;
+use crate::settings;
+use crate::tasks::{Task, TaskKind};
+use crate::updater;
+
+/// Release hosts tried in order when `download_mirrors` is not set in settings
+const DEFAULT_MIRRORS: [&str; 2] = [
+    "https://github.com/openclaw/openclaw/releases/latest/download",
+    "https://cdn.openclaw.ai/releases/latest",
+];
+
+/// Checksums always come from the official release host, so a mirror can't vouch
+/// for its own download
+const CHECKSUM_SOURCE: &str = DEFAULT_MIRRORS[0];
+
+const MAX_ATTEMPTS_PER_MIRROR: u32 = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// The running `npm install`, held so `cancel_install` can kill it
+static NPM_INSTALL: Mutex<Option<Child>> = Mutex::new(None);
+static INSTALL_CANCELLED: AtomicBool = AtomicBool::new(false);
+
+/// Bookkeeping stored next to a partial download so a later attempt can resume it
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct PartialMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    total: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BinaryInstallResult {
+    pub path: String,
+    pub source_url: String,
+    pub bytes: u64,
+    /// Free space measured before downloading
+    pub space: SpaceCheck,
+}
+
+fn binary_asset_name() -> String {
+    let suffix = if cfg!(target_os = "windows") {
+        ".exe"
+    } else {
+        ""
+    };
+    format!(
+        "openclaw-{}-{}{}",
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        suffix
+    )
+}
+
+fn bin_dir() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join("bin"))
+}
+
+/// Path where the prebuilt binary is installed
+pub fn installed_binary_path() -> Result<PathBuf, String> {
+    let name = if cfg!(target_os = "windows") {
+        "openclaw.exe"
+    } else {
+        "openclaw"
+    };
+    Ok(bin_dir()?.join(name))
+}
+
+fn mirrors() -> Vec<String> {
+    let configured = settings::current().download_mirrors;
+    if configured.is_empty() {
+        DEFAULT_MIRRORS.iter().map(|m| m.to_string()).collect()
+    } else {
+        configured
+    }
+}
+
+fn meta_path(part_path: &Path) -> PathBuf {
+    part_path.with_extension("part.json")
+}
+
+fn load_meta(part_path: &Path) -> Option<PartialMeta> {
+    let content = fs::read_to_string(meta_path(part_path)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_meta(part_path: &Path, meta: &PartialMeta) -> Result<(), String> {
+    let content = serde_json::to_string(meta)
+        .map_err(|e| format!("Failed to serialize download state: {}", e))?;
+    fs::write(meta_path(part_path), content)
+        .map_err(|e| format!("Failed to write download state: {}", e))
+}
+
+fn discard_partial(part_path: &Path) {
+    let _ = fs::remove_file(part_path);
+    let _ = fs::remove_file(meta_path(part_path));
+}
+
+/// Parse the total size out of a `Content-Range: bytes start-end/total` header
+fn content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let range = value.strip_prefix("bytes ")?;
+    let (span, total) = range.split_once('/')?;
+    let start = span.split_once('-')?.0.parse().ok()?;
+    Some((start, total.parse().ok()))
+}
+
+fn header_string(
+    response: &reqwest::Response,
+    name: reqwest::header::HeaderName,
+) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string())
+}
+
+/// Download `url` into `part_path`, resuming from whatever is already on disk.
+///
+/// The server's validator (ETag or Last-Modified) is sent as `If-Range`, so a
+/// changed file comes back as a full 200 response and the stale partial is replaced.
+async fn download_resumable(
+    client: &reqwest::Client,
+    url: &str,
+    part_path: &Path,
+) -> Result<u64, String> {
+    let mut meta = match load_meta(part_path) {
+        Some(meta) if meta.url == url => meta,
+        _ => {
+            discard_partial(part_path);
+            PartialMeta {
+                url: url.to_string(),
+                ..Default::default()
+            }
+        }
+    };
+
+    let existing = fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+    if existing > 0 && meta.total == Some(existing) {
+        return Ok(existing);
+    }
+
+    let mut request = client.get(url);
+    if existing > 0 {
+        request = request.header(RANGE, format!("bytes={}-", existing));
+        if let Some(validator) = meta.etag.as_ref().or(meta.last_modified.as_ref()) {
+            request = request.header(IF_RANGE, validator.as_str());
+        }
+    }
+
+    let mut response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach {}: {}", url, e))?;
+
+    let mut file = match response.status() {
+        StatusCode::PARTIAL_CONTENT => {
+            let range = header_string(&response, CONTENT_RANGE)
+                .and_then(|v| content_range(&v))
+                .ok_or("Server returned a partial response without Content-Range")?;
+            if range.0 != existing {
+                discard_partial(part_path);
+                return Err(format!(
+                    "Server resumed at byte {} but {} bytes are on disk",
+                    range.0, existing
+                ));
+            }
+            meta.total = range.1;
+            OpenOptions::new()
+                .append(true)
+                .open(part_path)
+                .map_err(|e| format!("Failed to open partial download: {}", e))?
+        }
+        StatusCode::RANGE_NOT_SATISFIABLE => {
+            // Either the partial is already complete or it is larger than the remote file
+            discard_partial(part_path);
+            return Err("Partial download no longer matches the remote file".to_string());
+        }
+        status if status.is_success() => {
+            meta.total = response.content_length();
+            File::create(part_path).map_err(|e| format!("Failed to create download file: {}", e))?
+        }
+        status => return Err(format!("Download from {} failed: HTTP {}", url, status)),
+    };
+
+    meta.etag = header_string(&response, ETAG);
+    meta.last_modified = header_string(&response, LAST_MODIFIED);
+    save_meta(part_path, &meta)?;
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Download interrupted: {}", e))?
+    {
+        file.write_all(&chunk)
+            .map_err(|e| format!("Failed to write download: {}", e))?;
+    }
+    file.flush()
+        .map_err(|e| format!("Failed to flush download: {}", e))?;
+
+    let written = fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+    match meta.total {
+        Some(total) if total != written => Err(format!(
+            "Download incomplete: {} of {} bytes",
+            written, total
+        )),
+        _ => {
+            meta.total = Some(written);
+            save_meta(part_path, &meta)?;
+            Ok(written)
+        }
+    }
+}
+
+fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open download: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buf)
+            .map_err(|e| format!("Failed to read download: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Fetch the published `<asset>.sha256` checksum from the official release host
+async fn fetch_checksum(client: &reqwest::Client, asset: &str) -> Result<String, String> {
+    let url = format!("{}/{}.sha256", CHECKSUM_SOURCE, asset);
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch checksum from {}: {}", url, e))?;
+    let text = response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read checksum from {}: {}", url, e))?;
+    text.split_whitespace()
+        .next()
+        .map(|s| s.to_lowercase())
+        .ok_or_else(|| format!("Empty checksum at {}", url))
+}
+
+/// Download and install the prebuilt openclaw binary, trying each mirror in turn.
+///
+/// Nothing is installed unless the download matches the official checksum.
+#[tauri::command]
+pub async fn install_openclaw_binary(app: AppHandle) -> Result<BinaryInstallResult, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    permissions::ensure_granted(&app, Permission::InstallSoftware).await?;
+    let result = download_binary().await;
+    audit::record_result("cli_install", "binary", &result);
+    app_state::refresh_install(&app).await;
+    result
+}
+
+/// The install itself, for callers that already asked for consent
+pub(crate) async fn download_binary() -> Result<BinaryInstallResult, DesktopError> {
+    let _operation = availability::begin(Operation::Install)?;
+    let dest = installed_binary_path()?;
+    let dir = bin_dir()?;
+    let space = disk_space::preflight(&dir, disk_space::BINARY_INSTALL_BYTES, 2)?;
+    let transaction = install_repair::begin(InstallKind::Binary, None)?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create bin directory: {}", e))?;
+    let part_path = dir.join(format!("{}.part", binary_asset_name()));
+
+    let client = proxy::apply(reqwest::Client::builder().connect_timeout(Duration::from_secs(15)))?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let asset = binary_asset_name();
+    let expected = fetch_checksum(&client, &asset).await?;
+    let mut errors = Vec::new();
+
+    for mirror in mirrors() {
+        let url = format!("{}/{}", mirror.trim_end_matches('/'), asset);
+
+        // A mismatch or unreadable download moves on to the next mirror
+        for attempt in 1..=MAX_ATTEMPTS_PER_MIRROR {
+            let bytes = match download_resumable(&client, &url, &part_path).await {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    errors.push(format!("{} (attempt {}): {}", url, attempt, e));
+                    tokio::time::sleep(RETRY_DELAY).await;
+                    continue;
+                }
+            };
+
+            match sha256_file(&part_path) {
+                Ok(actual) if actual == expected => {}
+                Ok(_) => {
+                    discard_partial(&part_path);
+                    errors.push(format!("{}: checksum mismatch", url));
+                    break;
+                }
+                Err(e) => {
+                    discard_partial(&part_path);
+                    errors.push(format!("{}: {}", url, e));
+                    break;
+                }
+            }
+
+            fs::rename(&part_path, &dest)
+                .map_err(|e| format!("Failed to install binary: {}", e))?;
+            let _ = fs::remove_file(meta_path(&part_path));
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                fs::set_permissions(&dest, fs::Permissions::from_mode(0o755))
+                    .map_err(|e| format!("Failed to mark binary executable: {}", e))?;
+            }
+            transaction.commit();
+
+            return Ok(BinaryInstallResult {
+                path: dest.display().to_string(),
+                source_url: url,
+                bytes,
+                space,
+            });
+        }
+    }
+
+    Err(format!(
+        "Failed to download openclaw from all mirrors:\n{}",
+        errors.join("\n")
+    )
+    .into())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NpmInstallResult {
+    /// Package manager that performed the install
+    pub package_manager: PackageManager,
+    pub version: Option<String>,
+    /// Where `openclaw` now resolves on PATH
+    pub path: Option<String>,
+    /// Free space measured before installing
+    pub space: SpaceCheck,
+}
+
+/// Check room for a global package install; package managers keep their
+/// caches and (by default) global prefixes under the home directory
+pub(crate) fn package_install_preflight() -> Result<SpaceCheck, String> {
+    let home = dirs::home_dir().ok_or("Could not find home directory")?;
+    disk_space::preflight(
+        &home,
+        disk_space::PACKAGE_INSTALL_BYTES,
+        disk_space::PACKAGE_INSTALL_INODES,
+    )
+}
+
+/// Forward a child's output line by line until both pipes close.
+///
+/// stderr is drained on a helper thread so neither pipe can fill up and stall
+/// the child; its lines are also returned for error reporting.
+pub(crate) fn pipe_output<F>(
+    stdout: Option<ChildStdout>,
+    stderr: Option<ChildStderr>,
+    on_line: F,
+) -> Vec<String>
+where
+    F: Fn(&'static str, String) + Clone + Send + 'static,
+{
+    let on_stderr = on_line.clone();
+    let stderr_thread = std::thread::spawn(move || {
+        let mut lines = Vec::new();
+        if let Some(stderr) = stderr {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                on_stderr("stderr", line.clone());
+                lines.push(line);
+            }
+        }
+        lines
+    });
+
+    if let Some(stdout) = stdout {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            on_line("stdout", line);
+        }
+    }
+    stderr_thread.join().unwrap_or_default()
+}
+
+fn run_npm_install<R: Runtime>(
+    app: &AppHandle<R>,
+    task: &Task<R>,
+) -> Result<NpmInstallResult, String> {
+    let runtime = prerequisites::detect();
+    let Some(package_manager) = runtime.package_manager else {
+        return Err(format!(
+            "Cannot install OpenClaw: {}",
+            runtime.suggestions.join("; ")
+        ));
+    };
+    let space = package_install_preflight()?;
+
+    let mut command = prerequisites::tool_command(package_manager.program());
+    command
+        .args(package_manager.global_install_args("openclaw"))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    proxy::apply_env(&mut command);
+
+    let mut slot = NPM_INSTALL
+        .lock()
+        .map_err(|_| "Install lock poisoned".to_string())?;
+    if slot.is_some() {
+        return Err("An install is already running".to_string());
+    }
+    let transaction = install_repair::begin(InstallKind::Package, Some(package_manager))?;
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to install: {}", e))?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    *slot = Some(child);
+    INSTALL_CANCELLED.store(false, Ordering::SeqCst);
+    drop(slot);
+
+    // The child stays in `NPM_INSTALL` while its pipes are read, so it can be cancelled
+    let app = app.clone();
+    let task = task.clone();
+    let stderr_lines = pipe_output(stdout, stderr, move |stream, line| {
+        task.progress(line.clone());
+        let payload = InstallProgress { stream, line };
+        if let Err(e) = app.emit(INSTALL_PROGRESS_EVENT, payload) {
+            log_error!("Failed to emit install progress: {}", e);
+        }
+    });
+
+    let child = NPM_INSTALL.lock().ok().and_then(|mut slot| slot.take());
+    let status = match child {
+        Some(mut child) => child
+            .wait()
+            .map_err(|e| format!("Failed to wait for {}: {}", package_manager.program(), e))?,
+        None => return Err("Install process was lost".to_string()),
+    };
+
+    if INSTALL_CANCELLED.swap(false, Ordering::SeqCst) {
+        return Err("Installation cancelled".to_string());
+    }
+    if !status.success() {
+        return Err(stderr_lines.join("\n"));
+    }
+    transaction.commit();
+
+    Ok(NpmInstallResult {
+        package_manager,
+        version: crate::detect_openclaw_version(),
+        path: updater::resolve_on_path().map(|p| p.display().to_string()),
+        space,
+    })
+}
+
+/// Install OpenClaw with the first usable package manager (npm, pnpm, bun), streaming its output as `install-progress` events
+#[tauri::command]
+pub async fn install_openclaw(app: AppHandle) -> Result<NpmInstallResult, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    permissions::ensure_granted(&app, Permission::InstallSoftware).await?;
+    connectivity::ensure_registry().await?;
+    let _operation = availability::begin(Operation::Install)?;
+    let task = Task::start(&app, TaskKind::Install);
+    task.on_cancel(|| {
+        if let Err(e) = kill_npm_install() {
+            log_error!("Failed to cancel install: {}", e);
+        }
+    });
+    let worker = task.clone();
+    let install_app = app.clone();
+    let result = task
+        .run(async move {
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                run_npm_install(&install_app, &worker)
+            })
+            .await
+            .map_err(|e| format!("Failed to run install: {}", e))??;
+            Ok(result)
+        })
+        .await;
+    audit::record_result("cli_install", "package_manager", &result);
+    app_state::refresh_install(&app).await;
+    result
+}
+
+fn kill_npm_install() -> Result<bool, String> {
+    let mut slot = NPM_INSTALL
+        .lock()
+        .map_err(|_| "Install lock poisoned".to_string())?;
+    let Some(child) = slot.as_mut() else {
+        return Ok(false);
+    };
+    INSTALL_CANCELLED.store(true, Ordering::SeqCst);
+    kill_process_tree(child)?;
+    Ok(true)
+}
+
+/// Kill a running `install_openclaw`; returns `false` when nothing was running
+#[tauri::command]
+pub fn cancel_install() -> Result<bool, DesktopError> {
+    Ok(kill_npm_install()?)
+}
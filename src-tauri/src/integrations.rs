@@ -0,0 +1,386 @@
+//! Webhooks that receive gateway events as HTTP POSTs.
+//!
+//! Each delivery is signed with the webhook's secret, kept in the keychain:
+//! `X-OpenClaw-Signature: sha256=<hex>` is the HMAC-SHA256 of
+//! `<X-OpenClaw-Timestamp>.<body>`. Slack, Discord and ntfy get a body they
+//! render directly; everything else gets the JSON envelope.
+
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::error::DesktopError;
+use crate::{audit, kiosk, proxy, secrets, settings};
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// Gateway error lines are forwarded at most this often, so a failure loop
+/// doesn't flood a channel
+const ERROR_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Last delivery per webhook id, since launch
+static DELIVERIES: Mutex<Option<HashMap<String, WebhookDelivery>>> = Mutex::new(None);
+static LAST_ERROR: Mutex<Option<Instant>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    /// An agent reply finished
+    SessionCompleted,
+    /// A reply failed, or the gateway logged an error
+    Error,
+    Crash,
+    /// The gateway came back after a crash or was restarted
+    Restart,
+}
+
+impl WebhookEvent {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::SessionCompleted => "session_completed",
+            Self::Error => "error",
+            Self::Crash => "crash",
+            Self::Restart => "restart",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookFormat {
+    #[default]
+    Json,
+    Slack,
+    Discord,
+    Ntfy,
+}
+
+/// A webhook as stored in settings; its secret is in the keychain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    /// Events to forward; empty forwards all of them
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+}
+
+fn enabled_default() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookInput {
+    /// Unset to add a new webhook
+    pub id: Option<String>,
+    pub name: String,
+    pub url: String,
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(default)]
+    pub format: WebhookFormat,
+    #[serde(default = "enabled_default")]
+    pub enabled: bool,
+    /// `None` keeps the stored secret; an empty string removes it
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub event: WebhookEvent,
+    /// Unix seconds
+    pub at: u64,
+    pub ok: bool,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookView {
+    #[serde(flatten)]
+    pub webhook: Webhook,
+    pub has_secret: bool,
+    pub last_delivery: Option<WebhookDelivery>,
+}
+
+#[derive(Serialize)]
+struct Envelope<'a> {
+    event: WebhookEvent,
+    at: u64,
+    summary: &'a str,
+    data: &'a Value,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn new_id() -> String {
+    let mut bytes = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn view(webhook: Webhook) -> WebhookView {
+    let last_delivery = DELIVERIES
+        .lock()
+        .ok()
+        .and_then(|d| d.as_ref().and_then(|d| d.get(&webhook.id).cloned()));
+    WebhookView {
+        has_secret: secrets::webhook_secret(&webhook.id).is_some(),
+        last_delivery,
+        webhook,
+    }
+}
+
+fn sign(secret: &str, timestamp: u64, body: &[u8]) -> Result<String, String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|e| format!("Failed to sign webhook: {}", e))?;
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    let digest = mac.finalize().into_bytes();
+    Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn body(webhook: &Webhook, event: WebhookEvent, at: u64, summary: &str, data: &Value) -> Vec<u8> {
+    let text = format!("OpenClaw: {}", summary);
+    match webhook.format {
+        WebhookFormat::Json => serde_json::to_vec(&Envelope {
+            event,
+            at,
+            summary,
+            data,
+        })
+        .unwrap_or_default(),
+        WebhookFormat::Slack => serde_json::json!({ "text": text }).to_string().into_bytes(),
+        WebhookFormat::Discord => serde_json::json!({ "content": text })
+            .to_string()
+            .into_bytes(),
+        WebhookFormat::Ntfy => summary.as_bytes().to_vec(),
+    }
+}
+
+async fn deliver(
+    webhook: &Webhook,
+    event: WebhookEvent,
+    summary: &str,
+    data: &Value,
+) -> WebhookDelivery {
+    let at = now_secs();
+    let result = async {
+        let client = proxy::apply(reqwest::Client::builder().timeout(DELIVERY_TIMEOUT))?
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let body = body(webhook, event, at, summary, data);
+        let mut request = client
+            .post(&webhook.url)
+            .header("X-OpenClaw-Event", event.as_str())
+            .header("X-OpenClaw-Timestamp", at.to_string());
+        request = match webhook.format {
+            WebhookFormat::Ntfy => request
+                .header(reqwest::header::CONTENT_TYPE, "text/plain")
+                .header("Title", "OpenClaw"),
+            _ => request.header(reqwest::header::CONTENT_TYPE, "application/json"),
+        };
+        if let Some(secret) = secrets::webhook_secret(&webhook.id) {
+            request = request.header(
+                "X-OpenClaw-Signature",
+                format!("sha256={}", sign(&secret, at, &body)?),
+            );
+        }
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach {}: {}", webhook.url, e))?;
+        Ok::<_, String>(response.status())
+    }
+    .await;
+
+    let delivery = match result {
+        Ok(status) => WebhookDelivery {
+            event,
+            at,
+            ok: status.is_success(),
+            status: Some(status.as_u16()),
+            error: (!status.is_success()).then(|| format!("HTTP {}", status)),
+        },
+        Err(e) => WebhookDelivery {
+            event,
+            at,
+            ok: false,
+            status: None,
+            error: Some(e),
+        },
+    };
+    if let Ok(mut deliveries) = DELIVERIES.lock() {
+        deliveries
+            .get_or_insert_with(HashMap::new)
+            .insert(webhook.id.clone(), delivery.clone());
+    }
+    delivery
+}
+
+/// Send an event to every enabled webhook that wants it, in the background
+pub(crate) fn forward(event: WebhookEvent, summary: String, data: Value) {
+    let targets: Vec<Webhook> = settings::current()
+        .webhooks
+        .into_iter()
+        .filter(|w| w.enabled && (w.events.is_empty() || w.events.contains(&event)))
+        .collect();
+    if targets.is_empty() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        for webhook in targets {
+            let delivery = deliver(&webhook, event, &summary, &data).await;
+            if let Some(error) = delivery.error {
+                log_error!("Webhook {} failed: {}", webhook.name, error);
+            }
+        }
+    });
+}
+
+/// Forward a gateway error log line, unless one went out within the last minute
+pub(crate) fn forward_log_error(message: &str) {
+    let Ok(mut last) = LAST_ERROR.lock() else {
+        return;
+    };
+    if last.is_some_and(|at| at.elapsed() < ERROR_INTERVAL) {
+        return;
+    }
+    *last = Some(Instant::now());
+    forward(
+        WebhookEvent::Error,
+        format!("Gateway error: {}", message),
+        serde_json::json!({ "source": "gateway_log", "message": message }),
+    );
+}
+
+fn validate(input: &WebhookInput) -> Result<(), DesktopError> {
+    if input.name.trim().is_empty() {
+        return Err(DesktopError::InvalidInput(
+            "A webhook needs a name".to_string(),
+        ));
+    }
+    let url = reqwest::Url::parse(input.url.trim())
+        .map_err(|e| DesktopError::InvalidInput(format!("Invalid webhook URL: {}", e)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(DesktopError::InvalidInput(
+            "Webhook URLs must use http or https".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_webhooks() -> Vec<WebhookView> {
+    settings::current().webhooks.into_iter().map(view).collect()
+}
+
+/// Add a webhook, or update the one with `id`
+#[tauri::command]
+pub fn save_webhook(input: WebhookInput) -> Result<WebhookView, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    validate(&input)?;
+    let id = match &input.id {
+        Some(id) if settings::current().webhooks.iter().any(|w| &w.id == id) => id.clone(),
+        Some(id) => return Err(DesktopError::NotFound(format!("Webhook {} not found", id))),
+        None => new_id(),
+    };
+    if let Some(secret) = &input.secret {
+        let secret = Some(secret.as_str()).filter(|s| !s.is_empty());
+        secrets::set_webhook_secret(&id, secret)?;
+    }
+    let webhook = Webhook {
+        id: id.clone(),
+        name: input.name.trim().to_string(),
+        url: input.url.trim().to_string(),
+        events: input.events,
+        format: input.format,
+        enabled: input.enabled,
+    };
+    let stored = webhook.clone();
+    settings::update(|s| match s.webhooks.iter_mut().find(|w| w.id == id) {
+        Some(existing) => *existing = stored,
+        None => s.webhooks.push(stored),
+    })?;
+    audit::record("webhook_save", &webhook.url, true, None);
+    Ok(view(webhook))
+}
+
+#[tauri::command]
+pub fn delete_webhook(id: String) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    settings::update(|s| s.webhooks.retain(|w| w.id != id))?;
+    secrets::set_webhook_secret(&id, None)?;
+    if let Ok(mut deliveries) = DELIVERIES.lock() {
+        if let Some(deliveries) = deliveries.as_mut() {
+            deliveries.remove(&id);
+        }
+    }
+    audit::record("webhook_delete", &id, true, None);
+    Ok(())
+}
+
+/// Send a sample `restart` event to one webhook and report how it went
+#[tauri::command]
+pub async fn test_webhook(id: String) -> Result<WebhookDelivery, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let webhook = settings::current()
+        .webhooks
+        .into_iter()
+        .find(|w| w.id == id)
+        .ok_or_else(|| DesktopError::NotFound(format!("Webhook {} not found", id)))?;
+    Ok(deliver(
+        &webhook,
+        WebhookEvent::Restart,
+        "Test event from OpenClaw Desktop",
+        &serde_json::json!({ "test": true }),
+    )
+    .await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signs_timestamp_and_body() {
+        // HMAC-SHA256 of `1700000000.{"event":"restart"}`, computed independently
+        assert_eq!(
+            sign("whsec_test", 1_700_000_000, br#"{"event":"restart"}"#).unwrap(),
+            "b4081e4b524d55645a4283ec59d126b48133c79b6b68e81fcdda5f9de56fc9df"
+        );
+        assert_eq!(
+            sign("", 0, b"").unwrap(),
+            "b849d5a581847b281957065739df36df2463d1977ea8d6e1e4e6cf33fadc68c3"
+        );
+    }
+
+    #[test]
+    fn signature_covers_every_input() {
+        let signature = sign("secret", 1, b"body").unwrap();
+        assert_ne!(signature, sign("other", 1, b"body").unwrap());
+        assert_ne!(signature, sign("secret", 2, b"body").unwrap());
+        assert_ne!(signature, sign("secret", 1, b"bodY").unwrap());
+        // The separator keeps the timestamp and body from running together
+        assert_ne!(
+            sign("secret", 12, b"3").unwrap(),
+            sign("secret", 1, b"23").unwrap()
+        );
+    }
+}
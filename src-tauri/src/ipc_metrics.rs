@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::ipc::{Invoke, InvokeBody};
+use tauri::{Manager, Runtime, State};
+
+use crate::crash_report;
+
+/// Commands called by the metrics code itself, kept out of the numbers
+const EXCLUDED_COMMANDS: [&str; 2] = ["record_ipc_timing", "get_ipc_metrics"];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommandMetrics {
+    pub command: String,
+    pub calls: u64,
+    pub payload_bytes: u64,
+    /// Time spent inside the invoke handler; the full run time for sync commands,
+    /// only the dispatch for async ones
+    pub total_dispatch_ms: f64,
+    pub max_dispatch_ms: f64,
+    /// Round trips reported by the frontend, including async work and serialization
+    pub round_trips: u64,
+    pub errors: u64,
+    pub total_round_trip_ms: f64,
+    pub max_round_trip_ms: f64,
+}
+
+/// In-memory per-command IPC statistics for this app session
+#[derive(Default)]
+pub struct IpcMetrics(Mutex<HashMap<String, CommandMetrics>>);
+
+impl IpcMetrics {
+    fn with_command(&self, command: &str, update: impl FnOnce(&mut CommandMetrics)) {
+        if let Ok(mut metrics) = self.0.lock() {
+            let entry = metrics
+                .entry(command.to_string())
+                .or_insert_with(|| CommandMetrics {
+                    command: command.to_string(),
+                    ..Default::default()
+                });
+            update(entry);
+        }
+    }
+}
+
+fn payload_size(body: &InvokeBody) -> u64 {
+    match body {
+        InvokeBody::Json(value) => value.to_string().len() as u64,
+        InvokeBody::Raw(bytes) => bytes.len() as u64,
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Wrap the generated invoke handler so every command's dispatch is measured
+pub fn instrument<R, F>(handler: F) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: Runtime,
+    F: Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke: Invoke<R>| {
+        let command = invoke.message.command().to_string();
+        if EXCLUDED_COMMANDS.contains(&command.as_str()) {
+            return handler(invoke);
+        }
+        let app = invoke.message.webview().app_handle().clone();
+        let payload_bytes = payload_size(invoke.message.payload());
+
+        let started = Instant::now();
+        let handled = handler(invoke);
+        let elapsed = millis(started.elapsed());
+
+        app.state::<IpcMetrics>().with_command(&command, |m| {
+            m.calls += 1;
+            m.payload_bytes += payload_bytes;
+            m.total_dispatch_ms += elapsed;
+            m.max_dispatch_ms = m.max_dispatch_ms.max(elapsed);
+        });
+        handled
+    }
+}
+
+/// Report a command's full round trip as measured by the frontend, with the
+/// error it returned, which also goes to the crash log
+#[tauri::command]
+pub fn record_ipc_timing(
+    metrics: State<'_, IpcMetrics>,
+    command: String,
+    duration_ms: f64,
+    success: bool,
+    error: Option<String>,
+) {
+    if let Some(error) = &error {
+        crash_report::record_command_error(&command, error);
+    }
+    metrics.with_command(&command, |m| {
+        m.round_trips += 1;
+        if !success {
+            m.errors += 1;
+        }
+        m.total_round_trip_ms += duration_ms;
+        m.max_round_trip_ms = m.max_round_trip_ms.max(duration_ms);
+    });
+}
+
+/// Per-command IPC statistics, slowest total first
+#[tauri::command]
+pub fn get_ipc_metrics(metrics: State<'_, IpcMetrics>) -> Vec<CommandMetrics> {
+    let mut all: Vec<CommandMetrics> = metrics
+        .0
+        .lock()
+        .map(|m| m.values().cloned().collect())
+        .unwrap_or_default();
+    all.sort_by(|a, b| {
+        (b.total_round_trip_ms + b.total_dispatch_ms)
+            .total_cmp(&(a.total_round_trip_ms + a.total_dispatch_ms))
+    });
+    all
+}
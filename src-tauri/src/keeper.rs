@@ -0,0 +1,292 @@
+//! "openclaw-keeper": the app binary relaunched headless to supervise the
+//! default gateway after the app quits.
+//!
+//! On quit (shutdown policy `keeper`) the app stops the gateway it spawned and
+//! starts the keeper, which restarts the gateway with output going straight to
+//! the log files and keeps restarting it per the auto-restart settings. On the
+//! next launch the app connects to the keeper's local socket, and the keeper
+//! exits, leaving the gateway running for the app to adopt.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::supervisor::{self, STABLE_UPTIME};
+use crate::tokens::generate_token;
+use crate::{
+    active_gateway_port, connection, gateway_command, gateway_log_paths, is_port_open, open_log,
+    openclaw_home_dir, path_env, settings,
+};
+
+pub const KEEPER_FLAG: &str = "--keeper";
+
+const STATE_FILE: &str = "run/keeper.json";
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long the keeper waits for the quitting app to free the gateway port
+const PORT_RELEASE_TIMEOUT: Duration = Duration::from_secs(30);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Where the keeper listens, so the next app launch can find it
+#[derive(Serialize, Deserialize)]
+struct KeeperState {
+    port: u16,
+    pid: u32,
+    /// Proves the handback request comes from someone who can read this file
+    token: String,
+}
+
+fn state_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(STATE_FILE))
+}
+
+fn write_state(state: &KeeperState) -> Result<(), String> {
+    let path = state_path()?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create run directory: {}", e))?;
+    }
+    let content = serde_json::to_string(state)
+        .map_err(|e| format!("Failed to serialize keeper state: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write keeper state: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict keeper state: {}", e))?;
+    }
+    Ok(())
+}
+
+fn read_state() -> Option<KeeperState> {
+    let content = fs::read_to_string(state_path().ok()?).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn remove_state() {
+    if let Ok(path) = state_path() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// Started with `--keeper` instead of as the desktop app
+pub fn is_keeper_launch() -> bool {
+    std::env::args().any(|arg| arg == KEEPER_FLAG)
+}
+
+/// The keeper has no window, so what it does goes to the gateway's error log
+fn log_line(message: &str) {
+    let Ok((_, error_log_path)) = gateway_log_paths() else {
+        return;
+    };
+    if let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(error_log_path)
+    {
+        let _ = writeln!(file, "[keeper] {}", message);
+    }
+}
+
+fn spawn_gateway(port: u16) -> Result<Child, String> {
+    let (log_path, error_log_path) = gateway_log_paths()?;
+    let stdout = open_log(&log_path, false)
+        .map_err(|e| format!("Failed to open gateway log file: {}", e))?;
+    let stderr = open_log(&error_log_path, false)
+        .map_err(|e| format!("Failed to open gateway error log file: {}", e))?;
+    gateway_command(port)?
+        .stdin(Stdio::null())
+        .stdout(Stdio::from(stdout))
+        .stderr(Stdio::from(stderr))
+        .spawn()
+        .map_err(|e| format!("Failed to start gateway: {}", e))
+}
+
+/// Answer one connection; returns true once the app has taken the gateway back
+fn handle(stream: TcpStream, token: &str, gateway_pid: Option<u32>) -> bool {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT));
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(_) => return false,
+    };
+    let mut line = String::new();
+    if BufReader::new(stream).read_line(&mut line).is_err() {
+        return false;
+    }
+    if line.trim() != format!("HANDBACK {}", token) {
+        let _ = writer.write_all(b"ERR unauthorized\n");
+        return false;
+    }
+    let pid = gateway_pid.map_or("-".to_string(), |pid| pid.to_string());
+    writer.write_all(format!("OK {}\n", pid).as_bytes()).is_ok()
+}
+
+fn keep() -> Result<(), String> {
+    let listener = TcpListener::bind(("127.0.0.1", 0))
+        .map_err(|e| format!("Failed to open keeper socket: {}", e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure keeper socket: {}", e))?;
+    let socket_port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to read keeper socket address: {}", e))?
+        .port();
+    let token = generate_token();
+    write_state(&KeeperState {
+        port: socket_port,
+        pid: std::process::id(),
+        token: token.clone(),
+    })?;
+
+    let port = active_gateway_port();
+    let deadline = Instant::now() + PORT_RELEASE_TIMEOUT;
+    while is_port_open(port) && Instant::now() < deadline {
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    let mut child = Some(spawn_gateway(port)?);
+    let mut started_at = Instant::now();
+    let mut restart_at: Option<Instant> = None;
+    let mut attempts = 0u32;
+    log_line(&format!("Supervising gateway on port {}", port));
+
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        if let Ok((stream, _)) = listener.accept() {
+            if handle(stream, &token, child.as_ref().map(Child::id)) {
+                // Dropping the handle leaves the gateway running for the app
+                log_line("Handed the gateway back to the app");
+                return Ok(());
+            }
+        }
+
+        if let Some(at) = restart_at {
+            if Instant::now() >= at {
+                restart_at = None;
+                match spawn_gateway(port) {
+                    Ok(new_child) => {
+                        child = Some(new_child);
+                        started_at = Instant::now();
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            continue;
+        }
+
+        let Some(current) = child.as_mut() else {
+            continue;
+        };
+        if started_at.elapsed() >= STABLE_UPTIME {
+            attempts = 0;
+        }
+        match current.try_wait() {
+            Ok(Some(status)) => {
+                child = None;
+                let config = settings::current().auto_restart;
+                attempts += 1;
+                if !config.enabled || attempts > config.max_attempts {
+                    return Err(format!("Gateway exited ({}); not restarting", status));
+                }
+                let delay = supervisor::backoff(&config, attempts);
+                log_line(&format!(
+                    "Gateway exited ({}); restarting in {}s (attempt {})",
+                    status,
+                    delay.as_secs(),
+                    attempts
+                ));
+                restart_at = Some(Instant::now() + delay);
+            }
+            Ok(None) => {}
+            Err(e) => return Err(format!("Failed to poll gateway process: {}", e)),
+        }
+    }
+}
+
+/// Entry point of the keeper process
+pub fn run() {
+    // Same PATH as the app gets, so npm shims can find node
+    path_env::init();
+    if let Err(e) = keep() {
+        log_line(&format!("Stopped: {}", e));
+    }
+    remove_state();
+}
+
+/// Start the keeper for a gateway the app is about to stop on quit
+pub(crate) fn hand_over() -> Result<(), String> {
+    if connection::is_remote() {
+        return Ok(());
+    }
+    let exe =
+        std::env::current_exe().map_err(|e| format!("Failed to locate app executable: {}", e))?;
+    let mut command = Command::new(exe);
+    command
+        .arg(KEEPER_FLAG)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Detach from the app's process group so it outlives the app and its terminal
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x00000008;
+        command.creation_flags(crate::CREATE_NO_WINDOW | DETACHED_PROCESS);
+    }
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to start keeper: {}", e))
+}
+
+/// Take supervision back from a keeper left running by the last session.
+///
+/// The gateway keeps running and is treated like any adopted gateway; returns
+/// whether one was handed back.
+pub(crate) fn reclaim() -> bool {
+    let Some(state) = read_state() else {
+        return false;
+    };
+    let result = (|| -> Result<Option<u32>, String> {
+        let addr = SocketAddr::from(([127, 0, 0, 1], state.port));
+        let mut stream = TcpStream::connect_timeout(&addr, HANDSHAKE_TIMEOUT)
+            .map_err(|e| format!("Keeper is not answering: {}", e))?;
+        stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)).ok();
+        stream
+            .write_all(format!("HANDBACK {}\n", state.token).as_bytes())
+            .map_err(|e| format!("Failed to reach keeper: {}", e))?;
+        let mut line = String::new();
+        BufReader::new(stream)
+            .read_line(&mut line)
+            .map_err(|e| format!("Keeper did not answer: {}", e))?;
+        let pid = line
+            .trim()
+            .strip_prefix("OK ")
+            .ok_or_else(|| format!("Keeper refused the handback: {}", line.trim()))?;
+        Ok(pid.parse::<u32>().ok())
+    })();
+    // The keeper removes it too, but not if it had already died
+    remove_state();
+
+    match result {
+        Ok(Some(pid)) => {
+            log_error!("Took over gateway (pid {}) from the keeper", pid);
+            supervisor::set_desired_running(true);
+            true
+        }
+        Ok(None) => false,
+        Err(e) => {
+            log_error!("{}", e);
+            false
+        }
+    }
+}
@@ -0,0 +1,311 @@
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime};
+
+use crate::error::DesktopError;
+use crate::{settings, tokens, tray};
+
+/// CLI flag that turns kiosk mode on until the app quits
+pub const KIOSK_FLAG: &str = "--kiosk";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KioskSettings {
+    /// Restrict the app to read-only status and a viewer-mode dashboard
+    pub enabled: bool,
+    /// `pbkdf2-sha256$rounds$salt$hash`, salt and hash hex encoded. Hashes
+    /// from older versions are `salt$sha256(salt + passcode)` and are
+    /// replaced on the next unlock.
+    pub passcode_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KioskStatus {
+    pub enabled: bool,
+    /// Turned on by `--kiosk` for this run; settings can't turn it off
+    pub forced: bool,
+    pub locked: bool,
+    pub has_passcode: bool,
+}
+
+/// Unlocking lasts until the app quits or `lock_kiosk` is called
+static UNLOCKED: AtomicBool = AtomicBool::new(false);
+static FORCED: AtomicBool = AtomicBool::new(false);
+
+const PBKDF2_SCHEME: &str = "pbkdf2-sha256";
+const PBKDF2_ROUNDS: u32 = 200_000;
+
+/// Wrong passcodes allowed in a row before unlocking starts to back off
+const FREE_ATTEMPTS: u32 = 3;
+const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+static FAILED_ATTEMPTS: Mutex<FailedAttempts> = Mutex::new(FailedAttempts {
+    count: 0,
+    retry_at: None,
+});
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn pbkdf2_hash(salt: &str, passcode: &str, rounds: u32) -> String {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passcode.as_bytes(), salt.as_bytes(), rounds, &mut key);
+    hex(&key)
+}
+
+/// Hashes written before passcodes went through PBKDF2
+fn legacy_hash(salt: &str, passcode: &str) -> String {
+    hex(&Sha256::digest(format!("{}{}", salt, passcode).as_bytes()))
+}
+
+pub(crate) fn new_passcode_hash(passcode: &str) -> String {
+    let mut salt = [0u8; 16];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let salt = hex(&salt);
+    let hash = pbkdf2_hash(&salt, passcode, PBKDF2_ROUNDS);
+    format!("{}${}${}${}", PBKDF2_SCHEME, PBKDF2_ROUNDS, salt, hash)
+}
+
+/// Slow to compute by design; keep it off the main thread
+pub(crate) fn verify_passcode(stored: &str, passcode: &str) -> bool {
+    let (computed, hash) = match stored.split('$').collect::<Vec<_>>()[..] {
+        [PBKDF2_SCHEME, rounds, salt, hash] => match rounds.parse() {
+            Ok(rounds) if rounds > 0 => (pbkdf2_hash(salt, passcode, rounds), hash),
+            _ => return false,
+        },
+        [salt, hash] => (legacy_hash(salt, passcode), hash),
+        _ => return false,
+    };
+    tokens::constant_time_eq(&computed, hash)
+}
+
+/// A hash in an older format, to be replaced once the passcode is known
+fn is_legacy_hash(stored: &str) -> bool {
+    !stored.starts_with(&format!("{}$", PBKDF2_SCHEME))
+}
+
+/// Wrong passcodes in a row, and when the next try is allowed
+#[derive(Debug, Default)]
+struct FailedAttempts {
+    count: u32,
+    retry_at: Option<Instant>,
+}
+
+impl FailedAttempts {
+    /// Time left before another try is allowed
+    fn wait(&self, now: Instant) -> Option<Duration> {
+        self.retry_at
+            .map(|at| at.saturating_duration_since(now))
+            .filter(|wait| !wait.is_zero())
+    }
+
+    /// Past the free attempts, each failure doubles the wait, up to a cap
+    fn record_failure(&mut self, now: Instant) {
+        self.count += 1;
+        if self.count > FREE_ATTEMPTS {
+            let doublings = (self.count - FREE_ATTEMPTS).min(16);
+            let backoff = Duration::from_secs(1 << doublings).min(MAX_BACKOFF);
+            self.retry_at = Some(now + backoff);
+        }
+    }
+}
+
+/// Check a passcode against the stored hash, refusing while a previous
+/// failure's backoff lasts. Tries are serialized so they can't be raced.
+fn try_passcode(stored: &str, passcode: &str) -> Result<(), DesktopError> {
+    let mut failed = FAILED_ATTEMPTS
+        .lock()
+        .map_err(|_| "Failed to check the passcode")?;
+    if let Some(wait) = failed.wait(Instant::now()) {
+        return Err(DesktopError::Unavailable(format!(
+            "Too many incorrect passcodes; try again in {} seconds",
+            wait.as_secs().max(1)
+        )));
+    }
+    if !verify_passcode(stored, passcode) {
+        failed.record_failure(Instant::now());
+        return Err(DesktopError::InvalidInput("Incorrect passcode".to_string()));
+    }
+    *failed = FailedAttempts::default();
+    Ok(())
+}
+
+/// `--kiosk` on this launch, or forwarded from a later one
+fn is_forced() -> bool {
+    static LAUNCH_FLAG: OnceLock<bool> = OnceLock::new();
+    FORCED.load(Ordering::SeqCst)
+        || *LAUNCH_FLAG.get_or_init(|| std::env::args().any(|arg| arg == KIOSK_FLAG))
+}
+
+fn is_enabled() -> bool {
+    is_forced() || settings::current().kiosk.enabled
+}
+
+pub fn is_locked() -> bool {
+    is_enabled() && !UNLOCKED.load(Ordering::SeqCst)
+}
+
+/// Lock the app for the rest of this run, as `--kiosk` asks. Without a
+/// passcode in settings it can't be unlocked.
+pub(crate) fn force<R: Runtime>(app: &AppHandle<R>) {
+    FORCED.store(true, Ordering::SeqCst);
+    UNLOCKED.store(false, Ordering::SeqCst);
+    if let Err(e) = tray::rebuild_tray(app) {
+        log_error!("Failed to refresh tray menu: {}", e);
+    }
+}
+
+/// Guard for commands that change state; fails while kiosk mode is locked
+pub fn ensure_unlocked() -> Result<(), DesktopError> {
+    if is_locked() {
+        Err(DesktopError::KioskLocked)
+    } else {
+        Ok(())
+    }
+}
+
+fn status() -> KioskStatus {
+    let kiosk = settings::current().kiosk;
+    KioskStatus {
+        enabled: is_enabled(),
+        forced: is_forced(),
+        locked: is_locked(),
+        has_passcode: kiosk.passcode_hash.is_some(),
+    }
+}
+
+#[tauri::command]
+pub fn get_kiosk_status() -> KioskStatus {
+    status()
+}
+
+/// Turn kiosk mode on or off. Enabling needs a passcode unless one is already set
+/// (e.g. provisioned by policy); disabling needs the app to be unlocked first.
+#[tauri::command]
+pub async fn set_kiosk_mode(
+    enabled: bool,
+    passcode: Option<String>,
+) -> Result<KioskStatus, DesktopError> {
+    ensure_unlocked()?;
+    if !enabled && is_forced() {
+        return Err(DesktopError::InvalidInput(format!(
+            "Kiosk mode was turned on with {} and stays on until the app quits",
+            KIOSK_FLAG
+        )));
+    }
+
+    let passcode = passcode.filter(|p| !p.is_empty());
+    if enabled && passcode.is_none() && settings::current().kiosk.passcode_hash.is_none() {
+        return Err(DesktopError::InvalidInput(
+            "A passcode is required to enable kiosk mode".to_string(),
+        ));
+    }
+
+    let passcode_hash = match passcode {
+        Some(passcode) => Some(
+            tauri::async_runtime::spawn_blocking(move || new_passcode_hash(&passcode))
+                .await
+                .map_err(|e| format!("Failed to hash passcode: {}", e))?,
+        ),
+        None => None,
+    };
+    settings::update(|s| {
+        s.kiosk.enabled = enabled;
+        if let Some(hash) = passcode_hash {
+            s.kiosk.passcode_hash = Some(hash);
+        }
+    })?;
+    UNLOCKED.store(false, Ordering::SeqCst);
+    Ok(status())
+}
+
+#[tauri::command]
+pub async fn unlock_kiosk(passcode: String) -> Result<KioskStatus, DesktopError> {
+    let stored = settings::current()
+        .kiosk
+        .passcode_hash
+        .ok_or("Kiosk mode has no passcode set")?;
+    let rehashed = tauri::async_runtime::spawn_blocking(move || {
+        try_passcode(&stored, &passcode)?;
+        Ok::<_, DesktopError>(is_legacy_hash(&stored).then(|| new_passcode_hash(&passcode)))
+    })
+    .await
+    .map_err(|e| format!("Failed to check the passcode: {}", e))??;
+    if let Some(hash) = rehashed {
+        settings::update(|s| s.kiosk.passcode_hash = Some(hash))?;
+    }
+    UNLOCKED.store(true, Ordering::SeqCst);
+    Ok(status())
+}
+
+#[tauri::command]
+pub fn lock_kiosk() -> KioskStatus {
+    UNLOCKED.store(false, Ordering::SeqCst);
+    status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passcodes_round_trip() {
+        let stored = new_passcode_hash("1234");
+        assert!(stored.starts_with("pbkdf2-sha256$200000$"));
+        assert!(verify_passcode(&stored, "1234"));
+        assert!(!verify_passcode(&stored, "12345"));
+        assert!(!verify_passcode(&stored, ""));
+        assert_ne!(stored, new_passcode_hash("1234"));
+    }
+
+    #[test]
+    fn legacy_hashes_still_verify() {
+        let stored = format!("abcd${}", legacy_hash("abcd", "1234"));
+        assert!(is_legacy_hash(&stored));
+        assert!(verify_passcode(&stored, "1234"));
+        assert!(!verify_passcode(&stored, "4321"));
+        assert!(!is_legacy_hash(&new_passcode_hash("1234")));
+    }
+
+    #[test]
+    fn malformed_hashes_never_verify() {
+        let hash = pbkdf2_hash("abcd", "1234", 1);
+        for stored in [
+            String::new(),
+            "1234".to_string(),
+            format!("pbkdf2-sha256$0$abcd${}", pbkdf2_hash("abcd", "1234", 0)),
+            format!("pbkdf2-sha256$many$abcd${}", hash),
+            format!("pbkdf2-sha256$1$abcd${}$extra", hash),
+        ] {
+            assert!(!verify_passcode(&stored, "1234"), "{}", stored);
+        }
+        assert!(verify_passcode(
+            &format!("pbkdf2-sha256$1$abcd${}", hash),
+            "1234"
+        ));
+    }
+
+    #[test]
+    fn failures_back_off_after_the_free_attempts() {
+        let now = Instant::now();
+        let mut failed = FailedAttempts::default();
+        for _ in 0..FREE_ATTEMPTS {
+            failed.record_failure(now);
+            assert_eq!(failed.wait(now), None);
+        }
+        failed.record_failure(now);
+        assert_eq!(failed.wait(now), Some(Duration::from_secs(2)));
+        assert_eq!(failed.wait(now + Duration::from_secs(2)), None);
+        failed.record_failure(now);
+        assert_eq!(failed.wait(now), Some(Duration::from_secs(4)));
+        for _ in 0..20 {
+            failed.record_failure(now);
+        }
+        assert_eq!(failed.wait(now), Some(MAX_BACKOFF));
+    }
+}
@@ -0,0 +1,236 @@
+//! Importing a pre-rename `~/.clawdbot` installation into `~/.openclaw`.
+//!
+//! Nothing is overwritten: config keys already set in `openclaw.json` win, and
+//! sessions or agents that exist on both sides are skipped. The legacy folder
+//! is left in place. A marker records that the user migrated or declined, so
+//! the frontend only offers it once.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::DesktopError;
+use crate::{audit, config, kiosk, openclaw_home_dir, paths};
+
+const LEGACY_DIR: &str = ".clawdbot";
+const LEGACY_CONFIG: &str = "clawdbot.json";
+const MARKER_FILE: &str = "legacy-migration.json";
+/// Folders copied over, relative to each home
+const COPIED_DIRS: [&str; 2] = ["sessions", "agents"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegacyMarker {
+    /// Unix seconds
+    pub at: u64,
+    /// Declined rather than migrated
+    pub dismissed: bool,
+    pub summary: Option<MigrationSummary>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LegacyInstall {
+    pub path: Option<String>,
+    pub marker: Option<LegacyMarker>,
+    /// Found, and neither migrated nor declined yet
+    pub should_prompt: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigMigration {
+    /// There was no config yet; the legacy one became it
+    Imported,
+    /// Keys missing from `openclaw.json` were filled in from the legacy config
+    Merged,
+    /// Nothing to add, or no legacy config
+    Unchanged,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CopyCounts {
+    pub copied: u64,
+    /// Already present in `~/.openclaw`
+    pub skipped: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationSummary {
+    pub source: String,
+    pub config: ConfigMigration,
+    pub sessions: CopyCounts,
+    pub agents: CopyCounts,
+    /// Files that could not be read or copied
+    pub warnings: Vec<String>,
+}
+
+fn legacy_dir() -> Option<PathBuf> {
+    // An isolated root starts clean
+    if paths::data_profile().is_some() {
+        return None;
+    }
+    let dir = dirs::home_dir()?.join(LEGACY_DIR);
+    dir.is_dir().then_some(dir)
+}
+
+fn marker_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(MARKER_FILE))
+}
+
+fn load_marker() -> Option<LegacyMarker> {
+    let text = fs::read_to_string(marker_path().ok()?).ok()?;
+    serde_json::from_str(&text).ok()
+}
+
+fn save_marker(dismissed: bool, summary: Option<MigrationSummary>) -> Result<(), String> {
+    let marker = LegacyMarker {
+        at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        dismissed,
+        summary,
+    };
+    let content = serde_json::to_string_pretty(&marker)
+        .map_err(|e| format!("Failed to serialize migration marker: {}", e))?;
+    fs::write(marker_path()?, content)
+        .map_err(|e| format!("Failed to write migration marker: {}", e))
+}
+
+/// Add keys from `legacy` that `current` lacks, recursing into objects
+fn fill_missing(current: &mut Value, legacy: &Value) -> bool {
+    let (Value::Object(current), Value::Object(legacy)) = (current, legacy) else {
+        return false;
+    };
+    let mut changed = false;
+    for (key, value) in legacy {
+        match current.get_mut(key) {
+            Some(existing) => changed |= fill_missing(existing, value),
+            None => {
+                current.insert(key.clone(), value.clone());
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+fn migrate_config(legacy: &Path, warnings: &mut Vec<String>) -> ConfigMigration {
+    let path = legacy.join(LEGACY_CONFIG);
+    let Ok(text) = fs::read_to_string(&path) else {
+        return ConfigMigration::Unchanged;
+    };
+    let legacy_config: Value = match serde_json::from_str(&text) {
+        Ok(value) => value,
+        Err(e) => {
+            warnings.push(format!("Skipped {}: {}", path.display(), e));
+            return ConfigMigration::Unchanged;
+        }
+    };
+    let exists = config::editable_path().is_ok_and(|p| p.exists());
+    let (migration, merged) = if exists {
+        let mut current = match config::load_config() {
+            Ok(current) => current,
+            Err(e) => {
+                warnings.push(format!("Skipped config merge: {}", e));
+                return ConfigMigration::Unchanged;
+            }
+        };
+        if !fill_missing(&mut current, &legacy_config) {
+            return ConfigMigration::Unchanged;
+        }
+        (ConfigMigration::Merged, current)
+    } else {
+        (ConfigMigration::Imported, legacy_config)
+    };
+    match config::write_atomic(&merged) {
+        Ok(()) => migration,
+        Err(e) => {
+            warnings.push(e);
+            ConfigMigration::Unchanged
+        }
+    }
+}
+
+/// Copy `from` into `to` file by file, keeping whatever `to` already has
+fn copy_missing(from: &Path, to: &Path, counts: &mut CopyCounts, warnings: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(from) else {
+        return;
+    };
+    if let Err(e) = fs::create_dir_all(to) {
+        warnings.push(format!("Failed to create {}: {}", to.display(), e));
+        return;
+    }
+    for entry in entries.flatten() {
+        let (source, target) = (entry.path(), to.join(entry.file_name()));
+        let Ok(metadata) = source.symlink_metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            copy_missing(&source, &target, counts, warnings);
+        } else if !metadata.is_file() {
+            continue;
+        } else if target.exists() {
+            counts.skipped += 1;
+        } else {
+            match fs::copy(&source, &target) {
+                Ok(_) => counts.copied += 1,
+                Err(e) => warnings.push(format!("Failed to copy {}: {}", source.display(), e)),
+            }
+        }
+    }
+}
+
+fn migrate() -> Result<MigrationSummary, String> {
+    let legacy = legacy_dir().ok_or("No legacy .clawdbot installation was found")?;
+    let home = openclaw_home_dir()?;
+    let mut warnings = Vec::new();
+
+    let config = migrate_config(&legacy, &mut warnings);
+    let mut counts = [CopyCounts::default(), CopyCounts::default()];
+    for (dir, counts) in COPIED_DIRS.iter().zip(counts.iter_mut()) {
+        copy_missing(&legacy.join(dir), &home.join(dir), counts, &mut warnings);
+    }
+    let [sessions, agents] = counts;
+
+    let summary = MigrationSummary {
+        source: legacy.display().to_string(),
+        config,
+        sessions,
+        agents,
+        warnings,
+    };
+    save_marker(false, Some(summary.clone()))?;
+    Ok(summary)
+}
+
+/// Whether a legacy installation exists and the user still needs to be asked
+#[tauri::command]
+pub fn get_legacy_install() -> LegacyInstall {
+    let path = legacy_dir();
+    let marker = load_marker();
+    LegacyInstall {
+        should_prompt: path.is_some() && marker.is_none(),
+        path: path.map(|p| p.display().to_string()),
+        marker,
+    }
+}
+
+/// Bring config, sessions and agents over from `~/.clawdbot`
+#[tauri::command]
+pub async fn migrate_legacy_install() -> Result<MigrationSummary, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let result = tauri::async_runtime::spawn_blocking(migrate)
+        .await
+        .map_err(|e| format!("Failed to migrate legacy install: {}", e))?;
+    audit::record_result("legacy_migrate", LEGACY_DIR, &result);
+    Ok(result?)
+}
+
+/// Decline the migration so it isn't offered again
+#[tauri::command]
+pub fn dismiss_legacy_migration() -> Result<LegacyInstall, DesktopError> {
+    save_marker(true, None)?;
+    Ok(get_legacy_install())
+}
@@ -1,160 +1,375 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::fs::{self, OpenOptions};
+use std::fs::{File, OpenOptions};
 use std::net::TcpStream;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
-use tauri::{
-    menu::{Menu, MenuItem},
-    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    Manager, Runtime,
-};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+use ts_rs::TS;
+
+use availability::ActionId;
+use error::DesktopError;
+use events::{DashboardUnreachable, DashboardUnreachableReason, DASHBOARD_UNREACHABLE_EVENT};
+use gateway_core::{Core, GatewayDiagnostics, StartAction};
+use gateway_exit::ExitInfo;
+use permissions::Permission;
+use readiness::GatewayStartResult;
+
+/// `eprintln!` into the app log, which release builds can still be diagnosed from
+macro_rules! log_error {
+    ($($arg:tt)*) => {
+        $crate::app_log::write(&format!($($arg)*))
+    };
+}
+
+mod access_log;
+mod agents;
+mod annotations;
+mod announce;
+mod app_log;
+mod app_state;
+mod app_update;
+mod approvals;
+mod archive;
+mod attachments;
+mod audit;
+mod auto_lock;
+mod automation_api;
+mod autostart;
+mod availability;
+mod backup;
+mod benchmark;
+mod binary;
+mod capabilities;
+mod chat;
+mod cli;
+mod cli_runner;
+mod compatibility;
+mod config;
+mod config_bundle;
+mod config_conflict;
+mod connection;
+mod connectivity;
+mod crash_report;
+mod dashboard_proxy;
+mod deep_link;
+mod dependencies;
+mod diagnostics;
+mod disk_space;
+mod doctor;
+mod error;
+mod event_bridge;
+mod events;
+mod external;
+mod focus;
+mod gateway_api;
+mod gateway_core;
+mod gateway_env;
+mod gateway_exit;
+mod gateway_log_level;
+mod gateway_process;
+mod gateways;
+mod global_shortcut;
+mod home_backup;
+mod i18n;
+mod idle;
+mod install_repair;
+mod installer;
+mod integrations;
+mod ipc_metrics;
+mod keeper;
+mod kiosk;
+mod legacy;
+mod log_rotation;
+mod log_stream;
+mod login_start;
+mod logs;
+mod mcp;
+mod metrics;
+mod monitor;
+mod notices;
+mod notifications;
+mod onboarding;
+mod path_env;
+mod paths;
+mod pause;
+mod permissions;
+mod platform;
+mod policy;
+mod port_owner;
+mod power;
+mod preflight;
+mod prerequisites;
+mod presence;
+mod probe;
+mod process;
+mod profiles;
+mod profiling;
+mod providers;
+mod proxy;
+mod quota;
+mod readiness;
+mod remote_logs;
+mod resources;
+mod safe_mode;
+mod safety_mode;
+mod schedule;
+mod screen_capture;
+mod secrets;
+mod selftest;
+#[cfg(feature = "service")]
+mod service;
+mod service_unit;
+mod session_export;
+mod session_search;
+mod sessions;
+mod settings;
+mod share;
+mod shutdown;
+mod skills;
+mod snapshots;
+mod socket_transport;
+mod start_lock;
+mod storage;
+mod storage_quota;
+mod supervisor;
+mod tasks;
+mod terminal;
+mod theme;
+mod token_health;
+mod tokens;
+mod traces;
+mod tray;
+mod updater;
+mod upgrade;
+mod usage;
+mod warmup;
+mod window;
+mod window_state;
+mod workspaces;
 
 #[cfg(target_os = "windows")]
-const CREATE_NO_WINDOW: u32 = 0x08000000;
+pub(crate) const CREATE_NO_WINDOW: u32 = 0x08000000;
 
-const GATEWAY_PORT: u16 = 18789;
+pub(crate) const GATEWAY_PORT: u16 = 18789;
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GatewayDiagnostics {
-    pub openclaw_installed: bool,
-    pub gateway_running: bool,
-    pub gateway_port: u16,
-    pub dashboard_url: String,
-    pub openclaw_version: Option<String>,
-    pub profile_name: Option<String>,
-    pub log_path: String,
-    pub error_log_path: String,
-}
-
-fn openclaw_home_dir() -> Result<PathBuf, String> {
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
-    let openclaw_dir = home.join(".openclaw");
-    if !openclaw_dir.exists() {
-        fs::create_dir_all(&openclaw_dir)
-            .map_err(|e| format!("Failed to create OpenClaw directory: {}", e))?;
+/// Port of the default gateway; moves off 18789 after a failover upgrade
+pub(crate) fn active_gateway_port() -> u16 {
+    settings::current().gateway_port.unwrap_or(GATEWAY_PORT)
+}
+
+pub(crate) fn dashboard_base_url() -> String {
+    format!("{}/", connection::base_url())
+}
+
+/// Dashboard URL with the auth token in the query string, for when the user
+/// explicitly copies a URL that logs in by itself.
+///
+/// A locked kiosk gets the read-only viewer mode and never sees the token.
+pub(crate) fn tokenized_dashboard_url() -> String {
+    let base_url = dashboard_base_url();
+    if kiosk::is_locked() {
+        return format!("{}?mode=viewer", base_url);
+    }
+    match read_gateway_token() {
+        Some(token) => format!("{}?token={}", base_url, urlencoding::encode(&token)),
+        None => base_url,
     }
-    Ok(openclaw_dir)
 }
 
-fn gateway_log_paths() -> Result<(PathBuf, PathBuf), String> {
-    let openclaw_dir = openclaw_home_dir()?;
+/// The data root for this launch; see [`paths::data_root`]
+pub(crate) fn openclaw_home_dir() -> Result<PathBuf, String> {
+    paths::data_root()
+}
+
+pub(crate) fn gateway_log_paths() -> Result<(PathBuf, PathBuf), String> {
+    let log_dir = paths::log_dir()?;
     Ok((
-        openclaw_dir.join("gateway.log"),
-        openclaw_dir.join("gateway_error.log"),
+        log_dir.join("gateway.log"),
+        log_dir.join("gateway_error.log"),
     ))
 }
 
 fn openclaw_command() -> Command {
-    #[cfg(target_os = "windows")]
-    {
-        let mut cmd = Command::new("cmd");
-        cmd.args(["/c", "openclaw"]);
-        cmd
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-        Command::new("openclaw")
+    let mut cmd = match binary::resolve() {
+        Some((path, _)) => binary_command(&path),
+        None => openclaw_path_command(),
+    };
+
+    if let Some(profile) = profiles::active_profile() {
+        cmd.args(["--profile", &profile]);
     }
+    proxy::apply_env(&mut cmd);
+    paths::apply_env(&mut cmd);
+    cmd
 }
 
-fn run_openclaw_output(args: &[&str]) -> Result<std::process::Output, String> {
-    openclaw_command()
-        .args(args)
-        .output()
-        .map_err(|e| format!("Failed to run openclaw {}: {}", args.join(" "), e))
+/// Run a resolved binary, `.cmd` shims included, without going through a shell
+fn binary_command(path: &Path) -> Command {
+    paths::program_command(path)
 }
 
-fn run_openclaw_gateway_control(action: &str) -> Result<String, String> {
-    let output = run_openclaw_output(&["daemon", action])?;
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        if stdout.is_empty() {
-            Ok(format!("Gateway {} command sent", action))
-        } else {
-            Ok(stdout)
-        }
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        if stderr.is_empty() {
-            Err(format!("openclaw gateway {} failed", action))
-        } else {
-            Err(stderr)
-        }
+/// `openclaw` looked up on PATH; the bare name is left to fail with not-found
+fn openclaw_path_command() -> Command {
+    match paths::find_executable("openclaw") {
+        Some(path) => binary_command(&path),
+        None => Command::new("openclaw"),
     }
 }
 
-fn start_gateway_foreground_to_logs() -> Result<(), String> {
-    let (log_path, error_log_path) = gateway_log_paths()?;
+async fn run_openclaw_output(args: &[&str]) -> Result<std::process::Output, String> {
+    run_openclaw_output_timeout(args, process::DEFAULT_TIMEOUT).await
+}
 
-    let stdout_file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(&log_path)
-        .map_err(|e| format!("Failed to open gateway log file: {}", e))?;
+async fn run_openclaw_output_timeout(
+    args: &[&str],
+    timeout: std::time::Duration,
+) -> Result<std::process::Output, String> {
+    config::refresh_for_daemon(args)?;
+    let mut cmd = openclaw_command();
+    cmd.args(args);
+    process::output(cmd, timeout).await
+}
 
-    let stderr_file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(&error_log_path)
-        .map_err(|e| format!("Failed to open gateway error log file: {}", e))?;
+/// For callers already on a blocking thread
+fn run_openclaw_output_blocking(
+    args: &[&str],
+    timeout: std::time::Duration,
+) -> Result<std::process::Output, String> {
+    config::refresh_for_daemon(args)?;
+    let mut cmd = openclaw_command();
+    cmd.args(args);
+    process::output_blocking(cmd, timeout)
+}
+
+pub(crate) fn run_openclaw_gateway_control(action: &str) -> Result<String, String> {
+    let output = run_openclaw_output_blocking(&["daemon", action], process::DEFAULT_TIMEOUT)?;
+    gateway_core::control_result(action, output.into())
+}
+
+/// Open a log for the gateway to write, optionally emptied for the new run.
+///
+/// Append mode matters: log rotation truncates the file while the gateway is
+/// still writing, and without it the child would keep writing at its old offset.
+pub(crate) fn open_log(path: &Path, truncate: bool) -> std::io::Result<File> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    if truncate {
+        file.set_len(0)?;
+    }
+    Ok(file)
+}
+
+/// `openclaw gateway` for the given port, with the resolved config and keychain token
+/// in its environment; the caller decides where output goes
+pub(crate) fn gateway_command(port: u16) -> Result<Command, String> {
+    let socket = connection::socket_path();
+    if socket.is_none() {
+        policy::check_port(port)?;
+    }
 
     let mut command = openclaw_command();
-    command
-        .args(["gateway", "--port", &GATEWAY_PORT.to_string(), "--verbose"])
-        .stdout(Stdio::from(stdout_file))
-        .stderr(Stdio::from(stderr_file));
+    if let Some(resolved) = config::materialize()? {
+        command.env("OPENCLAW_CONFIG_PATH", resolved);
+    }
+    // A token moved to the keychain is no longer in openclaw.json for the gateway to read
+    if let Some(token) = secrets::keychain_token() {
+        command.env("OPENCLAW_GATEWAY_TOKEN", token);
+    }
+    providers::apply_keychain_env(&mut command);
+    gateway_env::apply(&mut command);
+    if let Some(trace_file) = traces::env_trace_file() {
+        command.env("OPENCLAW_TRACE_FILE", trace_file);
+    }
+    match &socket {
+        Some(socket) => {
+            socket_transport::remove_stale(socket);
+            command.arg("gateway").arg("--socket").arg(socket);
+        }
+        None => {
+            command.args(["gateway", "--port", &port.to_string()]);
+        }
+    }
+    gateway_log_level::apply(&mut command);
+    safety_mode::apply(&mut command);
 
     #[cfg(target_os = "windows")]
     {
         command.creation_flags(CREATE_NO_WINDOW);
     }
+    Ok(command)
+}
+
+/// Spawn `openclaw gateway` on the given port with stdout/stderr redirected to log files.
+///
+/// The files keep the full verbose output; `log_stream` tails them and forwards
+/// only the UI's chosen levels as events. With `fresh_logs` the files are emptied
+/// first; otherwise output is appended, so a standby gateway can share logs with
+/// the instance it replaces.
+pub(crate) fn spawn_gateway_process(
+    port: u16,
+    log_path: &Path,
+    error_log_path: &Path,
+    fresh_logs: bool,
+) -> Result<Child, String> {
+    let stdout_file = open_log(log_path, fresh_logs)
+        .map_err(|e| format!("Failed to open gateway log file: {}", e))?;
 
+    let stderr_file = open_log(error_log_path, fresh_logs)
+        .map_err(|e| format!("Failed to open gateway error log file: {}", e))?;
+
+    let mut command = gateway_command(port)?;
     command
+        .stdout(Stdio::from(stdout_file))
+        .stderr(Stdio::from(stderr_file));
+
+    let child = command
         .spawn()
         .map_err(|e| format!("Failed to start gateway: {}", e))?;
+    log_stream::follow("stdout", log_path);
+    log_stream::follow("stderr", error_log_path);
+    Ok(child)
+}
 
-    Ok(())
+pub(crate) fn detect_openclaw_version() -> Option<String> {
+    let output = run_openclaw_output_blocking(&["--version"], process::VERSION_TIMEOUT).ok()?;
+    gateway_core::version_from(output.into())
 }
 
-fn detect_openclaw_version() -> Option<String> {
-    let output = run_openclaw_output(&["--version"]).ok()?;
-    if !output.status.success() {
-        return None;
-    }
-    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if text.is_empty() {
-        None
-    } else {
-        Some(text)
+/// Gateway token from the OS keychain, falling back to the OpenClaw config file
+pub(crate) fn read_gateway_token() -> Option<String> {
+    if connection::is_remote() {
+        return secrets::remote_connection_token();
     }
+    secrets::keychain_token().or_else(read_config_token)
 }
 
 /// Read the gateway token from OpenClaw config file
-fn read_gateway_token() -> Option<String> {
-    // Try to find the config file
-    let home = dirs::home_dir()?;
-    let config_path = home.join(".openclaw").join("openclaw.json");
-
-    if !config_path.exists() {
-        // Try legacy path
-        let legacy_path = home.join(".clawdbot").join("clawdbot.json");
-        if legacy_path.exists() {
-            return read_token_from_file(&legacy_path);
+pub(crate) fn read_config_token() -> Option<String> {
+    // A templated config may pull the token from a variable
+    if let Ok(config) = config::load_resolved_config() {
+        if let Some(token) = config
+            .pointer("/gateway/auth/token")
+            .and_then(Value::as_str)
+        {
+            return Some(token.to_string());
         }
-        return None;
     }
 
+    // Try to find the config file, in whichever format it was written
+    let config_path = config::find_config(&paths::data_root().ok()?)
+        // Try legacy path, which only the shared root inherits
+        .or_else(|| {
+            let legacy_path = dirs::home_dir()?.join(".clawdbot").join("clawdbot.json");
+            (paths::data_profile().is_none() && legacy_path.exists()).then_some(legacy_path)
+        })?;
+
     read_token_from_file(&config_path)
 }
 
-fn read_token_from_file(path: &PathBuf) -> Option<String> {
-    let content = fs::read_to_string(path).ok()?;
-    let json: Value = serde_json::from_str(&content).ok()?;
+fn read_token_from_file(path: &Path) -> Option<String> {
+    let json = config::read_config_file(path).ok()?;
     json.get("gateway")
         .and_then(|g| g.get("auth"))
         .and_then(|a| a.get("token"))
@@ -162,103 +377,171 @@ fn read_token_from_file(path: &PathBuf) -> Option<String> {
         .map(|s| s.to_string())
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
 pub struct GatewayStatus {
     pub running: bool,
+    /// Running, with agents paused
+    pub paused: bool,
     pub port: u16,
     pub dashboard_url: String,
+    pub last_exit: Option<ExitInfo>,
+    pub probe: probe::GatewayProbeResult,
+    /// Timings of the warm-up run after the gateway last became ready
+    pub warmup: Option<warmup::WarmupReport>,
+}
+
+/// Check whether something is listening on a local port
+pub(crate) fn is_port_open(port: u16) -> bool {
+    TcpStream::connect(format!("127.0.0.1:{}", port)).is_ok()
 }
 
-/// Check if the OpenClaw gateway is running by probing the port
-fn is_gateway_running() -> bool {
-    TcpStream::connect(format!("127.0.0.1:{}", GATEWAY_PORT)).is_ok()
+/// The gateway's health endpoint answers on the active port
+pub(crate) fn is_gateway_running() -> bool {
+    probe::probe_gateway().verified
 }
 
 /// Get the current gateway status
 #[tauri::command]
 fn get_gateway_status() -> GatewayStatus {
+    gateway_status()
+}
+
+/// Probe the gateway and assemble its status
+pub(crate) fn gateway_status() -> GatewayStatus {
+    let probe = probe::probe_gateway();
     GatewayStatus {
-        running: is_gateway_running(),
-        port: GATEWAY_PORT,
-        dashboard_url: format!("http://127.0.0.1:{}/", GATEWAY_PORT),
+        running: probe.verified,
+        paused: probe.verified && pause::is_paused(),
+        port: probe.port,
+        dashboard_url: dashboard_base_url(),
+        last_exit: gateway_exit::last_exit(),
+        probe,
+        warmup: warmup::last_report(),
     }
 }
 
-/// Start the OpenClaw gateway
-#[tauri::command]
-fn start_gateway() -> Result<String, String> {
-    if is_gateway_running() {
-        return Ok("Gateway is already running".to_string());
-    }
+/// Start the local gateway with the same lock and pre-start checks as the
+/// `start_gateway` command, or report why it wasn't started
+pub(crate) fn start_default_gateway<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    app.state::<Core>().start(false)?.into_message()
+}
 
-    start_gateway_foreground_to_logs()?;
+/// Stop via `openclaw daemon stop`, falling back to killing the child we spawned
+pub(crate) fn stop_default_gateway<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    app.state::<Core>().stop()
+}
 
-    Ok("Gateway starting...".to_string())
+/// Start the OpenClaw gateway and wait until it passes its health check.
+/// Pre-start checks run first unless `force` is set.
+#[tauri::command]
+async fn start_gateway(
+    app: AppHandle,
+    force: Option<bool>,
+) -> Result<GatewayStartResult, DesktopError> {
+    availability::ensure(ActionId::StartGateway)?;
+    permissions::ensure_granted(&app, Permission::ControlGateway).await?;
+    Ok(start_and_wait(&app, force.unwrap_or(false)).await?)
+}
+
+/// Start the local gateway and wait until it passes its health check, or
+/// report why it wasn't started
+pub(crate) async fn start_and_wait(
+    app: &AppHandle,
+    force: bool,
+) -> Result<GatewayStartResult, String> {
+    let task_app = app.clone();
+    let started =
+        tauri::async_runtime::spawn_blocking(move || task_app.state::<Core>().start(force))
+            .await
+            .map_err(|e| format!("Failed to start gateway: {}", e))??;
+    Ok(match started {
+        StartAction::AlreadyStarting(lock) => GatewayStartResult::already_starting(&lock),
+        StartAction::Blocked(validation) => GatewayStartResult::blocked(&validation),
+        StartAction::AlreadyRunning | StartAction::Spawned => {
+            readiness::wait_until_ready(app).await
+        }
+    })
 }
 
 /// Stop the OpenClaw gateway
 #[tauri::command]
-fn stop_gateway() -> Result<String, String> {
-    run_openclaw_gateway_control("stop")
+async fn stop_gateway(app: AppHandle) -> Result<String, DesktopError> {
+    availability::ensure(ActionId::StopGateway)?;
+    permissions::ensure_granted(&app, Permission::ControlGateway).await?;
+    let message = tauri::async_runtime::spawn_blocking(move || stop_default_gateway(&app))
+        .await
+        .map_err(|e| format!("Failed to stop gateway: {}", e))??;
+    Ok(message)
+}
+
+/// Restart via `openclaw daemon restart`, falling back to respawning the child we own
+pub(crate) fn restart_default_gateway<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    app.state::<Core>().restart()
 }
 
 /// Restart the OpenClaw gateway
 #[tauri::command]
-fn restart_gateway() -> Result<String, String> {
-    run_openclaw_gateway_control("restart")
+async fn restart_gateway(app: AppHandle) -> Result<String, DesktopError> {
+    availability::ensure(ActionId::RestartGateway)?;
+    permissions::ensure_granted(&app, Permission::ControlGateway).await?;
+    let message = tauri::async_runtime::spawn_blocking(move || restart_default_gateway(&app))
+        .await
+        .map_err(|e| format!("Failed to restart gateway: {}", e))??;
+    Ok(message)
 }
 
-/// Auto-start gateway if not already running (called on app launch)
+/// Auto-start gateway if not already running (called on app launch), with the
+/// same pre-start checks as `start_gateway`. Returns `None` when it was already up.
 #[tauri::command]
-fn auto_start_gateway() -> Result<bool, String> {
-    if is_gateway_running() {
-        Ok(false) // already running
-    } else {
-        start_gateway_foreground_to_logs()?;
-        Ok(true) // started
+async fn auto_start_gateway(app: AppHandle) -> Result<Option<GatewayStartResult>, DesktopError> {
+    if connection::is_remote() {
+        return Ok(None);
     }
+    let task_app = app.clone();
+    let started =
+        tauri::async_runtime::spawn_blocking(move || task_app.state::<Core>().start(false))
+            .await
+            .map_err(|e| format!("Failed to start gateway: {}", e))??;
+    Ok(match started {
+        StartAction::AlreadyRunning => None,
+        StartAction::Spawned => Some(readiness::wait_until_ready(&app).await),
+        StartAction::AlreadyStarting(lock) => Some(GatewayStartResult::already_starting(&lock)),
+        StartAction::Blocked(validation) => Some(GatewayStartResult::blocked(&validation)),
+    })
 }
 
 /// Check if OpenClaw is installed
 #[tauri::command]
-fn is_openclaw_installed() -> bool {
-    #[cfg(target_os = "windows")]
-    {
-        Command::new("cmd")
-            .args(["/c", "where", "openclaw"])
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-    }
+async fn is_openclaw_installed() -> bool {
+    tauri::async_runtime::spawn_blocking(openclaw_installed)
+        .await
+        .unwrap_or(false)
+}
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        Command::new("which")
-            .arg("openclaw")
-            .output()
-            .map(|o| o.status.success())
-            .unwrap_or(false)
-    }
+pub(crate) fn openclaw_installed() -> bool {
+    binary::resolve().is_some()
 }
 
 #[tauri::command]
-fn get_gateway_diagnostics() -> Result<GatewayDiagnostics, String> {
-    let (log_path, error_log_path) = gateway_log_paths()?;
-    Ok(GatewayDiagnostics {
-        openclaw_installed: is_openclaw_installed(),
-        gateway_running: is_gateway_running(),
-        gateway_port: GATEWAY_PORT,
-        dashboard_url: format!("http://127.0.0.1:{}/", GATEWAY_PORT),
-        openclaw_version: detect_openclaw_version(),
-        profile_name: std::env::var("OPENCLAW_PROFILE").ok(),
-        log_path: log_path.display().to_string(),
-        error_log_path: error_log_path.display().to_string(),
-    })
+async fn get_gateway_diagnostics(app: AppHandle) -> Result<GatewayDiagnostics, DesktopError> {
+    let diagnostics =
+        tauri::async_runtime::spawn_blocking(move || app.state::<Core>().diagnostics())
+            .await
+            .map_err(|e| format!("Failed to collect diagnostics: {}", e))?;
+    Ok(diagnostics)
 }
 
 #[tauri::command]
-fn run_openclaw_doctor() -> Result<String, String> {
-    let output = run_openclaw_output(&["doctor"])?;
+async fn run_openclaw_doctor(app: AppHandle) -> Result<String, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    // Dropping the doctor future on cancel kills the CLI
+    let task = tasks::Task::start(&app, tasks::TaskKind::Doctor);
+    task.run(doctor_output()).await
+}
+
+async fn doctor_output() -> Result<String, DesktopError> {
+    let output = run_openclaw_output_timeout(&["doctor"], process::DOCTOR_TIMEOUT).await?;
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
     let stderr = String::from_utf8_lossy(&output.stderr).to_string();
 
@@ -278,48 +561,83 @@ fn run_openclaw_doctor() -> Result<String, String> {
             msg.push_str("\n\n");
             msg.push_str(stdout.trim());
         }
-        Err(msg)
+        Err(msg.into())
     }
 }
 
-/// Install OpenClaw via npm
-#[tauri::command]
-async fn install_openclaw() -> Result<String, String> {
-    let output = Command::new("npm")
-        .args(["install", "-g", "openclaw"])
-        .output()
+/// Run `npm install -g <spec>`, surfacing npm's stderr on failure
+pub(crate) fn npm_install_global(spec: &str) -> Result<(), String> {
+    let mut command = prerequisites::tool_command("npm");
+    command.args(["install", "-g", spec]);
+    proxy::apply_env(&mut command);
+    let output = process::output_blocking(command, process::INSTALL_TIMEOUT)
         .map_err(|e| format!("Failed to install: {}", e))?;
 
     if output.status.success() {
-        Ok("OpenClaw installed successfully".to_string())
+        Ok(())
     } else {
         Err(String::from_utf8_lossy(&output.stderr).to_string())
     }
 }
 
-/// Get the dashboard URL with auth token for iframe embedding
+/// Get the dashboard URL for iframe embedding, logged in with a one-time code when supported
 #[tauri::command]
-fn get_dashboard_url() -> String {
-    let base_url = format!("http://127.0.0.1:{}/", GATEWAY_PORT);
-    match read_gateway_token() {
-        Some(token) => format!("{}?token={}", base_url, urlencoding::encode(&token)),
-        None => base_url,
-    }
+async fn get_dashboard_url() -> Result<String, DesktopError> {
+    auto_lock::ensure_unlocked()?;
+    secrets::dashboard_login_url().await
 }
 
-/// Navigate main window to the dashboard
+/// The local proxy when it runs, so the token never appears in the address
+pub(crate) async fn dashboard_webview_url() -> Result<String, DesktopError> {
+    let url = match dashboard_proxy::dashboard_url() {
+        Some(url) => url,
+        None => secrets::dashboard_login_url().await?,
+    };
+    Ok(theme::with_theme_param(url))
+}
+
+/// Why the dashboard can't be shown right now, if it can't
+async fn dashboard_unreachable() -> Option<DashboardUnreachable> {
+    let probe = tauri::async_runtime::spawn_blocking(probe::probe_gateway)
+        .await
+        .unwrap_or_default();
+    let reason = match probe.http_status {
+        _ if probe.verified => return None,
+        Some(401 | 403) => DashboardUnreachableReason::Unauthorized,
+        _ if !probe.reachable => DashboardUnreachableReason::GatewayDown,
+        _ => DashboardUnreachableReason::Unhealthy,
+    };
+    Some(DashboardUnreachable {
+        reason,
+        message: probe
+            .error
+            .unwrap_or_else(|| "Gateway is not running".to_string()),
+    })
+}
+
+/// Navigate main window to the dashboard, after checking it will load.
+///
+/// When it wouldn't, the window stays put and `dashboard-unreachable` says why,
+/// instead of the webview showing a blank page.
 #[tauri::command]
-async fn open_dashboard_window(app: tauri::AppHandle) -> Result<(), String> {
+async fn open_dashboard_window(app: tauri::AppHandle) -> Result<(), DesktopError> {
+    availability::ensure(ActionId::OpenDashboard)?;
+    if let Some(unreachable) = dashboard_unreachable().await {
+        if let Err(e) = app.emit(DASHBOARD_UNREACHABLE_EVENT, &unreachable) {
+            log_error!("Failed to emit dashboard-unreachable event: {}", e);
+        }
+        return Err(DesktopError::Unavailable(unreachable.message));
+    }
     // Get the main window and navigate it to the dashboard
-    if let Some(window) = app.get_webview_window("main") {
-        // Build tokenized URL for authentication
-        let base_url = format!("http://127.0.0.1:{}/", GATEWAY_PORT);
-        let dashboard_url = match read_gateway_token() {
-            Some(token) => format!("{}?token={}", base_url, urlencoding::encode(&token)),
-            None => base_url,
-        };
+    if let Ok(window) = window::main_window(&app) {
+        let base = dashboard_webview_url().await?;
         window
-            .navigate(dashboard_url.parse().unwrap())
+            .navigate(match deep_link::take_pending_url(&base) {
+                Some(url) => url,
+                None => base
+                    .parse()
+                    .map_err(|e| format!("Invalid dashboard URL {}: {}", base, e))?,
+            })
             .map_err(|e| format!("Failed to navigate: {}", e))?;
     }
     Ok(())
@@ -327,147 +645,389 @@ async fn open_dashboard_window(app: tauri::AppHandle) -> Result<(), String> {
 
 /// Get the gateway logs from the log file
 #[tauri::command]
-fn get_gateway_logs(lines: Option<usize>) -> Result<String, String> {
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
-    let log_path = home.join(".openclaw").join("gateway.log");
+fn get_gateway_logs(lines: Option<usize>) -> Result<String, DesktopError> {
+    let (log_path, _) = gateway_log_paths()?;
 
     if !log_path.exists() {
         return Ok("No logs available yet. Start the gateway to see logs.".to_string());
     }
 
-    let content =
-        fs::read_to_string(&log_path).map_err(|e| format!("Failed to read log file: {}", e))?;
-
-    let max_lines = lines.unwrap_or(100);
-    let log_lines: Vec<&str> = content.lines().collect();
-    let start = if log_lines.len() > max_lines {
-        log_lines.len() - max_lines
-    } else {
-        0
-    };
-
-    Ok(log_lines[start..].join("\n"))
+    Ok(logs::tail_lines(&log_path, lines.unwrap_or(100))?.join("\n"))
 }
 
-/// Clear the gateway logs
+/// Get the gateway's stderr output from gateway_error.log
 #[tauri::command]
-fn clear_gateway_logs() -> Result<(), String> {
-    let home = dirs::home_dir().ok_or("Could not find home directory")?;
-    let log_path = home.join(".openclaw").join("gateway.log");
+fn get_gateway_error_logs(lines: Option<usize>) -> Result<String, DesktopError> {
+    let (_, error_log_path) = gateway_log_paths()?;
 
-    if log_path.exists() {
-        fs::write(&log_path, "").map_err(|e| format!("Failed to clear logs: {}", e))?;
+    if !error_log_path.exists() {
+        return Ok("No errors logged.".to_string());
     }
 
-    Ok(())
+    Ok(logs::tail_lines(&error_log_path, lines.unwrap_or(100))?.join("\n"))
 }
 
-fn create_tray_menu<R: Runtime>(app: &tauri::AppHandle<R>) -> tauri::Result<Menu<R>> {
-    let status = if is_gateway_running() {
-        "🟢 Running"
-    } else {
-        "🔴 Stopped"
-    };
-
-    let menu = Menu::with_items(
-        app,
-        &[
-            &MenuItem::with_id(app, "status", status, false, None::<&str>)?,
-            &MenuItem::with_id(app, "separator", "─────────", false, None::<&str>)?,
-            &MenuItem::with_id(app, "start", "▶ Start Gateway", true, None::<&str>)?,
-            &MenuItem::with_id(app, "stop", "⏹ Stop Gateway", true, None::<&str>)?,
-            &MenuItem::with_id(app, "dashboard", "🌐 Open Dashboard", true, None::<&str>)?,
-            &MenuItem::with_id(app, "separator2", "─────────", false, None::<&str>)?,
-            &MenuItem::with_id(app, "quit", "✖ Quit", true, None::<&str>)?,
-        ],
-    )?;
-
-    Ok(menu)
+/// Clear the gateway logs
+#[tauri::command]
+fn clear_gateway_logs(core: State<'_, Core>) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    Ok(core.clear_logs()?)
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
+    // Started by the service manager to host the gateway, not as the desktop app
+    #[cfg(all(target_os = "windows", feature = "service"))]
+    if service::host::is_service_launch() {
+        service::host::run();
+        return;
+    }
+    // Relaunched headless to look after the gateway while the app is closed
+    if keeper::is_keeper_launch() {
+        keeper::run();
+        return;
+    }
+    // `config get|set` from scripts, answered without opening a window
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run_config_command(&args) {
+        std::process::exit(code);
+    }
+
+    crash_report::install_panic_hook(env!("CARGO_PKG_VERSION"));
+    path_env::init();
+
+    let builder = tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
-            // Focus existing window instead of opening a duplicate
-            if let Some(w) = app.get_webview_window("main") {
-                let _ = w.show();
-                let _ = w.set_focus();
-            }
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_updater::Builder::new().build());
+    // An isolated data profile runs alongside the shared instance and other profiles
+    let builder = if paths::data_profile().is_none() {
+        builder.plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            // Focus existing window instead of opening a duplicate, then run
+            // whatever the second launch was asked to do
+            window::on_second_instance(app);
+            cli::handle(app, args);
         }))
+    } else {
+        builder
+    };
+    builder
+        .plugin(tauri_plugin_deep_link::init())
+        .manage(gateways::GatewayManager::default())
+        .manage(capabilities::CapabilityCache::default())
+        .manage(gateway_process::GatewayProcess::default())
+        .manage(supervisor::Supervisor::default())
+        .manage(ipc_metrics::IpcMetrics::default())
+        .manage(gateway_api::GatewayApi::default())
+        .manage(terminal::TerminalSessions::default())
         .setup(|app| {
+            app.manage(Core::system(app.handle())?);
+            // A keeper from the last session hands its gateway back before anything
+            // decides whether to start one
+            keeper::reclaim();
+            cli::mark_running();
+
             // Create system tray
-            let menu = create_tray_menu(app.handle())?;
-
-            let _tray = TrayIconBuilder::new()
-                .icon(app.default_window_icon().unwrap().clone())
-                .menu(&menu)
-                .show_menu_on_left_click(false)
-                .on_menu_event(|app, event| match event.id.as_ref() {
-                    "start" => {
-                        let _ = start_gateway();
-                    }
-                    "stop" => {
-                        let _ = stop_gateway();
-                    }
-                    "dashboard" => {
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                    "quit" => {
-                        app.exit(0);
-                    }
-                    _ => {}
-                })
-                .on_tray_icon_event(|tray, event| {
-                    if let TrayIconEvent::Click {
-                        button: MouseButton::Left,
-                        button_state: MouseButtonState::Up,
-                        ..
-                    } = event
-                    {
-                        let app = tray.app_handle();
-                        if let Some(window) = app.get_webview_window("main") {
-                            let _ = window.show();
-                            let _ = window.set_focus();
-                        }
-                    }
-                })
-                .build(app)?;
-
-            // Handle window close - minimize to tray instead of quitting
-            let main_window = app.get_webview_window("main").unwrap();
-            let main_window_clone = main_window.clone();
-            main_window.on_window_event(move |event| {
-                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
-                    // Prevent the window from closing
-                    api.prevent_close();
-                    // Hide the window instead
-                    let _ = main_window_clone.hide();
-                }
-            });
+            tray::create_tray(app.handle())?;
+
+            // The main window is created here rather than from config so
+            // tray-only mode can defer it until first use
+            window::init_main_window(app.handle())?;
+
+            if let Err(e) = deep_link::init(app.handle()) {
+                log_error!("{}", e);
+            }
+            cli::handle(app.handle(), std::env::args().collect());
+            global_shortcut::init(app.handle().clone());
+            config_conflict::check_at_startup(app.handle());
+            crash_report::check_previous_crash(app.handle());
+            selftest::spawn_startup_selftest(app.handle().clone());
+
+            // Keep the tray label and webview in sync with the real gateway state
+            settings::spawn_change_notifier(app.handle().clone());
+            app_state::spawn_initial_load(app.handle().clone());
+            log_stream::init(app.handle().clone());
+            // Safe mode skips every poller and watcher in case one of them is the problem
+            if !safe_mode::is_active() {
+                monitor::spawn_health_monitor(app.handle().clone());
+                connectivity::spawn_connectivity_monitor(app.handle().clone());
+                log_rotation::spawn_log_rotation();
+                notifications::spawn_update_checker(app.handle().clone());
+                app_update::spawn_app_update_checker(app.handle().clone());
+                notifications::spawn_focus_watcher(app.handle().clone());
+                auto_lock::spawn_auto_lock(app.handle().clone());
+                idle::spawn_idle_watcher(app.handle().clone());
+                power::spawn_power_monitor(app.handle().clone());
+                theme::spawn_theme_watcher(app.handle().clone());
+                event_bridge::spawn_event_bridge(app.handle().clone());
+                backup::spawn_backup_scheduler(app.handle().clone());
+                metrics::spawn_metrics_sampler(app.handle().clone());
+                resources::spawn_resource_monitor(app.handle().clone());
+                schedule::spawn_gateway_scheduler(app.handle().clone());
+                storage::spawn_storage_index();
+                storage_quota::spawn_storage_guard(app.handle().clone());
+                usage::spawn_usage_collector(app.handle().clone());
+                session_search::spawn_session_indexer(app.handle().clone());
+                token_health::spawn_token_monitor(app.handle().clone());
+                workspaces::spawn_workspace_watcher(app.handle().clone());
+                login_start::spawn_login_start(app.handle().clone());
+                install_repair::spawn_launch_check();
+                automation_api::spawn_automation_api(app.handle().clone());
+            }
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
+        .invoke_handler(ipc_metrics::instrument(tauri::generate_handler![
             get_gateway_status,
             start_gateway,
             stop_gateway,
             restart_gateway,
+            pause::pause_gateway,
+            pause::resume_gateway,
+            port_owner::start_gateway_on_free_port,
             auto_start_gateway,
             get_dashboard_url,
             is_openclaw_installed,
-            install_openclaw,
+            installer::install_openclaw,
+            installer::cancel_install,
+            tasks::list_tasks,
+            tasks::cancel_task,
+            preflight::validate_gateway_environment,
+            audit::get_audit_log,
+            selftest::run_startup_selftest,
+            selftest::get_selftest_report,
+            installer::install_openclaw_binary,
+            binary::get_openclaw_binary_path,
+            binary::set_openclaw_binary_path,
+            install_repair::get_installation_issues,
+            install_repair::repair_installation,
             open_dashboard_window,
             get_gateway_logs,
+            get_gateway_error_logs,
+            logs::get_combined_logs,
+            access_log::get_access_summary,
             clear_gateway_logs,
+            logs::query_gateway_logs,
+            log_rotation::list_log_files,
+            log_stream::get_log_stream_status,
+            log_stream::set_log_stream_boost,
+            remote_logs::start_remote_logs,
+            remote_logs::stop_remote_logs,
+            logs::get_log_page,
+            logs::search_gateway_logs,
+            logs::read_logs_from_offset,
+            annotations::add_log_annotation,
+            annotations::update_log_annotation,
+            annotations::list_log_annotations,
+            annotations::delete_log_annotation,
+            annotations::export_log_report,
+            capabilities::get_gateway_capabilities,
+            compatibility::check_compatibility,
+            availability::get_action_availability,
             get_gateway_diagnostics,
             run_openclaw_doctor,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+            doctor::run_doctor_checklist,
+            doctor::run_openclaw_doctor_structured,
+            doctor::run_doctor_fix,
+            diagnostics::export_diagnostics_bundle,
+            safe_mode::get_safe_mode,
+            settings::get_settings,
+            settings::update_settings,
+            settings::reset_settings,
+            onboarding::get_onboarding_state,
+            onboarding::advance_onboarding_step,
+            prerequisites::get_runtime_prerequisites,
+            login_start::get_login_start_report,
+            profiles::list_profiles,
+            config::get_config_variables,
+            config::set_config_variable,
+            gateway_env::get_gateway_env,
+            gateway_env::set_gateway_env,
+            profiles::get_active_profile,
+            profiles::set_active_profile,
+            profiles::create_profile,
+            gateways::list_gateways,
+            gateways::start_gateway_named,
+            gateways::stop_gateway_named,
+            gateway_process::get_gateway_process_info,
+            supervisor::set_gateway_autorestart,
+            supervisor::get_restart_history,
+            upgrade::upgrade_with_failover,
+            notifications::get_notification_history,
+            notifications::set_notification_preferences,
+            focus::get_focus_state,
+            connection::get_gateway_connection,
+            connection::set_gateway_connection,
+            connection::set_gateway_tls,
+            proxy::get_proxy_settings,
+            proxy::set_proxy_settings,
+            i18n::get_locale,
+            i18n::set_locale,
+            dashboard_proxy::start_dashboard_proxy,
+            dashboard_proxy::stop_dashboard_proxy,
+            dashboard_proxy::get_dashboard_proxy_status,
+            automation_api::get_automation_api_status,
+            automation_api::set_automation_api_enabled,
+            notices::get_notices,
+            notices::acknowledge_notice,
+            dependencies::get_gateway_dependencies,
+            dependencies::start_gateway_dependency,
+            dependencies::stop_gateway_dependency,
+            mcp::list_mcp_servers,
+            mcp::get_mcp_server_status,
+            mcp::restart_mcp_server,
+            integrations::list_webhooks,
+            integrations::save_webhook,
+            integrations::delete_webhook,
+            integrations::test_webhook,
+            attachments::upload_attachments,
+            attachments::send_clipboard_to_agent,
+            screen_capture::list_capture_sources,
+            screen_capture::capture_screenshot,
+            idle::get_idle_state,
+            power::get_power_state,
+            auto_lock::get_auto_lock_status,
+            auto_lock::set_auto_lock,
+            auto_lock::unlock_dashboard,
+            auto_lock::lock_dashboard,
+            permissions::list_permission_grants,
+            permissions::revoke_permission,
+            permissions::get_permission_modes,
+            permissions::set_permission_mode,
+            snapshots::list_state_snapshots,
+            snapshots::diff_state_snapshots,
+            skills::list_installed_skills,
+            skills::search_skills,
+            skills::install_skill,
+            updater::check_openclaw_update,
+            updater::update_openclaw,
+            updater::list_available_openclaw_versions,
+            updater::install_openclaw_version,
+            updater::rollback_openclaw,
+            config::read_openclaw_config,
+            config::write_openclaw_config,
+            config_bundle::export_config_bundle,
+            config_bundle::import_config_bundle,
+            config_conflict::get_config_conflicts,
+            config_conflict::resolve_config_conflict,
+            agents::list_agents,
+            agents::create_agent,
+            agents::update_agent_config,
+            agents::delete_agent,
+            config::validate_openclaw_config,
+            profiling::capture_gateway_profile,
+            profiling::list_gateway_profiles,
+            traces::enable_request_tracing,
+            traces::disable_request_tracing,
+            traces::get_request_tracing_status,
+            traces::get_traces,
+            tokens::rotate_gateway_token,
+            tokens::get_token_fingerprint,
+            tokens::copy_token_to_clipboard,
+            tokens::copy_dashboard_url,
+            token_health::reacquire_token,
+            external::open_external,
+            external::reveal_in_file_manager,
+            external::open_log_in_default_editor,
+            terminal::create_terminal_session,
+            terminal::write_terminal_input,
+            terminal::resize_terminal,
+            terminal::kill_terminal_session,
+            cli_runner::run_openclaw_subcommand,
+            connectivity::get_connectivity_state,
+            kiosk::get_kiosk_status,
+            kiosk::set_kiosk_mode,
+            kiosk::unlock_kiosk,
+            kiosk::lock_kiosk,
+            secrets::migrate_token_to_keychain,
+            secrets::set_remote_gateway_token,
+            providers::list_providers,
+            providers::set_provider_api_key,
+            providers::test_provider_connection,
+            quota::get_provider_quota_summary,
+            platform::install_gateway_service,
+            platform::uninstall_gateway_service,
+            service_unit::generate_service_unit,
+            backup::run_backup_now,
+            backup::get_backup_history,
+            backup::set_backup_passphrase,
+            home_backup::create_backup,
+            home_backup::restore_backup,
+            ipc_metrics::record_ipc_timing,
+            ipc_metrics::get_ipc_metrics,
+            crash_report::get_recent_backend_errors,
+            metrics::get_metrics_history,
+            usage::get_usage_summary,
+            resources::get_gateway_resource_usage,
+            resources::set_resource_limits,
+            schedule::get_gateway_schedule,
+            schedule::set_gateway_schedule,
+            storage::get_storage_report,
+            storage_quota::get_storage_breakdown,
+            storage_quota::prune_storage,
+            storage_quota::run_retention_now,
+            legacy::get_legacy_install,
+            legacy::migrate_legacy_install,
+            legacy::dismiss_legacy_migration,
+            config::get_openclaw_config_file,
+            gateway_log_level::set_gateway_log_level,
+            window::navigate_home,
+            window::set_zoom_level,
+            window::reload_dashboard,
+            window::toggle_devtools,
+            window::navigate_back,
+            theme::get_system_theme,
+            theme::set_theme_preference,
+            paths::get_data_root,
+            paths::set_data_profile,
+            paths::migrate_data_dir,
+            safety_mode::get_safety_mode,
+            safety_mode::set_safety_mode,
+            approvals::list_pending_approvals,
+            approvals::respond_to_approval,
+            benchmark::run_gateway_benchmark,
+            benchmark::get_benchmark_history,
+            app_state::get_app_state,
+            gateway_api::gateway_get_health,
+            gateway_api::gateway_list_sessions,
+            gateway_api::gateway_get_config,
+            sessions::list_sessions,
+            sessions::get_session_transcript,
+            session_export::export_session,
+            session_search::search_sessions,
+            sessions::delete_session,
+            tray::refresh_tray,
+            window::open_log_window,
+            window::open_chat_window,
+            global_shortcut::get_global_shortcut,
+            global_shortcut::set_global_shortcut,
+            app_update::check_app_update,
+            app_update::download_app_update,
+            app_update::install_app_update,
+            autostart::get_launch_at_login,
+            autostart::set_launch_at_login,
+            workspaces::get_workspace_status,
+            share::share_file,
+            chat::send_agent_message,
+            chat::stream_agent_reply,
+            shutdown::set_shutdown_policy,
+            shutdown::confirm_quit,
+            shutdown::quit_app,
+            archive::archive_sessions,
+            archive::list_archived_sessions,
+            archive::restore_archived_session,
+        ]))
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                automation_api::stop();
+                shutdown::on_exit(app);
+                // Shutdown itself may need the CLI, so in-flight calls are only killed after it
+                process::cancel_all();
+            }
+        });
 }
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use crate::error::DesktopError;
+use crate::gateway_log_paths;
+use crate::logs::real_path;
+use crate::{app_log, paths, settings};
+
+const ROTATION_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogRotationSettings {
+    pub enabled: bool,
+    /// Rotate once a log file grows past this many megabytes
+    pub max_file_mb: u64,
+    /// Number of rotated files kept per log (`gateway.log.1` .. `gateway.log.N`)
+    pub max_files: u32,
+}
+
+impl Default for LogRotationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_file_mb: 10,
+            max_files: 5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogFileInfo {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub modified: Option<u64>,
+    /// `None` for the live file, `Some(n)` for `<name>.n`
+    pub rotation_index: Option<u32>,
+}
+
+fn rotated_path(path: &Path, index: u32) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+/// Live log files of the default gateway, every named instance and the app
+fn rotation_targets() -> Vec<PathBuf> {
+    let mut targets = Vec::new();
+    if let Ok((log_path, error_log_path)) = gateway_log_paths() {
+        targets.push(log_path);
+        targets.push(error_log_path);
+    }
+    if let Ok(app_log) = app_log::log_path() {
+        targets.push(app_log);
+    }
+    if let Ok(entries) = paths::log_dir().and_then(|dir| {
+        fs::read_dir(dir.join("logs")).map_err(|e| format!("Failed to read logs directory: {}", e))
+    }) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let dir = entry.path();
+            if dir.is_dir() {
+                targets.push(dir.join("gateway.log"));
+                targets.push(dir.join("gateway_error.log"));
+            }
+        }
+    }
+    targets
+}
+
+/// Rename, falling back to copy-and-delete when the paths are on different
+/// volumes (e.g. a rotated file that was itself symlinked elsewhere)
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    match fs::rename(from, to) {
+        Ok(()) => Ok(()),
+        Err(rename_err) => {
+            fs::copy(from, to).map_err(|_| rename_err)?;
+            fs::remove_file(from)
+        }
+    }
+}
+
+/// Rotate a log the gateway is still writing to.
+///
+/// The gateway holds its log open for the lifetime of the process, so renaming
+/// it would leave the child writing into the rotated file. Instead the contents
+/// are copied aside and the live file truncated in place; the gateway opens its
+/// logs in append mode, so its next write lands at the new end of file.
+///
+/// A symlinked log is rotated at its target, so rotated copies stay on the
+/// volume the user moved the log to.
+fn rotate(path: &Path, max_files: u32) -> Result<(), String> {
+    let path = &real_path(path);
+    if max_files == 0 {
+        return truncate_log(path);
+    }
+
+    let _ = fs::remove_file(rotated_path(path, max_files));
+    for index in (1..max_files).rev() {
+        let from = rotated_path(path, index);
+        if from.exists() {
+            move_file(&from, &rotated_path(path, index + 1))
+                .map_err(|e| format!("Failed to shift rotated log: {}", e))?;
+        }
+    }
+
+    fs::copy(path, rotated_path(path, 1)).map_err(|e| format!("Failed to rotate log: {}", e))?;
+    truncate_log(path)
+}
+
+/// Empty a log in place, through any symlink, without replacing the file
+pub(crate) fn truncate_log(path: &Path) -> Result<(), String> {
+    OpenOptions::new()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_len(0))
+        .map_err(|e| format!("Failed to truncate log: {}", e))
+}
+
+fn rotate_oversized(config: &LogRotationSettings) {
+    let max_bytes = config.max_file_mb.saturating_mul(1024 * 1024);
+    for path in rotation_targets() {
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        if size > max_bytes {
+            if let Err(e) = rotate(&path, config.max_files) {
+                log_error!("Failed to rotate {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// Periodically rotate gateway logs that exceed the configured size
+pub fn spawn_log_rotation() {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(ROTATION_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let config = settings::current().log_rotation;
+            if !config.enabled {
+                continue;
+            }
+            let _ = tauri::async_runtime::spawn_blocking(move || rotate_oversized(&config)).await;
+        }
+    });
+}
+
+fn file_info(path: &Path, rotation_index: Option<u32>) -> Option<LogFileInfo> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(LogFileInfo {
+        name: path.file_name()?.to_string_lossy().to_string(),
+        path: path.display().to_string(),
+        size: metadata.len(),
+        modified: metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs()),
+        rotation_index,
+    })
+}
+
+/// List live and rotated gateway log files
+#[tauri::command]
+pub fn list_log_files() -> Result<Vec<LogFileInfo>, DesktopError> {
+    let max_files = settings::current().log_rotation.max_files;
+    let mut files = Vec::new();
+
+    for path in rotation_targets() {
+        files.extend(file_info(&path, None));
+        let target = real_path(&path);
+        for index in 1..=max_files {
+            files.extend(file_info(&rotated_path(&target, index), Some(index)));
+        }
+    }
+    Ok(files)
+}
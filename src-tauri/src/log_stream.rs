@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::error::DesktopError;
+use crate::events::{GatewayLogLine, GATEWAY_LOG_LINE_EVENT};
+use crate::logs::{self, LogLevel};
+use crate::{integrations, kiosk, settings};
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+/// Stream every line to the UI for the rest of this app session
+static BOOST: AtomicBool = AtomicBool::new(false);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogStreamSettings {
+    /// Lowest level streamed to the UI; the log files always get everything
+    pub ui_level: LogLevel,
+}
+
+impl Default for LogStreamSettings {
+    fn default() -> Self {
+        Self {
+            ui_level: LogLevel::Info,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogStreamStatus {
+    pub ui_level: LogLevel,
+    pub boosted: bool,
+}
+
+pub fn init(app: AppHandle) {
+    let _ = APP.set(app);
+}
+
+fn ui_threshold() -> Option<LogLevel> {
+    if BOOST.load(Ordering::Relaxed) {
+        None
+    } else {
+        Some(settings::current().log_stream.ui_level)
+    }
+}
+
+/// Log files already followed; a tail survives restarts and picks up truncation itself
+static FOLLOWED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// Where a tail has read up to in one log file
+struct Tail {
+    stream: &'static str,
+    path: PathBuf,
+    offset: u64,
+    line_number: usize,
+    /// Lines without a level (stack traces, wrapped output) take the level of the line before them
+    last_level: LogLevel,
+}
+
+impl Tail {
+    /// Forward the complete lines appended since the last read
+    fn read_new(&mut self, app: &AppHandle) {
+        let Ok(mut file) = File::open(&self.path) else {
+            return;
+        };
+        let len = file.metadata().map(|m| m.len()).unwrap_or_default();
+        if len < self.offset {
+            // Emptied by a fresh start or log rotation
+            self.offset = 0;
+            self.line_number = 0;
+        }
+        if file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return;
+        }
+        let mut reader = BufReader::new(file);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf) {
+                Ok(0) => break,
+                Ok(_) if !buf.ends_with(b"\n") => break,
+                Ok(n) => self.offset += n as u64,
+                Err(e) => {
+                    log_error!("Failed to read gateway {}: {}", self.stream, e);
+                    break;
+                }
+            }
+            self.line_number += 1;
+            let text = String::from_utf8_lossy(&buf);
+            let mut entry = logs::parse_line(self.line_number, text.trim_end());
+            self.last_level = *entry.level.get_or_insert(self.last_level);
+            if self.last_level >= LogLevel::Error {
+                integrations::forward_log_error(&entry.message);
+            }
+            emit_line(
+                app,
+                GatewayLogLine {
+                    stream: self.stream,
+                    entry,
+                    gateway: None,
+                },
+            );
+        }
+    }
+}
+
+/// Tail a gateway log file and forward new lines at or above the UI level as
+/// `gateway-log-line` events.
+///
+/// The gateway writes to the file directly, so it keeps logging after the app quits.
+pub(crate) fn follow(stream: &'static str, path: &Path) {
+    let Some(app) = APP.get().cloned() else {
+        return;
+    };
+    let newly_followed = FOLLOWED
+        .lock()
+        .map(|mut followed| followed.insert(path.to_path_buf()))
+        .unwrap_or(false);
+    if !newly_followed {
+        return;
+    }
+    let mut tail = Tail {
+        stream,
+        path: path.to_path_buf(),
+        offset: fs::metadata(path).map(|m| m.len()).unwrap_or_default(),
+        line_number: 0,
+        last_level: LogLevel::Info,
+    };
+    std::thread::spawn(move || loop {
+        tail.read_new(&app);
+        std::thread::sleep(POLL_INTERVAL);
+    });
+}
+
+/// Send a line to the webview if it passes the UI level filter
+pub(crate) fn emit_line(app: &AppHandle, line: GatewayLogLine) {
+    let level = line.entry.level.unwrap_or(LogLevel::Info);
+    if ui_threshold().is_some_and(|min| level < min) {
+        return;
+    }
+    if let Err(e) = app.emit(GATEWAY_LOG_LINE_EVENT, line) {
+        log_error!("Failed to emit gateway log line: {}", e);
+    }
+}
+
+#[tauri::command]
+pub fn get_log_stream_status() -> LogStreamStatus {
+    LogStreamStatus {
+        ui_level: settings::current().log_stream.ui_level,
+        boosted: BOOST.load(Ordering::Relaxed),
+    }
+}
+
+/// Temporarily stream every level to the UI; resets when the app restarts
+#[tauri::command]
+pub fn set_log_stream_boost(enabled: bool) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    BOOST.store(enabled, Ordering::Relaxed);
+    Ok(())
+}
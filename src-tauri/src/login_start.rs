@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Runtime};
+use ts_rs::TS;
+
+use crate::events::LOGIN_START_EVENT;
+use crate::{autostart, probe, settings, start_default_gateway};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Something that has to be up before the gateway starts at login
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum LoginDependency {
+    /// A running process with this executable name, e.g. a VPN client or secret agent
+    Process { name: String },
+    /// A shell command that exits 0 once the dependency is ready
+    Script { command: String },
+}
+
+impl LoginDependency {
+    fn label(&self) -> String {
+        match self {
+            Self::Process { name } => format!("process {}", name),
+            Self::Script { command } => format!("probe `{}`", command),
+        }
+    }
+}
+
+/// Starting the default gateway when the app is launched at login
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LoginStartSettings {
+    pub start_gateway: bool,
+    /// Waited for in order before starting
+    pub dependencies: Vec<LoginDependency>,
+    /// Give up on a dependency after this long
+    pub timeout_secs: u64,
+    /// Start anyway when a dependency times out
+    pub start_on_timeout: bool,
+}
+
+impl Default for LoginStartSettings {
+    fn default() -> Self {
+        Self {
+            start_gateway: false,
+            dependencies: Vec::new(),
+            timeout_secs: 120,
+            start_on_timeout: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum DependencyStatus {
+    Waiting,
+    Ready,
+    TimedOut,
+    /// Not checked because an earlier dependency timed out and start was abandoned
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct DependencyReport {
+    pub dependency: String,
+    pub status: DependencyStatus,
+    #[ts(type = "number")]
+    pub waited_secs: u64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct LoginStartReport {
+    pub dependencies: Vec<DependencyReport>,
+    pub gateway_started: bool,
+    pub error: Option<String>,
+}
+
+static LAST_REPORT: Mutex<Option<LoginStartReport>> = Mutex::new(None);
+
+fn process_running(name: &str) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        let image = if name.to_ascii_lowercase().ends_with(".exe") {
+            name.to_string()
+        } else {
+            format!("{}.exe", name)
+        };
+        Command::new("tasklist")
+            .args(["/FI", &format!("IMAGENAME eq {}", image), "/NH"])
+            .creation_flags(crate::CREATE_NO_WINDOW)
+            .output()
+            .map(|o| {
+                String::from_utf8_lossy(&o.stdout)
+                    .to_ascii_lowercase()
+                    .contains(&image.to_ascii_lowercase())
+            })
+            .unwrap_or(false)
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        Command::new("pgrep")
+            .args(["-x", name])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+}
+
+fn probe_succeeds(command: &str) -> bool {
+    #[cfg(target_os = "windows")]
+    let mut probe = {
+        use std::os::windows::process::CommandExt;
+        let mut probe = Command::new("cmd");
+        probe.args(["/c", command]);
+        probe.creation_flags(crate::CREATE_NO_WINDOW);
+        probe
+    };
+    #[cfg(not(target_os = "windows"))]
+    let mut probe = {
+        let mut probe = Command::new("sh");
+        probe.args(["-c", command]);
+        probe
+    };
+    probe.status().map(|s| s.success()).unwrap_or(false)
+}
+
+fn is_ready(dependency: &LoginDependency) -> bool {
+    match dependency {
+        LoginDependency::Process { name } => process_running(name),
+        LoginDependency::Script { command } => probe_succeeds(command),
+    }
+}
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, report: &DependencyReport) {
+    if let Err(e) = app.emit(LOGIN_START_EVENT, report) {
+        log_error!("Failed to emit login start progress: {}", e);
+    }
+}
+
+async fn wait_for<R: Runtime>(
+    app: &AppHandle<R>,
+    dependency: &LoginDependency,
+    timeout: Duration,
+) -> DependencyReport {
+    let started = Instant::now();
+    let mut report = DependencyReport {
+        dependency: dependency.label(),
+        status: DependencyStatus::Waiting,
+        waited_secs: 0,
+    };
+    emit_progress(app, &report);
+
+    loop {
+        let check = dependency.clone();
+        let ready = tauri::async_runtime::spawn_blocking(move || is_ready(&check))
+            .await
+            .unwrap_or(false);
+        report.waited_secs = started.elapsed().as_secs();
+        if ready {
+            report.status = DependencyStatus::Ready;
+            break;
+        }
+        if started.elapsed() >= timeout {
+            report.status = DependencyStatus::TimedOut;
+            break;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    emit_progress(app, &report);
+    report
+}
+
+/// At a login launch (`--minimized`), wait for the configured dependencies and then
+/// start the default gateway, recording the outcome for `get_login_start_report`
+pub fn spawn_login_start<R: Runtime>(app: AppHandle<R>) {
+    let config = settings::current().login_start;
+    if !config.start_gateway || !autostart::launched_minimized() {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        let timeout = Duration::from_secs(config.timeout_secs);
+        let mut report = LoginStartReport::default();
+        let mut abandoned = false;
+
+        for dependency in &config.dependencies {
+            if abandoned {
+                report.dependencies.push(DependencyReport {
+                    dependency: dependency.label(),
+                    status: DependencyStatus::Skipped,
+                    waited_secs: 0,
+                });
+                continue;
+            }
+            let result = wait_for(&app, dependency, timeout).await;
+            if result.status == DependencyStatus::TimedOut && !config.start_on_timeout {
+                abandoned = true;
+            }
+            report.dependencies.push(result);
+        }
+
+        if abandoned {
+            report.error = Some("A login dependency timed out; gateway not started".to_string());
+        } else {
+            let probe = tauri::async_runtime::spawn_blocking(probe::probe_gateway)
+                .await
+                .unwrap_or_default();
+            if probe.reachable && !probe.verified {
+                report.error = probe.error;
+            } else if !probe.verified {
+                match start_default_gateway(&app) {
+                    Ok(_) => report.gateway_started = true,
+                    Err(e) => report.error = Some(e),
+                }
+            }
+        }
+        if let Some(error) = &report.error {
+            log_error!("Login start: {}", error);
+        }
+        if let Ok(mut last) = LAST_REPORT.lock() {
+            *last = Some(report);
+        }
+    });
+}
+
+/// Outcome of this launch's login start, if one ran and finished
+#[tauri::command]
+pub fn get_login_start_report() -> Option<LoginStartReport> {
+    LAST_REPORT.lock().ok().and_then(|last| last.clone())
+}
@@ -0,0 +1,817 @@
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, File, Metadata};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use ts_rs::TS;
+
+use crate::annotations::LogAnnotation;
+use crate::error::DesktopError;
+use crate::{gateway_log_paths, openclaw_home_dir, paths};
+
+const DEFAULT_QUERY_LIMIT: usize = 200;
+const DEFAULT_SEARCH_RESULTS: usize = 200;
+const MAX_SEARCH_RESULTS: usize = 2000;
+const MAX_CONTEXT_LINES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+}
+
+impl LogLevel {
+    fn parse(token: &str) -> Option<Self> {
+        match token.to_ascii_lowercase().as_str() {
+            "trace" | "trc" => Some(Self::Trace),
+            "debug" | "dbg" | "verbose" => Some(Self::Debug),
+            "info" | "inf" => Some(Self::Info),
+            "warn" | "warning" | "wrn" => Some(Self::Warn),
+            "error" | "err" => Some(Self::Error),
+            "fatal" | "panic" | "crit" | "critical" => Some(Self::Fatal),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct LogEntry {
+    pub line: usize,
+    pub timestamp: Option<String>,
+    pub level: Option<LogLevel>,
+    pub component: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LogQuery {
+    /// Minimum severity to include
+    pub level: Option<LogLevel>,
+    pub component: Option<String>,
+    /// RFC 3339 timestamp; entries logged earlier are skipped
+    pub since: Option<String>,
+    pub limit: Option<usize>,
+    /// Case-insensitive substring match against the message
+    pub search: Option<String>,
+}
+
+fn strip_brackets(token: &str) -> &str {
+    token
+        .trim_start_matches(['[', '('])
+        .trim_end_matches([']', ')', ':'])
+}
+
+fn looks_like_timestamp(token: &str) -> bool {
+    let bytes = token.as_bytes();
+    let date = bytes.len() >= 10
+        && bytes[..4].iter().all(u8::is_ascii_digit)
+        && bytes[4] == b'-'
+        && bytes[7] == b'-';
+    let time = bytes.len() >= 8
+        && bytes[..2].iter().all(u8::is_ascii_digit)
+        && bytes[2] == b':'
+        && bytes[5] == b':';
+    date || time
+}
+
+/// Parse one gateway log line into its parts.
+///
+/// The gateway's format varies between versions, so the prefix is scanned
+/// token by token: a leading timestamp, a level, and a `[component]` tag in any
+/// order. Whatever remains is the message; unrecognized lines keep everything.
+pub fn parse_line(line_number: usize, raw: &str) -> LogEntry {
+    let mut timestamp = None;
+    let mut level = None;
+    let mut component = None;
+    let mut rest = raw.trim();
+
+    for _ in 0..4 {
+        let (token, remainder) = match rest.split_once(char::is_whitespace) {
+            Some((token, remainder)) => (token, remainder.trim_start()),
+            None => (rest, ""),
+        };
+        let bare = strip_brackets(token);
+
+        if timestamp.is_none() && looks_like_timestamp(bare) {
+            timestamp = Some(bare.to_string());
+        } else if level.is_none() && LogLevel::parse(bare).is_some() {
+            level = LogLevel::parse(bare);
+        } else if component.is_none()
+            && token.starts_with('[')
+            && token.trim_end_matches(':').ends_with(']')
+            && !bare.is_empty()
+        {
+            component = Some(bare.to_string());
+        } else {
+            break;
+        }
+        rest = remainder;
+    }
+
+    LogEntry {
+        line: line_number,
+        timestamp,
+        level,
+        component,
+        message: rest.to_string(),
+    }
+}
+
+/// A log timestamp as an instant. Zone-less ones are taken as local time;
+/// time-only ones can't be placed on a date and yield `None`.
+fn parse_timestamp(text: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(at) = DateTime::parse_from_rfc3339(text) {
+        return Some(at);
+    }
+    let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .ok()?
+                .and_hms_opt(0, 0, 0)
+        })?;
+    Some(naive.and_local_timezone(Local).earliest()?.fixed_offset())
+}
+
+fn matches(
+    entry: &LogEntry,
+    query: &LogQuery,
+    since: Option<DateTime<FixedOffset>>,
+    search: Option<&str>,
+) -> bool {
+    if let Some(min) = query.level {
+        if entry.level.is_none_or(|level| level < min) {
+            return false;
+        }
+    }
+    if let Some(component) = &query.component {
+        if !entry
+            .component
+            .as_deref()
+            .is_some_and(|c| c.eq_ignore_ascii_case(component))
+        {
+            return false;
+        }
+    }
+    if let (Some(since), Some(timestamp)) = (since, entry.timestamp.as_deref()) {
+        if parse_timestamp(timestamp).is_some_and(|at| at < since) {
+            return false;
+        }
+    }
+    if let Some(needle) = search {
+        if !entry.message.to_lowercase().contains(needle) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Query gateway.log as structured records, returning the newest matches
+#[tauri::command]
+pub fn query_gateway_logs(query: LogQuery) -> Result<Vec<LogEntry>, DesktopError> {
+    let (log_path, _) = gateway_log_paths()?;
+    if !log_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content =
+        fs::read_to_string(&log_path).map_err(|e| format!("Failed to read log file: {}", e))?;
+
+    let since = query
+        .since
+        .as_deref()
+        .map(|since| {
+            DateTime::parse_from_rfc3339(since).map_err(|e| {
+                DesktopError::InvalidInput(format!("Invalid since timestamp {}: {}", since, e))
+            })
+        })
+        .transpose()?;
+    let search = query.search.as_ref().map(|s| s.to_lowercase());
+    let limit = query.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+
+    let mut entries: Vec<LogEntry> = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_line(i + 1, line))
+        .filter(|entry| matches(entry, &query, since, search.as_deref()))
+        .collect();
+
+    if entries.len() > limit {
+        entries.drain(..entries.len() - limit);
+    }
+    Ok(entries)
+}
+
+const DEFAULT_PAGE_BYTES: u64 = 64 * 1024;
+const BACKWARD_CHUNK_BYTES: u64 = 64 * 1024;
+const MAX_BACKWARD_BYTES: u64 = 16 * 1024 * 1024;
+const DEFAULT_SCROLL_LINES: usize = 200;
+const MAX_SCROLL_LINES: usize = 5000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogLine {
+    pub offset: u64,
+    pub text: String,
+}
+
+/// Last `max_lines` lines of a log, read backwards from the end of the file
+pub(crate) fn tail_lines(path: &Path, max_lines: usize) -> Result<Vec<String>, String> {
+    let (lines, _) = read_lines_before(path, u64::MAX, max_lines)?;
+    Ok(lines.into_iter().map(|line| line.text).collect())
+}
+
+/// Up to `max_lines` whole lines ending before `end`, reading fixed-size chunks
+/// backwards until enough lines are found. Returns them with the offset of the
+/// first one, the `end` for reading further back.
+///
+/// A sparse log can report a huge size that is mostly holes, so the window
+/// stops growing at `MAX_BACKWARD_BYTES`.
+pub(crate) fn read_lines_before(
+    path: &Path,
+    end: u64,
+    max_lines: usize,
+) -> Result<(Vec<LogLine>, u64), String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open log file: {}", e))?;
+    let size = file
+        .metadata()
+        .map_err(|e| format!("Failed to read log metadata: {}", e))?
+        .len();
+    let end = end.min(size);
+
+    let mut start = end;
+    let mut buf: Vec<u8> = Vec::new();
+    let mut newlines = 0usize;
+    // One newline more than lines wanted, so the first line is known to be whole
+    while start > 0 && newlines <= max_lines && end - start < MAX_BACKWARD_BYTES {
+        let chunk_len = BACKWARD_CHUNK_BYTES.min(start);
+        start -= chunk_len;
+        file.seek(SeekFrom::Start(start))
+            .map_err(|e| format!("Failed to seek log file: {}", e))?;
+        let mut chunk = vec![0u8; chunk_len as usize];
+        file.read_exact(&mut chunk)
+            .map_err(|e| format!("Failed to read log file: {}", e))?;
+        newlines += chunk.iter().filter(|b| **b == b'\n').count();
+        chunk.extend_from_slice(&buf);
+        buf = chunk;
+    }
+
+    // Drop the partial line the window starts in
+    if start > 0 {
+        match buf.iter().position(|b| *b == b'\n') {
+            Some(pos) => {
+                buf.drain(..=pos);
+                start += pos as u64 + 1;
+            }
+            None => buf.clear(),
+        }
+    }
+
+    let mut lines = split_lines(start, &buf);
+    lines.drain(..lines.len().saturating_sub(max_lines));
+    let first = lines.first().map_or(end, |line| line.offset);
+    Ok((lines, first))
+}
+
+/// Split raw log bytes starting at file offset `base` into lines.
+///
+/// NUL bytes are dropped: a writer without append mode keeps its old offset
+/// after the file is truncated, leaving a sparse hole that reads back as zeros.
+fn split_lines(base: u64, buf: &[u8]) -> Vec<LogLine> {
+    let mut lines = Vec::new();
+    let mut push_line = |line_start: usize, bytes: &[u8]| {
+        let first_data = bytes.iter().position(|b| *b != 0);
+        let Some(skip) = first_data else {
+            return;
+        };
+        let text: Vec<u8> = bytes[skip..].iter().copied().filter(|b| *b != 0).collect();
+        lines.push(LogLine {
+            offset: base + (line_start + skip) as u64,
+            text: String::from_utf8_lossy(&text)
+                .trim_end_matches('\r')
+                .to_string(),
+        });
+    };
+    let mut line_start = 0usize;
+    for (i, byte) in buf.iter().enumerate() {
+        if *byte == b'\n' {
+            push_line(line_start, &buf[line_start..i]);
+            line_start = i + 1;
+        }
+    }
+    if line_start < buf.len() {
+        push_line(line_start, &buf[line_start..]);
+    }
+    lines
+}
+
+/// How recently `gateway_error.log` must have been written to count as recent errors
+const RECENT_ERROR_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// Whether the gateway wrote to its error log in the last few minutes
+pub(crate) fn has_recent_errors() -> bool {
+    let Ok((_, error_log_path)) = gateway_log_paths() else {
+        return false;
+    };
+    fs::metadata(&error_log_path)
+        .ok()
+        .and_then(|m| Some((m.len(), m.modified().ok()?)))
+        .is_some_and(|(len, modified)| is_recent_error_log(len, modified, SystemTime::now()))
+}
+
+/// Whether an error log of `len` bytes last written at `modified` counts as recent at `now`
+pub(crate) fn is_recent_error_log(len: u64, modified: SystemTime, now: SystemTime) -> bool {
+    len > 0
+        && now
+            .duration_since(modified)
+            .is_ok_and(|age| age < RECENT_ERROR_WINDOW)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombinedLogLine {
+    pub stream: LogStream,
+    /// Parsed timestamp, or the previous line's when this one has none
+    pub timestamp: Option<String>,
+    pub text: String,
+}
+
+fn stamp_lines(stream: LogStream, lines: Vec<String>) -> Vec<CombinedLogLine> {
+    let mut last = None;
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| {
+            if let Some(timestamp) = parse_line(i + 1, &text).timestamp {
+                last = Some(timestamp);
+            }
+            CombinedLogLine {
+                stream,
+                timestamp: last.clone(),
+                text,
+            }
+        })
+        .collect()
+}
+
+/// Tail of gateway.log and gateway_error.log interleaved by timestamp.
+///
+/// Each file is already in order, so the two are merged rather than sorted;
+/// untimestamped lines (stack traces) stay attached to the line above them.
+#[tauri::command]
+pub fn get_combined_logs(lines: Option<usize>) -> Result<Vec<CombinedLogLine>, DesktopError> {
+    let max_lines = lines.unwrap_or(200);
+    let (log_path, error_log_path) = gateway_log_paths()?;
+    let read = |path: &Path, stream| -> Result<Vec<CombinedLogLine>, String> {
+        if path.exists() {
+            Ok(stamp_lines(stream, tail_lines(path, max_lines)?))
+        } else {
+            Ok(Vec::new())
+        }
+    };
+    let stdout = read(&log_path, LogStream::Stdout)?;
+    let stderr = read(&error_log_path, LogStream::Stderr)?;
+
+    let mut merged = Vec::with_capacity(stdout.len() + stderr.len());
+    let mut stdout = stdout.into_iter().peekable();
+    let mut stderr = stderr.into_iter().peekable();
+    loop {
+        let take_stderr = match (stdout.peek(), stderr.peek()) {
+            (Some(out), Some(err)) => match (&out.timestamp, &err.timestamp) {
+                (Some(a), Some(b)) => b < a,
+                _ => false,
+            },
+            (None, Some(_)) => true,
+            (_, None) => false,
+        };
+        let next = if take_stderr {
+            stderr.next()
+        } else {
+            stdout.next()
+        };
+        match next {
+            Some(line) => merged.push(line),
+            None => break,
+        }
+    }
+
+    let start = merged.len().saturating_sub(max_lines);
+    Ok(merged.split_off(start))
+}
+
+/// Why a page restarted from the beginning instead of the requested offset
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogReset {
+    /// Same file, but it shrank below the requested offset (cleared or copy-truncated)
+    Truncated,
+    /// The path now points at a different file (renamed away or re-linked)
+    Rotated,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogPage {
+    pub file: String,
+    pub start_offset: u64,
+    pub end_offset: u64,
+    pub file_size: u64,
+    /// Identity of the underlying file; pass it back to detect rotation
+    pub generation: String,
+    pub reset: Option<LogReset>,
+    pub lines: Vec<LogLine>,
+    pub annotations: Vec<LogAnnotation>,
+}
+
+/// Follow symlinks to the file actually being written, e.g. a log moved to another volume
+pub(crate) fn real_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Identity of a file that survives renames but changes when it is replaced
+pub(crate) fn file_generation(metadata: &Metadata) -> String {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        format!("{:x}:{:x}", metadata.dev(), metadata.ino())
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::MetadataExt;
+        format!("{:x}", metadata.creation_time())
+    }
+}
+
+/// Resolve a log file id (path relative to the OpenClaw home, e.g. `gateway.log`
+/// or `logs/work/gateway.log.1`) without letting it escape that directory
+pub(crate) fn resolve_log_file(file_id: &str) -> Result<PathBuf, String> {
+    if !file_id.contains(".log") {
+        return Err(format!("Not a log file: {}", file_id));
+    }
+    let path = paths::join_within(&openclaw_home_dir()?, file_id)
+        .map_err(|_| format!("Invalid log file id: {}", file_id))?;
+    if path.exists() {
+        return Ok(path);
+    }
+    // Rotated copies of a symlinked log live next to the link's target
+    let live = match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.parse::<u32>().is_ok() => path.with_extension(""),
+        _ => return Ok(path),
+    };
+    match (live.is_symlink(), path.file_name()) {
+        (true, Some(name)) => Ok(real_path(&live).with_file_name(name)),
+        _ => Ok(path),
+    }
+}
+
+/// Read whole lines starting at `offset`, stopping near `max_bytes`.
+///
+/// A trailing partial line is held back (unless it is the end of the file) so
+/// `end_offset` can be used directly as the next page's starting offset.
+pub(crate) fn read_page(
+    path: &Path,
+    offset: u64,
+    max_bytes: u64,
+) -> Result<(Vec<LogLine>, u64, u64), String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open log file: {}", e))?;
+    let file_size = file
+        .metadata()
+        .map_err(|e| format!("Failed to read log metadata: {}", e))?
+        .len();
+    let start = offset.min(file_size);
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("Failed to seek log file: {}", e))?;
+
+    let mut buf = Vec::new();
+    file.take(max_bytes)
+        .read_to_end(&mut buf)
+        .map_err(|e| format!("Failed to read log file: {}", e))?;
+
+    let reached_eof = start + buf.len() as u64 >= file_size;
+    // A single line longer than the page is returned whole rather than stalling
+    if !reached_eof {
+        if let Some(last_newline) = buf.iter().rposition(|b| *b == b'\n') {
+            buf.truncate(last_newline + 1);
+        }
+    }
+
+    let lines = split_lines(start, &buf);
+    Ok((lines, start + buf.len() as u64, file_size))
+}
+
+/// Read a page of a log file together with the annotations that fall inside it.
+///
+/// When tailing, pass the previous page's `generation` so a rotated or
+/// truncated file restarts from the top instead of returning nothing.
+#[tauri::command]
+pub fn get_log_page(
+    app: tauri::AppHandle,
+    file: String,
+    offset: Option<u64>,
+    max_bytes: Option<u64>,
+    generation: Option<String>,
+) -> Result<LogPage, DesktopError> {
+    let path = resolve_log_file(&file)?;
+    if !path.exists() {
+        return Err(DesktopError::NotFound(format!(
+            "Log file not found: {}",
+            file
+        )));
+    }
+
+    let metadata =
+        fs::metadata(&path).map_err(|e| format!("Failed to read log metadata: {}", e))?;
+    let current_generation = file_generation(&metadata);
+
+    let reset = match (&generation, offset) {
+        (Some(previous), Some(_)) if *previous != current_generation => Some(LogReset::Rotated),
+        (_, Some(requested)) if requested > metadata.len() => Some(LogReset::Truncated),
+        _ => None,
+    };
+    let start_offset = if reset.is_some() {
+        0
+    } else {
+        offset.unwrap_or(0)
+    };
+    let (lines, end_offset, file_size) =
+        read_page(&path, start_offset, max_bytes.unwrap_or(DEFAULT_PAGE_BYTES))?;
+
+    let annotations = crate::annotations::load_anchored(&app, Some(&file))?
+        .into_iter()
+        .filter(|a| {
+            a.file == file
+                && a.offset >= start_offset
+                && a.offset < end_offset.max(start_offset + 1)
+        })
+        .collect();
+
+    Ok(LogPage {
+        file,
+        start_offset,
+        end_offset,
+        file_size,
+        generation: current_generation,
+        reset,
+        lines,
+        annotations,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogDirection {
+    /// Older lines, ending at the offset
+    #[default]
+    Backward,
+    /// Newer lines, starting at the offset
+    Forward,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogChunk {
+    pub file: String,
+    pub lines: Vec<LogLine>,
+    /// Pass as `offset` with `backward` to load the lines before these
+    pub start_offset: u64,
+    /// Pass as `offset` with `forward` to load the lines after these
+    pub end_offset: u64,
+    pub file_size: u64,
+}
+
+/// Whole lines after `offset`, at most `max_lines` of them
+fn read_lines_after(
+    path: &Path,
+    offset: u64,
+    max_lines: usize,
+) -> Result<(Vec<LogLine>, u64, u64), String> {
+    let mut lines = Vec::new();
+    let mut end = offset;
+    loop {
+        let (page, page_end, file_size) = read_page(path, end, DEFAULT_PAGE_BYTES)?;
+        let done = page_end == end || page_end >= file_size;
+        lines.extend(page);
+        end = page_end;
+        if lines.len() > max_lines {
+            end = lines[max_lines].offset;
+            lines.truncate(max_lines);
+            return Ok((lines, end, file_size));
+        }
+        if done {
+            return Ok((lines, end, file_size));
+        }
+    }
+}
+
+/// Line-counted pages of a log file for infinite scroll. Without `offset`,
+/// `backward` starts at the end of the file and `forward` at the start.
+#[tauri::command]
+pub async fn read_logs_from_offset(
+    file: String,
+    offset: Option<u64>,
+    direction: Option<LogDirection>,
+    max_lines: Option<usize>,
+) -> Result<LogChunk, DesktopError> {
+    let path = resolve_log_file(&file)?;
+    if !path.exists() {
+        return Err(DesktopError::NotFound(format!(
+            "Log file not found: {}",
+            file
+        )));
+    }
+    let max_lines = max_lines
+        .unwrap_or(DEFAULT_SCROLL_LINES)
+        .clamp(1, MAX_SCROLL_LINES);
+
+    let chunk = tauri::async_runtime::spawn_blocking(move || {
+        let (lines, start_offset, end_offset, file_size) = match direction.unwrap_or_default() {
+            LogDirection::Backward => {
+                let end = offset.unwrap_or(u64::MAX);
+                let (lines, start) = read_lines_before(&path, end, max_lines)?;
+                let file_size = fs::metadata(&path)
+                    .map_err(|e| format!("Failed to read log metadata: {}", e))?
+                    .len();
+                (lines, start, end.min(file_size), file_size)
+            }
+            LogDirection::Forward => {
+                let start = offset.unwrap_or(0);
+                let (lines, end, file_size) = read_lines_after(&path, start, max_lines)?;
+                (lines, start.min(file_size), end, file_size)
+            }
+        };
+        Ok::<_, String>(LogChunk {
+            file,
+            lines,
+            start_offset,
+            end_offset,
+            file_size,
+        })
+    })
+    .await
+    .map_err(|e| format!("Failed to read logs: {}", e))??;
+    Ok(chunk)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogSearchMatch {
+    /// Log file id, as taken by `get_log_page`
+    pub file: String,
+    pub line_number: usize,
+    /// Byte offset of the matching line, for jumping to it with `get_log_page`
+    pub offset: u64,
+    pub text: String,
+    pub before: Vec<LogLine>,
+    pub after: Vec<LogLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogSearchResult {
+    pub matches: Vec<LogSearchMatch>,
+    /// More lines matched than `max_results`
+    pub truncated: bool,
+}
+
+/// Lines are read one at a time so a large log is never held in memory
+fn search_file(
+    path: &Path,
+    file: &str,
+    pattern: &Regex,
+    (before, after): (usize, usize),
+    max_results: usize,
+    matches: &mut Vec<LogSearchMatch>,
+) -> Result<bool, String> {
+    let handle = File::open(path).map_err(|e| format!("Failed to open log file: {}", e))?;
+    let mut reader = BufReader::new(handle);
+    let mut context: VecDeque<LogLine> = VecDeque::with_capacity(before);
+    // Matches still collecting their `after` lines
+    let mut open: Vec<usize> = Vec::new();
+    let mut truncated = false;
+    let mut offset = 0u64;
+    let mut line_number = 0usize;
+    let mut buf = Vec::new();
+
+    loop {
+        buf.clear();
+        let read = reader
+            .read_until(b'\n', &mut buf)
+            .map_err(|e| format!("Failed to read log file: {}", e))?;
+        if read == 0 || (truncated && open.is_empty()) {
+            break;
+        }
+        let line_start = offset;
+        offset += read as u64;
+        line_number += 1;
+
+        // Same NUL handling as `read_page`, so offsets agree between the two
+        let Some(skip) = buf.iter().position(|b| *b != 0) else {
+            continue;
+        };
+        let text: Vec<u8> = buf[skip..].iter().copied().filter(|b| *b != 0).collect();
+        let line = LogLine {
+            offset: line_start + skip as u64,
+            text: String::from_utf8_lossy(&text)
+                .trim_end_matches(['\r', '\n'])
+                .to_string(),
+        };
+
+        open.retain(|&i| {
+            matches[i].after.push(line.clone());
+            matches[i].after.len() < after
+        });
+
+        if !truncated && pattern.is_match(&line.text) {
+            if matches.len() >= max_results {
+                truncated = true;
+            } else {
+                matches.push(LogSearchMatch {
+                    file: file.to_string(),
+                    line_number,
+                    offset: line.offset,
+                    text: line.text.clone(),
+                    before: context.iter().cloned().collect(),
+                    after: Vec::new(),
+                });
+                if after > 0 {
+                    open.push(matches.len() - 1);
+                }
+            }
+        }
+
+        if before > 0 {
+            if context.len() == before {
+                context.pop_front();
+            }
+            context.push_back(line);
+        }
+    }
+    Ok(truncated)
+}
+
+/// Search gateway.log and gateway_error.log, returning matching lines with
+/// `before`/`after` lines of context. `pattern` is a case-insensitive substring
+/// unless `regex` is set.
+#[tauri::command]
+pub async fn search_gateway_logs(
+    pattern: String,
+    regex: Option<bool>,
+    before: Option<usize>,
+    after: Option<usize>,
+    max_results: Option<usize>,
+) -> Result<LogSearchResult, DesktopError> {
+    if pattern.is_empty() {
+        return Err(DesktopError::InvalidInput(
+            "Search pattern is empty".to_string(),
+        ));
+    }
+    let pattern = if regex.unwrap_or(false) {
+        RegexBuilder::new(&pattern).build()
+    } else {
+        RegexBuilder::new(&regex::escape(&pattern))
+            .case_insensitive(true)
+            .build()
+    }
+    .map_err(|e| DesktopError::InvalidInput(format!("Invalid search pattern: {}", e)))?;
+    let context = (
+        before.unwrap_or(0).min(MAX_CONTEXT_LINES),
+        after.unwrap_or(0).min(MAX_CONTEXT_LINES),
+    );
+    let max_results = max_results
+        .unwrap_or(DEFAULT_SEARCH_RESULTS)
+        .min(MAX_SEARCH_RESULTS);
+
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let home = openclaw_home_dir()?;
+        let (log_path, error_log_path) = gateway_log_paths()?;
+        let mut matches = Vec::new();
+        let mut truncated = false;
+        for path in [log_path, error_log_path] {
+            if truncated || !path.exists() {
+                continue;
+            }
+            let file = path
+                .strip_prefix(&home)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            truncated = search_file(&path, &file, &pattern, context, max_results, &mut matches)?;
+        }
+        Ok::<_, String>(LogSearchResult { matches, truncated })
+    })
+    .await
+    .map_err(|e| format!("Failed to search logs: {}", e))??;
+    Ok(result)
+}
@@ -0,0 +1,218 @@
+//! MCP tool servers the gateway connects to: what `openclaw.json` declares,
+//! merged with the health the gateway reports for each.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::config;
+use crate::error::DesktopError;
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+use crate::{audit, kiosk};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpTransport {
+    Stdio,
+    Http,
+    Sse,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum McpServerState {
+    Connected,
+    Connecting,
+    Failed,
+    Disabled,
+    /// The gateway isn't running or didn't report this server
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct McpServer {
+    pub name: String,
+    pub transport: McpTransport,
+    /// Program for stdio servers; arguments and environment are left out as
+    /// they often carry keys
+    pub command: Option<String>,
+    /// Endpoint for HTTP and SSE servers
+    pub url: Option<String>,
+    pub enabled: bool,
+    pub state: McpServerState,
+    pub tool_count: Option<u32>,
+    pub error: Option<String>,
+    /// Unix seconds of the last successful connect, as the gateway reports it
+    pub connected_since: Option<u64>,
+    /// Declared in the local config; remote gateways only report their own
+    pub configured: bool,
+}
+
+/// A server as the gateway reports it on `/api/mcp/servers`
+#[derive(Deserialize)]
+struct ReportedServer {
+    name: String,
+    #[serde(default = "unknown_state", alias = "status")]
+    state: McpServerState,
+    #[serde(default)]
+    transport: Option<McpTransport>,
+    #[serde(default, alias = "toolCount", alias = "tools")]
+    tool_count: Option<u32>,
+    #[serde(default, alias = "message", alias = "lastError")]
+    error: Option<String>,
+    #[serde(default, alias = "connectedSince")]
+    connected_since: Option<u64>,
+}
+
+fn unknown_state() -> McpServerState {
+    McpServerState::Unknown
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ServersResponse {
+    List(Vec<ReportedServer>),
+    Wrapped { servers: Vec<ReportedServer> },
+}
+
+/// Servers declared under `mcp.servers` (or `mcpServers`, the common client format)
+fn configured(config: &Value) -> Vec<McpServer> {
+    let servers = config
+        .pointer("/mcp/servers")
+        .or_else(|| config.get("mcpServers"))
+        .and_then(Value::as_object);
+    let Some(servers) = servers else {
+        return Vec::new();
+    };
+    servers
+        .iter()
+        .map(|(name, entry)| {
+            let text = |key: &str| entry.get(key).and_then(Value::as_str).map(str::to_string);
+            let url = text("url");
+            let transport = entry
+                .get("transport")
+                .or_else(|| entry.get("type"))
+                .and_then(|t| serde_json::from_value(t.clone()).ok())
+                .unwrap_or(if url.is_some() {
+                    McpTransport::Http
+                } else {
+                    McpTransport::Stdio
+                });
+            let enabled = entry.get("enabled").and_then(Value::as_bool).unwrap_or(
+                !entry
+                    .get("disabled")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false),
+            );
+            McpServer {
+                name: name.clone(),
+                transport,
+                command: text("command"),
+                url,
+                enabled,
+                state: if enabled {
+                    McpServerState::Unknown
+                } else {
+                    McpServerState::Disabled
+                },
+                tool_count: None,
+                error: None,
+                connected_since: None,
+                configured: true,
+            }
+        })
+        .collect()
+}
+
+fn merge(mut servers: Vec<McpServer>, reported: Vec<ReportedServer>) -> Vec<McpServer> {
+    let mut by_name: HashMap<String, ReportedServer> = reported
+        .into_iter()
+        .map(|server| (server.name.clone(), server))
+        .collect();
+    for server in &mut servers {
+        if let Some(report) = by_name.remove(&server.name) {
+            server.state = report.state;
+            server.tool_count = report.tool_count;
+            server.error = report.error;
+            server.connected_since = report.connected_since;
+        }
+    }
+    // Servers the gateway knows from elsewhere, e.g. a remote gateway's own config
+    servers.extend(by_name.into_values().map(|report| McpServer {
+        name: report.name,
+        transport: report.transport.unwrap_or(McpTransport::Unknown),
+        command: None,
+        url: None,
+        enabled: report.state != McpServerState::Disabled,
+        state: report.state,
+        tool_count: report.tool_count,
+        error: report.error,
+        connected_since: report.connected_since,
+        configured: false,
+    }));
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+    servers
+}
+
+async fn servers(api: &GatewayApi) -> Result<Vec<McpServer>, DesktopError> {
+    let declared = tauri::async_runtime::spawn_blocking(|| {
+        config::load_resolved_config()
+            .map(|config| configured(&config))
+            .unwrap_or_default()
+    })
+    .await
+    .map_err(|e| format!("Failed to read MCP configuration: {}", e))?;
+    let reported = match api.get::<ServersResponse>("/api/mcp/servers").await {
+        Ok(ServersResponse::List(reported))
+        | Ok(ServersResponse::Wrapped { servers: reported }) => reported,
+        Err(e) if e.kind == GatewayApiErrorKind::Unauthorized => return Err(e.into()),
+        // Not running, or a gateway without the endpoint: the config is all there is
+        Err(_) => Vec::new(),
+    };
+    Ok(merge(declared, reported))
+}
+
+fn find(servers: Vec<McpServer>, name: &str) -> Result<McpServer, DesktopError> {
+    servers
+        .into_iter()
+        .find(|server| server.name == name)
+        .ok_or_else(|| DesktopError::NotFound(format!("No MCP server named '{}'", name)))
+}
+
+/// Every MCP server from the config and the gateway, with its connection state
+#[tauri::command]
+pub async fn list_mcp_servers(api: State<'_, GatewayApi>) -> Result<Vec<McpServer>, DesktopError> {
+    servers(&api).await
+}
+
+#[tauri::command]
+pub async fn get_mcp_server_status(
+    api: State<'_, GatewayApi>,
+    name: String,
+) -> Result<McpServer, DesktopError> {
+    find(servers(&api).await?, &name)
+}
+
+/// Ask the gateway to reconnect to one server, e.g. after it failed
+#[tauri::command]
+pub async fn restart_mcp_server(
+    api: State<'_, GatewayApi>,
+    name: String,
+) -> Result<McpServer, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    // Only known names reach the gateway's URL
+    find(servers(&api).await?, &name)?;
+    let result = api
+        .post::<Value>(
+            &format!("/api/mcp/servers/{}/restart", urlencoding::encode(&name)),
+            &serde_json::json!({}),
+        )
+        .await;
+    audit::record_result("mcp_restart", &name, &result);
+    result?;
+    find(servers(&api).await?, &name)
+}
@@ -0,0 +1,308 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::time::MissedTickBehavior;
+
+use crate::capabilities::{self, GatewayFeature};
+use crate::error::DesktopError;
+use crate::gateway_api::GatewayApi;
+use crate::{gateway_log_paths, is_gateway_running, openclaw_home_dir};
+
+const DB_FILE: &str = "metrics.db";
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+/// Samples older than this are pruned
+const RETENTION_SECS: u64 = 7 * 24 * 60 * 60;
+/// Longer ranges are averaged into buckets so charts stay around this many points
+const MAX_POINTS: u64 = 360;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsRange {
+    Hour,
+    Day,
+    Week,
+}
+
+impl MetricsRange {
+    fn secs(self) -> u64 {
+        match self {
+            MetricsRange::Hour => 60 * 60,
+            MetricsRange::Day => 24 * 60 * 60,
+            MetricsRange::Week => RETENTION_SECS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricsSource {
+    /// The gateway's metrics endpoint
+    Gateway,
+    /// Estimated from log throughput, for gateways without the endpoint
+    Logs,
+}
+
+/// One point of the rolling window; counts are per minute
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsSample {
+    /// Unix seconds at the start of the bucket
+    pub timestamp: u64,
+    pub requests_per_min: f64,
+    pub active_sessions: Option<f64>,
+    pub tokens_per_min: Option<f64>,
+    pub errors_per_min: f64,
+    pub source: MetricsSource,
+}
+
+/// Cumulative counters from `/api/metrics`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GatewayMetrics {
+    #[serde(default, alias = "requests_total")]
+    requests_total: u64,
+    #[serde(default, alias = "active_sessions")]
+    active_sessions: Option<u64>,
+    #[serde(default, alias = "tokens_total")]
+    tokens_total: Option<u64>,
+    #[serde(default, alias = "errors_total")]
+    errors_total: u64,
+}
+
+/// Where the sampler left off, so each sample records only the last interval
+#[derive(Default)]
+struct Cursor {
+    last_counters: Option<GatewayMetrics>,
+    log_offset: Option<u64>,
+    error_log_offset: Option<u64>,
+}
+
+static DB: Mutex<Option<Connection>> = Mutex::new(None);
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut guard = DB
+        .lock()
+        .map_err(|_| "Metrics store lock poisoned".to_string())?;
+    if guard.is_none() {
+        let conn = Connection::open(openclaw_home_dir()?.join(DB_FILE))
+            .map_err(|e| format!("Failed to open metrics store: {}", e))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS samples (
+                timestamp INTEGER PRIMARY KEY,
+                requests REAL NOT NULL,
+                active_sessions REAL,
+                tokens REAL,
+                errors REAL NOT NULL,
+                source TEXT NOT NULL
+            )",
+        )
+        .map_err(|e| format!("Failed to create metrics store: {}", e))?;
+        *guard = Some(conn);
+    }
+    let conn = guard.as_ref().ok_or("Metrics store unavailable")?;
+    f(conn).map_err(|e| format!("Metrics store error: {}", e))
+}
+
+fn store(sample: &MetricsSample) -> Result<(), String> {
+    let source = match sample.source {
+        MetricsSource::Gateway => "gateway",
+        MetricsSource::Logs => "logs",
+    };
+    with_db(|conn| {
+        conn.execute(
+            "INSERT OR REPLACE INTO samples
+                (timestamp, requests, active_sessions, tokens, errors, source)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                sample.timestamp as i64,
+                sample.requests_per_min,
+                sample.active_sessions,
+                sample.tokens_per_min,
+                sample.errors_per_min,
+                source
+            ],
+        )?;
+        conn.execute(
+            "DELETE FROM samples WHERE timestamp < ?1",
+            params![now_secs().saturating_sub(RETENTION_SECS) as i64],
+        )?;
+        Ok(())
+    })
+}
+
+/// Count lines appended since `offset`, starting over when the log was rotated
+fn new_lines(path: &Path, offset: &mut Option<u64>) -> u64 {
+    let Ok(mut file) = File::open(path) else {
+        *offset = None;
+        return 0;
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or_default();
+    let Some(start) = *offset else {
+        // First look: only count what arrives from now on
+        *offset = Some(len);
+        return 0;
+    };
+    let start = if len < start { 0 } else { start };
+    if file.seek(SeekFrom::Start(start)).is_err() {
+        return 0;
+    }
+    let mut count = 0;
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    let mut read = start;
+    while let Ok(n) = reader.read_until(b'\n', &mut line) {
+        if n == 0 || line.last() != Some(&b'\n') {
+            break;
+        }
+        read += n as u64;
+        count += 1;
+        line.clear();
+    }
+    *offset = Some(read);
+    count
+}
+
+fn per_minute(count: u64) -> f64 {
+    count as f64 * 60.0 / SAMPLE_INTERVAL.as_secs_f64()
+}
+
+/// Difference of cumulative counters; a gateway restart resets them, so start over
+fn delta(current: u64, previous: u64) -> u64 {
+    current.checked_sub(previous).unwrap_or(current)
+}
+
+async fn sample<R: Runtime>(app: &AppHandle<R>, cursor: &mut Cursor) -> Option<MetricsSample> {
+    let timestamp = now_secs();
+    if capabilities::require(app, GatewayFeature::MetricsApi).is_ok() {
+        match app
+            .state::<GatewayApi>()
+            .get::<GatewayMetrics>("/api/metrics")
+            .await
+        {
+            Ok(current) => {
+                // The first reading only establishes the baseline
+                let previous = cursor.last_counters.replace(current.clone())?;
+                let requests = delta(current.requests_total, previous.requests_total);
+                let errors = delta(current.errors_total, previous.errors_total);
+                let tokens = current
+                    .tokens_total
+                    .zip(previous.tokens_total)
+                    .map(|(total, previous)| delta(total, previous));
+                return Some(MetricsSample {
+                    timestamp,
+                    requests_per_min: per_minute(requests),
+                    active_sessions: current.active_sessions.map(|n| n as f64),
+                    tokens_per_min: tokens.map(per_minute),
+                    errors_per_min: per_minute(errors),
+                    source: MetricsSource::Gateway,
+                });
+            }
+            Err(e) => log_error!("Failed to read gateway metrics: {}", e),
+        }
+    }
+    cursor.last_counters = None;
+
+    let (log_path, error_log_path) = gateway_log_paths().ok()?;
+    let first = cursor.log_offset.is_none();
+    let requests = new_lines(&log_path, &mut cursor.log_offset);
+    let errors = new_lines(&error_log_path, &mut cursor.error_log_offset);
+    if first {
+        return None;
+    }
+    let active_sessions = app
+        .state::<GatewayApi>()
+        .list_sessions()
+        .await
+        .ok()
+        .map(|sessions| {
+            sessions
+                .iter()
+                .filter(|s| s.status.as_deref() == Some("running"))
+                .count() as f64
+        });
+    Some(MetricsSample {
+        timestamp,
+        requests_per_min: per_minute(requests),
+        active_sessions,
+        tokens_per_min: None,
+        errors_per_min: per_minute(errors),
+        source: MetricsSource::Logs,
+    })
+}
+
+/// Sample gateway metrics once a minute into the rolling store
+pub fn spawn_metrics_sampler<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut cursor = Cursor::default();
+        let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let running = tauri::async_runtime::spawn_blocking(is_gateway_running)
+                .await
+                .unwrap_or(false);
+            if !running {
+                cursor = Cursor::default();
+                continue;
+            }
+            let Some(sample) = sample(&app, &mut cursor).await else {
+                continue;
+            };
+            let result = tauri::async_runtime::spawn_blocking(move || store(&sample)).await;
+            if let Ok(Err(e)) = result {
+                log_error!("Failed to store metrics sample: {}", e);
+            }
+        }
+    });
+}
+
+fn history(range: MetricsRange) -> Result<Vec<MetricsSample>, String> {
+    let secs = range.secs();
+    let bucket = (secs / MAX_POINTS).max(SAMPLE_INTERVAL.as_secs()) as i64;
+    let since = now_secs().saturating_sub(secs) as i64;
+    with_db(|conn| {
+        let mut statement = conn.prepare(
+            "SELECT (timestamp / ?1) * ?1 AS bucket, AVG(requests), AVG(active_sessions),
+                    AVG(tokens), AVG(errors), MIN(source)
+             FROM samples WHERE timestamp >= ?2
+             GROUP BY bucket ORDER BY bucket",
+        )?;
+        let rows = statement.query_map(params![bucket, since], |row| {
+            let source: String = row.get(5)?;
+            Ok(MetricsSample {
+                timestamp: row.get::<_, i64>(0)? as u64,
+                requests_per_min: row.get(1)?,
+                active_sessions: row.get(2)?,
+                tokens_per_min: row.get(3)?,
+                errors_per_min: row.get(4)?,
+                // A bucket with any gateway-reported sample counts as reported
+                source: if source == "gateway" {
+                    MetricsSource::Gateway
+                } else {
+                    MetricsSource::Logs
+                },
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+/// Rolling gateway metrics for charts, averaged into buckets for longer ranges
+#[tauri::command]
+pub async fn get_metrics_history(range: MetricsRange) -> Result<Vec<MetricsSample>, DesktopError> {
+    let samples = tauri::async_runtime::spawn_blocking(move || history(range))
+        .await
+        .map_err(|e| format!("Failed to read metrics: {}", e))??;
+    Ok(samples)
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::time::MissedTickBehavior;
+
+use crate::announce::announce;
+use crate::app_state;
+use crate::capabilities;
+use crate::compatibility;
+use crate::events::{Politeness, GATEWAY_STATUS_CHANGED_EVENT};
+use crate::gateway_log_level;
+use crate::i18n::{t, t_with};
+use crate::logs;
+use crate::notifications::{self, NotificationCategory};
+use crate::pause;
+use crate::presence;
+use crate::probe;
+use crate::safety_mode;
+use crate::settings;
+use crate::supervisor;
+use crate::tray;
+use crate::warmup;
+use crate::{active_gateway_port, gateway_status};
+
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(3);
+
+/// A wall-clock gap this much longer than the interval means the machine slept
+const RESUME_GAP_THRESHOLD: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HealthCheckSettings {
+    /// After a system resume, failed probes are ignored for this long before
+    /// the gateway is reported as down
+    pub resume_grace_secs: u64,
+}
+
+impl Default for HealthCheckSettings {
+    fn default() -> Self {
+        Self {
+            resume_grace_secs: 30,
+        }
+    }
+}
+
+/// Probe the gateway port on an interval and react to state transitions.
+///
+/// The tray menu is rebuilt and `gateway-status-changed` is emitted to the
+/// webview only when the running state actually flips, so idle ticks are cheap.
+///
+/// Network and sockets take a moment to come back after the machine wakes, so
+/// a resume (detected as a jump in wall-clock time between ticks) opens a grace
+/// window in which failed probes are counted but the gateway stays "running".
+pub fn spawn_health_monitor<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_running: Option<bool> = None;
+        let mut last_errors = false;
+        let mut interval = tokio::time::interval(HEALTH_CHECK_INTERVAL);
+        // Don't fire a burst of catch-up ticks after sleeping
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_tick = SystemTime::now();
+        let mut grace_until: Option<Instant> = None;
+        let mut suppressed_failures = 0u32;
+
+        loop {
+            interval.tick().await;
+
+            let now = SystemTime::now();
+            let gap = now.duration_since(last_tick).unwrap_or_default();
+            last_tick = now;
+            if gap > HEALTH_CHECK_INTERVAL + RESUME_GAP_THRESHOLD {
+                let grace = settings::current().health_check.resume_grace_secs;
+                grace_until = Some(Instant::now() + Duration::from_secs(grace));
+                suppressed_failures = 0;
+            }
+
+            let probe = tauri::async_runtime::spawn_blocking(probe::probe_gateway)
+                .await
+                .unwrap_or_default();
+            let running = probe.verified;
+
+            if running {
+                grace_until = None;
+            } else if last_running == Some(true) {
+                match grace_until {
+                    Some(until) if Instant::now() < until => {
+                        suppressed_failures += 1;
+                        continue;
+                    }
+                    Some(_) => {
+                        log_error!(
+                            "Gateway still down after resume grace period ({} failed checks)",
+                            suppressed_failures
+                        );
+                        grace_until = None;
+                    }
+                    None => {}
+                }
+            }
+
+            presence::update(&app, running).await;
+
+            // Recent errors only change the tray's warning badge
+            let errors = logs::has_recent_errors();
+            if errors != last_errors {
+                last_errors = errors;
+                if last_running == Some(running) {
+                    if let Err(e) = tray::rebuild_tray_with(&app, &probe) {
+                        log_error!("Failed to refresh tray menu: {}", e);
+                    }
+                }
+            }
+
+            if last_running == Some(running) {
+                continue;
+            }
+            // No notifications for the state found at launch
+            let transition = last_running.is_some();
+            last_running = Some(running);
+
+            if running {
+                supervisor::on_gateway_running(&app);
+                if let Err(e) = capabilities::refresh(&app).await {
+                    log_error!("Failed to discover gateway capabilities: {}", e);
+                }
+                if transition {
+                    compatibility::check_and_warn(&app).await;
+                }
+                tray::load_quick_links(&app).await;
+                let warmup_app = app.clone();
+                tauri::async_runtime::spawn(async move { warmup::run(&warmup_app).await });
+                if transition {
+                    announce(&app, Politeness::Polite, &t("gateway.started"));
+                    notifications::notify(
+                        &app,
+                        NotificationCategory::GatewayStarted,
+                        &t("gateway.started"),
+                        &t_with("gateway.started_body", &[("port", &active_gateway_port())]),
+                    );
+                }
+            } else {
+                capabilities::clear(&app);
+                pause::clear();
+                gateway_log_level::clear();
+                safety_mode::clear();
+                warmup::clear();
+                tray::clear_quick_links();
+                // Unexpected losses are reported as crashes by the supervisor
+                if transition && !supervisor::is_desired_running() {
+                    announce(&app, Politeness::Polite, &t("gateway.stopped"));
+                    notifications::notify(
+                        &app,
+                        NotificationCategory::GatewayStopped,
+                        &t("gateway.stopped"),
+                        &t("gateway.stopped_body"),
+                    );
+                }
+                supervisor::on_port_lost(&app);
+            }
+
+            if let Err(e) = tray::rebuild_tray_with(&app, &probe) {
+                log_error!("Failed to refresh tray menu: {}", e);
+            }
+
+            let status = gateway_status();
+            app_state::set_gateway(&app, status.clone());
+            if let Err(e) = app.emit(GATEWAY_STATUS_CHANGED_EVENT, status) {
+                log_error!("Failed to emit gateway status event: {}", e);
+            }
+        }
+    });
+}
@@ -0,0 +1,277 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime};
+
+use crate::capabilities::parse_version;
+use crate::error::DesktopError;
+use crate::notifications::{self, NotificationCategory};
+use crate::{kiosk, openclaw_home_dir, proxy};
+
+const FEED_URL: &str = "https://cdn.openclaw.ai/releases/notices.json";
+const FEED_TIMEOUT: Duration = Duration::from_secs(10);
+const STATE_FILE: &str = "notices.json";
+
+/// Ed25519 key the release pipeline signs the notice feed with
+const FEED_PUBLIC_KEY: &str = "513a4863b191f235a03eadf89acd84e8cad99fbe99c76996327dccae52bf3d64";
+
+/// Serializes read-modify-write of the state file
+static STATE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NoticeSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// A deprecation or end-of-life notice from the release feed.
+///
+/// The `when_*` conditions all have to hold for the notice to apply; a notice
+/// without any applies to everyone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notice {
+    pub id: String,
+    pub severity: NoticeSeverity,
+    pub title: String,
+    pub message: String,
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Date the change takes effect, as published
+    #[serde(default)]
+    pub effective: Option<String>,
+    #[serde(default)]
+    pub when_cli_below: Option<String>,
+    #[serde(default)]
+    pub when_app_below: Option<String>,
+    /// Home-relative path, e.g. `.clawdbot`
+    #[serde(default)]
+    pub when_path_exists: Option<String>,
+    /// `macos`, `windows`, or `linux`
+    #[serde(default)]
+    pub when_platform: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Feed {
+    notices: Vec<Notice>,
+}
+
+/// Cached feed plus which notices were already shown and acknowledged
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct NoticeState {
+    /// Feed body exactly as signed
+    feed: Option<String>,
+    signature: Option<String>,
+    fetched_at: Option<u64>,
+    notified: Vec<String>,
+    acknowledged: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoticeView {
+    #[serde(flatten)]
+    pub notice: Notice,
+    pub acknowledged: bool,
+}
+
+fn state_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(STATE_FILE))
+}
+
+fn load_state() -> NoticeState {
+    state_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &NoticeState) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize notices: {}", e))?;
+    fs::write(state_path()?, content).map_err(|e| format!("Failed to write notices: {}", e))
+}
+
+fn decode_hex(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim();
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Check the feed against the release key and parse it
+fn verify(feed: &str, signature: &str) -> Result<Vec<Notice>, String> {
+    let key: [u8; 32] = decode_hex(FEED_PUBLIC_KEY)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("Invalid notice feed key")?;
+    let key =
+        VerifyingKey::from_bytes(&key).map_err(|e| format!("Invalid notice feed key: {}", e))?;
+    let signature: [u8; 64] = decode_hex(signature)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or("Malformed notice feed signature")?;
+    key.verify_strict(feed.as_bytes(), &Signature::from_bytes(&signature))
+        .map_err(|_| "Notice feed signature does not match".to_string())?;
+    serde_json::from_str::<Feed>(feed)
+        .map(|feed| feed.notices)
+        .map_err(|e| format!("Failed to parse notice feed: {}", e))
+}
+
+fn version_below(installed: Option<&str>, bound: &str) -> bool {
+    match (installed.and_then(parse_version), parse_version(bound)) {
+        (Some(installed), Some(bound)) => installed < bound,
+        // Unknown versions are not assumed to be affected
+        _ => false,
+    }
+}
+
+fn applies(notice: &Notice, cli_version: Option<&str>) -> bool {
+    if let Some(bound) = &notice.when_cli_below {
+        if !version_below(cli_version, bound) {
+            return false;
+        }
+    }
+    if let Some(bound) = &notice.when_app_below {
+        if !version_below(Some(env!("CARGO_PKG_VERSION")), bound) {
+            return false;
+        }
+    }
+    if let Some(path) = &notice.when_path_exists {
+        if !dirs::home_dir().is_some_and(|home| home.join(path).exists()) {
+            return false;
+        }
+    }
+    if let Some(platform) = &notice.when_platform {
+        if platform != std::env::consts::OS {
+            return false;
+        }
+    }
+    true
+}
+
+/// Notices from the cached feed that apply to this machine
+pub fn relevant() -> Vec<NoticeView> {
+    let state = load_state();
+    let (Some(feed), Some(signature)) = (&state.feed, &state.signature) else {
+        return Vec::new();
+    };
+    let notices = match verify(feed, signature) {
+        Ok(notices) => notices,
+        Err(e) => {
+            log_error!("Ignoring cached notices: {}", e);
+            return Vec::new();
+        }
+    };
+    let cli_version = crate::detect_openclaw_version();
+    notices
+        .into_iter()
+        .filter(|notice| applies(notice, cli_version.as_deref()))
+        .map(|notice| NoticeView {
+            acknowledged: state.acknowledged.contains(&notice.id),
+            notice,
+        })
+        .collect()
+}
+
+async fn fetch() -> Result<(String, String), String> {
+    let client = proxy::apply(reqwest::Client::builder().timeout(FEED_TIMEOUT))?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let get = |url: String| {
+        let client = client.clone();
+        async move {
+            let response = client
+                .get(&url)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to fetch {}: {}", url, e))?;
+            if !response.status().is_success() {
+                return Err(format!("{} returned HTTP {}", url, response.status()));
+            }
+            response
+                .text()
+                .await
+                .map_err(|e| format!("Failed to read {}: {}", url, e))
+        }
+    };
+    let feed = get(FEED_URL.to_string()).await?;
+    let signature = get(format!("{}.sig", FEED_URL)).await?;
+    Ok((feed, signature))
+}
+
+/// Fetch the feed, cache it once verified, and notify about new relevant notices once each.
+///
+/// A feed that fails verification is dropped and the previous cache kept.
+pub async fn refresh<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let (feed, signature) = fetch().await?;
+    verify(&feed, &signature)?;
+    {
+        let _guard = STATE_LOCK
+            .lock()
+            .map_err(|_| "Notice state lock poisoned")?;
+        let mut state = load_state();
+        state.feed = Some(feed);
+        state.signature = Some(signature);
+        state.fetched_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+        save_state(&state)?;
+    }
+
+    let fresh: Vec<NoticeView> = tauri::async_runtime::spawn_blocking(relevant)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|view| !view.acknowledged)
+        .collect();
+    let _guard = STATE_LOCK
+        .lock()
+        .map_err(|_| "Notice state lock poisoned")?;
+    let mut state = load_state();
+    for view in fresh {
+        if state.notified.contains(&view.notice.id) {
+            continue;
+        }
+        notifications::notify(
+            app,
+            NotificationCategory::Deprecation,
+            &view.notice.title,
+            &view.notice.message,
+        );
+        state.notified.push(view.notice.id);
+    }
+    save_state(&state)
+}
+
+/// Deprecation and end-of-life notices that apply to this machine
+#[tauri::command]
+pub async fn get_notices() -> Vec<NoticeView> {
+    tauri::async_runtime::spawn_blocking(relevant)
+        .await
+        .unwrap_or_default()
+}
+
+/// Mark a notice as read so it stops being highlighted
+#[tauri::command]
+pub fn acknowledge_notice(id: String) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let _guard = STATE_LOCK
+        .lock()
+        .map_err(|_| "Notice state lock poisoned")?;
+    let mut state = load_state();
+    if !state.acknowledged.contains(&id) {
+        state.acknowledged.push(id);
+        save_state(&state)?;
+    }
+    Ok(())
+}
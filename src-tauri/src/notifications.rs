@@ -0,0 +1,425 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_notification::NotificationExt;
+use ts_rs::TS;
+
+use crate::capabilities::parse_version;
+use crate::error::DesktopError;
+use crate::events::NOTIFICATION_RECORDED_EVENT;
+use crate::focus;
+use crate::i18n::t_with;
+use crate::kiosk;
+use crate::notices;
+use crate::settings;
+use crate::updater;
+
+const UPDATE_CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const FOCUS_POLL_INTERVAL: Duration = Duration::from_secs(30);
+/// Non-critical notifications raised this close together are shown as one per category
+const BATCH_WINDOW: Duration = Duration::from_secs(5);
+const HISTORY_LIMIT: usize = 200;
+
+/// Non-critical notifications held back during Do Not Disturb, newest per category
+static HELD: Mutex<Vec<HeldNotification>> = Mutex::new(Vec::new());
+/// Non-critical notifications waiting for the current batch window to close
+static PENDING: Mutex<Vec<HeldNotification>> = Mutex::new(Vec::new());
+/// Everything raised since launch, oldest first, for the notification center
+static HISTORY: Mutex<VecDeque<NotificationRecord>> = Mutex::new(VecDeque::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+struct HeldNotification {
+    category: NotificationCategory,
+    title: String,
+    body: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum NotificationCategory {
+    GatewayStarted,
+    GatewayStopped,
+    GatewayCrashed,
+    CliUpdate,
+    AppUpdate,
+    BackupFailed,
+    Deprecation,
+    ResourceLimit,
+    TokenInvalid,
+    Compatibility,
+    DiskSpace,
+    ToolApproval,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationPreferences {
+    pub gateway_started: bool,
+    pub gateway_stopped: bool,
+    pub gateway_crashed: bool,
+    pub cli_update: bool,
+    pub app_update: bool,
+    pub backup_failed: bool,
+    pub deprecation: bool,
+    pub resource_limit: bool,
+    pub token_invalid: bool,
+    pub compatibility: bool,
+    pub disk_space: bool,
+    /// An agent is waiting for a tool call to be allowed
+    pub tool_approval: bool,
+    /// Hold non-critical notifications and scheduled work while the OS is in
+    /// Do Not Disturb or the user is presenting
+    pub respect_focus: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            gateway_started: true,
+            gateway_stopped: true,
+            gateway_crashed: true,
+            cli_update: true,
+            app_update: true,
+            backup_failed: true,
+            deprecation: true,
+            resource_limit: true,
+            token_invalid: true,
+            compatibility: true,
+            disk_space: true,
+            tool_approval: true,
+            respect_focus: true,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    fn allows(&self, category: NotificationCategory) -> bool {
+        match category {
+            NotificationCategory::GatewayStarted => self.gateway_started,
+            NotificationCategory::GatewayStopped => self.gateway_stopped,
+            NotificationCategory::GatewayCrashed => self.gateway_crashed,
+            NotificationCategory::CliUpdate => self.cli_update,
+            NotificationCategory::AppUpdate => self.app_update,
+            NotificationCategory::BackupFailed => self.backup_failed,
+            NotificationCategory::Deprecation => self.deprecation,
+            NotificationCategory::ResourceLimit => self.resource_limit,
+            NotificationCategory::TokenInvalid => self.token_invalid,
+            NotificationCategory::Compatibility => self.compatibility,
+            NotificationCategory::DiskSpace => self.disk_space,
+            NotificationCategory::ToolApproval => self.tool_approval,
+        }
+    }
+}
+
+impl NotificationCategory {
+    /// Shown even during Do Not Disturb
+    fn is_critical(self) -> bool {
+        matches!(self, Self::GatewayCrashed | Self::BackupFailed)
+    }
+}
+
+/// How a notification reached the user when it was raised
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum NotificationDelivery {
+    /// Shown right away; critical notifications skip batching and Do Not Disturb
+    Immediate,
+    /// Folded into a summary with others of its category raised in the same window
+    Batched,
+    /// Held until Do Not Disturb or the presentation ends
+    Held,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct NotificationRecord {
+    #[ts(type = "number")]
+    pub id: u64,
+    pub category: NotificationCategory,
+    pub title: String,
+    pub body: String,
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub raised_at: u64,
+    pub delivery: NotificationDelivery,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn show<R: Runtime>(app: &AppHandle<R>, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log_error!("Failed to show notification: {}", e);
+    }
+}
+
+fn record<R: Runtime>(
+    app: &AppHandle<R>,
+    category: NotificationCategory,
+    title: &str,
+    body: &str,
+    delivery: NotificationDelivery,
+) {
+    let record = NotificationRecord {
+        id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        category,
+        title: title.to_string(),
+        body: body.to_string(),
+        raised_at: now_secs(),
+        delivery,
+    };
+    if let Ok(mut history) = HISTORY.lock() {
+        if history.len() == HISTORY_LIMIT {
+            history.pop_front();
+        }
+        history.push_back(record.clone());
+    }
+    if let Err(e) = app.emit(NOTIFICATION_RECORDED_EVENT, record) {
+        log_error!("Failed to emit notification record: {}", e);
+    }
+}
+
+/// Keep only the newest notification of its category
+fn hold(held: &mut Vec<HeldNotification>, notification: HeldNotification) {
+    held.retain(|n| n.category != notification.category);
+    held.push(notification);
+}
+
+/// Show one notification per category for the closed batch window: the latest
+/// one, noting how many others it stands for
+fn flush_batch<R: Runtime>(app: &AppHandle<R>) {
+    let pending = PENDING
+        .lock()
+        .map(|mut pending| std::mem::take(&mut *pending))
+        .unwrap_or_default();
+    let mut latest: Vec<(HeldNotification, usize)> = Vec::new();
+    for notification in pending {
+        match latest
+            .iter_mut()
+            .find(|(n, _)| n.category == notification.category)
+        {
+            Some(entry) => *entry = (notification, entry.1 + 1),
+            None => latest.push((notification, 1)),
+        }
+    }
+
+    // Do Not Disturb may have started while the window was open
+    let defer = focus::should_defer();
+    for (mut notification, count) in latest {
+        if count > 1 {
+            notification.body = t_with(
+                "notifications.batched_body",
+                &[("body", &notification.body), ("count", &(count - 1))],
+            );
+        }
+        if defer {
+            if let Ok(mut held) = HELD.lock() {
+                hold(&mut held, notification);
+            }
+        } else {
+            show(app, &notification.title, &notification.body);
+        }
+    }
+}
+
+/// Show a native notification unless the user has muted its category.
+///
+/// Non-critical notifications raised during Do Not Disturb or a presentation are
+/// held and shown once the user is available again. The rest are batched: the
+/// first one opens a short window and everything raised in it is shown together,
+/// one notification per category.
+pub fn notify<R: Runtime>(
+    app: &AppHandle<R>,
+    category: NotificationCategory,
+    title: &str,
+    body: &str,
+) {
+    if !settings::current().notifications.allows(category) {
+        return;
+    }
+    if category.is_critical() {
+        record(app, category, title, body, NotificationDelivery::Immediate);
+        show(app, title, body);
+        return;
+    }
+
+    let notification = HeldNotification {
+        category,
+        title: title.to_string(),
+        body: body.to_string(),
+    };
+    if focus::should_defer() {
+        record(app, category, title, body, NotificationDelivery::Held);
+        if let Ok(mut held) = HELD.lock() {
+            hold(&mut held, notification);
+        }
+        return;
+    }
+
+    record(app, category, title, body, NotificationDelivery::Batched);
+    let opens_window = match PENDING.lock() {
+        Ok(mut pending) => {
+            pending.push(notification);
+            pending.len() == 1
+        }
+        Err(_) => false,
+    };
+    if opens_window {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(BATCH_WINDOW).await;
+            flush_batch(&app);
+        });
+    }
+}
+
+/// Show a notification with buttons, calling `on_action` with the id of the one
+/// clicked, or `default` for the notification itself.
+///
+/// Buttons need a freedesktop notification server, so this is Linux only; other
+/// platforms, and Do Not Disturb, get a plain notification through [`notify`]
+/// and the app's own UI answers it.
+#[cfg(target_os = "linux")]
+pub fn notify_actionable<R: Runtime>(
+    app: &AppHandle<R>,
+    category: NotificationCategory,
+    title: &str,
+    body: &str,
+    actions: &[(&str, String)],
+    on_action: impl FnOnce(String) + Send + 'static,
+) {
+    if !settings::current().notifications.allows(category) {
+        return;
+    }
+    if focus::should_defer() {
+        notify(app, category, title, body);
+        return;
+    }
+    record(app, category, title, body, NotificationDelivery::Immediate);
+    let mut notification = notify_rust::Notification::new();
+    notification.summary(title).body(body).action("default", "");
+    for (id, label) in actions {
+        notification.action(id, label);
+    }
+    tauri::async_runtime::spawn_blocking(move || match notification.show() {
+        Ok(handle) => handle.wait_for_action(|action| {
+            // `__closed` when dismissed or expired
+            if action != "__closed" {
+                on_action(action.to_string());
+            }
+        }),
+        Err(e) => log_error!("Failed to show notification: {}", e),
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn notify_actionable<R: Runtime>(
+    app: &AppHandle<R>,
+    category: NotificationCategory,
+    title: &str,
+    body: &str,
+    _actions: &[(&str, String)],
+    _on_action: impl FnOnce(String) + Send + 'static,
+) {
+    notify(app, category, title, body);
+}
+
+/// Deliver held notifications once Do Not Disturb or the presentation ends
+pub fn spawn_focus_watcher<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(FOCUS_POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if HELD.lock().map_or(true, |held| held.is_empty()) {
+                continue;
+            }
+            focus::wait_until_available().await;
+            let held = HELD
+                .lock()
+                .map(|mut held| std::mem::take(&mut *held))
+                .unwrap_or_default();
+            for notification in held {
+                show(&app, &notification.title, &notification.body);
+            }
+        }
+    });
+}
+
+/// Periodically compare the installed CLI with npm and announce each new release once
+pub fn spawn_update_checker<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut announced: Option<String> = None;
+        let mut interval = tokio::time::interval(UPDATE_CHECK_INTERVAL);
+
+        loop {
+            interval.tick().await;
+            if let Err(e) = notices::refresh(&app).await {
+                log_error!("Failed to refresh notices: {}", e);
+            }
+            let settings = settings::current();
+            // A pinned version was chosen over newer releases on purpose
+            if !settings.notifications.cli_update || settings.openclaw_versions.pinned.is_some() {
+                continue;
+            }
+
+            let Ok(latest) = updater::fetch_latest_version().await else {
+                continue;
+            };
+            let Ok(Some(installed)) =
+                tauri::async_runtime::spawn_blocking(crate::detect_openclaw_version).await
+            else {
+                continue;
+            };
+
+            let newer = match (parse_version(&installed), parse_version(&latest)) {
+                (Some(current), Some(available)) => available > current,
+                _ => false,
+            };
+            if !newer || announced.as_deref() == Some(latest.as_str()) {
+                continue;
+            }
+
+            notify(
+                &app,
+                NotificationCategory::CliUpdate,
+                "OpenClaw update available",
+                &format!("Version {} is available (installed: {})", latest, installed),
+            );
+            announced = Some(latest);
+        }
+    });
+}
+
+/// Notifications raised since launch, newest first
+#[tauri::command]
+pub fn get_notification_history(limit: Option<usize>) -> Vec<NotificationRecord> {
+    HISTORY
+        .lock()
+        .map(|history| {
+            history
+                .iter()
+                .rev()
+                .take(limit.unwrap_or(HISTORY_LIMIT))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Enable or disable notification categories
+#[tauri::command]
+pub fn set_notification_preferences(
+    preferences: NotificationPreferences,
+) -> Result<NotificationPreferences, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    Ok(settings::update(|s| s.notifications = preferences)?.notifications)
+}
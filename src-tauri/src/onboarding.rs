@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+
+use crate::error::DesktopError;
+use crate::{
+    active_gateway_port, config, installer, is_gateway_running, kiosk, openclaw_installed,
+    prerequisites, read_gateway_token, settings, start_default_gateway, tokens,
+};
+
+/// Setup wizard steps, in the order they have to be completed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    NodeRuntime,
+    InstallOpenclaw,
+    CreateConfig,
+    GatewayToken,
+    StartGateway,
+    Done,
+}
+
+const STEPS: [OnboardingStep; 5] = [
+    OnboardingStep::NodeRuntime,
+    OnboardingStep::InstallOpenclaw,
+    OnboardingStep::CreateConfig,
+    OnboardingStep::GatewayToken,
+    OnboardingStep::StartGateway,
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StepStatus {
+    pub step: OnboardingStep,
+    pub complete: bool,
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingState {
+    /// First incomplete step, or `done`
+    pub current_step: OnboardingStep,
+    pub steps: Vec<StepStatus>,
+    /// The user finished or dismissed the wizard before
+    pub dismissed: bool,
+}
+
+fn check(step: OnboardingStep) -> StepStatus {
+    let (complete, detail) = match step {
+        OnboardingStep::NodeRuntime => {
+            let runtime = prerequisites::detect();
+            match runtime.package_manager {
+                Some(pm) => (
+                    true,
+                    Some(format!(
+                        "Node {}, installing with {}",
+                        runtime.node.version.unwrap_or_default(),
+                        pm.program()
+                    )),
+                ),
+                None => (false, Some(runtime.suggestions.join("; "))),
+            }
+        }
+        OnboardingStep::InstallOpenclaw => (openclaw_installed(), None),
+        OnboardingStep::CreateConfig => match config::editable_path() {
+            Ok(path) => (path.exists(), Some(path.display().to_string())),
+            Err(e) => (false, Some(e)),
+        },
+        OnboardingStep::GatewayToken => (read_gateway_token().is_some(), None),
+        OnboardingStep::StartGateway => (
+            is_gateway_running(),
+            Some(format!("Port {}", active_gateway_port())),
+        ),
+        OnboardingStep::Done => (true, None),
+    };
+    StepStatus {
+        step,
+        complete,
+        detail,
+    }
+}
+
+fn state() -> OnboardingState {
+    let steps: Vec<StepStatus> = STEPS.iter().map(|step| check(*step)).collect();
+    let current_step = steps
+        .iter()
+        .find(|s| !s.complete)
+        .map(|s| s.step)
+        .unwrap_or(OnboardingStep::Done);
+    OnboardingState {
+        current_step,
+        steps,
+        dismissed: settings::current().onboarding_dismissed,
+    }
+}
+
+/// Write a minimal config for the default gateway
+fn create_config() -> Result<(), String> {
+    let path = config::editable_path()?;
+    if path.exists() {
+        return Ok(());
+    }
+    config::write_atomic(&json!({
+        "gateway": { "port": active_gateway_port() }
+    }))
+}
+
+/// Store a fresh token in the config unless one is already configured
+fn create_token() -> Result<(), String> {
+    if read_gateway_token().is_some() {
+        return Ok(());
+    }
+    let mut config = config::load_config()?;
+    let root = config
+        .as_object_mut()
+        .ok_or("Config must be a JSON object")?;
+    let gateway = root.entry("gateway").or_insert_with(|| json!({}));
+    if !gateway.is_object() {
+        *gateway = json!({});
+    }
+    gateway["auth"] = json!({ "token": tokens::generate_token() });
+    config::write_atomic(&config)
+}
+
+/// Detect which setup prerequisites are missing and which wizard step comes next
+#[tauri::command]
+pub async fn get_onboarding_state() -> Result<OnboardingState, DesktopError> {
+    Ok(tauri::async_runtime::spawn_blocking(state)
+        .await
+        .map_err(|e| format!("Failed to check setup: {}", e))?)
+}
+
+/// Carry out `step` (the wizard's current step) and return the updated state.
+/// `done` dismisses the wizard so it is not shown again.
+#[tauri::command]
+pub async fn advance_onboarding_step(
+    app: AppHandle,
+    step: OnboardingStep,
+) -> Result<OnboardingState, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    match step {
+        OnboardingStep::NodeRuntime => {
+            let runtime = prerequisites::detect();
+            if runtime.package_manager.is_none() {
+                return Err(DesktopError::Unavailable(format!(
+                    "{}, then check again",
+                    runtime.suggestions.join("; ")
+                )));
+            }
+        }
+        OnboardingStep::InstallOpenclaw => {
+            installer::install_openclaw(app).await?;
+        }
+        OnboardingStep::CreateConfig => create_config()?,
+        OnboardingStep::GatewayToken => create_token()?,
+        OnboardingStep::StartGateway => {
+            start_default_gateway(&app)?;
+        }
+        OnboardingStep::Done => {
+            settings::update(|s| s.onboarding_dismissed = true)?;
+        }
+    }
+    get_onboarding_state().await
+}
@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Slow shell profiles shouldn't hold up the launch indefinitely
+const SHELL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Separates the environment dump from anything the profile scripts print
+const MARKER: &str = "__OPENCLAW_DESKTOP_ENV__";
+
+/// Variables copied from the login shell besides PATH, so version managers
+/// find their installs
+const EXTRA_VARS: &[&str] = &["NVM_DIR", "VOLTA_HOME", "PNPM_HOME", "BUN_INSTALL"];
+
+static LOGIN_ENV: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Run the user's login shell and capture the environment it ends up with
+fn read_login_env() -> Result<HashMap<String, String>, String> {
+    let shell = env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+    let mut child = Command::new(&shell)
+        .args(["-lc", &format!("echo {}; printenv", MARKER)])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run login shell {}: {}", shell, e))?;
+
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < SHELL_TIMEOUT => {
+                std::thread::sleep(Duration::from_millis(50))
+            }
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("Login shell {} timed out", shell));
+            }
+            Err(e) => return Err(format!("Failed to wait for login shell: {}", e)),
+        }
+    }
+
+    let mut output = String::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        stdout
+            .read_to_string(&mut output)
+            .map_err(|e| format!("Failed to read login shell output: {}", e))?;
+    }
+    let (_, dump) = output
+        .split_once(MARKER)
+        .ok_or("Login shell produced no environment")?;
+    Ok(dump
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect())
+}
+
+/// Login-shell PATH entries first, then whatever the app was launched with
+fn merged_path(login_path: &str) -> Option<OsString> {
+    let mut entries: Vec<_> = env::split_paths(login_path).collect();
+    if let Some(current) = env::var_os("PATH") {
+        for entry in env::split_paths(&current) {
+            if !entries.contains(&entry) {
+                entries.push(entry);
+            }
+        }
+    }
+    env::join_paths(entries).ok()
+}
+
+/// Adopt the login shell's PATH for this process so every spawned command
+/// inherits it. Apps started from Finder or a desktop launcher otherwise only
+/// see the minimal system PATH. Call once, before any threads are started.
+pub fn init() {
+    if cfg!(target_os = "windows") {
+        return;
+    }
+    let login_env = match read_login_env() {
+        Ok(login_env) => login_env,
+        Err(e) => {
+            log_error!("Failed to resolve login shell environment: {}", e);
+            return;
+        }
+    };
+
+    if let Some(path) = login_env.get("PATH").and_then(|p| merged_path(p)) {
+        env::set_var("PATH", path);
+    }
+    for key in EXTRA_VARS {
+        if let (None, Some(value)) = (env::var_os(key), login_env.get(*key)) {
+            env::set_var(key, value);
+        }
+    }
+    let _ = LOGIN_ENV.set(login_env);
+}
+
+/// PATH reported by the login shell, if it could be resolved
+pub fn login_path() -> Option<&'static str> {
+    LOGIN_ENV.get()?.get("PATH").map(String::as_str)
+}
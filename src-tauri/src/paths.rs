@@ -0,0 +1,747 @@
+//! Where things live on disk, starting with the data root that all app and
+//! gateway data hangs off, plus path and PATH helpers.
+//!
+//! The data root follows platform conventions (`$XDG_DATA_HOME/openclaw` on
+//! Linux, `%APPDATA%\OpenClaw` on Windows, `~/.openclaw` on macOS) unless
+//! `OPENCLAW_HOME` or a data profile says otherwise. An existing `~/.openclaw`
+//! keeps being used until it is migrated; once a platform root is in use,
+//! `~/.openclaw` links to it so the standalone `openclaw` CLI sees the same data.
+
+use serde::{Deserialize, Serialize};
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use tauri::AppHandle;
+
+use crate::error::DesktopError;
+use crate::{audit, connection, is_gateway_running, kiosk, profiles};
+
+/// The location every release before platform directories used
+const LEGACY_ROOT: &str = ".openclaw";
+#[cfg(target_os = "windows")]
+const PLATFORM_DIR: &str = "OpenClaw";
+#[cfg(not(target_os = "windows"))]
+const PLATFORM_DIR: &str = "openclaw";
+const HOME_ENV: &str = "OPENCLAW_HOME";
+/// Isolated roots live at `~/.openclaw-profiles/<name>`
+const ISOLATED_ROOTS: &str = ".openclaw-profiles";
+const DATA_PROFILE_FLAG: &str = "--data-profile";
+/// Lives outside every data root, so it can say which one to use
+const CHOICE_FILE: &str = "data-root.json";
+const APP_IDENTIFIER: &str = "com.ai-dev-2024.openclaw-desktop";
+
+/// Decided once per launch; switching roots under a running app would split its state
+static DATA_ROOT: OnceLock<DataRoot> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataRootSource {
+    /// The platform's data directory
+    Platform,
+    /// `~/.openclaw`, still in use because it has not been migrated
+    Legacy,
+    /// The `OPENCLAW_HOME` environment variable
+    Environment,
+    /// `--data-profile <name>` on the command line
+    LaunchFlag,
+    /// Chosen in settings with `set_data_profile`
+    Saved,
+}
+
+#[derive(Debug, Clone)]
+struct DataRoot {
+    path: PathBuf,
+    profile: Option<String>,
+    source: DataRootSource,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct DataRootChoice {
+    profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataRootInfo {
+    pub path: String,
+    /// Isolated profile in use; `None` is the shared `~/.openclaw`
+    pub profile: Option<String>,
+    pub source: DataRootSource,
+    /// Where gateway logs go; the state directory on Linux
+    pub log_dir: String,
+    /// Profile saved for the next launch, when it differs from this one
+    pub next_launch: Option<String>,
+    /// Where `migrate_data_dir` would move a legacy `~/.openclaw`
+    pub migration_target: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DataDirMigration {
+    pub from: String,
+    pub to: String,
+    /// The app relaunches on its own to switch to the new location
+    pub restarting: bool,
+}
+
+fn home() -> Result<PathBuf, String> {
+    dirs::home_dir().ok_or_else(|| "Could not find home directory".to_string())
+}
+
+fn choice_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join(APP_IDENTIFIER).join(CHOICE_FILE))
+}
+
+fn load_choice() -> DataRootChoice {
+    choice_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// The value of `--data-profile <name>` or `--data-profile=<name>`
+fn profile_from_args(args: &[String]) -> Option<String> {
+    args.iter()
+        .enumerate()
+        .find_map(|(i, arg)| match arg.strip_prefix(DATA_PROFILE_FLAG)? {
+            "" => args.get(i + 1).cloned(),
+            rest => rest.strip_prefix('=').map(str::to_string),
+        })
+}
+
+fn legacy_root() -> Result<PathBuf, String> {
+    Ok(home()?.join(LEGACY_ROOT))
+}
+
+/// The conventional data directory; macOS keeps the dot folder CLI users expect
+fn platform_root() -> Result<PathBuf, String> {
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    if let Some(dir) = dirs::data_dir() {
+        return Ok(dir.join(PLATFORM_DIR));
+    }
+    legacy_root()
+}
+
+fn profile_root(profile: String, source: DataRootSource) -> Result<DataRoot, String> {
+    profiles::validate_profile_name(&profile)?;
+    Ok(DataRoot {
+        path: home()?.join(ISOLATED_ROOTS).join(&profile),
+        profile: Some(profile),
+        source,
+    })
+}
+
+/// Launch flag, then `OPENCLAW_HOME`, then the saved profile, then the
+/// platform root unless only a legacy `~/.openclaw` exists
+fn choose_root(
+    args: &[String],
+    env_home: Option<OsString>,
+    choice: DataRootChoice,
+    platform: PathBuf,
+    legacy: PathBuf,
+) -> Result<DataRoot, String> {
+    if let Some(profile) = profile_from_args(args) {
+        return profile_root(profile, DataRootSource::LaunchFlag);
+    }
+    if let Some(path) = env_home.filter(|p| !p.is_empty()) {
+        return Ok(DataRoot {
+            path: PathBuf::from(path),
+            profile: None,
+            source: DataRootSource::Environment,
+        });
+    }
+    if let Some(profile) = choice.profile {
+        return profile_root(profile, DataRootSource::Saved);
+    }
+    let (path, source) = if platform != legacy && !platform.exists() && legacy.exists() {
+        (legacy, DataRootSource::Legacy)
+    } else {
+        (platform, DataRootSource::Platform)
+    };
+    Ok(DataRoot {
+        path,
+        profile: None,
+        source,
+    })
+}
+
+fn resolve() -> Result<DataRoot, String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    choose_root(
+        &args,
+        std::env::var_os(HOME_ENV),
+        load_choice(),
+        platform_root()?,
+        legacy_root()?,
+    )
+}
+
+fn current_root() -> Result<&'static DataRoot, String> {
+    if let Some(root) = DATA_ROOT.get() {
+        return Ok(root);
+    }
+    let root = resolve()?;
+    Ok(DATA_ROOT.get_or_init(|| root))
+}
+
+/// The data root picked for this launch; created if missing
+pub(crate) fn data_root() -> Result<PathBuf, String> {
+    let root = current_root()?;
+    if !root.path.exists() {
+        fs::create_dir_all(&root.path)
+            .map_err(|e| format!("Failed to create OpenClaw directory: {}", e))?;
+        if root.source == DataRootSource::Platform {
+            link_legacy(&root.path);
+        }
+    }
+    Ok(root.path.clone())
+}
+
+#[cfg(unix)]
+fn create_link(target: &Path, link: &Path) -> Result<(), String> {
+    std::os::unix::fs::symlink(target, link).map_err(|e| e.to_string())
+}
+
+/// A junction, which unlike a symlink needs neither developer mode nor admin
+/// rights. Made with `FSCTL_SET_REPARSE_POINT` on an empty directory, as
+/// `mklink /J` does, so no path goes through a shell.
+#[cfg(windows)]
+fn create_link(target: &Path, link: &Path) -> Result<(), String> {
+    fs::create_dir(link).map_err(|e| e.to_string())?;
+    let result = set_junction(target, link);
+    if result.is_err() {
+        let _ = fs::remove_dir(link);
+    }
+    result
+}
+
+#[cfg(windows)]
+fn set_junction(target: &Path, link: &Path) -> Result<(), String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{CloseHandle, GENERIC_WRITE, HANDLE};
+    use windows::Win32::Storage::FileSystem::{
+        CreateFileW, FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_OPEN_REPARSE_POINT, FILE_SHARE_NONE,
+        OPEN_EXISTING,
+    };
+    use windows::Win32::System::Ioctl::FSCTL_SET_REPARSE_POINT;
+    use windows::Win32::System::SystemServices::IO_REPARSE_TAG_MOUNT_POINT;
+    use windows::Win32::System::IO::DeviceIoControl;
+
+    let wide = |s: &OsStr| s.encode_wide().collect::<Vec<u16>>();
+    // The print name is the plain path; the substitute name is its NT form
+    let print = target.as_os_str().to_string_lossy();
+    let print = print.strip_prefix(r"\\?\").unwrap_or(&print);
+    let print_name = wide(OsStr::new(print));
+    let substitute_name = wide(OsStr::new(&format!(r"\??\{}", print)));
+
+    // REPARSE_DATA_BUFFER with a MountPointReparseBuffer; both names NUL terminated
+    let substitute_len = substitute_name.len() * 2;
+    let print_len = print_name.len() * 2;
+    let data_len = u16::try_from(8 + substitute_len + 2 + print_len + 2)
+        .map_err(|_| "Path is too long for a junction".to_string())?;
+    let mut buffer = Vec::with_capacity(8 + data_len as usize);
+    buffer.extend_from_slice(&IO_REPARSE_TAG_MOUNT_POINT.to_le_bytes());
+    buffer.extend_from_slice(&data_len.to_le_bytes());
+    buffer.extend_from_slice(&0u16.to_le_bytes());
+    for field in [0, substitute_len, substitute_len + 2, print_len] {
+        buffer.extend_from_slice(&(field as u16).to_le_bytes());
+    }
+    for unit in substitute_name
+        .iter()
+        .chain(&[0])
+        .chain(&print_name)
+        .chain(&[0])
+    {
+        buffer.extend_from_slice(&unit.to_le_bytes());
+    }
+
+    let link = wide(link.as_os_str())
+        .into_iter()
+        .chain([0])
+        .collect::<Vec<u16>>();
+    unsafe {
+        let handle = CreateFileW(
+            PCWSTR(link.as_ptr()),
+            GENERIC_WRITE.0,
+            FILE_SHARE_NONE,
+            None,
+            OPEN_EXISTING,
+            FILE_FLAG_OPEN_REPARSE_POINT | FILE_FLAG_BACKUP_SEMANTICS,
+            HANDLE::default(),
+        )
+        .map_err(|e| e.to_string())?;
+        let result = DeviceIoControl(
+            handle,
+            FSCTL_SET_REPARSE_POINT,
+            Some(buffer.as_ptr().cast()),
+            buffer.len() as u32,
+            None,
+            0,
+            None,
+            None,
+        );
+        let _ = CloseHandle(handle);
+        result.map_err(|e| e.to_string())
+    }
+}
+
+/// Point `~/.openclaw` at the platform root. The standalone `openclaw` CLI only
+/// knows the legacy location, so without the link it would start an empty one.
+fn link_legacy(root: &Path) {
+    let Ok(legacy) = legacy_root() else {
+        return;
+    };
+    if legacy == root || legacy.symlink_metadata().is_ok() {
+        return;
+    }
+    if let Err(e) = create_link(root, &legacy) {
+        log_error!(
+            "Failed to link {} to {}: {}",
+            legacy.display(),
+            root.display(),
+            e
+        );
+    }
+}
+
+/// Where gateway logs are written: `$XDG_STATE_HOME/openclaw` for the Linux
+/// platform root, the data root everywhere else
+pub(crate) fn log_dir() -> Result<PathBuf, String> {
+    #[cfg(target_os = "linux")]
+    if current_root()?.source == DataRootSource::Platform {
+        if let Some(state) = dirs::state_dir() {
+            let dir = state.join(PLATFORM_DIR);
+            fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create log directory: {}", e))?;
+            return Ok(dir);
+        }
+    }
+    data_root()
+}
+
+/// Isolated profile in use, if any
+pub(crate) fn data_profile() -> Option<String> {
+    current_root().ok().and_then(|root| root.profile.clone())
+}
+
+/// Keychain entries are kept apart per isolated profile too
+pub(crate) fn keychain_service(base: &str) -> String {
+    match data_profile() {
+        Some(profile) => format!("{}:{}", base, profile),
+        None => base.to_string(),
+    }
+}
+
+/// Point child processes at the same data root as the app, unless it is the
+/// `~/.openclaw` they default to
+pub(crate) fn apply_env(command: &mut Command) {
+    let (Ok(root), Ok(legacy)) = (data_root(), legacy_root()) else {
+        return;
+    };
+    if root != legacy {
+        command.env(HOME_ENV, root);
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to).map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+    let entries =
+        fs::read_dir(from).map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+    for entry in entries.flatten() {
+        let (source, target) = (entry.path(), to.join(entry.file_name()));
+        let metadata = source
+            .symlink_metadata()
+            .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+        if metadata.is_dir() {
+            copy_dir(&source, &target)?;
+        } else if metadata.is_file() {
+            fs::copy(&source, &target)
+                .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// Rename, or copy and delete when the two are on different filesystems
+fn move_dir(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if let Err(e) = copy_dir(from, to) {
+        let _ = fs::remove_dir_all(to);
+        return Err(e);
+    }
+    fs::remove_dir_all(from).map_err(|e| format!("Failed to remove {}: {}", from.display(), e))
+}
+
+/// Move the gateway logs out of a migrated root into the state directory
+#[cfg(target_os = "linux")]
+fn move_logs(root: &Path) {
+    let Some(state) = dirs::state_dir().map(|dir| dir.join(PLATFORM_DIR)) else {
+        return;
+    };
+    if let Err(e) = fs::create_dir_all(&state) {
+        log_error!("Failed to create log directory: {}", e);
+        return;
+    }
+    let Ok(entries) = fs::read_dir(root) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name_str = name.to_string_lossy();
+        if name_str == "logs" || (name_str.starts_with("gateway") && name_str.contains(".log")) {
+            let target = state.join(&name);
+            let moved = if entry.path().is_dir() {
+                move_dir(&entry.path(), &target)
+            } else {
+                fs::rename(entry.path(), &target).map_err(|e| e.to_string())
+            };
+            if let Err(e) = moved {
+                log_error!("Failed to move {}: {}", name_str, e);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn move_logs(_root: &Path) {}
+
+fn migrate() -> Result<DataDirMigration, String> {
+    if current_root()?.source != DataRootSource::Legacy {
+        return Err("There is no legacy ~/.openclaw in use to migrate".to_string());
+    }
+    let (from, to) = (legacy_root()?, platform_root()?);
+    if to.exists() {
+        return Err(format!("{} already exists", to.display()));
+    }
+    move_dir(&from, &to)?;
+    move_logs(&to);
+    link_legacy(&to);
+    Ok(DataDirMigration {
+        from: from.display().to_string(),
+        to: to.display().to_string(),
+        restarting: true,
+    })
+}
+
+#[tauri::command]
+pub fn get_data_root() -> Result<DataRootInfo, DesktopError> {
+    let root = current_root()?;
+    let saved = load_choice().profile;
+    let migration_target = match root.source {
+        DataRootSource::Legacy => Some(platform_root()?.display().to_string()),
+        _ => None,
+    };
+    Ok(DataRootInfo {
+        path: root.path.display().to_string(),
+        profile: root.profile.clone(),
+        source: root.source,
+        log_dir: log_dir()?.display().to_string(),
+        next_launch: (saved != root.profile).then_some(saved).flatten(),
+        migration_target,
+    })
+}
+
+/// Move a legacy `~/.openclaw` to the platform's data directory, and on Linux
+/// its logs to the state directory. The gateway must be stopped; the app
+/// restarts right after, since it would otherwise keep writing to the old path.
+#[tauri::command]
+pub async fn migrate_data_dir(app: AppHandle) -> Result<DataDirMigration, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    connection::ensure_local()?;
+    let result = tauri::async_runtime::spawn_blocking(|| {
+        if is_gateway_running() {
+            return Err("Stop the gateway before moving its data".to_string());
+        }
+        migrate()
+    })
+    .await
+    .map_err(|e| format!("Failed to migrate data directory: {}", e))?;
+    audit::record_result("data_dir_migrate", LEGACY_ROOT, &result);
+    let migration = result?;
+    app.request_restart();
+    Ok(migration)
+}
+
+/// Use an isolated data root from the next launch on; `None` goes back to `~/.openclaw`
+#[tauri::command]
+pub fn set_data_profile(profile: Option<String>) -> Result<DataRootInfo, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let profile = profile.filter(|p| !p.trim().is_empty());
+    if let Some(profile) = &profile {
+        profiles::validate_profile_name(profile).map_err(DesktopError::InvalidInput)?;
+    }
+    let path = choice_path().ok_or("Could not find the app config directory")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create app config directory: {}", e))?;
+    }
+    let content = serde_json::to_string_pretty(&DataRootChoice { profile })
+        .map_err(|e| format!("Failed to serialize data root choice: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to save data root choice: {}", e))?;
+    get_data_root()
+}
+
+/// Accept only plain relative paths: no root, drive prefix, `.` or `..`
+pub(crate) fn check_relative(relative: &Path) -> Result<(), String> {
+    let plain = relative.components().next().is_some()
+        && relative
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)));
+    if plain {
+        Ok(())
+    } else {
+        Err(format!("Invalid relative path: {}", relative.display()))
+    }
+}
+
+/// Join `relative` onto `base`, refusing anything that could land outside it.
+/// Spaces and non-ASCII names pass through untouched.
+pub(crate) fn join_within(base: &Path, relative: impl AsRef<Path>) -> Result<PathBuf, String> {
+    let relative = relative.as_ref();
+    check_relative(relative)?;
+    Ok(base.join(relative))
+}
+
+/// Extensions tried for a bare program name, in `PATHEXT` order
+#[cfg(target_os = "windows")]
+fn executable_extensions() -> Vec<String> {
+    std::env::var("PATHEXT")
+        .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn executable_extensions() -> Vec<String> {
+    Vec::new()
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+fn find_in_dirs(name: &str, path_var: &OsStr, extensions: &[String]) -> Option<PathBuf> {
+    std::env::split_paths(path_var).find_map(|dir| {
+        let exact = dir.join(name);
+        if is_executable(&exact) {
+            return Some(exact);
+        }
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}{}", name, ext.to_lowercase())))
+            .find(|candidate| is_executable(candidate))
+    })
+}
+
+/// Look a program up on PATH in-process. Asking `where`/`which` instead round-trips
+/// the result through the console code page, which mangles non-ASCII directories.
+pub(crate) fn find_executable(name: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    find_in_dirs(name, &path_var, &executable_extensions())
+}
+
+/// Command running the program at `path` directly.
+///
+/// npm's `.cmd` shims are not wrapped in `cmd /c`: std quotes arguments for
+/// batch files itself, while a nested `cmd /c` line splits at spaces in the path.
+pub(crate) fn program_command(path: &Path) -> Command {
+    Command::new(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir()
+            .join(format!("openclaw-paths-{}", std::process::id()))
+            .join(name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// `file` made executable in a fresh scratch directory, and that
+    /// directory as a PATH value
+    fn executable_in(dir: &str, file: &str, body: &str) -> (PathBuf, PathBuf, OsString) {
+        let dir = scratch_dir(dir);
+        let path = dir.join(file);
+        fs::write(&path, body).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let path_var = std::env::join_paths([Path::new("/nonexistent"), &dir]).unwrap();
+        (dir, path, path_var)
+    }
+
+    #[test]
+    fn joins_spaced_and_unicode_names() {
+        let base = Path::new("/home/José Smith/.openclaw");
+        assert_eq!(
+            join_within(base, "agents/my agent.json").unwrap(),
+            base.join("agents").join("my agent.json")
+        );
+        assert_eq!(
+            join_within(base, "профиль/日本語.json").unwrap(),
+            base.join("профиль").join("日本語.json")
+        );
+    }
+
+    #[test]
+    fn rejects_escaping_paths() {
+        let base = Path::new("/home/user/.openclaw");
+        assert!(join_within(base, "").is_err());
+        assert!(join_within(base, "../outside").is_err());
+        assert!(join_within(base, "logs/../../outside").is_err());
+        assert!(join_within(base, "./gateway.log").is_err());
+        assert!(join_within(base, "/etc/passwd").is_err());
+    }
+
+    #[test]
+    fn finds_programs_in_exotic_directories() {
+        let (dir, program, path_var) = executable_in("Ünïcödé user/npm global", "openclaw", "");
+        assert_eq!(find_in_dirs("openclaw", &path_var, &[]), Some(program));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn tries_extensions_in_order() {
+        let (dir, shim, path_var) = executable_in("with ext", "openclaw.cmd", "");
+        let extensions = vec![".EXE".to_string(), ".CMD".to_string()];
+        assert_eq!(find_in_dirs("openclaw", &path_var, &extensions), Some(shim));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn reads_data_profile_flag() {
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            profile_from_args(&args(&["--minimized", "--data-profile", "testing"])),
+            Some("testing".to_string())
+        );
+        assert_eq!(
+            profile_from_args(&args(&["--data-profile=clean"])),
+            Some("clean".to_string())
+        );
+        assert_eq!(profile_from_args(&args(&["--data-profile"])), None);
+        assert_eq!(profile_from_args(&args(&["--data-profiles=x"])), None);
+    }
+
+    fn choose(
+        args: &[&str],
+        env_home: Option<&str>,
+        saved: Option<&str>,
+        platform: &Path,
+        legacy: &Path,
+    ) -> DataRoot {
+        choose_root(
+            &args.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+            env_home.map(OsString::from),
+            DataRootChoice {
+                profile: saved.map(str::to_string),
+            },
+            platform.to_path_buf(),
+            legacy.to_path_buf(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn resolves_roots_in_precedence_order() {
+        let (platform, legacy) = (Path::new("/data/openclaw"), Path::new("/home/u/.openclaw"));
+        let flag = ["--data-profile", "flagged"];
+
+        let root = choose(&flag, Some("/custom"), Some("saved"), platform, legacy);
+        assert_eq!(root.source, DataRootSource::LaunchFlag);
+        assert_eq!(root.profile.as_deref(), Some("flagged"));
+
+        let root = choose(&[], Some("/custom"), Some("saved"), platform, legacy);
+        assert_eq!(root.source, DataRootSource::Environment);
+        assert_eq!(root.path, Path::new("/custom"));
+
+        let root = choose(&[], Some(""), Some("saved"), platform, legacy);
+        assert_eq!(root.source, DataRootSource::Saved);
+        assert_eq!(root.profile.as_deref(), Some("saved"));
+
+        let root = choose(&[], None, None, platform, legacy);
+        assert_eq!(root.source, DataRootSource::Platform);
+        assert_eq!(root.path, platform);
+    }
+
+    #[test]
+    fn keeps_legacy_root_until_migrated() {
+        let dir = scratch_dir("legacy detection");
+        let (platform, legacy) = (dir.join("platform"), dir.join(".openclaw"));
+
+        let root = choose(&[], None, None, &platform, &legacy);
+        assert_eq!(root.source, DataRootSource::Platform);
+
+        fs::create_dir_all(&legacy).unwrap();
+        let root = choose(&[], None, None, &platform, &legacy);
+        assert_eq!(root.source, DataRootSource::Legacy);
+        assert_eq!(root.path, legacy);
+
+        fs::create_dir_all(&platform).unwrap();
+        let root = choose(&[], None, None, &platform, &legacy);
+        assert_eq!(root.source, DataRootSource::Platform);
+
+        // macOS keeps the dot folder as its platform root
+        let root = choose(&[], None, None, &legacy, &legacy);
+        assert_eq!(root.source, DataRootSource::Platform);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    /// A shim under a path with spaces is the program itself, and gets each
+    /// argument intact rather than a command line re-split by a nested shell
+    #[test]
+    fn runs_shims_in_spaced_directories() {
+        #[cfg(unix)]
+        let (file, body, extensions) = ("openclaw", "#!/bin/sh\nprintf '%s\\n' \"$@\"\n", vec![]);
+        #[cfg(windows)]
+        let (file, body, extensions) = (
+            "openclaw.cmd",
+            "@echo off\r\necho %*\r\n",
+            vec![".CMD".to_string()],
+        );
+        let (dir, shim, path_var) = executable_in("José Smith/npm global", file, body);
+        let found = find_in_dirs("openclaw", &path_var, &extensions).unwrap();
+
+        let mut command = program_command(&found);
+        command.args(["gateway", "--profile", "my work"]);
+        assert_eq!(command.get_program(), shim.as_os_str());
+        assert_eq!(
+            command.get_args().collect::<Vec<_>>(),
+            ["gateway", "--profile", "my work"]
+        );
+
+        let output = command.output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        #[cfg(unix)]
+        assert_eq!(stdout, "gateway\n--profile\nmy work\n");
+        #[cfg(windows)]
+        assert_eq!(stdout.trim(), "gateway --profile \"my work\"");
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,91 @@
+//! Pausing the gateway: agents stop acting while the process, its sessions and
+//! in-memory state stay up. A gateway that restarts comes back unpaused.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::announce::announce;
+use crate::audit;
+use crate::availability::{self, ActionId};
+use crate::capabilities::{self, GatewayFeature};
+use crate::connection;
+use crate::error::DesktopError;
+use crate::events::{Politeness, GATEWAY_STATUS_CHANGED_EVENT};
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+use crate::i18n::t;
+use crate::permissions::{self, Permission};
+use crate::tray;
+use crate::{gateway_status, run_openclaw_gateway_control, GatewayStatus};
+
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::SeqCst)
+}
+
+/// Forget the paused state once the gateway process is gone
+pub(crate) fn clear() {
+    PAUSED.store(false, Ordering::SeqCst);
+}
+
+/// Ask the gateway API first; gateways without the endpoint are paused
+/// through `openclaw daemon`, which only reaches a local one
+async fn send<R: Runtime>(app: &AppHandle<R>, action: &'static str) -> Result<(), DesktopError> {
+    let api = app.state::<GatewayApi>();
+    match api
+        .post::<serde_json::Value>(&format!("/api/{}", action), &serde_json::json!({}))
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind == GatewayApiErrorKind::NotFound && !connection::is_remote() => {
+            tauri::async_runtime::spawn_blocking(move || run_openclaw_gateway_control(action))
+                .await
+                .map_err(|e| format!("Failed to {} gateway: {}", action, e))??;
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+pub(crate) async fn set_paused<R: Runtime>(
+    app: &AppHandle<R>,
+    paused: bool,
+) -> Result<GatewayStatus, DesktopError> {
+    availability::ensure(ActionId::PauseGateway)?;
+    capabilities::require(app, GatewayFeature::Pause)?;
+    let action = if paused { "pause" } else { "resume" };
+    let sent = send(app, action).await;
+    audit::record_result(&format!("gateway_{}", action), "gateway", &sent);
+    sent?;
+    PAUSED.store(paused, Ordering::SeqCst);
+
+    let key = if paused {
+        "gateway.paused"
+    } else {
+        "gateway.resumed"
+    };
+    announce(app, Politeness::Polite, &t(key));
+    if let Err(e) = tray::rebuild_tray(app) {
+        log_error!("Failed to refresh tray menu: {}", e);
+    }
+    let status = tauri::async_runtime::spawn_blocking(gateway_status)
+        .await
+        .map_err(|e| format!("Failed to read gateway status: {}", e))?;
+    if let Err(e) = app.emit(GATEWAY_STATUS_CHANGED_EVENT, status.clone()) {
+        log_error!("Failed to emit gateway status event: {}", e);
+    }
+    Ok(status)
+}
+
+/// Stop agents from acting without stopping the gateway
+#[tauri::command]
+pub async fn pause_gateway(app: AppHandle) -> Result<GatewayStatus, DesktopError> {
+    permissions::ensure_granted(&app, Permission::ControlGateway).await?;
+    set_paused(&app, true).await
+}
+
+#[tauri::command]
+pub async fn resume_gateway(app: AppHandle) -> Result<GatewayStatus, DesktopError> {
+    permissions::ensure_granted(&app, Permission::ControlGateway).await?;
+    set_paused(&app, false).await
+}
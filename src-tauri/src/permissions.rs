@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tokio::sync::{oneshot, Mutex};
+
+use crate::error::DesktopError;
+use crate::{audit, kiosk, settings};
+
+/// Sensitive capabilities that ask for confirmation before they are used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Permission {
+    /// Handing the gateway token out of the app, e.g. to the clipboard
+    ReadToken,
+    /// Installing or removing the gateway as an OS service
+    ManageService,
+    /// Installing, updating or downgrading the OpenClaw CLI
+    InstallSoftware,
+    /// Starting, stopping, restarting or pausing the gateway from the webview
+    ControlGateway,
+    /// Taking a screenshot of a display or window
+    CaptureScreen,
+}
+
+/// When a permission asks, from strictest to most lenient
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentMode {
+    /// Every time
+    Always,
+    /// Once per app launch
+    Session,
+    /// Once, then remembered until revoked
+    Remember,
+    /// Never
+    Allow,
+}
+
+impl Permission {
+    const ALL: [Permission; 5] = [
+        Permission::ReadToken,
+        Permission::ManageService,
+        Permission::InstallSoftware,
+        Permission::ControlGateway,
+        Permission::CaptureScreen,
+    ];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Permission::ReadToken => "read_token",
+            Permission::ManageService => "manage_service",
+            Permission::InstallSoftware => "install_software",
+            Permission::ControlGateway => "control_gateway",
+            Permission::CaptureScreen => "capture_screen",
+        }
+    }
+
+    fn default_mode(self) -> ConsentMode {
+        match self {
+            Permission::ReadToken | Permission::ManageService => ConsentMode::Remember,
+            Permission::InstallSoftware | Permission::ControlGateway => ConsentMode::Session,
+            Permission::CaptureScreen => ConsentMode::Always,
+        }
+    }
+
+    fn prompt(self) -> (&'static str, &'static str) {
+        match self {
+            Permission::ReadToken => (
+                "Allow access to the gateway token?",
+                "OpenClaw wants to copy your gateway token. Anyone with the token can control your gateway.",
+            ),
+            Permission::ManageService => (
+                "Allow changes to system services?",
+                "OpenClaw wants to install or remove the gateway's background service on this computer.",
+            ),
+            Permission::InstallSoftware => (
+                "Allow software installs?",
+                "OpenClaw wants to install or change the version of the OpenClaw command-line tool on this computer.",
+            ),
+            Permission::ControlGateway => (
+                "Allow control of the gateway?",
+                "OpenClaw wants to start, stop, restart or pause the gateway. Running agents may be interrupted.",
+            ),
+            Permission::CaptureScreen => (
+                "Allow a screenshot?",
+                "OpenClaw wants to capture your screen and may share the image with an agent. Anything visible will be included.",
+            ),
+        }
+    }
+}
+
+/// A remembered "Allow" for a permission
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PermissionGrant {
+    pub id: Permission,
+    /// Unix seconds
+    pub granted_at: u64,
+}
+
+/// Serializes prompts so concurrent calls don't stack dialogs for the same permission
+static PROMPT: Mutex<()> = Mutex::const_new(());
+/// Permissions allowed in `Session` mode since launch
+static SESSION_GRANTS: std::sync::Mutex<Option<HashSet<Permission>>> = std::sync::Mutex::new(None);
+
+fn consent_mode(permission: Permission) -> ConsentMode {
+    settings::current()
+        .permission_modes
+        .get(&permission)
+        .copied()
+        .unwrap_or_else(|| permission.default_mode())
+}
+
+fn is_granted(permission: Permission) -> bool {
+    match consent_mode(permission) {
+        ConsentMode::Always => false,
+        ConsentMode::Session => SESSION_GRANTS
+            .lock()
+            .is_ok_and(|grants| grants.as_ref().is_some_and(|g| g.contains(&permission))),
+        ConsentMode::Remember => settings::current()
+            .permission_grants
+            .iter()
+            .any(|grant| grant.id == permission),
+        ConsentMode::Allow => true,
+    }
+}
+
+fn forget_session_grant(permission: Permission) {
+    if let Ok(mut grants) = SESSION_GRANTS.lock() {
+        if let Some(grants) = grants.as_mut() {
+            grants.remove(&permission);
+        }
+    }
+}
+
+async fn confirm<R: Runtime>(app: &AppHandle<R>, permission: Permission) -> bool {
+    let (title, message) = permission.prompt();
+    ask(app, title, message).await
+}
+
+async fn ask<R: Runtime>(app: &AppHandle<R>, title: &str, message: &str) -> bool {
+    let (tx, rx) = oneshot::channel();
+    app.dialog()
+        .message(message)
+        .title(title)
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Allow".to_string(),
+            "Don't Allow".to_string(),
+        ))
+        .show(move |allowed| {
+            let _ = tx.send(allowed);
+        });
+    rx.await.unwrap_or(false)
+}
+
+/// Ask for a sensitive capability as its consent mode says, and keep an "Allow"
+/// for as long as the mode does.
+///
+/// A refusal is not remembered, so the next use asks again.
+pub async fn ensure_granted<R: Runtime>(
+    app: &AppHandle<R>,
+    permission: Permission,
+) -> Result<(), DesktopError> {
+    if is_granted(permission) {
+        return Ok(());
+    }
+    let _prompt = PROMPT.lock().await;
+    // Another call may have been granted while this one waited
+    if is_granted(permission) {
+        return Ok(());
+    }
+
+    let allowed = confirm(app, permission).await;
+    audit::record("permission_prompt", permission.as_str(), allowed, None);
+    if !allowed {
+        return Err(DesktopError::PermissionDenied(
+            "Permission denied".to_string(),
+        ));
+    }
+    match consent_mode(permission) {
+        ConsentMode::Session => {
+            if let Ok(mut grants) = SESSION_GRANTS.lock() {
+                grants.get_or_insert_with(HashSet::new).insert(permission);
+            }
+        }
+        ConsentMode::Remember => {
+            let granted_at = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            settings::update(|s| {
+                s.permission_grants.retain(|grant| grant.id != permission);
+                s.permission_grants.push(PermissionGrant {
+                    id: permission,
+                    granted_at,
+                });
+            })?;
+        }
+        ConsentMode::Always | ConsentMode::Allow => {}
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_permission_grants() -> Vec<PermissionGrant> {
+    settings::current().permission_grants
+}
+
+/// Forget a grant so the next use asks again
+#[tauri::command]
+pub fn revoke_permission(id: Permission) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    settings::update(|s| s.permission_grants.retain(|grant| grant.id != id))?;
+    forget_session_grant(id);
+    audit::record("permission_revoke", id.as_str(), true, None);
+    Ok(())
+}
+
+/// Consent mode of every permission, defaults included
+#[tauri::command]
+pub fn get_permission_modes() -> HashMap<Permission, ConsentMode> {
+    Permission::ALL
+        .into_iter()
+        .map(|permission| (permission, consent_mode(permission)))
+        .collect()
+}
+
+/// Change when a permission asks. Asking less often needs the user's native
+/// confirmation, so a compromised webview can't switch prompts off.
+#[tauri::command]
+pub async fn set_permission_mode(
+    app: AppHandle,
+    id: Permission,
+    mode: ConsentMode,
+) -> Result<HashMap<Permission, ConsentMode>, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if mode > consent_mode(id) {
+        let _prompt = PROMPT.lock().await;
+        let (title, _) = id.prompt();
+        let allowed = ask(
+            &app,
+            "Ask less often?",
+            &format!(
+                "OpenClaw wants to change when it asks \"{}\". Only allow this if you made the change yourself.",
+                title
+            ),
+        )
+        .await;
+        audit::record("permission_mode", id.as_str(), allowed, None);
+        if !allowed {
+            return Err(DesktopError::PermissionDenied(
+                "Permission denied".to_string(),
+            ));
+        }
+    }
+    settings::update(|s| {
+        s.permission_modes.insert(id, mode);
+        // A stricter mode starts without the grants a lenient one kept
+        if mode < ConsentMode::Remember {
+            s.permission_grants.retain(|grant| grant.id != id);
+        }
+    })?;
+    if mode == ConsentMode::Always {
+        forget_session_grant(id);
+    }
+    Ok(get_permission_modes())
+}
@@ -0,0 +1,147 @@
+//! OS integrations that only some platforms or builds have.
+//!
+//! Each subsystem sits behind a cargo feature (`service`, `keychain`, both on by
+//! default). Callers go through [`integration`], so a build without a feature,
+//! or on an unsupported OS, gets the stub behavior instead of a compile error.
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::error::DesktopError;
+use crate::permissions::{self, Permission};
+use crate::{connection, kiosk};
+
+#[cfg(feature = "keychain")]
+const KEYCHAIN_SERVICE: &str = "openclaw-desktop";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayServiceStatus {
+    /// `launchd`, `systemd`, `windows-service`, or `unavailable`
+    pub kind: String,
+    pub installed: bool,
+    pub running: bool,
+    /// Service definition file, where the platform has one
+    pub definition_path: Option<String>,
+}
+
+fn unavailable(feature: &str) -> String {
+    format!("This build of OpenClaw Desktop has no {} support", feature)
+}
+
+/// Platform-specific subsystems. The default methods are the stub fallback.
+pub trait Integration: Send + Sync {
+    fn install_service(&self) -> Result<(), String> {
+        Err(unavailable("OS service"))
+    }
+
+    fn uninstall_service(&self) -> Result<(), String> {
+        Err(unavailable("OS service"))
+    }
+
+    fn service_status(&self) -> GatewayServiceStatus {
+        GatewayServiceStatus {
+            kind: "unavailable".to_string(),
+            installed: false,
+            running: false,
+            definition_path: None,
+        }
+    }
+
+    fn keychain_get(&self, _account: &str) -> Option<String> {
+        None
+    }
+
+    /// Store a secret, or remove it with `None`
+    fn keychain_set(&self, _account: &str, _secret: Option<&str>) -> Result<(), String> {
+        Err(unavailable("keychain"))
+    }
+}
+
+/// Everything stubbed out, for operating systems without native backends
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+pub struct Stub;
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+impl Integration for Stub {}
+
+/// The integrations compiled into this build
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+pub struct Native;
+
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+impl Integration for Native {
+    #[cfg(feature = "service")]
+    fn install_service(&self) -> Result<(), String> {
+        crate::service::install()
+    }
+
+    #[cfg(feature = "service")]
+    fn uninstall_service(&self) -> Result<(), String> {
+        crate::service::uninstall()
+    }
+
+    #[cfg(feature = "service")]
+    fn service_status(&self) -> GatewayServiceStatus {
+        crate::service::status()
+    }
+
+    #[cfg(feature = "keychain")]
+    fn keychain_get(&self, account: &str) -> Option<String> {
+        keyring::Entry::new(&crate::paths::keychain_service(KEYCHAIN_SERVICE), account)
+            .ok()?
+            .get_password()
+            .ok()
+    }
+
+    #[cfg(feature = "keychain")]
+    fn keychain_set(&self, account: &str, secret: Option<&str>) -> Result<(), String> {
+        let entry = keyring::Entry::new(&crate::paths::keychain_service(KEYCHAIN_SERVICE), account)
+            .map_err(|e| format!("Failed to open keychain: {}", e))?;
+        match secret {
+            Some(secret) => entry.set_password(secret).map_err(|e| e.to_string()),
+            None => match entry.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(e.to_string()),
+            },
+        }
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "linux"))]
+static INTEGRATION: Native = Native;
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+static INTEGRATION: Stub = Stub;
+
+pub fn integration() -> &'static dyn Integration {
+    &INTEGRATION
+}
+
+pub fn service_status() -> GatewayServiceStatus {
+    integration().service_status()
+}
+
+/// Register the gateway as an OS service (launchd daemon, systemd user unit, or
+/// Windows service) so it keeps running without the app and across logouts
+#[tauri::command]
+pub async fn install_gateway_service(app: AppHandle) -> Result<GatewayServiceStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    connection::ensure_local()?;
+    permissions::ensure_granted(&app, Permission::ManageService).await?;
+    tauri::async_runtime::spawn_blocking(|| integration().install_service())
+        .await
+        .map_err(|e| format!("Failed to install gateway service: {}", e))??;
+    Ok(service_status())
+}
+
+/// Stop and remove the gateway service
+#[tauri::command]
+pub async fn uninstall_gateway_service(
+    app: AppHandle,
+) -> Result<GatewayServiceStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    permissions::ensure_granted(&app, Permission::ManageService).await?;
+    tauri::async_runtime::spawn_blocking(|| integration().uninstall_service())
+        .await
+        .map_err(|e| format!("Failed to uninstall gateway service: {}", e))??;
+    Ok(service_status())
+}
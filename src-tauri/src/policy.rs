@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::error::DesktopError;
+use crate::settings::Settings;
+
+#[cfg(target_os = "windows")]
+const POLICY_REGISTRY_KEY: &str = r"HKLM\SOFTWARE\Policies\OpenClaw\Desktop";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PortRange {
+    pub min: u16,
+    pub max: u16,
+}
+
+/// Machine-level configuration managed by an administrator.
+///
+/// `settings` holds locked values in the same shape as the user settings file;
+/// they are merged over the user's settings and cannot be changed from the app.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Policy {
+    pub settings: Map<String, Value>,
+    /// Ports gateways may listen on
+    pub allowed_port_range: Option<PortRange>,
+    /// Refuse to run `openclaw` subcommands passed in from the UI
+    pub disable_passthrough_runner: bool,
+    /// Keep the local gateway bound to loopback
+    pub disable_lan_access: bool,
+    /// Proxy URL used for all outbound downloads and CLI commands
+    pub force_proxy: Option<String>,
+}
+
+fn policy_path() -> PathBuf {
+    #[cfg(target_os = "windows")]
+    {
+        let program_data =
+            std::env::var("ProgramData").unwrap_or_else(|_| r"C:\ProgramData".to_string());
+        PathBuf::from(program_data)
+            .join("OpenClaw Desktop")
+            .join("policy.json")
+    }
+    #[cfg(target_os = "macos")]
+    {
+        PathBuf::from("/Library/Application Support/OpenClaw Desktop/policy.json")
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        PathBuf::from("/etc/openclaw-desktop/policy.json")
+    }
+}
+
+/// Policy JSON stored as the `Policy` string value under the HKLM policies key
+#[cfg(target_os = "windows")]
+fn read_registry_policy() -> Option<String> {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    let output = Command::new("reg")
+        .args(["query", POLICY_REGISTRY_KEY, "/v", "Policy"])
+        .creation_flags(crate::CREATE_NO_WINDOW)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.split_once("REG_SZ"))
+        .map(|(_, value)| value.trim().to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn read_registry_policy() -> Option<String> {
+    None
+}
+
+fn load_policy() -> Policy {
+    let content = read_registry_policy().or_else(|| fs::read_to_string(policy_path()).ok());
+    let Some(content) = content else {
+        return Policy::default();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        log_error!("Failed to parse deployment policy, ignoring it: {}", e);
+        Policy::default()
+    })
+}
+
+/// The deployment policy, read once at startup
+pub fn current() -> &'static Policy {
+    static POLICY: OnceLock<Policy> = OnceLock::new();
+    POLICY.get_or_init(load_policy)
+}
+
+fn merge(target: &mut Value, locked: &Value) {
+    match (target, locked) {
+        (Value::Object(target), Value::Object(locked)) => {
+            for (key, value) in locked {
+                merge(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+        (target, locked) => *target = locked.clone(),
+    }
+}
+
+fn collect_paths(prefix: &str, value: &Value, paths: &mut Vec<String>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                let path = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                collect_paths(&path, value, paths);
+            }
+        }
+        _ => paths.push(prefix.to_string()),
+    }
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+/// Dotted settings keys fixed by policy, e.g. `auto_restart.enabled`
+pub fn locked_keys() -> Vec<String> {
+    let mut paths = Vec::new();
+    collect_paths("", &Value::Object(current().settings.clone()), &mut paths);
+    paths.retain(|p| !p.is_empty());
+    paths
+}
+
+/// Overlay locked policy values on the user's settings
+pub fn apply(settings: &Settings) -> Settings {
+    let policy = current();
+    if policy.settings.is_empty() {
+        return settings.clone();
+    }
+    let Ok(mut value) = serde_json::to_value(settings) else {
+        return settings.clone();
+    };
+    merge(&mut value, &Value::Object(policy.settings.clone()));
+    serde_json::from_value(value).unwrap_or_else(|e| {
+        log_error!(
+            "Deployment policy does not match the settings format: {}",
+            e
+        );
+        settings.clone()
+    })
+}
+
+/// Copy the user's own values for locked keys back into `effective`, giving the
+/// settings to persist so a later policy change does not inherit locked values
+pub fn restore_unlocked(effective: &Settings, user: &Settings) -> Settings {
+    let keys = locked_keys();
+    if keys.is_empty() {
+        return effective.clone();
+    }
+    let (Ok(mut value), Ok(user_value)) =
+        (serde_json::to_value(effective), serde_json::to_value(user))
+    else {
+        return effective.clone();
+    };
+    for key in keys {
+        let pointer = format!("/{}", key.replace('.', "/"));
+        if let (Some(slot), Some(original)) =
+            (value.pointer_mut(&pointer), user_value.pointer(&pointer))
+        {
+            *slot = original.clone();
+        }
+    }
+    serde_json::from_value(value).unwrap_or_else(|_| effective.clone())
+}
+
+/// Reject a settings change that touches any locked key
+pub fn ensure_unlocked(before: &Settings, after: &Settings) -> Result<(), DesktopError> {
+    let (Ok(before), Ok(after)) = (serde_json::to_value(before), serde_json::to_value(after))
+    else {
+        return Ok(());
+    };
+    match locked_keys()
+        .into_iter()
+        .find(|key| lookup(&before, key) != lookup(&after, key))
+    {
+        Some(key) => Err(DesktopError::PolicyLocked(format!(
+            "Setting {} is locked by policy",
+            key
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Fail when policy turns off running `openclaw` subcommands passed in from the UI
+pub fn ensure_passthrough_allowed() -> Result<(), DesktopError> {
+    if current().disable_passthrough_runner {
+        return Err(DesktopError::PolicyLocked(
+            "Running openclaw commands from the app is disabled by policy".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Fail unless `port` is inside the policy's allowed range
+pub fn check_port(port: u16) -> Result<(), DesktopError> {
+    match current().allowed_port_range {
+        Some(range) if port < range.min || port > range.max => {
+            Err(DesktopError::PolicyLocked(format!(
+                "Port {} is outside the range {}-{} allowed by policy",
+                port, range.min, range.max
+            )))
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::net::TcpListener;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+use crate::error::DesktopError;
+use crate::{
+    active_gateway_port, gateway_status, is_port_open, kiosk, policy, settings,
+    start_default_gateway, GatewayStatus, GATEWAY_PORT,
+};
+
+/// How far above the active port to look for a free one
+const PORT_SEARCH_RANGE: u16 = 20;
+
+/// Process lookups spawn tools, so a verdict is reused for the health monitor's ticks
+const OWNER_CACHE_TTL: Duration = Duration::from_secs(30);
+
+static OWNER_CACHE: Mutex<Option<CachedOwner>> = Mutex::new(None);
+
+struct CachedOwner {
+    port: u16,
+    checked_at: Instant,
+    owner: Option<PortOwner>,
+}
+
+/// The process listening on a local port
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortOwner {
+    pub port: u16,
+    pub pid: u32,
+    pub name: Option<String>,
+    pub command_line: Option<String>,
+    /// The process looks like an OpenClaw gateway
+    pub is_gateway: bool,
+}
+
+fn output_text(command: &mut Command) -> Option<String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(crate::CREATE_NO_WINDOW);
+    }
+    let output = command.output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn listening_pid(port: u16) -> Option<u32> {
+    let text = output_text(Command::new("netstat").args(["-ano", "-p", "TCP"]))?;
+    let suffix = format!(":{}", port);
+    text.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            [_, local, _, state, pid] if local.ends_with(&suffix) && *state == "LISTENING" => {
+                pid.parse().ok()
+            }
+            _ => None,
+        }
+    })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn listening_pid(port: u16) -> Option<u32> {
+    let lsof = output_text(Command::new("lsof").args([
+        "-nP",
+        &format!("-iTCP:{}", port),
+        "-sTCP:LISTEN",
+        "-Fp",
+    ]));
+    if let Some(pid) = lsof
+        .as_deref()
+        .and_then(|text| text.lines().find_map(|l| l.strip_prefix('p')?.parse().ok()))
+    {
+        return Some(pid);
+    }
+    // Minimal Linux installs ship `ss` but not `lsof`: users:(("node",pid=123,fd=20))
+    let ss = output_text(Command::new("ss").args(["-ltnpH", &format!("sport = :{}", port)]))?;
+    let start = ss.find("pid=")? + 4;
+    let digits: String = ss[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(target_os = "windows")]
+fn process_details(pid: u32) -> (Option<String>, Option<String>) {
+    let query = format!(
+        "$p = Get-CimInstance Win32_Process -Filter 'ProcessId={}'; $p.Name; $p.CommandLine",
+        pid
+    );
+    let text = output_text(Command::new("powershell").args([
+        "-NoProfile",
+        "-NonInteractive",
+        "-Command",
+        &query,
+    ]));
+    let mut lines = text.as_deref().unwrap_or("").lines().map(str::trim);
+    let name = lines.next().filter(|l| !l.is_empty()).map(str::to_string);
+    let command_line = lines.next().filter(|l| !l.is_empty()).map(str::to_string);
+    (name, command_line)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn process_details(pid: u32) -> (Option<String>, Option<String>) {
+    let field = |format: &str| {
+        output_text(Command::new("ps").args(["-p", &pid.to_string(), "-o", format]))
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+    (field("comm="), field("command="))
+}
+
+/// Identify the process listening on `port`, if the platform tools can tell
+pub fn lookup(port: u16) -> Option<PortOwner> {
+    let pid = listening_pid(port)?;
+    let (name, command_line) = process_details(pid);
+    let is_gateway = command_line
+        .as_deref()
+        .or(name.as_deref())
+        .is_some_and(|text| text.to_ascii_lowercase().contains("openclaw"));
+    Some(PortOwner {
+        port,
+        pid,
+        name,
+        command_line,
+        is_gateway,
+    })
+}
+
+/// A process other than an OpenClaw gateway holding `port`.
+///
+/// Owners that can't be identified get the benefit of the doubt.
+pub fn foreign_owner(port: u16) -> Option<PortOwner> {
+    if let Ok(cache) = OWNER_CACHE.lock() {
+        if let Some(cached) = cache
+            .as_ref()
+            .filter(|c| c.port == port && c.checked_at.elapsed() < OWNER_CACHE_TTL)
+        {
+            return cached.owner.clone();
+        }
+    }
+    let owner = lookup(port).filter(|owner| !owner.is_gateway);
+    if let Ok(mut cache) = OWNER_CACHE.lock() {
+        *cache = Some(CachedOwner {
+            port,
+            checked_at: Instant::now(),
+            owner: owner.clone(),
+        });
+    }
+    owner
+}
+
+/// First port near `active` that is allowed by policy and free to bind
+pub(crate) fn find_free_port(active: u16) -> Option<u16> {
+    // Prefer ports just above the active one, then wrap back to the default
+    (1..=PORT_SEARCH_RANGE)
+        .filter_map(|offset| active.checked_add(offset))
+        .chain(std::iter::once(GATEWAY_PORT))
+        .filter(|port| *port != active && policy::check_port(*port).is_ok())
+        .find(|port| !is_port_open(*port) && TcpListener::bind(("127.0.0.1", *port)).is_ok())
+}
+
+/// Move the default gateway to a free port when another program holds the
+/// current one, then start it there. The dashboard URL follows the new port.
+#[tauri::command]
+pub async fn start_gateway_on_free_port(app: AppHandle) -> Result<GatewayStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    tauri::async_runtime::spawn_blocking(move || start_on_free_port(&app))
+        .await
+        .map_err(|e| format!("Failed to start gateway: {}", e))?
+}
+
+fn start_on_free_port(app: &AppHandle) -> Result<GatewayStatus, DesktopError> {
+    let current = active_gateway_port();
+    if is_port_open(current) && foreign_owner(current).is_none() {
+        return Err(DesktopError::Unavailable(format!(
+            "The gateway is already running on port {}",
+            current
+        )));
+    }
+
+    let port = if is_port_open(current) {
+        find_free_port(current).ok_or("No free port available for the gateway")?
+    } else {
+        current
+    };
+    if port != current {
+        settings::update(|s| s.gateway_port = (port != GATEWAY_PORT).then_some(port))?;
+    }
+    start_default_gateway(app)?;
+    Ok(gateway_status())
+}
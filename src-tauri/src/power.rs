@@ -0,0 +1,267 @@
+//! Battery awareness: pause or stop the local gateway while running on
+//! battery (optionally only once it runs low) and bring it back on AC.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+
+use crate::{
+    connection, is_gateway_running, pause, settings, start_default_gateway, stop_default_gateway,
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryAction {
+    #[default]
+    None,
+    /// Agents stop acting; sessions stay in memory
+    Pause,
+    /// The process exits, saving the most power
+    Stop,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerSettings {
+    pub on_battery: BatteryAction,
+    /// Act only once the charge is at or below this percentage; `None` acts as
+    /// soon as the machine is on battery
+    pub low_battery_percent: Option<u8>,
+    /// Undo the action once back on AC
+    pub resume_on_ac: bool,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            on_battery: BatteryAction::None,
+            low_battery_percent: None,
+            resume_on_ac: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerState {
+    pub source: PowerSource,
+    pub battery_percent: Option<u8>,
+    /// At or below the configured low-battery threshold
+    pub low_battery: bool,
+    /// What the power policy did to the gateway, until it is undone
+    pub throttled: Option<BatteryAction>,
+}
+
+static THROTTLED: Mutex<Option<BatteryAction>> = Mutex::new(None);
+
+#[cfg(target_os = "macos")]
+fn read_power() -> (PowerSource, Option<u8>) {
+    let Ok(output) = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+    else {
+        return (PowerSource::Unknown, None);
+    };
+    // "Now drawing from 'Battery Power'" then "-InternalBattery-0 (id=…)	85%; discharging; …"
+    let text = String::from_utf8_lossy(&output.stdout);
+    let source = if text.contains("'Battery Power'") {
+        PowerSource::Battery
+    } else if text.contains("'AC Power'") {
+        PowerSource::Ac
+    } else {
+        PowerSource::Unknown
+    };
+    let percent = text
+        .split_whitespace()
+        .find_map(|word| word.strip_suffix("%;"))
+        .and_then(|p| p.parse().ok());
+    (source, percent)
+}
+
+#[cfg(target_os = "windows")]
+fn read_power() -> (PowerSource, Option<u8>) {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status = SYSTEM_POWER_STATUS::default();
+    if unsafe { GetSystemPowerStatus(&mut status) }.is_err() {
+        return (PowerSource::Unknown, None);
+    }
+    let source = match status.ACLineStatus {
+        0 => PowerSource::Battery,
+        1 => PowerSource::Ac,
+        _ => PowerSource::Unknown,
+    };
+    // 255 means unknown, e.g. no battery
+    let percent = (status.BatteryLifePercent <= 100).then_some(status.BatteryLifePercent);
+    (source, percent)
+}
+
+#[cfg(target_os = "linux")]
+fn read_power() -> (PowerSource, Option<u8>) {
+    let read = |path: std::path::PathBuf| {
+        std::fs::read_to_string(path)
+            .map(|s| s.trim().to_string())
+            .ok()
+    };
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return (PowerSource::Unknown, None);
+    };
+    let (mut mains_online, mut discharging, mut percent) = (None, false, None);
+    for supply in supplies.flatten() {
+        let dir = supply.path();
+        match read(dir.join("type")).as_deref() {
+            Some("Mains") => {
+                let online = read(dir.join("online")).as_deref() == Some("1");
+                mains_online = Some(mains_online.unwrap_or(false) || online);
+            }
+            Some("Battery") => {
+                discharging |= read(dir.join("status")).as_deref() == Some("Discharging");
+                percent = percent.or_else(|| read(dir.join("capacity"))?.parse().ok());
+            }
+            _ => {}
+        }
+    }
+    let source = match (mains_online, percent) {
+        (Some(true), _) => PowerSource::Ac,
+        (Some(false), _) => PowerSource::Battery,
+        // No mains entry: go by the battery, and a machine without one is on AC
+        (None, Some(_)) if discharging => PowerSource::Battery,
+        (None, _) => PowerSource::Ac,
+    };
+    (source, percent)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+fn read_power() -> (PowerSource, Option<u8>) {
+    (PowerSource::Unknown, None)
+}
+
+fn throttled() -> Option<BatteryAction> {
+    THROTTLED.lock().ok().and_then(|t| *t)
+}
+
+fn set_throttled(action: Option<BatteryAction>) {
+    if let Ok(mut throttled) = THROTTLED.lock() {
+        *throttled = action;
+    }
+}
+
+/// Current power source and charge; blocks briefly on macOS
+pub(crate) fn state() -> PowerState {
+    let (source, battery_percent) = read_power();
+    let threshold = settings::current().power.low_battery_percent;
+    PowerState {
+        source,
+        battery_percent,
+        low_battery: matches!((battery_percent, threshold), (Some(p), Some(t)) if p <= t),
+        throttled: throttled(),
+    }
+}
+
+fn should_throttle(config: &PowerSettings, state: &PowerState) -> bool {
+    state.source == PowerSource::Battery
+        && (config.low_battery_percent.is_none() || state.low_battery)
+}
+
+async fn throttle<R: Runtime>(app: &AppHandle<R>, action: BatteryAction) {
+    let running = tauri::async_runtime::spawn_blocking(is_gateway_running)
+        .await
+        .unwrap_or(false);
+    if !running {
+        return;
+    }
+    let result = match action {
+        BatteryAction::None => return,
+        // A pause the user made themselves is theirs to undo
+        BatteryAction::Pause if pause::is_paused() => return,
+        BatteryAction::Pause => pause::set_paused(app, true)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        BatteryAction::Stop => {
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || stop_default_gateway(&app))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r.map(|_| ()))
+        }
+    };
+    match result {
+        Ok(()) => set_throttled(Some(action)),
+        Err(e) => log_error!("Failed to throttle gateway on battery: {}", e),
+    }
+}
+
+async fn restore<R: Runtime>(app: &AppHandle<R>, action: BatteryAction) {
+    set_throttled(None);
+    let result = match action {
+        BatteryAction::None => return,
+        BatteryAction::Pause if !pause::is_paused() => return,
+        BatteryAction::Pause => pause::set_paused(app, false)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        BatteryAction::Stop => {
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || start_default_gateway(&app))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r.map(|_| ()))
+        }
+    };
+    if let Err(e) = result {
+        log_error!("Failed to bring gateway back on AC: {}", e);
+    }
+}
+
+/// Apply the battery policy to the local gateway as the power source changes
+pub fn spawn_power_monitor<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let config = settings::current().power;
+            if connection::is_remote()
+                || (config.on_battery == BatteryAction::None && throttled().is_none())
+            {
+                continue;
+            }
+            let Ok(state) = tauri::async_runtime::spawn_blocking(state).await else {
+                continue;
+            };
+            match throttled() {
+                None if should_throttle(&config, &state) => throttle(&app, config.on_battery).await,
+                Some(action)
+                    if state.source == PowerSource::Ac
+                        && (config.resume_on_ac || config.on_battery == BatteryAction::None) =>
+                {
+                    restore(&app, action).await
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// Power source, charge, and whether the battery policy is holding the gateway
+#[tauri::command]
+pub async fn get_power_state() -> PowerState {
+    tauri::async_runtime::spawn_blocking(state)
+        .await
+        .unwrap_or_else(|_| PowerState {
+            source: PowerSource::Unknown,
+            battery_percent: None,
+            low_battery: false,
+            throttled: throttled(),
+        })
+}
@@ -0,0 +1,160 @@
+//! Checks run before spawning the gateway, so a start that can't succeed fails
+//! with a reason instead of a crashed process.
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::capabilities::parse_version;
+use crate::config::{self, IssueSeverity};
+use crate::disk_space::{self, format_bytes};
+use crate::error::DesktopError;
+use crate::{
+    active_gateway_port, connection, detect_openclaw_version, gateway_log_paths, is_port_open,
+    policy, port_owner, read_gateway_token,
+};
+
+/// Oldest CLI released under the `openclaw` name
+const MIN_OPENCLAW_VERSION: (u64, u64, u64) = (2026, 1, 0);
+/// Headroom for a day of logs before rotation catches up
+const LOG_SPACE_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum EnvironmentCheckId {
+    Config,
+    Token,
+    Port,
+    DiskSpace,
+    Version,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct EnvironmentCheck {
+    pub id: EnvironmentCheckId,
+    pub passed: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentValidation {
+    /// Every check passed
+    pub ok: bool,
+    pub checks: Vec<EnvironmentCheck>,
+}
+
+impl EnvironmentValidation {
+    pub(crate) fn failures(&self) -> Vec<EnvironmentCheck> {
+        self.checks.iter().filter(|c| !c.passed).cloned().collect()
+    }
+
+    /// The failed checks' messages, for a one-line error
+    pub(crate) fn summary(&self) -> String {
+        self.checks
+            .iter()
+            .filter(|c| !c.passed)
+            .map(|c| c.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
+
+fn check(id: EnvironmentCheckId, result: Result<String, String>) -> EnvironmentCheck {
+    let (passed, message) = match result {
+        Ok(message) => (true, message),
+        Err(message) => (false, message),
+    };
+    EnvironmentCheck {
+        id,
+        passed,
+        message,
+    }
+}
+
+pub(crate) fn check_config() -> Result<String, String> {
+    let config = config::load_resolved_config()?;
+    let validation = config::validate(&config);
+    match validation
+        .issues
+        .iter()
+        .find(|issue| issue.severity == IssueSeverity::Error)
+    {
+        Some(issue) if issue.path.is_empty() => Err(issue.message.clone()),
+        Some(issue) => Err(format!("{}: {}", issue.path, issue.message)),
+        None => Ok("Config is valid".to_string()),
+    }
+}
+
+fn check_token() -> Result<String, String> {
+    match read_gateway_token() {
+        Some(_) => Ok("Gateway token is set".to_string()),
+        None => Err("No gateway token is configured".to_string()),
+    }
+}
+
+pub(crate) fn check_port() -> Result<String, String> {
+    let port = active_gateway_port();
+    policy::check_port(port)?;
+    if !is_port_open(port) {
+        return Ok(format!("Port {} is free", port));
+    }
+    match port_owner::foreign_owner(port) {
+        Some(owner) => Err(format!(
+            "Port {} is in use by {} (pid {})",
+            port,
+            owner.name.as_deref().unwrap_or("another program"),
+            owner.pid
+        )),
+        None => Ok(format!("Port {} is held by the gateway", port)),
+    }
+}
+
+fn check_disk_space() -> Result<String, String> {
+    let (log_path, _) = gateway_log_paths()?;
+    let dir = log_path.parent().unwrap_or(&log_path);
+    let space = disk_space::preflight(dir, LOG_SPACE_BYTES, 1)?;
+    Ok(format!(
+        "{} free for logs",
+        format_bytes(space.available_bytes)
+    ))
+}
+
+fn check_version() -> Result<String, String> {
+    let installed = detect_openclaw_version().ok_or("Could not run `openclaw --version`")?;
+    let (major, minor, patch) = MIN_OPENCLAW_VERSION;
+    match parse_version(&installed) {
+        Some(version) if version >= MIN_OPENCLAW_VERSION => Ok(format!("OpenClaw {}", installed)),
+        Some(_) => Err(format!(
+            "OpenClaw {} is too old; {}.{}.{} or later is required",
+            installed, major, minor, patch
+        )),
+        None => Err(format!("Unrecognized OpenClaw version: {}", installed)),
+    }
+}
+
+/// Run every check; blocking, as the version check runs the CLI
+pub(crate) fn validate() -> EnvironmentValidation {
+    let checks = vec![
+        check(EnvironmentCheckId::Config, check_config()),
+        check(EnvironmentCheckId::Token, check_token()),
+        check(EnvironmentCheckId::Port, check_port()),
+        check(EnvironmentCheckId::DiskSpace, check_disk_space()),
+        check(EnvironmentCheckId::Version, check_version()),
+    ];
+    EnvironmentValidation {
+        ok: checks.iter().all(|c| c.passed),
+        checks,
+    }
+}
+
+/// Check that the gateway can start here: config parses, a token is set, the
+/// port is free, logs have room, and the CLI is recent enough
+#[tauri::command]
+pub async fn validate_gateway_environment() -> Result<EnvironmentValidation, DesktopError> {
+    connection::ensure_local()?;
+    let validation = tauri::async_runtime::spawn_blocking(validate)
+        .await
+        .map_err(|e| format!("Failed to validate environment: {}", e))?;
+    Ok(validation)
+}
@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use crate::error::DesktopError;
+use crate::paths;
+
+/// Node.js version OpenClaw requires
+const MIN_NODE: (u64, u64, u64) = (22, 0, 0);
+
+/// A global-install capable package manager, in order of preference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageManager {
+    Npm,
+    Pnpm,
+    Bun,
+}
+
+impl PackageManager {
+    const ALL: [PackageManager; 3] = [Self::Npm, Self::Pnpm, Self::Bun];
+
+    pub fn program(self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Pnpm => "pnpm",
+            Self::Bun => "bun",
+        }
+    }
+
+    /// Arguments that install a package globally
+    pub fn global_install_args(self, spec: &str) -> Vec<String> {
+        let verb = match self {
+            Self::Npm => "install",
+            Self::Pnpm | Self::Bun => "add",
+        };
+        vec![verb.to_string(), "-g".to_string(), spec.to_string()]
+    }
+
+    fn minimum(self) -> (u64, u64, u64) {
+        match self {
+            Self::Npm => (9, 0, 0),
+            Self::Pnpm => (8, 0, 0),
+            Self::Bun => (1, 0, 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeTool {
+    pub name: String,
+    pub version: Option<String>,
+    pub minimum: String,
+    /// Installed and at least the minimum version
+    pub ok: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimePrerequisites {
+    pub node: RuntimeTool,
+    pub package_managers: Vec<RuntimeTool>,
+    /// What `install_openclaw` will use, if anything qualifies
+    pub package_manager: Option<PackageManager>,
+    /// How to install what is missing on this platform
+    pub suggestions: Vec<String>,
+}
+
+/// Run a tool found on PATH, `.cmd` shims included, without a console window
+pub(crate) fn tool_command(program: &str) -> Command {
+    let command = match paths::find_executable(program) {
+        Some(path) => paths::program_command(&path),
+        None => Command::new(program),
+    };
+    #[cfg(target_os = "windows")]
+    let command = {
+        use std::os::windows::process::CommandExt;
+        let mut command = command;
+        command.creation_flags(crate::CREATE_NO_WINDOW);
+        command
+    };
+    command
+}
+
+/// `<program> --version`, or `None` when it is missing or fails
+pub(crate) fn tool_version(program: &str) -> Option<String> {
+    let output = tool_command(program).arg("--version").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!version.is_empty()).then_some(version)
+}
+
+/// `v22.3.1` -> `(22, 3, 1)`; missing parts count as zero
+fn parse_version(text: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = text
+        .trim()
+        .trim_start_matches('v')
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|part| part.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().and_then(Result::ok).unwrap_or(0);
+    let patch = parts.next().and_then(Result::ok).unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+fn check_tool(name: &str, minimum: (u64, u64, u64)) -> RuntimeTool {
+    let version = tool_version(name);
+    let ok = version
+        .as_deref()
+        .and_then(parse_version)
+        .is_some_and(|v| v >= minimum);
+    RuntimeTool {
+        name: name.to_string(),
+        version,
+        minimum: format!("{}.{}.{}", minimum.0, minimum.1, minimum.2),
+        ok,
+    }
+}
+
+fn node_suggestion() -> String {
+    if cfg!(target_os = "macos") {
+        "Install Node.js 22 or later with `brew install node` or from https://nodejs.org"
+            .to_string()
+    } else if cfg!(target_os = "windows") {
+        "Install Node.js 22 or later with `winget install OpenJS.NodeJS.LTS` or from https://nodejs.org".to_string()
+    } else {
+        "Install Node.js 22 or later with your distribution's package manager, nvm, or from https://nodejs.org".to_string()
+    }
+}
+
+/// Node.js as found on PATH, checked against the version OpenClaw requires
+pub(crate) fn node() -> RuntimeTool {
+    check_tool("node", MIN_NODE)
+}
+
+pub(crate) fn detect() -> RuntimePrerequisites {
+    let node = node();
+    let package_managers: Vec<RuntimeTool> = PackageManager::ALL
+        .iter()
+        .map(|pm| check_tool(pm.program(), pm.minimum()))
+        .collect();
+    // Every manager installs a Node program, so none qualifies without Node itself
+    let package_manager = node
+        .ok
+        .then(|| {
+            PackageManager::ALL
+                .iter()
+                .zip(&package_managers)
+                .find(|(_, tool)| tool.ok)
+                .map(|(pm, _)| *pm)
+        })
+        .flatten();
+
+    let mut suggestions = Vec::new();
+    match &node.version {
+        None => suggestions.push(node_suggestion()),
+        Some(version) if !node.ok => suggestions.push(format!(
+            "Node.js {} is too old; {}",
+            version,
+            node_suggestion()
+        )),
+        Some(_) => {}
+    }
+    if node.ok && package_manager.is_none() {
+        suggestions.push(
+            "No usable package manager found; npm ships with Node.js, so reinstalling Node.js restores it"
+                .to_string(),
+        );
+    }
+
+    RuntimePrerequisites {
+        node,
+        package_managers,
+        package_manager,
+        suggestions,
+    }
+}
+
+/// Report Node.js and package manager availability for installing OpenClaw
+#[tauri::command]
+pub async fn get_runtime_prerequisites() -> Result<RuntimePrerequisites, DesktopError> {
+    Ok(tauri::async_runtime::spawn_blocking(detect)
+        .await
+        .map_err(|e| format!("Failed to check prerequisites: {}", e))?)
+}
@@ -0,0 +1,118 @@
+//! Gateway state and active agent sessions outside the window: the dock badge
+//! on macOS, the launcher badge on Linux, the taskbar overlay on Windows, and
+//! the tray tooltip everywhere.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use crate::gateway_api::GatewayApi;
+use crate::i18n::{t, t_with};
+use crate::tray::TRAY_ID;
+use crate::window::MAIN_WINDOW;
+
+/// Sessions come from the gateway API, so they are fetched less often than the
+/// health monitor ticks
+const SESSION_REFRESH: Duration = Duration::from_secs(15);
+
+struct Presence {
+    /// What the badge and tooltip show now
+    shown: Option<(bool, usize)>,
+    active_sessions: usize,
+    fetched_at: Option<Instant>,
+}
+
+static PRESENCE: Mutex<Presence> = Mutex::new(Presence {
+    shown: None,
+    active_sessions: 0,
+    fetched_at: None,
+});
+
+/// Sessions an agent is working on right now, or the last count while it is fresh
+async fn active_sessions<R: Runtime>(app: &AppHandle<R>) -> usize {
+    if let Ok(presence) = PRESENCE.lock() {
+        if presence
+            .fetched_at
+            .is_some_and(|at| at.elapsed() < SESSION_REFRESH)
+        {
+            return presence.active_sessions;
+        }
+    }
+    let count = match app.state::<GatewayApi>().list_sessions().await {
+        Ok(sessions) => sessions
+            .iter()
+            .filter(|s| matches!(s.status.as_deref(), Some("running" | "active")))
+            .count(),
+        Err(e) => {
+            log_error!("Failed to count active sessions: {}", e);
+            0
+        }
+    };
+    if let Ok(mut presence) = PRESENCE.lock() {
+        presence.active_sessions = count;
+        presence.fetched_at = Some(Instant::now());
+    }
+    count
+}
+
+fn tooltip(running: bool, active: usize) -> String {
+    match (running, active) {
+        (false, _) => t("presence.stopped"),
+        (true, 0) => t("presence.running"),
+        (true, count) => t_with("presence.running_sessions", &[("count", &count)]),
+    }
+}
+
+fn set_badge<R: Runtime>(
+    window: &WebviewWindow<R>,
+    running: bool,
+    active: usize,
+) -> tauri::Result<()> {
+    #[cfg(target_os = "macos")]
+    window.set_badge_label((running && active > 0).then(|| active.to_string()))?;
+    #[cfg(target_os = "linux")]
+    window.set_badge_count((running && active > 0).then_some(active as i64))?;
+    #[cfg(target_os = "windows")]
+    {
+        // An overlay is too small for a count; the tooltip carries it
+        let _ = active;
+        let color = if running {
+            crate::tray::RUNNING_GREEN
+        } else {
+            crate::tray::STOPPED_GRAY
+        };
+        window.set_overlay_icon(Some(crate::tray::status_dot(color)))?;
+    }
+    Ok(())
+}
+
+/// Refresh the badge and tooltip for the latest health check. The OS is only
+/// touched when what they show changes.
+pub(crate) async fn update<R: Runtime>(app: &AppHandle<R>, running: bool) {
+    let active = if running {
+        active_sessions(app).await
+    } else {
+        0
+    };
+    let window = app.get_webview_window(MAIN_WINDOW);
+    if let Ok(mut presence) = PRESENCE.lock() {
+        if presence.shown == Some((running, active)) {
+            return;
+        }
+        // In tray-only mode the window comes later; keep retrying until it has its badge
+        if window.is_some() {
+            presence.shown = Some((running, active));
+        }
+    }
+
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        if let Err(e) = tray.set_tooltip(Some(tooltip(running, active))) {
+            log_error!("Failed to set tray tooltip: {}", e);
+        }
+    }
+    if let Some(window) = window {
+        if let Err(e) = set_badge(&window, running, active) {
+            log_error!("Failed to set app badge: {}", e);
+        }
+    }
+}
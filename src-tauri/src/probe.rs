@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::path::Path;
+use std::time::{Duration, Instant};
+use ts_rs::TS;
+
+use crate::connection::{self, GatewayConnection};
+use crate::{active_gateway_port, port_owner, read_gateway_token, settings, socket_transport};
+
+const HEALTH_PATH: &str = "/api/health";
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(500);
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+/// Health bodies are tiny; anything larger isn't a gateway
+const MAX_RESPONSE_BYTES: u64 = 64 * 1024;
+
+/// What answered on the gateway port
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct GatewayProbeResult {
+    pub port: u16,
+    /// Something accepted a connection on the port
+    pub reachable: bool,
+    /// The health endpoint answered like an OpenClaw gateway
+    pub verified: bool,
+    pub version: Option<String>,
+    pub instance_id: Option<String>,
+    pub http_status: Option<u16>,
+    #[ts(type = "number | null")]
+    pub latency_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HealthBody {
+    status: String,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default, alias = "instanceId")]
+    instance_id: Option<String>,
+}
+
+/// Plain HTTP/1.0 GET so the probe works from sync code and inside the async runtime alike
+fn http_get(port: u16, path: &str) -> Result<(u16, String), String> {
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let stream = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+        .map_err(|e| format!("Failed to connect to port {}: {}", port, e))?;
+    stream.set_read_timeout(Some(READ_TIMEOUT)).ok();
+    stream.set_write_timeout(Some(READ_TIMEOUT)).ok();
+    http_exchange(stream, &format!("127.0.0.1:{}", port), path)
+}
+
+/// The same GET over the gateway's unix socket or named pipe
+fn socket_get(socket: &Path, path: &str) -> Result<(u16, String), String> {
+    let stream = socket_transport::connect_blocking(socket, READ_TIMEOUT)?;
+    http_exchange(stream, "localhost", path)
+}
+
+fn http_exchange(
+    mut stream: impl Read + Write,
+    host: &str,
+    path: &str,
+) -> Result<(u16, String), String> {
+    let auth = read_gateway_token()
+        .map(|token| format!("Authorization: Bearer {}\r\n", token))
+        .unwrap_or_default();
+    let request = format!(
+        "GET {} HTTP/1.0\r\nHost: {}\r\nAccept: application/json\r\n{}Connection: close\r\n\r\n",
+        path, host, auth
+    );
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| format!("Failed to send health request: {}", e))?;
+
+    let mut raw = Vec::new();
+    stream
+        .take(MAX_RESPONSE_BYTES)
+        .read_to_end(&mut raw)
+        .map_err(|e| format!("Failed to read health response: {}", e))?;
+    let text = String::from_utf8_lossy(&raw);
+
+    let (head, body) = text
+        .split_once("\r\n\r\n")
+        .ok_or("Response on the gateway port is not HTTP")?;
+    let status = head
+        .lines()
+        .next()
+        .filter(|line| line.starts_with("HTTP/"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or("Response on the gateway port is not HTTP")?;
+    Ok((status, body.to_string()))
+}
+
+/// GET through reqwest, for remote gateways and HTTPS, with the configured certificate trust.
+///
+/// The request runs on its own thread and runtime so the probe stays callable
+/// from sync code that may itself be running on the async runtime.
+fn client_get(url: String) -> Result<(u16, String), String> {
+    let token = read_gateway_token();
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| format!("Failed to create probe runtime: {}", e))?;
+        runtime.block_on(async move {
+            let client = connection::apply_tls(
+                reqwest::Client::builder()
+                    .connect_timeout(CONNECT_TIMEOUT * 4)
+                    .timeout(READ_TIMEOUT * 2),
+            )?
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+            let mut request = client.get(&url);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            let response = request.send().await.map_err(|e| {
+                if e.is_connect() {
+                    format!("Failed to connect to {}: {}", url, e)
+                } else {
+                    format!("Failed to read health response: {}", e)
+                }
+            })?;
+            let status = response.status().as_u16();
+            let body = response
+                .text()
+                .await
+                .map_err(|e| format!("Failed to read health response: {}", e))?;
+            Ok((status, body))
+        })
+    })
+    .join()
+    .map_err(|_| "Health probe thread panicked".to_string())?
+}
+
+/// Check that the health response came from a gateway
+fn evaluate(
+    port: u16,
+    remote: bool,
+    started: Instant,
+    response: Result<(u16, String), String>,
+) -> GatewayProbeResult {
+    let mut result = GatewayProbeResult {
+        port,
+        ..Default::default()
+    };
+    result.latency_ms = Some(started.elapsed().as_millis() as u64);
+
+    // Port 0 stands for the gateway socket
+    let target = match port {
+        0 => "The gateway socket".to_string(),
+        port => format!("Port {}", port),
+    };
+    let (status, body) = match response {
+        Ok(response) => response,
+        Err(e) => {
+            // A refused connection is simply "not running"; anything else means something answered
+            result.reachable = !e.starts_with("Failed to connect");
+            result.latency_ms = None;
+            result.error = Some(e);
+            return result;
+        }
+    };
+    result.reachable = true;
+    result.http_status = Some(status);
+
+    match status {
+        200..=299 | 503 => match serde_json::from_str::<HealthBody>(body.trim()) {
+            Ok(health) => {
+                result.verified = true;
+                result.version = health.version;
+                result.instance_id = health.instance_id;
+                if status == 503 {
+                    result.error = Some(format!("Gateway reports {}", health.status));
+                }
+            }
+            Err(_) => {
+                result.error = Some(format!(
+                    "{} answered, but not with an OpenClaw health response",
+                    target
+                ));
+            }
+        },
+        404 if remote => {
+            result.error = Some("Remote gateway has no health endpoint".to_string());
+        }
+        // Nothing else listens on the app's own socket
+        404 if connection::socket_path().is_some() => result.verified = true,
+        // Older gateways have no health endpoint; trust the process name instead
+        404 => {
+            result.verified = port_owner::foreign_owner(port).is_none();
+            if !result.verified {
+                result.error = Some(format!("Port {} is held by another program", port));
+            }
+        }
+        401 | 403 => {
+            result.error = Some("Gateway rejected the configured token".to_string());
+        }
+        _ => {
+            result.error = Some(format!("{} returned HTTP {}", target, status));
+        }
+    }
+    result
+}
+
+/// Probe the gateway the app is connected to, local or remote
+pub fn probe_gateway() -> GatewayProbeResult {
+    let (port, remote) = match connection::current() {
+        GatewayConnection::Local => (active_gateway_port(), false),
+        GatewayConnection::Remote { port, .. } => (port, true),
+        GatewayConnection::Socket { .. } => {
+            let started = Instant::now();
+            let response = connection::socket_path()
+                .ok_or_else(|| "Failed to connect: no gateway socket path".to_string())
+                .and_then(|socket| socket_get(&socket, HEALTH_PATH));
+            // No TCP port is involved
+            return evaluate(0, false, started, response);
+        }
+    };
+    let started = Instant::now();
+    let response = if remote || settings::current().gateway_tls.local_https {
+        client_get(format!("{}{}", connection::base_url(), HEALTH_PATH))
+    } else {
+        http_get(port, HEALTH_PATH)
+    };
+    evaluate(port, remote, started, response)
+}
@@ -0,0 +1,121 @@
+use std::io::Read;
+use std::process::{Command, Output, Stdio};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::gateways::kill_process_tree;
+
+/// Most CLI calls answer in well under a second; this only catches a hung CLI
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+/// `openclaw doctor` probes the network and can legitimately take a while
+pub(crate) const DOCTOR_TIMEOUT: Duration = Duration::from_secs(120);
+pub(crate) const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+/// Package installs download and build; only a stuck one runs past this
+pub(crate) const INSTALL_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Shared by every child process the app waits on, cancelled when the app exits
+fn token() -> &'static CancellationToken {
+    static TOKEN: OnceLock<CancellationToken> = OnceLock::new();
+    TOKEN.get_or_init(CancellationToken::new)
+}
+
+/// Kill every process still being waited on and refuse to start new ones
+pub(crate) fn cancel_all() {
+    token().cancel();
+}
+
+fn label(cmd: &Command) -> String {
+    let mut parts = vec![cmd.get_program().to_string_lossy().into_owned()];
+    parts.extend(cmd.get_args().map(|a| a.to_string_lossy().into_owned()));
+    parts.join(" ")
+}
+
+/// Run a command to completion without blocking the async runtime, killing it on
+/// timeout or when the app shuts down
+pub(crate) async fn output(cmd: Command, timeout: Duration) -> Result<Output, String> {
+    let label = label(&cmd);
+    if token().is_cancelled() {
+        return Err(format!("Not running {}: the app is shutting down", label));
+    }
+    let mut cmd = tokio::process::Command::from(cmd);
+    cmd.stdin(Stdio::null()).kill_on_drop(true);
+
+    tokio::select! {
+        result = cmd.output() => result.map_err(|e| format!("Failed to run {}: {}", label, e)),
+        _ = tokio::time::sleep(timeout) => Err(format!(
+            "{} did not finish within {}s",
+            label,
+            timeout.as_secs()
+        )),
+        _ = token().cancelled() => Err(format!("{} was cancelled", label)),
+    }
+}
+
+/// Blocking counterpart of [`output`] for code already on a blocking thread.
+/// Polls the child rather than entering the runtime, so it is safe anywhere.
+pub(crate) fn output_blocking(mut cmd: Command, timeout: Duration) -> Result<Output, String> {
+    let label = label(&cmd);
+    if token().is_cancelled() {
+        return Err(format!("Not running {}: the app is shutting down", label));
+    }
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", label, e))?;
+
+    // Drain both pipes so a chatty child can't fill one and stall
+    let read_all = |pipe: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = read_all(
+        child
+            .stdout
+            .take()
+            .map(|p| Box::new(p) as Box<dyn Read + Send>),
+    );
+    let stderr = read_all(
+        child
+            .stderr
+            .take()
+            .map(|p| Box::new(p) as Box<dyn Read + Send>),
+    );
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) => {}
+            Err(e) => return Err(format!("Failed to wait for {}: {}", label, e)),
+        }
+        let error = if token().is_cancelled() {
+            format!("{} was cancelled", label)
+        } else if Instant::now() >= deadline {
+            format!("{} did not finish within {}s", label, timeout.as_secs())
+        } else {
+            thread::sleep(POLL_INTERVAL);
+            continue;
+        };
+        if let Err(e) = kill_process_tree(&mut child) {
+            log_error!("{}", e);
+        }
+        return Err(error);
+    };
+
+    Ok(Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
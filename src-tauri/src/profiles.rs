@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config;
+use crate::error::DesktopError;
+use crate::kiosk;
+use crate::openclaw_home_dir;
+use crate::paths;
+use crate::settings;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub path: String,
+    pub active: bool,
+    pub has_config: bool,
+}
+
+fn profiles_dir() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join("profiles"))
+}
+
+pub(crate) fn validate_profile_name(name: &str) -> Result<(), String> {
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Invalid profile name '{}': use letters, numbers, '-' or '_'",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// The profile passed to every spawned `openclaw` invocation.
+///
+/// A selection persisted in settings wins over the `OPENCLAW_PROFILE` environment variable.
+pub fn active_profile() -> Option<String> {
+    settings::current()
+        .active_profile
+        .or_else(|| std::env::var("OPENCLAW_PROFILE").ok())
+        .filter(|p| !p.trim().is_empty())
+}
+
+/// List profile directories under `~/.openclaw/profiles`
+#[tauri::command]
+pub fn list_profiles() -> Result<Vec<ProfileInfo>, DesktopError> {
+    let dir = profiles_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let active = active_profile();
+    let entries =
+        fs::read_dir(&dir).map_err(|e| format!("Failed to read profiles directory: {}", e))?;
+
+    let mut profiles: Vec<ProfileInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let path = entry.path();
+            Some(ProfileInfo {
+                active: active.as_deref() == Some(name.as_str()),
+                has_config: config::find_config(&path).is_some(),
+                path: path.display().to_string(),
+                name,
+            })
+        })
+        .collect();
+
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
+
+/// Get the currently active profile name, if any
+#[tauri::command]
+pub fn get_active_profile() -> Option<String> {
+    active_profile()
+}
+
+/// Persist the active profile; `None` returns to the default profile
+#[tauri::command]
+pub fn set_active_profile(name: Option<String>) -> Result<Option<String>, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if let Some(name) = &name {
+        validate_profile_name(name)?;
+        if !paths::join_within(&profiles_dir()?, name)?.is_dir() {
+            return Err(DesktopError::NotFound(format!(
+                "Profile '{}' does not exist",
+                name
+            )));
+        }
+    }
+
+    settings::update(|s| s.active_profile = name.clone())?;
+    Ok(name)
+}
+
+/// Create a new profile directory
+#[tauri::command]
+pub fn create_profile(name: String) -> Result<ProfileInfo, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    validate_profile_name(&name)?;
+
+    let path = paths::join_within(&profiles_dir()?, &name)?;
+    if path.exists() {
+        return Err(DesktopError::InvalidInput(format!(
+            "Profile '{}' already exists",
+            name
+        )));
+    }
+    fs::create_dir_all(&path).map_err(|e| format!("Failed to create profile: {}", e))?;
+
+    Ok(ProfileInfo {
+        active: active_profile().as_deref() == Some(name.as_str()),
+        has_config: false,
+        path: path.display().to_string(),
+        name,
+    })
+}
@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::disk_space;
+use crate::error::DesktopError;
+use crate::kiosk;
+use crate::{connection, openclaw_home_dir, read_gateway_token};
+
+/// `~/.openclaw/profiles` already holds OpenClaw config profiles, so captures get their own folder
+const CAPTURES_DIR: &str = "captures";
+const MAX_CAPTURE_SECS: u64 = 300;
+/// Headroom on top of the sampling window for the gateway to serialize the profile
+const CAPTURE_GRACE: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProfileKind {
+    Heap,
+    Cpu,
+}
+
+impl ProfileKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ProfileKind::Heap => "heap",
+            ProfileKind::Cpu => "cpu",
+        }
+    }
+
+    /// Extensions Chrome DevTools recognizes when loading the file
+    fn extension(self) -> &'static str {
+        match self {
+            ProfileKind::Heap => "heapsnapshot",
+            ProfileKind::Cpu => "cpuprofile",
+        }
+    }
+
+    /// Typical capture size, checked against free space before requesting one
+    fn estimated_bytes(self) -> u64 {
+        match self {
+            ProfileKind::Heap => 256 * 1024 * 1024,
+            ProfileKind::Cpu => 16 * 1024 * 1024,
+        }
+    }
+
+    fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "heapsnapshot" => Some(ProfileKind::Heap),
+            "cpuprofile" => Some(ProfileKind::Cpu),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileCapture {
+    pub name: String,
+    pub path: String,
+    pub kind: ProfileKind,
+    pub size: u64,
+    pub created_at: Option<u64>,
+}
+
+fn captures_dir() -> Result<PathBuf, String> {
+    let dir = openclaw_home_dir()?.join(CAPTURES_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create captures directory: {}", e))?;
+    Ok(dir)
+}
+
+fn capture_info(path: &Path) -> Option<ProfileCapture> {
+    let kind = ProfileKind::from_extension(path.extension()?.to_str()?)?;
+    let metadata = fs::metadata(path).ok()?;
+    Some(ProfileCapture {
+        name: path.file_name()?.to_string_lossy().to_string(),
+        path: path.display().to_string(),
+        kind,
+        size: metadata.len(),
+        created_at: metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs()),
+    })
+}
+
+/// Ask the gateway's debug API for a heap snapshot or a CPU profile sampled over
+/// `seconds`, and save it under `~/.openclaw/captures/`
+#[tauri::command]
+pub async fn capture_gateway_profile(
+    kind: ProfileKind,
+    seconds: Option<u64>,
+) -> Result<ProfileCapture, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if !crate::is_gateway_running() {
+        return Err(DesktopError::GatewayNotRunning(
+            "Gateway is not running".to_string(),
+        ));
+    }
+    let seconds = seconds.unwrap_or(10).clamp(1, MAX_CAPTURE_SECS);
+    disk_space::preflight(&captures_dir()?, kind.estimated_bytes(), 1)?;
+
+    let client = connection::apply_tls(
+        reqwest::Client::builder().timeout(Duration::from_secs(seconds) + CAPTURE_GRACE),
+    )?
+    .build()
+    .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let mut request = client
+        .post(format!("{}/api/debug/profile", connection::base_url()))
+        .query(&[("kind", kind.as_str()), ("seconds", &seconds.to_string())]);
+    if let Some(token) = read_gateway_token() {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to request profile: {}", e))?;
+    match response.status() {
+        status if status.is_success() => {}
+        reqwest::StatusCode::NOT_FOUND => {
+            return Err(DesktopError::Unavailable(
+                "This gateway does not expose a profiling API".to_string(),
+            ))
+        }
+        status => return Err(format!("Profile capture failed: HTTP {}", status).into()),
+    }
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download profile: {}", e))?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let path = captures_dir()?.join(format!(
+        "{}-{}.{}",
+        kind.as_str(),
+        timestamp,
+        kind.extension()
+    ));
+    disk_space::preflight(&path, body.len() as u64, 1)?;
+    fs::write(&path, &body).map_err(|e| format!("Failed to save profile: {}", e))?;
+
+    Ok(capture_info(&path).ok_or("Failed to read saved profile")?)
+}
+
+/// List saved profile captures, newest first
+#[tauri::command]
+pub fn list_gateway_profiles() -> Result<Vec<ProfileCapture>, DesktopError> {
+    let entries = fs::read_dir(captures_dir()?)
+        .map_err(|e| format!("Failed to read captures directory: {}", e))?;
+    let mut captures: Vec<ProfileCapture> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| capture_info(&e.path()))
+        .collect();
+    captures.sort_by_key(|capture| Reverse(capture.created_at));
+    Ok(captures)
+}
@@ -0,0 +1,383 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::process::Command;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::capabilities::{self, GatewayFeature};
+use crate::error::DesktopError;
+use crate::events::CONFIG_CHANGED_EVENT;
+use crate::{config, connection, kiosk, platform, proxy, quota};
+use crate::{is_gateway_running, restart_default_gateway};
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Model providers the app knows how to test, with the variable OpenClaw reads their key from
+struct KnownProvider {
+    id: &'static str,
+    name: &'static str,
+    env_var: &'static str,
+    /// Cheap authenticated endpoint that lists models without using any tokens
+    test_url: &'static str,
+}
+
+const KNOWN_PROVIDERS: &[KnownProvider] = &[
+    KnownProvider {
+        id: "anthropic",
+        name: "Anthropic",
+        env_var: "ANTHROPIC_API_KEY",
+        test_url: "https://api.anthropic.com/v1/models",
+    },
+    KnownProvider {
+        id: "openai",
+        name: "OpenAI",
+        env_var: "OPENAI_API_KEY",
+        test_url: "https://api.openai.com/v1/models",
+    },
+    KnownProvider {
+        id: "google",
+        name: "Google Gemini",
+        env_var: "GEMINI_API_KEY",
+        test_url: "https://generativelanguage.googleapis.com/v1beta/models",
+    },
+    KnownProvider {
+        id: "openrouter",
+        name: "OpenRouter",
+        env_var: "OPENROUTER_API_KEY",
+        test_url: "https://openrouter.ai/api/v1/auth/key",
+    },
+    KnownProvider {
+        id: "groq",
+        name: "Groq",
+        env_var: "GROQ_API_KEY",
+        test_url: "https://api.groq.com/openai/v1/models",
+    },
+    KnownProvider {
+        id: "mistral",
+        name: "Mistral",
+        env_var: "MISTRAL_API_KEY",
+        test_url: "https://api.mistral.ai/v1/models",
+    },
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyStorage {
+    /// `models.providers.<id>.apiKey` in `openclaw.json`
+    Config,
+    /// OS keychain, passed to gateways the app starts through the environment
+    Keychain,
+    /// Only set in the environment the app was launched with
+    Environment,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderInfo {
+    pub id: String,
+    pub name: String,
+    pub configured: bool,
+    pub storage: Option<KeyStorage>,
+    /// Last characters of the key, or the `${VAR}` placeholder it is read from
+    pub key_hint: Option<String>,
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderTest {
+    pub ok: bool,
+    pub status: Option<u16>,
+    pub latency_ms: u64,
+    pub message: String,
+}
+
+fn known(id: &str) -> Option<&'static KnownProvider> {
+    KNOWN_PROVIDERS.iter().find(|p| p.id == id)
+}
+
+fn keychain_account(id: &str) -> String {
+    format!("provider:{}", id)
+}
+
+fn keychain_key(id: &str) -> Option<String> {
+    platform::integration().keychain_get(&keychain_account(id))
+}
+
+fn hint(key: &str) -> String {
+    if key.starts_with("${") {
+        return key.to_string();
+    }
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 8 {
+        return "…".to_string();
+    }
+    format!("…{}", chars[chars.len() - 4..].iter().collect::<String>())
+}
+
+fn configured_providers(config: &Value) -> Map<String, Value> {
+    config
+        .pointer("/models/providers")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn config_key<'a>(config: &'a Value, id: &str) -> Option<&'a str> {
+    config
+        .pointer(&format!("/models/providers/{}/apiKey", id))
+        .and_then(Value::as_str)
+        .filter(|key| !key.is_empty())
+}
+
+fn describe(config: &Value, id: &str) -> ProviderInfo {
+    let known = known(id);
+    let (storage, key_hint) = if let Some(key) = config_key(config, id) {
+        (Some(KeyStorage::Config), Some(hint(key)))
+    } else if let Some(key) = keychain_key(id) {
+        (Some(KeyStorage::Keychain), Some(hint(&key)))
+    } else if let Some(key) = known.and_then(|p| std::env::var(p.env_var).ok()) {
+        (Some(KeyStorage::Environment), Some(hint(&key)))
+    } else {
+        (None, None)
+    };
+    ProviderInfo {
+        id: id.to_string(),
+        name: known.map_or_else(|| id.to_string(), |p| p.name.to_string()),
+        configured: storage.is_some(),
+        storage,
+        key_hint,
+        base_url: config
+            .pointer(&format!("/models/providers/{}/baseUrl", id))
+            .and_then(Value::as_str)
+            .map(str::to_string),
+    }
+}
+
+/// Keys kept in the keychain, as environment variables for a gateway being spawned
+pub(crate) fn apply_keychain_env(command: &mut Command) {
+    for provider in KNOWN_PROVIDERS {
+        if let Some(key) = keychain_key(provider.id) {
+            command.env(provider.env_var, key);
+        }
+    }
+}
+
+/// `(id, name, url)` of each provider with a key, for timing reachability; the
+/// URLs answer without a key, if only with 401
+pub(crate) fn reachability_targets() -> Result<Vec<(String, String, String)>, String> {
+    let config = config::load_resolved_config()?;
+    let providers = list_providers()?;
+    Ok(providers
+        .into_iter()
+        .filter(|p| p.configured)
+        .filter_map(|p| {
+            let url = match known(&p.id) {
+                Some(known) => known.test_url.to_string(),
+                None => {
+                    let base_url = config
+                        .pointer(&format!("/models/providers/{}/baseUrl", p.id))
+                        .and_then(Value::as_str)?;
+                    format!("{}/models", base_url.trim_end_matches('/'))
+                }
+            };
+            Some((p.id, p.name, url))
+        })
+        .collect())
+}
+
+fn validate_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid provider id: {}", id))
+    }
+}
+
+/// Known providers plus any others configured in `openclaw.json`; keys are only ever hinted at
+#[tauri::command]
+pub fn list_providers() -> Result<Vec<ProviderInfo>, DesktopError> {
+    let config = config::load_config()?;
+    let mut ids: Vec<String> = KNOWN_PROVIDERS.iter().map(|p| p.id.to_string()).collect();
+    for id in configured_providers(&config).keys() {
+        if !ids.contains(id) {
+            ids.push(id.clone());
+        }
+    }
+    Ok(ids.iter().map(|id| describe(&config, id)).collect())
+}
+
+/// Store a provider's API key in the config or the keychain, or remove it with `None`.
+///
+/// A key only lives in one place, so saving it to one removes it from the other.
+#[tauri::command]
+pub async fn set_provider_api_key(
+    app: AppHandle,
+    provider: String,
+    api_key: Option<String>,
+    storage: KeyStorage,
+) -> Result<ProviderInfo, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    connection::ensure_local()?;
+    validate_id(&provider)?;
+    tauri::async_runtime::spawn_blocking(move || store_api_key(&app, provider, api_key, storage))
+        .await
+        .map_err(|e| format!("Failed to save API key: {}", e))?
+}
+
+fn store_api_key(
+    app: &AppHandle,
+    provider: String,
+    api_key: Option<String>,
+    storage: KeyStorage,
+) -> Result<ProviderInfo, DesktopError> {
+    let api_key = api_key
+        .as_deref()
+        .map(str::trim)
+        .filter(|key| !key.is_empty());
+    if storage == KeyStorage::Environment {
+        return Err(DesktopError::InvalidInput(
+            "Environment keys can't be changed from the app".to_string(),
+        ));
+    }
+
+    let keychain_value = match storage {
+        KeyStorage::Keychain => api_key,
+        _ => None,
+    };
+    platform::integration()
+        .keychain_set(&keychain_account(&provider), keychain_value)
+        .map_err(|e| format!("Failed to update key in keychain: {}", e))?;
+
+    let mut config = config::load_config()?;
+    let config_value = match storage {
+        KeyStorage::Config => api_key,
+        _ => None,
+    };
+    let changed = {
+        let root = config
+            .as_object_mut()
+            .ok_or("Config must be a JSON object")?;
+        let providers = root
+            .entry("models")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or("models must be a JSON object")?
+            .entry("providers")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or("models.providers must be a JSON object")?;
+        match config_value {
+            Some(key) => {
+                let entry = providers
+                    .entry(provider.clone())
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .ok_or("Provider entry must be a JSON object")?;
+                entry.insert("apiKey".to_string(), Value::String(key.to_string()));
+                true
+            }
+            None => providers
+                .get_mut(&provider)
+                .and_then(Value::as_object_mut)
+                .is_some_and(|entry| entry.remove("apiKey").is_some()),
+        }
+    };
+    if changed {
+        config::write_atomic(&config)?;
+        if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, &config) {
+            log_error!("Failed to emit config change: {}", e);
+        }
+    }
+
+    // Keychain keys reach the gateway through its environment; config keys can
+    // be picked up by hot reload
+    let hot_reload = storage == KeyStorage::Config
+        && capabilities::require(app, GatewayFeature::HotReload).is_ok();
+    if is_gateway_running() && !hot_reload {
+        restart_default_gateway(app)?;
+    }
+    Ok(describe(&config, &provider))
+}
+
+fn stored_key(id: &str) -> Option<String> {
+    config::load_resolved_config()
+        .ok()
+        .and_then(|config| config_key(&config, id).map(str::to_string))
+        .or_else(|| keychain_key(id))
+        .or_else(|| known(id).and_then(|p| std::env::var(p.env_var).ok()))
+}
+
+/// Check a key with a model-listing call, which costs nothing. Tests the stored key
+/// unless `api_key` is given, so a key can be checked before saving it.
+#[tauri::command]
+pub async fn test_provider_connection(
+    provider: String,
+    api_key: Option<String>,
+) -> Result<ProviderTest, DesktopError> {
+    validate_id(&provider)?;
+    let key = match api_key.as_deref().map(str::trim).filter(|k| !k.is_empty()) {
+        Some(key) => key.to_string(),
+        None => {
+            let id = provider.clone();
+            tauri::async_runtime::spawn_blocking(move || stored_key(&id))
+                .await
+                .map_err(|e| format!("Failed to read provider key: {}", e))?
+                .ok_or_else(|| format!("No API key is set for {}", provider))?
+        }
+    };
+
+    let url = match known(&provider) {
+        Some(known) => known.test_url.to_string(),
+        None => {
+            let config = config::load_resolved_config()?;
+            let base_url = config
+                .pointer(&format!("/models/providers/{}/baseUrl", provider))
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("{} has no baseUrl to test against", provider))?;
+            format!("{}/models", base_url.trim_end_matches('/'))
+        }
+    };
+
+    let client = proxy::apply(reqwest::Client::builder().timeout(TEST_TIMEOUT))?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let request = match provider.as_str() {
+        "anthropic" => client
+            .get(&url)
+            .header("x-api-key", &key)
+            .header("anthropic-version", "2023-06-01"),
+        "google" => client.get(&url).header("x-goog-api-key", &key),
+        _ => client.get(&url).bearer_auth(&key),
+    };
+
+    let started = Instant::now();
+    let response = request.send().await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+    Ok(match response {
+        Ok(response) => {
+            quota::observe_headers(&provider, response.headers());
+            let status = response.status();
+            let message = match status.as_u16() {
+                200..=299 => "Key accepted".to_string(),
+                401 | 403 => "The provider rejected the key".to_string(),
+                429 => "Key accepted, but the provider is rate limiting it".to_string(),
+                _ => format!("Unexpected response: HTTP {}", status),
+            };
+            ProviderTest {
+                ok: status.is_success() || status.as_u16() == 429,
+                status: Some(status.as_u16()),
+                latency_ms,
+                message,
+            }
+        }
+        Err(e) => ProviderTest {
+            ok: false,
+            status: None,
+            latency_ms,
+            message: format!("Failed to reach {}: {}", provider, e),
+        },
+    })
+}
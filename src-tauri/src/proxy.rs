@@ -0,0 +1,162 @@
+//! Proxy for the app's internet traffic: downloads, registry checks, and the
+//! npm and `openclaw` processes it spawns. Connections to the gateway itself
+//! never go through it.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+use crate::error::DesktopError;
+use crate::{kiosk, policy, secrets, settings};
+
+const SCHEMES: &[&str] = &["http", "https", "socks5", "socks5h"];
+const PROXY_VARS: &[&str] = &[
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "http_proxy",
+    "https_proxy",
+    "all_proxy",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum ProxySettings {
+    /// Whatever the OS or the `HTTP_PROXY` family of variables provide
+    #[default]
+    System,
+    /// Connect directly, even when the environment names a proxy
+    Direct,
+    /// `url` is `http://`, `https://` or `socks5://`. The password lives in the
+    /// keychain, not in the settings file.
+    Manual {
+        url: String,
+        username: Option<String>,
+        /// Hosts that bypass the proxy, in `NO_PROXY` syntax
+        #[serde(default)]
+        no_proxy: Vec<String>,
+    },
+}
+
+enum Effective {
+    System,
+    Direct,
+    Url {
+        url: String,
+        no_proxy: Option<String>,
+    },
+}
+
+/// `url` with the credentials folded in, as both reqwest and npm expect them
+fn manual_url(url: &str, username: Option<&str>, password: Option<&str>) -> Result<String, String> {
+    let mut parsed =
+        reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid proxy URL {}: {}", url, e))?;
+    if !SCHEMES.contains(&parsed.scheme()) {
+        return Err(format!(
+            "Unsupported proxy scheme {}; use http, https or socks5",
+            parsed.scheme()
+        ));
+    }
+    if let Some(username) = username.filter(|u| !u.is_empty()) {
+        parsed
+            .set_username(username)
+            .and_then(|_| parsed.set_password(password))
+            .map_err(|_| format!("Proxy URL {} can't carry credentials", url))?;
+    }
+    Ok(parsed.to_string())
+}
+
+/// The policy's forced proxy wins over the user's choice
+fn effective() -> Result<Effective, String> {
+    if let Some(url) = &policy::current().force_proxy {
+        return Ok(Effective::Url {
+            url: url.clone(),
+            no_proxy: None,
+        });
+    }
+    Ok(match settings::current().proxy {
+        ProxySettings::System => Effective::System,
+        ProxySettings::Direct => Effective::Direct,
+        ProxySettings::Manual {
+            url,
+            username,
+            no_proxy,
+        } => {
+            // Skip the keychain when there are no credentials to complete
+            let password = username.as_ref().and_then(|_| secrets::proxy_password());
+            Effective::Url {
+                url: manual_url(&url, username.as_deref(), password.as_deref())?,
+                no_proxy: Some(no_proxy.join(",")).filter(|list| !list.is_empty()),
+            }
+        }
+    })
+}
+
+/// Route an outbound HTTP client through the configured proxy
+pub fn apply(builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder, String> {
+    match effective()? {
+        Effective::System => Ok(builder),
+        Effective::Direct => Ok(builder.no_proxy()),
+        Effective::Url { url, no_proxy } => reqwest::Proxy::all(&url)
+            .map(|proxy| {
+                let no_proxy = no_proxy.as_deref().and_then(reqwest::NoProxy::from_string);
+                builder.proxy(proxy.no_proxy(no_proxy))
+            })
+            .map_err(|e| format!("Invalid proxy: {}", e)),
+    }
+}
+
+/// Pass the configured proxy to a spawned npm or CLI process through the
+/// standard environment variables
+pub fn apply_env(command: &mut Command) {
+    match effective() {
+        Ok(Effective::System) => {}
+        Ok(Effective::Direct) => {
+            for var in PROXY_VARS {
+                command.env_remove(var);
+            }
+        }
+        Ok(Effective::Url { url, no_proxy }) => {
+            for var in PROXY_VARS {
+                command.env(var, &url);
+            }
+            if let Some(no_proxy) = no_proxy {
+                command
+                    .env("NO_PROXY", &no_proxy)
+                    .env("no_proxy", &no_proxy);
+            }
+        }
+        // The process runs with the inherited environment; its own errors will say why
+        Err(e) => log_error!("Not applying proxy: {}", e),
+    }
+}
+
+#[tauri::command]
+pub fn get_proxy_settings() -> ProxySettings {
+    settings::current().proxy
+}
+
+/// Save proxy settings. `password` replaces the stored one for a manual proxy;
+/// `None` keeps it and an empty string removes it.
+#[tauri::command]
+pub fn set_proxy_settings(
+    proxy: ProxySettings,
+    password: Option<String>,
+) -> Result<ProxySettings, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if policy::current().force_proxy.is_some() {
+        return Err(DesktopError::PolicyLocked(
+            "The proxy is set by your administrator".to_string(),
+        ));
+    }
+    match &proxy {
+        ProxySettings::Manual { url, username, .. } => {
+            manual_url(url, username.as_deref(), password.as_deref())
+                .map_err(DesktopError::InvalidInput)?;
+            if let Some(password) = &password {
+                secrets::set_proxy_password(Some(password))?;
+            }
+        }
+        ProxySettings::System | ProxySettings::Direct => secrets::set_proxy_password(None)?,
+    }
+    Ok(settings::update(|s| s.proxy = proxy)?.proxy)
+}
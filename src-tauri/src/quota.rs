@@ -0,0 +1,257 @@
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+use crate::error::DesktopError;
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+
+/// Observations older than this no longer say anything about current limits
+const RECENT_SECS: u64 = 60 * 60;
+/// Share of a limit left at which a provider is flagged as running low
+const LOW_FRACTION: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaSource {
+    /// Headers the gateway saw on its own provider calls
+    Gateway,
+    /// Headers from the app's key test against the provider
+    Probe,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaLevel {
+    Ok,
+    Low,
+    Exhausted,
+}
+
+/// Rate-limit headers from one provider response
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QuotaObservation {
+    pub provider: String,
+    pub model: Option<String>,
+    pub requests_limit: Option<u64>,
+    pub requests_remaining: Option<u64>,
+    pub tokens_limit: Option<u64>,
+    pub tokens_remaining: Option<u64>,
+    /// Unix seconds when the tighter of the two windows resets
+    pub reset_at: Option<u64>,
+    /// Unix seconds
+    pub observed_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderQuota {
+    #[serde(flatten)]
+    pub observation: QuotaObservation,
+    pub source: QuotaSource,
+    pub level: QuotaLevel,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RateLimitsResponse {
+    List(Vec<QuotaObservation>),
+    Wrapped { ratelimits: Vec<QuotaObservation> },
+}
+
+/// Provider and, when the limit is per model, the model
+type QuotaKey = (String, Option<String>);
+
+/// Latest observation per provider and model
+static OBSERVATIONS: Mutex<Option<HashMap<QuotaKey, ProviderQuota>>> = Mutex::new(None);
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn level(observation: &QuotaObservation) -> QuotaLevel {
+    let windows = [
+        (observation.requests_remaining, observation.requests_limit),
+        (observation.tokens_remaining, observation.tokens_limit),
+    ];
+    windows
+        .iter()
+        .map(|window| match *window {
+            (Some(0), _) => QuotaLevel::Exhausted,
+            (Some(remaining), Some(limit)) if limit > 0 => {
+                if (remaining as f64) < limit as f64 * LOW_FRACTION {
+                    QuotaLevel::Low
+                } else {
+                    QuotaLevel::Ok
+                }
+            }
+            _ => QuotaLevel::Ok,
+        })
+        .max()
+        .unwrap_or(QuotaLevel::Ok)
+}
+
+fn record(observation: QuotaObservation, source: QuotaSource) {
+    let Ok(mut guard) = OBSERVATIONS.lock() else {
+        return;
+    };
+    let observations = guard.get_or_insert_with(HashMap::new);
+    let key = (observation.provider.clone(), observation.model.clone());
+    if observations
+        .get(&key)
+        .is_some_and(|existing| existing.observation.observed_at > observation.observed_at)
+    {
+        return;
+    }
+    let level = level(&observation);
+    observations.insert(
+        key,
+        ProviderQuota {
+            observation,
+            source,
+            level,
+        },
+    );
+}
+
+/// Seconds in a reset value: OpenAI sends durations like `6m0s` or `20ms`
+fn parse_duration(text: &str) -> Option<f64> {
+    let mut total = 0.0;
+    let mut number = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_ascii_digit() || c == '.' {
+            number.push(c);
+            continue;
+        }
+        let value: f64 = number.parse().ok()?;
+        number.clear();
+        total += match c {
+            'h' => value * 3600.0,
+            'm' if chars.peek() == Some(&'s') => {
+                chars.next();
+                value / 1000.0
+            }
+            'm' => value * 60.0,
+            's' => value,
+            _ => return None,
+        };
+    }
+    if !number.is_empty() {
+        total += number.parse::<f64>().ok()?;
+    }
+    Some(total)
+}
+
+/// Reset as Unix seconds; Anthropic sends RFC 3339 timestamps
+fn parse_reset(text: &str, now: u64) -> Option<u64> {
+    if let Ok(at) = chrono::DateTime::parse_from_rfc3339(text) {
+        return Some(at.timestamp().max(0) as u64);
+    }
+    parse_duration(text).map(|secs| now + secs.ceil() as u64)
+}
+
+/// Record the rate-limit headers of a provider response, if it sent any
+pub(crate) fn observe_headers(provider: &str, headers: &HeaderMap) {
+    let get = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| headers.get(*name)?.to_str().ok())
+            .map(str::to_string)
+    };
+    let number = |names: &[&str]| get(names).and_then(|v| v.parse::<u64>().ok());
+    let now = now_secs();
+
+    let observation = QuotaObservation {
+        provider: provider.to_string(),
+        model: None,
+        requests_limit: number(&[
+            "anthropic-ratelimit-requests-limit",
+            "x-ratelimit-limit-requests",
+        ]),
+        requests_remaining: number(&[
+            "anthropic-ratelimit-requests-remaining",
+            "x-ratelimit-remaining-requests",
+        ]),
+        tokens_limit: number(&[
+            "anthropic-ratelimit-tokens-limit",
+            "x-ratelimit-limit-tokens",
+        ]),
+        tokens_remaining: number(&[
+            "anthropic-ratelimit-tokens-remaining",
+            "x-ratelimit-remaining-tokens",
+        ]),
+        reset_at: [
+            get(&[
+                "anthropic-ratelimit-requests-reset",
+                "x-ratelimit-reset-requests",
+            ]),
+            get(&[
+                "anthropic-ratelimit-tokens-reset",
+                "x-ratelimit-reset-tokens",
+            ]),
+        ]
+        .iter()
+        .flatten()
+        .filter_map(|reset| parse_reset(reset, now))
+        .min(),
+        observed_at: now,
+    };
+    let any = observation.requests_remaining.is_some() || observation.tokens_remaining.is_some();
+    if any {
+        record(observation, QuotaSource::Probe);
+    }
+}
+
+/// Latest remaining quota per provider seen within the last hour, tightest first.
+///
+/// Refreshed from the gateway, which sees the headers of every provider call its
+/// agents make; providers it has no data for fall back to the last key test.
+#[tauri::command]
+pub async fn get_provider_quota_summary(
+    app: AppHandle,
+) -> Result<Vec<ProviderQuota>, DesktopError> {
+    match app
+        .state::<GatewayApi>()
+        .get::<RateLimitsResponse>("/api/providers/ratelimits")
+        .await
+    {
+        Ok(RateLimitsResponse::List(observations))
+        | Ok(RateLimitsResponse::Wrapped {
+            ratelimits: observations,
+        }) => {
+            for observation in observations {
+                record(observation, QuotaSource::Gateway);
+            }
+        }
+        Err(e) if e.kind == GatewayApiErrorKind::Unauthorized => return Err(e.into()),
+        // Not running or too old for the endpoint; what was seen before still stands
+        Err(_) => {}
+    }
+
+    let cutoff = now_secs().saturating_sub(RECENT_SECS);
+    let mut quotas: Vec<ProviderQuota> = OBSERVATIONS
+        .lock()
+        .map_err(|_| "Quota store lock poisoned".to_string())?
+        .as_ref()
+        .map(|observations| {
+            observations
+                .values()
+                .filter(|quota| quota.observation.observed_at >= cutoff)
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+    quotas.sort_by(|a, b| {
+        b.level
+            .cmp(&a.level)
+            .then_with(|| a.observation.provider.cmp(&b.observation.provider))
+            .then_with(|| a.observation.model.cmp(&b.observation.model))
+    });
+    Ok(quotas)
+}
@@ -0,0 +1,169 @@
+//! Waiting for a freshly started gateway to pass its health check.
+
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use ts_rs::TS;
+
+use crate::events::{GatewayStartProgress, GATEWAY_START_PROGRESS_EVENT};
+use crate::gateway_exit::{self, ExitInfo};
+use crate::gateway_process::GatewayProcess;
+use crate::preflight::{EnvironmentCheck, EnvironmentValidation};
+use crate::probe::{self, GatewayProbeResult};
+use crate::start_lock::StartLock;
+use crate::{gateway_log_paths, logs};
+
+pub(crate) const START_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const LOG_EXCERPT_LINES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum StartOutcome {
+    Ready,
+    /// Pre-start checks failed, so nothing was spawned
+    Blocked,
+    /// The process exited before the health check passed
+    Failed,
+    TimedOut,
+    /// Another start was already in progress, so nothing was spawned
+    AlreadyStarting,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct GatewayStartResult {
+    pub outcome: StartOutcome,
+    pub message: String,
+    #[ts(type = "number")]
+    pub elapsed_ms: u64,
+    pub probe: GatewayProbeResult,
+    /// How the process ended, when it failed
+    pub exit: Option<ExitInfo>,
+    /// Last lines of the gateway logs, when it did not become ready
+    pub log_excerpt: Vec<String>,
+    /// Pre-start checks that failed, when blocked
+    pub failed_checks: Vec<EnvironmentCheck>,
+}
+
+impl GatewayStartResult {
+    /// The gateway was not started because `validation` found problems
+    pub(crate) fn blocked(validation: &EnvironmentValidation) -> Self {
+        Self {
+            outcome: StartOutcome::Blocked,
+            message: format!("Gateway not started: {}", validation.summary()),
+            elapsed_ms: 0,
+            probe: GatewayProbeResult::default(),
+            exit: None,
+            log_excerpt: Vec::new(),
+            failed_checks: validation.failures(),
+        }
+    }
+
+    /// The gateway was not started because `lock` shows a start in progress
+    pub(crate) fn already_starting(lock: &StartLock) -> Self {
+        Self {
+            outcome: StartOutcome::AlreadyStarting,
+            message: lock.message(),
+            elapsed_ms: 0,
+            probe: GatewayProbeResult::default(),
+            exit: None,
+            log_excerpt: Vec::new(),
+            failed_checks: Vec::new(),
+        }
+    }
+}
+
+/// Tail of the error log, or of the main log when the error log is empty
+fn log_excerpt() -> Vec<String> {
+    let Ok((log_path, error_log_path)) = gateway_log_paths() else {
+        return Vec::new();
+    };
+    [error_log_path, log_path]
+        .iter()
+        .filter_map(|path| logs::tail_lines(path, LOG_EXCERPT_LINES).ok())
+        .find(|lines| lines.iter().any(|line| !line.trim().is_empty()))
+        .unwrap_or_default()
+}
+
+fn finish(
+    outcome: StartOutcome,
+    started: Instant,
+    probe: GatewayProbeResult,
+) -> GatewayStartResult {
+    let (message, exit) = match outcome {
+        StartOutcome::Ready => ("Gateway is ready".to_string(), None),
+        StartOutcome::Failed => {
+            let exit = gateway_exit::last_exit();
+            let detail = exit.as_ref().and_then(|e| e.detail.clone());
+            let message = match detail {
+                Some(detail) => format!("Gateway exited during startup: {}", detail),
+                None => "Gateway exited during startup".to_string(),
+            };
+            (message, exit)
+        }
+        StartOutcome::Blocked => ("Gateway not started".to_string(), None),
+        StartOutcome::AlreadyStarting => ("Gateway is already starting".to_string(), None),
+        StartOutcome::TimedOut => (
+            format!(
+                "Gateway did not become ready within {}s",
+                START_TIMEOUT.as_secs()
+            ),
+            None,
+        ),
+    };
+    let log_excerpt = match outcome {
+        StartOutcome::Failed | StartOutcome::TimedOut => log_excerpt(),
+        StartOutcome::Ready | StartOutcome::Blocked | StartOutcome::AlreadyStarting => Vec::new(),
+    };
+    GatewayStartResult {
+        outcome,
+        message,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+        probe,
+        exit,
+        log_excerpt,
+        failed_checks: Vec::new(),
+    }
+}
+
+/// Poll the health endpoint until the gateway answers, the process the app
+/// spawned exits, or the timeout passes, emitting `gateway-start-progress` on
+/// every poll
+pub(crate) async fn wait_until_ready<R: Runtime>(app: &AppHandle<R>) -> GatewayStartResult {
+    let started = Instant::now();
+    let spawned_pid = app.state::<GatewayProcess>().pid();
+
+    loop {
+        let probe = tauri::async_runtime::spawn_blocking(probe::probe_gateway)
+            .await
+            .unwrap_or_default();
+        let exited = spawned_pid.is_some() && app.state::<GatewayProcess>().pid() != spawned_pid;
+        let outcome = if probe.verified {
+            Some(StartOutcome::Ready)
+        } else if exited {
+            Some(StartOutcome::Failed)
+        } else if started.elapsed() >= START_TIMEOUT {
+            Some(StartOutcome::TimedOut)
+        } else {
+            None
+        };
+        let result = outcome.map(|outcome| finish(outcome, started, probe.clone()));
+
+        let payload = GatewayStartProgress {
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            timeout_ms: START_TIMEOUT.as_millis() as u64,
+            probe,
+            result: result.clone(),
+        };
+        if let Err(e) = app.emit(GATEWAY_START_PROGRESS_EVENT, payload) {
+            log_error!("Failed to emit gateway start progress: {}", e);
+        }
+
+        if let Some(result) = result {
+            return result;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
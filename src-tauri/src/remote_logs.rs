@@ -0,0 +1,162 @@
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::connection;
+use crate::error::DesktopError;
+use crate::events::{GatewayLogLine, RemoteLogStatus, REMOTE_LOG_STATUS_EVENT};
+use crate::gateways;
+use crate::log_stream;
+use crate::logs::{self, LogLevel};
+use crate::secrets;
+
+const LOG_STREAM_PATH: &str = "/api/logs/stream";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Bumped on every start/stop so an older stream notices it was replaced
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn is_current(generation: u64) -> bool {
+    GENERATION.load(Ordering::SeqCst) == generation
+}
+
+fn emit_status(app: &AppHandle, gateway: &str, connected: bool, error: Option<String>) {
+    let status = RemoteLogStatus {
+        gateway: gateway.to_string(),
+        connected,
+        error,
+    };
+    if let Err(e) = app.emit(REMOTE_LOG_STATUS_EVENT, status) {
+        log_error!("Failed to emit remote log status: {}", e);
+    }
+}
+
+/// Turn one SSE `data:` payload into the stream name and raw log text.
+///
+/// Gateways send either plain log lines or `{"line"|"message", "stream"}` objects.
+fn parse_event(data: &str) -> (&'static str, String) {
+    let Ok(json) = serde_json::from_str::<Value>(data) else {
+        return ("stdout", data.to_string());
+    };
+    let stream = match json.get("stream").and_then(Value::as_str) {
+        Some("stderr") => "stderr",
+        _ => "stdout",
+    };
+    let text = ["line", "message", "msg"]
+        .iter()
+        .find_map(|key| json.get(key).and_then(Value::as_str))
+        .map(str::to_string)
+        .unwrap_or_else(|| data.to_string());
+    (stream, text)
+}
+
+async fn forward_stream(
+    app: &AppHandle,
+    gateway: &str,
+    generation: u64,
+    mut response: reqwest::Response,
+) -> Result<(), String> {
+    let mut buffer = String::new();
+    let mut line_number = 0;
+    let mut last_level = LogLevel::Info;
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Log stream interrupted: {}", e))?
+    {
+        if !is_current(generation) {
+            return Ok(());
+        }
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+        while let Some(end) = buffer.find('\n') {
+            let line: String = buffer.drain(..=end).collect();
+            let Some(data) = line.trim_end().strip_prefix("data:") else {
+                continue;
+            };
+            let (stream, text) = parse_event(data.trim_start());
+            line_number += 1;
+            let mut entry = logs::parse_line(line_number, &text);
+            last_level = *entry.level.get_or_insert(last_level);
+            log_stream::emit_line(
+                app,
+                GatewayLogLine {
+                    stream,
+                    entry,
+                    gateway: Some(gateway.to_string()),
+                },
+            );
+        }
+    }
+    Err("Log stream closed by the gateway".to_string())
+}
+
+async fn run(app: AppHandle, gateway: String, url: String, generation: u64) {
+    let client =
+        match connection::apply_tls(reqwest::Client::builder().connect_timeout(CONNECT_TIMEOUT))
+            .and_then(|builder| {
+                builder
+                    .build()
+                    .map_err(|e| format!("Failed to create HTTP client: {}", e))
+            }) {
+            Ok(client) => client,
+            Err(e) => {
+                emit_status(&app, &gateway, false, Some(e));
+                return;
+            }
+        };
+
+    while is_current(generation) {
+        let mut request = client
+            .get(&url)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+        if let Some(token) = secrets::remote_gateway_token(&gateway) {
+            request = request.bearer_auth(token);
+        }
+
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                emit_status(&app, &gateway, true, None);
+                forward_stream(&app, &gateway, generation, response)
+                    .await
+                    .err()
+            }
+            Ok(response) => Some(format!(
+                "Remote gateway returned HTTP {}",
+                response.status().as_u16()
+            )),
+            Err(e) => Some(format!("Failed to connect to remote gateway: {}", e)),
+        };
+        if !is_current(generation) {
+            break;
+        }
+        emit_status(&app, &gateway, false, error);
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Stream a remote gateway's logs into `gateway-log-line`, replacing any remote
+/// stream already running. Reconnects until `stop_remote_logs` is called.
+#[tauri::command]
+pub fn start_remote_logs(app: AppHandle, name: String) -> Result<(), DesktopError> {
+    let definition = gateways::known_definitions()
+        .into_iter()
+        .find(|g| g.name == name)
+        .ok_or_else(|| DesktopError::NotFound(format!("Gateway '{}' is not configured", name)))?;
+    let base = definition.url.ok_or_else(|| {
+        DesktopError::InvalidInput(format!("Gateway '{}' is not a remote gateway", name))
+    })?;
+    let url = format!("{}{}", base.trim_end_matches('/'), LOG_STREAM_PATH);
+
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    tauri::async_runtime::spawn(run(app, name, url, generation));
+    Ok(())
+}
+
+/// Stop the remote log stream, if one is running
+#[tauri::command]
+pub fn stop_remote_logs() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
@@ -0,0 +1,268 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::time::MissedTickBehavior;
+
+use crate::error::DesktopError;
+use crate::gateway_process::GatewayProcess;
+use crate::i18n;
+use crate::notifications::{self, NotificationCategory};
+use crate::{
+    active_gateway_port, connection, kiosk, port_owner, restart_default_gateway, settings,
+};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+/// One hour of samples
+const HISTORY_LIMIT: usize = 360;
+/// After a limit fires, give the gateway this long before acting again
+const ACTION_COOLDOWN: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LimitAction {
+    Notify,
+    Restart,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceLimits {
+    /// Memory of the gateway and its child processes, in MiB; unset disables the check
+    pub memory_limit_mb: Option<u64>,
+    /// Consecutive samples over the limit before acting, so brief spikes are ignored
+    pub sustained_samples: u32,
+    pub action: LimitAction,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            memory_limit_mb: None,
+            sustained_samples: 3,
+            action: LimitAction::Notify,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceSample {
+    /// Unix seconds
+    pub at: u64,
+    pub pid: u32,
+    /// Summed over the gateway's process tree; 100 is one full core
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    /// Only where the OS exposes it cheaply (Linux)
+    pub open_files: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GatewayResourceUsage {
+    pub current: Option<ResourceSample>,
+    /// Oldest first
+    pub history: Vec<ResourceSample>,
+    pub limits: ResourceLimits,
+}
+
+static HISTORY: Mutex<VecDeque<ResourceSample>> = Mutex::new(VecDeque::new());
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The process the app spawned, or whatever gateway holds the port (e.g. the OS service)
+fn gateway_pid<R: Runtime>(app: &AppHandle<R>) -> Option<u32> {
+    app.state::<GatewayProcess>().pid().or_else(|| {
+        port_owner::lookup(active_gateway_port())
+            .filter(|owner| owner.is_gateway)
+            .map(|owner| owner.pid)
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn open_files(pid: u32) -> Option<u64> {
+    std::fs::read_dir(format!("/proc/{}/fd", pid))
+        .ok()
+        .map(|entries| entries.count() as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_files(_pid: u32) -> Option<u64> {
+    None
+}
+
+/// Sample the process and its descendants, since npm shims and node spawn children
+fn sample(system: &mut System, pid: u32) -> Option<ResourceSample> {
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::new().with_cpu().with_memory(),
+    );
+    let root = Pid::from_u32(pid);
+    system.process(root)?;
+
+    let mut tree = HashSet::from([root]);
+    // Parents can be listed after their children, so repeat until nothing is added
+    loop {
+        let before = tree.len();
+        for (child, process) in system.processes() {
+            if process
+                .parent()
+                .is_some_and(|parent| tree.contains(&parent))
+            {
+                tree.insert(*child);
+            }
+        }
+        if tree.len() == before {
+            break;
+        }
+    }
+
+    let mut sample = ResourceSample {
+        at: now_secs(),
+        pid,
+        cpu_percent: 0.0,
+        memory_bytes: 0,
+        open_files: None,
+    };
+    for pid in &tree {
+        if let Some(process) = system.process(*pid) {
+            sample.cpu_percent += process.cpu_usage();
+            sample.memory_bytes += process.memory();
+            if let Some(files) = open_files(pid.as_u32()) {
+                *sample.open_files.get_or_insert(0) += files;
+            }
+        }
+    }
+    Some(sample)
+}
+
+fn record(sample: ResourceSample) {
+    if let Ok(mut history) = HISTORY.lock() {
+        if history.len() >= HISTORY_LIMIT {
+            history.pop_front();
+        }
+        history.push_back(sample);
+    }
+}
+
+fn act_on_limit<R: Runtime>(app: &AppHandle<R>, limits: &ResourceLimits, memory_mb: u64) {
+    let limit = limits.memory_limit_mb.unwrap_or_default();
+    match limits.action {
+        LimitAction::Notify => notifications::notify(
+            app,
+            NotificationCategory::ResourceLimit,
+            &i18n::t("resources.memory_high"),
+            &i18n::t_with(
+                "resources.memory_high_body",
+                &[("used", &memory_mb), ("limit", &limit)],
+            ),
+        ),
+        LimitAction::Restart => {
+            notifications::notify(
+                app,
+                NotificationCategory::ResourceLimit,
+                &i18n::t("resources.restarting"),
+                &i18n::t_with(
+                    "resources.restarting_body",
+                    &[("used", &memory_mb), ("limit", &limit)],
+                ),
+            );
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = restart_default_gateway(&app) {
+                    log_error!("Failed to restart gateway over memory limit: {}", e);
+                }
+            });
+        }
+    }
+}
+
+/// Sample CPU, memory, and open files of the local gateway and enforce the memory limit
+pub fn spawn_resource_monitor<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut system = System::new();
+        let mut over_limit = 0u32;
+        let mut cooldown_until: Option<Instant> = None;
+        let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            if connection::is_remote() {
+                continue;
+            }
+            let pid_app = app.clone();
+            let Ok(Some(pid)) =
+                tauri::async_runtime::spawn_blocking(move || gateway_pid(&pid_app)).await
+            else {
+                over_limit = 0;
+                continue;
+            };
+            let result =
+                tauri::async_runtime::spawn_blocking(move || (sample(&mut system, pid), system))
+                    .await;
+            let Ok((sample, returned)) = result else {
+                system = System::new();
+                continue;
+            };
+            system = returned;
+            let Some(sample) = sample else {
+                continue;
+            };
+
+            let limits = settings::current().resource_limits;
+            let memory_mb = sample.memory_bytes / (1024 * 1024);
+            record(sample);
+
+            let Some(limit) = limits.memory_limit_mb else {
+                over_limit = 0;
+                continue;
+            };
+            if memory_mb <= limit {
+                over_limit = 0;
+                continue;
+            }
+            over_limit += 1;
+            if over_limit < limits.sustained_samples.max(1)
+                || cooldown_until.is_some_and(|until| Instant::now() < until)
+            {
+                continue;
+            }
+            over_limit = 0;
+            cooldown_until = Some(Instant::now() + ACTION_COOLDOWN);
+            act_on_limit(&app, &limits, memory_mb);
+        }
+    });
+}
+
+/// Latest CPU, memory, and open-file counts of the gateway, with the last hour of samples
+#[tauri::command]
+pub fn get_gateway_resource_usage() -> GatewayResourceUsage {
+    let history: Vec<ResourceSample> = HISTORY
+        .lock()
+        .map(|history| history.iter().cloned().collect())
+        .unwrap_or_default();
+    // A sample older than two intervals means the gateway is no longer being watched
+    let current = history
+        .last()
+        .filter(|s| now_secs().saturating_sub(s.at) <= SAMPLE_INTERVAL.as_secs() * 2)
+        .cloned();
+    GatewayResourceUsage {
+        current,
+        history,
+        limits: settings::current().resource_limits,
+    }
+}
+
+#[tauri::command]
+pub fn set_resource_limits(limits: ResourceLimits) -> Result<ResourceLimits, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    Ok(settings::update(|s| s.resource_limits = limits)?.resource_limits)
+}
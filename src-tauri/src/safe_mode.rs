@@ -0,0 +1,39 @@
+use std::sync::OnceLock;
+
+/// CLI flag that starts the app in safe mode
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+
+static ACTIVE: OnceLock<bool> = OnceLock::new();
+
+#[cfg(target_os = "windows")]
+fn shift_held() -> bool {
+    use windows::Win32::UI::Input::KeyboardAndMouse::{GetAsyncKeyState, VK_SHIFT};
+    // The high bit is set while the key is down
+    unsafe { GetAsyncKeyState(VK_SHIFT.0 as i32) < 0 }
+}
+
+#[cfg(target_os = "macos")]
+fn shift_held() -> bool {
+    use objc2_app_kit::{NSEvent, NSEventModifierFlags};
+    unsafe { NSEvent::modifierFlags_class() }
+        .contains(NSEventModifierFlags::NSEventModifierFlagShift)
+}
+
+/// No portable way to read the keyboard state without a window on Linux
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn shift_held() -> bool {
+    false
+}
+
+/// Whether this launch is in safe mode: `--safe-mode`, or Shift held while starting.
+///
+/// Safe mode runs tray-only with default settings kept in memory and no
+/// background tasks, so a configuration that crashes the app can still be fixed.
+pub fn is_active() -> bool {
+    *ACTIVE.get_or_init(|| std::env::args().any(|arg| arg == SAFE_MODE_FLAG) || shift_held())
+}
+
+#[tauri::command]
+pub fn get_safe_mode() -> bool {
+    is_active()
+}
@@ -0,0 +1,123 @@
+//! Restricting what agents may do on this machine. The gateway enforces the
+//! mode; the app passes it as launch flags and restarts a running gateway when
+//! it changes, so a loosened or tightened mode never waits for the next start.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::error::DesktopError;
+use crate::{
+    audit, connection, is_gateway_running, kiosk, restart_default_gateway, settings, tray,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetyMode {
+    /// Agents run commands and write files as usual
+    #[default]
+    Full,
+    /// No shell commands; file writes are still allowed
+    NoExec,
+    /// No shell commands and no file writes; agents only report what they would do
+    DryRun,
+}
+
+impl SafetyMode {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            SafetyMode::Full => "full",
+            SafetyMode::NoExec => "no_exec",
+            SafetyMode::DryRun => "dry_run",
+        }
+    }
+
+    fn flags(self) -> &'static [&'static str] {
+        match self {
+            SafetyMode::Full => &[],
+            SafetyMode::NoExec => &["--no-exec"],
+            // Gateways that predate `--dry-run` still refuse to execute
+            SafetyMode::DryRun => &["--no-exec", "--dry-run"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafetyModeStatus {
+    /// Used the next time the gateway starts
+    pub configured: SafetyMode,
+    /// Mode the running gateway was started with
+    pub running: Option<SafetyMode>,
+}
+
+static RUNNING: Mutex<Option<SafetyMode>> = Mutex::new(None);
+
+fn set_running(mode: Option<SafetyMode>) {
+    if let Ok(mut running) = RUNNING.lock() {
+        *running = mode;
+    }
+}
+
+/// Add the configured mode to an `openclaw gateway` command about to be spawned
+pub(crate) fn apply(command: &mut Command) {
+    let mode = settings::current().safety_mode;
+    command.args(mode.flags());
+    set_running(Some(mode));
+}
+
+/// Forget the running mode once the gateway process is gone
+pub(crate) fn clear() {
+    set_running(None);
+}
+
+/// The mode in force: the running gateway's, or the configured one while stopped
+pub(crate) fn effective() -> SafetyMode {
+    RUNNING
+        .lock()
+        .ok()
+        .and_then(|r| *r)
+        .unwrap_or_else(|| settings::current().safety_mode)
+}
+
+pub(crate) fn status() -> SafetyModeStatus {
+    SafetyModeStatus {
+        configured: settings::current().safety_mode,
+        running: RUNNING.lock().ok().and_then(|r| *r),
+    }
+}
+
+#[tauri::command]
+pub fn get_safety_mode() -> SafetyModeStatus {
+    status()
+}
+
+/// Save the safety mode and restart a running local gateway so it takes effect
+#[tauri::command]
+pub async fn set_safety_mode(
+    app: AppHandle,
+    mode: SafetyMode,
+) -> Result<SafetyModeStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    // A remote gateway is started with its own host's flags
+    connection::ensure_local()?;
+    let saved = settings::update(|s| s.safety_mode = mode);
+    audit::record_result("safety_mode", mode.as_str(), &saved);
+    saved?;
+
+    let restart_app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let running = RUNNING.lock().ok().and_then(|r| *r);
+        if is_gateway_running() && running != Some(mode) {
+            restart_default_gateway(&restart_app)?;
+        }
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Failed to apply safety mode: {}", e))??;
+
+    if let Err(e) = tray::rebuild_tray(&app) {
+        log_error!("Failed to refresh tray: {}", e);
+    }
+    Ok(status())
+}
@@ -0,0 +1,194 @@
+use chrono::{Local, NaiveDate, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime};
+
+use crate::error::DesktopError;
+use crate::{
+    connection, focus, is_gateway_running, kiosk, openclaw_home_dir, restart_default_gateway,
+    settings, start_default_gateway, stop_default_gateway,
+};
+
+const STATE_FILE: &str = "schedule-state.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+/// A restart missed by more than this (app closed, user presenting) waits for the next day
+const RESTART_GRACE_MINUTES: u32 = 60;
+
+/// Local `HH:MM` window during which the gateway is kept stopped; may span midnight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GatewaySchedule {
+    pub enabled: bool,
+    /// Local `HH:MM` at which a running gateway is restarted each day
+    pub daily_restart: Option<String>,
+    pub quiet_hours: Option<QuietHours>,
+}
+
+/// What the scheduler did, kept on disk so an app restart doesn't repeat or forget it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ScheduleState {
+    last_restart: Option<NaiveDate>,
+    /// The gateway was stopped for quiet hours and should come back afterwards
+    paused: bool,
+}
+
+fn state_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(STATE_FILE))
+}
+
+fn load_state() -> ScheduleState {
+    state_path()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(state: &ScheduleState) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Failed to serialize schedule state: {}", e))?;
+    fs::write(state_path()?, content).map_err(|e| format!("Failed to write schedule state: {}", e))
+}
+
+fn parse_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid time '{}', expected HH:MM", value))
+}
+
+fn minutes(time: NaiveTime) -> u32 {
+    time.hour() * 60 + time.minute()
+}
+
+fn in_window(now: NaiveTime, start: NaiveTime, end: NaiveTime) -> bool {
+    let (now, start, end) = (minutes(now), minutes(start), minutes(end));
+    if start <= end {
+        now >= start && now < end
+    } else {
+        now >= start || now < end
+    }
+}
+
+fn in_quiet_hours(schedule: &GatewaySchedule, now: NaiveTime) -> bool {
+    let Some(quiet) = &schedule.quiet_hours else {
+        return false;
+    };
+    match (parse_time(&quiet.start), parse_time(&quiet.end)) {
+        (Ok(start), Ok(end)) => in_window(now, start, end),
+        _ => false,
+    }
+}
+
+fn restart_due(schedule: &GatewaySchedule, state: &ScheduleState) -> bool {
+    let Some(time) = schedule
+        .daily_restart
+        .as_deref()
+        .and_then(|t| parse_time(t).ok())
+    else {
+        return false;
+    };
+    let now = Local::now();
+    if state.last_restart == Some(now.date_naive()) {
+        return false;
+    }
+    let elapsed = minutes(now.time()) as i64 - minutes(time) as i64;
+    (0..RESTART_GRACE_MINUTES as i64).contains(&elapsed)
+}
+
+/// One pass of the scheduler; returns whether the state changed
+fn tick<R: Runtime>(
+    app: &AppHandle<R>,
+    schedule: &GatewaySchedule,
+    state: &mut ScheduleState,
+) -> bool {
+    let now = Local::now();
+    let running = is_gateway_running();
+
+    if in_quiet_hours(schedule, now.time()) {
+        if running && !state.paused {
+            match stop_default_gateway(app) {
+                Ok(_) => {
+                    state.paused = true;
+                    return true;
+                }
+                Err(e) => log_error!("Failed to pause gateway for quiet hours: {}", e),
+            }
+        }
+        return false;
+    }
+
+    if state.paused {
+        state.paused = false;
+        if let Err(e) = start_default_gateway(app) {
+            log_error!("Failed to resume gateway after quiet hours: {}", e);
+        }
+        return true;
+    }
+
+    if restart_due(schedule, state) && !focus::should_defer() {
+        state.last_restart = Some(now.date_naive());
+        if running {
+            if let Err(e) = restart_default_gateway(app) {
+                log_error!("Scheduled gateway restart failed: {}", e);
+            }
+        }
+        return true;
+    }
+    false
+}
+
+/// Run scheduled restarts and quiet hours for the local gateway
+pub fn spawn_gateway_scheduler<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let schedule = settings::current().gateway_schedule;
+            if !schedule.enabled || connection::is_remote() {
+                continue;
+            }
+            let task_app = app.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                let mut state = load_state();
+                if tick(&task_app, &schedule, &mut state) {
+                    save_state(&state)?;
+                }
+                Ok::<(), String>(())
+            })
+            .await;
+            if let Ok(Err(e)) = result {
+                log_error!("{}", e);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn get_gateway_schedule() -> GatewaySchedule {
+    settings::current().gateway_schedule
+}
+
+/// Save the restart time and quiet hours; times are local `HH:MM`
+#[tauri::command]
+pub fn set_gateway_schedule(schedule: GatewaySchedule) -> Result<GatewaySchedule, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if let Some(time) = &schedule.daily_restart {
+        parse_time(time)?;
+    }
+    if let Some(quiet) = &schedule.quiet_hours {
+        if parse_time(&quiet.start)? == parse_time(&quiet.end)? {
+            return Err(DesktopError::InvalidInput(
+                "Quiet hours must start and end at different times".to_string(),
+            ));
+        }
+    }
+    Ok(settings::update(|s| s.gateway_schedule = schedule)?.gateway_schedule)
+}
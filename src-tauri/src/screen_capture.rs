@@ -0,0 +1,207 @@
+//! Screenshots for agent context.
+//!
+//! Off unless turned on in settings, and each capture goes through the
+//! `capture_screen` consent prompt. Images are kept under
+//! `~/.openclaw/captures/`, readable only by the user.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, State};
+
+use crate::attachments::{self, Attachment};
+use crate::error::DesktopError;
+use crate::gateway_api::GatewayApi;
+use crate::permissions::{self, Permission};
+use crate::{audit, kiosk, openclaw_home_dir, settings};
+
+const CAPTURES_DIR: &str = "captures";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CaptureTarget {
+    /// A whole display; the primary one when `id` is unset
+    Display {
+        id: Option<u32>,
+    },
+    Window {
+        id: u32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureDisplay {
+    pub id: u32,
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub primary: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureWindow {
+    pub id: u32,
+    pub title: String,
+    pub app_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureSources {
+    pub displays: Vec<CaptureDisplay>,
+    /// Visible windows; minimized ones can't be captured
+    pub windows: Vec<CaptureWindow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Screenshot {
+    pub path: String,
+    pub width: u32,
+    pub height: u32,
+    /// Set when the capture was attached to a session
+    pub attachment: Option<Attachment>,
+}
+
+fn ensure_enabled() -> Result<(), DesktopError> {
+    if settings::current().screen_capture {
+        Ok(())
+    } else {
+        Err(DesktopError::Unavailable(
+            "Screen capture is turned off in settings".to_string(),
+        ))
+    }
+}
+
+fn captures_dir() -> Result<PathBuf, String> {
+    let dir = openclaw_home_dir()?.join(CAPTURES_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create captures folder: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to restrict captures folder: {}", e))?;
+    }
+    Ok(dir)
+}
+
+fn sources() -> Result<CaptureSources, String> {
+    let displays = xcap::Monitor::all()
+        .map_err(|e| format!("Failed to list displays: {}", e))?
+        .into_iter()
+        .map(|monitor| CaptureDisplay {
+            id: monitor.id(),
+            name: monitor.name().to_string(),
+            width: monitor.width(),
+            height: monitor.height(),
+            primary: monitor.is_primary(),
+        })
+        .collect();
+    let windows = xcap::Window::all()
+        .map_err(|e| format!("Failed to list windows: {}", e))?
+        .into_iter()
+        .filter(|window| !window.is_minimized() && !window.title().is_empty())
+        .map(|window| CaptureWindow {
+            id: window.id(),
+            title: window.title().to_string(),
+            app_name: window.app_name().to_string(),
+        })
+        .collect();
+    Ok(CaptureSources { displays, windows })
+}
+
+fn capture(target: &CaptureTarget) -> Result<xcap::image::RgbaImage, DesktopError> {
+    let image = match target {
+        CaptureTarget::Display { id } => {
+            let monitors =
+                xcap::Monitor::all().map_err(|e| format!("Failed to list displays: {}", e))?;
+            let monitor = monitors
+                .into_iter()
+                .find(|m| id.map_or(m.is_primary(), |id| m.id() == id))
+                .ok_or_else(|| DesktopError::NotFound("Display not found".to_string()))?;
+            monitor.capture_image()
+        }
+        CaptureTarget::Window { id } => {
+            let windows =
+                xcap::Window::all().map_err(|e| format!("Failed to list windows: {}", e))?;
+            let window = windows
+                .into_iter()
+                .find(|w| w.id() == *id)
+                .ok_or_else(|| DesktopError::NotFound("Window not found".to_string()))?;
+            window.capture_image()
+        }
+    };
+    Ok(image.map_err(|e| format!("Failed to capture screen: {}", e))?)
+}
+
+/// Capture and write the PNG, returning its path and size
+fn capture_to_file(target: &CaptureTarget) -> Result<(PathBuf, u32, u32, Vec<u8>), DesktopError> {
+    let image = capture(target)?;
+    let (width, height) = (image.width(), image.height());
+    let png = attachments::encode_png(width, height, image.as_raw())?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let path = captures_dir()?.join(format!("screenshot-{}.png", millis));
+    fs::write(&path, &png).map_err(|e| format!("Failed to save screenshot: {}", e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict screenshot: {}", e))?;
+    }
+    Ok((path, width, height, png))
+}
+
+/// Displays and windows that `capture_screenshot` can target
+#[tauri::command]
+pub async fn list_capture_sources() -> Result<CaptureSources, DesktopError> {
+    ensure_enabled()?;
+    Ok(tauri::async_runtime::spawn_blocking(sources)
+        .await
+        .map_err(|e| format!("Failed to list capture sources: {}", e))??)
+}
+
+/// Capture a display or window after asking for consent, and attach it to
+/// `session_id` when given
+#[tauri::command]
+pub async fn capture_screenshot(
+    app: AppHandle,
+    api: State<'_, GatewayApi>,
+    target: CaptureTarget,
+    session_id: Option<String>,
+) -> Result<Screenshot, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    ensure_enabled()?;
+    permissions::ensure_granted(&app, Permission::CaptureScreen).await?;
+
+    let captured = {
+        let target = target.clone();
+        tauri::async_runtime::spawn_blocking(move || capture_to_file(&target))
+            .await
+            .map_err(|e| format!("Failed to capture screen: {}", e))?
+    };
+    let target_name = match target {
+        CaptureTarget::Display { .. } => "display",
+        CaptureTarget::Window { .. } => "window",
+    };
+    audit::record_result("screen_capture", target_name, &captured);
+    let (path, width, height, png) = captured?;
+
+    let attachment = match session_id {
+        Some(session_id) => {
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "screenshot.png".to_string());
+            Some(attachments::upload(&api, name, "image/png", png, Some(&session_id)).await?)
+        }
+        None => None,
+    };
+    Ok(Screenshot {
+        path: path.display().to_string(),
+        width,
+        height,
+        attachment,
+    })
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::config;
+use crate::connection;
+use crate::dashboard_proxy;
+use crate::error::DesktopError;
+use crate::platform;
+use crate::{dashboard_base_url, kiosk, read_config_token, tokenized_dashboard_url};
+
+const GATEWAY_TOKEN_ACCOUNT: &str = "gateway-token";
+const BACKUP_PASSPHRASE_ACCOUNT: &str = "backup-passphrase";
+const REMOTE_CONNECTION_ACCOUNT: &str = "remote-connection";
+const PROXY_PASSWORD_ACCOUNT: &str = "proxy-password";
+const LOGIN_CODE_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenMigration {
+    /// Whether a token was copied into the keychain
+    pub migrated: bool,
+    pub removed_from_config: bool,
+}
+
+#[derive(Deserialize)]
+struct LoginCodeResponse {
+    code: String,
+}
+
+/// Gateway token stored in the OS keychain, if it has been migrated there
+pub fn keychain_token() -> Option<String> {
+    platform::integration().keychain_get(GATEWAY_TOKEN_ACCOUNT)
+}
+
+pub fn store_token(token: &str) -> Result<(), String> {
+    platform::integration()
+        .keychain_set(GATEWAY_TOKEN_ACCOUNT, Some(token))
+        .map_err(|e| format!("Failed to store token in keychain: {}", e))
+}
+
+pub fn backup_passphrase() -> Option<String> {
+    platform::integration().keychain_get(BACKUP_PASSPHRASE_ACCOUNT)
+}
+
+/// Store the backup passphrase, or remove it with `None`
+pub fn set_backup_passphrase(passphrase: Option<&str>) -> Result<(), String> {
+    platform::integration()
+        .keychain_set(BACKUP_PASSPHRASE_ACCOUNT, passphrase)
+        .map_err(|e| format!("Failed to update passphrase in keychain: {}", e))
+}
+
+/// Token for a remote gateway, stored under its own keychain account
+pub fn remote_gateway_token(name: &str) -> Option<String> {
+    platform::integration().keychain_get(&format!("remote:{}", name))
+}
+
+/// Store the token of a remote gateway, or remove it with `None`
+#[tauri::command]
+pub fn set_remote_gateway_token(name: String, token: Option<String>) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let token = token.as_deref().map(str::trim).filter(|t| !t.is_empty());
+    platform::integration()
+        .keychain_set(&format!("remote:{}", name), token)
+        .map_err(|e| format!("Failed to update token in keychain: {}", e))?;
+    Ok(())
+}
+
+/// Token for the gateway the app is connected to in remote mode
+pub fn remote_connection_token() -> Option<String> {
+    platform::integration().keychain_get(REMOTE_CONNECTION_ACCOUNT)
+}
+
+/// Store the remote connection token, or remove it with `None` or an empty string
+pub fn set_remote_connection_token(token: Option<&str>) -> Result<(), String> {
+    let token = token.map(str::trim).filter(|t| !t.is_empty());
+    platform::integration()
+        .keychain_set(REMOTE_CONNECTION_ACCOUNT, token)
+        .map_err(|e| format!("Failed to update token in keychain: {}", e))
+}
+
+/// Password for the manually configured proxy
+pub fn proxy_password() -> Option<String> {
+    platform::integration().keychain_get(PROXY_PASSWORD_ACCOUNT)
+}
+
+/// Store the proxy password, or remove it with `None` or an empty string
+pub fn set_proxy_password(password: Option<&str>) -> Result<(), String> {
+    let password = password.filter(|p| !p.is_empty());
+    platform::integration()
+        .keychain_set(PROXY_PASSWORD_ACCOUNT, password)
+        .map_err(|e| format!("Failed to update proxy password in keychain: {}", e))
+}
+
+fn gateway_env_account(profile: &str, name: &str) -> String {
+    format!("gateway-env:{}:{}", profile, name)
+}
+
+/// Secret value of a gateway environment variable for a profile
+pub fn gateway_env_secret(profile: &str, name: &str) -> Option<String> {
+    platform::integration().keychain_get(&gateway_env_account(profile, name))
+}
+
+/// Store a gateway environment secret, or remove it with `None`
+pub fn set_gateway_env_secret(
+    profile: &str,
+    name: &str,
+    value: Option<&str>,
+) -> Result<(), String> {
+    platform::integration()
+        .keychain_set(&gateway_env_account(profile, name), value)
+        .map_err(|e| format!("Failed to update {} in keychain: {}", name, e))
+}
+
+fn config_variable_account(profile: &str, name: &str) -> String {
+    format!("config-var:{}:{}", profile, name)
+}
+
+/// Value of a config template variable for a profile
+pub fn config_variable(profile: &str, name: &str) -> Option<String> {
+    platform::integration().keychain_get(&config_variable_account(profile, name))
+}
+
+/// Store a config template variable, or remove it with `None`
+pub fn set_config_variable(profile: &str, name: &str, value: Option<&str>) -> Result<(), String> {
+    platform::integration()
+        .keychain_set(&config_variable_account(profile, name), value)
+        .map_err(|e| format!("Failed to update {} in keychain: {}", name, e))
+}
+
+fn webhook_account(id: &str) -> String {
+    format!("webhook:{}", id)
+}
+
+/// Signing secret of a webhook
+pub fn webhook_secret(id: &str) -> Option<String> {
+    platform::integration().keychain_get(&webhook_account(id))
+}
+
+/// Store a webhook's signing secret, or remove it with `None`
+pub fn set_webhook_secret(id: &str, secret: Option<&str>) -> Result<(), String> {
+    platform::integration()
+        .keychain_set(&webhook_account(id), secret)
+        .map_err(|e| format!("Failed to update webhook secret in keychain: {}", e))
+}
+
+/// Dashboard URL that logs in without putting the token in the address.
+///
+/// The token is sent once as a header to exchange it for a short-lived
+/// one-time code. Gateways that can't issue one are reached through the local
+/// dashboard proxy, which adds the token as a header; the token never goes in
+/// the query string.
+pub async fn dashboard_login_url() -> Result<String, DesktopError> {
+    if kiosk::is_locked() {
+        return Ok(tokenized_dashboard_url());
+    }
+    let Some(token) = crate::read_gateway_token() else {
+        return Ok(dashboard_base_url());
+    };
+    match login_code(&token).await {
+        Some(code) => Ok(format!(
+            "{}?code={}",
+            dashboard_base_url(),
+            urlencoding::encode(&code)
+        )),
+        None => dashboard_proxy::login_url().await,
+    }
+}
+
+/// A one-time login code, or `None` when the gateway doesn't issue them
+async fn login_code(token: &str) -> Option<String> {
+    let client = connection::apply_tls(reqwest::Client::builder().timeout(LOGIN_CODE_TIMEOUT))
+        .ok()?
+        .build()
+        .ok()?;
+    let response = client
+        .post(format!("{}api/auth/login-code", dashboard_base_url()))
+        .bearer_auth(token)
+        .send()
+        .await
+        .ok()?;
+    if !response.status().is_success() {
+        return None;
+    }
+    response
+        .json::<LoginCodeResponse>()
+        .await
+        .ok()
+        .map(|body| body.code)
+}
+
+/// Move the gateway token from `openclaw.json` into the OS keychain.
+///
+/// With `remove_from_config` the plaintext copy is deleted; gateways started by
+/// the app then receive the token through their environment instead.
+#[tauri::command]
+pub fn migrate_token_to_keychain(remove_from_config: bool) -> Result<TokenMigration, DesktopError> {
+    kiosk::ensure_unlocked()?;
+
+    let config_token = read_config_token();
+    let migrated = match (&config_token, keychain_token()) {
+        (Some(token), existing) if existing.as_ref() != Some(token) => {
+            store_token(token)?;
+            true
+        }
+        (None, None) => {
+            return Err(DesktopError::NotFound(
+                "No gateway token to migrate".to_string(),
+            ))
+        }
+        _ => false,
+    };
+
+    let mut removed_from_config = false;
+    if remove_from_config && config_token.is_some() {
+        let mut config = config::load_config()?;
+        if let Some(auth) = config
+            .pointer_mut("/gateway/auth")
+            .and_then(Value::as_object_mut)
+        {
+            removed_from_config = auth.remove("token").is_some();
+        }
+        if removed_from_config {
+            config::write_atomic(&config)?;
+        }
+    }
+
+    Ok(TokenMigration {
+        migrated,
+        removed_from_config,
+    })
+}
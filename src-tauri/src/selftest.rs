@@ -0,0 +1,223 @@
+//! Self-test run once at launch, so a broken install shows up as a checklist
+//! and a tray badge rather than as a confusing failure later on.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use ts_rs::TS;
+
+use crate::error::DesktopError;
+use crate::events::SELFTEST_COMPLETED_EVENT;
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+use crate::{
+    binary, connection, dashboard_base_url, gateway_log_paths, is_gateway_running, preflight, tray,
+};
+
+const DASHBOARD_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Last report, for the tray and frontends that load after launch
+static LAST: Mutex<Option<SelfTestReport>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum SelfTestCheckId {
+    Binary,
+    Config,
+    Token,
+    Port,
+    Dashboard,
+    LogDirectory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum SelfTestStatus {
+    Passed,
+    Failed,
+    /// Needs a running gateway, and there is none
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct SelfTestCheck {
+    pub id: SelfTestCheckId,
+    pub status: SelfTestStatus,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct SelfTestReport {
+    /// No check failed
+    pub ok: bool,
+    /// Unix seconds
+    #[ts(type = "number")]
+    pub ran_at: u64,
+    pub checks: Vec<SelfTestCheck>,
+}
+
+fn check(id: SelfTestCheckId, result: Result<String, String>) -> SelfTestCheck {
+    let (status, message) = match result {
+        Ok(message) => (SelfTestStatus::Passed, message),
+        Err(message) => (SelfTestStatus::Failed, message),
+    };
+    SelfTestCheck {
+        id,
+        status,
+        message,
+    }
+}
+
+fn skipped(id: SelfTestCheckId) -> SelfTestCheck {
+    SelfTestCheck {
+        id,
+        status: SelfTestStatus::Skipped,
+        message: "Gateway is not running".to_string(),
+    }
+}
+
+fn check_binary() -> Result<String, String> {
+    match binary::resolve() {
+        Some((path, _)) => Ok(format!("Found at {}", path.display())),
+        None => Err("The openclaw binary could not be found".to_string()),
+    }
+}
+
+fn check_log_directory() -> Result<String, String> {
+    let (log_path, _) = gateway_log_paths()?;
+    let dir = log_path.parent().unwrap_or(&log_path);
+    let probe = dir.join(".selftest");
+    fs::write(&probe, b"").map_err(|e| format!("Cannot write to {}: {}", dir.display(), e))?;
+    if let Err(e) = fs::remove_file(&probe) {
+        log_error!("Failed to remove {}: {}", probe.display(), e);
+    }
+    Ok(format!("{} is writable", dir.display()))
+}
+
+async fn check_token<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    match app.state::<GatewayApi>().list_sessions().await {
+        Ok(_) => Ok("The gateway accepts the stored token".to_string()),
+        Err(e) if e.kind == GatewayApiErrorKind::Unauthorized => {
+            Err("The gateway rejected the stored token".to_string())
+        }
+        Err(e) => Err(format!("Could not ask the gateway: {}", e)),
+    }
+}
+
+/// Fetch the dashboard the way the webview loads it
+async fn check_dashboard() -> Result<String, String> {
+    let url = dashboard_base_url();
+    let client = connection::apply_tls(reqwest::Client::builder().timeout(DASHBOARD_TIMEOUT))?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Cannot reach {}: {}", url, e))?;
+    if response.status().is_success() {
+        Ok(format!("{} loads", url))
+    } else {
+        Err(format!("{} answered {}", url, response.status()))
+    }
+}
+
+async fn blocking(f: fn() -> Result<String, String>) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Self-test task failed: {}", e))?
+}
+
+async fn run<R: Runtime>(app: &AppHandle<R>) -> SelfTestReport {
+    let running = connection::is_remote()
+        || tauri::async_runtime::spawn_blocking(is_gateway_running)
+            .await
+            .unwrap_or(false);
+    let mut checks = vec![
+        check(SelfTestCheckId::Binary, blocking(check_binary).await),
+        check(
+            SelfTestCheckId::Config,
+            blocking(preflight::check_config).await,
+        ),
+    ];
+    if running {
+        checks.push(check(SelfTestCheckId::Token, check_token(app).await));
+    } else {
+        checks.push(skipped(SelfTestCheckId::Token));
+    }
+    // A remote gateway's port is not ours to probe
+    if !connection::is_remote() {
+        checks.push(check(
+            SelfTestCheckId::Port,
+            blocking(preflight::check_port).await,
+        ));
+    }
+    if running {
+        checks.push(check(SelfTestCheckId::Dashboard, check_dashboard().await));
+    } else {
+        checks.push(skipped(SelfTestCheckId::Dashboard));
+    }
+    checks.push(check(
+        SelfTestCheckId::LogDirectory,
+        blocking(check_log_directory).await,
+    ));
+
+    let report = SelfTestReport {
+        ok: checks.iter().all(|c| c.status != SelfTestStatus::Failed),
+        ran_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        checks,
+    };
+    if let Ok(mut last) = LAST.lock() {
+        *last = Some(report.clone());
+    }
+    if let Err(e) = app.emit(SELFTEST_COMPLETED_EVENT, &report) {
+        log_error!("Failed to emit self-test event: {}", e);
+    }
+    if let Err(e) = tray::rebuild_tray(app) {
+        log_error!("Failed to refresh tray menu: {}", e);
+    }
+    report
+}
+
+/// Number of failed checks in the last run
+pub(crate) fn failure_count() -> usize {
+    LAST.lock()
+        .ok()
+        .and_then(|last| {
+            last.as_ref().map(|report| {
+                report
+                    .checks
+                    .iter()
+                    .filter(|c| c.status == SelfTestStatus::Failed)
+                    .count()
+            })
+        })
+        .unwrap_or(0)
+}
+
+pub fn spawn_startup_selftest<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        run(&app).await;
+    });
+}
+
+/// Check the binary, config, token, port, dashboard and log directory. Runs at
+/// launch; the frontend can re-run it after fixing something.
+#[tauri::command]
+pub async fn run_startup_selftest(app: AppHandle) -> Result<SelfTestReport, DesktopError> {
+    Ok(run(&app).await)
+}
+
+/// Report from the last run, if it has finished
+#[tauri::command]
+pub fn get_selftest_report() -> Option<SelfTestReport> {
+    LAST.lock().ok().and_then(|last| last.clone())
+}
@@ -0,0 +1,426 @@
+#[cfg(not(target_os = "windows"))]
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::platform::GatewayServiceStatus;
+use crate::service_unit::service_spec;
+#[cfg(target_os = "windows")]
+use crate::service_unit::SERVICE_NAME;
+#[cfg(target_os = "macos")]
+use crate::service_unit::{launchd_plist, SERVICE_LABEL};
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+use crate::service_unit::{systemd_unit, UNIT_NAME};
+
+/// Flag the Windows service manager starts the app with to host the gateway
+#[cfg(target_os = "windows")]
+const SERVICE_HOST_FLAG: &str = "--gateway-service";
+
+fn run(command: &mut Command, action: &str) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to {}: {}", action, e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(format!(
+            "Failed to {}: {}",
+            action,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+fn write_script(name: &str, contents: &str) -> Result<PathBuf, String> {
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", name, e))?;
+    Ok(path)
+}
+
+#[cfg(target_os = "macos")]
+mod backend {
+    use super::*;
+
+    const KIND: &str = "launchd";
+
+    fn definition_path() -> PathBuf {
+        PathBuf::from("/Library/LaunchDaemons").join(format!("{}.plist", SERVICE_LABEL))
+    }
+
+    /// Run a shell script as root after the system's admin password prompt
+    fn run_as_admin(script: &std::path::Path, action: &str) -> Result<(), String> {
+        let shell = format!(
+            "do shell script \"/bin/sh '{}'\" with administrator privileges",
+            script.display()
+        );
+        run(Command::new("osascript").args(["-e", &shell]), action).map(|_| ())
+    }
+
+    pub fn install() -> Result<(), String> {
+        let spec = service_spec()?;
+        let staged = write_script("openclaw-gateway.plist", &launchd_plist(&spec)?)?;
+        let target = definition_path();
+        let script = write_script(
+            "install-openclaw-gateway.sh",
+            &format!(
+                "set -e\nlaunchctl bootout system/{label} 2>/dev/null || true\ncp '{staged}' '{target}'\nchown root:wheel '{target}'\nchmod 644 '{target}'\nlaunchctl bootstrap system '{target}'\n",
+                label = SERVICE_LABEL,
+                staged = staged.display(),
+                target = target.display(),
+            ),
+        )?;
+        let result = run_as_admin(&script, "install gateway service");
+        let _ = fs::remove_file(&staged);
+        let _ = fs::remove_file(&script);
+        result
+    }
+
+    pub fn uninstall() -> Result<(), String> {
+        let script = write_script(
+            "uninstall-openclaw-gateway.sh",
+            &format!(
+                "launchctl bootout system/{} 2>/dev/null || true\nrm -f '{}'\n",
+                SERVICE_LABEL,
+                definition_path().display()
+            ),
+        )?;
+        let result = run_as_admin(&script, "uninstall gateway service");
+        let _ = fs::remove_file(&script);
+        result
+    }
+
+    pub fn status() -> GatewayServiceStatus {
+        let path = definition_path();
+        let running = Command::new("launchctl")
+            .args(["print", &format!("system/{}", SERVICE_LABEL)])
+            .output()
+            .is_ok_and(|o| {
+                o.status.success() && String::from_utf8_lossy(&o.stdout).contains("state = running")
+            });
+        GatewayServiceStatus {
+            kind: KIND.to_string(),
+            installed: path.exists(),
+            running,
+            definition_path: Some(path.display().to_string()),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod backend {
+    use super::*;
+
+    const KIND: &str = "systemd";
+
+    fn definition_path() -> Result<PathBuf, String> {
+        let config = dirs::config_dir().ok_or("Could not find config directory")?;
+        Ok(config.join("systemd").join("user").join(UNIT_NAME))
+    }
+
+    fn systemctl(args: &[&str], action: &str) -> Result<String, String> {
+        run(Command::new("systemctl").arg("--user").args(args), action)
+    }
+
+    pub fn install() -> Result<(), String> {
+        let spec = service_spec()?;
+        let path = definition_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create systemd user directory: {}", e))?;
+        }
+        fs::write(&path, systemd_unit(&spec))
+            .map_err(|e| format!("Failed to write unit file: {}", e))?;
+        systemctl(&["daemon-reload"], "reload systemd")?;
+        systemctl(&["enable", "--now", UNIT_NAME], "enable gateway service")?;
+
+        // Keeps user services running after logout
+        if let Ok(user) = std::env::var("USER") {
+            if let Err(e) = run(
+                Command::new("loginctl").args(["enable-linger", &user]),
+                "enable lingering",
+            ) {
+                log_error!("{}", e);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn uninstall() -> Result<(), String> {
+        let path = definition_path()?;
+        if !path.exists() {
+            return Ok(());
+        }
+        if let Err(e) = systemctl(&["disable", "--now", UNIT_NAME], "disable gateway service") {
+            log_error!("{}", e);
+        }
+        fs::remove_file(&path).map_err(|e| format!("Failed to remove unit file: {}", e))?;
+        systemctl(&["daemon-reload"], "reload systemd").map(|_| ())
+    }
+
+    pub fn status() -> GatewayServiceStatus {
+        let path = definition_path().ok();
+        let running = systemctl(&["is-active", UNIT_NAME], "query gateway service")
+            .is_ok_and(|out| out.trim() == "active");
+        GatewayServiceStatus {
+            kind: KIND.to_string(),
+            installed: path.as_ref().is_some_and(|p| p.exists()),
+            running,
+            definition_path: path.map(|p| p.display().to_string()),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod backend {
+    use super::*;
+    use std::os::windows::process::CommandExt;
+
+    const KIND: &str = "windows-service";
+
+    fn sc(args: &[&str]) -> Option<String> {
+        let output = Command::new("sc")
+            .args(args)
+            .creation_flags(crate::CREATE_NO_WINDOW)
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Run a batch script elevated, after the UAC prompt
+    fn run_as_admin(script: &std::path::Path, action: &str) -> Result<(), String> {
+        let script = script.display().to_string().replace('\'', "''");
+        let command = format!(
+            "$p = Start-Process -FilePath cmd.exe -ArgumentList '/c', '\"{}\"' -Verb RunAs -Wait -PassThru -WindowStyle Hidden; exit $p.ExitCode",
+            script
+        );
+        run(
+            Command::new("powershell")
+                .args(["-NoProfile", "-NonInteractive", "-Command", &command])
+                .creation_flags(crate::CREATE_NO_WINDOW),
+            action,
+        )
+        .map(|_| ())
+    }
+
+    pub fn install() -> Result<(), String> {
+        let spec = service_spec()?;
+        let exe = std::env::current_exe()
+            .map_err(|e| format!("Failed to locate app executable: {}", e))?;
+        let home = dirs::home_dir().ok_or("Could not find home directory")?;
+
+        // The service runs as LocalSystem, so it is told where this user's files are
+        let mut bin_path = format!(
+            "\\\"{}\\\" {} --home \\\"{}\\\" --openclaw \\\"{}\\\" --log \\\"{}\\\" --error-log \\\"{}\\\"",
+            exe.display(),
+            SERVICE_HOST_FLAG,
+            home.display(),
+            spec.program.display(),
+            spec.log_path.display(),
+            spec.error_log_path.display()
+        );
+        if let Some(config_path) = &spec.config_path {
+            bin_path.push_str(&format!(" --config \\\"{}\\\"", config_path.display()));
+        }
+        bin_path.push_str(" --");
+        for arg in &spec.args {
+            bin_path.push_str(&format!(" \\\"{}\\\"", arg));
+        }
+
+        let script = write_script(
+            "install-openclaw-gateway.cmd",
+            &format!(
+                "@echo off\r\nsc stop {name} >nul 2>&1\r\nsc delete {name} >nul 2>&1\r\nsc create {name} binPath= \"{bin}\" start= auto DisplayName= \"OpenClaw Gateway\" || exit /b 1\r\nsc failure {name} reset= 86400 actions= restart/5000 >nul\r\nsc start {name} || exit /b 1\r\n",
+                name = SERVICE_NAME,
+                bin = bin_path,
+            ),
+        )?;
+        let result = run_as_admin(&script, "install gateway service");
+        let _ = std::fs::remove_file(&script);
+        result
+    }
+
+    pub fn uninstall() -> Result<(), String> {
+        if sc(&["query", SERVICE_NAME]).is_none() {
+            return Ok(());
+        }
+        let script = write_script(
+            "uninstall-openclaw-gateway.cmd",
+            &format!(
+                "@echo off\r\nsc stop {name} >nul 2>&1\r\nsc delete {name}\r\n",
+                name = SERVICE_NAME
+            ),
+        )?;
+        let result = run_as_admin(&script, "uninstall gateway service");
+        let _ = std::fs::remove_file(&script);
+        result
+    }
+
+    pub fn status() -> GatewayServiceStatus {
+        let query = sc(&["query", SERVICE_NAME]);
+        GatewayServiceStatus {
+            kind: KIND.to_string(),
+            installed: query.is_some(),
+            running: query.is_some_and(|out| out.contains("RUNNING")),
+            definition_path: None,
+        }
+    }
+
+    /// Service host: started by the service manager, keeps the gateway running
+    /// until the service is stopped
+    pub mod host {
+        use super::*;
+        use std::ffi::OsString;
+        use std::fs::OpenOptions;
+        use std::process::{Child, Stdio};
+        use std::sync::mpsc;
+        use std::time::Duration;
+        use windows_service::service::{
+            ServiceControl, ServiceControlAccept, ServiceExitCode, ServiceState, ServiceStatus,
+            ServiceType,
+        };
+        use windows_service::service_control_handler::{self, ServiceControlHandlerResult};
+        use windows_service::{define_windows_service, service_dispatcher};
+
+        const RESTART_DELAY: Duration = Duration::from_secs(5);
+
+        define_windows_service!(ffi_service_main, service_main);
+
+        struct HostArgs {
+            home: PathBuf,
+            openclaw: PathBuf,
+            log: PathBuf,
+            error_log: PathBuf,
+            config: Option<PathBuf>,
+            gateway_args: Vec<String>,
+        }
+
+        fn parse_args() -> Option<HostArgs> {
+            let mut args = std::env::args()
+                .skip_while(|arg| arg != SERVICE_HOST_FLAG)
+                .skip(1);
+            let mut home = None;
+            let mut openclaw = None;
+            let mut config = None;
+            let mut log = None;
+            let mut error_log = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--home" => home = args.next().map(PathBuf::from),
+                    "--openclaw" => openclaw = args.next().map(PathBuf::from),
+                    "--config" => config = args.next().map(PathBuf::from),
+                    "--log" => log = args.next().map(PathBuf::from),
+                    "--error-log" => error_log = args.next().map(PathBuf::from),
+                    "--" => break,
+                    _ => {}
+                }
+            }
+            Some(HostArgs {
+                home: home?,
+                openclaw: openclaw?,
+                log: log?,
+                error_log: error_log?,
+                config,
+                gateway_args: args.collect(),
+            })
+        }
+
+        pub fn is_service_launch() -> bool {
+            std::env::args().any(|arg| arg == SERVICE_HOST_FLAG)
+        }
+
+        pub fn run() {
+            if let Err(e) = service_dispatcher::start(SERVICE_NAME, ffi_service_main) {
+                log_error!("Failed to start gateway service: {}", e);
+            }
+        }
+
+        fn spawn_gateway(args: &HostArgs) -> Result<Child, String> {
+            let open = |path: &PathBuf| {
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+            };
+            let mut command = crate::binary_command(&args.openclaw);
+            command
+                .args(&args.gateway_args)
+                .env("USERPROFILE", &args.home)
+                .env("HOME", &args.home)
+                .stdout(Stdio::from(open(&args.log)?))
+                .stderr(Stdio::from(open(&args.error_log)?))
+                .creation_flags(crate::CREATE_NO_WINDOW);
+            if let Some(config) = &args.config {
+                command.env("OPENCLAW_CONFIG_PATH", config);
+            }
+            command
+                .spawn()
+                .map_err(|e| format!("Failed to start gateway: {}", e))
+        }
+
+        fn service_main(_arguments: Vec<OsString>) {
+            if let Err(e) = run_service() {
+                log_error!("Gateway service failed: {}", e);
+            }
+        }
+
+        fn run_service() -> Result<(), String> {
+            let args = parse_args().ok_or("Missing gateway service arguments")?;
+            let (stop_tx, stop_rx) = mpsc::channel();
+            let handler = move |control| match control {
+                ServiceControl::Stop | ServiceControl::Shutdown => {
+                    let _ = stop_tx.send(());
+                    ServiceControlHandlerResult::NoError
+                }
+                ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
+                _ => ServiceControlHandlerResult::NotImplemented,
+            };
+            let status_handle = service_control_handler::register(SERVICE_NAME, handler)
+                .map_err(|e| format!("Failed to register service handler: {}", e))?;
+            let set_state = |state, accepted| {
+                status_handle.set_service_status(ServiceStatus {
+                    service_type: ServiceType::OWN_PROCESS,
+                    current_state: state,
+                    controls_accepted: accepted,
+                    exit_code: ServiceExitCode::Win32(0),
+                    checkpoint: 0,
+                    wait_hint: Duration::default(),
+                    process_id: None,
+                })
+            };
+            set_state(
+                ServiceState::Running,
+                ServiceControlAccept::STOP | ServiceControlAccept::SHUTDOWN,
+            )
+            .map_err(|e| format!("Failed to report service status: {}", e))?;
+
+            'service: loop {
+                let mut child = spawn_gateway(&args)?;
+                loop {
+                    if stop_rx.recv_timeout(Duration::from_secs(1)).is_ok() {
+                        let _ = crate::gateways::kill_process_tree(&mut child);
+                        break 'service;
+                    }
+                    if matches!(child.try_wait(), Ok(Some(_))) {
+                        break;
+                    }
+                }
+                // Restart a gateway that exited on its own, unless asked to stop meanwhile
+                if stop_rx.recv_timeout(RESTART_DELAY).is_ok() {
+                    break;
+                }
+            }
+
+            set_state(ServiceState::Stopped, ServiceControlAccept::empty())
+                .map_err(|e| format!("Failed to report service status: {}", e))
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub use backend::host;
+pub use backend::{install, status, uninstall};
@@ -0,0 +1,240 @@
+//! Service definitions for the gateway, shared by the installer in `service.rs`
+//! and by [`generate_service_unit`] for users who manage services themselves.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::DesktopError;
+use crate::{
+    active_gateway_port, binary, config, gateway_log_paths, kiosk, profiles, read_config_token,
+    secrets,
+};
+
+pub(crate) const SERVICE_LABEL: &str = "com.ai-dev-2024.openclaw-gateway";
+pub(crate) const UNIT_NAME: &str = "openclaw-gateway.service";
+pub(crate) const SERVICE_NAME: &str = "OpenClawGateway";
+
+/// What the service runs, resolved when it is installed or generated
+pub(crate) struct ServiceSpec {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub config_path: Option<PathBuf>,
+    pub log_path: PathBuf,
+    pub error_log_path: PathBuf,
+}
+
+pub(crate) fn service_spec() -> Result<ServiceSpec, String> {
+    let (program, _) = binary::resolve().ok_or("OpenClaw is not installed")?;
+    // The service can't read the keychain, so the token has to be in the config
+    if secrets::keychain_token().is_some() && read_config_token().is_none() {
+        return Err(
+            "The gateway token is stored in the keychain, which the service cannot read; \
+             move it back into openclaw.json first"
+                .to_string(),
+        );
+    }
+
+    let mut args = Vec::new();
+    if let Some(profile) = profiles::active_profile() {
+        args.extend(["--profile".to_string(), profile]);
+    }
+    args.extend([
+        "gateway".to_string(),
+        "--port".to_string(),
+        active_gateway_port().to_string(),
+    ]);
+    let (log_path, error_log_path) = gateway_log_paths()?;
+    Ok(ServiceSpec {
+        program,
+        args,
+        config_path: config::materialize()?,
+        log_path,
+        error_log_path,
+    })
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub(crate) fn launchd_plist(spec: &ServiceSpec) -> Result<String, String> {
+    let user = std::env::var("USER").map_err(|_| "Could not determine the current user")?;
+    let arguments: String = std::iter::once(spec.program.display().to_string())
+        .chain(spec.args.iter().cloned())
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(&arg)))
+        .collect();
+    let environment = match &spec.config_path {
+        Some(path) => format!(
+            "    <key>EnvironmentVariables</key>\n    <dict>\n        <key>OPENCLAW_CONFIG_PATH</key>\n        <string>{}</string>\n    </dict>\n",
+            xml_escape(&path.display().to_string())
+        ),
+        None => String::new(),
+    };
+    Ok(format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>UserName</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+{}    </array>
+{}    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <true/>
+    <key>StandardOutPath</key>
+    <string>{}</string>
+    <key>StandardErrorPath</key>
+    <string>{}</string>
+</dict>
+</plist>
+"#,
+        SERVICE_LABEL,
+        xml_escape(&user),
+        arguments,
+        environment,
+        xml_escape(&spec.log_path.display().to_string()),
+        xml_escape(&spec.error_log_path.display().to_string()),
+    ))
+}
+
+/// Quote a value for a systemd `ExecStart=` or `Environment=` line
+fn systemd_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+pub(crate) fn systemd_unit(spec: &ServiceSpec) -> String {
+    let exec: Vec<String> = std::iter::once(spec.program.display().to_string())
+        .chain(spec.args.iter().cloned())
+        .map(|arg| systemd_quote(&arg))
+        .collect();
+    let environment = spec
+        .config_path
+        .as_ref()
+        .map(|path| {
+            format!(
+                "Environment={}\n",
+                systemd_quote(&format!("OPENCLAW_CONFIG_PATH={}", path.display()))
+            )
+        })
+        .unwrap_or_default();
+    // PATH from the login shell so npm shims can find node
+    let path = std::env::var("PATH").unwrap_or_default();
+    format!(
+        "[Unit]\nDescription=OpenClaw Gateway\nAfter=network-online.target\n\n[Service]\nExecStart={}\nEnvironment={}\n{}Restart=on-failure\nRestartSec=5\nStandardOutput=append:{}\nStandardError=append:{}\n\n[Install]\nWantedBy=default.target\n",
+        exec.join(" "),
+        systemd_quote(&format!("PATH={}", path)),
+        environment,
+        spec.log_path.display(),
+        spec.error_log_path.display(),
+    )
+}
+
+/// Quote an argument for a `cmd.exe` command line
+fn cmd_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+/// Batch script registering the gateway with NSSM (the Non-Sucking Service Manager)
+pub(crate) fn nssm_script(spec: &ServiceSpec) -> String {
+    // npm's `.cmd` shims can't be started directly by the service manager
+    let shim = spec
+        .program
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cmd"));
+    let (program, mut args) = if shim {
+        (
+            "cmd.exe".to_string(),
+            vec!["/c".to_string(), spec.program.display().to_string()],
+        )
+    } else {
+        (spec.program.display().to_string(), Vec::new())
+    };
+    args.extend(spec.args.iter().cloned());
+    let args: Vec<String> = args.iter().map(|arg| cmd_quote(arg)).collect();
+
+    let mut script = format!(
+        "@echo off\r\nnssm install {name} {program} {args}\r\nnssm set {name} DisplayName \"OpenClaw Gateway\"\r\nnssm set {name} Start SERVICE_AUTO_START\r\nnssm set {name} AppExit Default Restart\r\nnssm set {name} AppRestartDelay 5000\r\nnssm set {name} AppStdout {stdout}\r\nnssm set {name} AppStderr {stderr}\r\n",
+        name = SERVICE_NAME,
+        program = cmd_quote(&program),
+        args = args.join(" "),
+        stdout = cmd_quote(&spec.log_path.display().to_string()),
+        stderr = cmd_quote(&spec.error_log_path.display().to_string()),
+    );
+    if let Some(path) = &spec.config_path {
+        script.push_str(&format!(
+            "nssm set {} AppEnvironmentExtra {}\r\n",
+            SERVICE_NAME,
+            cmd_quote(&format!("OPENCLAW_CONFIG_PATH={}", path.display()))
+        ));
+    }
+    script
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceUnitKind {
+    Systemd,
+    Launchd,
+    Nssm,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneratedServiceUnit {
+    pub kind: ServiceUnitKind,
+    /// Conventional file name for the definition
+    pub file_name: String,
+    pub contents: String,
+    /// Where the definition was saved, when a path was given
+    pub written_to: Option<String>,
+}
+
+/// Render a service definition for the current binary, port, profile, and
+/// config without installing anything, optionally saving it to `path`
+#[tauri::command]
+pub fn generate_service_unit(
+    kind: ServiceUnitKind,
+    path: Option<String>,
+) -> Result<GeneratedServiceUnit, DesktopError> {
+    let spec = service_spec()?;
+    let (file_name, contents) = match kind {
+        ServiceUnitKind::Systemd => (UNIT_NAME.to_string(), systemd_unit(&spec)),
+        ServiceUnitKind::Launchd => (format!("{}.plist", SERVICE_LABEL), launchd_plist(&spec)?),
+        ServiceUnitKind::Nssm => (
+            "install-openclaw-gateway.cmd".to_string(),
+            nssm_script(&spec),
+        ),
+    };
+
+    let written_to = match path {
+        Some(path) => {
+            kiosk::ensure_unlocked()?;
+            let path = Path::new(&path);
+            if !path.is_absolute() {
+                return Err(DesktopError::InvalidInput(format!(
+                    "Not an absolute path: {}",
+                    path.display()
+                )));
+            }
+            fs::write(path, &contents)
+                .map_err(|e| format!("Failed to write service definition: {}", e))?;
+            Some(path.display().to_string())
+        }
+        None => None,
+    };
+
+    Ok(GeneratedServiceUnit {
+        kind,
+        file_name,
+        contents,
+        written_to,
+    })
+}
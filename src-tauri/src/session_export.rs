@@ -0,0 +1,308 @@
+//! Export a session transcript as Markdown, JSON or a standalone HTML page.
+//!
+//! Message content is either plain text or a list of blocks in the gateway's
+//! format: text, tool calls and their results, and attachments. Attachments
+//! carried inline as base64 stay inline so the export is self-contained.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use tauri::State;
+
+use crate::error::DesktopError;
+use crate::gateway_api::GatewayApi;
+use crate::kiosk;
+use crate::sessions::{self, TranscriptMessage};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Html,
+}
+
+#[derive(Serialize)]
+struct JsonExport<'a> {
+    session_id: &'a str,
+    title: Option<&'a str>,
+    exported_at: String,
+    messages: &'a [TranscriptMessage],
+}
+
+enum Block {
+    Text(String),
+    ToolCall {
+        name: String,
+        input: String,
+    },
+    ToolResult(String),
+    Attachment {
+        name: String,
+        media_type: Option<String>,
+        /// `data:` URI for inline content, otherwise where the gateway keeps it
+        url: Option<String>,
+    },
+}
+
+fn str_field<'a>(value: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter().find_map(|key| value.get(key)?.as_str())
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// Tool results hold a string or their own list of text blocks
+fn plain_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| match str_field(item, &["text"]) {
+                Some(text) => text.to_string(),
+                None => pretty(item),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Value::Null => String::new(),
+        other => pretty(other),
+    }
+}
+
+fn attachment(item: &Value) -> Block {
+    let source = item.get("source").unwrap_or(item);
+    let media_type = str_field(source, &["media_type", "mime_type", "mimeType"])
+        .or_else(|| str_field(item, &["media_type", "mime_type", "mimeType"]))
+        .map(str::to_string);
+    let url = match (str_field(source, &["data"]), &media_type) {
+        (Some(data), Some(media_type)) => Some(format!("data:{};base64,{}", media_type, data)),
+        _ => str_field(source, &["url", "path"]).map(str::to_string),
+    };
+    Block::Attachment {
+        name: str_field(item, &["name", "filename", "title"])
+            .unwrap_or("attachment")
+            .to_string(),
+        media_type,
+        url,
+    }
+}
+
+fn blocks(content: &Value) -> Vec<Block> {
+    let items = match content {
+        Value::String(text) => return vec![Block::Text(text.clone())],
+        Value::Array(items) => items.as_slice(),
+        Value::Null => return Vec::new(),
+        single => std::slice::from_ref(single),
+    };
+    items
+        .iter()
+        .map(
+            |item| match str_field(item, &["type"]).unwrap_or_default() {
+                "text" => Block::Text(str_field(item, &["text"]).unwrap_or_default().to_string()),
+                "tool_use" | "tool_call" | "function_call" => Block::ToolCall {
+                    name: str_field(item, &["name"]).unwrap_or("tool").to_string(),
+                    input: pretty(
+                        item.get("input")
+                            .or_else(|| item.get("arguments"))
+                            .unwrap_or(&Value::Null),
+                    ),
+                },
+                "tool_result" | "function_result" => Block::ToolResult(plain_text(
+                    item.get("content")
+                        .or_else(|| item.get("output"))
+                        .unwrap_or(&Value::Null),
+                )),
+                "image" | "file" | "document" | "attachment" => attachment(item),
+                _ => match item {
+                    Value::String(text) => Block::Text(text.clone()),
+                    other => Block::Text(pretty(other)),
+                },
+            },
+        )
+        .collect()
+}
+
+fn heading(message: &TranscriptMessage) -> String {
+    let mut role = message.role.clone();
+    if let Some(first) = role.get_mut(..1) {
+        first.make_ascii_uppercase();
+    }
+    match &message.timestamp {
+        Some(timestamp) => format!("{} · {}", role, timestamp),
+        None => role,
+    }
+}
+
+/// Code fence longer than any backtick run in `text`, so tool output can't close it
+fn fenced(text: &str, language: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest.max(2) + 1);
+    format!("{}{}\n{}\n{}", fence, language, text, fence)
+}
+
+fn to_markdown(id: &str, title: Option<&str>, messages: &[TranscriptMessage]) -> String {
+    let mut out = format!("# {}\n\nSession `{}`\n", title.unwrap_or(id), id);
+    for message in messages {
+        out.push_str(&format!("\n## {}\n", heading(message)));
+        for block in blocks(&message.content) {
+            out.push('\n');
+            match block {
+                Block::Text(text) => out.push_str(&text),
+                Block::ToolCall { name, input } => out.push_str(&format!(
+                    "**Tool call: {}**\n\n{}",
+                    name,
+                    fenced(&input, "json")
+                )),
+                Block::ToolResult(output) => {
+                    out.push_str(&format!("**Tool output**\n\n{}", fenced(&output, "")))
+                }
+                Block::Attachment {
+                    name,
+                    media_type,
+                    url,
+                } => out.push_str(&match url {
+                    Some(url)
+                        if media_type
+                            .as_deref()
+                            .is_some_and(|m| m.starts_with("image/")) =>
+                    {
+                        format!("![{}]({})", name, url)
+                    }
+                    Some(url) => format!("[{}]({})", name, url),
+                    None => format!("_Attachment: {}_", name),
+                }),
+            }
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn html_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const HTML_STYLE: &str = "body{font-family:system-ui,sans-serif;max-width:50rem;margin:2rem auto;padding:0 1rem;line-height:1.5}\
+section{border-top:1px solid #ddd;padding:.5rem 0}\
+h2{font-size:1rem;color:#555}\
+pre{background:#f5f5f5;padding:.75rem;overflow-x:auto;white-space:pre-wrap}\
+img{max-width:100%}";
+
+fn to_html(id: &str, title: Option<&str>, messages: &[TranscriptMessage]) -> String {
+    let title = html_escape(title.unwrap_or(id));
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n<p>Session <code>{}</code></p>\n",
+        title,
+        HTML_STYLE,
+        title,
+        html_escape(id)
+    );
+    for message in messages {
+        out.push_str(&format!(
+            "<section>\n<h2>{}</h2>\n",
+            html_escape(&heading(message))
+        ));
+        for block in blocks(&message.content) {
+            match block {
+                Block::Text(text) => out.push_str(&format!(
+                    "<p>{}</p>\n",
+                    html_escape(&text).replace('\n', "<br>\n")
+                )),
+                Block::ToolCall { name, input } => out.push_str(&format!(
+                    "<p><strong>Tool call: {}</strong></p>\n<pre>{}</pre>\n",
+                    html_escape(&name),
+                    html_escape(&input)
+                )),
+                Block::ToolResult(output) => out.push_str(&format!(
+                    "<p><strong>Tool output</strong></p>\n<pre>{}</pre>\n",
+                    html_escape(&output)
+                )),
+                Block::Attachment {
+                    name,
+                    media_type,
+                    url,
+                } => out.push_str(&match url {
+                    Some(url)
+                        if media_type
+                            .as_deref()
+                            .is_some_and(|m| m.starts_with("image/")) =>
+                    {
+                        format!(
+                            "<p><img src=\"{}\" alt=\"{}\"></p>\n",
+                            html_escape(&url),
+                            html_escape(&name)
+                        )
+                    }
+                    Some(url) => format!(
+                        "<p><a href=\"{}\" download=\"{}\">{}</a></p>\n",
+                        html_escape(&url),
+                        html_escape(&name),
+                        html_escape(&name)
+                    ),
+                    None => format!("<p><em>Attachment: {}</em></p>\n", html_escape(&name)),
+                }),
+            }
+        }
+        out.push_str("</section>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn render(
+    format: ExportFormat,
+    id: &str,
+    title: Option<&str>,
+    messages: &[TranscriptMessage],
+) -> Result<String, String> {
+    Ok(match format {
+        ExportFormat::Markdown => to_markdown(id, title, messages),
+        ExportFormat::Html => to_html(id, title, messages),
+        ExportFormat::Json => serde_json::to_string_pretty(&JsonExport {
+            session_id: id,
+            title,
+            exported_at: chrono::Utc::now().to_rfc3339(),
+            messages,
+        })
+        .map_err(|e| format!("Failed to serialize session: {}", e))?,
+    })
+}
+
+/// Write a session's transcript, tool calls and attachments included, to `path`.
+/// The transcript comes from the gateway, or from `~/.openclaw/sessions/` while
+/// it is down. Returns the path written.
+#[tauri::command]
+pub async fn export_session(
+    api: State<'_, GatewayApi>,
+    session_id: String,
+    format: ExportFormat,
+    path: String,
+) -> Result<String, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let path = PathBuf::from(path);
+    if !path.is_absolute() {
+        return Err(DesktopError::InvalidInput(
+            "Export path must be absolute".to_string(),
+        ));
+    }
+    let messages = sessions::load_transcript(&api, &session_id).await?;
+    // Only the gateway knows titles; a file export is titled by its id
+    let title = api.list_sessions().await.ok().and_then(|list| {
+        list.into_iter()
+            .find(|s| s.id == session_id)
+            .and_then(|s| s.title)
+    });
+
+    let content = render(format, &session_id, title.as_deref(), &messages)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create export folder: {}", e))?;
+    }
+    fs::write(&path, content).map_err(|e| format!("Failed to write export: {}", e))?;
+    Ok(path.display().to_string())
+}
@@ -0,0 +1,331 @@
+//! Full-text index of session transcripts, kept in SQLite FTS5 under `~/.openclaw`.
+//!
+//! The indexer compares the gateway's session list with what it stored last
+//! time and only re-reads transcripts whose `updated_at` or message count
+//! moved. A finished chat reply marks its session dirty so it is picked up right
+//! away instead of at the next poll.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::Notify;
+
+use crate::error::DesktopError;
+use crate::gateway_api::{GatewayApi, GatewaySession};
+use crate::sessions::{self, TranscriptMessage};
+use crate::{is_gateway_running, openclaw_home_dir};
+
+const DB_FILE: &str = "session-index.db";
+const POLL_INTERVAL: Duration = Duration::from_secs(120);
+const MAX_RESULTS: usize = 50;
+/// Content keys that hold identifiers or encoded data rather than words
+const SKIPPED_KEYS: &[&str] = &[
+    "type",
+    "id",
+    "tool_use_id",
+    "data",
+    "media_type",
+    "mime_type",
+];
+
+static DB: Mutex<Option<Connection>> = Mutex::new(None);
+/// Sessions changed since the last pass, by id
+static DIRTY: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+fn wake() -> &'static Notify {
+    static WAKE: OnceLock<Notify> = OnceLock::new();
+    WAKE.get_or_init(Notify::new)
+}
+
+/// Inclusive range of message times, RFC 3339; either end may be open
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DateRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionSearchHit {
+    pub session_id: String,
+    pub title: Option<String>,
+    pub agent: Option<String>,
+    pub role: String,
+    pub timestamp: Option<String>,
+    /// Matching text with the terms wrapped in `[` `]`
+    pub snippet: String,
+}
+
+fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut guard = DB
+        .lock()
+        .map_err(|_| "Session index lock poisoned".to_string())?;
+    if guard.is_none() {
+        let conn = Connection::open(openclaw_home_dir()?.join(DB_FILE))
+            .map_err(|e| format!("Failed to open session index: {}", e))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS indexed_sessions (
+                id TEXT PRIMARY KEY,
+                title TEXT,
+                agent TEXT,
+                updated_at TEXT,
+                message_count INTEGER
+            );
+            CREATE VIRTUAL TABLE IF NOT EXISTS session_messages USING fts5 (
+                session_id UNINDEXED,
+                role UNINDEXED,
+                timestamp UNINDEXED,
+                unix_time UNINDEXED,
+                text
+            )",
+        )
+        .map_err(|e| format!("Failed to create session index: {}", e))?;
+        *guard = Some(conn);
+    }
+    let conn = guard.as_ref().ok_or("Session index unavailable")?;
+    f(conn).map_err(|e| format!("Session index error: {}", e))
+}
+
+/// Re-index a session soon, e.g. after a reply to it finished
+pub fn mark_dirty(session_id: &str) {
+    if let Ok(mut dirty) = DIRTY.lock() {
+        dirty
+            .get_or_insert_with(HashSet::new)
+            .insert(session_id.to_string());
+    }
+    wake().notify_one();
+}
+
+fn take_dirty() -> HashSet<String> {
+    DIRTY
+        .lock()
+        .ok()
+        .and_then(|mut dirty| dirty.take())
+        .unwrap_or_default()
+}
+
+fn unix_time(timestamp: Option<&str>) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(timestamp?)
+        .ok()
+        .map(|t| t.timestamp())
+}
+
+/// The words in a message, tool input and output included
+fn collect_text(value: &Value, out: &mut String) {
+    match value {
+        Value::String(text) => {
+            out.push_str(text);
+            out.push('\n');
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_text(item, out)),
+        Value::Object(fields) => fields
+            .iter()
+            .filter(|(key, _)| !SKIPPED_KEYS.contains(&key.as_str()))
+            .for_each(|(_, value)| collect_text(value, out)),
+        _ => {}
+    }
+}
+
+/// `updated_at` and message count, to tell whether a session changed since indexing
+type IndexedState = (Option<String>, Option<i64>);
+
+/// Stored state per indexed session
+fn indexed() -> Result<HashMap<String, IndexedState>, String> {
+    with_db(|conn| {
+        let mut statement =
+            conn.prepare("SELECT id, updated_at, message_count FROM indexed_sessions")?;
+        let rows = statement.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, (row.get(1)?, row.get(2)?)))
+        })?;
+        rows.collect()
+    })
+}
+
+fn remove(id: &str) -> Result<(), String> {
+    with_db(|conn| {
+        conn.execute(
+            "DELETE FROM session_messages WHERE session_id = ?1",
+            params![id],
+        )?;
+        conn.execute("DELETE FROM indexed_sessions WHERE id = ?1", params![id])?;
+        Ok(())
+    })
+}
+
+fn store(session: &GatewaySession, messages: &[TranscriptMessage]) -> Result<(), String> {
+    with_db(|conn| {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM session_messages WHERE session_id = ?1",
+            params![session.id],
+        )?;
+        for message in messages {
+            let mut text = String::new();
+            collect_text(&message.content, &mut text);
+            if text.trim().is_empty() {
+                continue;
+            }
+            tx.execute(
+                "INSERT INTO session_messages (session_id, role, timestamp, unix_time, text)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    session.id,
+                    message.role,
+                    message.timestamp,
+                    unix_time(message.timestamp.as_deref())
+                        .or_else(|| unix_time(session.updated_at.as_deref())),
+                    text
+                ],
+            )?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO indexed_sessions (id, title, agent, updated_at, message_count)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                session.id,
+                session.title,
+                session.agent,
+                session.updated_at,
+                session.message_count.map(|n| n as i64)
+            ],
+        )?;
+        tx.commit()
+    })
+}
+
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| format!("Session index task failed: {}", e))?
+}
+
+/// Bring the index in line with the gateway's session list
+async fn sync(api: &GatewayApi, dirty: HashSet<String>) -> Result<(), String> {
+    let listed = api.list_sessions().await.map_err(|e| e.to_string())?;
+    let known = blocking(indexed).await?;
+
+    let live: HashSet<&str> = listed.iter().map(|s| s.id.as_str()).collect();
+    for id in known.keys().filter(|id| !live.contains(id.as_str())) {
+        let id = id.clone();
+        blocking(move || remove(&id)).await?;
+    }
+
+    for session in listed {
+        let unchanged = known.get(&session.id).is_some_and(|(updated_at, count)| {
+            *updated_at == session.updated_at && *count == session.message_count.map(|n| n as i64)
+        });
+        if unchanged && !dirty.contains(&session.id) {
+            continue;
+        }
+        if sessions::check_session_id(&session.id).is_err() {
+            continue;
+        }
+        match sessions::fetch_transcript(api, &session.id).await {
+            Ok(messages) => blocking(move || store(&session, &messages)).await?,
+            Err(e) => log_error!("Failed to index session {}: {}", session.id, e),
+        }
+    }
+    Ok(())
+}
+
+/// Keep the index current while the gateway is up: poll its session list, and
+/// re-index at once when a session is marked dirty
+pub fn spawn_session_indexer<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let dirty = take_dirty();
+            let running = tauri::async_runtime::spawn_blocking(is_gateway_running)
+                .await
+                .unwrap_or(false);
+            // A stopped gateway has nothing new; its sessions stay searchable as indexed
+            if running {
+                if let Err(e) = sync(&app.state::<GatewayApi>(), dirty).await {
+                    log_error!("Failed to update session index: {}", e);
+                }
+            }
+            let _ = tokio::time::timeout(POLL_INTERVAL, wake().notified()).await;
+        }
+    });
+}
+
+/// Each word as an FTS5 string so punctuation can't form query syntax; the last
+/// one matches as a prefix while the user is still typing
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    let (last, rest) = terms.split_last()?;
+    let mut query = rest.join(" ");
+    if !query.is_empty() {
+        query.push(' ');
+    }
+    query.push_str(last);
+    query.push('*');
+    Some(query)
+}
+
+fn run_search(
+    query: &str,
+    agent: Option<&str>,
+    from: Option<i64>,
+    to: Option<i64>,
+) -> Result<Vec<SessionSearchHit>, String> {
+    let Some(query) = fts_query(query) else {
+        return Ok(Vec::new());
+    };
+    with_db(|conn| {
+        let mut statement = conn.prepare(
+            "SELECT m.session_id, s.title, s.agent, m.role, m.timestamp,
+                    snippet(session_messages, 4, '[', ']', '…', 12)
+             FROM session_messages m
+             LEFT JOIN indexed_sessions s ON s.id = m.session_id
+             WHERE session_messages MATCH ?1
+               AND (?2 IS NULL OR s.agent = ?2)
+               AND (?3 IS NULL OR m.unix_time >= ?3)
+               AND (?4 IS NULL OR m.unix_time <= ?4)
+             ORDER BY rank
+             LIMIT ?5",
+        )?;
+        let rows =
+            statement.query_map(params![query, agent, from, to, MAX_RESULTS as i64], |row| {
+                Ok(SessionSearchHit {
+                    session_id: row.get(0)?,
+                    title: row.get(1)?,
+                    agent: row.get(2)?,
+                    role: row.get(3)?,
+                    timestamp: row.get(4)?,
+                    snippet: row.get(5)?,
+                })
+            })?;
+        rows.collect()
+    })
+}
+
+fn range_bound(value: Option<&str>, name: &str) -> Result<Option<i64>, DesktopError> {
+    match value.filter(|v| !v.trim().is_empty()) {
+        Some(text) => unix_time(Some(text))
+            .map(Some)
+            .ok_or_else(|| DesktopError::InvalidInput(format!("Invalid {} date: {}", name, text))),
+        None => Ok(None),
+    }
+}
+
+/// Search indexed transcripts, best matches first. Sessions the gateway has
+/// not reported yet are not included until the indexer reaches them.
+#[tauri::command]
+pub async fn search_sessions(
+    query: String,
+    agent: Option<String>,
+    date_range: Option<DateRange>,
+) -> Result<Vec<SessionSearchHit>, DesktopError> {
+    let range = date_range.unwrap_or_default();
+    let from = range_bound(range.from.as_deref(), "start")?;
+    let to = range_bound(range.to.as_deref(), "end")?;
+    Ok(blocking(move || run_search(&query, agent.as_deref(), from, to)).await?)
+}
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+use tauri::State;
+
+use crate::error::DesktopError;
+use crate::gateway_api::{GatewayApi, GatewayApiError, GatewayApiErrorKind, GatewaySession};
+use crate::kiosk;
+use crate::openclaw_home_dir;
+use crate::paths;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionSource {
+    Gateway,
+    Disk,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub id: String,
+    pub title: Option<String>,
+    pub agent: Option<String>,
+    pub updated_at: Option<String>,
+    pub message_count: Option<u64>,
+    pub source: SessionSource,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptMessage {
+    pub role: String,
+    pub content: serde_json::Value,
+    pub timestamp: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MessagesResponse {
+    List(Vec<TranscriptMessage>),
+    Wrapped { messages: Vec<TranscriptMessage> },
+}
+
+impl From<GatewaySession> for SessionSummary {
+    fn from(session: GatewaySession) -> Self {
+        Self {
+            id: session.id,
+            title: session.title,
+            agent: session.agent,
+            updated_at: session.updated_at,
+            message_count: session.message_count,
+            source: SessionSource::Gateway,
+        }
+    }
+}
+
+fn sessions_dir() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join("sessions"))
+}
+
+/// Session ids become file names when reading from disk, so keep them plain
+pub(crate) fn check_session_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && !id.starts_with('.');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid session id: {}", id))
+    }
+}
+
+/// Transcript file for a session: `<id>.jsonl` (one message per line) or `<id>.json`
+fn session_file(id: &str) -> Result<Option<PathBuf>, String> {
+    let dir = sessions_dir()?;
+    for ext in ["jsonl", "json"] {
+        let path = paths::join_within(&dir, format!("{}.{}", id, ext))?;
+        if path.exists() {
+            return Ok(Some(path));
+        }
+    }
+    Ok(None)
+}
+
+fn read_transcript_file(id: &str) -> Result<Vec<TranscriptMessage>, String> {
+    let path = session_file(id)?.ok_or_else(|| format!("Session {} not found", id))?;
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read session: {}", e))?;
+
+    if path.extension().is_some_and(|ext| ext == "jsonl") {
+        Ok(content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect())
+    } else {
+        match serde_json::from_str(&content) {
+            Ok(MessagesResponse::List(messages) | MessagesResponse::Wrapped { messages }) => {
+                Ok(messages)
+            }
+            Err(e) => Err(format!("Failed to parse session: {}", e)),
+        }
+    }
+}
+
+fn list_session_files() -> Result<Vec<SessionSummary>, String> {
+    let dir = sessions_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let entries =
+        fs::read_dir(&dir).map_err(|e| format!("Failed to read sessions directory: {}", e))?;
+
+    let mut sessions: Vec<(u64, SessionSummary)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let ext = path.extension()?.to_str()?;
+            if ext != "jsonl" && ext != "json" {
+                return None;
+            }
+            let id = path.file_stem()?.to_str()?.to_string();
+            let modified = entry
+                .metadata()
+                .ok()
+                .and_then(|m| m.modified().ok())
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            Some((
+                modified,
+                SessionSummary {
+                    id,
+                    title: None,
+                    agent: None,
+                    updated_at: Some(modified.to_string()),
+                    message_count: None,
+                    source: SessionSource::Disk,
+                },
+            ))
+        })
+        .collect();
+    sessions.sort_by_key(|(modified, _)| Reverse(*modified));
+    Ok(sessions.into_iter().map(|(_, s)| s).collect())
+}
+
+/// List sessions from the gateway, or from `~/.openclaw/sessions/` while it is down
+#[tauri::command]
+pub async fn list_sessions(
+    api: State<'_, GatewayApi>,
+) -> Result<Vec<SessionSummary>, DesktopError> {
+    match api.list_sessions().await {
+        Ok(sessions) => Ok(sessions.into_iter().map(SessionSummary::from).collect()),
+        Err(e) if e.kind == GatewayApiErrorKind::NotRunning => Ok(list_session_files()?),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Fetch a session's messages from the gateway
+pub(crate) async fn fetch_transcript(
+    api: &GatewayApi,
+    id: &str,
+) -> Result<Vec<TranscriptMessage>, GatewayApiError> {
+    match api
+        .get::<MessagesResponse>(&format!("/api/sessions/{}/messages", id))
+        .await?
+    {
+        MessagesResponse::List(messages) | MessagesResponse::Wrapped { messages } => Ok(messages),
+    }
+}
+
+/// A session's messages from the gateway, or from its file while the gateway is down
+pub(crate) async fn load_transcript(
+    api: &GatewayApi,
+    id: &str,
+) -> Result<Vec<TranscriptMessage>, DesktopError> {
+    check_session_id(id)?;
+    match fetch_transcript(api, id).await {
+        Ok(messages) => Ok(messages),
+        Err(e) if e.kind == GatewayApiErrorKind::NotRunning => Ok(read_transcript_file(id)?),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[tauri::command]
+pub async fn get_session_transcript(
+    api: State<'_, GatewayApi>,
+    id: String,
+) -> Result<Vec<TranscriptMessage>, DesktopError> {
+    load_transcript(&api, &id).await
+}
+
+#[tauri::command]
+pub async fn delete_session(api: State<'_, GatewayApi>, id: String) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    check_session_id(&id)?;
+    match api.delete(&format!("/api/sessions/{}", id)).await {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind == GatewayApiErrorKind::NotRunning => {
+            let path = session_file(&id)?
+                .ok_or_else(|| DesktopError::NotFound(format!("Session {} not found", id)))?;
+            fs::remove_file(path).map_err(|e| format!("Failed to delete session: {}", e))?;
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
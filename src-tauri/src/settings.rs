@@ -0,0 +1,434 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::sync::watch;
+
+use crate::app_update::AppUpdateSettings;
+use crate::auto_lock::AutoLockSettings;
+use crate::automation_api::AutomationApiSettings;
+use crate::backup::BackupSettings;
+use crate::config;
+use crate::connection::{GatewayConnection, GatewayTls};
+use crate::error::DesktopError;
+use crate::events::SETTINGS_CHANGED_EVENT;
+use crate::gateway_env::GatewayEnvVar;
+use crate::gateway_log_level::GatewayLogSettings;
+use crate::gateways::GatewayDefinition;
+use crate::global_shortcut::GlobalShortcutSettings;
+use crate::idle::IdleSettings;
+use crate::integrations::Webhook;
+use crate::kiosk::{self, KioskSettings};
+use crate::log_rotation::LogRotationSettings;
+use crate::log_stream::LogStreamSettings;
+use crate::login_start::LoginStartSettings;
+use crate::monitor::HealthCheckSettings;
+use crate::notifications::NotificationPreferences;
+use crate::openclaw_home_dir;
+use crate::permissions::{ConsentMode, Permission, PermissionGrant};
+use crate::policy;
+use crate::power::PowerSettings;
+use crate::proxy::ProxySettings;
+use crate::resources::ResourceLimits;
+use crate::safe_mode;
+use crate::safety_mode::SafetyMode;
+use crate::schedule::GatewaySchedule;
+use crate::shutdown::ShutdownPolicy;
+use crate::storage_quota::{RetentionSettings, StorageQuotas};
+use crate::supervisor::AutoRestartSettings;
+use crate::theme::ThemePreference;
+use crate::updater::OpenclawVersions;
+use crate::warmup::WarmupSettings;
+use crate::window::{StartupMode, WindowGeometry};
+use crate::window_state::WindowState;
+use crate::workspaces::WorkspaceWatch;
+
+const SETTINGS_FILE: &str = "desktop-settings.json";
+
+/// Desktop app preferences persisted next to the OpenClaw home directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Badge color per profile name, as `#RRGGBB`
+    pub profile_colors: HashMap<String, String>,
+    /// Profile passed as `--profile` to spawned `openclaw` commands
+    pub active_profile: Option<String>,
+    /// Named gateway instances beyond the default one
+    pub gateways: Vec<GatewayDefinition>,
+    /// Project directories that start their gateway instance on use
+    pub workspaces: Vec<WorkspaceWatch>,
+    /// `openclaw` binary to run instead of the one found automatically
+    pub openclaw_binary_path: Option<PathBuf>,
+    /// Previous and pinned CLI versions, for rollback
+    pub openclaw_versions: OpenclawVersions,
+    pub app_update: AppUpdateSettings,
+    /// Base URLs tried in order by the prebuilt-binary installer
+    pub download_mirrors: Vec<String>,
+    /// Proxy for downloads, registry checks and spawned npm and `openclaw` processes
+    pub proxy: ProxySettings,
+    pub log_rotation: LogRotationSettings,
+    pub log_stream: LogStreamSettings,
+    pub auto_restart: AutoRestartSettings,
+    /// Port of the default gateway when it differs from 18789
+    pub gateway_port: Option<u16>,
+    /// Local gateway, or a remote one the app only controls through its API
+    pub connection: GatewayConnection,
+    pub gateway_tls: GatewayTls,
+    pub notifications: NotificationPreferences,
+    pub kiosk: KioskSettings,
+    pub auto_lock: AutoLockSettings,
+    pub backup: BackupSettings,
+    pub health_check: HealthCheckSettings,
+    /// Requests sent right after the gateway becomes ready to absorb cold-start costs
+    pub warmup: WarmupSettings,
+    pub resource_limits: ResourceLimits,
+    /// Nightly restart and quiet hours for the local gateway
+    pub gateway_schedule: GatewaySchedule,
+    pub startup_mode: StartupMode,
+    /// System-wide hotkey that summons the app or the quick chat
+    pub global_shortcut: GlobalShortcutSettings,
+    /// Where detached log and chat windows were last closed, by window kind
+    pub window_geometry: HashMap<String, WindowGeometry>,
+    /// Main window size, position and maximized state from the last session
+    pub main_window_state: Option<WindowState>,
+    pub login_start: LoginStartSettings,
+    pub shutdown_policy: ShutdownPolicy,
+    /// Language for tray, notification and error text; `None` follows the system
+    pub locale: Option<String>,
+    /// The first-run setup wizard was finished or skipped
+    pub onboarding_dismissed: bool,
+    /// Names of the `${VAR}` placeholders set for the config template, per profile
+    /// name (`default` when no profile is active); their values are in the keychain
+    /// and the environment is the fallback
+    pub config_variables: HashMap<String, Vec<String>>,
+    /// Extra environment for the gateway process, per profile name like `config_variables`
+    pub gateway_env: HashMap<String, Vec<GatewayEnvVar>>,
+    /// Sensitive capabilities the user allowed when first asked
+    pub permission_grants: Vec<PermissionGrant>,
+    /// How each capability asks for consent; missing ones use their default
+    pub permission_modes: HashMap<Permission, ConsentMode>,
+    /// Loopback HTTP API for scripts
+    pub automation_api: AutomationApiSettings,
+    /// Endpoints that receive gateway events; their secrets are in the keychain
+    pub webhooks: Vec<Webhook>,
+    /// Allow `capture_screenshot`; each capture still asks for consent
+    pub screen_capture: bool,
+    /// What to do while the OS session is locked or idle
+    pub idle: IdleSettings,
+    /// What to do with the gateway while on battery
+    pub power: PowerSettings,
+    /// Size limits for logs, sessions and caches, and the low-disk warning
+    pub storage_quotas: StorageQuotas,
+    /// How long rotated logs and session transcripts are kept
+    pub retention: RetentionSettings,
+    /// How verbosely the gateway logs
+    pub gateway_log: GatewayLogSettings,
+    /// Zoom factor of the app's windows; unset is 100%
+    pub dashboard_zoom: Option<f64>,
+    /// Light or dark mode for the app and the dashboard
+    pub theme: ThemePreference,
+    /// Whether agents may run shell commands and write files
+    pub safety_mode: SafetyMode,
+}
+
+/// Process-wide settings shared by commands, the tray, and background tasks.
+///
+/// Readers get the latest value from the watch channel without touching disk;
+/// writers are serialized so concurrent updates can't drop each other's changes.
+/// The channel carries the effective settings (policy applied), while `user`
+/// keeps what the user chose, which is all that gets persisted.
+struct SettingsStore {
+    tx: watch::Sender<Settings>,
+    user: Mutex<Settings>,
+}
+
+static STORE: OnceLock<SettingsStore> = OnceLock::new();
+
+fn store() -> &'static SettingsStore {
+    STORE.get_or_init(|| {
+        // Safe mode ignores saved settings in case they are what breaks startup
+        let user = if safe_mode::is_active() {
+            Settings::default()
+        } else {
+            load_settings()
+        };
+        let (tx, _rx) = watch::channel(policy::apply(&user));
+        SettingsStore {
+            tx,
+            user: Mutex::new(user),
+        }
+    })
+}
+
+fn settings_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(SETTINGS_FILE))
+}
+
+/// Load settings from disk, falling back to defaults when missing or unreadable
+fn load_settings() -> Settings {
+    let path = match settings_path() {
+        Ok(path) => path,
+        Err(e) => {
+            log_error!("Failed to resolve settings path: {}", e);
+            return Settings::default();
+        }
+    };
+
+    if !path.exists() {
+        return Settings::default();
+    }
+
+    let parsed = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str::<Value>(&content).map_err(|e| e.to_string()));
+    match parsed {
+        Ok(mut raw) => {
+            let migrated = config::migrate_plaintext_variables(&mut raw);
+            match serde_json::from_value::<Settings>(raw) {
+                Ok(settings) => {
+                    if migrated {
+                        if let Err(e) = save_settings(&settings) {
+                            log_error!("Failed to save migrated settings: {}", e);
+                        }
+                    }
+                    settings
+                }
+                Err(e) => {
+                    log_error!("Failed to parse settings, using defaults: {}", e);
+                    Settings::default()
+                }
+            }
+        }
+        Err(e) => {
+            log_error!("Failed to load settings, using defaults: {}", e);
+            Settings::default()
+        }
+    }
+}
+
+fn save_settings(settings: &Settings) -> Result<(), String> {
+    // Changes made in safe mode last only for the session
+    if safe_mode::is_active() {
+        return Ok(());
+    }
+    let path = settings_path()?;
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write settings: {}", e))
+}
+
+/// Snapshot of the current settings
+pub fn current() -> Settings {
+    store().tx.borrow().clone()
+}
+
+/// Observe settings changes from a background task
+pub fn subscribe() -> watch::Receiver<Settings> {
+    store().tx.subscribe()
+}
+
+/// Apply a change, persist it, and notify every subscriber
+pub fn update<F>(change: F) -> Result<Settings, String>
+where
+    F: FnOnce(&mut Settings),
+{
+    let store = store();
+    let mut user = store
+        .user
+        .lock()
+        .map_err(|_| "Settings lock poisoned".to_string())?;
+
+    let before = store.tx.borrow().clone();
+    let mut next = before.clone();
+    change(&mut next);
+    if next == before {
+        return Ok(next);
+    }
+    policy::ensure_unlocked(&before, &next)?;
+
+    // Locked keys are unchanged, so the user's own values for them can be kept
+    let next_user = policy::restore_unlocked(&next, &user);
+    save_settings(&next_user)?;
+    *user = next_user;
+    store.tx.send_replace(next.clone());
+    Ok(next)
+}
+
+/// Forward settings changes to the webview and refresh settings-dependent UI
+pub fn spawn_change_notifier<R: Runtime>(app: AppHandle<R>) {
+    let mut rx = subscribe();
+    tauri::async_runtime::spawn(async move {
+        while rx.changed().await.is_ok() {
+            let settings = rx.borrow_and_update().clone();
+
+            if let Err(e) = crate::tray::rebuild_tray(&app) {
+                log_error!("Failed to refresh tray after settings change: {}", e);
+            }
+            if let Err(e) = app.emit(SETTINGS_CHANGED_EVENT, settings) {
+                log_error!("Failed to emit settings event: {}", e);
+            }
+            crate::app_state::refresh_profile(&app);
+        }
+    });
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsView {
+    #[serde(flatten)]
+    pub settings: Settings,
+    /// Dotted keys fixed by the machine's deployment policy
+    pub locked_keys: Vec<String>,
+}
+
+/// Get the current desktop settings
+#[tauri::command]
+pub fn get_settings() -> SettingsView {
+    SettingsView {
+        settings: current(),
+        locked_keys: policy::locked_keys(),
+    }
+}
+
+/// Groups of settings that can be reset to defaults independently
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsSection {
+    Profiles,
+    Notifications,
+    GatewayLaunch,
+    Logs,
+    Backup,
+    Startup,
+    Workspaces,
+}
+
+impl SettingsSection {
+    /// Top-level settings keys that belong to the section
+    fn keys(self) -> &'static [&'static str] {
+        match self {
+            Self::Profiles => &[
+                "profile_colors",
+                "active_profile",
+                "gateways",
+                "config_variables",
+                "gateway_env",
+            ],
+            Self::Notifications => &["notifications"],
+            Self::GatewayLaunch => &[
+                "gateway_port",
+                "connection",
+                "gateway_tls",
+                "auto_restart",
+                "health_check",
+                "warmup",
+                "resource_limits",
+                "gateway_schedule",
+                "shutdown_policy",
+                "download_mirrors",
+                "proxy",
+                "openclaw_binary_path",
+                "openclaw_versions",
+                "safety_mode",
+            ],
+            Self::Logs => &[
+                "log_rotation",
+                "log_stream",
+                "storage_quotas",
+                "retention",
+                "gateway_log",
+            ],
+            Self::Backup => &["backup"],
+            Self::Startup => &[
+                "app_update",
+                "startup_mode",
+                "window_geometry",
+                "main_window_state",
+                "dashboard_zoom",
+                "theme",
+                "login_start",
+                "global_shortcut",
+                "automation_api",
+                "webhooks",
+            ],
+            Self::Workspaces => &["workspaces"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsResetResult {
+    /// Keys whose value actually changed
+    pub changed: Vec<String>,
+    /// Copy of the settings file taken before the reset
+    pub backup_path: Option<String>,
+}
+
+/// Copy the saved settings aside before a destructive change
+fn backup_settings_file() -> Result<Option<PathBuf>, String> {
+    let path = settings_path()?;
+    if !path.exists() || safe_mode::is_active() {
+        return Ok(None);
+    }
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let backup = path.with_file_name(format!("desktop-settings.{}.bak.json", stamp));
+    fs::copy(&path, &backup).map_err(|e| format!("Failed to back up settings: {}", e))?;
+    Ok(Some(backup))
+}
+
+/// Reset the given sections to their defaults, leaving everything else alone.
+/// Keys locked by policy keep their enforced values.
+#[tauri::command]
+pub fn reset_settings(sections: Vec<SettingsSection>) -> Result<SettingsResetResult, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let backup_path = backup_settings_file()?;
+
+    let before = serde_json::to_value(current())
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let defaults = serde_json::to_value(Settings::default())
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let mut next = before.clone();
+    for key in sections.iter().flat_map(|s| s.keys()) {
+        if let (Some(slot), Some(default)) = (next.get_mut(*key), defaults.get(*key)) {
+            *slot = default.clone();
+        }
+    }
+    let next: Settings =
+        serde_json::from_value(next).map_err(|e| format!("Failed to reset settings: {}", e))?;
+
+    let after = update(|current| *current = policy::apply(&next))?;
+    let after =
+        serde_json::to_value(after).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let changed = sections
+        .iter()
+        .flat_map(|s| s.keys())
+        .filter(|key| before.get(**key) != after.get(**key))
+        .map(|key| key.to_string())
+        .collect();
+
+    Ok(SettingsResetResult {
+        changed,
+        backup_path: backup_path.map(|p| p.display().to_string()),
+    })
+}
+
+/// Replace the desktop settings
+#[tauri::command]
+pub fn update_settings(settings: Settings) -> Result<Settings, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    // Grants and consent modes only change through the consent prompt,
+    // `revoke_permission` and `set_permission_mode`
+    Ok(update(|current| {
+        let grants = std::mem::take(&mut current.permission_grants);
+        let modes = std::mem::take(&mut current.permission_modes);
+        *current = settings;
+        current.permission_grants = grants;
+        current.permission_modes = modes;
+    })?)
+}
@@ -0,0 +1,159 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Runtime};
+
+use crate::error::DesktopError;
+use crate::{audit, external, settings};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareMethod {
+    /// The OS share sheet was shown
+    ShareSheet,
+    /// No share sheet on this platform (or it failed), so the file was revealed instead
+    Revealed,
+}
+
+/// Backups may live outside the OpenClaw folders, so the configured destination is allowed too
+fn check_shareable<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<PathBuf, String> {
+    if let Some(destination) = settings::current().backup.destination {
+        if let (Ok(root), Ok(resolved)) =
+            (Path::new(&destination).canonicalize(), path.canonicalize())
+        {
+            if resolved.starts_with(&root) {
+                return Ok(resolved);
+            }
+        }
+    }
+    external::check_file(app, path)
+}
+
+#[cfg(target_os = "macos")]
+type Picker = objc2::rc::Retained<objc2_app_kit::NSSharingServicePicker>;
+
+#[cfg(target_os = "macos")]
+thread_local! {
+    /// The picker on screen; AppKit doesn't keep it alive while it is shown
+    static PICKER: std::cell::RefCell<Option<Picker>> = const { std::cell::RefCell::new(None) };
+}
+
+/// Synchronous commands run on the main thread, which AppKit requires
+#[cfg(target_os = "macos")]
+fn show_share_sheet<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<(), String> {
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2_app_kit::{NSSharingServicePicker, NSView};
+    use objc2_foundation::{MainThreadMarker, NSArray, NSRectEdge, NSString, NSURL};
+    use tauri::Manager;
+
+    let mtm = MainThreadMarker::new().ok_or("Share sheet must be shown from the main thread")?;
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Main window is not available")?;
+    let view = window
+        .ns_view()
+        .map_err(|e| format!("Failed to get window view: {}", e))?;
+
+    unsafe {
+        let url = NSURL::fileURLWithPath(&NSString::from_str(&path.to_string_lossy()));
+        let item: Retained<AnyObject> = Retained::cast(url);
+        let items = NSArray::from_vec(vec![item]);
+        let picker =
+            NSSharingServicePicker::initWithItems(mtm.alloc::<NSSharingServicePicker>(), &items);
+        let view = &*(view as *const NSView);
+        picker.showRelativeToRect_ofView_preferredEdge(view.bounds(), view, NSRectEdge::NSMinYEdge);
+        PICKER.with(|current| *current.borrow_mut() = Some(picker));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn show_share_sheet<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<(), String> {
+    use std::sync::{Arc, OnceLock};
+    use tauri::Manager;
+    use windows::core::{factory, Interface, HSTRING};
+    use windows::ApplicationModel::DataTransfer::{DataRequestedEventArgs, DataTransferManager};
+    use windows::Foundation::Collections::IIterable;
+    use windows::Foundation::TypedEventHandler;
+    use windows::Storage::{IStorageItem, StorageFile};
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::Shell::IDataTransferManagerInterop;
+
+    let window = app
+        .get_webview_window("main")
+        .ok_or("Main window is not available")?;
+    let hwnd = HWND(
+        window
+            .hwnd()
+            .map_err(|e| format!("Failed to get window handle: {}", e))?
+            .0 as _,
+    );
+    let title = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let path = HSTRING::from(path.as_os_str());
+
+    let share = || -> windows::core::Result<()> {
+        let file: IStorageItem = StorageFile::GetFileFromPathAsync(&path)?.get()?.cast()?;
+        let interop = factory::<DataTransferManager, IDataTransferManagerInterop>()?;
+        let manager: DataTransferManager = unsafe { interop.GetForWindow(hwnd)? };
+        let title = HSTRING::from(title);
+        // The manager is per window and outlives this call, so the handler
+        // unregisters itself once it has served this share
+        let registration = Arc::new(OnceLock::new());
+        let handler_registration = registration.clone();
+        let token = manager.DataRequested(&TypedEventHandler::new(
+            move |sender: &Option<DataTransferManager>, args: &Option<DataRequestedEventArgs>| {
+                if let (Some(sender), Some(token)) = (sender, handler_registration.get()) {
+                    sender.RemoveDataRequested(*token)?;
+                }
+                if let Some(args) = args {
+                    let data = args.Request()?.Data()?;
+                    data.Properties()?.SetTitle(&title)?;
+                    let items = IIterable::<IStorageItem>::from(vec![Some(file.clone())]);
+                    data.SetStorageItemsReadOnly(&items)?;
+                }
+                Ok(())
+            },
+        ))?;
+        let _ = registration.set(token);
+        let shown = unsafe { interop.ShowShareUIForWindow(hwnd) };
+        if shown.is_err() {
+            let _ = manager.RemoveDataRequested(token);
+        }
+        shown
+    };
+    share().map_err(|e| format!("Failed to show share sheet: {}", e))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn show_share_sheet<R: Runtime>(_app: &AppHandle<R>, _path: &Path) -> Result<(), String> {
+    Err("No share sheet on this platform".to_string())
+}
+
+/// Offer an exported file (diagnostics bundle, log report, backup) through the OS share
+/// sheet, falling back to revealing it in the file manager
+#[tauri::command]
+pub fn share_file(app: AppHandle, path: String) -> Result<ShareMethod, DesktopError> {
+    let result =
+        check_shareable(&app, Path::new(&path)).and_then(|resolved| {
+            match show_share_sheet(&app, &resolved) {
+                Ok(()) => Ok(ShareMethod::ShareSheet),
+                Err(e) => {
+                    log_error!("{}; revealing file instead", e);
+                    opener::reveal(&resolved)
+                        .map(|_| ShareMethod::Revealed)
+                        .map_err(|e| format!("Failed to reveal file: {}", e))
+                }
+            }
+        });
+
+    audit::record(
+        "share_file",
+        &path,
+        result.is_ok(),
+        result.as_ref().err().cloned(),
+    );
+    Ok(result?)
+}
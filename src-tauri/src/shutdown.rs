@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::error::DesktopError;
+use crate::events::{QuitRequest, QUIT_REQUESTED_EVENT};
+use crate::gateway_api::GatewayApi;
+use crate::gateway_process::GatewayProcess;
+use crate::gateways::GatewayManager;
+use crate::keeper;
+use crate::{is_gateway_running, kiosk, settings, stop_default_gateway};
+
+/// What happens to the gateway when the app quits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownPolicy {
+    /// Stop the gateway however it was started
+    AlwaysStop,
+    /// Stop only gateway processes this app spawned
+    #[default]
+    StopIfStarted,
+    /// Leave the gateway running
+    Never,
+    /// Hand a gateway this app spawned to the background keeper, which restarts
+    /// it after crashes until the app is opened again
+    Keeper,
+}
+
+const ACTIVE_STATUSES: [&str; 3] = ["running", "active", "busy"];
+
+async fn active_sessions<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    if !is_gateway_running() {
+        return Vec::new();
+    }
+    app.state::<GatewayApi>()
+        .list_sessions()
+        .await
+        .map(|sessions| {
+            sessions
+                .into_iter()
+                .filter(|s| {
+                    s.status
+                        .as_deref()
+                        .is_some_and(|status| ACTIVE_STATUSES.contains(&status))
+                })
+                .map(|s| s.title.unwrap_or(s.id))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Quit, unless agents are mid-task, in which case ask the frontend to confirm first
+pub(crate) fn request_quit<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let active_sessions = active_sessions(&app).await;
+        if active_sessions.is_empty() {
+            app.exit(0);
+            return;
+        }
+        crate::window::show_main_window(&app);
+        if let Err(e) = app.emit(QUIT_REQUESTED_EVENT, QuitRequest { active_sessions }) {
+            log_error!("Failed to emit quit request: {}", e);
+            app.exit(0);
+        }
+    });
+}
+
+/// Apply the shutdown policy; called once the app is exiting
+pub(crate) fn on_exit<R: Runtime>(app: &AppHandle<R>) {
+    let policy = settings::current().shutdown_policy;
+    if policy == ShutdownPolicy::Never {
+        return;
+    }
+
+    if let Err(e) = app.state::<GatewayManager>().stop_all() {
+        log_error!("Failed to stop gateway instances on quit: {}", e);
+    }
+    let spawned = app.state::<GatewayProcess>().pid().is_some();
+    if policy == ShutdownPolicy::Keeper {
+        // Only the app's own child is handed over; a service or daemon keeps itself alive
+        if spawned {
+            if let Err(e) = app.state::<GatewayProcess>().kill() {
+                log_error!("Failed to stop gateway for the keeper: {}", e);
+            } else if let Err(e) = keeper::hand_over() {
+                log_error!("{}", e);
+            }
+        }
+        return;
+    }
+    if policy == ShutdownPolicy::AlwaysStop || spawned {
+        if let Err(e) = stop_default_gateway(app) {
+            log_error!("Failed to stop gateway on quit: {}", e);
+        }
+    }
+}
+
+#[tauri::command]
+pub fn set_shutdown_policy(policy: ShutdownPolicy) -> Result<ShutdownPolicy, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    Ok(settings::update(|s| s.shutdown_policy = policy)?.shutdown_policy)
+}
+
+/// Quit after the user confirmed despite active sessions
+#[tauri::command]
+pub fn confirm_quit(app: AppHandle) {
+    app.exit(0);
+}
+
+/// Quit from the frontend, going through the active-session check
+#[tauri::command]
+pub fn quit_app(app: AppHandle) {
+    request_quit(&app);
+}
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::process::Stdio;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::error::DesktopError;
+use crate::events::{SkillInstallProgress, SKILL_INSTALL_PROGRESS_EVENT};
+use crate::{audit, connection, installer, kiosk};
+
+/// A skill as reported by `openclaw skills list|search --json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Skill {
+    pub name: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    /// Set on search results the user already has
+    #[serde(default)]
+    pub installed: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SkillsResponse {
+    List(Vec<Skill>),
+    Wrapped { skills: Vec<Skill> },
+}
+
+async fn run_skills(args: &[&str]) -> Result<Vec<Skill>, String> {
+    let output = crate::run_openclaw_output(args).await?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("openclaw {} failed", args.join(" "))
+        } else {
+            stderr
+        });
+    }
+    let value: Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Failed to parse skills output: {}", e))?;
+    match serde_json::from_value(value) {
+        Ok(SkillsResponse::List(skills)) | Ok(SkillsResponse::Wrapped { skills }) => Ok(skills),
+        Err(e) => Err(format!("Failed to parse skills output: {}", e)),
+    }
+}
+
+/// Names go straight onto the command line, so anything that could pass for a flag is refused
+fn validate_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.@/".contains(c));
+    if valid {
+        Ok(name)
+    } else {
+        Err(format!("Invalid skill name: {}", name))
+    }
+}
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, name: &str, stage: &'static str, line: String) {
+    let payload = SkillInstallProgress {
+        name: name.to_string(),
+        stage,
+        line,
+    };
+    if let Err(e) = app.emit(SKILL_INSTALL_PROGRESS_EVENT, payload) {
+        log_error!("Failed to emit skill install progress: {}", e);
+    }
+}
+
+fn run_install<R: Runtime>(app: &AppHandle<R>, name: &str) -> Result<(), String> {
+    let mut child = crate::openclaw_command()
+        .args(["skills", "install", name])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start skill install: {}", e))?;
+
+    let progress_app = app.clone();
+    let progress_name = name.to_string();
+    let stderr_lines =
+        installer::pipe_output(child.stdout.take(), child.stderr.take(), move |_, line| {
+            emit_progress(&progress_app, &progress_name, "output", line)
+        });
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for skill install: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(stderr_lines
+            .last()
+            .cloned()
+            .unwrap_or_else(|| format!("Skill install exited with {}", status)))
+    }
+}
+
+/// Skills installed for the active profile
+#[tauri::command]
+pub async fn list_installed_skills() -> Result<Vec<Skill>, DesktopError> {
+    Ok(run_skills(&["skills", "list", "--json"]).await?)
+}
+
+/// Search the skill registry
+#[tauri::command]
+pub async fn search_skills(query: String) -> Result<Vec<Skill>, DesktopError> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(run_skills(&["skills", "search", "--json", "--", &query]).await?)
+}
+
+/// Install a skill, streaming the CLI's output as `skill-install-progress` events
+#[tauri::command]
+pub async fn install_skill(app: AppHandle, name: String) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    connection::ensure_local()?;
+    let name = validate_name(&name)?.to_string();
+    emit_progress(&app, &name, "started", format!("Installing {}", name));
+
+    let task_app = app.clone();
+    let task_name = name.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || run_install(&task_app, &task_name))
+        .await
+        .map_err(|e| format!("Failed to install skill: {}", e))?;
+
+    audit::record("skill_install", &name, result.is_ok(), result.clone().err());
+    match result {
+        Ok(()) => {
+            emit_progress(&app, &name, "finished", format!("Installed {}", name));
+            Ok(())
+        }
+        Err(e) => {
+            emit_progress(&app, &name, "failed", e.clone());
+            Err(e.into())
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::DesktopError;
+use crate::logs::{parse_line, read_page, LogLevel};
+use crate::{
+    active_gateway_port, config, gateway_exit, gateway_log_paths, is_gateway_running, profiles,
+};
+
+const SNAPSHOTS_FILE: &str = "state-snapshots.json";
+const MAX_SNAPSHOTS: usize = 100;
+
+/// How much of each log's tail is scanned for recent errors
+const ERROR_SCAN_BYTES: u64 = 32 * 1024;
+const MAX_RECENT_ERRORS: usize = 10;
+
+/// Serializes read-modify-write of the snapshot file between concurrent actions
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Compact record of gateway state taken just before a lifecycle action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub id: u64,
+    pub taken_at: u64,
+    /// The action about to run, e.g. `start`, `stop`, `restart`, `upgrade`
+    pub action: String,
+    pub running: bool,
+    pub port: u16,
+    pub version: Option<String>,
+    pub profile: Option<String>,
+    /// SHA-256 of `openclaw.json`, `None` when there is no config file
+    pub config_hash: Option<String>,
+    pub last_exit: Option<gateway_exit::ExitReason>,
+    pub recent_errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub from: StateSnapshot,
+    pub to: StateSnapshot,
+    pub changes: Vec<FieldChange>,
+    /// Errors present in the later snapshot but not the earlier one
+    pub new_errors: Vec<String>,
+}
+
+fn snapshots_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(SNAPSHOTS_FILE))
+}
+
+fn load<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<StateSnapshot>, String> {
+    let path = snapshots_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read state snapshots: {}", e))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse state snapshots: {}", e))
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, snapshots: &[StateSnapshot]) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(snapshots)
+        .map_err(|e| format!("Failed to serialize state snapshots: {}", e))?;
+    fs::write(snapshots_path(app)?, content)
+        .map_err(|e| format!("Failed to write state snapshots: {}", e))
+}
+
+fn config_hash() -> Option<String> {
+    let content = fs::read(config::editable_path().ok()?).ok()?;
+    Some(format!("{:x}", Sha256::digest(&content)))
+}
+
+/// Error-level lines from the end of the gateway logs, oldest first
+fn recent_errors() -> Vec<String> {
+    let Ok((log_path, error_log_path)) = gateway_log_paths() else {
+        return Vec::new();
+    };
+
+    let mut errors = Vec::new();
+    for (path, everything_is_error) in [(log_path, false), (error_log_path, true)] {
+        let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let Ok((lines, _, _)) = read_page(&path, size.saturating_sub(ERROR_SCAN_BYTES), u64::MAX)
+        else {
+            continue;
+        };
+        // The first line is usually cut mid-way by the scan window
+        for line in lines.into_iter().skip(1) {
+            let entry = parse_line(0, &line.text);
+            let is_error = entry
+                .level
+                .map_or(everything_is_error, |l| l >= LogLevel::Error);
+            if is_error && !entry.message.is_empty() {
+                errors.push(entry.message);
+            }
+        }
+    }
+
+    if errors.len() > MAX_RECENT_ERRORS {
+        errors.drain(..errors.len() - MAX_RECENT_ERRORS);
+    }
+    errors
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Record the current state before `action` runs; failures are logged, never fatal
+pub fn capture<R: Runtime>(app: &AppHandle<R>, action: &str) {
+    let snapshot = StateSnapshot {
+        id: 0,
+        taken_at: now_secs(),
+        action: action.to_string(),
+        running: is_gateway_running(),
+        port: active_gateway_port(),
+        version: crate::detect_openclaw_version(),
+        profile: profiles::active_profile(),
+        config_hash: config_hash(),
+        last_exit: gateway_exit::last_exit().map(|e| e.reason),
+        recent_errors: recent_errors(),
+    };
+
+    let _guard = WRITE_LOCK.lock();
+    let result = load(app).and_then(|mut snapshots| {
+        let id = snapshots.last().map_or(1, |s| s.id + 1);
+        snapshots.push(StateSnapshot { id, ..snapshot });
+        if snapshots.len() > MAX_SNAPSHOTS {
+            snapshots.drain(..snapshots.len() - MAX_SNAPSHOTS);
+        }
+        save(app, &snapshots)
+    });
+    if let Err(e) = result {
+        log_error!("Failed to record state snapshot: {}", e);
+    }
+}
+
+fn describe<T: Serialize>(value: &Option<T>) -> String {
+    match value {
+        Some(value) => serde_json::to_string(value)
+            .unwrap_or_default()
+            .trim_matches('"')
+            .to_string(),
+        None => "none".to_string(),
+    }
+}
+
+fn diff(from: StateSnapshot, to: StateSnapshot) -> SnapshotDiff {
+    let mut changes = Vec::new();
+    let mut compare = |field: &str, before: String, after: String| {
+        if before != after {
+            changes.push(FieldChange {
+                field: field.to_string(),
+                before,
+                after,
+            });
+        }
+    };
+
+    compare("running", from.running.to_string(), to.running.to_string());
+    compare("port", from.port.to_string(), to.port.to_string());
+    compare("version", describe(&from.version), describe(&to.version));
+    compare("profile", describe(&from.profile), describe(&to.profile));
+    compare(
+        "config_hash",
+        describe(&from.config_hash),
+        describe(&to.config_hash),
+    );
+    compare(
+        "last_exit",
+        describe(&from.last_exit),
+        describe(&to.last_exit),
+    );
+
+    let new_errors = to
+        .recent_errors
+        .iter()
+        .filter(|e| !from.recent_errors.contains(e))
+        .cloned()
+        .collect();
+
+    SnapshotDiff {
+        from,
+        to,
+        changes,
+        new_errors,
+    }
+}
+
+/// List recorded state snapshots, oldest first
+#[tauri::command]
+pub fn list_state_snapshots(app: AppHandle) -> Result<Vec<StateSnapshot>, DesktopError> {
+    Ok(load(&app)?)
+}
+
+/// Compare two snapshots by id, e.g. the last one taken while working against now-broken state
+#[tauri::command]
+pub fn diff_state_snapshots(app: AppHandle, a: u64, b: u64) -> Result<SnapshotDiff, DesktopError> {
+    let snapshots = load(&app)?;
+    let find = |id: u64| {
+        snapshots
+            .iter()
+            .find(|s| s.id == id)
+            .cloned()
+            .ok_or_else(|| DesktopError::NotFound(format!("Snapshot {} not found", id)))
+    };
+    let (first, second) = (find(a)?, find(b)?);
+    Ok(if first.id <= second.id {
+        diff(first, second)
+    } else {
+        diff(second, first)
+    })
+}
@@ -0,0 +1,191 @@
+//! Reaching the local gateway over a unix domain socket or Windows named pipe,
+//! for machines where it may not listen on a TCP port.
+//!
+//! The probe speaks HTTP on the socket directly. reqwest and the dashboard
+//! webview can only dial TCP, so they go through a relay on an ephemeral
+//! loopback port that the app opens for itself and that forwards nothing but
+//! the socket.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::oneshot;
+
+#[cfg(unix)]
+use crate::openclaw_home_dir;
+
+#[cfg(unix)]
+const SOCKET_FILE: &str = "run/gateway.sock";
+#[cfg(windows)]
+const DEFAULT_PIPE: &str = r"\\.\pipe\openclaw-gateway";
+/// `sun_path` is 104 bytes on macOS and 108 on Linux, including the terminator
+#[cfg(unix)]
+const MAX_SOCKET_PATH: usize = 103;
+
+static RELAY: Mutex<Option<Relay>> = Mutex::new(None);
+
+struct Relay {
+    path: PathBuf,
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+}
+
+/// `~/.openclaw/run/gateway.sock`, or the `openclaw-gateway` pipe on Windows
+#[cfg(unix)]
+pub(crate) fn default_path() -> Result<PathBuf, String> {
+    let path = openclaw_home_dir()?.join(SOCKET_FILE);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create runtime directory: {}", e))?;
+    }
+    Ok(path)
+}
+
+#[cfg(windows)]
+pub(crate) fn default_path() -> Result<PathBuf, String> {
+    Ok(PathBuf::from(DEFAULT_PIPE))
+}
+
+/// Reject paths the platform can't use as a socket
+pub(crate) fn check_path(path: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    if path.as_os_str().len() > MAX_SOCKET_PATH {
+        return Err(format!(
+            "Socket path is longer than {} bytes: {}",
+            MAX_SOCKET_PATH,
+            path.display()
+        ));
+    }
+    #[cfg(windows)]
+    if !path.to_string_lossy().starts_with(r"\\.\pipe\") {
+        return Err(format!(
+            "Named pipe must start with \\\\.\\pipe\\: {}",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// Blocking connection for the probe, with the given read and write timeout
+#[cfg(unix)]
+pub(crate) fn connect_blocking(
+    path: &Path,
+    timeout: Duration,
+) -> Result<impl Read + Write, String> {
+    let stream = std::os::unix::net::UnixStream::connect(path)
+        .map_err(|e| format!("Failed to connect to {}: {}", path.display(), e))?;
+    stream.set_read_timeout(Some(timeout)).ok();
+    stream.set_write_timeout(Some(timeout)).ok();
+    Ok(stream)
+}
+
+#[cfg(windows)]
+pub(crate) fn connect_blocking(
+    path: &Path,
+    _timeout: Duration,
+) -> Result<impl Read + Write, String> {
+    std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .map_err(|e| format!("Failed to connect to {}: {}", path.display(), e))
+}
+
+/// Remove a socket file left behind by a gateway that didn't shut down cleanly,
+/// so a new one can bind it
+#[cfg(unix)]
+pub(crate) fn remove_stale(path: &Path) {
+    if path.exists() && std::os::unix::net::UnixStream::connect(path).is_err() {
+        if let Err(e) = std::fs::remove_file(path) {
+            log_error!("Failed to remove stale gateway socket: {}", e);
+        }
+    }
+}
+
+#[cfg(windows)]
+pub(crate) fn remove_stale(_path: &Path) {}
+
+#[cfg(unix)]
+async fn splice(mut client: TcpStream, path: &Path) -> Result<(), String> {
+    let mut upstream = tokio::net::UnixStream::connect(path)
+        .await
+        .map_err(|e| format!("Failed to connect to {}: {}", path.display(), e))?;
+    let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+    Ok(())
+}
+
+#[cfg(windows)]
+async fn splice(mut client: TcpStream, path: &Path) -> Result<(), String> {
+    let mut upstream = tokio::net::windows::named_pipe::ClientOptions::new()
+        .open(path)
+        .map_err(|e| format!("Failed to connect to {}: {}", path.display(), e))?;
+    let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+    Ok(())
+}
+
+async fn serve(listener: std::net::TcpListener, path: PathBuf, mut stopped: oneshot::Receiver<()>) {
+    let listener = match tokio::net::TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log_error!("Failed to start gateway socket relay: {}", e);
+            return;
+        }
+    };
+    loop {
+        tokio::select! {
+            _ = &mut stopped => break,
+            accepted = listener.accept() => match accepted {
+                Ok((client, _)) => {
+                    let path = path.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = splice(client, &path).await {
+                            log_error!("Gateway socket relay: {}", e);
+                        }
+                    });
+                }
+                Err(e) => log_error!("Gateway socket relay failed to accept: {}", e),
+            }
+        }
+    }
+}
+
+/// Loopback port relaying to the gateway socket at `path`, started on first
+/// use and restarted when the path changes
+pub(crate) fn relay_port(path: &Path) -> Result<u16, String> {
+    let mut relay = RELAY
+        .lock()
+        .map_err(|_| "Socket relay lock poisoned".to_string())?;
+    if let Some(running) = relay.as_ref().filter(|r| r.path == path) {
+        return Ok(running.port);
+    }
+    if let Some(old) = relay.take() {
+        let _ = old.shutdown.send(());
+    }
+
+    let listener = std::net::TcpListener::bind(("127.0.0.1", 0))
+        .map_err(|e| format!("Failed to start gateway socket relay: {}", e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to start gateway socket relay: {}", e))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("Failed to start gateway socket relay: {}", e))?
+        .port();
+    let (shutdown, stopped) = oneshot::channel();
+    tauri::async_runtime::spawn(serve(listener, path.to_path_buf(), stopped));
+    *relay = Some(Relay {
+        path: path.to_path_buf(),
+        port,
+        shutdown,
+    });
+    Ok(port)
+}
+
+/// Close the relay, e.g. after switching back to a TCP connection
+pub(crate) fn stop_relay() {
+    if let Some(relay) = RELAY.lock().ok().and_then(|mut relay| relay.take()) {
+        let _ = relay.shutdown.send(());
+    }
+}
@@ -0,0 +1,169 @@
+//! Keeping two starts of the gateway from racing, whether from the tray and the
+//! UI at once or from a second app instance.
+//!
+//! Within the app a flag guards the start; across processes
+//! `~/.openclaw/gateway.lock` names the pid of whoever is starting. The lock is
+//! held until the gateway answers its health check, exits, or the start times
+//! out. A lock whose process is gone, or that is older than any start could
+//! take, is stale and gets taken over.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::gateway_process::GatewayProcess;
+use crate::readiness::START_TIMEOUT;
+use crate::{openclaw_home_dir, probe};
+
+const LOCK_FILE: &str = "gateway.lock";
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Well past the readiness timeout, in case the pid was reused
+const STALE_AFTER: Duration = Duration::from_secs(120);
+
+static STARTING: AtomicBool = AtomicBool::new(false);
+
+/// Who is starting the gateway
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StartLock {
+    pub pid: u32,
+    /// Unix seconds
+    pub since: u64,
+}
+
+impl StartLock {
+    fn ours() -> Self {
+        Self {
+            pid: std::process::id(),
+            since: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn is_stale(&self) -> bool {
+        let age = Self::ours().since.saturating_sub(self.since);
+        if self.pid == std::process::id() || age > STALE_AFTER.as_secs() {
+            return true;
+        }
+        let pid = Pid::from_u32(self.pid);
+        let mut system = System::new();
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            true,
+            ProcessRefreshKind::new(),
+        );
+        system.process(pid).is_none()
+    }
+
+    pub fn message(&self) -> String {
+        if self.pid == std::process::id() {
+            "Gateway is already starting".to_string()
+        } else {
+            format!("Gateway is already being started by process {}", self.pid)
+        }
+    }
+}
+
+/// Held while a start is in progress; dropping it releases the lock
+pub(crate) struct StartGuard {
+    /// Whether this guard wrote the lock file, as opposed to only the in-app flag
+    file: bool,
+}
+
+impl StartGuard {
+    /// Keep the lock until the gateway is ready, the spawned process exits, or
+    /// the start times out
+    pub(crate) fn release_when_ready<R: Runtime>(self, app: &AppHandle<R>) {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let started = Instant::now();
+            let spawned_pid = app.state::<GatewayProcess>().pid();
+            while started.elapsed() < START_TIMEOUT {
+                let ready = tauri::async_runtime::spawn_blocking(probe::probe_gateway)
+                    .await
+                    .is_ok_and(|probe| probe.verified);
+                let exited = app.state::<GatewayProcess>().pid() != spawned_pid;
+                if ready || exited {
+                    break;
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+            drop(self);
+        });
+    }
+}
+
+impl Drop for StartGuard {
+    fn drop(&mut self) {
+        if self.file {
+            if let Ok(path) = lock_path() {
+                let _ = fs::remove_file(path);
+            }
+        }
+        STARTING.store(false, Ordering::SeqCst);
+    }
+}
+
+fn lock_path() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(LOCK_FILE))
+}
+
+fn read_lock(path: &PathBuf) -> Option<StartLock> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Write the lock file, failing only if someone else holds a live one
+fn lock_file() -> Result<bool, StartLock> {
+    let path = match lock_path() {
+        Ok(path) => path,
+        Err(e) => {
+            log_error!("Failed to lock gateway start: {}", e);
+            return Ok(false);
+        }
+    };
+    // A second attempt follows taking over a stale lock
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let content = serde_json::to_string(&StartLock::ours()).unwrap_or_default();
+                if let Err(e) = file.write_all(content.as_bytes()) {
+                    log_error!("Failed to write gateway lock: {}", e);
+                }
+                return Ok(true);
+            }
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => match read_lock(&path) {
+                Some(lock) if !lock.is_stale() => return Err(lock),
+                // Unreadable, stale, or left behind by this process
+                _ => {
+                    let _ = fs::remove_file(&path);
+                }
+            },
+            Err(e) => {
+                // Starting matters more than the cross-process guard
+                log_error!("Failed to create gateway lock: {}", e);
+                return Ok(false);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Claim the right to start the gateway, or learn who already has it
+pub(crate) fn acquire() -> Result<StartGuard, StartLock> {
+    if STARTING.swap(true, Ordering::SeqCst) {
+        return Err(StartLock::ours());
+    }
+    match lock_file() {
+        Ok(file) => Ok(StartGuard { file }),
+        Err(lock) => {
+            STARTING.store(false, Ordering::SeqCst);
+            Err(lock)
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Size index of `~/.openclaw`, kept current by a file watcher.
+//!
+//! The index stores one entry per directory (its direct files' count and bytes,
+//! plus its subdirectories), so memory grows with the number of folders rather
+//! than files. Watcher events only mark directories dirty; the next report
+//! re-reads just those, instead of walking the whole tree again.
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::error::DesktopError;
+use crate::openclaw_home_dir;
+
+/// Direct contents of one directory
+#[derive(Default)]
+struct DirStats {
+    files: u64,
+    bytes: u64,
+    subdirs: Vec<PathBuf>,
+}
+
+#[derive(Default)]
+struct Index {
+    dirs: BTreeMap<PathBuf, DirStats>,
+    scanned_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageCategory {
+    /// Top-level entry of `~/.openclaw`, or `(files)` for loose files at its root
+    pub name: String,
+    pub bytes: u64,
+    pub files: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageReport {
+    pub root: String,
+    pub total_bytes: u64,
+    pub total_files: u64,
+    /// Largest first
+    pub categories: Vec<StorageCategory>,
+    /// When the index was last brought up to date, in Unix seconds
+    pub scanned_at: u64,
+}
+
+static INDEX: Mutex<Index> = Mutex::new(Index {
+    dirs: BTreeMap::new(),
+    scanned_at: None,
+});
+/// Directories touched since the last refresh; kept apart from the index so the
+/// watcher never waits on a scan in progress
+static DIRTY: Mutex<Option<HashSet<PathBuf>>> = Mutex::new(None);
+/// The watcher lost events, so nothing in the index can be trusted
+static FULL_RESCAN: AtomicBool = AtomicBool::new(true);
+static WATCHER: OnceLock<Mutex<Option<RecommendedWatcher>>> = OnceLock::new();
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn mark_dirty(path: &Path) {
+    if let Ok(mut dirty) = DIRTY.lock() {
+        let dirty = dirty.get_or_insert_with(HashSet::new);
+        // The parent lists the entry; the path itself matters when it is a directory
+        if let Some(parent) = path.parent() {
+            dirty.insert(parent.to_path_buf());
+        }
+        dirty.insert(path.to_path_buf());
+    }
+}
+
+/// Read one directory without descending; symlinks are counted, never followed
+fn scan_dir(dir: &Path) -> Option<DirStats> {
+    let entries = fs::read_dir(dir).ok()?;
+    let mut stats = DirStats::default();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let Ok(metadata) = entry.path().symlink_metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            stats.subdirs.push(entry.path());
+        } else {
+            stats.files += 1;
+            stats.bytes += metadata.len();
+        }
+    }
+    Some(stats)
+}
+
+/// Index `dir` and everything below it
+fn scan_tree(index: &mut Index, dir: &Path) {
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        if let Some(stats) = scan_dir(&dir) {
+            stack.extend(stats.subdirs.iter().cloned());
+            index.dirs.insert(dir, stats);
+        }
+    }
+}
+
+fn forget_tree(index: &mut Index, dir: &Path) {
+    index.dirs.retain(|path, _| !path.starts_with(dir));
+}
+
+/// Re-read the directories marked dirty, or everything after a lost event
+fn refresh(index: &mut Index, root: &Path) {
+    let dirty = DIRTY
+        .lock()
+        .ok()
+        .and_then(|mut dirty| dirty.take())
+        .unwrap_or_default();
+
+    // Without a watcher nothing marks directories dirty, so every refresh is a full walk
+    let watching = WATCHER
+        .get()
+        .and_then(|slot| slot.lock().ok().map(|w| w.is_some()))
+        .unwrap_or(false);
+    if FULL_RESCAN.swap(false, Ordering::SeqCst) || !watching || index.scanned_at.is_none() {
+        index.dirs.clear();
+        scan_tree(index, root);
+        index.scanned_at = Some(now_secs());
+        return;
+    }
+
+    // Parents first, so a new subtree is indexed once through its parent
+    let mut dirty: Vec<PathBuf> = dirty.into_iter().filter(|d| d.starts_with(root)).collect();
+    dirty.sort_by_key(|d| d.components().count());
+    for dir in dirty {
+        // Unknown paths are files, or directories their parent's rescan picks up
+        let Some(old_subdirs) = index.dirs.get(&dir).map(|old| old.subdirs.clone()) else {
+            continue;
+        };
+        let Some(stats) = scan_dir(&dir) else {
+            forget_tree(index, &dir);
+            continue;
+        };
+        for removed in old_subdirs.iter().filter(|d| !stats.subdirs.contains(d)) {
+            forget_tree(index, removed);
+        }
+        let added: Vec<PathBuf> = stats
+            .subdirs
+            .iter()
+            .filter(|d| !index.dirs.contains_key(*d))
+            .cloned()
+            .collect();
+        index.dirs.insert(dir, stats);
+        for subdir in added {
+            scan_tree(index, &subdir);
+        }
+    }
+    index.scanned_at = Some(now_secs());
+}
+
+fn totals(index: &Index, dir: &Path) -> (u64, u64) {
+    index
+        .dirs
+        .iter()
+        .filter(|(path, _)| path.starts_with(dir))
+        .fold((0, 0), |(bytes, files), (_, stats)| {
+            (bytes + stats.bytes, files + stats.files)
+        })
+}
+
+/// Up-to-date sizes of `~/.openclaw` by top-level entry
+pub fn report() -> Result<StorageReport, String> {
+    let root = openclaw_home_dir()?;
+    let mut index = INDEX
+        .lock()
+        .map_err(|_| "Storage index lock poisoned".to_string())?;
+    refresh(&mut index, &root);
+
+    let root_stats = index.dirs.get(&root);
+    let mut categories: Vec<StorageCategory> = root_stats
+        .map(|stats| stats.subdirs.clone())
+        .unwrap_or_default()
+        .iter()
+        .map(|dir| {
+            let (bytes, files) = totals(&index, dir);
+            StorageCategory {
+                name: dir
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                bytes,
+                files,
+            }
+        })
+        .collect();
+    if let Some(stats) = root_stats.filter(|s| s.files > 0) {
+        categories.push(StorageCategory {
+            name: "(files)".to_string(),
+            bytes: stats.bytes,
+            files: stats.files,
+        });
+    }
+    categories.sort_by_key(|category| Reverse(category.bytes));
+    let (total_bytes, total_files) = totals(&index, &root);
+
+    Ok(StorageReport {
+        root: root.display().to_string(),
+        total_bytes,
+        total_files,
+        categories,
+        scanned_at: index.scanned_at.unwrap_or_default(),
+    })
+}
+
+/// Watch `~/.openclaw` and build the index in the background
+pub fn spawn_storage_index() {
+    let root = match openclaw_home_dir() {
+        Ok(root) => root,
+        Err(e) => {
+            log_error!("Storage index disabled: {}", e);
+            return;
+        }
+    };
+    let watcher =
+        notify::recommended_watcher(|result: notify::Result<notify::Event>| match result {
+            Ok(event) if event.need_rescan() => FULL_RESCAN.store(true, Ordering::SeqCst),
+            Ok(event) => event.paths.iter().for_each(|path| mark_dirty(path)),
+            Err(_) => FULL_RESCAN.store(true, Ordering::SeqCst),
+        })
+        .and_then(|mut watcher| {
+            watcher.watch(&root, RecursiveMode::Recursive)?;
+            Ok(watcher)
+        });
+    match watcher {
+        Ok(watcher) => {
+            let slot = WATCHER.get_or_init(|| Mutex::new(None));
+            if let Ok(mut slot) = slot.lock() {
+                *slot = Some(watcher);
+            }
+        }
+        // Without a watcher every report has to walk the tree again
+        Err(e) => log_error!("Failed to watch {}: {}", root.display(), e),
+    }
+
+    tauri::async_runtime::spawn_blocking(|| {
+        if let Err(e) = report() {
+            log_error!("Failed to index storage: {}", e);
+        }
+    });
+}
+
+/// Disk usage of `~/.openclaw`, broken down by top-level folder
+#[tauri::command]
+pub async fn get_storage_report() -> Result<StorageReport, DesktopError> {
+    let report = tauri::async_runtime::spawn_blocking(report)
+        .await
+        .map_err(|e| format!("Failed to build storage report: {}", e))??;
+    Ok(report)
+}
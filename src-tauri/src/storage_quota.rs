@@ -0,0 +1,578 @@
+//! Quotas and retention for what accumulates under `~/.openclaw`, and a
+//! warning before the disk fills up.
+//!
+//! Pruning only removes what can be lost safely: rotated logs (never the live
+//! ones), session transcripts untouched for a day, and caches. Oldest go first.
+//! Exports are written wherever the user saves them and are never touched.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime};
+
+use crate::disk_space::format_bytes;
+use crate::error::DesktopError;
+use crate::i18n::{t, t_with};
+use crate::notifications::{self, NotificationCategory};
+use crate::{audit, kiosk, openclaw_home_dir, paths, settings, storage};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+/// Transcripts written more recently than this may belong to a live session
+const SESSION_MIN_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+const MB: u64 = 1024 * 1024;
+const RETENTION_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+/// When retention last ran in this process; it also runs once per launch
+static RETENTION_RAN: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Set while free space is below the threshold, so the warning is shown once per episode
+static LOW_DISK_WARNED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageQuotas {
+    /// Megabytes per kind; `None` never prunes it
+    pub logs_mb: Option<u64>,
+    pub sessions_mb: Option<u64>,
+    pub caches_mb: Option<u64>,
+    /// Warn when the disk holding `~/.openclaw` has less free space; 0 turns it off
+    pub low_disk_warning_mb: u64,
+}
+
+impl Default for StorageQuotas {
+    fn default() -> Self {
+        Self {
+            logs_mb: None,
+            sessions_mb: None,
+            caches_mb: None,
+            low_disk_warning_mb: 2048,
+        }
+    }
+}
+
+/// Age limits applied once a day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionSettings {
+    pub enabled: bool,
+    /// Days to keep rotated logs; `None` keeps them
+    pub logs_days: Option<u32>,
+    /// Days to keep session transcripts since they were last written
+    pub sessions_days: Option<u32>,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            logs_days: Some(30),
+            sessions_days: Some(90),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageKind {
+    Logs,
+    Sessions,
+    Caches,
+}
+
+impl StorageKind {
+    const ALL: [StorageKind; 3] = [Self::Logs, Self::Sessions, Self::Caches];
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Logs => "logs",
+            Self::Sessions => "sessions",
+            Self::Caches => "caches",
+        }
+    }
+
+    fn quota_mb(self, quotas: &StorageQuotas) -> Option<u64> {
+        match self {
+            Self::Logs => quotas.logs_mb,
+            Self::Sessions => quotas.sessions_mb,
+            Self::Caches => quotas.caches_mb,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageUsage {
+    pub kind: StorageKind,
+    pub bytes: u64,
+    pub files: u64,
+    pub quota_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageBreakdown {
+    pub root: String,
+    pub total_bytes: u64,
+    pub kinds: Vec<StorageUsage>,
+    /// Everything under the root not counted in `kinds`
+    pub other_bytes: u64,
+    /// Free space on the disk holding the root
+    pub available_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruneResult {
+    pub removed_files: u64,
+    pub freed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionCandidate {
+    pub kind: StorageKind,
+    pub path: String,
+    pub bytes: u64,
+    pub age_days: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionReport {
+    /// Nothing was removed; `candidates` is what would have been
+    pub dry_run: bool,
+    /// Unix seconds
+    pub ran_at: u64,
+    pub candidates: Vec<RetentionCandidate>,
+    pub removed_files: u64,
+    pub freed_bytes: u64,
+}
+
+struct FileEntry {
+    path: PathBuf,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+fn walk(dir: &Path, files: &mut Vec<FileEntry>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        // Symlinks are neither followed nor removed
+        let Ok(metadata) = entry.path().symlink_metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            walk(&entry.path(), files);
+        } else if metadata.is_file() {
+            files.push(FileEntry {
+                path: entry.path(),
+                bytes: metadata.len(),
+                modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            });
+        }
+    }
+}
+
+fn is_live_log(name: &str) -> bool {
+    name.ends_with(".log")
+}
+
+fn is_log(name: &str) -> bool {
+    name.contains(".log")
+}
+
+fn files_of(root: &Path, kind: StorageKind) -> Vec<FileEntry> {
+    let mut files = Vec::new();
+    match kind {
+        StorageKind::Logs => {
+            let root = paths::log_dir().unwrap_or_else(|_| root.to_path_buf());
+            walk(&root.join("logs"), &mut files);
+            // The default gateway logs at the top level
+            if let Ok(entries) = fs::read_dir(&root) {
+                for entry in entries.flatten() {
+                    let name = entry.file_name().to_string_lossy().into_owned();
+                    let Ok(metadata) = entry.path().symlink_metadata() else {
+                        continue;
+                    };
+                    if metadata.is_file() && is_log(&name) {
+                        files.push(FileEntry {
+                            path: entry.path(),
+                            bytes: metadata.len(),
+                            modified: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                        });
+                    }
+                }
+            }
+        }
+        StorageKind::Sessions => {
+            walk(&root.join("sessions"), &mut files);
+            if let Ok(agents) = fs::read_dir(root.join("agents")) {
+                for agent in agents.flatten() {
+                    walk(&agent.path().join("sessions"), &mut files);
+                }
+            }
+        }
+        StorageKind::Caches => {
+            for dir in ["cache", "caches", "tmp", "captures"] {
+                walk(&root.join(dir), &mut files);
+            }
+        }
+    }
+    files
+}
+
+/// How long ago the file was last written; zero for a time in the future
+fn age(file: &FileEntry, now: SystemTime) -> Duration {
+    now.duration_since(file.modified).unwrap_or_default()
+}
+
+fn prunable(kind: StorageKind, file: &FileEntry, now: SystemTime) -> bool {
+    match kind {
+        StorageKind::Logs => {
+            let name = file
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            !is_live_log(&name)
+        }
+        StorageKind::Sessions => age(file, now) >= SESSION_MIN_AGE,
+        StorageKind::Caches => true,
+    }
+}
+
+/// Older than its kind's retention limit of `days`, and safe to remove
+fn past_retention(kind: StorageKind, file: &FileEntry, days: u32, now: SystemTime) -> bool {
+    age(file, now).as_secs() >= u64::from(days) * DAY_SECS && prunable(kind, file, now)
+}
+
+/// Remove the oldest prunable files of `kind` until it fits in `quota` bytes
+fn prune_kind(root: &Path, kind: StorageKind, quota: u64) -> PruneResult {
+    let mut files = files_of(root, kind);
+    let mut total: u64 = files.iter().map(|f| f.bytes).sum();
+    let mut result = PruneResult::default();
+    if total <= quota {
+        return result;
+    }
+    let now = SystemTime::now();
+    files.retain(|f| prunable(kind, f, now));
+    files.sort_by_key(|f| f.modified);
+    for file in files {
+        if total <= quota {
+            break;
+        }
+        match fs::remove_file(&file.path) {
+            Ok(()) => {
+                total = total.saturating_sub(file.bytes);
+                result.removed_files += 1;
+                result.freed_bytes += file.bytes;
+            }
+            Err(e) => log_error!("Failed to prune {}: {}", file.path.display(), e),
+        }
+    }
+    if result.removed_files > 0 {
+        audit::record(
+            "storage_prune",
+            kind.as_str(),
+            true,
+            Some(format!(
+                "{} files, {}",
+                result.removed_files,
+                format_bytes(result.freed_bytes)
+            )),
+        );
+    }
+    result
+}
+
+fn enforce() -> Result<PruneResult, String> {
+    let root = openclaw_home_dir()?;
+    let quotas = settings::current().storage_quotas;
+    let mut result = PruneResult::default();
+    for kind in StorageKind::ALL {
+        if let Some(mb) = kind.quota_mb(&quotas) {
+            let pruned = prune_kind(&root, kind, mb * MB);
+            result.removed_files += pruned.removed_files;
+            result.freed_bytes += pruned.freed_bytes;
+        }
+    }
+    Ok(result)
+}
+
+fn retention_days(kind: StorageKind, retention: &RetentionSettings) -> Option<u32> {
+    match kind {
+        StorageKind::Logs => retention.logs_days,
+        StorageKind::Sessions => retention.sessions_days,
+        StorageKind::Caches => None,
+    }
+}
+
+/// Remove, or with `dry_run` only list, prunable files older than their kind's limit
+fn apply_retention(dry_run: bool) -> Result<RetentionReport, String> {
+    let root = openclaw_home_dir()?;
+    let retention = settings::current().retention;
+    let now = SystemTime::now();
+    let mut report = RetentionReport {
+        dry_run,
+        ran_at: now
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default(),
+        candidates: Vec::new(),
+        removed_files: 0,
+        freed_bytes: 0,
+    };
+    for kind in StorageKind::ALL {
+        let Some(days) = retention_days(kind, &retention) else {
+            continue;
+        };
+        let mut removed = PruneResult::default();
+        for file in files_of(&root, kind) {
+            if !past_retention(kind, &file, days, now) {
+                continue;
+            }
+            if !dry_run {
+                if let Err(e) = fs::remove_file(&file.path) {
+                    log_error!("Failed to remove {}: {}", file.path.display(), e);
+                    continue;
+                }
+                removed.removed_files += 1;
+                removed.freed_bytes += file.bytes;
+            }
+            report.candidates.push(RetentionCandidate {
+                kind,
+                path: file.path.display().to_string(),
+                bytes: file.bytes,
+                age_days: age(&file, now).as_secs() / DAY_SECS,
+            });
+        }
+        if removed.removed_files > 0 {
+            audit::record(
+                "storage_retention",
+                kind.as_str(),
+                true,
+                Some(format!(
+                    "{} files, {}",
+                    removed.removed_files,
+                    format_bytes(removed.freed_bytes)
+                )),
+            );
+        }
+        report.removed_files += removed.removed_files;
+        report.freed_bytes += removed.freed_bytes;
+    }
+    Ok(report)
+}
+
+/// Run retention when enabled and it hasn't run for a day
+fn retention_due() -> bool {
+    if !settings::current().retention.enabled {
+        return false;
+    }
+    let Ok(mut ran) = RETENTION_RAN.lock() else {
+        return false;
+    };
+    if ran.is_some_and(|at| at.elapsed() < RETENTION_INTERVAL) {
+        return false;
+    }
+    *ran = Some(Instant::now());
+    true
+}
+
+fn breakdown() -> Result<StorageBreakdown, String> {
+    let root = openclaw_home_dir()?;
+    let quotas = settings::current().storage_quotas;
+    let report = storage::report()?;
+    let kinds: Vec<StorageUsage> = StorageKind::ALL
+        .iter()
+        .map(|&kind| {
+            let files = files_of(&root, kind);
+            StorageUsage {
+                kind,
+                bytes: files.iter().map(|f| f.bytes).sum(),
+                files: files.len() as u64,
+                quota_bytes: kind.quota_mb(&quotas).map(|mb| mb * MB),
+            }
+        })
+        .collect();
+    let counted: u64 = kinds.iter().map(|k| k.bytes).sum();
+    Ok(StorageBreakdown {
+        root: report.root,
+        total_bytes: report.total_bytes,
+        other_bytes: report.total_bytes.saturating_sub(counted),
+        kinds,
+        available_bytes: fs2::available_space(&root).ok(),
+    })
+}
+
+fn check_free_space<R: Runtime>(app: &AppHandle<R>) {
+    let threshold = settings::current().storage_quotas.low_disk_warning_mb * MB;
+    let Some(available) = openclaw_home_dir()
+        .ok()
+        .and_then(|root| fs2::available_space(root).ok())
+    else {
+        return;
+    };
+    if threshold == 0 || available >= threshold {
+        LOW_DISK_WARNED.store(false, Ordering::SeqCst);
+        return;
+    }
+    if LOW_DISK_WARNED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    notifications::notify(
+        app,
+        NotificationCategory::DiskSpace,
+        &t("storage.low_disk"),
+        &t_with(
+            "storage.low_disk_body",
+            &[("available", &format_bytes(available))],
+        ),
+    );
+}
+
+/// Enforce quotas and retention, and watch free space, in the background
+pub fn spawn_storage_guard<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let task_app = app.clone();
+            let result = tauri::async_runtime::spawn_blocking(move || {
+                check_free_space(&task_app);
+                if retention_due() {
+                    apply_retention(false)?;
+                }
+                enforce()
+            })
+            .await;
+            if let Ok(Err(e)) = result {
+                log_error!("Failed to enforce storage quotas: {}", e);
+            }
+        }
+    });
+}
+
+/// Size of logs, sessions and caches against their quotas, plus free disk space
+#[tauri::command]
+pub async fn get_storage_breakdown() -> Result<StorageBreakdown, DesktopError> {
+    let breakdown = tauri::async_runtime::spawn_blocking(breakdown)
+        .await
+        .map_err(|e| format!("Failed to build storage breakdown: {}", e))??;
+    Ok(breakdown)
+}
+
+/// Prune everything over quota now instead of waiting for the next check
+#[tauri::command]
+pub async fn prune_storage() -> Result<PruneResult, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let result = tauri::async_runtime::spawn_blocking(enforce)
+        .await
+        .map_err(|e| format!("Failed to prune storage: {}", e))??;
+    Ok(result)
+}
+
+/// Apply the retention limits now. With `dry_run` nothing is removed and the
+/// report lists what would be.
+#[tauri::command]
+pub async fn run_retention_now(dry_run: bool) -> Result<RetentionReport, DesktopError> {
+    if !dry_run {
+        kiosk::ensure_unlocked()?;
+    }
+    let report = tauri::async_runtime::spawn_blocking(move || apply_retention(dry_run))
+        .await
+        .map_err(|e| format!("Failed to apply retention: {}", e))??;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn file(name: &str, days_old: u64) -> FileEntry {
+        FileEntry {
+            path: PathBuf::from("/home/u/.openclaw").join(name),
+            bytes: 100,
+            modified: UNIX_EPOCH + Duration::from_secs(NOW - days_old * DAY_SECS),
+        }
+    }
+
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(NOW)
+    }
+
+    #[test]
+    fn live_logs_are_never_pruned() {
+        assert!(!prunable(
+            StorageKind::Logs,
+            &file("gateway.log", 400),
+            now()
+        ));
+        assert!(!prunable(
+            StorageKind::Logs,
+            &file("logs/work/gateway_error.log", 400),
+            now()
+        ));
+        assert!(prunable(
+            StorageKind::Logs,
+            &file("gateway.log.1", 0),
+            now()
+        ));
+        assert!(prunable(
+            StorageKind::Logs,
+            &file("gateway_error.log.5", 0),
+            now()
+        ));
+    }
+
+    #[test]
+    fn recent_sessions_are_kept() {
+        assert!(!prunable(
+            StorageKind::Sessions,
+            &file("sessions/a.jsonl", 0),
+            now()
+        ));
+        assert!(prunable(
+            StorageKind::Sessions,
+            &file("sessions/a.jsonl", 1),
+            now()
+        ));
+
+        let mut future = file("sessions/b.jsonl", 0);
+        future.modified = now() + Duration::from_secs(DAY_SECS * 2);
+        assert!(!prunable(StorageKind::Sessions, &future, now()));
+    }
+
+    #[test]
+    fn caches_are_always_prunable() {
+        assert!(prunable(StorageKind::Caches, &file("cache/x", 0), now()));
+    }
+
+    #[test]
+    fn retention_needs_the_age_and_a_prunable_file() {
+        let rotated = file("gateway.log.2", 30);
+        assert!(past_retention(StorageKind::Logs, &rotated, 30, now()));
+        assert!(!past_retention(StorageKind::Logs, &rotated, 31, now()));
+        assert!(!past_retention(
+            StorageKind::Logs,
+            &file("gateway.log", 90),
+            30,
+            now()
+        ));
+        assert!(past_retention(
+            StorageKind::Sessions,
+            &file("sessions/a.jsonl", 90),
+            90,
+            now()
+        ));
+        assert!(!past_retention(
+            StorageKind::Sessions,
+            &file("sessions/a.jsonl", 89),
+            90,
+            now()
+        ));
+    }
+}
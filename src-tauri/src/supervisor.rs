@@ -0,0 +1,343 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::announce::announce;
+use crate::error::DesktopError;
+use crate::events::{
+    GatewayCrashedPayload, GatewayRecoveredPayload, Politeness, GATEWAY_CRASHED_EVENT,
+    GATEWAY_RECOVERED_EVENT,
+};
+use crate::gateway_exit::{ExitInfo, ExitReason};
+use crate::gateway_process::GatewayProcess;
+use crate::i18n::{t, t_with};
+use crate::integrations::{self, WebhookEvent};
+use crate::kiosk;
+use crate::notifications::{self, NotificationCategory};
+use crate::settings;
+use crate::{is_gateway_running, start_default_gateway};
+
+const HISTORY_LIMIT: usize = 50;
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A gateway that stays up this long after a restart resets the backoff
+pub(crate) const STABLE_UPTIME: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoRestartSettings {
+    pub enabled: bool,
+    pub initial_backoff_secs: u64,
+    pub max_backoff_secs: u64,
+    pub max_attempts: u32,
+}
+
+impl Default for AutoRestartSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            initial_backoff_secs: 2,
+            max_backoff_secs: 300,
+            max_attempts: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestartRecord {
+    pub at: u64,
+    pub attempt: u32,
+    pub reason: Option<ExitReason>,
+    pub succeeded: bool,
+    pub detail: Option<String>,
+}
+
+#[derive(Default)]
+struct SupervisorState {
+    restarting: bool,
+    /// The current outage was already reported; the exit watcher and the health
+    /// monitor can both notice the same crash
+    crash_reported: bool,
+    attempts: u32,
+    last_recovered: Option<Instant>,
+    history: VecDeque<RestartRecord>,
+}
+
+/// Restart bookkeeping for the default gateway
+#[derive(Default)]
+pub struct Supervisor {
+    state: Mutex<SupervisorState>,
+}
+
+/// Whether the user wants the gateway up; cleared by explicit stops so they
+/// are never mistaken for crashes
+static DESIRED_RUNNING: AtomicBool = AtomicBool::new(false);
+
+pub fn set_desired_running(running: bool) {
+    DESIRED_RUNNING.store(running, Ordering::SeqCst);
+}
+
+pub fn is_desired_running() -> bool {
+    DESIRED_RUNNING.load(Ordering::SeqCst)
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+pub(crate) fn backoff(config: &AutoRestartSettings, attempt: u32) -> Duration {
+    let factor = 2u64.saturating_pow(attempt.saturating_sub(1));
+    Duration::from_secs(
+        config
+            .initial_backoff_secs
+            .saturating_mul(factor)
+            .min(config.max_backoff_secs),
+    )
+}
+
+impl Supervisor {
+    fn record(&self, record: RestartRecord) {
+        if let Ok(mut state) = self.state.lock() {
+            if state.history.len() >= HISTORY_LIMIT {
+                state.history.pop_front();
+            }
+            state.history.push_back(record);
+        }
+    }
+}
+
+/// Called when the health monitor sees the gateway up again
+pub fn on_gateway_running<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(mut state) = app.state::<Supervisor>().state.lock() {
+        state.crash_reported = false;
+    }
+}
+
+/// Called before any start the supervisor didn't make itself, e.g. from the UI,
+/// so a later crash gets the full set of attempts again
+pub fn on_start_requested<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(mut state) = app.state::<Supervisor>().state.lock() {
+        if !state.restarting {
+            state.attempts = 0;
+            state.crash_reported = false;
+        }
+    }
+}
+
+/// Called when the tracked gateway child exits
+pub fn on_gateway_exit<R: Runtime>(app: &AppHandle<R>, exit: &ExitInfo) {
+    if exit.reason == ExitReason::CleanStop {
+        return;
+    }
+    handle_crash(app, Some(exit.clone()));
+}
+
+/// Called by the health monitor when the port stops answering.
+///
+/// Only relevant for gateways we don't hold a child handle for (e.g. adopted
+/// daemons); tracked children report through `on_gateway_exit` instead.
+pub fn on_port_lost<R: Runtime>(app: &AppHandle<R>) {
+    if !DESIRED_RUNNING.load(Ordering::SeqCst) {
+        return;
+    }
+    if app.state::<GatewayProcess>().pid().is_some() {
+        return;
+    }
+    handle_crash(app, None);
+}
+
+fn handle_crash<R: Runtime>(app: &AppHandle<R>, exit: Option<ExitInfo>) {
+    let config = settings::current().auto_restart;
+    let supervisor = app.state::<Supervisor>();
+
+    let restarting = {
+        let Ok(mut state) = supervisor.state.lock() else {
+            return;
+        };
+        if state.restarting || state.crash_reported {
+            return;
+        }
+        state.crash_reported = true;
+        if state
+            .last_recovered
+            .is_some_and(|at| at.elapsed() >= STABLE_UPTIME)
+        {
+            state.attempts = 0;
+        }
+        state.restarting = config.enabled && DESIRED_RUNNING.load(Ordering::SeqCst);
+        state.restarting
+    };
+
+    let payload = GatewayCrashedPayload {
+        exit: exit.clone(),
+        restarting,
+    };
+    if let Err(e) = app.emit(GATEWAY_CRASHED_EVENT, payload) {
+        log_error!("Failed to emit gateway crash event: {}", e);
+    }
+
+    let reason = exit
+        .as_ref()
+        .and_then(|e| e.detail.clone())
+        .unwrap_or_else(|| t("gateway.crashed_reason"));
+    integrations::forward(
+        WebhookEvent::Crash,
+        format!("Gateway crashed: {}", reason),
+        serde_json::json!({ "exit": exit, "restarting": restarting }),
+    );
+    let body = if restarting {
+        t_with("gateway.crashed_body_restarting", &[("reason", &reason)])
+    } else {
+        reason
+    };
+    announce(
+        app,
+        Politeness::Assertive,
+        &if restarting {
+            t("gateway.crashed_restarting")
+        } else {
+            t("gateway.crashed")
+        },
+    );
+    notifications::notify(
+        app,
+        NotificationCategory::GatewayCrashed,
+        &t("gateway.crashed"),
+        &body,
+    );
+
+    if restarting {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            restart_with_backoff(&app, config, exit.map(|e| e.reason)).await;
+        });
+    }
+}
+
+async fn wait_until_ready() -> bool {
+    let deadline = Instant::now() + READY_TIMEOUT;
+    while Instant::now() < deadline {
+        if tauri::async_runtime::spawn_blocking(is_gateway_running)
+            .await
+            .unwrap_or(false)
+        {
+            return true;
+        }
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+    false
+}
+
+async fn restart_with_backoff<R: Runtime>(
+    app: &AppHandle<R>,
+    config: AutoRestartSettings,
+    reason: Option<ExitReason>,
+) {
+    let supervisor = app.state::<Supervisor>();
+    let crashed_at = Instant::now();
+
+    loop {
+        let attempt = match supervisor.state.lock() {
+            Ok(mut state) => {
+                state.attempts += 1;
+                state.attempts
+            }
+            Err(_) => break,
+        };
+        if attempt > config.max_attempts {
+            if let Ok(mut state) = supervisor.state.lock() {
+                state.attempts = 0;
+            }
+            supervisor.record(RestartRecord {
+                at: now_secs(),
+                attempt,
+                reason,
+                succeeded: false,
+                detail: Some(format!("Gave up after {} attempts", config.max_attempts)),
+            });
+            break;
+        }
+
+        tokio::time::sleep(backoff(&config, attempt)).await;
+
+        // The user stopped the gateway or disabled supervision while we waited
+        if !DESIRED_RUNNING.load(Ordering::SeqCst) || !settings::current().auto_restart.enabled {
+            break;
+        }
+
+        let started = if is_gateway_running() {
+            Ok(())
+        } else {
+            start_default_gateway(app).map(|_| ())
+        };
+        let ready = match started {
+            Ok(()) => wait_until_ready().await,
+            Err(e) => {
+                log_error!("Auto-restart attempt {} failed: {}", attempt, e);
+                false
+            }
+        };
+
+        supervisor.record(RestartRecord {
+            at: now_secs(),
+            attempt,
+            reason,
+            succeeded: ready,
+            detail: None,
+        });
+
+        if ready {
+            if let Ok(mut state) = supervisor.state.lock() {
+                state.last_recovered = Some(Instant::now());
+                state.crash_reported = false;
+            }
+            let payload = GatewayRecoveredPayload {
+                attempts: attempt,
+                downtime_secs: crashed_at.elapsed().as_secs(),
+            };
+            integrations::forward(
+                WebhookEvent::Restart,
+                format!("Gateway recovered after {} attempt(s)", attempt),
+                serde_json::to_value(&payload).unwrap_or_default(),
+            );
+            if let Err(e) = app.emit(GATEWAY_RECOVERED_EVENT, payload) {
+                log_error!("Failed to emit gateway recovered event: {}", e);
+            }
+            announce(app, Politeness::Polite, &t("gateway.recovered"));
+            break;
+        }
+
+        // A child that never started listening is killed before the next attempt
+        if let Err(e) = app.state::<GatewayProcess>().kill() {
+            log_error!("Failed to kill unresponsive gateway: {}", e);
+        }
+    }
+
+    if let Ok(mut state) = supervisor.state.lock() {
+        state.restarting = false;
+    };
+}
+
+/// Enable or disable automatic gateway restarts after a crash
+#[tauri::command]
+pub fn set_gateway_autorestart(enabled: bool) -> Result<AutoRestartSettings, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    Ok(settings::update(|s| s.auto_restart.enabled = enabled)?.auto_restart)
+}
+
+/// Restart attempts made by the supervisor, oldest first
+#[tauri::command]
+pub fn get_restart_history(supervisor: State<'_, Supervisor>) -> Vec<RestartRecord> {
+    supervisor
+        .state
+        .lock()
+        .map(|state| state.history.iter().cloned().collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,189 @@
+//! Long-running operations the frontend can follow and cancel.
+//!
+//! A command registers a [`Task`], reports through it, and runs its work inside
+//! [`Task::run`]. Every change is emitted as `task-progress`, and `cancel_task`
+//! stops the work wherever it is waiting.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio_util::sync::CancellationToken;
+use ts_rs::TS;
+
+use crate::app_state;
+use crate::error::DesktopError;
+use crate::events::{TaskProgress, TASK_PROGRESS_EVENT};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum TaskKind {
+    Install,
+    Doctor,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum TaskState {
+    Running,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+struct Entry {
+    kind: TaskKind,
+    message: Option<String>,
+    token: CancellationToken,
+    /// Stops work the token can't reach, such as a child on a blocking thread
+    on_cancel: Option<Box<dyn FnOnce() + Send>>,
+}
+
+fn registry() -> &'static Mutex<HashMap<String, Entry>> {
+    static TASKS: OnceLock<Mutex<HashMap<String, Entry>>> = OnceLock::new();
+    TASKS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn next_id(kind: TaskKind) -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    let kind = match kind {
+        TaskKind::Install => "install",
+        TaskKind::Doctor => "doctor",
+    };
+    format!("{}-{}", kind, NEXT.fetch_add(1, Ordering::Relaxed))
+}
+
+/// A registered operation; clones report on the same task
+pub(crate) struct Task<R: Runtime> {
+    id: String,
+    kind: TaskKind,
+    token: CancellationToken,
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> Clone for Task<R> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            kind: self.kind,
+            token: self.token.clone(),
+            app: self.app.clone(),
+        }
+    }
+}
+
+impl<R: Runtime> Task<R> {
+    pub(crate) fn start(app: &AppHandle<R>, kind: TaskKind) -> Self {
+        let task = Self {
+            id: next_id(kind),
+            kind,
+            token: CancellationToken::new(),
+            app: app.clone(),
+        };
+        if let Ok(mut tasks) = registry().lock() {
+            tasks.insert(
+                task.id.clone(),
+                Entry {
+                    kind,
+                    message: None,
+                    token: task.token.clone(),
+                    on_cancel: None,
+                },
+            );
+        }
+        task.emit(TaskState::Running, None);
+        task
+    }
+
+    /// Run `hook` if the task is cancelled while it is registered
+    pub(crate) fn on_cancel(&self, hook: impl FnOnce() + Send + 'static) {
+        if let Ok(mut tasks) = registry().lock() {
+            if let Some(entry) = tasks.get_mut(&self.id) {
+                entry.on_cancel = Some(Box::new(hook));
+            }
+        }
+    }
+
+    pub(crate) fn progress(&self, message: impl Into<String>) {
+        let message = message.into();
+        if let Ok(mut tasks) = registry().lock() {
+            if let Some(entry) = tasks.get_mut(&self.id) {
+                entry.message = Some(message.clone());
+            }
+        }
+        self.emit(TaskState::Running, Some(message));
+    }
+
+    /// Drive `work` to completion unless the task is cancelled first, then unregister it
+    pub(crate) async fn run<T>(
+        self,
+        work: impl Future<Output = Result<T, DesktopError>>,
+    ) -> Result<T, DesktopError> {
+        let result = tokio::select! {
+            result = work => result,
+            _ = self.token.cancelled() => Err(DesktopError::Cancelled),
+        };
+        if let Ok(mut tasks) = registry().lock() {
+            tasks.remove(&self.id);
+        }
+        match &result {
+            Ok(_) => self.emit(TaskState::Finished, None),
+            Err(DesktopError::Cancelled) => self.emit(TaskState::Cancelled, None),
+            Err(e) => self.emit(TaskState::Failed, Some(e.to_string())),
+        }
+        result
+    }
+
+    fn emit(&self, state: TaskState, message: Option<String>) {
+        let payload = TaskProgress {
+            id: self.id.clone(),
+            kind: self.kind,
+            state,
+            message,
+        };
+        if let Err(e) = self.app.emit(TASK_PROGRESS_EVENT, payload) {
+            log_error!("Failed to emit task progress: {}", e);
+        }
+        app_state::refresh_tasks(&self.app);
+    }
+}
+
+/// Tasks still running, for a webview that reloaded mid-operation
+#[tauri::command]
+pub fn list_tasks() -> Vec<TaskProgress> {
+    let Ok(tasks) = registry().lock() else {
+        return Vec::new();
+    };
+    tasks
+        .iter()
+        .map(|(id, entry)| TaskProgress {
+            id: id.clone(),
+            kind: entry.kind,
+            state: TaskState::Running,
+            message: entry.message.clone(),
+        })
+        .collect()
+}
+
+/// Cancel a running task; returns `false` when it already ended
+#[tauri::command]
+pub fn cancel_task(task_id: String) -> Result<bool, DesktopError> {
+    let hook = {
+        let mut tasks = registry()
+            .lock()
+            .map_err(|_| "Task registry lock poisoned".to_string())?;
+        let Some(entry) = tasks.get_mut(&task_id) else {
+            return Ok(false);
+        };
+        entry.token.cancel();
+        entry.on_cancel.take()
+    };
+    if let Some(hook) = hook {
+        hook();
+    }
+    Ok(true)
+}
@@ -0,0 +1,218 @@
+//! PTY-backed sessions running `openclaw` subcommands, streamed to the webview
+//! as `terminal-output` and `terminal-exit` events.
+
+use portable_pty::{native_pty_system, Child, CommandBuilder, MasterPty, PtySize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
+
+use crate::error::DesktopError;
+use crate::events::{TerminalExit, TerminalOutput, TERMINAL_EXIT_EVENT, TERMINAL_OUTPUT_EVENT};
+use crate::{kiosk, openclaw_command, openclaw_home_dir, policy};
+
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+const READ_BUFFER_BYTES: usize = 8 * 1024;
+const DEFAULT_COLS: u16 = 80;
+const DEFAULT_ROWS: u16 = 24;
+
+struct Session {
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    child: Box<dyn Child + Send + Sync>,
+}
+
+/// Open terminal sessions by id
+#[derive(Default)]
+pub struct TerminalSessions {
+    sessions: Mutex<HashMap<u64, Session>>,
+}
+
+impl TerminalSessions {
+    fn with_session<T>(
+        &self,
+        session_id: u64,
+        f: impl FnOnce(&mut Session) -> Result<T, String>,
+    ) -> Result<T, DesktopError> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|_| "Terminal sessions lock poisoned".to_string())?;
+        let session = sessions
+            .get_mut(&session_id)
+            .ok_or_else(|| DesktopError::NotFound(format!("No terminal session {}", session_id)))?;
+        Ok(f(session)?)
+    }
+}
+
+/// `openclaw` with the active profile and proxy settings, as a PTY command
+fn openclaw_pty_command(args: &[String]) -> Result<CommandBuilder, String> {
+    let command = openclaw_command();
+    let mut builder = CommandBuilder::new(command.get_program());
+    builder.args(command.get_args());
+    builder.args(args);
+    for (key, value) in command.get_envs() {
+        match value {
+            Some(value) => builder.env(key, value),
+            None => builder.env_remove(key),
+        }
+    }
+    builder.env("TERM", "xterm-256color");
+    builder.cwd(openclaw_home_dir()?);
+    Ok(builder)
+}
+
+/// Split off a trailing partial UTF-8 sequence so a character cut across two
+/// reads isn't turned into replacement characters
+fn take_utf8(pending: &mut Vec<u8>) -> String {
+    let valid = match std::str::from_utf8(pending) {
+        Ok(_) => pending.len(),
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        Err(_) => pending.len(),
+    };
+    let rest = pending.split_off(valid);
+    let text = String::from_utf8_lossy(pending).into_owned();
+    *pending = rest;
+    text
+}
+
+fn forward_output<R: Runtime>(
+    app: AppHandle<R>,
+    session_id: u64,
+    mut reader: Box<dyn Read + Send>,
+) {
+    let mut buf = [0u8; READ_BUFFER_BYTES];
+    let mut pending = Vec::new();
+    loop {
+        match reader.read(&mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                pending.extend_from_slice(&buf[..n]);
+                let data = take_utf8(&mut pending);
+                if data.is_empty() {
+                    continue;
+                }
+                let payload = TerminalOutput { session_id, data };
+                if let Err(e) = app.emit(TERMINAL_OUTPUT_EVENT, payload) {
+                    log_error!("Failed to emit terminal output: {}", e);
+                }
+            }
+        }
+    }
+
+    // The reader ends when the child exits and closes its side of the PTY
+    let code = app
+        .state::<TerminalSessions>()
+        .sessions
+        .lock()
+        .ok()
+        .and_then(|mut sessions| sessions.remove(&session_id))
+        .and_then(|mut session| session.child.wait().ok())
+        .map(|status| status.exit_code());
+    if let Err(e) = app.emit(TERMINAL_EXIT_EVENT, TerminalExit { session_id, code }) {
+        log_error!("Failed to emit terminal exit: {}", e);
+    }
+}
+
+/// Run `openclaw <args>` in a new pseudo-terminal and return its session id
+#[tauri::command]
+pub fn create_terminal_session(
+    app: AppHandle,
+    sessions: State<'_, TerminalSessions>,
+    args: Vec<String>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+) -> Result<u64, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    policy::ensure_passthrough_allowed()?;
+    let pair = native_pty_system()
+        .openpty(PtySize {
+            rows: rows.unwrap_or(DEFAULT_ROWS),
+            cols: cols.unwrap_or(DEFAULT_COLS),
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|e| format!("Failed to open terminal: {}", e))?;
+    let child = pair
+        .slave
+        .spawn_command(openclaw_pty_command(&args)?)
+        .map_err(|e| format!("Failed to start openclaw: {}", e))?;
+    // Only the child holds the slave, so the reader sees EOF when it exits
+    drop(pair.slave);
+
+    let reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|e| format!("Failed to read terminal: {}", e))?;
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|e| format!("Failed to write terminal: {}", e))?;
+
+    let session_id = NEXT_SESSION_ID.fetch_add(1, Ordering::Relaxed);
+    sessions
+        .sessions
+        .lock()
+        .map_err(|_| "Terminal sessions lock poisoned".to_string())?
+        .insert(
+            session_id,
+            Session {
+                master: pair.master,
+                writer,
+                child,
+            },
+        );
+    std::thread::spawn(move || forward_output(app, session_id, reader));
+    Ok(session_id)
+}
+
+/// Send keystrokes or pasted text to a session
+#[tauri::command]
+pub fn write_terminal_input(
+    sessions: State<'_, TerminalSessions>,
+    session_id: u64,
+    data: String,
+) -> Result<(), DesktopError> {
+    sessions.with_session(session_id, |session| {
+        session
+            .writer
+            .write_all(data.as_bytes())
+            .and_then(|_| session.writer.flush())
+            .map_err(|e| format!("Failed to write to terminal: {}", e))
+    })
+}
+
+#[tauri::command]
+pub fn resize_terminal(
+    sessions: State<'_, TerminalSessions>,
+    session_id: u64,
+    cols: u16,
+    rows: u16,
+) -> Result<(), DesktopError> {
+    sessions.with_session(session_id, |session| {
+        session
+            .master
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| format!("Failed to resize terminal: {}", e))
+    })
+}
+
+/// Kill the session's process; `terminal-exit` follows once its output is drained
+#[tauri::command]
+pub fn kill_terminal_session(
+    sessions: State<'_, TerminalSessions>,
+    session_id: u64,
+) -> Result<(), DesktopError> {
+    sessions.with_session(session_id, |session| {
+        session
+            .child
+            .kill()
+            .map_err(|e| format!("Failed to kill terminal process: {}", e))
+    })
+}
@@ -0,0 +1,190 @@
+//! Light and dark mode across the OS, the app's own UI and the dashboard.
+//!
+//! The app's windows follow the preference natively. The dashboard gets the
+//! effective scheme as a `theme=` query parameter when it is opened, and as a
+//! `postMessage` when it changes while open.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewWindow};
+use ts_rs::TS;
+
+use crate::error::DesktopError;
+use crate::events::THEME_CHANGED_EVENT;
+use crate::{settings, window};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum ThemePreference {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+}
+
+impl ColorScheme {
+    fn as_str(self) -> &'static str {
+        match self {
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct ThemeState {
+    pub preference: ThemePreference,
+    /// The OS setting, when it can be read
+    pub system: Option<ColorScheme>,
+    /// What the app and the dashboard should show
+    pub effective: ColorScheme,
+}
+
+/// Last state sent out, so the watcher only reports changes
+static LAST: Mutex<Option<ThemeState>> = Mutex::new(None);
+
+fn system_scheme() -> Option<ColorScheme> {
+    match dark_light::detect() {
+        dark_light::Mode::Dark => Some(ColorScheme::Dark),
+        dark_light::Mode::Light => Some(ColorScheme::Light),
+        dark_light::Mode::Default => None,
+    }
+}
+
+fn state() -> ThemeState {
+    let preference = settings::current().theme;
+    let system = system_scheme();
+    let effective = match preference {
+        ThemePreference::Light => ColorScheme::Light,
+        ThemePreference::Dark => ColorScheme::Dark,
+        ThemePreference::System => system.unwrap_or(ColorScheme::Light),
+    };
+    ThemeState {
+        preference,
+        system,
+        effective,
+    }
+}
+
+fn native_theme(preference: ThemePreference) -> Option<tauri::Theme> {
+    match preference {
+        ThemePreference::System => None,
+        ThemePreference::Light => Some(tauri::Theme::Light),
+        ThemePreference::Dark => Some(tauri::Theme::Dark),
+    }
+}
+
+/// Give a newly created window the preferred theme
+pub(crate) fn apply_to<R: Runtime>(window: &WebviewWindow<R>) {
+    if let Err(e) = window.set_theme(native_theme(settings::current().theme)) {
+        log_error!("Failed to set window theme: {}", e);
+    }
+}
+
+/// Add the effective scheme to a dashboard URL
+pub(crate) fn with_theme_param(url: String) -> String {
+    let Ok(mut parsed) = tauri::Url::parse(&url) else {
+        return url;
+    };
+    let scheme = LAST
+        .lock()
+        .ok()
+        .and_then(|last| last.map(|s| s.effective))
+        .unwrap_or_else(|| state().effective);
+    parsed
+        .query_pairs_mut()
+        .append_pair("theme", scheme.as_str());
+    parsed.to_string()
+}
+
+/// Whether the window shows the dashboard rather than the app's own page
+fn on_dashboard<R: Runtime>(window: &WebviewWindow<R>) -> bool {
+    let (Some(app_url), Ok(current)) = (window::app_url(), window.url()) else {
+        return false;
+    };
+    current.scheme() != app_url.scheme() || current.host_str() != app_url.host_str()
+}
+
+/// Restyle the windows and tell the app UI and any open dashboard
+fn publish<R: Runtime>(app: &AppHandle<R>, state: ThemeState) {
+    if let Ok(mut last) = LAST.lock() {
+        *last = Some(state);
+    }
+    let script = format!(
+        "window.postMessage({{ type: 'openclaw:theme', theme: '{}' }}, '*')",
+        state.effective.as_str()
+    );
+    for window in app.webview_windows().values() {
+        if let Err(e) = window.set_theme(native_theme(state.preference)) {
+            log_error!("Failed to set window theme: {}", e);
+        }
+        if on_dashboard(window) {
+            if let Err(e) = window.eval(&script) {
+                log_error!("Failed to send theme to dashboard: {}", e);
+            }
+        }
+    }
+    if let Err(e) = app.emit(THEME_CHANGED_EVENT, state) {
+        log_error!("Failed to emit theme change: {}", e);
+    }
+}
+
+/// Follow the OS setting, reporting each change
+pub fn spawn_theme_watcher<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let Ok(current) = tauri::async_runtime::spawn_blocking(state).await else {
+                continue;
+            };
+            let last = LAST.lock().ok().and_then(|last| *last);
+            if last.is_none() {
+                if let Ok(mut last) = LAST.lock() {
+                    *last = Some(current);
+                }
+            } else if last != Some(current) {
+                publish(&app, current);
+            }
+        }
+    });
+}
+
+/// The OS color scheme, the preference, and what is in effect
+#[tauri::command]
+pub async fn get_system_theme() -> ThemeState {
+    tauri::async_runtime::spawn_blocking(state)
+        .await
+        .unwrap_or(ThemeState {
+            preference: settings::current().theme,
+            system: None,
+            effective: ColorScheme::Light,
+        })
+}
+
+/// Follow the OS, or force light or dark, in the app and the dashboard
+#[tauri::command]
+pub async fn set_theme_preference(
+    app: AppHandle,
+    preference: ThemePreference,
+) -> Result<ThemeState, DesktopError> {
+    settings::update(|s| s.theme = preference)?;
+    let state = tauri::async_runtime::spawn_blocking(state)
+        .await
+        .map_err(|e| format!("Failed to read system theme: {}", e))?;
+    publish(&app, state);
+    Ok(state)
+}
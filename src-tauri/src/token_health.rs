@@ -0,0 +1,206 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::time::MissedTickBehavior;
+
+use crate::error::DesktopError;
+use crate::events::{TokenInvalid, TOKEN_INVALID_EVENT};
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+use crate::i18n::t;
+use crate::notifications::{self, NotificationCategory};
+use crate::{connection, is_gateway_running, kiosk, read_config_token, secrets, tokens};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long to wait for a restarted gateway to accept a freshly generated token
+const ROTATE_WAIT: Duration = Duration::from_secs(30);
+
+/// Unix seconds since the gateway started rejecting the stored token
+static INVALID_SINCE: Mutex<Option<u64>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenSource {
+    Keychain,
+    Config,
+    Generated,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenReacquire {
+    /// The gateway accepts the token now in use
+    pub valid: bool,
+    pub source: Option<TokenSource>,
+    pub fingerprint: Option<String>,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// When the stored token was first rejected, while it still is
+pub fn invalid_since() -> Option<u64> {
+    INVALID_SINCE.lock().ok().and_then(|since| *since)
+}
+
+fn set_valid(valid: bool) -> Option<u64> {
+    let mut since = INVALID_SINCE.lock().ok()?;
+    match (valid, *since) {
+        (true, _) => {
+            *since = None;
+            None
+        }
+        // Already reported
+        (false, Some(_)) => None,
+        (false, None) => {
+            let now = now_secs();
+            *since = Some(now);
+            Some(now)
+        }
+    }
+}
+
+/// Whether the gateway accepts `token`, or `None` when it couldn't be asked
+async fn accepts(token: &str) -> Option<bool> {
+    let client = connection::apply_tls(reqwest::Client::builder().timeout(CHECK_TIMEOUT))
+        .ok()?
+        .build()
+        .ok()?;
+    let response = client
+        .get(format!("{}/api/sessions", connection::base_url()))
+        .bearer_auth(token)
+        .send()
+        .await
+        .ok()?;
+    match response.status().as_u16() {
+        401 | 403 => Some(false),
+        _ => Some(true),
+    }
+}
+
+async fn check<R: Runtime>(app: &AppHandle<R>) {
+    if !connection::is_remote()
+        && !tauri::async_runtime::spawn_blocking(is_gateway_running)
+            .await
+            .unwrap_or(false)
+    {
+        return;
+    }
+    let valid = match app.state::<GatewayApi>().list_sessions().await {
+        Ok(_) => true,
+        Err(e) if e.kind == GatewayApiErrorKind::Unauthorized => false,
+        // Unreachable or unhappy for other reasons; says nothing about the token
+        Err(_) => return,
+    };
+    let Some(since) = set_valid(valid) else {
+        return;
+    };
+    let payload = TokenInvalid {
+        since,
+        detail: t("token.invalid_body"),
+    };
+    if let Err(e) = app.emit(TOKEN_INVALID_EVENT, &payload) {
+        log_error!("Failed to emit token-invalid event: {}", e);
+    }
+    notifications::notify(
+        app,
+        NotificationCategory::TokenInvalid,
+        &t("token.invalid"),
+        &payload.detail,
+    );
+}
+
+/// Ping the gateway with the stored token every few minutes and report when it
+/// starts being rejected
+pub fn spawn_token_monitor<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            check(&app).await;
+        }
+    });
+}
+
+/// Recover from a rejected token.
+///
+/// Tries the keychain and `openclaw.json` copies in turn and keeps whichever the
+/// gateway accepts, so a token rotated by the CLI replaces a stale keychain copy.
+/// With `generate`, a local gateway gets a new token when neither works.
+#[tauri::command]
+pub async fn reacquire_token(
+    app: AppHandle,
+    generate: bool,
+) -> Result<TokenReacquire, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    if connection::is_remote() {
+        // Remote tokens only come from the user
+        let token = secrets::remote_connection_token();
+        let valid = match &token {
+            Some(token) => accepts(token).await.unwrap_or(false),
+            None => false,
+        };
+        set_valid(valid);
+        return Ok(TokenReacquire {
+            valid,
+            source: token.as_ref().map(|_| TokenSource::Keychain),
+            fingerprint: tokens::get_token_fingerprint(),
+        });
+    }
+
+    let (keychain, config) =
+        tauri::async_runtime::spawn_blocking(|| (secrets::keychain_token(), read_config_token()))
+            .await
+            .map_err(|e| format!("Failed to read token: {}", e))?;
+    let candidates = [
+        (TokenSource::Keychain, keychain.clone()),
+        (TokenSource::Config, config),
+    ];
+    for (source, token) in candidates {
+        let Some(token) = token else {
+            continue;
+        };
+        if accepts(&token).await == Some(true) {
+            if source == TokenSource::Config && keychain.is_some() {
+                secrets::store_token(&token)?;
+            }
+            set_valid(true);
+            return Ok(TokenReacquire {
+                valid: true,
+                source: Some(source),
+                fingerprint: tokens::get_token_fingerprint(),
+            });
+        }
+    }
+
+    if !generate {
+        return Ok(TokenReacquire {
+            valid: false,
+            source: None,
+            fingerprint: tokens::get_token_fingerprint(),
+        });
+    }
+
+    let fingerprint = tokens::rotate_gateway_token(app.clone()).await?;
+    let token = crate::read_gateway_token().ok_or("No gateway token after rotation")?;
+    let deadline = tokio::time::Instant::now() + ROTATE_WAIT;
+    let mut valid = false;
+    while tokio::time::Instant::now() < deadline {
+        if accepts(&token).await == Some(true) {
+            valid = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    set_valid(valid);
+    Ok(TokenReacquire {
+        valid,
+        source: Some(TokenSource::Generated),
+        fingerprint: Some(fingerprint),
+    })
+}
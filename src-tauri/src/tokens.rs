@@ -0,0 +1,143 @@
+use rand::RngCore;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::audit;
+use crate::capabilities::{self, GatewayFeature};
+use crate::config;
+use crate::error::DesktopError;
+use crate::events::CONFIG_CHANGED_EVENT;
+use crate::kiosk;
+use crate::permissions::{self, Permission};
+use crate::secrets;
+use crate::{
+    dashboard_base_url, is_gateway_running, read_gateway_token, restart_default_gateway,
+    tokenized_dashboard_url,
+};
+
+const TOKEN_BYTES: usize = 32;
+
+pub(crate) fn generate_token() -> String {
+    let mut bytes = [0u8; TOKEN_BYTES];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Compare tokens without leaking through timing where they differ
+pub(crate) fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a.bytes()
+            .zip(b.bytes())
+            .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+            == 0
+}
+
+/// Short, non-reversible identifier for a token, e.g. `3f2a:91c0:77de:0b14`
+fn fingerprint(token: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(token.as_bytes()));
+    digest.as_bytes()[..16]
+        .chunks(4)
+        .map(|chunk| String::from_utf8_lossy(chunk).to_string())
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn object_entry<'a>(map: &'a mut Map<String, Value>, key: &str) -> &'a mut Map<String, Value> {
+    let entry = map
+        .entry(key.to_string())
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !entry.is_object() {
+        *entry = Value::Object(Map::new());
+    }
+    entry
+        .as_object_mut()
+        .expect("entry was just made an object")
+}
+
+/// Generate a new gateway auth token, save it where the current one lives, and restart a running
+/// gateway so it takes effect. Returns the new token's fingerprint.
+#[tauri::command]
+pub async fn rotate_gateway_token(app: AppHandle) -> Result<String, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let result = tauri::async_runtime::spawn_blocking(move || rotate_token(&app))
+        .await
+        .map_err(|e| format!("Failed to generate token: {}", e))?;
+    audit::record_result("token_rotate", "gateway", &result);
+    result
+}
+
+fn rotate_token(app: &AppHandle) -> Result<String, DesktopError> {
+    let mut config = config::load_config()?;
+    let root = config
+        .as_object_mut()
+        .ok_or("Config must be a JSON object")?;
+
+    let token = generate_token();
+    let in_keychain = secrets::keychain_token().is_some();
+    if in_keychain {
+        secrets::store_token(&token)?;
+    }
+
+    // A token migrated out of the config stays out of it
+    let auth = object_entry(object_entry(root, "gateway"), "auth");
+    if !in_keychain || auth.contains_key("token") {
+        auth.insert("token".to_string(), Value::String(token.clone()));
+        config::write_atomic(&config)?;
+        if let Err(e) = app.emit(CONFIG_CHANGED_EVENT, &config) {
+            log_error!("Failed to emit config change: {}", e);
+        }
+    }
+
+    // A keychain token reaches the gateway through its environment, so only a
+    // config-only change can be picked up by hot reload
+    let hot_reload = !in_keychain && capabilities::require(app, GatewayFeature::HotReload).is_ok();
+    if is_gateway_running() && !hot_reload {
+        restart_default_gateway(app)?;
+    }
+    Ok(fingerprint(&token))
+}
+
+/// Fingerprint of the current gateway token, for comparing without revealing it
+#[tauri::command]
+pub fn get_token_fingerprint() -> Option<String> {
+    read_gateway_token().map(|token| fingerprint(&token))
+}
+
+#[tauri::command]
+pub async fn copy_token_to_clipboard(app: AppHandle) -> Result<(), DesktopError> {
+    kiosk::ensure_unlocked()?;
+    permissions::ensure_granted(&app, Permission::ReadToken).await?;
+    let token = read_gateway_token().ok_or("No gateway token is configured")?;
+    app.clipboard()
+        .write_text(token)
+        .map_err(|e| format!("Failed to copy token: {}", e))?;
+    Ok(())
+}
+
+/// Copy the dashboard URL. With `include_token` the URL logs in by itself, so
+/// it counts as handing out the token and needs the same permission.
+pub(crate) async fn copy_dashboard_url_to_clipboard<R: Runtime>(
+    app: &AppHandle<R>,
+    include_token: bool,
+) -> Result<(), DesktopError> {
+    let url = if kiosk::is_locked() {
+        // Viewer mode, never the token
+        tokenized_dashboard_url()
+    } else if include_token {
+        permissions::ensure_granted(app, Permission::ReadToken).await?;
+        tokenized_dashboard_url()
+    } else {
+        dashboard_base_url()
+    };
+    app.clipboard()
+        .write_text(url)
+        .map_err(|e| format!("Failed to copy dashboard URL: {}", e))?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn copy_dashboard_url(app: AppHandle, include_token: bool) -> Result<(), DesktopError> {
+    copy_dashboard_url_to_clipboard(&app, include_token).await
+}
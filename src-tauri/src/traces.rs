@@ -0,0 +1,514 @@
+use rusqlite::{params, params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::error::DesktopError;
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+use crate::{
+    connection, focus, is_gateway_running, kiosk, openclaw_home_dir, start_default_gateway,
+    stop_default_gateway,
+};
+
+const DB_FILE: &str = "traces.db";
+/// Written by gateways that only support tracing through `OPENCLAW_TRACE_FILE`
+const TRACE_FILE: &str = "request-trace.jsonl";
+const MAX_MINUTES: u64 = 60;
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+/// Oldest spans are dropped beyond this many
+const MAX_SPANS: i64 = 50_000;
+const DEFAULT_TRACE_LIMIT: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TracingMode {
+    /// Switched on through the gateway's debug API
+    Api,
+    /// The gateway was restarted with a trace file in its environment
+    Restart,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TracingStatus {
+    pub active: bool,
+    pub mode: Option<TracingMode>,
+    /// Unix seconds when tracing switches off
+    pub until: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TracingSession {
+    mode: TracingMode,
+    until: u64,
+    generation: u64,
+}
+
+/// One timed operation inside a request, e.g. the HTTP handler, an agent turn,
+/// a tool call, or a model call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceSpan {
+    #[serde(alias = "traceId")]
+    pub trace_id: String,
+    #[serde(alias = "spanId", alias = "id")]
+    pub span_id: String,
+    #[serde(default, alias = "parentId")]
+    pub parent_id: Option<String>,
+    pub name: String,
+    /// `http`, `agent`, `tool`, `model`, ...
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Unix milliseconds
+    #[serde(alias = "startMs", alias = "startTime")]
+    pub start_ms: u64,
+    #[serde(alias = "durationMs", alias = "duration")]
+    pub duration_ms: f64,
+    #[serde(default)]
+    pub status: Option<u16>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub attributes: Value,
+}
+
+/// All spans of one request, ordered by start for a waterfall
+#[derive(Debug, Clone, Serialize)]
+pub struct Trace {
+    pub trace_id: String,
+    /// Name of the root span
+    pub name: String,
+    pub start_ms: u64,
+    pub duration_ms: f64,
+    pub has_error: bool,
+    pub spans: Vec<TraceSpan>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TraceFilter {
+    /// Unix milliseconds
+    pub since_ms: Option<u64>,
+    pub min_duration_ms: Option<f64>,
+    /// Substring of any span name in the trace
+    pub name: Option<String>,
+    pub errors_only: bool,
+    pub limit: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SpansResponse {
+    List(Vec<TraceSpan>),
+    Wrapped { spans: Vec<TraceSpan> },
+}
+
+static DB: Mutex<Option<Connection>> = Mutex::new(None);
+static SESSION: Mutex<Option<TracingSession>> = Mutex::new(None);
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn trace_file() -> Result<PathBuf, String> {
+    Ok(openclaw_home_dir()?.join(TRACE_FILE))
+}
+
+fn current_session() -> Option<TracingSession> {
+    SESSION
+        .lock()
+        .ok()?
+        .filter(|session| session.until > now_secs())
+}
+
+/// Trace file to hand a gateway being spawned while restart-mode tracing is on
+pub(crate) fn env_trace_file() -> Option<PathBuf> {
+    current_session()
+        .filter(|session| session.mode == TracingMode::Restart)
+        .and_then(|_| trace_file().ok())
+}
+
+fn status() -> TracingStatus {
+    let session = current_session();
+    TracingStatus {
+        active: session.is_some(),
+        mode: session.map(|s| s.mode),
+        until: session.map(|s| s.until),
+    }
+}
+
+fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut guard = DB
+        .lock()
+        .map_err(|_| "Trace store lock poisoned".to_string())?;
+    if guard.is_none() {
+        let conn = Connection::open(openclaw_home_dir()?.join(DB_FILE))
+            .map_err(|e| format!("Failed to open trace store: {}", e))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS spans (
+                span_id TEXT PRIMARY KEY,
+                trace_id TEXT NOT NULL,
+                parent_id TEXT,
+                name TEXT NOT NULL,
+                kind TEXT,
+                start_ms INTEGER NOT NULL,
+                duration_ms REAL NOT NULL,
+                status INTEGER,
+                error TEXT,
+                attributes TEXT
+            );
+            CREATE INDEX IF NOT EXISTS spans_trace ON spans (trace_id);
+            CREATE INDEX IF NOT EXISTS spans_start ON spans (start_ms)",
+        )
+        .map_err(|e| format!("Failed to create trace store: {}", e))?;
+        *guard = Some(conn);
+    }
+    let conn = guard.as_ref().ok_or("Trace store unavailable")?;
+    f(conn).map_err(|e| format!("Trace store error: {}", e))
+}
+
+fn store(spans: &[TraceSpan]) -> Result<(), String> {
+    if spans.is_empty() {
+        return Ok(());
+    }
+    with_db(|conn| {
+        for span in spans {
+            conn.execute(
+                "INSERT OR REPLACE INTO spans
+                    (span_id, trace_id, parent_id, name, kind, start_ms, duration_ms,
+                     status, error, attributes)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    span.span_id,
+                    span.trace_id,
+                    span.parent_id,
+                    span.name,
+                    span.kind,
+                    span.start_ms as i64,
+                    span.duration_ms,
+                    span.status,
+                    span.error,
+                    span.attributes.to_string()
+                ],
+            )?;
+        }
+        conn.execute(
+            "DELETE FROM spans WHERE rowid <= (SELECT MAX(rowid) FROM spans) - ?1",
+            params![MAX_SPANS],
+        )?;
+        Ok(())
+    })
+}
+
+/// Spans appended to the trace file since `offset`, starting over when it was truncated
+fn read_trace_file(offset: &mut u64) -> Vec<TraceSpan> {
+    let Some(mut file) = trace_file().ok().and_then(|path| File::open(path).ok()) else {
+        return Vec::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or_default();
+    if len < *offset {
+        *offset = 0;
+    }
+    if file.seek(SeekFrom::Start(*offset)).is_err() {
+        return Vec::new();
+    }
+    let mut spans = Vec::new();
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    while let Ok(n) = reader.read_line(&mut line) {
+        // A partial line is picked up once the gateway finishes writing it
+        if n == 0 || !line.ends_with('\n') {
+            break;
+        }
+        *offset += n as u64;
+        match serde_json::from_str::<TraceSpan>(line.trim()) {
+            Ok(span) => spans.push(span),
+            Err(e) => log_error!("Skipping malformed trace line: {}", e),
+        }
+        line.clear();
+    }
+    spans
+}
+
+async fn fetch_spans<R: Runtime>(app: &AppHandle<R>, since_ms: u64) -> Vec<TraceSpan> {
+    let path = format!("/api/debug/traces?since={}", since_ms);
+    match app.state::<GatewayApi>().get::<SpansResponse>(&path).await {
+        Ok(SpansResponse::List(spans)) | Ok(SpansResponse::Wrapped { spans }) => spans,
+        Err(e) => {
+            log_error!("Failed to fetch traces: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Restart the local gateway so a change to its tracing environment takes effect;
+/// a gateway the user stopped in the meantime stays stopped
+fn restart_for_tracing<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    if !is_gateway_running() {
+        return Ok(());
+    }
+    stop_default_gateway(app)?;
+    start_default_gateway(app).map(|_| ())
+}
+
+/// Pull spans into the store until the session ends or a newer one replaces it
+fn spawn_collector<R: Runtime>(app: AppHandle<R>, session: TracingSession) {
+    tauri::async_runtime::spawn(async move {
+        let mut since_ms = now_secs() * 1000;
+        let mut offset = 0;
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            if GENERATION.load(Ordering::SeqCst) != session.generation {
+                return;
+            }
+            let spans = match session.mode {
+                TracingMode::Api => fetch_spans(&app, since_ms).await,
+                TracingMode::Restart => read_trace_file(&mut offset),
+            };
+            if let Some(latest) = spans.iter().map(|s| s.start_ms).max() {
+                since_ms = since_ms.max(latest + 1);
+            }
+            let result = tauri::async_runtime::spawn_blocking(move || store(&spans)).await;
+            if let Ok(Err(e)) = result {
+                log_error!("Failed to store traces: {}", e);
+            }
+            if now_secs() >= session.until {
+                break;
+            }
+        }
+
+        if let Ok(mut current) = SESSION.lock() {
+            if current.is_some_and(|s| s.generation == session.generation) {
+                *current = None;
+            }
+        }
+        if session.mode == TracingMode::Restart {
+            focus::wait_until_available().await;
+            if GENERATION.load(Ordering::SeqCst) == session.generation {
+                let result =
+                    tauri::async_runtime::spawn_blocking(move || restart_for_tracing(&app)).await;
+                if let Ok(Err(e)) = result {
+                    log_error!("Failed to restart gateway after tracing: {}", e);
+                }
+            }
+        }
+    });
+}
+
+/// Turn on request tracing for `minutes`, through the gateway's debug API when it has
+/// one and otherwise by restarting the local gateway with a trace file configured
+#[tauri::command]
+pub async fn enable_request_tracing(
+    app: AppHandle,
+    minutes: u64,
+) -> Result<TracingStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let minutes = minutes.clamp(1, MAX_MINUTES);
+    let until = now_secs() + minutes * 60;
+    let body = json!({ "enabled": true, "durationSecs": minutes * 60 });
+
+    let mode = match app
+        .state::<GatewayApi>()
+        .post::<Value>("/api/debug/tracing", &body)
+        .await
+    {
+        Ok(_) => TracingMode::Api,
+        Err(e) if e.kind == GatewayApiErrorKind::NotFound && !connection::is_remote() => {
+            TracingMode::Restart
+        }
+        Err(e) if e.kind == GatewayApiErrorKind::NotFound => {
+            return Err(DesktopError::Unavailable(
+                "This gateway does not expose a tracing API".to_string(),
+            ))
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let session = TracingSession {
+        mode,
+        until,
+        generation: GENERATION.fetch_add(1, Ordering::SeqCst) + 1,
+    };
+    *SESSION
+        .lock()
+        .map_err(|_| "Tracing state lock poisoned".to_string())? = Some(session);
+
+    if mode == TracingMode::Restart {
+        let path = trace_file()?;
+        let task_app = app.clone();
+        let result = tauri::async_runtime::spawn_blocking(move || {
+            // Spans from an earlier session were already collected
+            let _ = std::fs::remove_file(path);
+            restart_for_tracing(&task_app)
+        })
+        .await
+        .map_err(|e| format!("Failed to restart gateway: {}", e))?;
+        if let Err(e) = result {
+            GENERATION.fetch_add(1, Ordering::SeqCst);
+            if let Ok(mut current) = SESSION.lock() {
+                *current = None;
+            }
+            return Err(e.into());
+        }
+    }
+
+    spawn_collector(app, session);
+    Ok(status())
+}
+
+/// Stop tracing before its time is up
+#[tauri::command]
+pub async fn disable_request_tracing(app: AppHandle) -> Result<TracingStatus, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let Some(session) = current_session() else {
+        return Ok(status());
+    };
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    *SESSION
+        .lock()
+        .map_err(|_| "Tracing state lock poisoned".to_string())? = None;
+
+    match session.mode {
+        TracingMode::Api => {
+            let body = json!({ "enabled": false });
+            if let Err(e) = app
+                .state::<GatewayApi>()
+                .post::<Value>("/api/debug/tracing", &body)
+                .await
+            {
+                log_error!("Failed to switch off gateway tracing: {}", e);
+            }
+        }
+        TracingMode::Restart => {
+            tauri::async_runtime::spawn_blocking(move || restart_for_tracing(&app))
+                .await
+                .map_err(|e| format!("Failed to restart gateway: {}", e))??;
+        }
+    }
+    Ok(status())
+}
+
+#[tauri::command]
+pub fn get_request_tracing_status() -> TracingStatus {
+    status()
+}
+
+fn span_from_row(row: &rusqlite::Row) -> rusqlite::Result<TraceSpan> {
+    let attributes: Option<String> = row.get(9)?;
+    Ok(TraceSpan {
+        span_id: row.get(0)?,
+        trace_id: row.get(1)?,
+        parent_id: row.get(2)?,
+        name: row.get(3)?,
+        kind: row.get(4)?,
+        start_ms: row.get::<_, i64>(5)? as u64,
+        duration_ms: row.get(6)?,
+        status: row.get(7)?,
+        error: row.get(8)?,
+        attributes: attributes
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or(Value::Null),
+    })
+}
+
+fn query(filter: &TraceFilter) -> Result<Vec<Trace>, String> {
+    let limit = filter.limit.unwrap_or(DEFAULT_TRACE_LIMIT).clamp(1, 1000);
+    with_db(|conn| {
+        let mut statement = conn.prepare(
+            "SELECT trace_id, MIN(start_ms) AS started,
+                    MAX(start_ms + duration_ms) - MIN(start_ms) AS duration
+             FROM spans
+             WHERE trace_id IN (
+                SELECT trace_id FROM spans WHERE ?1 IS NULL OR instr(lower(name), lower(?1)) > 0
+             )
+             GROUP BY trace_id
+             HAVING started >= ?2
+                AND duration >= ?3
+                AND (?4 = 0 OR SUM(error IS NOT NULL OR status >= 500) > 0)
+             ORDER BY started DESC
+             LIMIT ?5",
+        )?;
+        let summaries = statement
+            .query_map(
+                params![
+                    filter.name.as_deref().filter(|n| !n.is_empty()),
+                    filter.since_ms.unwrap_or(0) as i64,
+                    filter.min_duration_ms.unwrap_or(0.0),
+                    filter.errors_only,
+                    limit
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, i64>(1)? as u64,
+                        row.get::<_, f64>(2)?,
+                    ))
+                },
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        if summaries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = vec!["?"; summaries.len()].join(", ");
+        let mut statement = conn.prepare(&format!(
+            "SELECT span_id, trace_id, parent_id, name, kind, start_ms, duration_ms,
+                    status, error, attributes
+             FROM spans WHERE trace_id IN ({}) ORDER BY start_ms",
+            placeholders
+        ))?;
+        let spans = statement
+            .query_map(
+                params_from_iter(summaries.iter().map(|(id, ..)| id)),
+                span_from_row,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(summaries
+            .into_iter()
+            .map(|(trace_id, start_ms, duration_ms)| {
+                let spans: Vec<TraceSpan> = spans
+                    .iter()
+                    .filter(|span| span.trace_id == trace_id)
+                    .cloned()
+                    .collect();
+                let name = spans
+                    .iter()
+                    .find(|span| span.parent_id.is_none())
+                    .or(spans.first())
+                    .map(|span| span.name.clone())
+                    .unwrap_or_default();
+                let has_error = spans
+                    .iter()
+                    .any(|span| span.error.is_some() || span.status.is_some_and(|s| s >= 500));
+                Trace {
+                    trace_id,
+                    name,
+                    start_ms,
+                    duration_ms,
+                    has_error,
+                    spans,
+                }
+            })
+            .collect())
+    })
+}
+
+/// Collected request traces, newest first, each with its spans for a waterfall view
+#[tauri::command]
+pub async fn get_traces(filter: Option<TraceFilter>) -> Result<Vec<Trace>, DesktopError> {
+    let filter = filter.unwrap_or_default();
+    let traces = tauri::async_runtime::spawn_blocking(move || query(&filter))
+        .await
+        .map_err(|e| format!("Failed to read traces: {}", e))??;
+    Ok(traces)
+}
@@ -0,0 +1,654 @@
+use std::sync::Mutex;
+use tauri::{
+    image::Image,
+    menu::{IconMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    AppHandle, Emitter, Manager, Runtime,
+};
+
+use crate::app_update;
+use crate::approvals;
+use crate::connection;
+use crate::error::DesktopError;
+use crate::events::{TrayTarget, TRAY_OPEN_EVENT};
+use crate::external::{self, PathKind};
+use crate::gateway_api::GatewayApi;
+use crate::i18n::{t, t_with};
+use crate::kiosk;
+use crate::logs;
+use crate::pause;
+use crate::probe::{self, GatewayProbeResult};
+use crate::profiles::active_profile;
+use crate::safe_mode;
+use crate::safety_mode::{self, SafetyMode};
+use crate::selftest;
+use crate::settings;
+use crate::shutdown;
+use crate::tokens;
+use crate::window::{show_log_window, show_main_window};
+use crate::{restart_default_gateway, start_default_gateway, stop_default_gateway};
+
+pub const TRAY_ID: &str = "main";
+
+/// Fallback badge colors for profiles without an explicit color in settings
+const PROFILE_PALETTE: [[u8; 3]; 6] = [
+    [0x3b, 0x82, 0xf6],
+    [0xa8, 0x55, 0xf7],
+    [0xf9, 0x73, 0x16],
+    [0x14, 0xb8, 0xa6],
+    [0xec, 0x48, 0x99],
+    [0xea, 0xb3, 0x08],
+];
+
+pub(crate) const RUNNING_GREEN: [u8; 3] = [0x22, 0xc5, 0x5e];
+pub(crate) const STOPPED_GRAY: [u8; 3] = [0x6b, 0x72, 0x80];
+/// Badge shown while the gateway has recently written to its error log
+const WARNING_AMBER: [u8; 3] = [0xf5, 0x9e, 0x0b];
+const CONFLICT_RED: [u8; 3] = [0xef, 0x44, 0x44];
+const PAUSED_BLUE: [u8; 3] = [0x3b, 0x82, 0xf6];
+/// Badge shown while tool calls wait for approval
+const APPROVAL_VIOLET: [u8; 3] = [0x8b, 0x5c, 0xf6];
+/// Status dot beside the first menu item
+const STATUS_DOT_SIZE: u32 = 16;
+/// macOS menu bar icons are 18pt tall; drawn at 2x for Retina
+const TEMPLATE_ICON_SIZE: u32 = 36;
+
+/// How many recent sessions and agents the tray lists
+const QUICK_LINK_LIMIT: usize = 5;
+const QUICK_LINK_LABEL_CHARS: usize = 40;
+
+/// Sessions and agents last fetched from the gateway, as `(id, label)` pairs
+struct QuickLinks {
+    sessions: Vec<(String, String)>,
+    agents: Vec<(String, String)>,
+}
+
+static QUICK_LINKS: Mutex<QuickLinks> = Mutex::new(QuickLinks {
+    sessions: Vec::new(),
+    agents: Vec::new(),
+});
+
+fn short_label(text: &str) -> String {
+    if text.chars().count() <= QUICK_LINK_LABEL_CHARS {
+        return text.to_string();
+    }
+    let mut label: String = text.chars().take(QUICK_LINK_LABEL_CHARS - 1).collect();
+    label.push('…');
+    label
+}
+
+/// Fetch recent sessions and agents from the gateway for the tray menu
+pub(crate) async fn load_quick_links<R: Runtime>(app: &AppHandle<R>) {
+    let api = app.state::<GatewayApi>();
+    let sessions = match api.list_sessions().await {
+        Ok(sessions) => sessions
+            .into_iter()
+            .take(QUICK_LINK_LIMIT)
+            .map(|s| {
+                let label = short_label(s.title.as_deref().unwrap_or(&s.id));
+                (s.id, label)
+            })
+            .collect(),
+        Err(e) => {
+            log_error!("Failed to load sessions for tray: {}", e);
+            Vec::new()
+        }
+    };
+    // Older gateways have no agents endpoint; the submenu is simply left out
+    let agents = api
+        .list_agents()
+        .await
+        .map(|agents| {
+            agents
+                .into_iter()
+                .take(QUICK_LINK_LIMIT)
+                .map(|a| {
+                    let label = short_label(a.name.as_deref().unwrap_or(&a.id));
+                    (a.id, label)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if let Ok(mut links) = QUICK_LINKS.lock() {
+        links.sessions = sessions;
+        links.agents = agents;
+    }
+}
+
+pub(crate) fn clear_quick_links() {
+    if let Ok(mut links) = QUICK_LINKS.lock() {
+        links.sessions.clear();
+        links.agents.clear();
+    }
+}
+
+fn quick_link_submenu<R: Runtime>(
+    app: &AppHandle<R>,
+    title: &str,
+    prefix: &str,
+    entries: &[(String, String)],
+) -> tauri::Result<Submenu<R>> {
+    let items = entries
+        .iter()
+        .map(|(id, label)| {
+            MenuItem::with_id(app, format!("{}:{}", prefix, id), label, true, None::<&str>)
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let refs: Vec<&dyn IsMenuItem<R>> = items.iter().map(|i| i as &dyn IsMenuItem<R>).collect();
+    Submenu::with_items(app, title, !items.is_empty(), &refs)
+}
+
+/// Gateway state as the tray shows it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GatewayState {
+    Running,
+    /// Running, but the error log has fresh entries
+    Degraded,
+    /// Running with agents paused
+    Paused,
+    Stopped,
+    /// Something other than the gateway answers on its port
+    PortConflict,
+}
+
+impl GatewayState {
+    fn color(self) -> [u8; 3] {
+        match self {
+            Self::Running => RUNNING_GREEN,
+            Self::Degraded => WARNING_AMBER,
+            Self::Paused => PAUSED_BLUE,
+            Self::Stopped => STOPPED_GRAY,
+            Self::PortConflict => CONFLICT_RED,
+        }
+    }
+}
+
+/// Probe once for both the menu and the icon
+fn gateway_state() -> (GatewayState, Option<String>) {
+    state_from(&probe::probe_gateway())
+}
+
+fn state_from(probe: &GatewayProbeResult) -> (GatewayState, Option<String>) {
+    let state = if !probe.verified && probe.reachable {
+        GatewayState::PortConflict
+    } else if !probe.verified {
+        GatewayState::Stopped
+    } else if pause::is_paused() {
+        GatewayState::Paused
+    } else if logs::has_recent_errors() {
+        GatewayState::Degraded
+    } else {
+        GatewayState::Running
+    };
+    (state, probe.version.clone())
+}
+
+fn create_tray_menu<R: Runtime>(
+    app: &AppHandle<R>,
+    state: GatewayState,
+    version: Option<&str>,
+) -> tauri::Result<Menu<R>> {
+    let running = matches!(
+        state,
+        GatewayState::Running | GatewayState::Degraded | GatewayState::Paused
+    );
+    // A remote gateway is started and stopped on its own host
+    let control = !connection::is_remote() && !kiosk::is_locked();
+    let status = match (state, version) {
+        (GatewayState::PortConflict, _) => t("tray.status.port_in_use"),
+        (GatewayState::Stopped, _) => t("tray.status.stopped"),
+        (GatewayState::Degraded, _) => t("tray.status.recent_errors"),
+        (GatewayState::Paused, _) => t("tray.status.paused"),
+        (GatewayState::Running, Some(version)) => {
+            t_with("tray.status.running_version", &[("version", &version)])
+        }
+        (GatewayState::Running, None) => t("tray.status.running"),
+    };
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &IconMenuItem::with_id(
+                app,
+                "status",
+                &status,
+                false,
+                Some(status_dot(state.color())),
+                None::<&str>,
+            )?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(
+                app,
+                "start",
+                t("tray.start"),
+                control && state == GatewayState::Stopped,
+                None::<&str>,
+            )?,
+            &MenuItem::with_id(
+                app,
+                "stop",
+                t("tray.stop"),
+                control && running,
+                None::<&str>,
+            )?,
+            &MenuItem::with_id(
+                app,
+                "restart",
+                t("tray.restart"),
+                control && running,
+                None::<&str>,
+            )?,
+            &if state == GatewayState::Paused {
+                MenuItem::with_id(app, "resume", t("tray.resume"), control, None::<&str>)?
+            } else {
+                MenuItem::with_id(
+                    app,
+                    "pause",
+                    t("tray.pause"),
+                    control && running,
+                    None::<&str>,
+                )?
+            },
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "dashboard", t("tray.dashboard"), true, None::<&str>)?,
+            &MenuItem::with_id(
+                app,
+                "copy_dashboard_url",
+                t("tray.copy_dashboard_url"),
+                running,
+                None::<&str>,
+            )?,
+            &MenuItem::with_id(app, "logs", t("tray.view_logs"), true, None::<&str>)?,
+            &MenuItem::with_id(
+                app,
+                "openclaw_folder",
+                t("tray.openclaw_folder"),
+                true,
+                None::<&str>,
+            )?,
+        ],
+    )?;
+
+    if let Some((version, downloaded)) = app_update::pending() {
+        let label = if downloaded {
+            t_with("tray.restart_to_update", &[("version", &version)])
+        } else {
+            t_with("tray.update_available", &[("version", &version)])
+        };
+        menu.append(&MenuItem::with_id(
+            app,
+            "app_update",
+            &label,
+            !kiosk::is_locked(),
+            None::<&str>,
+        )?)?;
+    }
+
+    let failures = selftest::failure_count();
+    if failures > 0 {
+        menu.insert(
+            &MenuItem::with_id(
+                app,
+                "selftest",
+                t_with("tray.selftest_failed", &[("count", &failures)]),
+                true,
+                None::<&str>,
+            )?,
+            1,
+        )?;
+    }
+
+    let safety_label = match safety_mode::effective() {
+        SafetyMode::Full => None,
+        SafetyMode::NoExec => Some(t("tray.safety_no_exec")),
+        SafetyMode::DryRun => Some(t("tray.safety_dry_run")),
+    };
+    if let Some(label) = safety_label {
+        menu.insert(
+            &MenuItem::with_id(app, "safety_mode", label, false, None::<&str>)?,
+            1,
+        )?;
+    }
+
+    let approvals = approvals::pending_count();
+    if approvals > 0 {
+        menu.insert(
+            &MenuItem::with_id(
+                app,
+                "approvals",
+                t_with("tray.approvals_pending", &[("count", &approvals)]),
+                true,
+                None::<&str>,
+            )?,
+            1,
+        )?;
+    }
+
+    if safe_mode::is_active() {
+        menu.insert(
+            &MenuItem::with_id(app, "safe_mode", t("tray.safe_mode"), false, None::<&str>)?,
+            1,
+        )?;
+    }
+
+    if running {
+        if let Ok(links) = QUICK_LINKS.lock() {
+            menu.append(&quick_link_submenu(
+                app,
+                &t("tray.recent_sessions"),
+                "session",
+                &links.sessions,
+            )?)?;
+            if !links.agents.is_empty() {
+                menu.append(&quick_link_submenu(
+                    app,
+                    &t("tray.agents"),
+                    "agent",
+                    &links.agents,
+                )?)?;
+            }
+        }
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(
+        app,
+        "quit",
+        t("tray.quit"),
+        true,
+        None::<&str>,
+    )?)?;
+
+    Ok(menu)
+}
+
+/// Bring up the main window and have it navigate to a session or agent
+fn open_target<R: Runtime>(app: &AppHandle<R>, target: TrayTarget) {
+    show_main_window(app);
+    if let Err(e) = app.emit(TRAY_OPEN_EVENT, target) {
+        log_error!("Failed to emit tray open event: {}", e);
+    }
+}
+
+fn parse_hex_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+/// Resolve the badge color for a profile: explicit setting first, then a stable palette pick
+fn profile_color(profile: &str) -> [u8; 3] {
+    if let Some(color) = settings::current()
+        .profile_colors
+        .get(profile)
+        .and_then(|c| parse_hex_color(c))
+    {
+        return color;
+    }
+    let hash = profile.bytes().fold(0usize, |acc, b| {
+        acc.wrapping_mul(31).wrapping_add(b as usize)
+    });
+    PROFILE_PALETTE[hash % PROFILE_PALETTE.len()]
+}
+
+/// Draw a filled circular badge in the bottom-right corner of an RGBA buffer.
+///
+/// A light ring separates the badge from the base artwork; when the gateway is
+/// down the fill is blended towards gray so the profile stays recognizable.
+fn compose_badge(base: &Image<'_>, color: [u8; 3], healthy: bool) -> Image<'static> {
+    let width = base.width();
+    let height = base.height();
+    let mut rgba = base.rgba().to_vec();
+
+    let fill = if healthy {
+        color
+    } else {
+        [
+            ((color[0] as u16 + STOPPED_GRAY[0] as u16 * 2) / 3) as u8,
+            ((color[1] as u16 + STOPPED_GRAY[1] as u16 * 2) / 3) as u8,
+            ((color[2] as u16 + STOPPED_GRAY[2] as u16 * 2) / 3) as u8,
+        ]
+    };
+
+    let radius = (width.min(height) as f32) * 0.22;
+    let ring = (radius * 0.2).max(1.0);
+    let cx = width as f32 - radius - 1.0;
+    let cy = height as f32 - radius - 1.0;
+
+    for y in 0..height {
+        for x in 0..width {
+            let dx = x as f32 + 0.5 - cx;
+            let dy = y as f32 + 0.5 - cy;
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance > radius {
+                continue;
+            }
+            let pixel = if distance > radius - ring {
+                [0xff, 0xff, 0xff]
+            } else {
+                fill
+            };
+            let idx = ((y * width + x) * 4) as usize;
+            rgba[idx] = pixel[0];
+            rgba[idx + 1] = pixel[1];
+            rgba[idx + 2] = pixel[2];
+            rgba[idx + 3] = 0xff;
+        }
+    }
+
+    Image::new_owned(rgba, width, height)
+}
+
+/// Coverage of a pixel centered `distance` from a circle's center, for smooth edges
+fn coverage(distance: f32, radius: f32) -> f32 {
+    (radius - distance + 0.5).clamp(0.0, 1.0)
+}
+
+/// A filled circle in `color`, the menu's stand-in for a status emoji
+pub(crate) fn status_dot(color: [u8; 3]) -> Image<'static> {
+    let size = STATUS_DOT_SIZE;
+    let center = size as f32 / 2.0;
+    let radius = center - 2.0;
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x as f32 + 0.5 - center;
+            let dy = y as f32 + 0.5 - center;
+            let alpha = coverage((dx * dx + dy * dy).sqrt(), radius);
+            rgba.extend_from_slice(&[color[0], color[1], color[2], (alpha * 255.0) as u8]);
+        }
+    }
+    Image::new_owned(rgba, size, size)
+}
+
+/// Monochrome menu bar glyph that macOS tints for light and dark mode: a ring,
+/// filled in while the gateway runs
+fn template_icon(state: GatewayState) -> Image<'static> {
+    let size = TEMPLATE_ICON_SIZE;
+    let center = size as f32 / 2.0;
+    let outer = center - 3.0;
+    let inner = outer - 4.0;
+    let core = (state != GatewayState::Stopped).then_some(inner - 4.0);
+    let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+    for y in 0..size {
+        for x in 0..size {
+            let dx = x as f32 + 0.5 - center;
+            let dy = y as f32 + 0.5 - center;
+            let distance = (dx * dx + dy * dy).sqrt();
+            let ring = coverage(distance, outer) - coverage(distance, inner);
+            let alpha = match core {
+                Some(core) => ring.max(coverage(distance, core)),
+                None => ring,
+            };
+            rgba.extend_from_slice(&[0, 0, 0, (alpha * 255.0) as u8]);
+        }
+    }
+    Image::new_owned(rgba, size, size)
+}
+
+/// Compose the tray icon for the gateway state and active profile, and whether
+/// it is a template image.
+///
+/// Problems, a pause and profiles need color, so they badge the app icon. Otherwise
+/// macOS gets a template glyph and other platforms a state-colored badge.
+fn tray_icon<R: Runtime>(
+    app: &AppHandle<R>,
+    state: GatewayState,
+) -> Option<(Image<'static>, bool)> {
+    let badge = match (state, active_profile()) {
+        (GatewayState::Degraded | GatewayState::PortConflict | GatewayState::Paused, _) => {
+            Some((state.color(), true))
+        }
+        _ if approvals::pending_count() > 0 => Some((APPROVAL_VIOLET, true)),
+        _ if selftest::failure_count() > 0 => Some((WARNING_AMBER, true)),
+        (_, Some(profile)) => Some((profile_color(&profile), state == GatewayState::Running)),
+        (_, None) if cfg!(target_os = "macos") => None,
+        (_, None) => Some((state.color(), true)),
+    };
+    match badge {
+        Some((color, healthy)) => {
+            let base = app.default_window_icon()?;
+            Some((compose_badge(base, color, healthy), false))
+        }
+        None => Some((template_icon(state), true)),
+    }
+}
+
+/// Rebuild the tray menu and icon so they reflect the current gateway state
+pub fn rebuild_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    if app.tray_by_id(TRAY_ID).is_none() {
+        return Ok(());
+    }
+    rebuild_tray_with(app, &probe::probe_gateway())
+}
+
+/// Rebuild the tray for a probe the caller already ran, without probing again
+pub fn rebuild_tray_with<R: Runtime>(
+    app: &AppHandle<R>,
+    probe: &GatewayProbeResult,
+) -> tauri::Result<()> {
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        let (state, version) = state_from(probe);
+        let menu = create_tray_menu(app, state, version.as_deref())?;
+        tray.set_menu(Some(menu))?;
+        match tray_icon(app, state) {
+            Some((icon, template)) => {
+                tray.set_icon(Some(icon))?;
+                tray.set_icon_as_template(template)?;
+            }
+            None => tray.set_icon(None)?,
+        }
+        // The pending count beside the icon, where the platform shows a title
+        let approvals = approvals::pending_count();
+        tray.set_title((approvals > 0).then(|| approvals.to_string()))?;
+    }
+    Ok(())
+}
+
+pub fn create_tray<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    let (state, version) = gateway_state();
+    let menu = create_tray_menu(app, state, version.as_deref())?;
+
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id.as_ref() {
+            "start" if !kiosk::is_locked() => {
+                let app = app.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    if let Err(e) = start_default_gateway(&app) {
+                        log_error!("Failed to start gateway from tray: {}", e);
+                    }
+                });
+            }
+            "stop" if !kiosk::is_locked() => {
+                let app = app.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    if let Err(e) = stop_default_gateway(&app) {
+                        log_error!("Failed to stop gateway from tray: {}", e);
+                    }
+                });
+            }
+            "restart" if !kiosk::is_locked() => {
+                let app = app.clone();
+                tauri::async_runtime::spawn_blocking(move || {
+                    if let Err(e) = restart_default_gateway(&app) {
+                        log_error!("Failed to restart gateway from tray: {}", e);
+                    }
+                });
+            }
+            action @ ("pause" | "resume") if !kiosk::is_locked() => {
+                let app = app.clone();
+                let paused = action == "pause";
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = pause::set_paused(&app, paused).await {
+                        log_error!("Failed to change gateway pause from tray: {}", e);
+                    }
+                });
+            }
+            "dashboard" | "selftest" | "approvals" => show_main_window(app),
+            "copy_dashboard_url" => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = tokens::copy_dashboard_url_to_clipboard(&app, true).await {
+                        log_error!("Failed to copy dashboard URL from tray: {}", e);
+                    }
+                });
+            }
+            "logs" => {
+                if let Err(e) = show_log_window(app) {
+                    log_error!("{}", e);
+                }
+            }
+            "openclaw_folder" => {
+                if let Err(e) = external::reveal_in_file_manager(PathKind::OpenclawDir) {
+                    log_error!("Failed to open OpenClaw folder: {}", e);
+                }
+            }
+            "app_update" => app_update::update_from_tray(app),
+            "quit" => shutdown::request_quit(app),
+            id => {
+                if let Some(session) = id.strip_prefix("session:") {
+                    open_target(app, TrayTarget::Session(session.to_string()));
+                } else if let Some(agent) = id.strip_prefix("agent:") {
+                    open_target(app, TrayTarget::Agent(agent.to_string()));
+                }
+            }
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+
+    if let Some((icon, template)) = tray_icon(app, state) {
+        builder = builder.icon(icon).icon_as_template(template);
+    }
+
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Re-fetch recent sessions and agents from the gateway and rebuild the tray menu
+#[tauri::command]
+pub async fn refresh_tray(app: AppHandle) -> Result<(), DesktopError> {
+    let probe = tauri::async_runtime::spawn_blocking(probe::probe_gateway)
+        .await
+        .map_err(|e| format!("Failed to refresh tray: {}", e))?;
+    if probe.verified {
+        load_quick_links(&app).await;
+    } else {
+        clear_quick_links();
+    }
+    rebuild_tray_with(&app, &probe).map_err(|e| format!("Failed to refresh tray: {}", e))?;
+    Ok(())
+}
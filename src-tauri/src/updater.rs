@@ -0,0 +1,424 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Runtime};
+
+use crate::announce::announce;
+use crate::app_state;
+use crate::audit;
+use crate::availability::{self, ActionId, Operation};
+use crate::capabilities::parse_version;
+use crate::connectivity;
+use crate::disk_space::{self, SpaceCheck};
+use crate::error::DesktopError;
+use crate::events::{Politeness, UpdateProgress, OPENCLAW_UPDATE_PROGRESS_EVENT};
+use crate::i18n;
+use crate::installer;
+use crate::paths;
+use crate::permissions::{self, Permission};
+use crate::prerequisites;
+use crate::proxy;
+use crate::settings;
+
+const REGISTRY_URL: &str = "https://registry.npmjs.org/openclaw/latest";
+const PACKAGE_URL: &str = "https://registry.npmjs.org/openclaw";
+/// Abbreviated package metadata: versions and dist-tags without the readmes
+const ABBREVIATED_METADATA: &str = "application/vnd.npm.install-v1+json";
+const REGISTRY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How the `openclaw` CLI on this machine was installed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallSource {
+    Npm,
+    Pnpm,
+    Brew,
+    /// Prebuilt binary under `~/.openclaw/bin`, managed by the app's installer
+    Standalone,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateCheck {
+    pub installed: Option<String>,
+    pub latest: String,
+    pub update_available: bool,
+    pub source: InstallSource,
+    /// Version the user chose to stay on; update reminders are off while set
+    pub pinned: Option<String>,
+}
+
+/// CLI versions the app installed, so a bad release can be undone
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OpenclawVersions {
+    /// Installed before the most recent install, update or rollback
+    pub previous: Option<String>,
+    /// Installed on request through `install_openclaw_version` or `rollback_openclaw`
+    pub pinned: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AvailableVersion {
+    pub version: String,
+    pub latest: bool,
+    pub installed: bool,
+    pub previous: bool,
+    /// The registry's deprecation message
+    pub deprecated: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RegistryPackage {
+    version: String,
+}
+
+#[derive(Deserialize)]
+struct RegistryVersion {
+    #[serde(default)]
+    deprecated: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RegistryPackument {
+    #[serde(rename = "dist-tags", default)]
+    dist_tags: std::collections::HashMap<String, String>,
+    #[serde(default)]
+    versions: std::collections::HashMap<String, RegistryVersion>,
+}
+
+/// Latest `openclaw` version published to the npm registry
+pub async fn fetch_latest_version() -> Result<String, String> {
+    let client = proxy::apply(reqwest::Client::builder().timeout(REGISTRY_TIMEOUT))?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(REGISTRY_URL)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach npm registry: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("npm registry returned HTTP {}", response.status()));
+    }
+    response
+        .json::<RegistryPackage>()
+        .await
+        .map(|p| p.version)
+        .map_err(|e| format!("Failed to parse npm registry response: {}", e))
+}
+
+/// Resolve `openclaw` on PATH, following symlinks into the package manager's tree
+pub(crate) fn resolve_on_path() -> Option<PathBuf> {
+    let path = paths::find_executable("openclaw")?;
+    Some(path.canonicalize().unwrap_or(path))
+}
+
+pub fn detect_install_source() -> InstallSource {
+    if installer::installed_binary_path().is_ok_and(|p| p.exists()) {
+        return InstallSource::Standalone;
+    }
+    let Some(path) = resolve_on_path() else {
+        return InstallSource::Unknown;
+    };
+    let path = path.to_string_lossy().to_lowercase().replace('\\', "/");
+    if path.contains("/cellar/") || path.contains("homebrew") || path.contains("linuxbrew") {
+        InstallSource::Brew
+    } else if path.contains("pnpm") {
+        InstallSource::Pnpm
+    } else {
+        InstallSource::Npm
+    }
+}
+
+fn send_progress<R: Runtime>(app: &AppHandle<R>, payload: UpdateProgress) {
+    if let Err(e) = app.emit(OPENCLAW_UPDATE_PROGRESS_EVENT, payload) {
+        log_error!("Failed to emit update progress: {}", e);
+    }
+}
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, stage: &'static str, message: impl Into<String>) {
+    send_progress(
+        app,
+        UpdateProgress {
+            stage,
+            message: message.into(),
+            space: None,
+        },
+    );
+}
+
+/// Check room for the update where the given tool writes it
+fn update_preflight(source: InstallSource) -> Result<Option<SpaceCheck>, String> {
+    match source {
+        InstallSource::Standalone => {
+            let binary = installer::installed_binary_path()?;
+            let dir = binary.parent().unwrap_or(&binary);
+            disk_space::preflight(dir, disk_space::BINARY_INSTALL_BYTES, 2).map(Some)
+        }
+        InstallSource::Unknown => Ok(None),
+        _ => installer::package_install_preflight().map(Some),
+    }
+}
+
+/// The package manager's command for `version`, or the latest release with `None`.
+/// Homebrew and the prebuilt binary only offer the latest.
+fn updater_command(source: InstallSource, version: Option<&str>) -> Option<Command> {
+    let spec = format!("openclaw@{}", version.unwrap_or("latest"));
+    let (program, args): (&str, Vec<&str>) = match (source, version) {
+        (InstallSource::Npm, _) => ("npm", vec!["install", "-g", &spec]),
+        (InstallSource::Pnpm, _) => ("pnpm", vec!["add", "-g", &spec]),
+        (InstallSource::Brew, None) => ("brew", vec!["upgrade", "openclaw"]),
+        _ => return None,
+    };
+
+    let mut command = prerequisites::tool_command(program);
+    command.args(args);
+    proxy::apply_env(&mut command);
+    Some(command)
+}
+
+/// Run the package manager's updater, forwarding each output line as a progress event
+fn run_streamed<R: Runtime>(app: &AppHandle<R>, mut command: Command) -> Result<(), String> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start updater: {}", e))?;
+
+    let progress_app = app.clone();
+    let stderr_lines =
+        installer::pipe_output(child.stdout.take(), child.stderr.take(), move |_, line| {
+            emit_progress(&progress_app, "output", line)
+        });
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for updater: {}", e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(stderr_lines
+            .last()
+            .cloned()
+            .unwrap_or_else(|| format!("Updater exited with {}", status)))
+    }
+}
+
+/// Compare the installed CLI with the latest release on npm
+#[tauri::command]
+pub async fn check_openclaw_update() -> Result<UpdateCheck, DesktopError> {
+    connectivity::ensure_registry().await?;
+    let (installed, source) = tauri::async_runtime::spawn_blocking(|| {
+        (crate::detect_openclaw_version(), detect_install_source())
+    })
+    .await
+    .map_err(|e| format!("Failed to inspect installed CLI: {}", e))?;
+    let latest = fetch_latest_version().await?;
+
+    let update_available = match installed.as_deref().and_then(parse_version) {
+        Some(current) => parse_version(&latest).is_some_and(|available| available > current),
+        None => true,
+    };
+
+    Ok(UpdateCheck {
+        installed,
+        latest,
+        update_available,
+        source,
+        pinned: settings::current().openclaw_versions.pinned,
+    })
+}
+
+/// `1.4.2` from whatever `openclaw --version` prints
+fn plain_version(text: &str) -> Option<String> {
+    parse_version(text).map(|(major, minor, patch)| format!("{}.{}.{}", major, minor, patch))
+}
+
+fn check_version_spec(version: &str) -> Result<(), DesktopError> {
+    let valid = version.starts_with(|c: char| c.is_ascii_digit())
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'));
+    if valid {
+        Ok(())
+    } else {
+        Err(DesktopError::InvalidInput(format!(
+            "Invalid version: {}",
+            version
+        )))
+    }
+}
+
+/// Install `version`, or the latest release with `None`, with whichever tool
+/// installed the CLI, streaming output as `openclaw-update-progress` events.
+/// The version it replaces is recorded so it can be rolled back to.
+async fn install_version(
+    app: &AppHandle,
+    version: Option<String>,
+) -> Result<UpdateCheck, DesktopError> {
+    availability::ensure(ActionId::UpdateOpenclaw)?;
+    permissions::ensure_granted(app, Permission::InstallSoftware).await?;
+    let _operation = availability::begin(Operation::Upgrade)?;
+    let (before, source) = tauri::async_runtime::spawn_blocking(|| {
+        (crate::detect_openclaw_version(), detect_install_source())
+    })
+    .await
+    .map_err(|e| format!("Failed to detect install source: {}", e))?;
+    if version.is_some() && !matches!(source, InstallSource::Npm | InstallSource::Pnpm) {
+        return Err(DesktopError::Unavailable(format!(
+            "Choosing a version needs an npm or pnpm install; this one is {:?}",
+            source
+        )));
+    }
+    let space = match update_preflight(source) {
+        Ok(space) => space,
+        Err(e) => {
+            emit_progress(app, "failed", e.clone());
+            return Err(e.into());
+        }
+    };
+    send_progress(
+        app,
+        UpdateProgress {
+            stage: "started",
+            message: match &version {
+                Some(version) => format!("Installing {} via {:?}", version, source),
+                None => format!("Updating via {:?}", source).to_lowercase(),
+            },
+            space,
+        },
+    );
+
+    let result = match source {
+        InstallSource::Standalone => installer::download_binary().await.map(|_| ()),
+        InstallSource::Unknown => Err(DesktopError::NotInstalled("OpenClaw".to_string())),
+        _ => {
+            let command = updater_command(source, version.as_deref())
+                .ok_or("No updater for install source")?;
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || run_streamed(&app, command))
+                .await
+                .map_err(|e| format!("Failed to run updater: {}", e))?
+                .map_err(DesktopError::from)
+        }
+    };
+
+    let target = version.clone().unwrap_or_else(|| "latest".to_string());
+    audit::record_result("cli_update", &target, &result);
+    if let Err(e) = result {
+        emit_progress(app, "failed", e.to_string());
+        return Err(e);
+    }
+
+    app_state::refresh_install(app).await;
+    let check = check_openclaw_update().await?;
+    let before = before.as_deref().and_then(plain_version);
+    let after = check.installed.as_deref().and_then(plain_version);
+    settings::update(|s| {
+        if before.is_some() && before != after {
+            s.openclaw_versions.previous = before;
+        }
+        s.openclaw_versions.pinned = version;
+    })?;
+    let check = UpdateCheck {
+        pinned: settings::current().openclaw_versions.pinned,
+        ..check
+    };
+    let message = format!(
+        "OpenClaw is now {}",
+        check.installed.as_deref().unwrap_or("installed")
+    );
+    announce(
+        app,
+        Politeness::Polite,
+        &i18n::t_with(
+            "cli_update.installed",
+            &[(
+                "version",
+                &check.installed.as_deref().unwrap_or("installed"),
+            )],
+        ),
+    );
+    emit_progress(app, "finished", message);
+    Ok(check)
+}
+
+/// Update the CLI to the latest release, dropping any pinned version
+#[tauri::command]
+pub async fn update_openclaw(app: AppHandle) -> Result<UpdateCheck, DesktopError> {
+    install_version(&app, None).await
+}
+
+/// Every published CLI version, newest first
+#[tauri::command]
+pub async fn list_available_openclaw_versions() -> Result<Vec<AvailableVersion>, DesktopError> {
+    connectivity::ensure_registry().await?;
+    let client = proxy::apply(reqwest::Client::builder().timeout(REGISTRY_TIMEOUT))?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let response = client
+        .get(PACKAGE_URL)
+        .header(reqwest::header::ACCEPT, ABBREVIATED_METADATA)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach npm registry: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("npm registry returned HTTP {}", response.status()).into());
+    }
+    let package = response
+        .json::<RegistryPackument>()
+        .await
+        .map_err(|e| format!("Failed to parse npm registry response: {}", e))?;
+
+    let installed = tauri::async_runtime::spawn_blocking(crate::detect_openclaw_version)
+        .await
+        .ok()
+        .flatten()
+        .as_deref()
+        .and_then(plain_version);
+    let history = settings::current().openclaw_versions;
+    let latest = package.dist_tags.get("latest");
+    let mut versions: Vec<AvailableVersion> = package
+        .versions
+        .into_iter()
+        .map(|(version, meta)| AvailableVersion {
+            latest: latest == Some(&version),
+            installed: installed.as_ref() == Some(&version),
+            previous: history.previous.as_ref() == Some(&version),
+            deprecated: meta.deprecated,
+            version,
+        })
+        .collect();
+    versions.sort_by(|a, b| {
+        parse_version(&b.version)
+            .cmp(&parse_version(&a.version))
+            .then_with(|| b.version.cmp(&a.version))
+    });
+    Ok(versions)
+}
+
+/// Install a specific CLI version and stay on it; update reminders stop until
+/// `update_openclaw` moves back to the latest release
+#[tauri::command]
+pub async fn install_openclaw_version(
+    app: AppHandle,
+    version: String,
+) -> Result<UpdateCheck, DesktopError> {
+    let version = version.trim().trim_start_matches('v').to_string();
+    check_version_spec(&version)?;
+    install_version(&app, Some(version)).await
+}
+
+/// Go back to the version installed before the last install or update
+#[tauri::command]
+pub async fn rollback_openclaw(app: AppHandle) -> Result<UpdateCheck, DesktopError> {
+    let previous = settings::current()
+        .openclaw_versions
+        .previous
+        .ok_or_else(|| {
+            DesktopError::NotFound("No previous OpenClaw version recorded".to_string())
+        })?;
+    install_version(&app, Some(previous)).await
+}
@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::auto_lock;
+use crate::availability::{self, ActionId, Operation};
+use crate::disk_space::SpaceCheck;
+use crate::error::DesktopError;
+use crate::events::GATEWAY_STATUS_CHANGED_EVENT;
+use crate::gateways::kill_process_tree;
+use crate::installer;
+use crate::secrets;
+use crate::{
+    active_gateway_port, capabilities, gateway_log_paths, gateway_process, gateway_status,
+    is_port_open, npm_install_global, port_owner, run_openclaw_gateway_control, settings,
+    snapshots, spawn_gateway_process, GATEWAY_PORT,
+};
+
+const STANDBY_READY_TIMEOUT: Duration = Duration::from_secs(60);
+const STANDBY_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpgradeResult {
+    pub previous_port: u16,
+    pub port: u16,
+    pub previous_version: Option<String>,
+    pub version: Option<String>,
+    /// Dashboard windows moved over to the new instance
+    pub windows_switched: Vec<String>,
+    /// Free space measured before installing the new version
+    pub space: SpaceCheck,
+}
+
+async fn wait_for_port(port: u16) -> bool {
+    let deadline = Instant::now() + STANDBY_READY_TIMEOUT;
+    while Instant::now() < deadline {
+        if tauri::async_runtime::spawn_blocking(move || is_port_open(port))
+            .await
+            .unwrap_or(false)
+        {
+            return true;
+        }
+        tokio::time::sleep(STANDBY_POLL_INTERVAL).await;
+    }
+    false
+}
+
+/// Point every window showing the old dashboard at the new one
+async fn switch_dashboard_windows<R: Runtime>(app: &AppHandle<R>, old_port: u16) -> Vec<String> {
+    // A locked dashboard stays on the lock screen
+    if auto_lock::is_locked() {
+        return Vec::new();
+    }
+    let Some(target) = secrets::dashboard_login_url()
+        .await
+        .ok()
+        .and_then(|url| url.parse::<tauri::Url>().ok())
+    else {
+        return Vec::new();
+    };
+    let mut switched = Vec::new();
+    for (label, window) in app.webview_windows() {
+        let on_old_dashboard = window.url().is_ok_and(|url| {
+            matches!(url.host_str(), Some("127.0.0.1" | "localhost"))
+                && url.port() == Some(old_port)
+        });
+        if !on_old_dashboard {
+            continue;
+        }
+        match window.navigate(target.clone()) {
+            Ok(()) => switched.push(label),
+            Err(e) => log_error!("Failed to switch window {} to new gateway: {}", label, e),
+        }
+    }
+    switched
+}
+
+/// Upgrade the CLI and swap in a new gateway without taking the dashboard down.
+///
+/// The new version is started on a secondary port while the old gateway keeps
+/// serving. Only once it answers does the app switch its active port and
+/// dashboard windows over and stop the old instance; if the standby never comes
+/// up it is killed and the old gateway stays active.
+#[tauri::command]
+pub async fn upgrade_with_failover(
+    app: AppHandle,
+    version: Option<String>,
+) -> Result<UpgradeResult, DesktopError> {
+    availability::ensure(ActionId::UpgradeWithFailover)?;
+    let _operation = availability::begin(Operation::Upgrade)?;
+    let space = installer::package_install_preflight()?;
+    tauri::async_runtime::spawn_blocking({
+        let app = app.clone();
+        move || snapshots::capture(&app, "upgrade")
+    })
+    .await
+    .map_err(|e| format!("Failed to snapshot gateway state: {}", e))?;
+
+    let previous_port = active_gateway_port();
+    let previous_version = crate::detect_openclaw_version();
+
+    let spec = format!("openclaw@{}", version.as_deref().unwrap_or("latest"));
+    tauri::async_runtime::spawn_blocking(move || npm_install_global(&spec))
+        .await
+        .map_err(|e| format!("Failed to run upgrade: {}", e))??;
+
+    if !crate::is_gateway_running() {
+        return Err(DesktopError::GatewayNotRunning(
+            "OpenClaw was upgraded, but no gateway is running to fail over from".to_string(),
+        ));
+    }
+
+    let port =
+        tauri::async_runtime::spawn_blocking(move || port_owner::find_free_port(previous_port))
+            .await
+            .map_err(|e| format!("Failed to find standby port: {}", e))?
+            .ok_or("No free port available for the standby gateway")?;
+
+    let (log_path, error_log_path) = gateway_log_paths()?;
+    let mut standby = spawn_gateway_process(port, &log_path, &error_log_path, false)?;
+
+    if !wait_for_port(port).await {
+        let _ = kill_process_tree(&mut standby);
+        return Err(format!(
+            "Standby gateway did not become healthy on port {}; kept the gateway on port {}",
+            port, previous_port
+        )
+        .into());
+    }
+
+    // Swap the tracked child before changing the port, so the exit watcher of the
+    // old instance does not treat its shutdown as a crash
+    let old_child = gateway_process::detach(&app);
+    gateway_process::track(&app, standby);
+    settings::update(|s| s.gateway_port = (port != GATEWAY_PORT).then_some(port))?;
+
+    let windows_switched = switch_dashboard_windows(&app, previous_port).await;
+
+    match old_child {
+        Some(mut child) => {
+            if let Err(e) = kill_process_tree(&mut child) {
+                log_error!("Failed to stop previous gateway: {}", e);
+            }
+        }
+        None => {
+            // Adopted daemon: ask it to stop on its own
+            if let Err(e) = run_openclaw_gateway_control("stop") {
+                log_error!("Failed to stop previous gateway daemon: {}", e);
+            }
+        }
+    }
+
+    let _ = capabilities::refresh(&app).await;
+    if let Err(e) = app.emit(GATEWAY_STATUS_CHANGED_EVENT, gateway_status()) {
+        log_error!("Failed to emit gateway status: {}", e);
+    }
+
+    Ok(UpgradeResult {
+        previous_port,
+        port,
+        previous_version,
+        version: crate::detect_openclaw_version(),
+        windows_switched,
+        space,
+    })
+}
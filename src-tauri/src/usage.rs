@@ -0,0 +1,486 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::time::MissedTickBehavior;
+
+use crate::error::DesktopError;
+use crate::gateway_api::{GatewayApi, GatewayApiErrorKind};
+use crate::logs::parse_line;
+use crate::{gateway_log_paths, is_gateway_running, openclaw_home_dir};
+
+const DB_FILE: &str = "usage.db";
+const COLLECT_INTERVAL: Duration = Duration::from_secs(60);
+/// Log component the gateway tags usage lines with, e.g.
+/// `[usage] agent=main model=claude-sonnet-4 input=1200 output=340 cost=0.0087`
+const USAGE_COMPONENT: &str = "usage";
+
+/// Published list prices in USD per million tokens, (input, output), matched by
+/// model name prefix. Only used for records the gateway didn't price itself.
+const PRICES: &[(&str, f64, f64)] = &[
+    ("claude-opus-4", 15.0, 75.0),
+    ("claude-sonnet-4", 3.0, 15.0),
+    ("claude-3-7-sonnet", 3.0, 15.0),
+    ("claude-3-5-sonnet", 3.0, 15.0),
+    ("claude-3-5-haiku", 0.8, 4.0),
+    ("claude-haiku-4", 1.0, 5.0),
+    ("gpt-4.1-mini", 0.4, 1.6),
+    ("gpt-4.1-nano", 0.1, 0.4),
+    ("gpt-4.1", 2.0, 8.0),
+    ("gpt-4o-mini", 0.15, 0.6),
+    ("gpt-4o", 2.5, 10.0),
+    ("o3-mini", 1.1, 4.4),
+    ("o4-mini", 1.1, 4.4),
+    ("o3", 2.0, 8.0),
+    ("gemini-2.5-pro", 1.25, 10.0),
+    ("gemini-2.5-flash", 0.3, 2.5),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsagePeriod {
+    Day,
+    Week,
+    Month,
+    All,
+}
+
+impl UsagePeriod {
+    fn secs(self) -> Option<u64> {
+        match self {
+            UsagePeriod::Day => Some(24 * 60 * 60),
+            UsagePeriod::Week => Some(7 * 24 * 60 * 60),
+            UsagePeriod::Month => Some(30 * 24 * 60 * 60),
+            UsagePeriod::All => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsageGroupBy {
+    Agent,
+    Model,
+    /// Local calendar day
+    Day,
+}
+
+impl UsageGroupBy {
+    fn column(self) -> &'static str {
+        match self {
+            UsageGroupBy::Agent => "COALESCE(agent, '')",
+            UsageGroupBy::Model => "COALESCE(model, '')",
+            UsageGroupBy::Day => "date(timestamp, 'unixepoch', 'localtime')",
+        }
+    }
+}
+
+/// One model call as reported by `/api/usage` or a usage log line
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UsageRecord {
+    #[serde(default)]
+    id: Option<String>,
+    /// Unix seconds
+    #[serde(alias = "ts")]
+    timestamp: u64,
+    #[serde(default)]
+    agent: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default, alias = "input_tokens")]
+    input_tokens: u64,
+    #[serde(default, alias = "output_tokens")]
+    output_tokens: u64,
+    #[serde(default, alias = "cost_usd", alias = "cost")]
+    cost_usd: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UsageResponse {
+    List(Vec<UsageRecord>),
+    Wrapped { usage: Vec<UsageRecord> },
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UsageTotals {
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cost_usd: f64,
+    /// Part of the cost comes from the built-in price list rather than the gateway
+    pub cost_estimated: bool,
+    /// Tokens from models without a known price, left out of the cost
+    pub unpriced_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageGroup {
+    /// Agent id, model name, or `YYYY-MM-DD`; empty when the record didn't say
+    pub key: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageSummary {
+    pub period: UsagePeriod,
+    pub group_by: UsageGroupBy,
+    pub total: UsageTotals,
+    pub groups: Vec<UsageGroup>,
+}
+
+/// Where the collector left off
+#[derive(Default)]
+struct Cursor {
+    /// Unix seconds of the newest record fetched from the API
+    api_since: Option<u64>,
+    log_offset: u64,
+}
+
+static DB: Mutex<Option<Connection>> = Mutex::new(None);
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn with_db<T>(f: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Result<T, String> {
+    let mut guard = DB
+        .lock()
+        .map_err(|_| "Usage store lock poisoned".to_string())?;
+    if guard.is_none() {
+        let conn = Connection::open(openclaw_home_dir()?.join(DB_FILE))
+            .map_err(|e| format!("Failed to open usage store: {}", e))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS usage (
+                id TEXT PRIMARY KEY,
+                timestamp INTEGER NOT NULL,
+                agent TEXT,
+                model TEXT,
+                input_tokens INTEGER NOT NULL,
+                output_tokens INTEGER NOT NULL,
+                cost_usd REAL
+            );
+            CREATE INDEX IF NOT EXISTS usage_timestamp ON usage (timestamp)",
+        )
+        .map_err(|e| format!("Failed to create usage store: {}", e))?;
+        *guard = Some(conn);
+    }
+    let conn = guard.as_ref().ok_or("Usage store unavailable")?;
+    f(conn).map_err(|e| format!("Usage store error: {}", e))
+}
+
+/// Insert records, skipping ones already stored; log lines are re-read after restarts
+fn store(records: &[(String, UsageRecord)]) -> Result<(), String> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    with_db(|conn| {
+        for (id, record) in records {
+            conn.execute(
+                "INSERT OR IGNORE INTO usage
+                    (id, timestamp, agent, model, input_tokens, output_tokens, cost_usd)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id,
+                    record.timestamp as i64,
+                    record.agent,
+                    record.model,
+                    record.input_tokens as i64,
+                    record.output_tokens as i64,
+                    record.cost_usd
+                ],
+            )?;
+        }
+        Ok(())
+    })
+}
+
+fn latest_timestamp() -> Option<u64> {
+    with_db(|conn| {
+        conn.query_row("SELECT MAX(timestamp) FROM usage", [], |row| {
+            row.get::<_, Option<i64>>(0)
+        })
+    })
+    .ok()
+    .flatten()
+    .map(|ts| ts as u64)
+}
+
+fn line_id(line: &str) -> String {
+    format!("log:{:x}", Sha256::digest(line.as_bytes()))
+}
+
+/// `key=value` pairs of a usage line; the timestamp comes from the line prefix
+fn parse_usage_line(raw: &str) -> Option<UsageRecord> {
+    let entry = parse_line(0, raw);
+    if !entry
+        .component
+        .as_deref()
+        .is_some_and(|c| c.eq_ignore_ascii_case(USAGE_COMPONENT))
+    {
+        return None;
+    }
+    let timestamp = entry
+        .timestamp
+        .as_deref()
+        .and_then(|ts| chrono::DateTime::parse_from_rfc3339(ts).ok())
+        .map(|ts| ts.timestamp().max(0) as u64)
+        .unwrap_or_else(now_secs);
+    let mut record = UsageRecord {
+        id: None,
+        timestamp,
+        agent: None,
+        model: None,
+        input_tokens: 0,
+        output_tokens: 0,
+        cost_usd: None,
+    };
+    for pair in entry.message.split_whitespace() {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        match key {
+            "agent" => record.agent = Some(value.to_string()),
+            "model" => record.model = Some(value.to_string()),
+            "input" | "input_tokens" => record.input_tokens = value.parse().ok()?,
+            "output" | "output_tokens" => record.output_tokens = value.parse().ok()?,
+            "cost" | "cost_usd" => record.cost_usd = value.parse().ok(),
+            _ => {}
+        }
+    }
+    Some(record)
+}
+
+/// Usage lines appended to the gateway log since `offset`
+fn read_log(offset: &mut u64) -> Vec<(String, UsageRecord)> {
+    let Some(mut file) = gateway_log_paths()
+        .ok()
+        .and_then(|(log_path, _)| File::open(log_path).ok())
+    else {
+        return Vec::new();
+    };
+    let len = file.metadata().map(|m| m.len()).unwrap_or_default();
+    if len < *offset {
+        *offset = 0;
+    }
+    if file.seek(SeekFrom::Start(*offset)).is_err() {
+        return Vec::new();
+    }
+    let mut records = Vec::new();
+    let mut reader = BufReader::new(file);
+    let mut line = String::new();
+    while let Ok(n) = reader.read_line(&mut line) {
+        if n == 0 || !line.ends_with('\n') {
+            break;
+        }
+        *offset += n as u64;
+        if let Some(record) = parse_usage_line(&line) {
+            records.push((line_id(&line), record));
+        }
+        line.clear();
+    }
+    records
+}
+
+/// Records from the gateway's usage API, or `None` when it doesn't have one
+async fn fetch_api<R: Runtime>(
+    app: &AppHandle<R>,
+    cursor: &mut Cursor,
+) -> Option<Vec<(String, UsageRecord)>> {
+    let since = cursor.api_since.unwrap_or(0);
+    let path = format!("/api/usage?since={}", since);
+    match app.state::<GatewayApi>().get::<UsageResponse>(&path).await {
+        Ok(UsageResponse::List(records)) | Ok(UsageResponse::Wrapped { usage: records }) => {
+            if let Some(latest) = records.iter().map(|r| r.timestamp).max() {
+                cursor.api_since = Some(since.max(latest));
+            }
+            Some(
+                records
+                    .into_iter()
+                    .map(|record| {
+                        let id = match &record.id {
+                            Some(id) => format!("api:{}", id),
+                            None => format!(
+                                "api:{}:{}:{}:{}",
+                                record.timestamp,
+                                record.model.as_deref().unwrap_or_default(),
+                                record.input_tokens,
+                                record.output_tokens
+                            ),
+                        };
+                        (id, record)
+                    })
+                    .collect(),
+            )
+        }
+        Err(e) if e.kind == GatewayApiErrorKind::NotFound => None,
+        Err(e) => {
+            log_error!("Failed to fetch usage: {}", e);
+            Some(Vec::new())
+        }
+    }
+}
+
+/// Pull usage into the store once a minute, from the API when the gateway has
+/// one and from its log otherwise
+pub fn spawn_usage_collector<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut cursor = Cursor {
+            api_since: tauri::async_runtime::spawn_blocking(latest_timestamp)
+                .await
+                .ok()
+                .flatten(),
+            log_offset: 0,
+        };
+        let mut interval = tokio::time::interval(COLLECT_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let running = tauri::async_runtime::spawn_blocking(is_gateway_running)
+                .await
+                .unwrap_or(false);
+            let from_api = if running {
+                fetch_api(&app, &mut cursor).await
+            } else {
+                None
+            };
+            let records = match from_api {
+                Some(records) => records,
+                None => {
+                    let mut offset = cursor.log_offset;
+                    let records = tauri::async_runtime::spawn_blocking(move || {
+                        let records = read_log(&mut offset);
+                        (records, offset)
+                    })
+                    .await;
+                    match records {
+                        Ok((records, offset)) => {
+                            cursor.log_offset = offset;
+                            records
+                        }
+                        Err(_) => continue,
+                    }
+                }
+            };
+            let result = tauri::async_runtime::spawn_blocking(move || store(&records)).await;
+            if let Ok(Err(e)) = result {
+                log_error!("Failed to store usage: {}", e);
+            }
+        }
+    });
+}
+
+fn price(model: &str) -> Option<(f64, f64)> {
+    let model = model.to_lowercase();
+    // Strip a `provider/` prefix such as `anthropic/claude-sonnet-4`
+    let model = model.rsplit('/').next().unwrap_or(&model);
+    PRICES
+        .iter()
+        .find(|(prefix, ..)| model.starts_with(prefix))
+        .map(|&(_, input, output)| (input, output))
+}
+
+impl UsageTotals {
+    fn add(&mut self, other: &UsageTotals) {
+        self.requests += other.requests;
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cost_usd += other.cost_usd;
+        self.cost_estimated |= other.cost_estimated;
+        self.unpriced_tokens += other.unpriced_tokens;
+    }
+}
+
+fn summarize(period: UsagePeriod, group_by: UsageGroupBy) -> Result<UsageSummary, String> {
+    let since = period
+        .secs()
+        .map_or(0, |secs| now_secs().saturating_sub(secs)) as i64;
+    let rows = with_db(|conn| {
+        // Split by model and whether the gateway priced the call, so unpriced rows
+        // can be estimated per model
+        let mut statement = conn.prepare(&format!(
+            "SELECT {} AS key, COALESCE(model, ''), cost_usd IS NOT NULL,
+                    COUNT(*), SUM(input_tokens), SUM(output_tokens), SUM(cost_usd)
+             FROM usage WHERE timestamp >= ?1
+             GROUP BY key, model, cost_usd IS NOT NULL",
+            group_by.column()
+        ))?;
+        let rows = statement.query_map(params![since], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, i64>(3)? as u64,
+                row.get::<_, i64>(4)? as u64,
+                row.get::<_, i64>(5)? as u64,
+                row.get::<_, Option<f64>>(6)?,
+            ))
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+
+    let mut groups: BTreeMap<String, UsageTotals> = BTreeMap::new();
+    for (key, model, priced, requests, input_tokens, output_tokens, cost) in rows {
+        let mut totals = UsageTotals {
+            requests,
+            input_tokens,
+            output_tokens,
+            ..Default::default()
+        };
+        if priced {
+            totals.cost_usd = cost.unwrap_or_default();
+        } else if let Some((input_price, output_price)) = price(&model) {
+            totals.cost_usd = (input_tokens as f64 * input_price
+                + output_tokens as f64 * output_price)
+                / 1_000_000.0;
+            totals.cost_estimated = true;
+        } else {
+            totals.unpriced_tokens = input_tokens + output_tokens;
+        }
+        groups.entry(key).or_default().add(&totals);
+    }
+
+    let mut total = UsageTotals::default();
+    for totals in groups.values() {
+        total.add(totals);
+    }
+    let mut groups: Vec<UsageGroup> = groups
+        .into_iter()
+        .map(|(key, totals)| UsageGroup { key, totals })
+        .collect();
+    // Days read best in order; agents and models by what they used
+    if group_by != UsageGroupBy::Day {
+        groups.sort_by(|a, b| {
+            let tokens = |g: &UsageGroup| g.totals.input_tokens + g.totals.output_tokens;
+            tokens(b).cmp(&tokens(a))
+        });
+    }
+    Ok(UsageSummary {
+        period,
+        group_by,
+        total,
+        groups,
+    })
+}
+
+/// Token counts and spend for the period, grouped by agent, model, or day
+#[tauri::command]
+pub async fn get_usage_summary(
+    period: UsagePeriod,
+    group_by: UsageGroupBy,
+) -> Result<UsageSummary, DesktopError> {
+    let summary = tauri::async_runtime::spawn_blocking(move || summarize(period, group_by))
+        .await
+        .map_err(|e| format!("Failed to read usage: {}", e))??;
+    Ok(summary)
+}
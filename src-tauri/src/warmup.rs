@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use ts_rs::TS;
+
+use crate::events::GATEWAY_STATUS_CHANGED_EVENT;
+use crate::gateway_api::GatewayApi;
+use crate::{connection, dashboard_base_url, gateway_status, read_gateway_token, settings};
+
+const STEP_TIMEOUT: Duration = Duration::from_secs(20);
+const WEBSOCKET_PATH: &str = "/ws";
+/// Any base64 nonce will do; the connection is dropped right after the handshake
+const WEBSOCKET_KEY: &str = "dGhlIHNhbXBsZSBub25jZQ==";
+
+static REPORT: Mutex<Option<WarmupReport>> = Mutex::new(None);
+/// Bumped whenever the gateway goes down, so a slow run can't report on a dead gateway
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub enum WarmupAction {
+    /// Fetch the model list so provider catalogs are loaded
+    ListModels,
+    /// Complete a WebSocket handshake with the gateway
+    OpenWebsocket,
+    /// Load the dashboard page so its assets are built and cached
+    PrimeDashboard,
+    /// Send a trivial chat message; this reaches the model provider and costs tokens
+    AgentPing,
+}
+
+/// Requests sent once the gateway answers, before the user gets to it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WarmupSettings {
+    pub enabled: bool,
+    /// Run in order; a failing action doesn't stop the rest
+    pub actions: Vec<WarmupAction>,
+    /// Agent the ping goes to; the gateway's default agent when unset
+    pub ping_agent: Option<String>,
+}
+
+impl Default for WarmupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            actions: vec![
+                WarmupAction::ListModels,
+                WarmupAction::OpenWebsocket,
+                WarmupAction::PrimeDashboard,
+            ],
+            ping_agent: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct WarmupStep {
+    pub action: WarmupAction,
+    pub ok: bool,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "../../src/bindings/events.ts")]
+pub struct WarmupReport {
+    /// Unix seconds when the run started
+    pub started_at: u64,
+    pub total_ms: u64,
+    pub steps: Vec<WarmupStep>,
+}
+
+pub(crate) fn last_report() -> Option<WarmupReport> {
+    REPORT.lock().ok().and_then(|report| report.clone())
+}
+
+/// Forget the last run once the gateway is gone
+pub(crate) fn clear() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut report) = REPORT.lock() {
+        *report = None;
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    connection::apply_tls(reqwest::Client::builder().timeout(STEP_TIMEOUT))?
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+async fn open_websocket() -> Result<(), String> {
+    let mut request = client()?
+        .get(format!("{}{}", connection::base_url(), WEBSOCKET_PATH))
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header("Sec-WebSocket-Key", WEBSOCKET_KEY);
+    if let Some(token) = read_gateway_token() {
+        request = request.bearer_auth(token);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach gateway: {}", e))?;
+    match response.status() {
+        reqwest::StatusCode::SWITCHING_PROTOCOLS => Ok(()),
+        status => Err(format!(
+            "Gateway refused the WebSocket upgrade ({})",
+            status
+        )),
+    }
+}
+
+async fn prime_dashboard() -> Result<(), String> {
+    let response = client()?
+        .get(dashboard_base_url())
+        .send()
+        .await
+        .map_err(|e| format!("Failed to load dashboard: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Dashboard answered {}", response.status()));
+    }
+    response
+        .bytes()
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to load dashboard: {}", e))
+}
+
+async fn run_action<R: Runtime>(
+    app: &AppHandle<R>,
+    action: WarmupAction,
+    ping_agent: Option<&str>,
+) -> Result<(), String> {
+    let api = app.state::<GatewayApi>();
+    match action {
+        WarmupAction::ListModels => api
+            .get::<Value>("/api/models")
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        WarmupAction::OpenWebsocket => open_websocket().await,
+        WarmupAction::PrimeDashboard => prime_dashboard().await,
+        WarmupAction::AgentPing => api
+            .post::<Value>(
+                "/api/chat",
+                &json!({ "message": "ping", "agent": ping_agent, "stream": false }),
+            )
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+    }
+}
+
+/// Run the configured warm-up actions against a gateway that just became ready,
+/// then re-emit `gateway-status-changed` with the timings
+pub(crate) async fn run<R: Runtime>(app: &AppHandle<R>) {
+    let config = settings::current().warmup;
+    if !config.enabled || config.actions.is_empty() {
+        return;
+    }
+    let generation = GENERATION.load(Ordering::SeqCst);
+    let started_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let started = Instant::now();
+
+    let mut steps = Vec::with_capacity(config.actions.len());
+    for action in config.actions {
+        let step_started = Instant::now();
+        let result = tokio::time::timeout(
+            STEP_TIMEOUT,
+            run_action(app, action, config.ping_agent.as_deref()),
+        )
+        .await
+        .unwrap_or_else(|_| Err("Timed out".to_string()));
+        if let Err(e) = &result {
+            log_error!("Gateway warm-up {:?} failed: {}", action, e);
+        }
+        steps.push(WarmupStep {
+            action,
+            ok: result.is_ok(),
+            duration_ms: millis(step_started.elapsed()),
+            error: result.err(),
+        });
+    }
+
+    let report = WarmupReport {
+        started_at,
+        total_ms: millis(started.elapsed()),
+        steps,
+    };
+    match REPORT.lock() {
+        Ok(mut slot) if GENERATION.load(Ordering::SeqCst) == generation => *slot = Some(report),
+        _ => return,
+    }
+    if let Err(e) = app.emit(GATEWAY_STATUS_CHANGED_EVENT, gateway_status()) {
+        log_error!("Failed to emit gateway status event: {}", e);
+    }
+}
@@ -0,0 +1,350 @@
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use tauri::{
+    AppHandle, Manager, Monitor, Runtime, Url, WebviewUrl, WebviewWindow, WebviewWindowBuilder,
+};
+
+use crate::error::DesktopError;
+use crate::{attachments, auto_lock, autostart, kiosk, safe_mode, settings, theme, window_state};
+
+pub const MAIN_WINDOW: &str = "main";
+pub const LOG_WINDOW: &str = "logs";
+/// Followed by the session id, one window per session
+const CHAT_WINDOW_PREFIX: &str = "chat-";
+const DETACHED_DEFAULT_SIZE: (f64, f64) = (900.0, 640.0);
+const ZOOM_RANGE: std::ops::RangeInclusive<f64> = 0.25..=5.0;
+
+/// The app's own page, captured before the window first navigates to a dashboard
+static APP_URL: OnceLock<Url> = OnceLock::new();
+
+pub(crate) fn app_url() -> Option<Url> {
+    APP_URL.get().cloned()
+}
+
+/// How the app presents itself at launch
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StartupMode {
+    /// Show the main window
+    #[default]
+    Normal,
+    /// Load the main window hidden so the tray can show it instantly
+    Minimized,
+    /// Run from the tray only; the main window is created on first use
+    TrayOnly,
+}
+
+/// The mode for this launch; `--minimized` (login items) never shows the window,
+/// and safe mode is always tray-only
+pub fn startup_mode() -> StartupMode {
+    if safe_mode::is_active() {
+        return StartupMode::TrayOnly;
+    }
+    match settings::current().startup_mode {
+        StartupMode::Normal if autostart::launched_minimized() => StartupMode::Minimized,
+        mode => mode,
+    }
+}
+
+/// The main window, creating it from the app config if it does not exist yet
+pub(crate) fn main_window<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<WebviewWindow<R>> {
+    if let Some(window) = app.get_webview_window(MAIN_WINDOW) {
+        return Ok(window);
+    }
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == MAIN_WINDOW)
+        .cloned()
+        .unwrap_or_default();
+    let window = WebviewWindowBuilder::from_config(app, &config)?.build()?;
+    if let Ok(url) = window.url() {
+        let _ = APP_URL.set(url);
+    }
+    window_state::restore(app, &window);
+    apply_zoom(&window);
+    theme::apply_to(&window);
+
+    // Closing hides to the tray instead of quitting
+    let handle = window.clone();
+    window.on_window_event(move |event| match event {
+        tauri::WindowEvent::CloseRequested { api, .. } => {
+            api.prevent_close();
+            window_state::flush(&handle);
+            let _ = handle.hide();
+        }
+        tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+            window_state::on_changed(&handle)
+        }
+        tauri::WindowEvent::Focused(focused) => auto_lock::on_focus_changed(*focused),
+        tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+            attachments::on_drop(handle.app_handle(), paths.clone())
+        }
+        _ => {}
+    });
+    Ok(window)
+}
+
+pub(crate) fn show_main_window<R: Runtime>(app: &AppHandle<R>) {
+    match main_window(app) {
+        Ok(window) => {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        Err(e) => log_error!("Failed to open main window: {}", e),
+    }
+}
+
+/// Show the app's own UI with the log panel open, leaving the dashboard if it was there
+pub(crate) fn show_logs<R: Runtime>(app: &AppHandle<R>) {
+    show_main_window(app);
+    let Ok(window) = main_window(app) else {
+        return;
+    };
+    // Before the first dashboard visit the window is still on the app's page
+    let Some(mut url) = app_url().or_else(|| window.url().ok()) else {
+        return;
+    };
+    url.set_fragment(Some("logs"));
+    if let Err(e) = window.navigate(url) {
+        log_error!("Failed to show logs: {}", e);
+    }
+}
+
+/// Return the main window to the app's own UI, at `route` (a hash route) when given
+#[tauri::command]
+pub fn navigate_home(app: AppHandle, route: Option<String>) -> Result<(), DesktopError> {
+    let window = main_window(&app).map_err(|e| format!("Failed to open main window: {}", e))?;
+    let mut url = app_url()
+        .or_else(|| window.url().ok())
+        .ok_or_else(|| DesktopError::Unavailable("The app's page is not known".to_string()))?;
+    url.set_fragment(route.as_deref());
+    window
+        .navigate(url)
+        .map_err(|e| format!("Failed to navigate: {}", e))?;
+    Ok(())
+}
+
+fn apply_zoom<R: Runtime>(window: &WebviewWindow<R>) {
+    if let Some(zoom) = settings::current().dashboard_zoom {
+        if let Err(e) = window.set_zoom(zoom) {
+            log_error!("Failed to restore zoom: {}", e);
+        }
+    }
+}
+
+/// The window a control command acts on: `label`, or the main window
+fn target_window(app: &AppHandle, label: Option<String>) -> Result<WebviewWindow, DesktopError> {
+    match label {
+        Some(label) => app
+            .get_webview_window(&label)
+            .ok_or_else(|| DesktopError::NotFound(format!("Window not found: {}", label))),
+        None => Ok(main_window(app).map_err(|e| format!("Failed to open main window: {}", e))?),
+    }
+}
+
+/// Zoom the dashboard, 1.0 being 100%, and keep the level for new windows and
+/// the next launch
+#[tauri::command]
+pub fn set_zoom_level(
+    app: AppHandle,
+    level: f64,
+    label: Option<String>,
+) -> Result<f64, DesktopError> {
+    if !ZOOM_RANGE.contains(&level) {
+        return Err(DesktopError::InvalidInput(format!(
+            "Zoom must be between {}% and {}%",
+            ZOOM_RANGE.start() * 100.0,
+            ZOOM_RANGE.end() * 100.0
+        )));
+    }
+    target_window(&app, label)?
+        .set_zoom(level)
+        .map_err(|e| format!("Failed to zoom: {}", e))?;
+    let saved = (level != 1.0).then_some(level);
+    Ok(settings::update(|s| s.dashboard_zoom = saved)?
+        .dashboard_zoom
+        .unwrap_or(1.0))
+}
+
+#[tauri::command]
+pub fn reload_dashboard(app: AppHandle, label: Option<String>) -> Result<(), DesktopError> {
+    target_window(&app, label)?
+        .reload()
+        .map_err(|e| format!("Failed to reload: {}", e))?;
+    Ok(())
+}
+
+/// Go back in the webview's history, like a browser's back button
+#[tauri::command]
+pub fn navigate_back(app: AppHandle, label: Option<String>) -> Result<(), DesktopError> {
+    target_window(&app, label)?
+        .eval("history.back()")
+        .map_err(|e| format!("Failed to navigate back: {}", e))?;
+    Ok(())
+}
+
+/// Open or close the web inspector, returning whether it is now open
+#[tauri::command]
+pub fn toggle_devtools(app: AppHandle, label: Option<String>) -> Result<bool, DesktopError> {
+    kiosk::ensure_unlocked()?;
+    let window = target_window(&app, label)?;
+    if window.is_devtools_open() {
+        window.close_devtools();
+        Ok(false)
+    } else {
+        window.open_devtools();
+        Ok(true)
+    }
+}
+
+/// Last size and position of a window, in logical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+pub(crate) fn current_geometry<R: Runtime>(window: &WebviewWindow<R>) -> Option<WindowGeometry> {
+    let scale = window.scale_factor().ok()?;
+    let position = window.outer_position().ok()?.to_logical::<f64>(scale);
+    let size = window.inner_size().ok()?.to_logical::<f64>(scale);
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+/// Whether the geometry's top-left corner lies on `monitor`
+pub(crate) fn monitor_contains(monitor: &Monitor, geometry: &WindowGeometry) -> bool {
+    let scale = monitor.scale_factor();
+    let position = monitor.position().to_logical::<f64>(scale);
+    let size = monitor.size().to_logical::<f64>(scale);
+    geometry.x >= position.x
+        && geometry.y >= position.y
+        && geometry.x < position.x + size.width
+        && geometry.y < position.y + size.height
+}
+
+/// Whether the saved position is still on a connected monitor
+fn on_screen<R: Runtime>(app: &AppHandle<R>, geometry: &WindowGeometry) -> bool {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .any(|monitor| monitor_contains(monitor, geometry))
+}
+
+/// Bring up a secondary window, or focus it when it is already open.
+///
+/// Unlike the main window these close for real; their geometry is remembered per
+/// `kind` so every chat window opens where the last one was.
+fn open_detached<R: Runtime>(
+    app: &AppHandle<R>,
+    label: &str,
+    kind: &'static str,
+    route: &str,
+    title: &str,
+) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(());
+    }
+
+    let mut builder = WebviewWindowBuilder::new(app, label, WebviewUrl::App(route.into()))
+        .title(title)
+        .min_inner_size(480.0, 320.0);
+    let saved = settings::current().window_geometry.get(kind).copied();
+    builder = match saved {
+        Some(geometry) => {
+            let builder = builder.inner_size(geometry.width, geometry.height);
+            if on_screen(app, &geometry) {
+                builder.position(geometry.x, geometry.y)
+            } else {
+                builder.center()
+            }
+        }
+        None => builder
+            .inner_size(DETACHED_DEFAULT_SIZE.0, DETACHED_DEFAULT_SIZE.1)
+            .center(),
+    };
+    let window = builder
+        .build()
+        .map_err(|e| format!("Failed to open window: {}", e))?;
+    apply_zoom(&window);
+    theme::apply_to(&window);
+
+    let handle = window.clone();
+    window.on_window_event(move |event| match event {
+        tauri::WindowEvent::CloseRequested { .. } => {
+            if let Some(geometry) = current_geometry(&handle) {
+                if let Err(e) = settings::update(|s| {
+                    s.window_geometry.insert(kind.to_string(), geometry);
+                }) {
+                    log_error!("Failed to remember window position: {}", e);
+                }
+            }
+        }
+        tauri::WindowEvent::Focused(focused) => auto_lock::on_focus_changed(*focused),
+        _ => {}
+    });
+    Ok(())
+}
+
+pub(crate) fn show_log_window<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    open_detached(app, LOG_WINDOW, "logs", "index.html#logs", "OpenClaw Logs")
+}
+
+/// Open the log viewer in its own window
+#[tauri::command]
+pub fn open_log_window(app: AppHandle) -> Result<(), DesktopError> {
+    Ok(show_log_window(&app)?)
+}
+
+/// Open a session's chat in its own window; each session gets one window
+#[tauri::command]
+pub fn open_chat_window(app: AppHandle, session_id: String) -> Result<(), DesktopError> {
+    // Window labels and the route only allow plain ids
+    let valid = !session_id.is_empty()
+        && session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'));
+    if !valid {
+        return Err(DesktopError::InvalidInput(format!(
+            "Invalid session id: {}",
+            session_id
+        )));
+    }
+    Ok(open_detached(
+        &app,
+        &format!("{}{}", CHAT_WINDOW_PREFIX, session_id),
+        "chat",
+        &format!("index.html#chat/{}", session_id),
+        "OpenClaw Chat",
+    )?)
+}
+
+/// Create (and for `Normal`, show) the main window according to the startup mode
+pub(crate) fn init_main_window<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    match startup_mode() {
+        StartupMode::Normal => main_window(app)?.show(),
+        StartupMode::Minimized => main_window(app).map(|_| ()),
+        StartupMode::TrayOnly => Ok(()),
+    }
+}
+
+/// A second launch brings the window forward, except that tray-only mode
+/// does not create a window the user has not opened yet
+pub(crate) fn on_second_instance<R: Runtime>(app: &AppHandle<R>) {
+    if startup_mode() == StartupMode::TrayOnly && app.get_webview_window(MAIN_WINDOW).is_none() {
+        return;
+    }
+    show_main_window(app);
+}
@@ -0,0 +1,125 @@
+//! Remembers the main window's size, position, maximized state and monitor
+//! across launches. Detached windows keep their own geometry in `window.rs`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Runtime, WebviewWindow};
+
+use crate::settings;
+use crate::window::{self, WindowGeometry};
+
+/// Moves and resizes arrive in bursts while dragging; save once they settle
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+
+static PENDING: Mutex<Option<WindowState>> = Mutex::new(None);
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    /// Size and position when not maximized, in logical pixels
+    #[serde(flatten)]
+    pub geometry: WindowGeometry,
+    pub maximized: bool,
+    /// Name of the monitor the window was on, when the OS reports one
+    pub monitor: Option<String>,
+}
+
+fn capture<R: Runtime>(window: &WebviewWindow<R>) -> Option<WindowState> {
+    // Minimized windows report a parking position off-screen on Windows
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let maximized = window.is_maximized().unwrap_or(false);
+    let previous = settings::current().main_window_state;
+    let geometry = match (maximized, &previous) {
+        // Keep the size to go back to when the window is restored
+        (true, Some(previous)) => previous.geometry,
+        _ => window::current_geometry(window)?,
+    };
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|monitor| monitor.name().cloned());
+    Some(WindowState {
+        geometry,
+        maximized,
+        monitor,
+    })
+}
+
+fn save(state: WindowState) {
+    if settings::current().main_window_state.as_ref() == Some(&state) {
+        return;
+    }
+    if let Err(e) = settings::update(|s| s.main_window_state = Some(state)) {
+        log_error!("Failed to save window state: {}", e);
+    }
+}
+
+/// Called for every `Moved`/`Resized` event of the main window
+pub(crate) fn on_changed<R: Runtime>(window: &WebviewWindow<R>) {
+    let Some(state) = capture(window) else {
+        return;
+    };
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = Some(state);
+    }
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DELAY).await;
+        if GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        if let Some(state) = PENDING.lock().ok().and_then(|mut p| p.take()) {
+            save(state);
+        }
+    });
+}
+
+/// Save right away, for when the window is about to be hidden or closed
+pub(crate) fn flush<R: Runtime>(window: &WebviewWindow<R>) {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut pending) = PENDING.lock() {
+        *pending = None;
+    }
+    if let Some(state) = capture(window) {
+        save(state);
+    }
+}
+
+/// Whether the saved position is still visible, on the same monitor when it was recorded
+fn position_visible<R: Runtime>(app: &AppHandle<R>, state: &WindowState) -> bool {
+    app.available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .filter(|monitor| match &state.monitor {
+            Some(name) => monitor.name() == Some(name),
+            None => true,
+        })
+        .any(|monitor| window::monitor_contains(monitor, &state.geometry))
+}
+
+/// Apply the saved state to a freshly created, still hidden main window.
+/// A position on a monitor that is gone falls back to the centered default.
+pub(crate) fn restore<R: Runtime>(app: &AppHandle<R>, window: &WebviewWindow<R>) {
+    let Some(state) = settings::current().main_window_state else {
+        return;
+    };
+    let geometry = state.geometry;
+    if let Err(e) = window.set_size(tauri::LogicalSize::new(geometry.width, geometry.height)) {
+        log_error!("Failed to restore window size: {}", e);
+    }
+    if position_visible(app, &state) {
+        if let Err(e) = window.set_position(tauri::LogicalPosition::new(geometry.x, geometry.y)) {
+            log_error!("Failed to restore window position: {}", e);
+        }
+    } else {
+        let _ = window.center();
+    }
+    if state.maximized {
+        let _ = window.maximize();
+    }
+}
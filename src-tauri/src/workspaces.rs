@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use crate::events::{WorkspaceAction, WorkspaceGatewayEvent, WORKSPACE_GATEWAY_EVENT};
+use crate::gateways::{self, GatewayManager};
+use crate::{is_port_open, settings};
+
+const WATCH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A project directory whose gateway instance starts on activity and stops when idle
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkspaceWatch {
+    pub enabled: bool,
+    /// Name of the gateway instance (from `settings.gateways`) serving this project
+    pub gateway: String,
+    pub directory: String,
+    /// File inside the directory whose modification or access counts as opening the project
+    pub marker: Option<String>,
+    /// Stop the gateway after this many minutes without activity; 0 keeps it running
+    pub idle_minutes: u64,
+}
+
+impl Default for WorkspaceWatch {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            gateway: String::new(),
+            directory: String::new(),
+            marker: Some(".openclaw".to_string()),
+            idle_minutes: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkspaceStatus {
+    pub gateway: String,
+    pub directory: String,
+    /// Unix seconds of the last activity seen in the directory
+    pub last_activity: Option<u64>,
+    /// Whether the watcher started the gateway and will stop it when idle
+    pub auto_started: bool,
+}
+
+#[derive(Default)]
+struct WatchState {
+    last_seen: Option<SystemTime>,
+    auto_started: bool,
+}
+
+/// Per-gateway watcher state, keyed by gateway name
+static STATES: Mutex<Option<HashMap<String, WatchState>>> = Mutex::new(None);
+
+fn newest(times: impl IntoIterator<Item = Option<SystemTime>>) -> Option<SystemTime> {
+    times.into_iter().flatten().max()
+}
+
+/// Latest sign of use: the directory or its entries changing, or the marker being read.
+/// Access times are only as fresh as the filesystem's atime policy allows.
+fn last_activity(watch: &WorkspaceWatch) -> Option<SystemTime> {
+    let dir = Path::new(&watch.directory);
+    let dir_modified = fs::metadata(dir).and_then(|m| m.modified()).ok();
+    let marker = watch
+        .marker
+        .as_deref()
+        .and_then(|marker| fs::metadata(dir.join(marker)).ok())
+        .map(|m| newest([m.modified().ok(), m.accessed().ok()]));
+    newest([dir_modified, marker.flatten()])
+}
+
+fn emit<R: Runtime>(
+    app: &AppHandle<R>,
+    watch: &WorkspaceWatch,
+    action: WorkspaceAction,
+    message: Option<String>,
+) {
+    let event = WorkspaceGatewayEvent {
+        gateway: watch.gateway.clone(),
+        directory: watch.directory.clone(),
+        action,
+        message,
+    };
+    if let Err(e) = app.emit(WORKSPACE_GATEWAY_EVENT, event) {
+        log_error!("Failed to emit workspace event: {}", e);
+    }
+}
+
+fn check_watch<R: Runtime>(app: &AppHandle<R>, watch: &WorkspaceWatch, state: &mut WatchState) {
+    let Some(activity) = last_activity(watch) else {
+        return;
+    };
+    let manager = app.state::<GatewayManager>();
+
+    // The first pass only records a baseline, so launching the app starts nothing
+    let Some(last_seen) = state.last_seen else {
+        state.last_seen = Some(activity);
+        return;
+    };
+
+    if activity > last_seen {
+        state.last_seen = Some(activity);
+        let Some(definition) = gateways::known_definitions()
+            .into_iter()
+            .find(|g| g.name == watch.gateway)
+        else {
+            emit(
+                app,
+                watch,
+                WorkspaceAction::Failed,
+                Some(format!("Gateway '{}' is not configured", watch.gateway)),
+            );
+            return;
+        };
+        if is_port_open(definition.port) {
+            return;
+        }
+        match manager.spawn_instance(&definition) {
+            Ok(()) => {
+                state.auto_started = true;
+                emit(app, watch, WorkspaceAction::Started, None);
+            }
+            Err(e) => emit(app, watch, WorkspaceAction::Failed, Some(e)),
+        }
+        return;
+    }
+
+    let idle = SystemTime::now()
+        .duration_since(last_seen)
+        .unwrap_or_default();
+    if state.auto_started
+        && watch.idle_minutes > 0
+        && idle >= Duration::from_secs(watch.idle_minutes * 60)
+    {
+        state.auto_started = false;
+        match manager.stop_instance(&watch.gateway) {
+            Ok(_) => emit(app, watch, WorkspaceAction::Stopped, None),
+            Err(e) => emit(app, watch, WorkspaceAction::Failed, Some(e)),
+        }
+    }
+}
+
+/// Poll configured project directories and start/stop their gateways with use
+pub fn spawn_workspace_watcher<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(WATCH_INTERVAL);
+        loop {
+            interval.tick().await;
+            let watches: Vec<WorkspaceWatch> = settings::current()
+                .workspaces
+                .into_iter()
+                .filter(|w| w.enabled && !w.gateway.is_empty() && !w.directory.is_empty())
+                .collect();
+
+            let app = app.clone();
+            let _ = tauri::async_runtime::spawn_blocking(move || {
+                let Ok(mut guard) = STATES.lock() else {
+                    return;
+                };
+                let states = guard.get_or_insert_with(HashMap::new);
+                states.retain(|gateway, _| watches.iter().any(|w| &w.gateway == gateway));
+                for watch in &watches {
+                    let state = states.entry(watch.gateway.clone()).or_default();
+                    check_watch(&app, watch, state);
+                }
+            })
+            .await;
+        }
+    });
+}
+
+/// Watched projects with their last activity and whether the watcher owns the gateway
+#[tauri::command]
+pub fn get_workspace_status() -> Vec<WorkspaceStatus> {
+    let guard = STATES.lock().ok();
+    let states = guard.as_ref().and_then(|g| g.as_ref());
+    settings::current()
+        .workspaces
+        .into_iter()
+        .map(|watch| {
+            let state = states.and_then(|s| s.get(&watch.gateway));
+            WorkspaceStatus {
+                last_activity: state
+                    .and_then(|s| s.last_seen)
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs()),
+                auto_started: state.is_some_and(|s| s.auto_started),
+                gateway: watch.gateway,
+                directory: watch.directory,
+            }
+        })
+        .collect()
+}
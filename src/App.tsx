@@ -1,12 +1,17 @@
-import { useState, useEffect } from "react";
-import { invoke } from "@tauri-apps/api/core";
-import { openUrl } from "@tauri-apps/plugin-opener";
+import { useState, useEffect, useRef } from "react";
+import { errorMessage, invoke } from "./ipc";
+import { useAppState } from "./appState";
+import type { GatewayStartResult } from "./events";
 import "./App.css";
 
-interface GatewayStatus {
-  running: boolean;
-  port: number;
-  dashboard_url: string;
+type ExitReason = "clean_stop" | "crashed" | "signaled" | "oom_killed" | "port_conflict";
+
+interface ExitInfo {
+  reason: ExitReason;
+  code: number | null;
+  signal: number | null;
+  detail: string | null;
+  exited_at: number;
 }
 
 interface GatewayDiagnostics {
@@ -18,84 +23,99 @@ interface GatewayDiagnostics {
   profile_name: string | null;
   log_path: string;
   error_log_path: string;
+  last_exit: ExitInfo | null;
+}
+
+interface AutoLockStatus {
+  enabled: boolean;
+  locked: boolean;
+  idle_minutes: number;
+  has_passcode: boolean;
+  allow_os_verification: boolean;
 }
 
-type Page = "loading" | "setup" | "dashboard";
+type Page = "loading" | "setup" | "dashboard" | "locked";
 
 function App() {
   const [page, setPage] = useState<Page>("loading");
-  const [status, setStatus] = useState<GatewayStatus | null>(null);
+  const appState = useAppState();
+  const status = appState?.gateway ?? null;
+  const install = appState?.install ?? null;
+  const initialized = useRef(false);
   const [installing, setInstalling] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [startingGateway, setStartingGateway] = useState(false);
-  const [navigatedToDashboard, setNavigatedToDashboard] = useState(false);
+  const [startFailure, setStartFailure] = useState<GatewayStartResult | null>(null);
+  // `--show-logs` opens the app on its log panel instead of the dashboard
+  const openedForLogs = window.location.hash === "#logs";
+  const [navigatedToDashboard, setNavigatedToDashboard] = useState(openedForLogs);
 
   // Log panel state
-  const [showLogs, setShowLogs] = useState(false);
+  const [showLogs, setShowLogs] = useState(openedForLogs);
   const [logs, setLogs] = useState<string>("");
   const [diagnostics, setDiagnostics] = useState<GatewayDiagnostics | null>(null);
   const [runningDoctor, setRunningDoctor] = useState(false);
 
-  // Auto-start gateway on first load
+  // Inactivity lock state
+  const [lockStatus, setLockStatus] = useState<AutoLockStatus | null>(null);
+  const [passcode, setPasscode] = useState("");
+
+  // Auto-start gateway on first load, once the backend has checked the install
   useEffect(() => {
-    async function init() {
-      const isInstalled = await invoke<boolean>("is_openclaw_installed");
-      if (!isInstalled) {
+    if (!install || initialized.current) return;
+    initialized.current = true;
+
+    async function init(installed: boolean) {
+      if (!installed) {
         setPage("setup");
         return;
       }
 
+      // The backend sends a locked dashboard back here
+      const lock = await invoke<AutoLockStatus>("get_auto_lock_status");
+      setLockStatus(lock);
+      if (lock.locked) {
+        setPage("locked");
+        return;
+      }
+
       setPage("dashboard");
 
       // Auto-start if not running
       try {
-        const started = await invoke<boolean>("auto_start_gateway");
-        if (started) {
-          setStartingGateway(true);
+        setStartingGateway(true);
+        const result = await invoke<GatewayStartResult | null>("auto_start_gateway");
+        if (result && result.outcome !== "ready") {
+          setStartFailure(result);
         }
       } catch (e) {
         console.error("Auto-start failed:", e);
+      } finally {
+        setStartingGateway(false);
       }
     }
-    init();
-  }, []);
+    init(install.installed);
+  }, [install]);
 
-  // Poll status every 3 seconds
+  // Follow the gateway state the backend health monitor keeps current
   useEffect(() => {
-    if (page !== "dashboard") return;
+    if (page !== "dashboard" || !status) return;
 
-    async function checkStatus() {
-      try {
-        const gatewayStatus = await invoke<GatewayStatus>("get_gateway_status");
-        setStatus(gatewayStatus);
-
-        if (gatewayStatus.running) {
-          setStartingGateway(false);
-
-          // Navigate the entire webview to the dashboard (bypasses X-Frame-Options)
-          if (!navigatedToDashboard) {
-            setNavigatedToDashboard(true);
-            try {
-              await invoke("open_dashboard_window");
-            } catch (e) {
-              console.error("Failed to navigate to dashboard:", e);
-            }
-          }
-        } else {
-          // Gateway stopped — reset so we re-navigate when it comes back
-          if (navigatedToDashboard) {
-            setNavigatedToDashboard(false);
-          }
-        }
-      } catch (e) {
-        console.error("Status check failed:", e);
+    if (status.running) {
+      setStartingGateway(false);
+
+      // Navigate the entire webview to the dashboard (bypasses X-Frame-Options)
+      if (!navigatedToDashboard) {
+        setNavigatedToDashboard(true);
+        invoke("open_dashboard_window").catch((e) => {
+          console.error("Failed to navigate to dashboard:", e);
+        });
       }
+    } else if (navigatedToDashboard) {
+      // Gateway stopped — reset so we re-navigate when it comes back
+      setNavigatedToDashboard(false);
     }
-
-    checkStatus();
-    const interval = setInterval(checkStatus, 3000);
-    return () => clearInterval(interval);
-  }, [page, navigatedToDashboard]);
+  }, [page, status, navigatedToDashboard]);
 
   // Poll logs when panel is open
   useEffect(() => {
@@ -133,7 +153,7 @@ function App() {
       setLogs((prev) => `${prev}\n\n===== openclaw doctor =====\n${output}`.trim());
     } catch (e) {
       console.error("OpenClaw doctor failed:", e);
-      setLogs((prev) => `${prev}\n\n===== openclaw doctor (failed) =====\n${String(e)}`.trim());
+      setLogs((prev) => `${prev}\n\n===== openclaw doctor (failed) =====\n${errorMessage(e)}`.trim());
     } finally {
       setRunningDoctor(false);
       fetchDiagnostics();
@@ -152,19 +172,24 @@ function App() {
         setStartingGateway(true);
       } catch (_) { /* ignore */ }
     } catch (e) {
-      setError(String(e));
+      setError(errorMessage(e));
     } finally {
       setInstalling(false);
     }
   }
 
-  async function handleStartGateway() {
+  async function handleStartGateway(force = false) {
     if (startingGateway) return;
     setStartingGateway(true);
+    setStartFailure(null);
     try {
-      await invoke("start_gateway");
+      const result = await invoke<GatewayStartResult>("start_gateway", { force });
+      if (result.outcome !== "ready") {
+        setStartFailure(result);
+      }
     } catch (e) {
       console.error("Failed to start gateway:", e);
+    } finally {
       setStartingGateway(false);
     }
   }
@@ -179,13 +204,36 @@ function App() {
     }
   }
 
+  async function openPath(command: string, pathKind: string) {
+    try {
+      await invoke(command, { pathKind });
+    } catch (e) {
+      console.error(`Failed to ${command}:`, e);
+    }
+  }
+
   async function openInBrowser() {
     const url = await invoke<string>("get_dashboard_url");
-    await openUrl(url);
+    await invoke("open_external", { target: url });
+  }
+
+  async function handleUnlock(usePasscode: boolean) {
+    setError(null);
+    try {
+      const lock = await invoke<AutoLockStatus>("unlock_dashboard", {
+        passcode: usePasscode ? passcode : null,
+      });
+      setLockStatus(lock);
+      setPasscode("");
+      setNavigatedToDashboard(false);
+      setPage("dashboard");
+    } catch (e) {
+      setError(errorMessage(e));
+    }
   }
 
   async function openKofi() {
-    await openUrl("https://ko-fi.com/ai_dev_2024");
+    await invoke("open_external", { target: "https://ko-fi.com/ai_dev_2024" });
   }
 
   // Loading screen
@@ -201,6 +249,47 @@ function App() {
     );
   }
 
+  // Lock screen after inactivity
+  if (page === "locked") {
+    return (
+      <main className="container setup">
+        <div className="logo-container">
+          <span className="logo-emoji">🔒</span>
+        </div>
+        <h1>Dashboard locked</h1>
+        <p className="subtitle">The dashboard was locked after a period of inactivity</p>
+
+        <div className="setup-card">
+          {lockStatus?.has_passcode && (
+            <form
+              onSubmit={(e) => {
+                e.preventDefault();
+                handleUnlock(true);
+              }}
+            >
+              <input
+                type="password"
+                placeholder="Passcode"
+                value={passcode}
+                onChange={(e) => setPasscode(e.target.value)}
+                autoFocus
+              />
+              <button className="primary-btn" type="submit" disabled={!passcode}>
+                Unlock
+              </button>
+            </form>
+          )}
+          {lockStatus?.allow_os_verification && (
+            <button className="primary-btn" onClick={() => handleUnlock(false)}>
+              Unlock with system authentication
+            </button>
+          )}
+          {error && <p className="error">{error}</p>}
+        </div>
+      </main>
+    );
+  }
+
   // Setup wizard
   if (page === "setup") {
     return (
@@ -265,7 +354,7 @@ function App() {
           {!status?.running ? (
             <button
               className="control-btn start"
-              onClick={handleStartGateway}
+              onClick={() => handleStartGateway()}
               disabled={startingGateway}
             >
               {startingGateway ? "Starting..." : "▶ Start"}
@@ -303,11 +392,31 @@ function App() {
           <span className="offline-emoji">🦞</span>
           <h2>{startingGateway ? "Starting Gateway..." : "Gateway is not running"}</h2>
           <p>{startingGateway ? "Please wait, this may take a few seconds..." : "Click \"Start\" to launch the OpenClaw gateway"}</p>
+          {!startingGateway && startFailure && (
+            <div className="error">
+              <p>{startFailure.message}</p>
+              {startFailure.failed_checks.length > 0 && (
+                <ul>
+                  {startFailure.failed_checks.map((check) => (
+                    <li key={check.id}>{check.message}</li>
+                  ))}
+                </ul>
+              )}
+              {startFailure.log_excerpt.length > 0 && (
+                <pre>{startFailure.log_excerpt.join("\n")}</pre>
+              )}
+            </div>
+          )}
           {!startingGateway && (
-            <button className="primary-btn" onClick={handleStartGateway}>
+            <button className="primary-btn" onClick={() => handleStartGateway()}>
               ▶ Start Gateway
             </button>
           )}
+          {!startingGateway && startFailure?.outcome === "blocked" && (
+            <button className="log-tool-btn" onClick={() => handleStartGateway(true)}>
+              Start Anyway
+            </button>
+          )}
 
           <div className="support-section">
             <p>Enjoying OpenClaw Desktop?</p>
@@ -335,6 +444,12 @@ function App() {
               <button className="log-tool-btn" onClick={fetchDiagnostics} title="Refresh diagnostics">
                 Refresh
               </button>
+              <button className="log-tool-btn" onClick={() => openPath("open_log_in_default_editor", "gateway_log")} title="Open gateway.log in its default editor">
+                Open Log
+              </button>
+              <button className="log-tool-btn" onClick={() => openPath("reveal_in_file_manager", "openclaw_dir")} title="Show the OpenClaw folder">
+                Show Folder
+              </button>
               <button className="log-tool-btn" onClick={runDoctor} disabled={runningDoctor} title="Run openclaw doctor">
                 {runningDoctor ? "Running..." : "Run Doctor"}
               </button>
@@ -0,0 +1,45 @@
+import { useEffect, useState } from "react";
+import { listen, type AppState } from "./events";
+import { invoke } from "./ipc";
+
+// The backend's app state, loaded once and kept current from `state-changed` diffs.
+// A diff that doesn't follow the last revision means one was missed, so the whole
+// state is fetched again.
+export function useAppState(): AppState | null {
+  const [state, setState] = useState<AppState | null>(null);
+
+  useEffect(() => {
+    let current: AppState | null = null;
+    let disposed = false;
+
+    async function reload() {
+      try {
+        const snapshot = await invoke<AppState>("get_app_state");
+        // Diffs that arrived while the snapshot was in flight are already newer
+        if (disposed || (current && current.revision > snapshot.revision)) return;
+        current = snapshot;
+        setState(snapshot);
+      } catch (e) {
+        console.error("Failed to load app state:", e);
+      }
+    }
+
+    const unlisten = listen("state-changed", ({ revision, changes }) => {
+      if (!current || revision !== current.revision + 1) {
+        reload();
+        return;
+      }
+      current = { ...current, ...changes, revision };
+      setState(current);
+    });
+    // Subscribe first so no change falls between the snapshot and the listener
+    unlisten.then(reload);
+
+    return () => {
+      disposed = true;
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  return state;
+}
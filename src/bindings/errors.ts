@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DesktopError = { code: ErrorCode, message: string, hint: string | null, };
+
+export type ErrorCode = "not_installed" | "permission_denied" | "kiosk_locked" | "dashboard_locked" | "remote_connection" | "policy_locked" | "unavailable" | "gateway_not_running" | "gateway_unauthorized" | "not_found" | "invalid_input" | "cancelled" | "offline" | "registry_unreachable" | "internal";
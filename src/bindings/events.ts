@@ -0,0 +1,306 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AgentChange = "created" | "updated" | "deleted";
+
+export type AgentConfigChanged = { id: string, change: AgentChange, };
+
+export type AgentReplyChunk = { stream_id: number, delta: string, };
+
+export type AgentReplyDone = { stream_id: number, session_id: string | null, error: string | null, };
+
+export type Announcement = { message: string, politeness: Politeness, };
+
+export type Attachment = { id: string, name: string, size: number, mime_type: string, };
+
+export type AttachmentFailure = { path: string, error: string, };
+
+export type AttachmentUploads = { attachments: Array<Attachment>, failed: Array<AttachmentFailure>, };
+
+export type AppState = { 
+/**
+ * Bumped on every change; a diff applies on top of the revision before it
+ */
+revision: number, 
+/**
+ * Unset until the first check after launch, as is `install`
+ */
+gateway: GatewayStatus | null, install: InstallState | null, active_profile: string | null, connectivity: ConnectivityState | null, 
+/**
+ * Running tasks; finished ones drop out
+ */
+tasks: Array<TaskProgress>, };
+
+export type AppUpdateProgress = { version: string, 
+/**
+ * `started`, `downloading`, `finished`, or `failed`
+ */
+stage: string, downloaded: number, 
+/**
+ * Package size, when the server reports it
+ */
+total: number | null, };
+
+/**
+ * A value the app's settings and `openclaw.json` disagree on
+ */
+export type CliDone = { run_id: number, 
+/**
+ * Exit code, absent when the process was killed by a signal or never ran
+ */
+code: number | null, error: string | null, };
+
+export type CliOutput = { run_id: number, 
+/**
+ * `stdout` or `stderr`
+ */
+stream: string, line: string, };
+
+export type ColorScheme = "light" | "dark";
+
+export type ConfigConflict = { field: ConflictField, app_value: string | null, config_value: string | null, };
+
+export type ConflictField = "port" | "profile";
+
+export type ConnectivityState = { status: ConnectivityStatus, gateway: boolean, internet: boolean, npm_registry: boolean, 
+/**
+ * Unix milliseconds
+ */
+checked_at: number, };
+
+/**
+ * The most pressing connectivity problem, for a banner
+ */
+export type ConnectivityStatus = "online" | "no_internet" | "gateway_down" | "registry_unreachable";
+
+/**
+ * Why the dashboard was not opened; the window stays on the app's own UI
+ */
+export type DashboardUnreachable = { reason: DashboardUnreachableReason, message: string, };
+
+export type DashboardUnreachableReason = "gateway_down" | "unauthorized" | "unhealthy";
+
+/**
+ * View an `openclaw://` link points at
+ */
+export type DeepLinkTarget = { "kind": "session", "id": string } | { "kind": "agent", "id": string } | { "kind": "agent_chat", "id": string };
+
+export type DependencyReport = { dependency: string, status: DependencyStatus, waited_secs: number, };
+
+export type DependencyStatus = "waiting" | "ready" | "timed_out" | "skipped";
+
+export type EnvironmentCheck = { id: EnvironmentCheckId, passed: boolean, message: string, };
+
+export type EnvironmentCheckId = "config" | "token" | "port" | "disk_space" | "version";
+
+export type ExitInfo = { reason: ExitReason, code: number | null, signal: number | null, detail: string | null, exited_at: number, };
+
+export type ExitReason = "clean_stop" | "crashed" | "signaled" | "oom_killed" | "port_conflict";
+
+export type GatewayCrashedPayload = { exit: ExitInfo | null, restarting: boolean, };
+
+export type GatewayLogLine = { stream: string, entry: LogEntry, 
+/**
+ * Remote gateway the line came from; unset for the local gateway
+ */
+gateway?: string, };
+
+/**
+ * What answered on the gateway port
+ */
+export type GatewayProbeResult = { port: number, 
+/**
+ * Something accepted a connection on the port
+ */
+reachable: boolean, 
+/**
+ * The health endpoint answered like an OpenClaw gateway
+ */
+verified: boolean, version: string | null, instance_id: string | null, http_status: number | null, latency_ms: number | null, error: string | null, };
+
+export type GatewayRecoveredPayload = { attempts: number, downtime_secs: number, };
+
+export type GatewayStartProgress = { elapsed_ms: number, timeout_ms: number, probe: GatewayProbeResult, 
+/**
+ * Set on the last event, once the gateway is ready or given up on
+ */
+result: GatewayStartResult | null, };
+
+export type GatewayStartResult = { outcome: StartOutcome, message: string, elapsed_ms: number, probe: GatewayProbeResult, 
+/**
+ * How the process ended, when it failed
+ */
+exit: ExitInfo | null, 
+/**
+ * Last lines of the gateway logs, when it did not become ready
+ */
+log_excerpt: Array<string>, 
+/**
+ * Pre-start checks that failed, when blocked
+ */
+failed_checks: Array<EnvironmentCheck>, };
+
+export type GatewayStatus = { running: boolean, 
+/**
+ * Running, with agents paused
+ */
+paused: boolean, port: number, dashboard_url: string, last_exit: ExitInfo | null, probe: GatewayProbeResult, 
+/**
+ * Timings of the warm-up run after the gateway last became ready
+ */
+warmup: WarmupReport | null, };
+
+export type InstallProgress = { 
+/**
+ * `stdout` or `stderr`
+ */
+stream: string, line: string, };
+
+export type InstallState = { installed: boolean, version: string | null, };
+
+export type LogEntry = { line: number, timestamp: string | null, level: LogLevel | null, component: string | null, message: string, };
+
+export type LogLevel = "trace" | "debug" | "info" | "warn" | "error" | "fatal";
+
+export type LoginStartReport = { dependencies: Array<DependencyReport>, gateway_started: boolean, error: string | null, };
+
+export type NotificationCategory = "gateway_started" | "gateway_stopped" | "gateway_crashed" | "cli_update" | "app_update" | "backup_failed" | "deprecation" | "resource_limit" | "token_invalid" | "compatibility" | "disk_space" | "tool_approval";
+
+/**
+ * How a notification reached the user when it was raised
+ */
+export type NotificationDelivery = "immediate" | "batched" | "held";
+
+export type NotificationRecord = { id: number, category: NotificationCategory, title: string, body: string, 
+/**
+ * Unix seconds
+ */
+raised_at: number, delivery: NotificationDelivery, };
+
+/**
+ * Maps to `aria-live`: polite waits for the reader to go idle, assertive interrupts
+ */
+export type PendingApproval = { id: string, 
+/**
+ * Tool the agent wants to run, e.g. `shell` or `write_file`
+ */
+tool: string, agent: string | null, session_id: string | null, 
+/**
+ * What the call would do, as the gateway describes it
+ */
+summary: string | null, 
+/**
+ * Unix seconds; filled in on arrival when the gateway leaves it out
+ */
+requested_at: number, };
+
+export type Politeness = "polite" | "assertive";
+
+export type QuitRequest = { 
+/**
+ * Sessions the gateway reports as still running
+ */
+active_sessions: Array<string>, };
+
+export type RemoteLogStatus = { gateway: string, connected: boolean, error: string | null, };
+
+export type SelfTestCheck = { id: SelfTestCheckId, status: SelfTestStatus, message: string, };
+
+export type SelfTestCheckId = "binary" | "config" | "token" | "port" | "dashboard" | "log_directory";
+
+export type SelfTestReport = { 
+/**
+ * No check failed
+ */
+ok: boolean, 
+/**
+ * Unix seconds
+ */
+ran_at: number, checks: Array<SelfTestCheck>, };
+
+export type SelfTestStatus = "passed" | "failed" | "skipped";
+
+export type SkillInstallProgress = { name: string, 
+/**
+ * `started`, `output`, `finished`, or `failed`
+ */
+stage: string, line: string, };
+
+export type SpaceCheck = { path: string, required_bytes: number, available_bytes: number, 
+/**
+ * Only measured on Linux
+ */
+required_inodes: number | null, available_inodes: number | null, };
+
+export type StartOutcome = "ready" | "blocked" | "failed" | "timed_out" | "already_starting";
+
+export type StateChanged = { revision: number, 
+/**
+ * The fields that changed, with their new values
+ */
+changes: Partial<Omit<AppState, "revision">>, };
+
+export type TaskKind = "install" | "doctor";
+
+export type TaskProgress = { 
+/**
+ * Pass to `cancel_task`
+ */
+id: string, kind: TaskKind, state: TaskState, 
+/**
+ * Latest progress line while running, the error once failed
+ */
+message: string | null, };
+
+export type TaskState = "running" | "finished" | "failed" | "cancelled";
+
+export type TerminalExit = { session_id: number, 
+/**
+ * Unknown when the process could not be waited on
+ */
+code: number | null, };
+
+export type TerminalOutput = { session_id: number, data: string, };
+
+export type ThemePreference = "system" | "light" | "dark";
+
+export type ThemeState = { preference: ThemePreference, 
+/**
+ * The OS setting, when it can be read
+ */
+system: ColorScheme | null, 
+/**
+ * What the app and the dashboard should show
+ */
+effective: ColorScheme, };
+
+export type TokenInvalid = { 
+/**
+ * Unix seconds of the first rejected check
+ */
+since: number, detail: string, };
+
+export type TrayTarget = { "kind": "session", "id": string } | { "kind": "agent", "id": string };
+
+export type UpdateProgress = { 
+/**
+ * `started`, `output`, `finished`, or `failed`
+ */
+stage: string, message: string, 
+/**
+ * Free space measured before starting, on the `started` event
+ */
+space?: SpaceCheck, };
+
+export type WarmupAction = "list_models" | "open_websocket" | "prime_dashboard" | "agent_ping";
+
+export type WarmupReport = { 
+/**
+ * Unix seconds when the run started
+ */
+started_at: number, total_ms: number, steps: Array<WarmupStep>, };
+
+export type WarmupStep = { action: WarmupAction, ok: boolean, duration_ms: number, error: string | null, };
+
+export type WorkspaceAction = "started" | "stopped" | "failed";
+
+export type WorkspaceGatewayEvent = { gateway: string, directory: string, action: WorkspaceAction, message: string | null, };
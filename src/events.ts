@@ -0,0 +1,95 @@
+import { listen as tauriListen, type UnlistenFn } from "@tauri-apps/api/event";
+
+import type {
+  AgentConfigChanged,
+  AgentReplyChunk,
+  AgentReplyDone,
+  Announcement,
+  AppUpdateProgress,
+  AttachmentUploads,
+  CliDone,
+  CliOutput,
+  ConfigConflict,
+  ConnectivityState,
+  DashboardUnreachable,
+  DeepLinkTarget,
+  GatewayCrashedPayload,
+  GatewayLogLine,
+  GatewayRecoveredPayload,
+  GatewayStartProgress,
+  GatewayStatus,
+  InstallProgress,
+  LoginStartReport,
+  NotificationRecord,
+  PendingApproval,
+  QuitRequest,
+  RemoteLogStatus,
+  SelfTestReport,
+  SkillInstallProgress,
+  StateChanged,
+  TaskProgress,
+  TerminalExit,
+  TerminalOutput,
+  ThemeState,
+  TokenInvalid,
+  TrayTarget,
+  UpdateProgress,
+  WorkspaceGatewayEvent,
+} from "./bindings/events";
+
+export type * from "./bindings/events";
+
+// Payload of every event the backend emits, keyed by event name (see src-tauri/src/events.rs)
+export interface EventMap {
+  "gateway-status-changed": GatewayStatus;
+  "gateway-crashed": GatewayCrashedPayload;
+  "gateway-recovered": GatewayRecoveredPayload;
+  "gateway-start-progress": GatewayStartProgress;
+  "gateway-log-line": GatewayLogLine;
+  "remote-log-status": RemoteLogStatus;
+  "install-progress": InstallProgress;
+  "openclaw-update-progress": UpdateProgress;
+  "app-update-progress": AppUpdateProgress;
+  "skill-install-progress": SkillInstallProgress;
+  "task-progress": TaskProgress;
+  "terminal-output": TerminalOutput;
+  "terminal-exit": TerminalExit;
+  "cli-output": CliOutput;
+  "cli-done": CliDone;
+  "connectivity-changed": ConnectivityState;
+  "notification-recorded": NotificationRecord;
+  "selftest-completed": SelfTestReport;
+  // Files dropped on the main window, already uploaded to the gateway
+  "attachments-dropped": AttachmentUploads;
+  // The dashboard was not opened; route to the fallback view
+  "dashboard-unreachable": DashboardUnreachable;
+  "theme-changed": ThemeState;
+  "approvals-changed": Array<PendingApproval>;
+  "state-changed": StateChanged;
+  "agent-reply-chunk": AgentReplyChunk;
+  "agent-reply-done": AgentReplyDone;
+  "workspace-gateway": WorkspaceGatewayEvent;
+  "login-start-progress": LoginStartReport;
+  "tray-open": TrayTarget;
+  "deep-link": DeepLinkTarget;
+  "agent-config-changed": AgentConfigChanged;
+  "token-invalid": TokenInvalid;
+  "quit-requested": QuitRequest;
+  // Whole documents; their shape follows get_settings / the openclaw.json schema
+  "settings-changed": Record<string, unknown>;
+  "config-changed": Record<string, unknown>;
+  // Values settings and openclaw.json disagree on, found at launch
+  "config-conflict": ConfigConflict[];
+  // Screen-reader text for an ARIA live region; already deduplicated and rate-limited
+  announce: Announcement;
+}
+
+export type EventName = keyof EventMap;
+
+// Subscribe to a backend event with its payload typed from the Rust definitions
+export function listen<K extends EventName>(
+  event: K,
+  handler: (payload: EventMap[K]) => void,
+): Promise<UnlistenFn> {
+  return tauriListen<EventMap[K]>(event, (e) => handler(e.payload));
+}
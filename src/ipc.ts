@@ -0,0 +1,32 @@
+import { invoke as tauriInvoke, type InvokeArgs } from "@tauri-apps/api/core";
+import type { DesktopError } from "./bindings/errors";
+
+export type * from "./bindings/errors";
+
+export function isDesktopError(e: unknown): e is DesktopError {
+  return typeof e === "object" && e !== null && "code" in e && "message" in e;
+}
+
+// Text for a rejected command, with the backend's hint when it has one
+export function errorMessage(e: unknown): string {
+  if (!isDesktopError(e)) return String(e);
+  return e.hint ? `${e.message}. ${e.hint}` : e.message;
+}
+
+// Times every command round trip and reports it, with any error, to the backend's IPC metrics
+export async function invoke<T>(command: string, args?: InvokeArgs): Promise<T> {
+  const started = performance.now();
+  let success = false;
+  let error: string | null = null;
+  try {
+    const result = await tauriInvoke<T>(command, args);
+    success = true;
+    return result;
+  } catch (e) {
+    error = isDesktopError(e) ? `${e.code}: ${e.message}` : String(e);
+    throw e;
+  } finally {
+    const durationMs = performance.now() - started;
+    tauriInvoke("record_ipc_timing", { command, durationMs, success, error }).catch(() => {});
+  }
+}